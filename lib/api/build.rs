@@ -149,6 +149,7 @@ fn configure_validation(builder: Builder) -> Builder {
         // Service: points.proto
         .validates(&[
             ("UpsertPoints.collection_name", "length(min = 1, max = 255)"),
+            ("UpsertPoints.condition", ""),
             ("DeletePoints.collection_name", "length(min = 1, max = 255)"),
             ("UpdatePointVectors.collection_name", "length(min = 1, max = 255)"),
             ("UpdatePointVectors.vectors", "custom(function = \"crate::grpc::validate::validate_named_vectors_not_empty\", message = \"must specify vectors to update\")"),
@@ -173,6 +174,9 @@ fn configure_validation(builder: Builder) -> Builder {
             ("SearchBatchPoints.collection_name", "length(min = 1, max = 255)"),
             ("SearchBatchPoints.search_points", ""),
             ("SearchBatchPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("SearchStreamPoints.collection_name", "length(min = 1, max = 255)"),
+            ("SearchStreamPoints.search_points", ""),
+            ("SearchStreamPoints.chunk_size", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             // TODO(sparse) validate sparse vector for `SearchPointGroups`
             ("SearchPointGroups.collection_name", "length(min = 1, max = 255)"),
             ("SearchPointGroups.group_by", "length(min = 1)"),
@@ -195,6 +199,8 @@ fn configure_validation(builder: Builder) -> Builder {
             ("RecommendBatchPoints.collection_name", "length(min = 1, max = 255)"),
             ("RecommendBatchPoints.recommend_points", ""),
             ("RecommendBatchPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("RecommendBatchPoints.filter", ""),
+            ("RecommendBatchPoints.params", ""),
             ("RecommendPointGroups.collection_name", "length(min = 1, max = 255)"),
             ("RecommendPointGroups.filter", ""),
             ("RecommendPointGroups.group_by", "length(min = 1)"),
@@ -212,8 +218,14 @@ fn configure_validation(builder: Builder) -> Builder {
             ("DiscoverBatchPoints.collection_name", "length(min = 1, max = 255)"),
             ("DiscoverBatchPoints.discover_points", ""),
             ("DiscoverBatchPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("DiscoverBatchPoints.filter", ""),
+            ("DiscoverBatchPoints.params", ""),
             ("CountPoints.collection_name", "length(min = 1, max = 255)"),
             ("CountPoints.filter", ""),
+            ("FacetCounts.collection_name", "length(min = 1, max = 255)"),
+            ("FacetCounts.key", "length(min = 1)"),
+            ("FacetCounts.filter", ""),
+            ("FacetCounts.limit", "custom = \"crate::grpc::validate::validate_u32_range_min_1\""),
             ("GeoPolygon.exterior", "custom = \"crate::grpc::validate::validate_geo_polygon_exterior\""),
             ("GeoPolygon.interiors", "custom = \"crate::grpc::validate::validate_geo_polygon_interiors\""),
             ("Filter.should", ""),
@@ -243,6 +255,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("ClearPayloadPointsInternal.clear_payload_points", ""),
             ("CreateFieldIndexCollectionInternal.create_field_index_collection", ""),
             ("DeleteFieldIndexCollectionInternal.delete_field_index_collection", ""),
+            ("UpdateBatchInternal.update_batch", ""),
             ("SearchPointsInternal.search_points", ""),
             ("SearchBatchPointsInternal.collection_name", "length(min = 1, max = 255)"),
             ("SearchBatchPointsInternal.search_points", ""),
@@ -256,6 +269,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("ScrollPointsInternal.scroll_points", ""),
             ("GetPointsInternal.get_points", ""),
             ("CountPointsInternal.count_points", ""),
+            ("FacetCountsInternal.facet_counts", ""),
             ("SyncPointsInternal.sync_points", ""),
             ("SyncPoints.collection_name", "length(min = 1, max = 255)"),
         ], &[])
@@ -2,6 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
 use chrono::{NaiveDateTime, Timelike};
+use segment::data_types::index::KeywordIndexType;
 use segment::data_types::text_index::TextIndexType;
 use segment::data_types::vectors::VectorElementType;
 use segment::types::default_quantization_ignore_value;
@@ -19,14 +20,14 @@ use crate::grpc::qdrant::vectors::VectorsOptions;
 use crate::grpc::qdrant::with_payload_selector::SelectorOptions;
 use crate::grpc::qdrant::{
     shard_key, with_vectors_selector, CollectionDescription, CollectionOperationResponse,
-    Condition, Distance, FieldCondition, Filter, GeoBoundingBox, GeoPoint, GeoPolygon, GeoRadius,
-    HasIdCondition, HealthCheckReply, HnswConfigDiff, IsEmptyCondition, IsNullCondition,
-    ListCollectionsResponse, ListValue, Match, NamedVectors, NestedCondition,
-    PayloadExcludeSelector, PayloadIncludeSelector, PayloadIndexParams, PayloadSchemaInfo,
+    Condition, Direction, Distance, FieldCondition, Filter, GeoBoundingBox, GeoPoint, GeoPolygon,
+    GeoRadius, HasIdCondition, HealthCheckReply, HnswConfigDiff, IsEmptyCondition, IsNullCondition,
+    KeywordIndexParams, ListCollectionsResponse, ListValue, Match, NamedVectors, NestedCondition,
+    OrderBy, PayloadExcludeSelector, PayloadIncludeSelector, PayloadIndexParams, PayloadSchemaInfo,
     PayloadSchemaType, PointId, ProductQuantization, QuantizationConfig, QuantizationSearchParams,
     QuantizationType, Range, RepeatedIntegers, RepeatedStrings, ScalarQuantization, ScoredPoint,
-    SearchParams, ShardKey, Struct, TextIndexParams, TokenizerType, Value, ValuesCount, Vector,
-    Vectors, VectorsSelector, WithPayloadSelector, WithVectorsSelector,
+    SearchParams, ShardKey, StemmerParams, StopwordsParams, Struct, TextIndexParams, TokenizerType,
+    Value, ValuesCount, Vector, Vectors, VectorsSelector, WithPayloadSelector, WithVectorsSelector,
 };
 
 pub fn payload_to_proto(payload: segment::types::Payload) -> HashMap<String, Value> {
@@ -181,6 +182,7 @@ impl From<segment::data_types::text_index::TokenizerType> for TokenizerType {
                 TokenizerType::Multilingual
             }
             segment::data_types::text_index::TokenizerType::Word => TokenizerType::Word,
+            segment::data_types::text_index::TokenizerType::Ngram => TokenizerType::Ngram,
         }
     }
 }
@@ -194,11 +196,38 @@ impl From<segment::data_types::text_index::TextIndexParams> for PayloadIndexPara
                 lowercase: params.lowercase,
                 min_token_len: params.min_token_len.map(|x| x as u64),
                 max_token_len: params.max_token_len.map(|x| x as u64),
+                stemmer: params.stemmer.map(|stemmer| StemmerParams {
+                    language: language_name(&stemmer.language),
+                }),
+                stopwords: params.stopwords.map(|stopwords| StopwordsParams {
+                    languages: stopwords.languages.iter().map(language_name).collect(),
+                    custom: stopwords.custom,
+                }),
+                languages: params.languages.iter().map(language_name).collect(),
+            })),
+        }
+    }
+}
+
+impl From<segment::data_types::index::KeywordIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::index::KeywordIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::KeywordIndexParams(KeywordIndexParams {
+                is_tenant: params.is_tenant,
             })),
         }
     }
 }
 
+impl From<KeywordIndexParams> for segment::data_types::index::KeywordIndexParams {
+    fn from(params: KeywordIndexParams) -> Self {
+        segment::data_types::index::KeywordIndexParams {
+            r#type: KeywordIndexType::Keyword,
+            is_tenant: params.is_tenant,
+        }
+    }
+}
+
 impl From<segment::types::PayloadIndexInfo> for PayloadSchemaInfo {
     fn from(schema: segment::types::PayloadIndexInfo) -> Self {
         PayloadSchemaInfo {
@@ -215,8 +244,12 @@ impl From<segment::types::PayloadIndexInfo> for PayloadSchemaInfo {
                 segment::types::PayloadSchemaParams::Text(text_index_params) => {
                     text_index_params.into()
                 }
+                segment::types::PayloadSchemaParams::Keyword(keyword_index_params) => {
+                    keyword_index_params.into()
+                }
             }),
             points: Some(schema.points as u64),
+            points_pending: schema.points_pending.map(|pending| pending as u64),
         }
     }
 }
@@ -234,6 +267,7 @@ impl TryFrom<TokenizerType> for segment::data_types::text_index::TokenizerType {
                 Ok(segment::data_types::text_index::TokenizerType::Whitespace)
             }
             TokenizerType::Word => Ok(segment::data_types::text_index::TokenizerType::Word),
+            TokenizerType::Ngram => Ok(segment::data_types::text_index::TokenizerType::Ngram),
         }
     }
 }
@@ -249,10 +283,49 @@ impl TryFrom<TextIndexParams> for segment::data_types::text_index::TextIndexPara
             lowercase: params.lowercase,
             min_token_len: params.min_token_len.map(|x| x as usize),
             max_token_len: params.max_token_len.map(|x| x as usize),
+            stemmer: params
+                .stemmer
+                .map(|stemmer| {
+                    Ok::<_, Status>(segment::data_types::text_index::StemmerParams {
+                        language: parse_language(stemmer.language)?,
+                    })
+                })
+                .transpose()?,
+            stopwords: params
+                .stopwords
+                .map(|stopwords| {
+                    Ok::<_, Status>(segment::data_types::text_index::StopwordsParams {
+                        languages: stopwords
+                            .languages
+                            .into_iter()
+                            .map(parse_language)
+                            .collect::<Result<_, _>>()?,
+                        custom: stopwords.custom,
+                    })
+                })
+                .transpose()?,
+            languages: params
+                .languages
+                .into_iter()
+                .map(parse_language)
+                .collect::<Result<_, _>>()?,
         })
     }
 }
 
+/// Name of the language, as it is used in REST API
+fn language_name(language: &impl serde::Serialize) -> String {
+    match serde_json::to_value(language) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => unreachable!("languages are serialized as strings"),
+    }
+}
+
+fn parse_language<T: serde::de::DeserializeOwned>(name: String) -> Result<T, Status> {
+    serde_json::from_value(serde_json::Value::String(name.to_lowercase()))
+        .map_err(|_| Status::invalid_argument(format!("unsupported language: {name}")))
+}
+
 impl TryFrom<PayloadIndexParams> for segment::data_types::text_index::TextIndexParams {
     type Error = Status;
     fn try_from(params: PayloadIndexParams) -> Result<Self, Self::Error> {
@@ -261,6 +334,9 @@ impl TryFrom<PayloadIndexParams> for segment::data_types::text_index::TextIndexP
             Some(IndexParams::TextIndexParams(text_index_params)) => {
                 Ok(text_index_params.try_into()?)
             }
+            Some(IndexParams::KeywordIndexParams(_)) => Err(Status::invalid_argument(
+                "keyword index params are not applicable to text index",
+            )),
         }
     }
 }
@@ -273,6 +349,9 @@ impl TryFrom<IndexParams> for segment::types::PayloadSchemaParams {
             IndexParams::TextIndexParams(text_index_params) => Ok(
                 segment::types::PayloadSchemaParams::Text(text_index_params.try_into()?),
             ),
+            IndexParams::KeywordIndexParams(keyword_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Keyword(keyword_index_params.into()),
+            ),
         }
     }
 }
@@ -313,6 +392,7 @@ impl TryFrom<PayloadSchemaInfo> for segment::types::PayloadIndexInfo {
             data_type,
             params,
             points: schema.points.unwrap_or(0) as usize,
+            points_pending: schema.points_pending.map(|pending| pending as usize),
         })
     }
 }
@@ -1099,6 +1179,57 @@ impl From<segment::types::Range> for Range {
     }
 }
 
+impl TryFrom<OrderBy> for segment::data_types::order_by::OrderBy {
+    type Error = Status;
+
+    fn try_from(value: OrderBy) -> Result<Self, Self::Error> {
+        let direction = value
+            .direction
+            .map(|direction| {
+                Direction::from_i32(direction)
+                    .map(segment::data_types::order_by::Direction::from)
+                    .ok_or_else(|| {
+                        Status::invalid_argument(format!("Unknown direction: {direction}"))
+                    })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            key: value.key,
+            direction,
+            start_from: value.start_from,
+        })
+    }
+}
+
+impl From<segment::data_types::order_by::OrderBy> for OrderBy {
+    fn from(value: segment::data_types::order_by::OrderBy) -> Self {
+        Self {
+            key: value.key,
+            direction: value.direction.map(|d| Direction::from(d) as i32),
+            start_from: value.start_from,
+        }
+    }
+}
+
+impl From<Direction> for segment::data_types::order_by::Direction {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Asc => Self::Asc,
+            Direction::Desc => Self::Desc,
+        }
+    }
+}
+
+impl From<segment::data_types::order_by::Direction> for Direction {
+    fn from(value: segment::data_types::order_by::Direction) -> Self {
+        match value {
+            segment::data_types::order_by::Direction::Asc => Self::Asc,
+            segment::data_types::order_by::Direction::Desc => Self::Desc,
+        }
+    }
+}
+
 impl From<ValuesCount> for segment::types::ValuesCount {
     fn from(value: ValuesCount) -> Self {
         Self {
@@ -1153,7 +1284,7 @@ impl From<segment::types::Match> for Match {
                 segment::types::ValueVariants::Integer(int) => MatchValue::Integer(int),
                 segment::types::ValueVariants::Bool(flag) => MatchValue::Boolean(flag),
             },
-            segment::types::Match::Text(segment::types::MatchText { text }) => {
+            segment::types::Match::Text(segment::types::MatchText { text, .. }) => {
                 MatchValue::Text(text)
             }
             segment::types::Match::Any(any) => match any.any {
@@ -5,15 +5,34 @@ use tonic::transport::{Channel, ClientTlsConfig, Error as TonicError, Uri};
 
 use crate::grpc::dynamic_pool::{CountedItem, DynamicPool};
 
+/// HTTP/2 keep-alive settings applied to the channels of a pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAliveConfig {
+    /// Interval between HTTP/2 PING frames sent to the remote side.
+    pub interval: Duration,
+    /// How long to wait for a PING acknowledgement before the connection is closed.
+    pub timeout: Duration,
+    /// Whether to send PING frames even if there are no in-flight requests.
+    pub while_idle: bool,
+}
+
 pub async fn make_grpc_channel(
     timeout: Duration,
     connection_timeout: Duration,
     uri: Uri,
     tls_config: Option<ClientTlsConfig>,
+    keep_alive: Option<KeepAliveConfig>,
 ) -> Result<Channel, TonicError> {
     let mut endpoint = Channel::builder(uri)
         .timeout(timeout)
         .connect_timeout(connection_timeout);
+    if let Some(keep_alive) = keep_alive {
+        endpoint = endpoint
+            .http2_keep_alive_interval(keep_alive.interval)
+            .keep_alive_timeout(keep_alive.timeout)
+            .keep_alive_while_idle(keep_alive.while_idle)
+            .tcp_keepalive(Some(keep_alive.interval));
+    }
     if let Some(config) = tls_config {
         endpoint = endpoint.tls_config(config)?;
     }
@@ -28,6 +47,7 @@ pub struct DynamicChannelPool {
     timeout: Duration,
     connection_timeout: Duration,
     tls_config: Option<ClientTlsConfig>,
+    keep_alive: Option<KeepAliveConfig>,
 }
 
 impl DynamicChannelPool {
@@ -36,14 +56,20 @@ impl DynamicChannelPool {
         timeout: Duration,
         connection_timeout: Duration,
         tls_config: Option<ClientTlsConfig>,
+        keep_alive: Option<KeepAliveConfig>,
         usage_per_channel: usize,
         min_channels: usize,
     ) -> Result<Self, TonicError> {
         let mut channels = Vec::with_capacity(min_channels);
         for _ in 0..min_channels {
-            let channel =
-                make_grpc_channel(timeout, connection_timeout, uri.clone(), tls_config.clone())
-                    .await?;
+            let channel = make_grpc_channel(
+                timeout,
+                connection_timeout,
+                uri.clone(),
+                tls_config.clone(),
+                keep_alive,
+            )
+            .await?;
             channels.push(channel);
         }
 
@@ -57,6 +83,7 @@ impl DynamicChannelPool {
             timeout,
             connection_timeout,
             tls_config,
+            keep_alive,
         })
     }
 
@@ -73,6 +100,7 @@ impl DynamicChannelPool {
                     self.connection_timeout,
                     self.uri.clone(),
                     self.tls_config.clone(),
+                    self.keep_alive,
                 )
                 .await?;
                 self.pool.lock().add(channel)
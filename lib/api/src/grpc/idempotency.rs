@@ -0,0 +1,263 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio::sync::OnceCell;
+use tokio::task::JoinError;
+use tonic::metadata::AsciiMetadataValue;
+use tonic::Request;
+
+/// gRPC metadata key, which carries the idempotency key of an internal update request
+pub const IDEMPOTENCY_KEY_HEADER: &str = "qdrant-idempotency-key";
+
+/// How long to remember the results of applied operations.
+///
+/// Should be longer than the time it takes to exhaust all retries of a single request.
+pub const DEFAULT_DEDUPLICATION_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How many results to remember at most. The oldest results are forgotten first.
+pub const DEFAULT_DEDUPLICATION_CAPACITY: usize = 100_000;
+
+/// Generate a new unique idempotency key
+pub fn new_idempotency_key() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Wrap the message into a request, tagged with the given idempotency key, if any
+pub fn request_with_idempotency_key<T>(message: T, idempotency_key: Option<&str>) -> Request<T> {
+    let mut request = Request::new(message);
+    if let Some(Ok(value)) = idempotency_key.map(AsciiMetadataValue::try_from) {
+        request.metadata_mut().insert(IDEMPOTENCY_KEY_HEADER, value);
+    }
+    request
+}
+
+/// Extract the idempotency key from the request, if any
+pub fn idempotency_key<T>(request: &Request<T>) -> Option<String> {
+    request
+        .metadata()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string)
+}
+
+/// Makes sure that operations with the same idempotency key are applied at most once.
+///
+/// If an operation with the same key is already in progress, the new one waits for it to finish
+/// and returns its result. Failed operations are not remembered, so they can be retried.
+///
+/// Operations with a key run in a separate task, so their result is remembered even if the
+/// request, which started them, is cancelled.
+pub struct OperationDeduplicator<T> {
+    ttl: Duration,
+    capacity: usize,
+    operations: Mutex<Operations<T>>,
+}
+
+struct Operations<T> {
+    results: HashMap<String, Arc<OnceCell<T>>>,
+    /// Keys in order of insertion, used to expire old results
+    expiration_queue: VecDeque<(Instant, String)>,
+}
+
+impl<T: Clone + Send + Sync + 'static> OperationDeduplicator<T> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            operations: Mutex::new(Operations {
+                results: HashMap::new(),
+                expiration_queue: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Run the `operation`, unless an operation with the same key was already applied.
+    ///
+    /// Operations without a key are always executed.
+    pub async fn deduplicate<E, F, Fut>(
+        &self,
+        idempotency_key: Option<String>,
+        operation: F,
+    ) -> Result<Result<T, E>, JoinError>
+    where
+        E: Send + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        let Some(idempotency_key) = idempotency_key else {
+            return Ok(operation().await);
+        };
+
+        let result_cell = self.result_cell(idempotency_key);
+        tokio::spawn(async move { result_cell.get_or_try_init(operation).await.cloned() }).await
+    }
+
+    fn result_cell(&self, idempotency_key: String) -> Arc<OnceCell<T>> {
+        let now = Instant::now();
+        let mut operations = self.operations.lock();
+
+        while let Some((created_at, _)) = operations.expiration_queue.front() {
+            if now.duration_since(*created_at) < self.ttl {
+                break;
+            }
+            if let Some((_, key)) = operations.expiration_queue.pop_front() {
+                operations.results.remove(&key);
+            }
+        }
+
+        while operations.results.len() >= self.capacity {
+            let Some((_, key)) = operations.expiration_queue.pop_front() else {
+                break;
+            };
+            operations.results.remove(&key);
+        }
+
+        if let Some(cell) = operations.results.get(&idempotency_key) {
+            return cell.clone();
+        }
+
+        let cell = Arc::new(OnceCell::new());
+        operations
+            .results
+            .insert(idempotency_key.clone(), cell.clone());
+        operations
+            .expiration_queue
+            .push_back((now, idempotency_key));
+        cell
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for OperationDeduplicator<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUPLICATION_TTL, DEFAULT_DEDUPLICATION_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_operation_applied_once() {
+        let deduplicator = OperationDeduplicator::<usize>::default();
+        let applied = Arc::new(AtomicUsize::new(0));
+
+        let apply = || {
+            let applied = applied.clone();
+            move || async move { Ok::<_, ()>(applied.fetch_add(1, Ordering::SeqCst) + 1) }
+        };
+
+        let key = new_idempotency_key();
+        let first = deduplicator.deduplicate(Some(key.clone()), apply()).await;
+        let second = deduplicator.deduplicate(Some(key), apply()).await;
+        assert_eq!(first.unwrap(), Ok(1));
+        assert_eq!(second.unwrap(), Ok(1));
+
+        // Other key, or no key at all, is applied again
+        let other = deduplicator
+            .deduplicate(Some(new_idempotency_key()), apply())
+            .await;
+        let without_key = deduplicator.deduplicate(None, apply()).await;
+        assert_eq!(other.unwrap(), Ok(2));
+        assert_eq!(without_key.unwrap(), Ok(3));
+    }
+
+    #[tokio::test]
+    async fn test_failed_operation_is_retried() {
+        let deduplicator = OperationDeduplicator::<usize>::default();
+        let key = new_idempotency_key();
+
+        let failed = deduplicator
+            .deduplicate(Some(key.clone()), || async { Err("transient") })
+            .await;
+        let retried = deduplicator
+            .deduplicate(Some(key.clone()), || async { Ok(42) })
+            .await;
+        let deduplicated = deduplicator
+            .deduplicate(Some(key), || async { Ok::<_, &str>(0) })
+            .await;
+
+        assert_eq!(failed.unwrap(), Err("transient"));
+        assert_eq!(retried.unwrap(), Ok(42));
+        assert_eq!(deduplicated.unwrap(), Ok(42));
+    }
+
+    #[tokio::test]
+    async fn test_results_expire() {
+        let deduplicator = OperationDeduplicator::<usize>::new(Duration::ZERO, 10);
+        let key = new_idempotency_key();
+
+        let first = deduplicator
+            .deduplicate(Some(key.clone()), || async { Ok::<_, ()>(1) })
+            .await;
+        let second = deduplicator
+            .deduplicate(Some(key), || async { Ok::<_, ()>(2) })
+            .await;
+
+        assert_eq!(first.unwrap(), Ok(1));
+        assert_eq!(second.unwrap(), Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_oldest_results_are_evicted() {
+        let deduplicator = OperationDeduplicator::<usize>::new(DEFAULT_DEDUPLICATION_TTL, 2);
+        let keys: Vec<_> = (0..3).map(|_| new_idempotency_key()).collect();
+
+        for (i, key) in keys.iter().enumerate() {
+            let result = deduplicator
+                .deduplicate(Some(key.clone()), move || async move { Ok::<_, ()>(i) })
+                .await;
+            assert_eq!(result.unwrap(), Ok(i));
+        }
+
+        // The first result is forgotten, the last one is remembered
+        let first = deduplicator
+            .deduplicate(Some(keys[0].clone()), || async { Ok::<_, ()>(10) })
+            .await;
+        let last = deduplicator
+            .deduplicate(Some(keys[2].clone()), || async { Ok::<_, ()>(10) })
+            .await;
+        assert_eq!(first.unwrap(), Ok(10));
+        assert_eq!(last.unwrap(), Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_request_is_applied() {
+        let deduplicator = OperationDeduplicator::<usize>::default();
+        let key = new_idempotency_key();
+        let (started_sender, started_receiver) = tokio::sync::oneshot::channel();
+
+        // The caller goes away while the operation is in progress
+        let cancelled = deduplicator.deduplicate(Some(key.clone()), || async move {
+            let _ = started_sender.send(());
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok::<_, ()>(1)
+        });
+        tokio::select! {
+            _ = cancelled => panic!("operation must not finish before the cancellation"),
+            _ = started_receiver => {}
+        }
+
+        // The retry gets the result of the first attempt instead of applying it again
+        let retried = deduplicator
+            .deduplicate(Some(key), || async { Ok::<_, ()>(2) })
+            .await;
+        assert_eq!(retried.unwrap(), Ok(1));
+    }
+
+    #[test]
+    fn test_idempotency_key_roundtrip() {
+        let key = new_idempotency_key();
+        let request = request_with_idempotency_key((), Some(&key));
+        assert_eq!(idempotency_key(&request), Some(key));
+        assert_eq!(
+            idempotency_key(&request_with_idempotency_key((), None)),
+            None
+        );
+    }
+}
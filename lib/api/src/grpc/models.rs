@@ -39,7 +39,7 @@ pub enum ApiStatus {
     Accepted,
 }
 
-#[derive(Debug, Serialize, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ApiResponse<D> {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -5,14 +5,19 @@ use std::time::Duration;
 
 use rand::{thread_rng, Rng};
 use tokio::select;
+use tonic::codec::CompressionEncoding;
 use tonic::codegen::InterceptedService;
 use tonic::service::Interceptor;
 use tonic::transport::{Channel, ClientTlsConfig, Error as TonicError, Uri};
 use tonic::{Code, Request, Status};
 
-use crate::grpc::dynamic_channel_pool::DynamicChannelPool;
+use crate::grpc::dynamic_channel_pool::{DynamicChannelPool, KeepAliveConfig};
 use crate::grpc::dynamic_pool::CountedItem;
+use crate::grpc::qdrant::collections_internal_client::CollectionsInternalClient;
+use crate::grpc::qdrant::points_internal_client::PointsInternalClient;
 use crate::grpc::qdrant::qdrant_client::QdrantClient;
+use crate::grpc::qdrant::qdrant_internal_client::QdrantInternalClient;
+use crate::grpc::qdrant::shard_snapshots_client::ShardSnapshotsClient;
 use crate::grpc::qdrant::HealthCheckRequest;
 
 /// Maximum lifetime of a gRPC channel.
@@ -31,7 +36,9 @@ pub const DEFAULT_POOL_SIZE: usize = 2;
 /// More info: <https://github.com/qdrant/qdrant/issues/1907>
 const MAX_CONNECTIONS_PER_CHANNEL: usize = 1024;
 pub const DEFAULT_RETRIES: usize = 2;
-const DEFAULT_BACKOFF: Duration = Duration::from_millis(100);
+pub const DEFAULT_BACKOFF: Duration = Duration::from_millis(100);
+/// Upper bound of the exponential backoff between retries
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
 
 /// How long to wait for response from server, before checking health of the server
 const SMART_CONNECT_INTERVAL: Duration = Duration::from_secs(1);
@@ -92,6 +99,36 @@ impl Interceptor for AddTimeout {
     }
 }
 
+/// Internal client, which can compress messages sent over the pooled channels.
+pub trait WithCompression {
+    /// Compress requests and accept compressed responses with the `encoding`, if set.
+    fn with_compression(self, encoding: Option<CompressionEncoding>) -> Self;
+}
+
+macro_rules! impl_with_compression {
+    ($($client:ident),*) => {
+        $(
+            impl WithCompression for $client<InterceptedService<Channel, AddTimeout>> {
+                fn with_compression(self, encoding: Option<CompressionEncoding>) -> Self {
+                    match encoding {
+                        Some(encoding) => {
+                            self.send_compressed(encoding).accept_compressed(encoding)
+                        }
+                        None => self,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_with_compression!(
+    PointsInternalClient,
+    CollectionsInternalClient,
+    ShardSnapshotsClient,
+    QdrantInternalClient
+);
+
 /// Holds a pool of channels established for a set of URIs.
 /// Channel are shared by cloning them.
 /// Make the `pool_size` larger to increase throughput.
@@ -101,6 +138,13 @@ pub struct TransportChannelPool {
     grpc_timeout: Duration,
     connection_timeout: Duration,
     tls_config: Option<ClientTlsConfig>,
+    keep_alive: Option<KeepAliveConfig>,
+    /// Number of retries used by [`TransportChannelPool::with_channel`]
+    retries: usize,
+    /// Base delay of the exponential backoff between retries
+    backoff: Duration,
+    /// Compression of messages of internal clients, see [`WithCompression`]
+    compression: Option<CompressionEncoding>,
 }
 
 impl Default for TransportChannelPool {
@@ -111,6 +155,10 @@ impl Default for TransportChannelPool {
             grpc_timeout: DEFAULT_GRPC_TIMEOUT,
             connection_timeout: DEFAULT_CONNECT_TIMEOUT,
             tls_config: None,
+            keep_alive: None,
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            compression: None,
         }
     }
 }
@@ -128,15 +176,46 @@ impl TransportChannelPool {
             connection_timeout,
             pool_size: NonZeroUsize::new(pool_size).unwrap(),
             tls_config,
+            keep_alive: None,
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            compression: None,
         }
     }
 
+    /// Enable HTTP/2 keep-alive pings on all channels created by this pool.
+    ///
+    /// Keeping idle connections alive avoids re-establishing them on traffic spikes,
+    /// which is especially costly between availability zones.
+    pub fn with_keep_alive(mut self, keep_alive: KeepAliveConfig) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// Set default number of retries and the base backoff delay between them.
+    pub fn with_retry_policy(mut self, retries: usize, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// Compress messages of internal clients with the given encoding.
+    pub fn with_compression(mut self, compression: Option<CompressionEncoding>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn compression(&self) -> Option<CompressionEncoding> {
+        self.compression
+    }
+
     async fn _init_pool_for_uri(&self, uri: Uri) -> Result<DynamicChannelPool, TonicError> {
         DynamicChannelPool::new(
             uri,
             MAX_GRPC_CHANNEL_TIMEOUT,
             self.connection_timeout,
             self.tls_config.clone(),
+            self.keep_alive,
             MAX_CONNECTIONS_PER_CHANNEL,
             self.pool_size.get(),
         )
@@ -366,7 +445,7 @@ impl TransportChannelPool {
                 RetryAction::RetryImmediately(fallback_status) => (Duration::ZERO, fallback_status),
                 RetryAction::RetryWithBackoff(fallback_status) => {
                     // Calculate backoff
-                    let backoff = DEFAULT_BACKOFF * 2u32.pow(attempt as u32)
+                    let backoff = exponential_backoff(self.backoff, attempt)
                         + Duration::from_millis(thread_rng().gen_range(0..100));
 
                     if backoff > max_timeout {
@@ -400,7 +479,30 @@ impl TransportChannelPool {
         uri: &Uri,
         f: impl Fn(InterceptedService<Channel, AddTimeout>) -> O,
     ) -> Result<T, RequestError<Status>> {
-        self.with_channel_timeout(uri, f, None, DEFAULT_RETRIES)
-            .await
+        self.with_channel_timeout(uri, f, None, self.retries).await
+    }
+}
+
+/// Delay before the retry after the given number of attempts, capped by [`MAX_BACKOFF`]
+fn exponential_backoff(base: Duration, attempt: usize) -> Duration {
+    u32::try_from(attempt)
+        .ok()
+        .and_then(|attempt| 2u32.checked_pow(attempt))
+        .and_then(|factor| base.checked_mul(factor))
+        .map_or(MAX_BACKOFF, |backoff| backoff.min(MAX_BACKOFF))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let base = Duration::from_millis(100);
+        assert_eq!(exponential_backoff(base, 0), base);
+        assert_eq!(exponential_backoff(base, 3), base * 8);
+        assert_eq!(exponential_backoff(base, 20), MAX_BACKOFF);
+        assert_eq!(exponential_backoff(base, 1000), MAX_BACKOFF);
+        assert_eq!(exponential_backoff(Duration::MAX, 1), MAX_BACKOFF);
     }
 }
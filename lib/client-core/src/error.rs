@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("Invalid API key")]
+    InvalidApiKey,
+    #[error("Request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("gRPC transport error: {0}")]
+    Transport(#[from] tonic::transport::Error),
+    #[error("Failed to parse response: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("Server responded with status {status}: {message}")]
+    Api { status: u16, message: String },
+    #[error("Server response contains no result")]
+    EmptyResult,
+}
@@ -0,0 +1,82 @@
+use api::grpc::qdrant::collections_client::CollectionsClient;
+use api::grpc::qdrant::points_client::PointsClient;
+use api::grpc::qdrant::qdrant_client::QdrantClient;
+use api::grpc::qdrant::shard_snapshots_client::ShardSnapshotsClient;
+use api::grpc::qdrant::snapshots_client::SnapshotsClient;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+use crate::error::ClientError;
+
+const API_KEY_HEADER: &str = "api-key";
+
+pub type GrpcChannel = InterceptedService<Channel, ApiKeyInterceptor>;
+
+/// Adds the API key, if any, to the metadata of each request
+#[derive(Clone)]
+pub struct ApiKeyInterceptor {
+    api_key: Option<MetadataValue<Ascii>>,
+}
+
+impl Interceptor for ApiKeyInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(api_key) = &self.api_key {
+            request
+                .metadata_mut()
+                .insert(API_KEY_HEADER, api_key.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// Clients of the public gRPC services, sharing a single connection
+#[derive(Clone)]
+pub struct GrpcClient {
+    channel: Channel,
+    interceptor: ApiKeyInterceptor,
+}
+
+impl GrpcClient {
+    pub async fn connect(url: &str, api_key: Option<&str>) -> Result<Self, ClientError> {
+        let api_key = api_key
+            .map(|api_key| api_key.parse())
+            .transpose()
+            .map_err(|_| ClientError::InvalidApiKey)?;
+        let channel = Channel::from_shared(url.to_string())
+            .map_err(|err| ClientError::InvalidUrl(err.to_string()))?
+            .connect()
+            .await?;
+        Ok(Self::from_channel(channel, api_key))
+    }
+
+    pub fn from_channel(channel: Channel, api_key: Option<MetadataValue<Ascii>>) -> Self {
+        Self {
+            channel,
+            interceptor: ApiKeyInterceptor { api_key },
+        }
+    }
+
+    pub fn collections(&self) -> CollectionsClient<GrpcChannel> {
+        CollectionsClient::with_interceptor(self.channel.clone(), self.interceptor.clone())
+    }
+
+    pub fn points(&self) -> PointsClient<GrpcChannel> {
+        PointsClient::with_interceptor(self.channel.clone(), self.interceptor.clone())
+    }
+
+    pub fn snapshots(&self) -> SnapshotsClient<GrpcChannel> {
+        SnapshotsClient::with_interceptor(self.channel.clone(), self.interceptor.clone())
+    }
+
+    pub fn shard_snapshots(&self) -> ShardSnapshotsClient<GrpcChannel> {
+        ShardSnapshotsClient::with_interceptor(self.channel.clone(), self.interceptor.clone())
+    }
+
+    /// Client of the service info and health check endpoints
+    pub fn service(&self) -> QdrantClient<GrpcChannel> {
+        QdrantClient::with_interceptor(self.channel.clone(), self.interceptor.clone())
+    }
+}
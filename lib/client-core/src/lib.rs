@@ -0,0 +1,15 @@
+//! Clients for Qdrant.
+//!
+//! * [`RestClient`] talks to the REST API, with bodies of any serde types matching the OpenAPI
+//!   specification.
+//! * [`GrpcClient`] provides the generated gRPC clients of the `api` crate over a shared channel,
+//!   so its messages are the same types the server uses.
+
+pub mod error;
+pub mod grpc;
+pub mod rest;
+
+pub use api::grpc::qdrant;
+pub use error::ClientError;
+pub use grpc::GrpcClient;
+pub use rest::RestClient;
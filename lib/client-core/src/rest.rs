@@ -0,0 +1,322 @@
+use api::grpc::models::{ApiResponse, ApiStatus, CollectionsResponse};
+use reqwest::{Method, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::ClientError;
+
+const API_KEY_HEADER: &str = "api-key";
+
+/// Client of the REST API
+///
+/// Bodies of requests and responses are any serde types matching the REST models of the OpenAPI
+/// specification, e.g. generated from it or plain [`serde_json::Value`].
+#[derive(Clone)]
+pub struct RestClient {
+    base_url: String,
+    api_key: Option<String>,
+    http: reqwest::Client,
+}
+
+impl RestClient {
+    pub fn new(base_url: &str, api_key: Option<String>) -> Self {
+        Self::with_http_client(base_url, api_key, reqwest::Client::new())
+    }
+
+    /// Use preconfigured HTTP client, e.g. with custom timeouts or TLS settings
+    pub fn with_http_client(
+        base_url: &str,
+        api_key: Option<String>,
+        http: reqwest::Client,
+    ) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            http,
+        }
+    }
+
+    pub async fn list_collections(&self) -> Result<CollectionsResponse, ClientError> {
+        self.send(Method::GET, &["collections"], &[], None::<&()>)
+            .await
+    }
+
+    pub async fn collection_info<T: DeserializeOwned>(
+        &self,
+        collection_name: &str,
+    ) -> Result<T, ClientError> {
+        self.send(
+            Method::GET,
+            &["collections", collection_name],
+            &[],
+            None::<&()>,
+        )
+        .await
+    }
+
+    pub async fn delete_collection(&self, collection_name: &str) -> Result<bool, ClientError> {
+        self.send(
+            Method::DELETE,
+            &["collections", collection_name],
+            &[],
+            None::<&()>,
+        )
+        .await
+    }
+
+    pub async fn upsert_points<B, T>(
+        &self,
+        collection_name: &str,
+        points: &B,
+        wait: bool,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let wait = wait.to_string();
+        self.send(
+            Method::PUT,
+            &["collections", collection_name, "points"],
+            &[("wait", wait.as_str())],
+            Some(points),
+        )
+        .await
+    }
+
+    pub async fn retrieve_points<B, T>(
+        &self,
+        collection_name: &str,
+        request: &B,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.points_request(collection_name, &[], request).await
+    }
+
+    pub async fn search_points<B, T>(
+        &self,
+        collection_name: &str,
+        request: &B,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.points_request(collection_name, &["search"], request)
+            .await
+    }
+
+    pub async fn search_batch_points<B, T>(
+        &self,
+        collection_name: &str,
+        request: &B,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.points_request(collection_name, &["search", "batch"], request)
+            .await
+    }
+
+    pub async fn count_points<B, T>(
+        &self,
+        collection_name: &str,
+        request: &B,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.points_request(collection_name, &["count"], request)
+            .await
+    }
+
+    pub async fn scroll_points<B, T>(
+        &self,
+        collection_name: &str,
+        request: &B,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.points_request(collection_name, &["scroll"], request)
+            .await
+    }
+
+    /// POST request to `/collections/{collection_name}/points/...`
+    async fn points_request<B, T>(
+        &self,
+        collection_name: &str,
+        path: &[&str],
+        body: &B,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let segments: Vec<_> = ["collections", collection_name, "points"]
+            .into_iter()
+            .chain(path.iter().copied())
+            .collect();
+        self.send(Method::POST, &segments, &[], Some(body)).await
+    }
+
+    /// Build URL of the endpoint, percent-encoding every path segment
+    fn url(&self, segments: &[&str], query: &[(&str, &str)]) -> Result<Url, ClientError> {
+        let mut url =
+            Url::parse(&self.base_url).map_err(|err| ClientError::InvalidUrl(err.to_string()))?;
+        url.path_segments_mut()
+            .map_err(|()| ClientError::InvalidUrl(self.base_url.clone()))?
+            .pop_if_empty()
+            .extend(segments);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+
+    async fn send<B, T>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let url = self.url(segments, query)?;
+        self.execute(method, url, body).await
+    }
+
+    /// Send request to any endpoint, not covered by the typed methods
+    ///
+    /// The `path` is appended to the base URL as is, so values in it must be percent-encoded.
+    pub async fn request<B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let url = Url::parse(&format!("{}{path}", self.base_url))
+            .map_err(|err| ClientError::InvalidUrl(err.to_string()))?;
+        self.execute(method, url, body).await
+    }
+
+    async fn execute<B, T>(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<&B>,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let mut request = self.http.request(method, url);
+        if let Some(api_key) = &self.api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+        if let Some(body) = body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(body)?);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        parse_response(status, &body)
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(status: StatusCode, body: &[u8]) -> Result<T, ClientError> {
+    let response: ApiResponse<T> = match serde_json::from_slice(body) {
+        Ok(response) => response,
+        // Some errors, e.g. rejected API key, are returned as plain text
+        Err(_) if !status.is_success() => {
+            return Err(ClientError::Api {
+                status: status.as_u16(),
+                message: String::from_utf8_lossy(body).into_owned(),
+            })
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    match response.status {
+        ApiStatus::Error(message) => Err(ClientError::Api {
+            status: status.as_u16(),
+            message,
+        }),
+        ApiStatus::Ok | ApiStatus::Accepted => response.result.ok_or(ClientError::EmptyResult),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Deserialize, Debug)]
+    struct CountResult {
+        count: usize,
+    }
+
+    #[test]
+    fn test_url_path_is_encoded() {
+        let client = RestClient::new("http://localhost:6333/", None);
+
+        let url = client
+            .url(
+                &["collections", "my collection/../a?b#c", "points"],
+                &[("wait", "true")],
+            )
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:6333/collections/my%20collection%2F..%2Fa%3Fb%23c/points?wait=true",
+        );
+
+        let client = RestClient::new("https://example.com/qdrant", None);
+        let url = client.url(&["collections"], &[]).unwrap();
+        assert_eq!(url.as_str(), "https://example.com/qdrant/collections");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let result: CountResult = parse_response(
+            StatusCode::OK,
+            br#"{"result":{"count":5},"status":"ok","time":0.001}"#,
+        )
+        .unwrap();
+        assert_eq!(result.count, 5);
+
+        let err = parse_response::<CountResult>(
+            StatusCode::NOT_FOUND,
+            br#"{"status":{"error":"Not found: Collection `test` doesn't exist!"},"time":0.0}"#,
+        )
+        .unwrap_err();
+        let ClientError::Api { status, message } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(status, 404);
+        assert!(message.contains("doesn't exist"));
+
+        let err =
+            parse_response::<CountResult>(StatusCode::FORBIDDEN, b"Invalid api-key").unwrap_err();
+        assert!(
+            matches!(err, ClientError::Api { status: 403, message } if message == "Invalid api-key")
+        );
+    }
+}
@@ -85,6 +85,10 @@ fn batch_search_bench(c: &mut Criterion) {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        metadata: None,
+        strict_mode_config: None,
+        rate_limit: None,
+        query_expansion: None,
     };
 
     let shared_config = Arc::new(RwLock::new(collection_config));
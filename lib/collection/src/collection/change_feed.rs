@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use tokio::sync::broadcast::error::RecvError;
+
+use super::Collection;
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::shards::change_feed::{ChangeReceiver, ShardChange};
+use crate::shards::shard::ShardId;
+
+/// Operation, applied to one of the local shards of the collection
+#[derive(Debug, Clone)]
+pub struct CollectionChange {
+    pub shard_id: ShardId,
+    pub change: Arc<ShardChange>,
+}
+
+impl Collection {
+    /// Subscribe to operations, applied to the shards of this peer
+    ///
+    /// Operations of each shard come in the order of the shard WAL, operations of different shards
+    /// are interleaved. Changes of shards on other peers are not included, so a consumer has to
+    /// subscribe on every peer.
+    ///
+    /// The stream fails if the consumer falls behind or if a local shard is replaced, e.g. by a
+    /// shard transfer. Operations after that point are missed and the consumer has to resync.
+    pub async fn subscribe_changes(
+        &self,
+    ) -> CollectionResult<BoxStream<'static, CollectionResult<CollectionChange>>> {
+        let shards_holder = self.shards_holder.read().await;
+        let mut shard_streams = vec![];
+        for replica_set in shards_holder.all_shards() {
+            if let Some(receiver) = replica_set.subscribe_local_changes().await {
+                shard_streams.push(shard_changes(replica_set.shard_id, receiver));
+            }
+        }
+
+        if shard_streams.is_empty() {
+            return Err(CollectionError::bad_request(format!(
+                "Collection {} has no shards on this peer",
+                self.name(),
+            )));
+        }
+
+        Ok(stream::select_all(shard_streams).boxed())
+    }
+}
+
+/// Stream of changes of a single shard, ends after the first error
+fn shard_changes(
+    shard_id: ShardId,
+    receiver: ChangeReceiver,
+) -> BoxStream<'static, CollectionResult<CollectionChange>> {
+    stream::unfold(Some(receiver), move |receiver| async move {
+        let mut receiver = receiver?;
+        match receiver.recv().await {
+            Ok(change) => Some((Ok(CollectionChange { shard_id, change }), Some(receiver))),
+            Err(RecvError::Lagged(skipped)) => {
+                let err = CollectionError::service_error(format!(
+                    "Subscriber fell behind, {skipped} operations of shard {shard_id} are missed",
+                ));
+                Some((Err(err), None))
+            }
+            Err(RecvError::Closed) => {
+                let err = CollectionError::service_error(format!(
+                    "Shard {shard_id} was unloaded from this peer",
+                ));
+                Some((Err(err), None))
+            }
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::ExtendedPointId;
+
+    use super::*;
+    use crate::operations::payload_ops::PayloadOps;
+    use crate::operations::CollectionUpdateOperations;
+    use crate::shards::change_feed::ChangeFeed;
+
+    fn operation() -> CollectionUpdateOperations {
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayload {
+            points: vec![ExtendedPointId::NumId(1)],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_shard_changes() {
+        let feed = ChangeFeed::default();
+        // Nothing is buffered without subscribers
+        feed.publish(0, &operation());
+
+        let mut changes = shard_changes(3, feed.subscribe());
+        feed.publish(1, &operation());
+        feed.publish(2, &operation());
+        drop(feed);
+
+        let first = changes.next().await.unwrap().unwrap();
+        assert_eq!((first.shard_id, first.change.operation_id), (3, 1));
+        let second = changes.next().await.unwrap().unwrap();
+        assert_eq!(second.change.operation_id, 2);
+
+        // Dropped feed is reported once, then the stream ends
+        assert!(changes.next().await.unwrap().is_err());
+        assert!(changes.next().await.is_none());
+    }
+}
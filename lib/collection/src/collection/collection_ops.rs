@@ -1,16 +1,21 @@
 use std::cmp;
+use std::collections::HashMap;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 
 use futures::{future, TryStreamExt as _};
-use segment::types::QuantizationConfig;
+use segment::types::{Payload, QuantizationConfig};
 
 use super::Collection;
+use crate::config::{QueryExpansionConfig, RateLimitConfig, StrictModeConfig};
 use crate::operations::config_diff::*;
+use crate::operations::query_expansion::ExpandableRequest;
 use crate::operations::shard_selector_internal::ShardSelectorInternal;
 use crate::operations::types::*;
+use crate::operations::verification::StrictModeVerification;
 use crate::optimizers_builder::OptimizersConfig;
 use crate::shards::replica_set::{Change, ReplicaState};
-use crate::shards::shard::PeerId;
+use crate::shards::shard::{PeerId, ShardId};
 
 impl Collection {
     /// Updates collection params:
@@ -101,6 +106,79 @@ impl Collection {
         Ok(())
     }
 
+    /// Replaces user metadata of the collection and saves it on disk
+    pub async fn update_metadata(&self, metadata: Payload) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            config.metadata = Some(metadata);
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        Ok(())
+    }
+
+    /// Replaces strict mode config of the collection and saves it on disk
+    pub async fn update_strict_mode_config(
+        &self,
+        strict_mode_config: StrictModeConfig,
+    ) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            config.strict_mode_config = Some(strict_mode_config);
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        Ok(())
+    }
+
+    /// Replaces rate limits of the collection and saves them on disk
+    pub async fn update_rate_limit(&self, rate_limit: RateLimitConfig) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            config.rate_limit = Some(rate_limit);
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        Ok(())
+    }
+
+    pub async fn rate_limit(&self) -> Option<RateLimitConfig> {
+        self.collection_config.read().await.rate_limit.clone()
+    }
+
+    /// Replaces query expansion config of the collection and saves it on disk
+    pub async fn update_query_expansion(
+        &self,
+        query_expansion: QueryExpansionConfig,
+    ) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            config.query_expansion = Some(query_expansion);
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        Ok(())
+    }
+
+    /// Expands full-text conditions of the request with synonyms and stop words of the collection
+    pub async fn expand_query(&self, request: &mut impl ExpandableRequest) {
+        let config = self.collection_config.read().await;
+        let Some(query_expansion) = &config.query_expansion else {
+            return;
+        };
+        for filter in request.filters_mut() {
+            query_expansion.expand_filter(filter);
+        }
+    }
+
+    /// Checks the request against the strict mode config of the collection, if it is set
+    pub async fn check_strict_mode(
+        &self,
+        request: &impl StrictModeVerification,
+    ) -> CollectionResult<()> {
+        let config = self.collection_config.read().await;
+        let Some(strict_mode_config) = &config.strict_mode_config else {
+            return Ok(());
+        };
+        request.check_strict_mode(strict_mode_config, &self.payload_index_schema.read().schema)
+    }
+
     /// Updates shard optimization params: Saves new params on disk
     ///
     /// After this, `recreate_optimizers_blocking` must be called to create new optimizers using
@@ -200,6 +278,30 @@ impl Collection {
         Ok(())
     }
 
+    /// Set desired number of replicas for the given shards
+    ///
+    /// Missing replicas are added in the background by `sync_local_state`.
+    /// `None` resets the shards to the replication factor of the collection.
+    pub async fn set_shard_replication_factor(
+        &self,
+        shard_ids: &[ShardId],
+        replication_factor: Option<NonZeroU32>,
+    ) -> CollectionResult<()> {
+        let shard_holder = self.shards_holder.read().await;
+
+        for shard_id in shard_ids {
+            let Some(replica_set) = shard_holder.get_shard(shard_id) else {
+                return Err(CollectionError::BadRequest {
+                    description: format!("Shard {} of {} not found", shard_id, self.name()),
+                });
+            };
+
+            replica_set.set_replication_factor(replication_factor)?;
+        }
+
+        Ok(())
+    }
+
     /// Recreate the optimizers on all shards for this collection
     ///
     /// This will stop existing optimizers, and start new ones with new configurations.
@@ -254,7 +356,12 @@ impl Collection {
             for (key, response_schema) in response.payload_schema {
                 info.payload_schema
                     .entry(key)
-                    .and_modify(|info_schema| info_schema.points += response_schema.points)
+                    .and_modify(|info_schema| {
+                        info_schema.points += response_schema.points;
+                        if let Some(pending) = response_schema.points_pending {
+                            *info_schema.points_pending.get_or_insert(0) += pending;
+                        }
+                    })
                     .or_insert(response_schema);
             }
         }
@@ -262,7 +369,31 @@ impl Collection {
         Ok(info)
     }
 
-    pub async fn cluster_info(&self, peer_id: PeerId) -> CollectionResult<CollectionClusterInfo> {
+    /// Exact number of points in every active replica of the shard
+    ///
+    /// Replicas with the same data have the same number of points. Counts of a shard, which
+    /// receives updates, might differ for a short time.
+    pub async fn count_shard_replicas(
+        &self,
+        shard_id: ShardId,
+    ) -> CollectionResult<HashMap<PeerId, usize>> {
+        let shards_holder = self.shards_holder.read().await;
+        let Some(replica_set) = shards_holder.get_shard(&shard_id) else {
+            return Err(CollectionError::NotFound {
+                what: format!("Shard {shard_id} of {}", self.name()),
+            });
+        };
+        replica_set.count_active_replicas().await
+    }
+
+    /// Shards of the collection and their replicas
+    ///
+    /// With `with_checksums`, all points of local shards are read to compute their checksums.
+    pub async fn cluster_info(
+        &self,
+        peer_id: PeerId,
+        with_checksums: bool,
+    ) -> CollectionResult<CollectionClusterInfo> {
         let shards_holder = self.shards_holder.read().await;
         let shard_count = shards_holder.len();
         let mut local_shards = Vec::new();
@@ -288,11 +419,17 @@ impl Collection {
                     .await
                     .unwrap_or_default();
                 let points_count = count_result.map(|x| x.count).unwrap_or(0);
+                let checksum = if with_checksums {
+                    replica_set.local_data_checksum().await?
+                } else {
+                    None
+                };
                 local_shards.push(LocalShardInfo {
                     shard_id,
                     points_count,
                     state,
                     shard_key: shard_to_key.get(&shard_id).cloned(),
+                    checksum,
                 })
             }
             for (peer_id, state) in replica_set.peers().into_iter() {
@@ -322,4 +459,130 @@ impl Collection {
         };
         Ok(info)
     }
+
+    /// Distribution of points between shards, compared to the assignment of the hash ring
+    pub async fn shards_distribution(&self) -> CollectionResult<CollectionShardsDistribution> {
+        let shards_holder = self.shards_holder.read().await;
+        let count_request = Arc::new(CountRequestInternal {
+            filter: None,
+            exact: false, // Don't need exact count of unique ids here, only size estimation
+        });
+
+        let mut rings = Vec::new();
+        for (shard_key, ring_shares) in shards_holder.hash_ring_shares() {
+            if ring_shares.is_empty() {
+                continue;
+            }
+
+            let mut shards = Vec::with_capacity(ring_shares.len());
+            for (shard_id, ring_share) in ring_shares {
+                let points_count = match shards_holder.get_shard(&shard_id) {
+                    Some(replica_set) => replica_set
+                        .count(count_request.clone(), None, false)
+                        .await
+                        .map(|count_result| count_result.count)
+                        .ok(),
+                    None => None,
+                };
+                shards.push(ShardDistributionInfo {
+                    shard_id,
+                    ring_share,
+                    points_count,
+                });
+            }
+            shards.sort_by_key(|shard| shard.shard_id);
+
+            let counts: Vec<_> = shards
+                .iter()
+                .filter_map(|shard| shard.points_count)
+                .collect();
+            let imbalance = match counts.iter().max() {
+                Some(&max_count) if max_count > 0 => {
+                    let mean_count = counts.iter().sum::<usize>() as f64 / counts.len() as f64;
+                    max_count as f64 / mean_count - 1.0
+                }
+                _ => 0.0,
+            };
+
+            rings.push(HashRingInfo {
+                shard_key,
+                shards,
+                imbalance,
+            });
+        }
+
+        Ok(CollectionShardsDistribution { rings })
+    }
+
+    /// Statistics of indexed payload fields, maintained by the field indexes of local shards
+    pub async fn statistics(&self) -> CollectionResult<CollectionStatistics> {
+        let shards_holder = self.shards_holder.read().await;
+        let mut statistics = CollectionStatistics {
+            local_shards_count: 0,
+            payload_fields: HashMap::new(),
+        };
+
+        for replica_set in shards_holder.all_shards() {
+            let Some(shard_statistics) = replica_set.get_local_field_statistics().await else {
+                continue;
+            };
+            statistics.local_shards_count += 1;
+            for (key, field_statistics) in shard_statistics {
+                statistics
+                    .payload_fields
+                    .entry(key)
+                    .or_default()
+                    .merge(field_statistics);
+            }
+        }
+
+        Ok(statistics)
+    }
+
+    /// Parameters of each vector with its usage by the local shards
+    pub async fn vectors_info(&self) -> CollectionResult<CollectionVectorsInfo> {
+        let params = self.collection_config.read().await.params.clone();
+        let shards_holder = self.shards_holder.read().await;
+
+        let mut local_shards_count = 0;
+        let mut usage: HashMap<String, VectorUsage> = HashMap::new();
+        for replica_set in shards_holder.all_shards() {
+            let Some(shard_usage) = replica_set.get_local_vectors_usage().await else {
+                continue;
+            };
+            local_shards_count += 1;
+            for (vector_name, vector_usage) in shard_usage {
+                usage.entry(vector_name).or_default().merge(vector_usage);
+            }
+        }
+
+        let dense_vectors = params
+            .vectors
+            .params_iter()
+            .map(|(name, params)| (name.to_string(), Some(params.clone()), None));
+        let sparse_vectors = params
+            .sparse_vectors
+            .into_iter()
+            .flatten()
+            .map(|(name, params)| (name, None, Some(params)));
+
+        let vectors = dense_vectors
+            .chain(sparse_vectors)
+            .map(|(name, params, sparse_params)| {
+                let usage = usage.remove(&name).unwrap_or_default();
+                let info = VectorInfo {
+                    params,
+                    sparse_params,
+                    index_status: VectorIndexStatus::from(&usage),
+                    usage,
+                };
+                (name, info)
+            })
+            .collect();
+
+        Ok(CollectionVectorsInfo {
+            local_shards_count,
+            vectors,
+        })
+    }
 }
@@ -0,0 +1,240 @@
+use futures::future;
+use itertools::Itertools;
+use segment::types::{Order, ScoredPoint, ShardKey, WithPayloadInterface, WithVector};
+
+use super::Collection;
+use crate::operations::shard_selector_internal::ShardSelectorInternal;
+use crate::operations::types::{CollectionError, CollectionResult, QueryEnum};
+use crate::shards::local_shard_cursor::CursorQuery;
+use crate::shards::replica_set::ShardCursor;
+use crate::shards::shard::ShardId;
+
+/// Cursor over the points of a search or a scroll of the whole collection
+///
+/// Every shard evaluates the query once, when the cursor is created. Pages are merged from the
+/// shards in the order of the result, payload and vectors are read for the points of a page only.
+pub struct CollectionCursor {
+    collection_name: String,
+    shards: Vec<CursorShard>,
+    order: CursorOrder,
+    with_payload: WithPayloadInterface,
+    with_vector: WithVector,
+    /// Number of points, which are left to page out
+    remaining: usize,
+}
+
+struct CursorShard {
+    shard_id: ShardId,
+    shard_key: Option<ShardKey>,
+    cursor: ShardCursor,
+}
+
+/// Order of the points of a cursor
+#[derive(Debug, Clone, Copy)]
+enum CursorOrder {
+    Score(Order),
+    Id,
+}
+
+impl CursorOrder {
+    /// Whether point `a` comes before point `b`
+    fn precedes(self, a: &ScoredPoint, b: &ScoredPoint) -> bool {
+        match self {
+            CursorOrder::Score(Order::LargeBetter) => a.score > b.score,
+            CursorOrder::Score(Order::SmallBetter) => a.score < b.score,
+            CursorOrder::Id => a.id < b.id,
+        }
+    }
+}
+
+impl CollectionCursor {
+    /// Name of the collection, which the cursor was created for
+    pub fn collection_name(&self) -> &str {
+        &self.collection_name
+    }
+
+    /// Next point of the cursor, which is not paged out yet
+    pub fn peek(&self) -> Option<&ScoredPoint> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.shards
+            .iter()
+            .filter_map(|shard| shard.cursor.points().front())
+            .reduce(|best, candidate| {
+                if self.order.precedes(candidate, best) {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.peek().is_none()
+    }
+
+    /// Number of points of each shard among the next `size` points of the cursor
+    fn take_counts(&self, size: usize) -> Vec<usize> {
+        let mut counts = vec![0; self.shards.len()];
+        for _ in 0..size {
+            let next = self
+                .shards
+                .iter()
+                .enumerate()
+                .filter_map(|(index, shard)| {
+                    Some((index, shard.cursor.points().get(counts[index])?))
+                })
+                .reduce(|best, candidate| {
+                    if self.order.precedes(candidate.1, best.1) {
+                        candidate
+                    } else {
+                        best
+                    }
+                });
+
+            let Some((index, _)) = next else {
+                break;
+            };
+            counts[index] += 1;
+        }
+        counts
+    }
+}
+
+impl Collection {
+    /// Evaluate the query once on every shard, and create a cursor to page out its points
+    ///
+    /// A search cursor pages out at most `limit` points after `offset`, in the order of their
+    /// scores. A scroll cursor pages out all points, which satisfy the filter, in ascending order
+    /// of their IDs.
+    pub async fn cursor(
+        &self,
+        query: CursorQuery,
+        with_payload: WithPayloadInterface,
+        with_vector: WithVector,
+        shard_selection: &ShardSelectorInternal,
+    ) -> CollectionResult<CollectionCursor> {
+        let (order, offset, limit) = match &query {
+            CursorQuery::Search { request, .. } => {
+                let order = match &request.query {
+                    QueryEnum::Nearest(_) => self
+                        .collection_config
+                        .read()
+                        .await
+                        .params
+                        .get_distance(request.query.get_vector_name())?
+                        .distance_order(),
+
+                    // Same as for the regular search, the score of these queries is not a distance
+                    QueryEnum::Discover(_)
+                    | QueryEnum::Context(_)
+                    | QueryEnum::RecommendBestScore(_) => Order::LargeBetter,
+                };
+                (CursorOrder::Score(order), request.offset, request.limit)
+            }
+            CursorQuery::Scroll { .. } => (CursorOrder::Id, 0, usize::MAX),
+        };
+
+        let shards = {
+            let shards_holder = self.shards_holder.read().await;
+            let target_shards = shards_holder.select_shards(shard_selection)?;
+            let query = &query;
+            let cursor_futures =
+                target_shards
+                    .into_iter()
+                    .map(|(replica_set, shard_key)| async move {
+                        let cursor = replica_set.cursor(query).await?;
+                        CollectionResult::Ok(CursorShard {
+                            shard_id: replica_set.shard_id,
+                            shard_key: shard_key.cloned(),
+                            cursor,
+                        })
+                    });
+            future::try_join_all(cursor_futures).await?
+        };
+
+        let mut cursor = CollectionCursor {
+            collection_name: self.name(),
+            shards,
+            order,
+            with_payload,
+            with_vector,
+            remaining: limit,
+        };
+
+        // Every shard found `offset + limit` points, the first `offset` of them are not paged out
+        let skipped = cursor.take_counts(offset);
+        for (shard, count) in cursor.shards.iter_mut().zip(skipped) {
+            shard.cursor.skip(count);
+        }
+
+        Ok(cursor)
+    }
+
+    /// Read the next `size` points of the cursor with their payload and vectors
+    ///
+    /// Points deleted after the cursor was created are skipped, so a page may be shorter than
+    /// `size`. An empty page is returned only once the cursor is exhausted.
+    pub async fn cursor_next_page(
+        &self,
+        cursor: &mut CollectionCursor,
+        size: usize,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        if size == 0 {
+            return Err(CollectionError::bad_request(
+                "Page size must be at least 1".to_string(),
+            ));
+        }
+        if cursor.collection_name != self.name() {
+            return Err(CollectionError::bad_request(format!(
+                "Cursor was created for collection {}",
+                cursor.collection_name,
+            )));
+        }
+
+        while !cursor.is_exhausted() {
+            let counts = cursor.take_counts(size.min(cursor.remaining));
+            cursor.remaining -= counts.iter().sum::<usize>();
+
+            let shards_holder = self.shards_holder.read().await;
+            let with_payload = &cursor.with_payload;
+            let with_vector = &cursor.with_vector;
+            let page_futures = cursor
+                .shards
+                .iter_mut()
+                .zip(counts)
+                .filter(|(_, count)| *count > 0)
+                .map(|(shard, count)| {
+                    let replica_set = shards_holder.get_shard(&shard.shard_id);
+                    async move {
+                        let replica_set = replica_set.ok_or_else(|| {
+                            CollectionError::service_error(format!(
+                                "Shard {} is not found",
+                                shard.shard_id,
+                            ))
+                        })?;
+                        let mut page = replica_set
+                            .cursor_next_page(&mut shard.cursor, count, with_payload, with_vector)
+                            .await?;
+                        for point in &mut page {
+                            point.shard_key.clone_from(&shard.shard_key);
+                        }
+                        CollectionResult::Ok(page)
+                    }
+                });
+            let pages = future::try_join_all(page_futures).await?;
+
+            let order = cursor.order;
+            let page = pages
+                .into_iter()
+                .kmerge_by(|a, b| order.precedes(a, b))
+                .collect_vec();
+            if !page.is_empty() {
+                return Ok(page);
+            }
+        }
+
+        Ok(vec![])
+    }
+}
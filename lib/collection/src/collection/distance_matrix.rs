@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use segment::data_types::vectors::{NamedVectorStruct, Vector, DEFAULT_VECTOR_NAME};
+use segment::types::{
+    Condition, Filter, HasIdCondition, PointIdType, WithPayloadInterface, WithVector,
+};
+
+use super::Collection;
+use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::shard_selector_internal::ShardSelectorInternal;
+use crate::operations::types::*;
+
+impl Collection {
+    /// Sample points and find the nearest neighbours of each sampled point within the sample.
+    ///
+    /// The result can be used to build a sparse distance matrix of the sampled points.
+    pub async fn search_points_matrix(
+        &self,
+        request: SearchMatrixRequestInternal,
+        shard_selection: ShardSelectorInternal,
+        read_consistency: Option<ReadConsistency>,
+        timeout: Option<Duration>,
+    ) -> CollectionResult<SearchMatrix> {
+        let SearchMatrixRequestInternal {
+            filter,
+            sample,
+            limit,
+            using,
+        } = request;
+
+        let sample_size = sample.unwrap_or(DEFAULT_SEARCH_MATRIX_SAMPLE);
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_MATRIX_LIMIT);
+        let vector_name = using.unwrap_or_else(|| DEFAULT_VECTOR_NAME.to_string());
+
+        if sample_size < 2 {
+            return Err(CollectionError::bad_request(
+                "Sample size must be at least 2".to_string(),
+            ));
+        }
+
+        // Sample random points together with the vector to search with
+        let sample_request = ScrollRequestInternal {
+            offset: None,
+            limit: Some(sample_size),
+            filter: filter.clone(),
+            with_payload: Some(WithPayloadInterface::Bool(false)),
+            with_vector: WithVector::Selector(vec![vector_name.clone()]),
+            order_by: None,
+            query: Some(ScrollQuery::Sample(Sample::Random)),
+        };
+        let sampled_points = self
+            .scroll_by(sample_request, read_consistency, &shard_selection)
+            .await?
+            .points;
+
+        // Points without the requested vector can't take part in the matrix
+        let sampled: Vec<(PointIdType, Vector)> = sampled_points
+            .into_iter()
+            .filter_map(|record| {
+                let vector = record.vector.as_ref()?.get(&vector_name)?;
+                Some((record.id, Vector::from(vector)))
+            })
+            .collect();
+
+        if sampled.len() < 2 {
+            return Ok(SearchMatrix {
+                nearests: vec![vec![]; sampled.len()],
+                sample_ids: sampled.into_iter().map(|(id, _)| id).collect(),
+            });
+        }
+
+        let sample_ids: Vec<PointIdType> = sampled.iter().map(|(id, _)| *id).collect();
+
+        // Search only among the sampled points
+        let sample_filter = Filter::new_must(Condition::HasId(HasIdCondition {
+            has_id: sample_ids.iter().copied().collect(),
+        }));
+        let filter = match filter {
+            Some(filter) => filter.merge(&sample_filter),
+            None => sample_filter,
+        };
+
+        let searches = sampled
+            .into_iter()
+            .map(|(_, vector)| CoreSearchRequest {
+                query: QueryEnum::Nearest(NamedVectorStruct::new_from_vector(
+                    vector,
+                    vector_name.clone(),
+                )),
+                filter: Some(filter.clone()),
+                params: None,
+                // One extra point, as the sampled point itself is also found
+                limit: limit + 1,
+                offset: 0,
+                with_payload: None,
+                with_vector: None,
+                score_threshold: None,
+            })
+            .collect();
+
+        let results = self
+            .core_search_batch(
+                CoreSearchRequestBatch { searches },
+                read_consistency,
+                shard_selection,
+                timeout,
+            )
+            .await?;
+
+        let nearests = sample_ids
+            .iter()
+            .zip(results)
+            .map(|(sample_id, hits)| {
+                hits.into_iter()
+                    .filter(|hit| hit.id != *sample_id)
+                    .take(limit)
+                    .collect()
+            })
+            .collect();
+
+        Ok(SearchMatrix {
+            sample_ids,
+            nearests,
+        })
+    }
+}
@@ -0,0 +1,413 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef;
+use futures::future;
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+use segment::data_types::vectors::VectorStruct;
+use segment::types::{Filter, Payload, PointIdType, ShardKey, WithPayloadInterface, WithVector};
+use serde::Serialize;
+use tempfile::TempDir;
+use url::Url;
+
+use super::Collection;
+use crate::common::arrow_points::{points_schema, records_to_batch, selected_dense_vectors};
+use crate::common::snapshots_manager::upload_object;
+use crate::operations::shard_selector_internal::ShardSelectorInternal;
+use crate::operations::types::*;
+use crate::shards::replica_set::ShardReplicaSet;
+
+/// Single point of a JSONL export, in the format of the upsert points API
+#[derive(Serialize)]
+struct ExportedPoint {
+    id: PointIdType,
+    vector: VectorStruct,
+    payload: Payload,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shard_key: Option<ShardKey>,
+}
+
+impl From<Record> for ExportedPoint {
+    fn from(record: Record) -> Self {
+        Self {
+            id: record.id,
+            vector: record
+                .vector
+                .unwrap_or_else(|| VectorStruct::Multi(Default::default())),
+            payload: record.payload.unwrap_or_default(),
+            shard_key: record.shard_key,
+        }
+    }
+}
+
+fn parquet_error(err: ParquetError) -> CollectionError {
+    CollectionError::service_error(format!("Can't write Parquet file: {err}"))
+}
+
+/// Writes points of a single shard into a local file
+enum PointsWriter {
+    Jsonl(BufWriter<File>),
+    Parquet {
+        writer: ArrowWriter<File>,
+        schema: SchemaRef,
+        vectors: Vec<(String, usize)>,
+    },
+}
+
+impl PointsWriter {
+    fn create(
+        path: &Path,
+        format: ExportFormat,
+        vectors: &[(String, usize)],
+    ) -> CollectionResult<Self> {
+        // Existing files are never overwritten
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::AlreadyExists => {
+                    CollectionError::bad_request(format!("File {} already exists", path.display()))
+                }
+                _ => err.into(),
+            })?;
+        match format {
+            ExportFormat::Jsonl => Ok(Self::Jsonl(BufWriter::new(file))),
+            ExportFormat::Parquet => {
+                let schema = points_schema(vectors);
+                let writer =
+                    ArrowWriter::try_new(file, schema.clone(), None).map_err(parquet_error)?;
+                Ok(Self::Parquet {
+                    writer,
+                    schema,
+                    vectors: vectors.to_vec(),
+                })
+            }
+        }
+    }
+
+    fn write(&mut self, records: Vec<Record>) -> CollectionResult<()> {
+        match self {
+            Self::Jsonl(writer) => {
+                for record in records {
+                    serde_json::to_writer(&mut *writer, &ExportedPoint::from(record))?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+            Self::Parquet {
+                writer,
+                schema,
+                vectors,
+            } => {
+                let batch = records_to_batch(schema.clone(), vectors, &records).map_err(|err| {
+                    CollectionError::service_error(format!("Can't convert points: {err}"))
+                })?;
+                writer.write(&batch).map_err(parquet_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> CollectionResult<()> {
+        match self {
+            Self::Jsonl(mut writer) => writer.flush()?,
+            Self::Parquet { writer, .. } => {
+                writer.close().map_err(parquet_error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where exported files are written to
+enum ExportDestination {
+    /// Files are written directly into a local directory
+    Local(PathBuf),
+    /// Files are written into a temporary directory and uploaded under a key prefix
+    S3 {
+        store: Arc<dyn ObjectStore>,
+        bucket: String,
+        prefix: ObjectPath,
+    },
+}
+
+impl ExportDestination {
+    fn new(location: &Url) -> CollectionResult<Self> {
+        match location.scheme() {
+            "file" => {
+                let dir = location.to_file_path().map_err(|_| {
+                    CollectionError::bad_request(
+                        "Invalid export URL, file path must be absolute or on localhost"
+                            .to_string(),
+                    )
+                })?;
+                Ok(Self::Local(dir))
+            }
+            "s3" => {
+                let bucket = location.host_str().unwrap_or_default().to_string();
+                // Credentials and region are taken from `AWS_*` environment variables
+                let store = AmazonS3Builder::from_env()
+                    .with_url(location.as_str())
+                    .build()
+                    .map_err(|err| {
+                        CollectionError::bad_request(format!(
+                            "Can't configure S3 storage for export: {err}"
+                        ))
+                    })?;
+                Ok(Self::S3 {
+                    store: Arc::new(store),
+                    bucket,
+                    prefix: ObjectPath::from(location.path()),
+                })
+            }
+            scheme => Err(CollectionError::bad_request(format!(
+                "URL {location} with schema {scheme} is not supported"
+            ))),
+        }
+    }
+}
+
+/// What to export from each shard
+struct ExportSelection {
+    format: ExportFormat,
+    filter: Option<Filter>,
+    with_payload: WithPayloadInterface,
+    with_vector: WithVector,
+    /// Dense vectors, which are stored in Parquet columns
+    vectors: Vec<(String, usize)>,
+    batch_size: usize,
+}
+
+impl Collection {
+    /// Export points into files, one per shard. Shards are read and written in parallel.
+    ///
+    /// Files for object storage are written into `temp_dir` first and uploaded once complete.
+    pub async fn export_points(
+        &self,
+        request: ExportPointsRequest,
+        temp_dir: &Path,
+    ) -> CollectionResult<ExportPointsResult> {
+        let ExportPointsRequest {
+            location,
+            format,
+            filter,
+            with_payload,
+            with_vector,
+            batch_size,
+        } = request;
+
+        let with_vector = with_vector.unwrap_or(WithVector::Bool(true));
+        let vectors = {
+            let config = self.collection_config.read().await;
+            selected_dense_vectors(&config.params.vectors, &with_vector)
+        };
+        let selection = ExportSelection {
+            format,
+            filter,
+            with_payload: with_payload.unwrap_or(WithPayloadInterface::Bool(true)),
+            with_vector,
+            vectors,
+            batch_size: batch_size.unwrap_or(DEFAULT_EXPORT_BATCH_SIZE),
+        };
+
+        let destination = ExportDestination::new(&location)?;
+        let (local_dir, _temp_dir): (PathBuf, Option<TempDir>) = match &destination {
+            ExportDestination::Local(dir) => {
+                tokio::fs::create_dir_all(dir).await?;
+                (dir.clone(), None)
+            }
+            ExportDestination::S3 { .. } => {
+                let dir = tempfile::Builder::new()
+                    .prefix(&format!("{}-export-", self.name()))
+                    .tempdir_in(temp_dir)?;
+                (dir.path().to_path_buf(), Some(dir))
+            }
+        };
+
+        let shards_holder = self.shards_holder.read().await;
+        let target_shards = shards_holder.select_shards(&ShardSelectorInternal::All)?;
+        let export_futures = target_shards.into_iter().map(|(shard, shard_key)| {
+            let file_name = format!(
+                "{}-shard-{}.{}",
+                self.name(),
+                shard.shard_id,
+                format.extension(),
+            );
+            let local_path = local_dir.join(&file_name);
+            let destination = &destination;
+            let selection = &selection;
+            async move {
+                let points_count =
+                    export_shard(shard, shard_key.cloned(), &local_path, selection).await?;
+
+                let location = match destination {
+                    ExportDestination::Local(_) => Url::from_file_path(&local_path)
+                        .map(String::from)
+                        .unwrap_or_else(|_| local_path.display().to_string()),
+                    ExportDestination::S3 {
+                        store,
+                        bucket,
+                        prefix,
+                    } => {
+                        let key = prefix.child(file_name.as_str());
+                        let file = tokio::fs::File::open(&local_path).await?;
+                        upload_object(store.as_ref(), &key, file).await?;
+                        tokio::fs::remove_file(&local_path).await?;
+                        format!("s3://{bucket}/{key}")
+                    }
+                };
+
+                CollectionResult::Ok(ExportedFile {
+                    location,
+                    points_count,
+                })
+            }
+        });
+        let files = future::try_join_all(export_futures).await?;
+
+        Ok(ExportPointsResult {
+            points_count: files.iter().map(|file| file.points_count).sum(),
+            files,
+        })
+    }
+}
+
+/// Scroll over all selected points of the shard and write them into the file at `path`
+///
+/// The file is removed if the export fails.
+async fn export_shard(
+    shard: &ShardReplicaSet,
+    shard_key: Option<ShardKey>,
+    path: &Path,
+    selection: &ExportSelection,
+) -> CollectionResult<usize> {
+    let mut writer = PointsWriter::create(path, selection.format, &selection.vectors)?;
+
+    let write_points = async move {
+        let mut points_count = 0;
+        let mut offset = None;
+        loop {
+            // One more point is read to start the next page from it
+            let mut records = shard
+                .scroll_by(
+                    offset,
+                    selection.batch_size + 1,
+                    &selection.with_payload,
+                    &selection.with_vector,
+                    selection.filter.as_ref(),
+                    None,
+                    false,
+                    None,
+                    None,
+                )
+                .await?;
+            offset = if records.len() > selection.batch_size {
+                records.pop().map(|record| record.id)
+            } else {
+                None
+            };
+
+            for record in &mut records {
+                record.shard_key = shard_key.clone();
+            }
+            points_count += records.len();
+
+            // Serialization and compression are CPU bound, so they are done outside of async
+            writer = tokio::task::spawn_blocking(move || {
+                writer.write(records)?;
+                CollectionResult::Ok(writer)
+            })
+            .await??;
+
+            if offset.is_none() {
+                break;
+            }
+        }
+        tokio::task::spawn_blocking(move || writer.finish()).await??;
+        CollectionResult::Ok(points_count)
+    };
+
+    let result = write_points.await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
+    use serde_json::json;
+
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        (1..=3)
+            .map(|id| Record {
+                id: PointIdType::NumId(id),
+                payload: Some(Payload::from(json!({ "number": id }))),
+                vector: Some(VectorStruct::Single(vec![id as f32, 0.5])),
+                shard_key: None,
+                order_value: None,
+                version: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_write_jsonl() {
+        let dir = tempfile::Builder::new().prefix("export").tempdir().unwrap();
+        let path = dir.path().join("points.jsonl");
+
+        let mut writer = PointsWriter::create(&path, ExportFormat::Jsonl, &[]).unwrap();
+        writer.write(records()).unwrap();
+        writer.finish().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            json!({"id": 1, "vector": [1.0, 0.5], "payload": {"number": 1}}),
+        );
+
+        // Existing files are not overwritten
+        assert!(PointsWriter::create(&path, ExportFormat::Jsonl, &[]).is_err());
+    }
+
+    #[test]
+    fn test_write_parquet() {
+        let dir = tempfile::Builder::new().prefix("export").tempdir().unwrap();
+        let path = dir.path().join("points.parquet");
+        let vectors = vec![(DEFAULT_VECTOR_NAME.to_string(), 2)];
+
+        let mut writer = PointsWriter::create(&path, ExportFormat::Parquet, &vectors).unwrap();
+        writer.write(records()).unwrap();
+        writer.write(vec![]).unwrap();
+        writer.finish().unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(Result::unwrap).collect();
+        let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(rows, 3);
+
+        let schema = batches[0].schema();
+        let columns: Vec<_> = schema.fields().iter().map(|field| field.name()).collect();
+        assert_eq!(columns, ["id", "payload", "vector"]);
+    }
+}
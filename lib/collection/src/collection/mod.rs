@@ -1,27 +1,35 @@
+pub mod change_feed;
 mod collection_ops;
+pub mod cursor;
+mod distance_matrix;
+mod export;
 pub mod payload_index_schema;
 mod point_ops;
+mod quantization_estimate;
 mod search;
 mod shard_transfer;
 mod sharding_keys;
 mod snapshots;
 mod state_management;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
+use segment::common::migration::migrate_storage;
 use segment::common::version::StorageVersion;
 use segment::types::ShardKey;
 use semver::Version;
 use tokio::runtime::Handle;
 use tokio::sync::{Mutex, RwLock, RwLockWriteGuard};
+use tokio::task::JoinHandle;
 
 use crate::collection::payload_index_schema::PayloadIndexSchema;
 use crate::collection_state::{ShardInfo, State};
 use crate::common::is_ready::IsReady;
+use crate::common::maintenance_hooks::MaintenanceHooks;
 use crate::config::CollectionConfig;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{CollectionError, CollectionResult, NodeType};
@@ -32,7 +40,9 @@ use crate::shards::replica_set::ReplicaState::{Active, Dead, Initializing, Liste
 use crate::shards::replica_set::{ChangePeerState, ReplicaState, ShardReplicaSet};
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_holder::{shard_not_found_error, LockedShardHolder, ShardHolder};
-use crate::shards::transfer::helpers::check_transfer_conflicts_strict;
+use crate::shards::transfer::helpers::{
+    check_transfer_conflicts_strict, suggest_peer_to_add_replica, suggest_transfer_source,
+};
 use crate::shards::transfer::transfer_tasks_pool::TransferTasksPool;
 use crate::shards::transfer::ShardTransfer;
 use crate::shards::{replica_set, CollectionId};
@@ -61,7 +71,9 @@ pub struct Collection {
     // Lock to temporary block collection update operations while the collection is being migrated.
     // Lock is acquired for read on update operation and can be acquired for write externally,
     // which will block all update operations until the lock is released.
-    updates_lock: RwLock<()>,
+    updates_lock: Arc<RwLock<()>>,
+    // Background tasks, which move points of the split shard keys into the new shards
+    shard_key_split_tasks: Mutex<HashMap<ShardKey, JoinHandle<CollectionResult<()>>>>,
     // Update runtime handle.
     update_runtime: Handle,
     // Search runtime handle.
@@ -143,7 +155,8 @@ impl Collection {
             abort_shard_transfer_cb: abort_shard_transfer,
             init_time: start_time.elapsed(),
             is_initialized: Arc::new(Default::default()),
-            updates_lock: RwLock::new(()),
+            updates_lock: Arc::new(RwLock::new(())),
+            shard_key_split_tasks: Mutex::new(HashMap::new()),
             update_runtime: update_runtime.unwrap_or_else(Handle::current),
             search_runtime: search_runtime.unwrap_or_else(Handle::current),
         })
@@ -164,29 +177,8 @@ impl Collection {
         update_runtime: Option<Handle>,
     ) -> Self {
         let start_time = std::time::Instant::now();
-        let stored_version = CollectionVersion::load(path)
-            .expect("Can't read collection version")
-            .parse()
-            .expect("Failed to parse stored collection version as semver");
-
-        let app_version: Version = CollectionVersion::current()
-            .parse()
-            .expect("Failed to parse current collection version as semver");
-
-        if stored_version > app_version {
-            panic!("Collection version is greater than application version");
-        }
-
-        if stored_version != app_version {
-            if Self::can_upgrade_storage(&stored_version, &app_version) {
-                log::info!("Migrating collection {stored_version} -> {app_version}");
-                CollectionVersion::save(path)
-                    .unwrap_or_else(|err| panic!("Can't save collection version {err}"));
-            } else {
-                log::error!("Cannot upgrade version {stored_version} to {app_version}.");
-                panic!("Cannot upgrade version {stored_version} to {app_version}. Try to use older version of Qdrant first.");
-            }
-        }
+        migrate_storage::<CollectionVersion>(path, &MIN_COLLECTION_VERSION, &[])
+            .unwrap_or_else(|err| panic!("Can't upgrade collection storage: {err}"));
 
         let collection_config = CollectionConfig::load(path).unwrap_or_else(|err| {
             panic!(
@@ -237,39 +229,22 @@ impl Collection {
             abort_shard_transfer_cb: abort_shard_transfer,
             init_time: start_time.elapsed(),
             is_initialized: Arc::new(Default::default()),
-            updates_lock: RwLock::new(()),
+            updates_lock: Arc::new(RwLock::new(())),
+            shard_key_split_tasks: Mutex::new(HashMap::new()),
             update_runtime: update_runtime.unwrap_or_else(Handle::current),
             search_runtime: search_runtime.unwrap_or_else(Handle::current),
         }
     }
 
-    /// Check if stored version have consequent version.
-    /// If major version is different, then it is not compatible.
-    /// If the difference in consecutive versions is greater than 1 in patch,
-    /// then the collection is not compatible with the current version.
-    ///
-    /// Example:
-    ///   0.4.0 -> 0.4.1 = true
-    ///   0.4.0 -> 0.4.2 = false
-    ///   0.4.0 -> 0.5.0 = false
-    ///   0.4.0 -> 0.5.1 = false
-    pub fn can_upgrade_storage(stored: &Version, app: &Version) -> bool {
-        if stored.major != app.major {
-            return false;
-        }
-        if stored.minor != app.minor {
-            return false;
-        }
-        if stored.patch + 1 < app.patch {
-            return false;
-        }
-        true
-    }
-
     pub fn name(&self) -> String {
         self.id.clone()
     }
 
+    /// Hooks to run around maintenance operations of the collection
+    pub(crate) fn maintenance_hooks(&self) -> MaintenanceHooks {
+        MaintenanceHooks::new(&self.shared_storage_config.maintenance_hooks, self.name())
+    }
+
     pub async fn get_shard_keys(&self) -> Vec<ShardKey> {
         self.shards_holder
             .read()
@@ -413,6 +388,59 @@ impl Collection {
         Ok(())
     }
 
+    /// Replace data of the shard replica with data of other replicas
+    ///
+    /// Replica is marked as dead, its local data is deleted on its peer, and the peer recovers
+    /// it from an active replica with a regular shard transfer. Used when data of the replica is
+    /// corrupted, without removing the peer from the cluster.
+    pub async fn rebuild_shard_replica(
+        &self,
+        shard_id: ShardId,
+        peer_id: PeerId,
+    ) -> CollectionResult<()> {
+        {
+            let shard_holder = self.shards_holder.read().await;
+            let replica_set = shard_holder
+                .get_shard(&shard_id)
+                .ok_or_else(|| shard_not_found_error(shard_id))?;
+
+            let peers = replica_set.peers();
+            if !peers.contains_key(&peer_id) {
+                return Err(CollectionError::bad_input(format!(
+                    "Peer {peer_id} has no replica of shard {shard_id}"
+                )));
+            }
+
+            let has_other_active_replica = peers
+                .iter()
+                .any(|(&peer, &state)| peer != peer_id && state == ReplicaState::Active);
+            if !has_other_active_replica {
+                return Err(CollectionError::bad_input(format!(
+                    "Cannot rebuild replica {peer_id} of shard {shard_id}, there is no other active replica to recover it from"
+                )));
+            }
+        }
+
+        // Recovery transfer is requested through consensus, so it starts after the local data
+        // is deleted
+        self.set_shard_replica_state(shard_id, peer_id, ReplicaState::Dead, None)
+            .await?;
+
+        if peer_id == self.this_peer_id {
+            log::info!(
+                "Rebuilding replica {peer_id}/{}:{shard_id}, deleting local data",
+                self.id,
+            );
+            let shard_holder = self.shards_holder.read().await;
+            let replica_set = shard_holder
+                .get_shard(&shard_id)
+                .ok_or_else(|| shard_not_found_error(shard_id))?;
+            replica_set.clear_local_shard().await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn state(&self) -> State {
         let shards_holder = self.shards_holder.read().await;
         let transfers = shards_holder.shard_transfers.read().clone();
@@ -423,6 +451,7 @@ impl Collection {
                 .map(|(shard_id, replicas)| {
                     let shard_info = ShardInfo {
                         replicas: replicas.peers(),
+                        replication_factor: replicas.replication_factor(),
                     };
                     (*shard_id, shard_info)
                 })
@@ -568,9 +597,105 @@ impl Collection {
             }
         }
 
+        // Add replicas to shards with a replication factor above their current number of replicas
+        // Such transfers are background work, so they are deferred until the maintenance window
+        let is_maintenance_allowed = self
+            .collection_config
+            .read()
+            .await
+            .params
+            .is_maintenance_allowed();
+        if self.shared_storage_config.node_type != NodeType::Listener && is_maintenance_allowed {
+            self.request_missing_shard_replicas(&shard_holder);
+        }
+
+        drop(shard_holder);
+
+        // Resume splits of shard keys, which were interrupted by a restart or have failed
+        self.resume_shard_key_splits().await;
+
         Ok(())
     }
 
+    /// Request one replica at a time for shards, which have less replicas than their desired
+    /// replication factor.
+    ///
+    /// Every peer computes the same target for a shard, only the target peer requests the transfer.
+    fn request_missing_shard_replicas(&self, shard_holder: &ShardHolder) {
+        let known_peers: HashSet<PeerId> = self
+            .channel_service
+            .id_to_address
+            .read()
+            .keys()
+            .copied()
+            .collect();
+
+        let shard_distribution: HashMap<ShardId, HashSet<PeerId>> = shard_holder
+            .all_shards()
+            .map(|replica_set| {
+                (
+                    replica_set.shard_id,
+                    replica_set.peers().into_keys().collect(),
+                )
+            })
+            .collect();
+
+        let transfers = shard_holder.get_transfers(|_| true);
+
+        for replica_set in shard_holder.all_shards() {
+            let shard_id = replica_set.shard_id;
+
+            let Some(replication_factor) = replica_set.replication_factor() else {
+                continue;
+            };
+
+            let peers = replica_set.peers();
+            if peers.len() >= replication_factor.get() as usize {
+                continue;
+            }
+
+            if transfers
+                .iter()
+                .any(|transfer| transfer.shard_id == shard_id)
+            {
+                continue; // wait for ongoing transfers of this shard to finish
+            }
+
+            let Some(target_peer) =
+                suggest_peer_to_add_replica(shard_id, shard_distribution.clone(), &known_peers)
+            else {
+                continue; // no peers left to place the replica on
+            };
+
+            if target_peer != self.this_peer_id {
+                continue;
+            }
+
+            let Some(source_peer) =
+                suggest_transfer_source(shard_id, target_peer, &transfers, &peers)
+            else {
+                continue;
+            };
+
+            log::debug!(
+                "Adding replica of shard {}:{} to peer {} from {} to reach replication factor {}",
+                self.name(),
+                shard_id,
+                target_peer,
+                source_peer,
+                replication_factor,
+            );
+
+            self.request_shard_transfer(ShardTransfer {
+                from: source_peer,
+                to: target_peer,
+                shard_id,
+                sync: true,
+                method: None,
+            });
+        }
+    }
+
     pub async fn get_telemetry_data(&self) -> CollectionTelemetry {
         let (shards_telemetry, transfers) = {
             let mut shards_telemetry = Vec::new();
@@ -603,6 +728,11 @@ impl Collection {
     }
 }
 
+/// Oldest collection version, which can be loaded
+///
+/// Collection files are compatible since then, older versions are upgraded in place.
+const MIN_COLLECTION_VERSION: Version = Version::new(0, 4, 0);
+
 struct CollectionVersion;
 
 impl StorageVersion for CollectionVersion {
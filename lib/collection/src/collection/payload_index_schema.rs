@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-use segment::types::{PayloadFieldSchema, PayloadKeyType};
+use segment::types::{PayloadFieldSchema, PayloadKeyType, PayloadSchemaType};
 use serde::{Deserialize, Serialize};
 
 use crate::collection::Collection;
+use crate::operations::point_timestamps::EXPIRE_AT_KEY;
 use crate::operations::types::{CollectionResult, UpdateResult};
 use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use crate::save_on_disk::SaveOnDisk;
@@ -60,6 +61,36 @@ impl Collection {
         Ok(result)
     }
 
+    /// Index `__expire_at` if point expiration is enabled, so expired points are found by the
+    /// index instead of a full scan
+    ///
+    /// An existing index of the field is kept.
+    pub async fn ensure_point_expiration_index(&self) -> CollectionResult<()> {
+        let point_expiration = self
+            .collection_config
+            .read()
+            .await
+            .params
+            .point_expiration
+            .unwrap_or(false);
+        if !point_expiration
+            || self
+                .payload_index_schema
+                .read()
+                .schema
+                .contains_key(EXPIRE_AT_KEY)
+        {
+            return Ok(());
+        }
+
+        self.create_payload_index(
+            EXPIRE_AT_KEY.to_string(),
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Integer),
+        )
+        .await?;
+        Ok(())
+    }
+
     pub async fn drop_payload_index(
         &self,
         field_name: String,
@@ -1,13 +1,20 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use futures::{future, TryFutureExt, TryStreamExt as _};
 use itertools::Itertools as _;
-use segment::types::{ShardKey, WithPayload, WithPayloadInterface};
+use rand::Rng;
+use segment::data_types::order_by::OrderBy;
+use segment::types::{
+    Filter, PayloadSchemaType, ScoredPoint, ShardKey, WithPayload, WithPayloadInterface,
+};
 use validator::Validate as _;
 
 use super::Collection;
 use crate::operations::consistency_params::ReadConsistency;
-use crate::operations::point_ops::WriteOrdering;
+use crate::operations::highlight::{highlight_payload, Highlights};
+use crate::operations::point_ops::{PointOperations, PointStruct, WriteOrdering};
+use crate::operations::point_timestamps::{now_millis, stamp_operation};
 use crate::operations::shard_selector_internal::ShardSelectorInternal;
 use crate::operations::types::*;
 use crate::operations::CollectionUpdateOperations;
@@ -82,16 +89,52 @@ impl Collection {
 
     pub async fn update_from_client(
         &self,
-        operation: CollectionUpdateOperations,
+        mut operation: CollectionUpdateOperations,
         wait: bool,
         ordering: WriteOrdering,
         shard_keys_selection: Option<ShardKey>,
     ) -> CollectionResult<UpdateResult> {
         operation.validate()?;
+        let (read_only, point_timestamps, replication_factor) = {
+            let params = &self.collection_config.read().await.params;
+            (
+                params.read_only.unwrap_or(false),
+                params.point_timestamps.unwrap_or(false),
+                params.replication_factor.get(),
+            )
+        };
+        if read_only {
+            return Err(CollectionError::bad_request(format!(
+                "Collection {} is read-only",
+                self.name(),
+            )));
+        }
+        let is_versioned_update = operation.single_operations().into_iter().any(|operation| {
+            matches!(
+                operation,
+                CollectionUpdateOperations::PointOperation(PointOperations::UpsertPointsIfVersion(
+                    _
+                )),
+            )
+        });
+        // Versions are assigned by each replica separately, they can't be compared across replicas
+        if is_versioned_update && replication_factor > 1 {
+            return Err(CollectionError::bad_request(format!(
+                "Versioned updates are not supported in collection {} with replication factor {}",
+                self.name(),
+                replication_factor,
+            )));
+        }
+        if point_timestamps {
+            stamp_operation(&mut operation, now_millis());
+        }
         let _update_lock = self.updates_lock.read().await;
 
         let mut results = {
             let shards_holder = self.shards_holder.read().await;
+            // New shards of a split shard key must receive the update as well
+            let new_shard_to_op =
+                shards_holder.split_by_new_shards(operation.clone(), &shard_keys_selection);
             let shard_to_op = shards_holder.split_by_shard(operation, &shard_keys_selection)?;
 
             if shard_to_op.is_empty() {
@@ -105,7 +148,23 @@ impl Collection {
                 .map(move |(replica_set, operation)| {
                     replica_set.update_with_consistency(operation, wait, ordering)
                 });
-            future::join_all(shard_requests).await
+            let results = future::join_all(shard_requests).await;
+
+            // Failures are expected for points, which are not copied into the new shards yet.
+            // Such points will be copied later together with the update.
+            let new_shard_requests =
+                new_shard_to_op
+                    .into_iter()
+                    .map(move |(replica_set, operation)| {
+                        replica_set.update_with_consistency(operation, wait, ordering)
+                    });
+            for result in future::join_all(new_shard_requests).await {
+                if let Err(err) = result {
+                    log::debug!("Update of a new shard of the split shard key failed: {err}");
+                }
+            }
+
+            results
         };
 
         let with_error = results.iter().filter(|result| result.is_err()).count();
@@ -136,6 +195,25 @@ impl Collection {
         }
     }
 
+    /// Check points against the collection configuration before upserting them
+    ///
+    /// Returns the reason of rejection for each point, `None` if the point is valid
+    pub async fn check_points(&self, points: &[PointStruct]) -> Vec<Option<String>> {
+        let collection_params = &self.collection_config.read().await.params;
+        points
+            .iter()
+            .map(|point| {
+                if let Err(err) = point.validate() {
+                    return Some(err.to_string());
+                }
+                collection_params
+                    .check_point_vectors(&point.get_vectors())
+                    .err()
+                    .map(|err| err.to_string())
+            })
+            .collect()
+    }
+
     pub async fn scroll_by(
         &self,
         request: ScrollRequestInternal,
@@ -153,6 +231,10 @@ impl Collection {
             .clone()
             .unwrap_or_else(|| default_request.with_payload.clone().unwrap());
         let with_vector = request.with_vector;
+        let order_by = request.order_by.map(OrderBy::from);
+        let sample = request.query.map(|query| match query {
+            ScrollQuery::Sample(sample) => sample,
+        });
 
         if limit == 0 {
             return Err(CollectionError::BadRequest {
@@ -160,8 +242,32 @@ impl Collection {
             });
         }
 
+        if order_by.is_some() && offset.is_some() {
+            return Err(CollectionError::BadRequest {
+                description: "Cannot use an `offset` when using `order_by`. The alternative for paginating results is to use `order_by.start_from` and a filter to exclude the IDs that you've already seen for the `order_by.start_from` value".to_string(),
+            });
+        }
+
+        if sample.is_some() && (offset.is_some() || order_by.is_some()) {
+            return Err(CollectionError::BadRequest {
+                description: "Cannot use `offset` or `order_by` when sampling points with `query`"
+                    .to_string(),
+            });
+        }
+
+        // Every replica returns its own random sample, so they can't be compared
+        let read_consistency = if sample.is_some() {
+            None
+        } else {
+            read_consistency
+        };
+
         // Needed to return next page offset.
-        let limit = limit + 1;
+        let limit = if order_by.is_some() || sample.is_some() {
+            limit
+        } else {
+            limit + 1
+        };
         let retrieved_points: Vec<_> = {
             let shards_holder = self.shards_holder.read().await;
             let target_shards = shards_holder.select_shards(shard_selection)?;
@@ -176,6 +282,8 @@ impl Collection {
                         request.filter.as_ref(),
                         read_consistency,
                         shard_selection.is_shard_id(),
+                        order_by.as_ref(),
+                        sample,
                     )
                     .and_then(move |mut records| async move {
                         if shard_key.is_none() {
@@ -188,12 +296,71 @@ impl Collection {
                     })
             });
 
-            future::try_join_all(scroll_futures).await?
+            let retrieved_points = future::try_join_all(scroll_futures).await?;
+
+            if sample.is_some() {
+                // Shards with fewer sampled points than the limit are sampled completely,
+                // only the others are counted
+                let count_request = Arc::new(CountRequestInternal {
+                    filter: request.filter.clone(),
+                    exact: true,
+                });
+                let count_futures = shards_holder
+                    .select_shards(shard_selection)?
+                    .into_iter()
+                    .zip(&retrieved_points)
+                    .map(|((shard, _), points)| {
+                        let count_request = count_request.clone();
+                        async move {
+                            if points.len() < limit {
+                                return Ok(points.len());
+                            }
+                            let count = shard
+                                .count(count_request, None, shard_selection.is_shard_id())
+                                .await?;
+                            CollectionResult::Ok(count.count.max(points.len()))
+                        }
+                    });
+                let counts = future::try_join_all(count_futures).await?;
+
+                return Ok(ScrollResult {
+                    points: merge_samples(retrieved_points, counts, limit, &mut rand::thread_rng()),
+                    next_page_offset: None,
+                });
+            }
+
+            retrieved_points
         };
+
+        if let Some(order_by) = &order_by {
+            // Pagination by point ID is not possible when ordering by payload field,
+            // `order_by.start_from` should be used instead.
+            let points = retrieved_points
+                .into_iter()
+                .flatten()
+                .sorted_by(|a, b| {
+                    order_by
+                        .compare(
+                            a.order_value.unwrap_or_default(),
+                            b.order_value.unwrap_or_default(),
+                        )
+                        .then_with(|| a.id.cmp(&b.id))
+                })
+                .take(limit)
+                .collect();
+
+            return Ok(ScrollResult {
+                points,
+                next_page_offset: None,
+            });
+        }
+
+        // Each shard returns points in ascending order of their IDs, merge them into a single
+        // sorted page. The same point may be present in multiple shards during resharding.
         let mut points: Vec<_> = retrieved_points
             .into_iter()
-            .flatten()
-            .sorted_by_key(|point| point.id)
+            .kmerge_by(|a, b| a.id < b.id)
+            .dedup_by(|a, b| a.id == b.id)
             .take(limit)
             .collect();
 
@@ -241,6 +408,70 @@ impl Collection {
         Ok(CountResult { count })
     }
 
+    pub async fn facet(
+        &self,
+        request: FacetRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: &ShardSelectorInternal,
+    ) -> CollectionResult<FacetResult> {
+        let is_keyword_indexed = self
+            .payload_index_schema
+            .read()
+            .schema
+            .get(&request.key)
+            .is_some_and(|schema| schema.schema_type() == PayloadSchemaType::Keyword);
+        if !is_keyword_indexed {
+            return Err(CollectionError::bad_request(format!(
+                "There is no keyword index for the facet key: `{}`. Please create one to use facets.",
+                request.key,
+            )));
+        }
+
+        let limit = request.limit.unwrap_or(DEFAULT_FACET_LIMIT);
+        let exact = request.exact;
+        let request = Arc::new(request);
+
+        let shard_results = {
+            let shards_holder = self.shards_holder.read().await;
+            let target_shards = shards_holder.select_shards(shard_selection)?;
+            let facet_futures = target_shards.into_iter().map(|(shard, _shard_key)| {
+                shard.facet(
+                    request.clone(),
+                    read_consistency,
+                    shard_selection.is_shard_id(),
+                )
+            });
+            future::try_join_all(facet_futures).await?
+        };
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for hit in shard_results.into_iter().flat_map(|result| result.hits) {
+            *counts.entry(hit.value).or_insert(0) += hit.count;
+        }
+
+        // Requests to a specific shard come from other peers, which merge the results on their own
+        let limit = if exact && shard_selection.is_shard_id() {
+            None
+        } else {
+            Some(limit)
+        };
+
+        Ok(FacetResult::from_counts(counts, limit))
+    }
+
+    /// Highlights of the words matched by full-text conditions of `filter` in the returned
+    /// payload of each point
+    pub fn highlight(&self, filter: &Filter, points: &[ScoredPoint]) -> Vec<Highlights> {
+        let payload_index_schema = self.payload_index_schema.read();
+        points
+            .iter()
+            .map(|point| match &point.payload {
+                Some(payload) => highlight_payload(filter, payload, &payload_index_schema.schema),
+                None => Highlights::new(),
+            })
+            .collect()
+    }
+
     pub async fn retrieve(
         &self,
         request: PointRequestInternal,
@@ -282,3 +513,105 @@ impl Collection {
         Ok(points)
     }
 }
+
+/// Merge uniform samples of shards into a uniform sample of all their points
+///
+/// Every point is taken from a shard with a probability proportional to the number of its points,
+/// which are not taken yet. Samples must be in random order and contain at least as many points
+/// as the shard can contribute, which is `min(count, limit)`.
+fn merge_samples<R: Rng>(
+    samples: Vec<Vec<Record>>,
+    mut counts: Vec<usize>,
+    limit: usize,
+    rng: &mut R,
+) -> Vec<Record> {
+    let mut samples = samples.into_iter().map(Vec::into_iter).collect_vec();
+    let mut points = Vec::with_capacity(limit);
+
+    while points.len() < limit {
+        let total: usize = counts.iter().sum();
+        if total == 0 {
+            break;
+        }
+
+        let mut pick = rng.gen_range(0..total);
+        let Some(shard) = counts.iter().position(|&count| {
+            if pick < count {
+                return true;
+            }
+            pick -= count;
+            false
+        }) else {
+            break;
+        };
+
+        match samples[shard].next() {
+            Some(point) => {
+                counts[shard] -= 1;
+                points.push(point);
+            }
+            // Fewer points than counted, e.g. deleted in the meantime
+            None => counts[shard] = 0,
+        }
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn records(ids: std::ops::Range<u64>) -> Vec<Record> {
+        ids.map(|id| Record {
+            id: id.into(),
+            payload: None,
+            vector: None,
+            shard_key: None,
+            order_value: None,
+            version: None,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_merge_samples_weighted_by_shard_size() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let limit = 10;
+
+        // A large shard with 1000 points and a small one with 10 points
+        let mut small_shard_points = 0;
+        for _ in 0..1000 {
+            let samples = vec![records(0..10), records(1000..1010)];
+            let points = merge_samples(samples, vec![1000, 10], limit, &mut rng);
+            assert_eq!(points.len(), limit);
+            small_shard_points += points
+                .iter()
+                .filter(|point| point.id >= 1000u64.into())
+                .count();
+        }
+
+        // About 1% of all sampled points are expected from the small shard
+        let expected = 1000 * limit * 10 / 1010;
+        assert!(
+            small_shard_points.abs_diff(expected) < expected / 3,
+            "{small_shard_points} points from the small shard, expected about {expected}",
+        );
+    }
+
+    #[test]
+    fn test_merge_samples_smaller_than_limit() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let samples = vec![records(0..3), records(10..12)];
+        let points = merge_samples(samples, vec![3, 2], 10, &mut rng);
+        assert_eq!(points.len(), 5);
+
+        // Counts larger than samples don't fail
+        let samples = vec![records(0..3), vec![]];
+        let points = merge_samples(samples, vec![3, 5], 10, &mut rng);
+        assert_eq!(points.len(), 3);
+    }
+}
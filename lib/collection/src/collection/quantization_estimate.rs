@@ -0,0 +1,115 @@
+use std::mem::size_of;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use segment::data_types::vectors::{VectorElementType, VectorRef, DEFAULT_VECTOR_NAME};
+use segment::types::{WithPayloadInterface, WithVector};
+use segment::vector_storage::quantized::quality_estimation::estimate_quantization_quality;
+
+use super::Collection;
+use crate::operations::shard_selector_internal::ShardSelectorInternal;
+use crate::operations::types::*;
+
+impl Collection {
+    /// Sample vectors, quantize them with the requested config in a temporary directory and
+    /// compare searches among the sampled vectors with and without quantization.
+    ///
+    /// The collection itself is not changed.
+    pub async fn estimate_quantization(
+        &self,
+        request: QuantizationEstimateRequest,
+        temp_dir: &Path,
+    ) -> CollectionResult<QuantizationEstimate> {
+        let QuantizationEstimateRequest {
+            quantization_config,
+            using,
+            sample,
+            queries,
+            limit,
+        } = request;
+
+        let sample_size = sample.unwrap_or(DEFAULT_QUANTIZATION_ESTIMATE_SAMPLE);
+        let queries_count = queries.unwrap_or(DEFAULT_QUANTIZATION_ESTIMATE_QUERIES);
+        let limit = limit.unwrap_or(DEFAULT_QUANTIZATION_ESTIMATE_LIMIT);
+        let vector_name = using.unwrap_or_else(|| DEFAULT_VECTOR_NAME.to_string());
+
+        let distance = {
+            let config = self.collection_config.read().await;
+            let params = config
+                .params
+                .vectors
+                .get_params(&vector_name)
+                .ok_or_else(|| {
+                    CollectionError::bad_request(format!(
+                        "Dense vector {vector_name} does not exist in the collection"
+                    ))
+                })?;
+            params.distance
+        };
+
+        let sample_request = ScrollRequestInternal {
+            offset: None,
+            limit: Some(sample_size),
+            filter: None,
+            with_payload: Some(WithPayloadInterface::Bool(false)),
+            with_vector: WithVector::Selector(vec![vector_name.clone()]),
+            order_by: None,
+            query: Some(ScrollQuery::Sample(Sample::Random)),
+        };
+        let sampled_points = self
+            .scroll_by(sample_request, None, &ShardSelectorInternal::All)
+            .await?
+            .points;
+
+        // Points without the requested vector are not sampled
+        let vectors: Vec<_> = sampled_points
+            .into_iter()
+            .filter_map(|record| match record.vector?.get(&vector_name)? {
+                VectorRef::Dense(vector) => Some(vector.to_vec()),
+                VectorRef::Sparse(_) => None,
+            })
+            .collect();
+
+        if vectors.len() < 2 {
+            return Err(CollectionError::bad_request(format!(
+                "At least 2 points with vector {vector_name} are required to estimate quantization"
+            )));
+        }
+
+        let sampled_count = vectors.len();
+        let vector_size_bytes = vectors[0].len() * size_of::<VectorElementType>();
+        let quantization_dir = tempfile::Builder::new()
+            .prefix(&format!("{}-quantization-estimate-", self.name()))
+            .tempdir_in(temp_dir)?;
+
+        let quality = self
+            .search_runtime
+            .spawn_blocking(move || {
+                estimate_quantization_quality(
+                    &vectors,
+                    distance,
+                    &quantization_config,
+                    queries_count,
+                    limit,
+                    quantization_dir.path(),
+                    // A sample is small enough to be quantized in a single thread
+                    1,
+                    &AtomicBool::new(false),
+                )
+            })
+            .await??;
+
+        let recalls = &quality.recalls;
+        let recall_mean = recalls.iter().sum::<f64>() / recalls.len() as f64;
+        let recall_min = recalls.iter().copied().fold(1.0, f64::min);
+
+        Ok(QuantizationEstimate {
+            sampled_count,
+            queries_count: recalls.len(),
+            recall_mean,
+            recall_min,
+            vector_size_bytes,
+            quantized_vector_size_bytes: quality.quantized_vector_size_bytes,
+        })
+    }
+}
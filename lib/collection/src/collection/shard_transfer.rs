@@ -139,6 +139,7 @@ impl Collection {
             self.snapshots_path.clone(),
             self.name(),
             temp_dir,
+            self.maintenance_hooks().for_shard(transfer.shard_id),
             on_finish,
             on_error,
         );
@@ -1,13 +1,22 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-use segment::types::ShardKey;
+use segment::types::{PointIdType, ShardKey, WithPayloadInterface};
+use tokio::sync::RwLock;
 
 use crate::collection::Collection;
 use crate::config::ShardingMethod;
-use crate::operations::types::CollectionError;
+use crate::operations::point_ops::{
+    PointInsertOperationsInternal, PointOperations, PointStruct, WriteOrdering,
+};
+use crate::operations::types::{CollectionError, CollectionResult};
 use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use crate::shards::replica_set::{ReplicaState, ShardReplicaSet};
 use crate::shards::shard::{PeerId, ShardId, ShardsPlacement};
+use crate::shards::shard_holder::{shard_not_found_error, LockedShardHolder, ShardHolder};
+
+/// Number of points, moved into the new shards of a split shard key at once
+const SHARD_KEY_SPLIT_BATCH_SIZE: usize = 100;
 
 impl Collection {
     pub async fn create_replica_set(
@@ -86,10 +95,28 @@ impl Collection {
             )));
         }
 
-        let max_shard_id = state.max_shard_id();
+        let new_shards = self
+            .create_shard_key_replica_sets(state.max_shard_id(), &placement)
+            .await?;
+
+        let mut shards_holder = self.shards_holder.write().await;
+        for (shard_id, replica_set) in new_shards {
+            shards_holder.add_shard(shard_id, replica_set, Some(shard_key.clone()))?;
+        }
+        Ok(())
+    }
 
+    /// Create replica sets for the new shards of a shard key, with all payload indexes in place.
+    ///
+    /// New shard ids are allocated after `max_shard_id`.
+    async fn create_shard_key_replica_sets(
+        &self,
+        max_shard_id: ShardId,
+        placement: &ShardsPlacement,
+    ) -> Result<Vec<(ShardId, ShardReplicaSet)>, CollectionError> {
         let payload_schema = self.payload_index_schema.read().schema.clone();
 
+        let mut new_shards = Vec::with_capacity(placement.len());
         for (idx, shard_replicas_placement) in placement.iter().enumerate() {
             let shard_id = max_shard_id + idx as ShardId + 1;
 
@@ -108,15 +135,125 @@ impl Collection {
                 replica_set.update_local(create_index_op, true).await?;
             }
 
-            self.shards_holder.write().await.add_shard(
-                shard_id,
-                replica_set,
-                Some(shard_key.clone()),
-            )?;
+            new_shards.push((shard_id, replica_set));
+        }
+        Ok(new_shards)
+    }
+
+    /// Split the data of an existing shard key across additional shards.
+    ///
+    /// New shards are created according to the `placement`, and points of the key are moved into
+    /// them by a background task. Until the move is finished, the new shards are not used for
+    /// reads, and receive copies of all updates of the shard key.
+    pub async fn split_shard_key(
+        &self,
+        shard_key: ShardKey,
+        placement: ShardsPlacement,
+    ) -> Result<(), CollectionError> {
+        let state = self.state().await;
+        match state.config.params.sharding_method.unwrap_or_default() {
+            ShardingMethod::Auto => {
+                return Err(CollectionError::bad_request(format!(
+                    "Shard Key {} cannot be split with Auto sharding method",
+                    shard_key
+                )));
+            }
+            ShardingMethod::Custom => {}
+        }
+
+        if !state.shards_key_mapping.contains_key(&shard_key) {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {} does not exist",
+                shard_key
+            )));
+        }
+
+        if placement.is_empty() {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {} must be split into at least one new shard",
+                shard_key
+            )));
+        }
+
+        // Moved points are tracked by the updates lock of this peer only,
+        // so updates coming through other peers would not be accounted for
+        if self.channel_service.id_to_address.read().len() > 1 {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {} cannot be split in a cluster with multiple peers",
+                shard_key
+            )));
+        }
+
+        if self
+            .shards_holder
+            .read()
+            .await
+            .get_shard_key_splits()
+            .contains_key(&shard_key)
+        {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {} is already being split",
+                shard_key
+            )));
+        }
+
+        let new_shards = self
+            .create_shard_key_replica_sets(state.max_shard_id(), &placement)
+            .await?;
+
+        {
+            let _update_lock = self.updates_lock.write().await;
+            self.shards_holder
+                .write()
+                .await
+                .start_shard_key_split(shard_key.clone(), new_shards)?;
         }
+
+        self.spawn_shard_key_split_task(shard_key).await;
         Ok(())
     }
 
+    /// Restart tasks of the shard key splits, which are not running anymore
+    pub(super) async fn resume_shard_key_splits(&self) {
+        let split_keys = self.shards_holder.read().await.get_shard_key_splits();
+
+        for shard_key in split_keys.into_keys() {
+            let is_running = self
+                .shard_key_split_tasks
+                .lock()
+                .await
+                .get(&shard_key)
+                .map(|task| !task.is_finished())
+                .unwrap_or(false);
+
+            if !is_running {
+                log::debug!(
+                    "Resuming split of shard key {} in collection {}",
+                    shard_key,
+                    self.name(),
+                );
+                self.spawn_shard_key_split_task(shard_key).await;
+            }
+        }
+    }
+
+    async fn spawn_shard_key_split_task(&self, shard_key: ShardKey) {
+        let task = tokio::spawn(move_shard_key_points(
+            self.shards_holder.clone(),
+            self.updates_lock.clone(),
+            shard_key.clone(),
+        ));
+
+        if let Some(previous_task) = self
+            .shard_key_split_tasks
+            .lock()
+            .await
+            .insert(shard_key, task)
+        {
+            previous_task.abort();
+        }
+    }
+
     pub async fn drop_shard_key(&self, shard_key: ShardKey) -> Result<(), CollectionError> {
         let state = self.state().await;
 
@@ -130,6 +267,10 @@ impl Collection {
             ShardingMethod::Custom => {}
         }
 
+        if let Some(split_task) = self.shard_key_split_tasks.lock().await.remove(&shard_key) {
+            split_task.abort();
+        }
+
         self.shards_holder
             .write()
             .await
@@ -137,3 +278,175 @@ impl Collection {
             .await
     }
 }
+
+/// Move points of the split shard key into the new shards, and finish the split.
+///
+/// Points are copied in batches, while updates of the collection are blocked,
+/// so the copies can't miss an update. Copied points are removed from the old shards at once,
+/// when the new shards start to be used for reads.
+async fn move_shard_key_points(
+    shards_holder: Arc<LockedShardHolder>,
+    updates_lock: Arc<RwLock<()>>,
+    shard_key: ShardKey,
+) -> CollectionResult<()> {
+    let old_shard_ids: Vec<ShardId> = {
+        let shards_holder = shards_holder.read().await;
+        let Some(new_shard_ids) = shards_holder.get_shard_key_splits().remove(&shard_key) else {
+            return Ok(());
+        };
+        shards_holder
+            .get_shard_key_to_ids_mapping()
+            .remove(&shard_key)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|shard_id| !new_shard_ids.contains(shard_id))
+            .collect()
+    };
+
+    for &shard_id in &old_shard_ids {
+        let mut offset = None;
+        loop {
+            let _update_lock = updates_lock.write().await;
+            let shards_holder = shards_holder.read().await;
+            offset =
+                copy_shard_key_points_batch(&shards_holder, &shard_key, shard_id, offset).await?;
+            if offset.is_none() {
+                break;
+            }
+        }
+    }
+
+    let _update_lock = updates_lock.write().await;
+    let mut shards_holder = shards_holder.write().await;
+    for &shard_id in &old_shard_ids {
+        delete_moved_points(&shards_holder, &shard_key, shard_id).await?;
+    }
+    shards_holder.finish_shard_key_split(&shard_key)?;
+
+    log::info!("Split of shard key {shard_key} is finished");
+    Ok(())
+}
+
+/// Copy a batch of points of the old shard, which belong to the new shards of the split.
+///
+/// Returns an offset of the next batch.
+async fn copy_shard_key_points_batch(
+    shards_holder: &ShardHolder,
+    shard_key: &ShardKey,
+    shard_id: ShardId,
+    offset: Option<PointIdType>,
+) -> CollectionResult<Option<PointIdType>> {
+    let Some(split_ring) = shards_holder.split_ring(shard_key) else {
+        // Split was cancelled
+        return Ok(None);
+    };
+    let Some(replica_set) = shards_holder.get_shard(&shard_id) else {
+        return Err(shard_not_found_error(shard_id));
+    };
+
+    let limit = SHARD_KEY_SPLIT_BATCH_SIZE + 1;
+    let mut batch = replica_set
+        .scroll_by(
+            offset,
+            limit,
+            &WithPayloadInterface::Bool(true),
+            &true.into(),
+            None,
+            None,
+            true,
+            None,
+            None,
+        )
+        .await?;
+    let next_page_offset = if batch.len() < limit {
+        // This was the last page
+        None
+    } else {
+        // remove extra point, it would be a first point of the next page
+        Some(batch.pop().unwrap().id)
+    };
+
+    let mut points_by_shard: HashMap<ShardId, Vec<PointStruct>> = HashMap::new();
+    for record in batch {
+        let target_shard_id = *split_ring
+            .get(&record.id)
+            .expect("Hash ring is guaranteed to be non-empty");
+        if target_shard_id == shard_id {
+            continue;
+        }
+        let point = PointStruct::try_from(record).map_err(CollectionError::service_error)?;
+        points_by_shard
+            .entry(target_shard_id)
+            .or_default()
+            .push(point);
+    }
+
+    for (target_shard_id, points) in points_by_shard {
+        let Some(target_replica_set) = shards_holder.get_shard(&target_shard_id) else {
+            return Err(shard_not_found_error(target_shard_id));
+        };
+        let upsert_operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(PointInsertOperationsInternal::PointsList(points)),
+        );
+        target_replica_set
+            .update_with_consistency(upsert_operation, true, WriteOrdering::default())
+            .await?;
+    }
+
+    Ok(next_page_offset)
+}
+
+/// Delete points from the old shard, which were copied into the new shards of the split
+async fn delete_moved_points(
+    shards_holder: &ShardHolder,
+    shard_key: &ShardKey,
+    shard_id: ShardId,
+) -> CollectionResult<()> {
+    let Some(split_ring) = shards_holder.split_ring(shard_key) else {
+        return Ok(());
+    };
+    let Some(replica_set) = shards_holder.get_shard(&shard_id) else {
+        return Err(shard_not_found_error(shard_id));
+    };
+
+    let limit = SHARD_KEY_SPLIT_BATCH_SIZE + 1;
+    let mut offset = None;
+    loop {
+        let mut batch = replica_set
+            .scroll_by(
+                offset,
+                limit,
+                &WithPayloadInterface::Bool(false),
+                &false.into(),
+                None,
+                None,
+                true,
+                None,
+                None,
+            )
+            .await?;
+        offset = if batch.len() < limit {
+            None
+        } else {
+            Some(batch.pop().unwrap().id)
+        };
+
+        let ids: Vec<_> = batch
+            .into_iter()
+            .map(|record| record.id)
+            .filter(|id| split_ring.get(id) != Some(&shard_id))
+            .collect();
+
+        if !ids.is_empty() {
+            let delete_operation =
+                CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints { ids });
+            replica_set
+                .update_with_consistency(delete_operation, true, WriteOrdering::default())
+                .await?;
+        }
+
+        if offset.is_none() {
+            return Ok(());
+        }
+    }
+}
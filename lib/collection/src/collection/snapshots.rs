@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use io::file_operations::read_json;
@@ -8,8 +9,10 @@ use tokio::fs;
 
 use super::Collection;
 use crate::collection::CollectionVersion;
+use crate::common::maintenance_hooks::{HookStage, MaintenanceOperation};
+use crate::common::snapshots_manager::SnapshotStorageManager;
 use crate::config::{CollectionConfig, ShardingMethod};
-use crate::operations::snapshot_ops::{self, SnapshotDescription};
+use crate::operations::snapshot_ops::SnapshotDescription;
 use crate::operations::types::{CollectionError, CollectionResult, NodeType};
 use crate::shards::local_shard::LocalShard;
 use crate::shards::remote_shard::RemoteShard;
@@ -20,8 +23,14 @@ use crate::shards::shard_holder::{ShardKeyMapping, SHARD_KEY_MAPPING_FILE};
 use crate::shards::shard_versioning;
 
 impl Collection {
+    pub fn get_snapshots_storage_manager(&self) -> CollectionResult<SnapshotStorageManager> {
+        SnapshotStorageManager::new(&self.shared_storage_config.snapshots_config)
+    }
+
     pub async fn list_snapshots(&self) -> CollectionResult<Vec<SnapshotDescription>> {
-        snapshot_ops::list_snapshots_in_directory(&self.snapshots_path).await
+        self.get_snapshots_storage_manager()?
+            .list_snapshots(&self.snapshots_path)
+            .await
     }
 
     /// Creates a snapshot of the collection.
@@ -41,6 +50,28 @@ impl Collection {
         &self,
         global_temp_dir: &Path,
         this_peer_id: PeerId,
+    ) -> CollectionResult<SnapshotDescription> {
+        let maintenance_hooks = self.maintenance_hooks();
+        maintenance_hooks
+            .run(MaintenanceOperation::Snapshot, HookStage::Before, None)
+            .await;
+        let result = self
+            .create_snapshot_archive(global_temp_dir, this_peer_id)
+            .await;
+        maintenance_hooks
+            .run(
+                MaintenanceOperation::Snapshot,
+                HookStage::After,
+                Some(result.is_ok()),
+            )
+            .await;
+        result
+    }
+
+    async fn create_snapshot_archive(
+        &self,
+        global_temp_dir: &Path,
+        this_peer_id: PeerId,
     ) -> CollectionResult<SnapshotDescription> {
         let snapshot_name = format!(
             "{}-{}-{}.snapshot",
@@ -124,23 +155,18 @@ impl Collection {
         });
         snapshot_temp_arc_file = archiving.await??;
 
-        // Move snapshot to permanent location.
-        // We can't move right away, because snapshot folder can be on another mounting point.
-        // We can't copy to the target location directly, because copy is not atomic.
-        // So we copy to the final location with a temporary name and then rename atomically.
-        let snapshot_path_tmp_move = snapshot_path.with_extension("tmp");
-
-        // Ensure that the temporary file is deleted on error
-        let _temp_path = TempPath::from_path(&snapshot_path_tmp_move);
-        fs::copy(&snapshot_temp_arc_file.path(), &snapshot_path_tmp_move).await?;
-        fs::rename(&snapshot_path_tmp_move, &snapshot_path).await?;
+        // Move snapshot to permanent location
+        let snapshot_description = self
+            .get_snapshots_storage_manager()?
+            .store_file(snapshot_temp_arc_file.path(), &snapshot_path)
+            .await?;
 
         log::info!(
             "Collection snapshot {} completed into {:?}",
             snapshot_name,
             snapshot_path
         );
-        snapshot_ops::get_snapshot_description(&snapshot_path).await
+        Ok(snapshot_description)
     }
 
     /// Restore collection from snapshot
@@ -152,9 +178,24 @@ impl Collection {
         this_peer_id: PeerId,
         is_distributed: bool,
     ) -> CollectionResult<()> {
-        // decompress archive
         let archive_file = std::fs::File::open(snapshot_path)?;
-        let mut ar = tar::Archive::new(archive_file);
+        Self::restore_snapshot_from_reader(archive_file, target_dir, this_peer_id, is_distributed)
+    }
+
+    /// Restore collection from snapshot archive, read sequentially from `reader`
+    ///
+    /// The archive is unpacked as it is read, so it may come straight from a network stream
+    /// without being buffered on disk first.
+    ///
+    /// This method performs blocking IO.
+    pub fn restore_snapshot_from_reader(
+        reader: impl Read,
+        target_dir: &Path,
+        this_peer_id: PeerId,
+        is_distributed: bool,
+    ) -> CollectionResult<()> {
+        // decompress archive
+        let mut ar = tar::Archive::new(reader);
         ar.unpack(target_dir)?;
 
         let config = CollectionConfig::load(target_dir)?;
@@ -242,34 +283,31 @@ impl Collection {
     }
 
     pub async fn get_snapshot_path(&self, snapshot_name: &str) -> CollectionResult<PathBuf> {
-        let snapshot_path = self.snapshots_path.join(snapshot_name);
+        self.get_snapshots_storage_manager()?
+            .get_snapshot_path(&self.snapshots_path, snapshot_name)
+            .await
+    }
 
-        let absolute_snapshot_path =
-            snapshot_path
-                .canonicalize()
-                .map_err(|_| CollectionError::NotFound {
-                    what: format!("Snapshot {snapshot_name}"),
-                })?;
-
-        let absolute_snapshot_dir =
-            self.snapshots_path
-                .canonicalize()
-                .map_err(|_| CollectionError::NotFound {
-                    what: format!("Snapshot directory: {}", self.snapshots_path.display()),
-                })?;
-
-        if !absolute_snapshot_path.starts_with(absolute_snapshot_dir) {
-            return Err(CollectionError::NotFound {
-                what: format!("Snapshot {snapshot_name}"),
-            });
-        }
+    /// Get the snapshot as a local file, downloading it into `temp_dir` if it is stored remotely.
+    ///
+    /// The returned `TempPath`, if any, deletes the downloaded file once it is dropped.
+    pub async fn get_snapshot_file(
+        &self,
+        snapshot_name: &str,
+        temp_dir: &Path,
+    ) -> CollectionResult<(PathBuf, Option<TempPath>)> {
+        let snapshot_path = self.get_snapshot_path(snapshot_name).await?;
+        self.get_snapshots_storage_manager()?
+            .get_stored_file(&snapshot_path, temp_dir)
+            .await
+    }
 
-        if !snapshot_path.exists() {
-            return Err(CollectionError::NotFound {
-                what: format!("Snapshot {snapshot_name}"),
-            });
-        }
-        Ok(snapshot_path)
+    pub async fn delete_snapshot(&self, snapshot_name: &str) -> CollectionResult<bool> {
+        let snapshot_path = self.get_snapshot_path(snapshot_name).await?;
+        log::info!("Deleting collection snapshot {:?}", snapshot_path);
+        self.get_snapshots_storage_manager()?
+            .delete_snapshot(&snapshot_path)
+            .await
     }
 
     pub async fn list_shard_snapshots(
@@ -65,6 +65,18 @@ impl Collection {
             let mut config = self.collection_config.write().await;
             config.params.replication_factor = new_config.params.replication_factor;
             config.params.write_consistency_factor = new_config.params.write_consistency_factor;
+            config.params.maintenance_windows = new_config.params.maintenance_windows;
+            config.params.payload_compression = new_config.params.payload_compression;
+            config.params.payload_blobs = new_config.params.payload_blobs;
+            config.params.on_disk_payload_backend = new_config.params.on_disk_payload_backend;
+            config.params.kv_store = new_config.params.kv_store;
+            config.params.point_timestamps = new_config.params.point_timestamps;
+            config.params.point_expiration = new_config.params.point_expiration;
+            config.params.read_only = new_config.params.read_only;
+            config.metadata = new_config.metadata;
+            config.strict_mode_config = new_config.strict_mode_config;
+            config.rate_limit = new_config.rate_limit;
+            config.query_expansion = new_config.query_expansion;
         }
 
         self.recreate_optimizers_blocking().await?;
@@ -89,11 +101,15 @@ impl Collection {
 
         for (shard_id, shard_info) in shards {
             match self.shards_holder.read().await.get_shard(&shard_id) {
-                Some(replica_set) => replica_set.apply_state(shard_info.replicas).await?,
+                Some(replica_set) => {
+                    replica_set.apply_state(shard_info.replicas).await?;
+                    replica_set.set_replication_factor(shard_info.replication_factor)?;
+                }
                 None => {
                     let shard_replicas: Vec<_> = shard_info.replicas.keys().copied().collect();
                     let replica_set = self.create_replica_set(shard_id, &shard_replicas).await?;
                     replica_set.apply_state(shard_info.replicas).await?;
+                    replica_set.set_replication_factor(shard_info.replication_factor)?;
                     extra_shards.insert(shard_id, replica_set);
                 }
             }
@@ -49,7 +49,19 @@ impl CollectionUpdater {
     ) -> CollectionResult<usize> {
         // Allow only one update at a time, ensure no data races between segments.
         // let _lock = self.update_lock.lock().unwrap();
-        let operation_result = match operation {
+        let operation_result = Self::process_operation(segments, op_num, operation);
+
+        CollectionUpdater::handle_update_result(segments, op_num, &operation_result);
+
+        operation_result
+    }
+
+    fn process_operation(
+        segments: &RwLock<SegmentHolder>,
+        op_num: SeqNumberType,
+        operation: CollectionUpdateOperations,
+    ) -> CollectionResult<usize> {
+        match operation {
             CollectionUpdateOperations::PointOperation(point_operation) => {
                 process_point_operation(segments, op_num, point_operation)
             }
@@ -62,18 +74,20 @@ impl CollectionUpdater {
             CollectionUpdateOperations::FieldIndexOperation(index_operation) => {
                 process_field_index_operation(segments, op_num, &index_operation)
             }
-        };
-
-        CollectionUpdater::handle_update_result(segments, op_num, &operation_result);
-
-        operation_result
+            // Operations of a batch share the operation number, the first failure stops the batch
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                operations.into_iter().try_fold(0, |total, operation| {
+                    Ok(total + Self::process_operation(segments, op_num, operation)?)
+                })
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
-    use segment::types::{Payload, WithPayload};
+    use segment::types::{Payload, PayloadContainer, WithPayload};
     use serde_json::json;
     use tempfile::Builder;
 
@@ -214,6 +228,7 @@ mod tests {
                 payload,
                 points: Some(points.clone()),
                 filter: None,
+                key: None,
             }),
         )
         .unwrap();
@@ -286,4 +301,50 @@ mod tests {
         assert_eq!(res.len(), 1);
         assert!(!res[0].payload.as_ref().unwrap().contains_key("color"));
     }
+
+    #[test]
+    fn test_set_payload_by_key() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segments = build_test_holder(dir.path());
+        let points = vec![1.into()];
+
+        let set_payload = |op_num, payload: &str, key: &str| {
+            process_payload_operation(
+                &segments,
+                op_num,
+                PayloadOps::SetPayload(SetPayloadOp {
+                    payload: serde_json::from_str(payload).unwrap(),
+                    points: Some(points.clone()),
+                    filter: None,
+                    key: Some(key.to_string()),
+                }),
+            )
+            .unwrap();
+        };
+        set_payload(
+            100,
+            r#"{"reviews": [{"score": 1}, {"score": 2}]}"#,
+            "metadata",
+        );
+        set_payload(
+            101,
+            r#"{"score": 5, "verified": true}"#,
+            "metadata.reviews[1]",
+        );
+
+        let res =
+            SegmentsSearcher::retrieve(&segments, &points, &WithPayload::from(true), &false.into())
+                .unwrap();
+        let payload = res[0].payload.as_ref().unwrap();
+        // Other keys of the payload are kept
+        assert!(payload.contains_key("color"));
+        assert_eq!(
+            payload.get_value("metadata.reviews[].score").values(),
+            [&json!(1), &json!(5)],
+        );
+        assert_eq!(
+            payload.get_value("metadata.reviews[1].verified").values(),
+            [&json!(true)],
+        );
+    }
 }
@@ -4,17 +4,19 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
+use common::types::ScoreType;
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 use segment::common::operation_error::{OperationResult, SegmentFailedState};
 use segment::data_types::named_vectors::NamedVectors;
-use segment::data_types::vectors::{QueryVector, Vector};
+use segment::data_types::order_by::{OrderBy, OrderValue};
+use segment::data_types::vectors::{QueryVector, Vector, VectorType};
 use segment::entry::entry_point::SegmentEntry;
 use segment::index::field_index::CardinalityEstimation;
 use segment::telemetry::SegmentTelemetry;
 use segment::types::{
-    Condition, Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
-    ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType, WithPayload,
-    WithVector,
+    Condition, Filter, Payload, PayloadFieldSchema, PayloadFieldStatistics, PayloadKeyType,
+    PayloadKeyTypeRef, PointIdType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo,
+    SegmentType, SeqNumberType, WithPayload, WithVector,
 };
 
 use crate::collection_manager::holders::segment_holder::LockedSegment;
@@ -506,6 +508,95 @@ impl SegmentEntry for ProxySegment {
         read_points
     }
 
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>> {
+        let deleted_points = self.deleted_points.read();
+        let mut read_points = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .read_ordered_filtered(limit, filter, order_by)?
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment.get().read().read_ordered_filtered(
+                limit,
+                Some(&wrapped_filter),
+                order_by,
+            )?
+        };
+        let mut write_segment_points = self
+            .write_segment
+            .get()
+            .read()
+            .read_ordered_filtered(limit, filter, order_by)?;
+        read_points.append(&mut write_segment_points);
+        read_points.sort_unstable_by(|(a, _), (b, _)| order_by.compare(*a, *b));
+        Ok(read_points)
+    }
+
+    fn read_random_filtered<'a>(
+        &'a self,
+        limit: usize,
+        filter: Option<&'a Filter>,
+    ) -> Vec<(ScoreType, PointIdType)> {
+        let deleted_points = self.deleted_points.read();
+        let mut read_points = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .read_random_filtered(limit, filter)
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment
+                .get()
+                .read()
+                .read_random_filtered(limit, Some(&wrapped_filter))
+        };
+        let mut write_segment_points = self
+            .write_segment
+            .get()
+            .read()
+            .read_random_filtered(limit, filter);
+        read_points.append(&mut write_segment_points);
+        // Keep the points with the largest sampling keys from both segments
+        read_points.sort_unstable_by(|(a, _), (b, _)| b.total_cmp(a));
+        read_points.truncate(limit);
+        read_points
+    }
+
+    fn facet<'a>(
+        &'a self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&'a Filter>,
+        exact: bool,
+    ) -> OperationResult<HashMap<String, usize>> {
+        let deleted_points = self.deleted_points.read();
+        let mut counts = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .facet(key, filter, exact)?
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment
+                .get()
+                .read()
+                .facet(key, Some(&wrapped_filter), exact)?
+        };
+        let write_segment_counts = self.write_segment.get().read().facet(key, filter, exact)?;
+        for (value, count) in write_segment_counts {
+            *counts.entry(value).or_insert(0) += count;
+        }
+        Ok(counts)
+    }
+
     /// Read points in [from; to) range
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType> {
         let deleted_points = self.deleted_points.read();
@@ -528,6 +619,26 @@ impl SegmentEntry for ProxySegment {
         };
     }
 
+    fn select_points_in_storage_order(&self, point_ids: &[PointIdType]) -> Vec<PointIdType> {
+        let deleted_points = self.deleted_points.read();
+        let mut write_segment_points = self
+            .write_segment
+            .get()
+            .read()
+            .select_points_in_storage_order(point_ids);
+        let written: HashSet<_> = write_segment_points.iter().copied().collect();
+
+        let mut selected_points = self
+            .wrapped_segment
+            .get()
+            .read()
+            .select_points_in_storage_order(point_ids);
+        selected_points
+            .retain(|point_id| !deleted_points.contains(point_id) && !written.contains(point_id));
+        selected_points.append(&mut write_segment_points);
+        selected_points
+    }
+
     fn available_point_count(&self) -> usize {
         let deleted_points_count = self.deleted_points.read().len();
         let wrapped_segment_count = self.wrapped_segment.get().read().available_point_count();
@@ -724,6 +835,34 @@ impl SegmentEntry for ProxySegment {
             .collect()
     }
 
+    /// Statistics of the wrapped segment only, the write segment is shared between proxies
+    fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        let deleted_indexes = self.deleted_indexes.read();
+        self.wrapped_segment
+            .get()
+            .read()
+            .get_field_statistics()
+            .into_iter()
+            .filter(|(key, _)| !deleted_indexes.contains(key))
+            .collect()
+    }
+
+    /// Sizes of the wrapped segment only, the write segment is shared between proxies
+    fn get_vector_storage_size_bytes(&self) -> HashMap<String, usize> {
+        self.wrapped_segment
+            .get()
+            .read()
+            .get_vector_storage_size_bytes()
+    }
+
+    /// Only the wrapped segment can be quantized
+    fn get_quantization_drift(&self, vector_name: &str, vectors: &[VectorType]) -> Option<f32> {
+        self.wrapped_segment
+            .get()
+            .read()
+            .get_quantization_drift(vector_name, vectors)
+    }
+
     fn check_error(&self) -> Option<SegmentFailedState> {
         self.write_segment.get().read().check_error()
     }
@@ -1346,6 +1485,9 @@ mod tests {
             ]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         };
         let mut original_segment = build_segment(dir.path(), &config, true).unwrap();
         let write_segment = build_segment(dir.path(), &config, true).unwrap();
@@ -148,6 +148,9 @@ pub struct SegmentHolder {
 
     /// Holds the first uncorrected error happened with optimizer
     pub optimizer_errors: Option<CollectionError>,
+
+    /// Segments, in which background scrubbing found corrupted data, with the found problem
+    corrupted_segments: HashMap<SegmentId, String>,
 }
 
 pub type LockedSegmentHolder = Arc<RwLock<SegmentHolder>>;
@@ -397,7 +400,12 @@ impl<'s> SegmentHolder {
 
         let _applied_points_count = self.apply_points(ids, |point_id, _idx, write_segment| {
             if let Some(point_version) = write_segment.point_version(point_id) {
-                if point_version >= op_num {
+                // Operations of a batch share the operation number, so a point written by an
+                // earlier operation of the same batch is updated again. A point moved into an
+                // appendable segment by this call is not.
+                if point_version > op_num
+                    || (point_version == op_num && applied_points.contains(&point_id))
+                {
                     applied_points.insert(point_id);
                     return Ok(false);
                 }
@@ -435,7 +443,7 @@ impl<'s> SegmentHolder {
         for segment in self.segments.values() {
             let segment_arc = segment.get();
             let read_segment = segment_arc.read();
-            for point in ids.iter().cloned().filter(|id| read_segment.has_point(*id)) {
+            for point in read_segment.select_points_in_storage_order(ids) {
                 let is_ok = f(point, &read_segment)?;
                 read_points += is_ok as usize;
             }
@@ -576,6 +584,28 @@ impl<'s> SegmentHolder {
         }
     }
 
+    /// Remember the result of the integrity check of a segment, done by background scrubbing
+    pub fn report_segment_integrity(&mut self, segment_id: SegmentId, error: Option<String>) {
+        match error {
+            Some(error) => {
+                self.corrupted_segments.insert(segment_id, error);
+            }
+            None => {
+                self.corrupted_segments.remove(&segment_id);
+            }
+        }
+    }
+
+    /// Segments with corrupted data, which are still in use
+    ///
+    /// Corrupted segments, which were already replaced by optimizations, are not included.
+    pub fn corrupted_segments(&self) -> impl Iterator<Item = (SegmentId, &str)> + '_ {
+        self.corrupted_segments
+            .iter()
+            .filter(|(segment_id, _)| self.segments.contains_key(segment_id))
+            .map(|(segment_id, error)| (*segment_id, error.as_str()))
+    }
+
     /// Duplicated points can appear in case of interrupted optimization.
     /// LocalShard can still work with duplicated points, but it is better to remove them.
     /// Duplicated points should not affect the search results.
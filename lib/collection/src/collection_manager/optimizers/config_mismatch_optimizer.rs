@@ -8,7 +8,9 @@ use segment::common::operation_time_statistics::{
     OperationDurationStatistics, OperationDurationsAggregator,
 };
 use segment::index::sparse_index::sparse_index_config::SparseIndexType;
-use segment::types::{HnswConfig, Indexes, QuantizationConfig, SegmentType, VECTOR_ELEMENT_SIZE};
+use segment::types::{
+    HnswConfig, Indexes, PayloadKeyType, QuantizationConfig, SegmentType, VECTOR_ELEMENT_SIZE,
+};
 
 use crate::collection_manager::holders::segment_holder::{LockedSegmentHolder, SegmentId};
 use crate::collection_manager::optimizers::segment_optimizer::{
@@ -71,6 +73,15 @@ impl ConfigMismatchOptimizer {
             .and_then(|index| index.on_disk)
     }
 
+    /// Payload key, current configuration requires sparse vectors index to be partitioned by
+    fn get_sparse_vectors_partition_by(&self, vector_name: &str) -> Option<PayloadKeyType> {
+        self.collection_params
+            .sparse_vectors
+            .as_ref()
+            .and_then(|vector_params| vector_params.get(vector_name))
+            .and_then(|params| params.partition_by.clone())
+    }
+
     /// Calculates and HNSW config that should be used for a given vector
     /// with current configuration.
     ///
@@ -128,8 +139,8 @@ impl ConfigMismatchOptimizer {
                     return None; // Never optimize already optimized segment
                 }
 
-                if self.collection_params.on_disk_payload
-                    != segment_config.payload_storage_type.is_on_disk()
+                if self.collection_params.payload_storage_type()
+                    != segment_config.payload_storage_type
                 {
                     return Some((*idx, vector_size)); // Skip segments with payload mismatch
                 }
@@ -194,6 +205,14 @@ impl ConfigMismatchOptimizer {
                         .sparse_vector_data
                         .iter()
                         .any(|(vector_name, vector_data)| {
+                            // Rebuild immutable index if it is partitioned by a different key
+                            if vector_data.is_index_immutable()
+                                && vector_data.partition_by
+                                    != self.get_sparse_vectors_partition_by(vector_name)
+                            {
+                                return true;
+                            }
+
                             let Some(is_required_on_disk) =
                                 self.check_if_sparse_vectors_index_on_disk(vector_name)
                             else {
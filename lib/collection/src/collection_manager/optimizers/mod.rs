@@ -57,6 +57,13 @@ impl TrackerLog {
         });
     }
 
+    /// Check whether a tracker with the given name is still in progress
+    pub fn is_running(&self, name: &str) -> bool {
+        self.descriptions.iter().any(|tracker| {
+            tracker.name == name && tracker.state.lock().status == TrackerStatus::Optimizing
+        })
+    }
+
     /// Convert log into list of objects usable in telemetry
     pub fn to_telemetry(&self) -> Vec<TrackerTelemetry> {
         self.descriptions
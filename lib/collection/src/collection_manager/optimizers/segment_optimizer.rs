@@ -16,8 +16,8 @@ use segment::segment::{Segment, SegmentVersion};
 use segment::segment_constructor::build_segment;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
 use segment::types::{
-    HnswConfig, Indexes, PayloadFieldSchema, PayloadKeyType, PayloadStorageType, PointIdType,
-    QuantizationConfig, SegmentConfig, VectorStorageType, VECTOR_ELEMENT_SIZE,
+    HnswConfig, Indexes, PayloadFieldSchema, PayloadKeyType, PointIdType, QuantizationConfig,
+    SegmentConfig, VectorStorageType, VECTOR_ELEMENT_SIZE,
 };
 
 use crate::collection_manager::holders::proxy_segment::ProxySegment;
@@ -84,11 +84,10 @@ pub trait SegmentOptimizer {
         let config = SegmentConfig {
             vector_data: collection_params.into_base_vector_data()?,
             sparse_vector_data: collection_params.into_sparse_vector_data()?,
-            payload_storage_type: if collection_params.on_disk_payload {
-                PayloadStorageType::OnDisk
-            } else {
-                PayloadStorageType::InMemory
-            },
+            payload_storage_type: collection_params.payload_storage_type(),
+            payload_compression: collection_params.payload_compression.clone(),
+            payload_blobs: collection_params.payload_blobs.clone(),
+            kv_store: collection_params.kv_store,
         };
         Ok(LockedSegment::new(build_segment(
             self.collection_path(),
@@ -238,11 +237,10 @@ pub trait SegmentOptimizer {
         let optimized_config = SegmentConfig {
             vector_data,
             sparse_vector_data,
-            payload_storage_type: if collection_params.on_disk_payload {
-                PayloadStorageType::OnDisk
-            } else {
-                PayloadStorageType::InMemory
-            },
+            payload_storage_type: collection_params.payload_storage_type(),
+            payload_compression: collection_params.payload_compression.clone(),
+            payload_blobs: collection_params.payload_blobs.clone(),
+            kv_store: collection_params.kv_store,
         };
 
         Ok(SegmentBuilder::new(
@@ -354,14 +352,17 @@ pub trait SegmentOptimizer {
 
         self.check_cancellation(stopped)?;
 
-        for segment in optimizing_segments {
-            match segment {
-                LockedSegment::Original(segment_arc) => {
-                    let segment_guard = segment_arc.read();
-                    segment_builder.update_from(&segment_guard, stopped)?;
-                }
-                LockedSegment::Proxy(_) => panic!("Attempt to optimize segment which is already currently under optimization. Should never happen"),
-            }
+        {
+            let segment_guards: Vec<_> = optimizing_segments
+                .iter()
+                .map(|segment| match segment {
+                    LockedSegment::Original(segment_arc) => segment_arc.read(),
+                    LockedSegment::Proxy(_) => panic!("Attempt to optimize segment which is already currently under optimization. Should never happen"),
+                })
+                .collect();
+            let segments: Vec<&Segment> = segment_guards.iter().map(|guard| &**guard).collect();
+            // All segments are added at once, so points of the same tenant end up next to each other
+            segment_builder.update(&segments, stopped)?;
         }
 
         for field in proxy_deleted_indexes.read().iter() {
@@ -0,0 +1,153 @@
+//! Background building of payload indexes for non-appendable segments
+//!
+//! Payload index operations are applied to appendable segments right away, while large
+//! non-appendable segments receive new indexes here, without blocking updates of the collection.
+
+use std::cmp::min;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use common::types::PointOffsetType;
+use segment::types::{PayloadFieldSchema, PayloadKeyType};
+
+use crate::collection_manager::holders::segment_holder::{
+    LockedSegment, LockedSegmentHolder, SegmentHolder, SegmentId,
+};
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// Name of the payload index builder, as displayed in optimizer telemetry
+pub const PAYLOAD_INDEX_BUILDER_NAME: &str = "payload index builder";
+
+/// Number of points to index while holding a read lock of the segment
+const INDEXING_CHUNK_SIZE: usize = 10_000;
+
+/// Payload indexes, which are expected to be present in all segments
+///
+/// Index operations are always applied to appendable segments, so they define the target schema.
+pub fn target_payload_schema(
+    segments: &SegmentHolder,
+) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
+    let mut schema = HashMap::new();
+    for (_idx, segment) in segments.iter() {
+        let segment = segment.get();
+        let segment_guard = segment.read();
+        if segment_guard.is_appendable() {
+            schema.extend(segment_guard.get_indexed_fields());
+        }
+    }
+    schema
+}
+
+/// Find non-appendable segments, which miss some payload indexes of the target schema
+///
+/// Also includes indexes with outdated parameters, which are rebuilt in place.
+pub fn missing_field_indexes(
+    segments: &SegmentHolder,
+) -> Vec<(SegmentId, PayloadKeyType, PayloadFieldSchema)> {
+    let target_schema = target_payload_schema(segments);
+    if target_schema.is_empty() {
+        return vec![];
+    }
+
+    let mut missing = vec![];
+    for (&segment_id, segment) in segments.iter() {
+        let LockedSegment::Original(segment) = segment else {
+            // Proxy segments get their indexes with the optimized segment
+            continue;
+        };
+        let segment_guard = segment.read();
+        if segment_guard.is_appendable() {
+            continue;
+        }
+        let indexed_fields = segment_guard.get_indexed_fields();
+        for (field, schema) in &target_schema {
+            let is_missing = match indexed_fields.get(field) {
+                None => true,
+                Some(current) => can_rebuild_in_place(current, schema),
+            };
+            if is_missing {
+                missing.push((segment_id, field.clone(), schema.clone()));
+            }
+        }
+    }
+    missing
+}
+
+/// Check if an index with the `current` schema can be replaced by the `target` one in background
+///
+/// Rebuilt index reuses the storage of the current one, so it is only possible if the type of
+/// the indexed values does not change. Otherwise the current index has to be dropped first.
+pub fn can_rebuild_in_place(current: &PayloadFieldSchema, target: &PayloadFieldSchema) -> bool {
+    current != target && current.schema_type() == target.schema_type()
+}
+
+/// Build a single payload index of a non-appendable segment
+///
+/// The index is filled in chunks of points, each under a short read lock of the segment, so
+/// searches and updates are not blocked. Non-appendable segments only change by deletion of
+/// points, which are removed from the index once it is attached. It is attached with a write
+/// lock, if the segment and the target schema did not change in the meantime. If the field is
+/// already indexed with other parameters, the current index is used until the new one is attached.
+///
+/// Returns `true` if the index was attached to the segment.
+pub fn build_field_index(
+    segments: &LockedSegmentHolder,
+    segment_id: SegmentId,
+    field: &PayloadKeyType,
+    schema: &PayloadFieldSchema,
+    stopped: &AtomicBool,
+) -> CollectionResult<bool> {
+    let segment = match segments.read().get(segment_id) {
+        Some(LockedSegment::Original(segment)) => segment.clone(),
+        _ => return Ok(false),
+    };
+
+    let (current_schema, mut field_indexes, total_points) = {
+        let mut segment_guard = segment.write();
+        let current_schema = segment_guard.get_indexed_fields().remove(field);
+        if let Some(current_schema) = &current_schema {
+            if !can_rebuild_in_place(current_schema, schema) {
+                return Ok(false);
+            }
+        }
+        (
+            current_schema,
+            segment_guard.init_field_index(field, schema)?,
+            segment_guard.total_point_count() as PointOffsetType,
+        )
+    };
+
+    for chunk_start in (0..total_points).step_by(INDEXING_CHUNK_SIZE) {
+        if stopped.load(Ordering::Relaxed) {
+            return Err(CollectionError::Cancelled {
+                description: "Payload index building was cancelled".to_string(),
+            });
+        }
+        let chunk_end = min(
+            chunk_start + INDEXING_CHUNK_SIZE as PointOffsetType,
+            total_points,
+        );
+        let segment_guard = segment.read();
+        // The field was indexed or dropped in the meantime, so the built index is outdated
+        if segment_guard.get_indexed_fields().get(field) != current_schema.as_ref() {
+            return Ok(false);
+        }
+        segment_guard.index_field_points(field, &mut field_indexes, chunk_start..chunk_end)?;
+    }
+
+    // Keep the holder locked, so the segment can not be replaced by an optimizer meanwhile
+    let segments_guard = segments.read();
+    let is_same_segment = matches!(
+        segments_guard.get(segment_id),
+        Some(LockedSegment::Original(current)) if Arc::ptr_eq(current, &segment),
+    );
+    if !is_same_segment || target_payload_schema(&segments_guard).get(field) != Some(schema) {
+        return Ok(false);
+    }
+
+    let is_applied = segment
+        .write()
+        .apply_field_index(field, schema.clone(), field_indexes)?;
+    Ok(is_applied)
+}
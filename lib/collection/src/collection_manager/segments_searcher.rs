@@ -332,6 +332,8 @@ impl SegmentsSearcher {
                             }
                         },
                         shard_key: None,
+                        order_value: None,
+                        version: Some(version),
                     },
                 );
                 point_version.insert(id, version);
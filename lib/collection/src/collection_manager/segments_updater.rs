@@ -1,5 +1,6 @@
 //! A collection of functions for updating points and payloads stored in segments
 
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 use parking_lot::{RwLock, RwLockWriteGuard};
@@ -7,13 +8,14 @@ use segment::common::operation_error::{OperationError, OperationResult};
 use segment::data_types::named_vectors::NamedVectors;
 use segment::entry::entry_point::SegmentEntry;
 use segment::types::{
-    Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
+    Condition, Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
     SeqNumberType,
 };
 
 use crate::collection_manager::holders::segment_holder::SegmentHolder;
 use crate::operations::payload_ops::PayloadOps;
-use crate::operations::point_ops::{PointInsertOperationsInternal, PointOperations, PointStruct};
+use crate::operations::point_ops::{PointOperations, PointStruct};
+use crate::operations::point_timestamps::CREATED_AT_KEY;
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::operations::vector_ops::{PointVectors, VectorOperations};
 use crate::operations::FieldIndexOperations;
@@ -96,10 +98,19 @@ pub(crate) fn overwrite_payload(
     op_num: SeqNumberType,
     payload: &Payload,
     points: &[PointIdType],
+    key: Option<&str>,
 ) -> CollectionResult<usize> {
     let updated_points =
-        segments.apply_points_to_appendable(op_num, points, |id, write_segment| {
-            write_segment.set_full_payload(op_num, id, payload)
+        segments.apply_points_to_appendable(op_num, points, |id, write_segment| match key {
+            Some(key) => {
+                let mut full_payload = write_segment.payload(id)?;
+                full_payload.overwrite_by_key(payload, key);
+                write_segment.set_full_payload(op_num, id, &full_payload)
+            }
+            None => {
+                let payload = preserve_created_at(write_segment, id, payload)?;
+                write_segment.set_full_payload(op_num, id, &payload)
+            }
         })?;
 
     check_unprocessed_points(points, &updated_points)?;
@@ -111,9 +122,10 @@ pub(crate) fn overwrite_payload_by_filter(
     op_num: SeqNumberType,
     payload: &Payload,
     filter: &Filter,
+    key: Option<&str>,
 ) -> CollectionResult<usize> {
     let affected_points = points_by_filter(segments, filter)?;
-    overwrite_payload(segments, op_num, payload, &affected_points)
+    overwrite_payload(segments, op_num, payload, &affected_points, key)
 }
 
 pub(crate) fn set_payload(
@@ -121,10 +133,16 @@ pub(crate) fn set_payload(
     op_num: SeqNumberType,
     payload: &Payload,
     points: &[PointIdType],
+    key: Option<&str>,
 ) -> CollectionResult<usize> {
     let updated_points =
-        segments.apply_points_to_appendable(op_num, points, |id, write_segment| {
-            write_segment.set_payload(op_num, id, payload)
+        segments.apply_points_to_appendable(op_num, points, |id, write_segment| match key {
+            Some(key) => {
+                let mut full_payload = write_segment.payload(id)?;
+                full_payload.merge_by_key(payload, key);
+                write_segment.set_full_payload(op_num, id, &full_payload)
+            }
+            None => write_segment.set_payload(op_num, id, payload),
         })?;
 
     check_unprocessed_points(points, &updated_points)?;
@@ -149,9 +167,10 @@ pub(crate) fn set_payload_by_filter(
     op_num: SeqNumberType,
     payload: &Payload,
     filter: &Filter,
+    key: Option<&str>,
 ) -> CollectionResult<usize> {
     let affected_points = points_by_filter(segments, filter)?;
-    set_payload(segments, op_num, payload, &affected_points)
+    set_payload(segments, op_num, payload, &affected_points, key)
 }
 
 pub(crate) fn delete_payload(
@@ -222,6 +241,22 @@ pub(crate) fn create_field_index(
 ) -> CollectionResult<usize> {
     segments
         .apply_segments(|write_segment| {
+            // Building an index over a large non-appendable segment may take a long time,
+            // so it is done in background by the payload index builder instead
+            if !write_segment.is_appendable() {
+                // Index of values of a different type is outdated, drop it to be rebuilt.
+                // Index with other parameters keeps serving requests until it is rebuilt.
+                let is_outdated = field_schema.is_some_and(|schema| {
+                    write_segment
+                        .get_indexed_fields()
+                        .get(field_name)
+                        .is_some_and(|current| current.schema_type() != schema.schema_type())
+                });
+                if is_outdated {
+                    return write_segment.delete_field_index(op_num, field_name);
+                }
+                return Ok(false);
+            }
             write_segment.create_field_index(op_num, field_name, field_schema)
         })
         .map_err(Into::into)
@@ -242,6 +277,28 @@ pub(crate) fn delete_field_index(
 /// - Ok(true) if the operation was successful and point replaced existing value
 /// - Ok(false) if the operation was successful and point was inserted
 /// - Err if the operation failed
+/// Keeps the creation time of an already stored point, if the new payload records timestamps.
+fn preserve_created_at<'a>(
+    segment: &RwLockWriteGuard<dyn SegmentEntry>,
+    point_id: PointIdType,
+    payload: &'a Payload,
+) -> OperationResult<Cow<'a, Payload>> {
+    if !payload.contains_key(CREATED_AT_KEY) || !segment.has_point(point_id) {
+        return Ok(Cow::Borrowed(payload));
+    }
+    let stored_payload = segment.payload(point_id)?;
+    match stored_payload.0.get(CREATED_AT_KEY) {
+        Some(created_at) => {
+            let mut payload = payload.clone();
+            payload
+                .0
+                .insert(CREATED_AT_KEY.to_string(), created_at.clone());
+            Ok(Cow::Owned(payload))
+        }
+        None => Ok(Cow::Borrowed(payload)),
+    }
+}
+
 fn upsert_with_payload(
     segment: &mut RwLockWriteGuard<dyn SegmentEntry>,
     op_num: SeqNumberType,
@@ -251,7 +308,8 @@ fn upsert_with_payload(
 ) -> OperationResult<bool> {
     let mut res = segment.upsert_point(op_num, point_id, vectors)?;
     if let Some(full_payload) = payload {
-        res &= segment.set_full_payload(op_num, point_id, full_payload)?;
+        let full_payload = preserve_created_at(segment, point_id, full_payload)?;
+        res &= segment.set_full_payload(op_num, point_id, &full_payload)?;
     }
     Ok(res)
 }
@@ -391,6 +449,64 @@ where
     Ok(res)
 }
 
+/// Same as [`upsert_points`], but already existing points are only overwritten if they match
+/// the condition. Points, which don't exist yet, are always inserted.
+/// Returns: number of updated points.
+pub(crate) fn conditional_upsert(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    points: &[PointStruct],
+    condition: &Filter,
+) -> CollectionResult<usize> {
+    let point_ids: HashSet<_> = points.iter().map(|point| point.id).collect();
+
+    // Stored points, which would be overwritten, but don't match the condition
+    let skip_filter = Filter::new_must(Condition::HasId(point_ids.into()))
+        .merge(&Filter::new_must_not(Condition::Filter(condition.clone())));
+    let skipped_points: HashSet<_> = points_by_filter(segments, &skip_filter)?
+        .into_iter()
+        .collect();
+
+    upsert_points(
+        segments,
+        op_num,
+        points
+            .iter()
+            .filter(|point| !skipped_points.contains(&point.id)),
+    )
+}
+
+/// Upsert points, if none of the stored points was modified after `if_version`
+///
+/// Operation is rejected as a whole, so a client can re-read the points and retry.
+pub(crate) fn upsert_points_if_version(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    points: &[PointStruct],
+    if_version: SeqNumberType,
+) -> CollectionResult<usize> {
+    let point_ids: Vec<_> = points.iter().map(|point| point.id).collect();
+
+    let mut stale_point = None;
+    segments.read_points(&point_ids, |id, segment| {
+        match segment.point_version(id) {
+            Some(version) if version > if_version && stale_point.is_none() => {
+                stale_point = Some((id, version));
+            }
+            _ => {}
+        }
+        Ok(true)
+    })?;
+
+    if let Some((id, version)) = stale_point {
+        return Err(CollectionError::bad_request(format!(
+            "Point {id} was modified after version {if_version}, current version is {version}",
+        )));
+    }
+
+    upsert_points(segments, op_num, points.iter())
+}
+
 pub(crate) fn process_point_operation(
     segments: &RwLock<SegmentHolder>,
     op_num: SeqNumberType,
@@ -399,33 +515,18 @@ pub(crate) fn process_point_operation(
     match point_operation {
         PointOperations::DeletePoints { ids, .. } => delete_points(&segments.read(), op_num, &ids),
         PointOperations::UpsertPoints(operation) => {
-            let points: Vec<_> = match operation {
-                PointInsertOperationsInternal::PointsBatch(batch) => {
-                    let all_vectors = batch.vectors.into_all_vectors(batch.ids.len());
-                    let vectors_iter = batch.ids.into_iter().zip(all_vectors);
-                    match batch.payloads {
-                        None => vectors_iter
-                            .map(|(id, vectors)| PointStruct {
-                                id,
-                                vector: vectors.into(),
-                                payload: None,
-                            })
-                            .collect(),
-                        Some(payloads) => vectors_iter
-                            .zip(payloads)
-                            .map(|((id, vectors), payload)| PointStruct {
-                                id,
-                                vector: vectors.into(),
-                                payload,
-                            })
-                            .collect(),
-                    }
-                }
-                PointInsertOperationsInternal::PointsList(points) => points,
-            };
+            let points = operation.into_point_vec();
             let res = upsert_points(&segments.read(), op_num, points.iter())?;
             Ok(res)
         }
+        PointOperations::UpsertPointsConditional(operation) => {
+            let points = operation.points_op.into_point_vec();
+            conditional_upsert(&segments.read(), op_num, &points, &operation.condition)
+        }
+        PointOperations::UpsertPointsIfVersion(operation) => {
+            let points = operation.points_op.into_point_vec();
+            upsert_points_if_version(&segments.read(), op_num, &points, operation.if_version)
+        }
         PointOperations::DeletePointsByFilter(filter) => {
             delete_points_by_filter(&segments.read(), op_num, &filter)
         }
@@ -468,10 +569,11 @@ pub(crate) fn process_payload_operation(
     match payload_operation {
         PayloadOps::SetPayload(sp) => {
             let payload: Payload = sp.payload;
+            let key = sp.key.as_deref();
             if let Some(points) = sp.points {
-                set_payload(&segments.read(), op_num, &payload, &points)
+                set_payload(&segments.read(), op_num, &payload, &points, key)
             } else if let Some(filter) = sp.filter {
-                set_payload_by_filter(&segments.read(), op_num, &payload, &filter)
+                set_payload_by_filter(&segments.read(), op_num, &payload, &filter, key)
             } else {
                 Err(CollectionError::BadRequest {
                     description: "No points or filter specified".to_string(),
@@ -497,10 +599,11 @@ pub(crate) fn process_payload_operation(
         }
         PayloadOps::OverwritePayload(sp) => {
             let payload: Payload = sp.payload;
+            let key = sp.key.as_deref();
             if let Some(points) = sp.points {
-                overwrite_payload(&segments.read(), op_num, &payload, &points)
+                overwrite_payload(&segments.read(), op_num, &payload, &points, key)
             } else if let Some(filter) = sp.filter {
-                overwrite_payload_by_filter(&segments.read(), op_num, &payload, &filter)
+                overwrite_payload_by_filter(&segments.read(), op_num, &payload, &filter, key)
             } else {
                 Err(CollectionError::BadRequest {
                     description: "No points or filter specified".to_string(),
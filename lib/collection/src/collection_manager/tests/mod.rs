@@ -2,21 +2,32 @@ mod test_search_aggregation;
 
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use itertools::Itertools;
 use parking_lot::RwLock;
+use segment::data_types::text_index::TextIndexParams;
 use segment::data_types::vectors::only_default_vector;
 use segment::entry::entry_point::SegmentEntry;
-use segment::types::{PayloadFieldSchema, PayloadKeyType, PointIdType};
+use segment::types::{
+    Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadSchemaParams, PayloadSchemaType,
+    PointIdType,
+};
+use serde_json::json;
 use tempfile::Builder;
 
-use crate::collection_manager::fixtures::{build_segment_1, build_segment_2, empty_segment};
+use crate::collection_manager::fixtures::{
+    build_segment_1, build_segment_2, empty_segment, optimize_segment,
+};
 use crate::collection_manager::holders::proxy_segment::ProxySegment;
 use crate::collection_manager::holders::segment_holder::{
     LockedSegment, LockedSegmentHolder, SegmentHolder, SegmentId,
 };
-use crate::collection_manager::segments_updater::upsert_points;
+use crate::collection_manager::payload_index_builder::{build_field_index, missing_field_indexes};
+use crate::collection_manager::segments_updater::{
+    conditional_upsert, create_field_index, upsert_points, upsert_points_if_version,
+};
 use crate::operations::point_ops::PointStruct;
 
 fn wrap_proxy(segments: LockedSegmentHolder, sid: SegmentId, path: &Path) -> SegmentId {
@@ -177,3 +188,152 @@ fn test_move_points_to_copy_on_write() {
         eprintln!("{idx} -> {external}");
     }
 }
+
+#[test]
+fn test_build_payload_index_in_background() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let mut holder = SegmentHolder::default();
+
+    let sid1 = holder.add_locked(optimize_segment(build_segment_1(dir.path())));
+    let _sid2 = holder.add(build_segment_2(dir.path()));
+
+    let segments = Arc::new(RwLock::new(holder));
+
+    let field = "color".to_string();
+    let schema = PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword);
+
+    // Only the appendable segment is indexed right away
+    create_field_index(&segments.read(), 100, &field, Some(&schema)).unwrap();
+    assert_eq!(
+        missing_field_indexes(&segments.read()),
+        vec![(sid1, field.clone(), schema.clone())],
+    );
+
+    let segment = segments.read().get(sid1).unwrap().get();
+    segment.write().delete_point(101, 1.into()).unwrap();
+
+    let stopped = AtomicBool::new(false);
+    assert!(build_field_index(&segments, sid1, &field, &schema, &stopped).unwrap());
+    assert!(missing_field_indexes(&segments.read()).is_empty());
+
+    let info = segment.read().info();
+    assert_eq!(info.index_schema[&field].points, 4);
+    assert_eq!(segment.read().version(), 101);
+}
+
+#[test]
+fn test_rebuild_payload_index_with_new_params() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let mut holder = SegmentHolder::default();
+
+    let sid1 = holder.add_locked(optimize_segment(build_segment_1(dir.path())));
+    let _sid2 = holder.add(build_segment_2(dir.path()));
+
+    let segments = Arc::new(RwLock::new(holder));
+
+    let field = "color".to_string();
+    let schema = PayloadFieldSchema::FieldType(PayloadSchemaType::Text);
+    let stopped = AtomicBool::new(false);
+
+    create_field_index(&segments.read(), 100, &field, Some(&schema)).unwrap();
+    assert!(build_field_index(&segments, sid1, &field, &schema, &stopped).unwrap());
+
+    let new_schema = PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(TextIndexParams {
+        lowercase: Some(false),
+        ..Default::default()
+    }));
+    create_field_index(&segments.read(), 101, &field, Some(&new_schema)).unwrap();
+
+    // Outdated index is kept until the new one is built
+    let segment = segments.read().get(sid1).unwrap().get();
+    assert_eq!(segment.read().get_indexed_fields()[&field], schema);
+    assert_eq!(
+        missing_field_indexes(&segments.read()),
+        vec![(sid1, field.clone(), new_schema.clone())],
+    );
+
+    assert!(build_field_index(&segments, sid1, &field, &new_schema, &stopped).unwrap());
+    assert!(missing_field_indexes(&segments.read()).is_empty());
+
+    let info = segment.read().info();
+    assert_eq!(
+        info.index_schema[&field].params,
+        Some(PayloadSchemaParams::Text(TextIndexParams {
+            lowercase: Some(false),
+            ..Default::default()
+        }))
+    );
+    assert_eq!(info.index_schema[&field].points, 5);
+}
+
+#[test]
+fn test_conditional_upsert() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let mut holder = SegmentHolder::default();
+    let sid = holder.add(empty_segment(dir.path()));
+
+    let point = |id: u64, updated_at: u64| PointStruct {
+        id: id.into(),
+        vector: vec![0.0, 0.0, 0.0, 0.0].into(),
+        payload: Some(json!({ "updated_at": updated_at }).into()),
+    };
+    upsert_points(&holder, 1, &[point(1, 10), point(2, 30)]).unwrap();
+
+    // Last write wins: only overwrite points, which were updated earlier
+    let condition: Filter = serde_json::from_value(json!({
+        "must": [{ "key": "updated_at", "range": { "lt": 20 } }]
+    }))
+    .unwrap();
+    conditional_upsert(
+        &holder,
+        2,
+        &[point(1, 20), point(2, 20), point(3, 20)],
+        &condition,
+    )
+    .unwrap();
+
+    let segment = holder.get(sid).unwrap().get();
+    let stored_payload = |id: u64| segment.read().payload(id.into()).unwrap();
+    let expected_payload = |updated_at: u64| Payload::from(json!({ "updated_at": updated_at }));
+
+    assert_eq!(stored_payload(1), expected_payload(20));
+    assert_eq!(stored_payload(2), expected_payload(30));
+    // New points are inserted regardless of the condition
+    assert_eq!(stored_payload(3), expected_payload(20));
+}
+
+#[test]
+fn test_upsert_points_if_version() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let mut holder = SegmentHolder::default();
+    let sid = holder.add(empty_segment(dir.path()));
+
+    let point = |id: u64, value: u64| PointStruct {
+        id: id.into(),
+        vector: vec![0.0, 0.0, 0.0, 0.0].into(),
+        payload: Some(json!({ "value": value }).into()),
+    };
+    upsert_points(&holder, 1, &[point(1, 10)]).unwrap();
+    upsert_points(&holder, 2, &[point(2, 20)]).unwrap();
+
+    let segment = holder.get(sid).unwrap().get();
+    let stored_payload = |id: u64| segment.read().payload(id.into()).unwrap();
+    let expected_payload = |value: u64| Payload::from(json!({ "value": value }));
+
+    // Point 2 was modified after version 1, nothing is written
+    let result = upsert_points_if_version(&holder, 3, &[point(1, 11), point(2, 21)], 1);
+    assert!(result.is_err());
+    assert_eq!(stored_payload(1), expected_payload(10));
+    assert_eq!(stored_payload(2), expected_payload(20));
+
+    // New points have no version and never conflict
+    upsert_points_if_version(&holder, 4, &[point(1, 11), point(2, 21), point(3, 31)], 2).unwrap();
+    assert_eq!(stored_payload(1), expected_payload(11));
+    assert_eq!(stored_payload(2), expected_payload(21));
+    assert_eq!(stored_payload(3), expected_payload(31));
+    assert_eq!(segment.read().point_version(1.into()), Some(4));
+}
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU32;
 
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -13,6 +14,9 @@ use crate::shards::transfer::ShardTransfer;
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct ShardInfo {
     pub replicas: HashMap<PeerId, ReplicaState>,
+    /// Desired number of replicas of the shard, if it differs from the collection default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replication_factor: Option<NonZeroU32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate, Clone, PartialEq)]
@@ -0,0 +1,88 @@
+//! Conversion of points into Arrow record batches
+//!
+//! Points are stored with an `id` column, a `payload` column with the payload as JSON and
+//! a fixed size list column per dense vector. Sparse vectors are not converted.
+
+use std::sync::Arc;
+
+use arrow_array::builder::{FixedSizeListBuilder, Float32Builder};
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use segment::data_types::vectors::{VectorRef, DEFAULT_VECTOR_NAME};
+use segment::types::WithVector;
+
+use crate::operations::types::{Record, VectorsConfig};
+
+/// Name and dimension of the dense vectors, selected by `with_vector`
+pub fn selected_dense_vectors(
+    vectors_config: &VectorsConfig,
+    with_vector: &WithVector,
+) -> Vec<(String, usize)> {
+    vectors_config
+        .params_iter()
+        .filter(|(name, _)| match with_vector {
+            WithVector::Bool(enabled) => *enabled,
+            WithVector::Selector(names) => names.iter().any(|selected| selected == name),
+        })
+        .map(|(name, params)| (name.to_string(), params.size.get() as usize))
+        .collect()
+}
+
+pub fn vector_column_name(name: &str) -> String {
+    if name == DEFAULT_VECTOR_NAME {
+        "vector".to_string()
+    } else {
+        format!("vector.{name}")
+    }
+}
+
+pub fn points_schema(vectors: &[(String, usize)]) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("payload", DataType::Utf8, true),
+    ];
+    fields.extend(vectors.iter().map(|(name, dim)| {
+        let item = Arc::new(Field::new("item", DataType::Float32, true));
+        let data_type = DataType::FixedSizeList(item, *dim as i32);
+        Field::new(vector_column_name(name), data_type, true)
+    }));
+    Arc::new(Schema::new(fields))
+}
+
+pub fn records_to_batch(
+    schema: SchemaRef,
+    vectors: &[(String, usize)],
+    records: &[Record],
+) -> Result<RecordBatch, ArrowError> {
+    let ids: StringArray = records
+        .iter()
+        .map(|record| Some(record.id.to_string()))
+        .collect();
+    let payloads: StringArray = records
+        .iter()
+        .map(|record| {
+            let payload = record.payload.as_ref()?;
+            serde_json::to_string(payload).ok()
+        })
+        .collect();
+
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(ids), Arc::new(payloads)];
+    for (name, dim) in vectors {
+        let mut builder = FixedSizeListBuilder::new(Float32Builder::new(), *dim as i32);
+        for record in records {
+            match record.vector.as_ref().and_then(|vector| vector.get(name)) {
+                Some(VectorRef::Dense(vector)) if vector.len() == *dim => {
+                    builder.values().append_slice(vector);
+                    builder.append(true);
+                }
+                _ => {
+                    builder.values().append_nulls(*dim);
+                    builder.append(false);
+                }
+            }
+        }
+        columns.push(Arc::new(builder.finish()));
+    }
+
+    RecordBatch::try_new(schema, columns)
+}
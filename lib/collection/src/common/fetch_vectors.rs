@@ -224,6 +224,22 @@ impl<'coll_name> ReferencedPoints<'coll_name> {
     }
 }
 
+pub fn convert_to_vector_owned(
+    example: RecommendExample,
+    all_vectors_records_map: &ReferencedVectors,
+    vector_name: &str,
+    collection_name: Option<&String>,
+) -> Option<Vector> {
+    match example {
+        RecommendExample::Vector(vector) => Some(vector.into()),
+        RecommendExample::Sparse(vector) => Some(vector.into()),
+        RecommendExample::PointId(vid) => {
+            let rec = all_vectors_records_map.get(&collection_name, vid).unwrap();
+            rec.get_vector_by_name(vector_name).map(|v| v.to_owned())
+        }
+    }
+}
+
 pub fn convert_to_vectors_owned(
     examples: Vec<RecommendExample>,
     all_vectors_records_map: &ReferencedVectors,
@@ -232,30 +248,46 @@ pub fn convert_to_vectors_owned(
 ) -> Vec<Vector> {
     examples
         .into_iter()
-        .filter_map(|example| match example {
-            RecommendExample::Vector(vector) => Some(vector.into()),
-            RecommendExample::Sparse(vector) => Some(vector.into()),
-            RecommendExample::PointId(vid) => {
-                let rec = all_vectors_records_map.get(&collection_name, vid).unwrap();
-                rec.get_vector_by_name(vector_name).map(|v| v.to_owned())
-            }
+        .filter_map(|example| {
+            convert_to_vector_owned(
+                example,
+                all_vectors_records_map,
+                vector_name,
+                collection_name,
+            )
         })
         .collect()
 }
 
-pub fn convert_to_vectors<'a>(
-    examples: impl Iterator<Item = &'a RecommendExample> + 'a,
+pub fn convert_to_vector<'a>(
+    example: &'a RecommendExample,
     all_vectors_records_map: &'a ReferencedVectors,
-    vector_name: &'a str,
-    collection_name: Option<&'a String>,
-) -> impl Iterator<Item = VectorRef<'a>> + 'a {
-    examples.filter_map(move |example| match example {
+    vector_name: &str,
+    collection_name: Option<&String>,
+) -> Option<VectorRef<'a>> {
+    match example {
         RecommendExample::Vector(vector) => Some(vector.into()),
         RecommendExample::Sparse(vector) => Some(vector.into()),
         RecommendExample::PointId(vid) => {
             let rec = all_vectors_records_map.get(&collection_name, *vid).unwrap();
             rec.get_vector_by_name(vector_name)
         }
+    }
+}
+
+pub fn convert_to_vectors<'a>(
+    examples: impl Iterator<Item = &'a RecommendExample> + 'a,
+    all_vectors_records_map: &'a ReferencedVectors,
+    vector_name: &'a str,
+    collection_name: Option<&'a String>,
+) -> impl Iterator<Item = VectorRef<'a>> + 'a {
+    examples.filter_map(move |example| {
+        convert_to_vector(
+            example,
+            all_vectors_records_map,
+            vector_name,
+            collection_name,
+        )
     })
 }
 
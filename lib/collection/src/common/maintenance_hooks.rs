@@ -0,0 +1,268 @@
+//! External commands, run before and after heavy maintenance operations
+//!
+//! Operators use them to coordinate with other systems, e.g. to silence alerts while a segment
+//! is being optimized or to warm up caches once a shard transfer is finished. A webhook can be
+//! called with a command like `curl`.
+//!
+//! Failed hooks are logged, they never prevent or fail the maintenance operation.
+
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::shards::shard::ShardId;
+use crate::shards::CollectionId;
+
+/// Interval to check if a hook command has finished
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceOperation {
+    Optimization,
+    Snapshot,
+    Transfer,
+}
+
+impl MaintenanceOperation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Optimization => "optimization",
+            Self::Snapshot => "snapshot",
+            Self::Transfer => "transfer",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookStage {
+    Before,
+    After,
+}
+
+impl HookStage {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Before => "before",
+            Self::After => "after",
+        }
+    }
+}
+
+/// Command to run before or after maintenance operations
+///
+/// The event is passed in environment variables: `QDRANT_HOOK_OPERATION`, `QDRANT_HOOK_STAGE`,
+/// `QDRANT_COLLECTION`, `QDRANT_SHARD_ID` if the operation affects a single shard and
+/// `QDRANT_HOOK_SUCCESS` in the `after` stage.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct MaintenanceHook {
+    /// Operations, which trigger the hook. If empty - all operations.
+    #[serde(default)]
+    pub operations: Vec<MaintenanceOperation>,
+    pub stage: HookStage,
+    /// Program and its arguments
+    pub command: Vec<String>,
+    /// Maximum time to wait for the command, it is killed afterwards.
+    /// Hooks of the `before` stage delay the operation for up to this time.
+    #[serde(default = "default_hook_timeout_sec")]
+    pub timeout_sec: u64,
+}
+
+const fn default_hook_timeout_sec() -> u64 {
+    10
+}
+
+impl MaintenanceHook {
+    fn matches(&self, operation: MaintenanceOperation, stage: HookStage) -> bool {
+        self.stage == stage && (self.operations.is_empty() || self.operations.contains(&operation))
+    }
+
+    /// Run the command and wait for it to finish, returns an error message on failure
+    fn run(&self, env: &[(&str, String)]) -> Result<(), String> {
+        let Some((program, args)) = self.command.split_first() else {
+            return Err("command is empty".to_string());
+        };
+        let mut child = Command::new(program)
+            .args(args)
+            .envs(env.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("can't start {program}: {err}"))?;
+
+        let deadline = Instant::now() + Duration::from_secs(self.timeout_sec);
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => return Ok(()),
+                Ok(Some(status)) => return Err(format!("{program} failed with {status}")),
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!("{program} timed out after {}s", self.timeout_sec));
+                }
+                Ok(None) => thread::sleep(HOOK_POLL_INTERVAL),
+                Err(err) => return Err(format!("can't wait for {program}: {err}")),
+            }
+        }
+    }
+}
+
+/// Configured hooks, together with the collection and shard they are run for
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceHooks {
+    hooks: Arc<Vec<MaintenanceHook>>,
+    collection_id: CollectionId,
+    shard_id: Option<ShardId>,
+}
+
+impl MaintenanceHooks {
+    pub fn new(hooks: &[MaintenanceHook], collection_id: CollectionId) -> Self {
+        Self {
+            hooks: Arc::new(hooks.to_vec()),
+            collection_id,
+            shard_id: None,
+        }
+    }
+
+    pub fn for_shard(&self, shard_id: ShardId) -> Self {
+        Self {
+            shard_id: Some(shard_id),
+            ..self.clone()
+        }
+    }
+
+    /// Run matching hooks one by one. Blocks until all of them finish or time out.
+    ///
+    /// `success` is the result of the operation, only known in the `after` stage.
+    pub fn run_blocking(
+        &self,
+        operation: MaintenanceOperation,
+        stage: HookStage,
+        success: Option<bool>,
+    ) {
+        let mut hooks = self
+            .hooks
+            .iter()
+            .filter(|hook| hook.matches(operation, stage))
+            .peekable();
+        if hooks.peek().is_none() {
+            return;
+        }
+
+        let mut env = vec![
+            ("QDRANT_HOOK_OPERATION", operation.as_str().to_string()),
+            ("QDRANT_HOOK_STAGE", stage.as_str().to_string()),
+            ("QDRANT_COLLECTION", self.collection_id.clone()),
+        ];
+        if let Some(shard_id) = self.shard_id {
+            env.push(("QDRANT_SHARD_ID", shard_id.to_string()));
+        }
+        if let Some(success) = success {
+            env.push(("QDRANT_HOOK_SUCCESS", success.to_string()));
+        }
+
+        for hook in hooks {
+            if let Err(err) = hook.run(&env) {
+                log::warn!(
+                    "Hook {} {} of collection {} failed: {err}",
+                    stage.as_str(),
+                    operation.as_str(),
+                    self.collection_id,
+                );
+            }
+        }
+    }
+
+    /// Same as [`Self::run_blocking`], but runs hooks on a blocking thread
+    pub async fn run(
+        &self,
+        operation: MaintenanceOperation,
+        stage: HookStage,
+        success: Option<bool>,
+    ) {
+        if !self.hooks.iter().any(|hook| hook.matches(operation, stage)) {
+            return;
+        }
+        let hooks = self.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            hooks.run_blocking(operation, stage, success);
+        })
+        .await;
+        if let Err(err) = result {
+            log::warn!("Hooks of collection {} panicked: {err}", self.collection_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_run_hooks() {
+        let dir = tempfile::Builder::new().prefix("hooks").tempdir().unwrap();
+        let output = dir.path().join("events");
+
+        let hook = |stage, operations| MaintenanceHook {
+            operations,
+            stage,
+            command: vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!(
+                    "echo $QDRANT_HOOK_STAGE $QDRANT_HOOK_OPERATION $QDRANT_COLLECTION \
+                     $QDRANT_SHARD_ID $QDRANT_HOOK_SUCCESS >> {}",
+                    output.display(),
+                ),
+            ],
+            timeout_sec: 10,
+        };
+        let configured = [
+            hook(HookStage::Before, vec![]),
+            hook(HookStage::After, vec![MaintenanceOperation::Snapshot]),
+            // Failing and slow hooks don't stop other hooks
+            MaintenanceHook {
+                operations: vec![],
+                stage: HookStage::Before,
+                command: vec!["sleep".to_string(), "10".to_string()],
+                timeout_sec: 0,
+            },
+            MaintenanceHook {
+                operations: vec![],
+                stage: HookStage::After,
+                command: vec![],
+                timeout_sec: 10,
+            },
+        ];
+
+        let hooks = MaintenanceHooks::new(&configured, "test".to_string());
+        hooks.for_shard(3).run_blocking(
+            MaintenanceOperation::Optimization,
+            HookStage::Before,
+            None,
+        );
+        hooks.run_blocking(
+            MaintenanceOperation::Optimization,
+            HookStage::After,
+            Some(true),
+        );
+        hooks.run_blocking(
+            MaintenanceOperation::Snapshot,
+            HookStage::After,
+            Some(false),
+        );
+
+        let events = fs::read_to_string(&output).unwrap();
+        let events: Vec<_> = events.lines().map(str::trim_end).collect();
+        assert_eq!(
+            events,
+            ["before optimization test 3", "after snapshot test false"],
+        );
+    }
+}
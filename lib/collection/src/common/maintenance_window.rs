@@ -0,0 +1,99 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Daily time window in UTC, during which heavy background work of the collection is allowed
+///
+/// If `start` is later than `end`, the window spans over midnight.
+/// If `start` is equal to `end`, the window spans the whole day.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct MaintenanceWindow {
+    /// Start of the window, time of the day in UTC. Example: `22:00:00`
+    pub start: NaiveTime,
+    /// End of the window, time of the day in UTC. Example: `06:00:00`
+    pub end: NaiveTime,
+}
+
+impl MaintenanceWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Less => self.start <= time && time < self.end,
+            std::cmp::Ordering::Greater => self.start <= time || time < self.end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+/// Check if heavy background work is allowed at the given moment
+///
+/// No windows means no restrictions.
+pub fn is_maintenance_allowed_at(windows: &[MaintenanceWindow], now: DateTime<Utc>) -> bool {
+    if windows.is_empty() {
+        return true;
+    }
+    let time = now.time();
+    windows.iter().any(|window| window.contains(time))
+}
+
+pub fn is_maintenance_allowed(windows: &[MaintenanceWindow]) -> bool {
+    is_maintenance_allowed_at(windows, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn time(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_maintenance_window_contains() {
+        let day = MaintenanceWindow {
+            start: time(9, 0),
+            end: time(17, 0),
+        };
+        assert!(day.contains(time(9, 0)));
+        assert!(day.contains(time(12, 30)));
+        assert!(!day.contains(time(17, 0)));
+        assert!(!day.contains(time(3, 0)));
+
+        let night = MaintenanceWindow {
+            start: time(22, 0),
+            end: time(6, 0),
+        };
+        assert!(night.contains(time(23, 0)));
+        assert!(night.contains(time(0, 0)));
+        assert!(night.contains(time(5, 59)));
+        assert!(!night.contains(time(6, 0)));
+        assert!(!night.contains(time(12, 0)));
+
+        let whole_day = MaintenanceWindow {
+            start: time(0, 0),
+            end: time(0, 0),
+        };
+        assert!(whole_day.contains(time(13, 0)));
+    }
+
+    #[test]
+    fn test_is_maintenance_allowed_at() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert!(is_maintenance_allowed_at(&[], now));
+
+        let windows = [
+            MaintenanceWindow {
+                start: time(1, 0),
+                end: time(2, 0),
+            },
+            MaintenanceWindow {
+                start: time(11, 0),
+                end: time(13, 0),
+            },
+        ];
+        assert!(is_maintenance_allowed_at(&windows, now));
+        assert!(!is_maintenance_allowed_at(&windows[..1], now));
+    }
+}
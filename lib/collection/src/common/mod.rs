@@ -1,8 +1,14 @@
+pub mod arrow_points;
 pub mod batching;
 pub mod fetch_vectors;
 pub mod file_utils;
 pub mod is_ready;
+pub mod maintenance_hooks;
+pub mod maintenance_window;
+pub mod points_checksum;
 pub mod retrieve_request_trait;
+pub mod sha_256;
+pub mod snapshots_manager;
 pub mod stoppable_task;
 pub mod stoppable_task_async;
 pub mod stopping_guard;
@@ -0,0 +1,90 @@
+//! Checksum of points, which does not depend on their order or placement in segments
+//!
+//! Each point is hashed separately, point digests are summed up. Replicas with the same points
+//! have the same checksum, regardless of how their segments were built or optimized.
+
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::vectors::VectorRef;
+use segment::types::{Payload, PointIdType};
+use sha2::{Digest, Sha256};
+
+/// Digest of a single point, covers id, all vectors and payload
+pub fn point_digest(point_id: PointIdType, vectors: &NamedVectors, payload: &Payload) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(point_id.to_string().as_bytes());
+
+    // Vectors are stored in a map, names are sorted to make the digest stable
+    let mut vectors: Vec<_> = vectors.iter().collect();
+    vectors.sort_unstable_by_key(|(name, _)| *name);
+    for (name, vector) in vectors {
+        hasher.update(name.as_bytes());
+        match vector {
+            VectorRef::Dense(vector) => {
+                for value in vector {
+                    hasher.update(value.to_le_bytes());
+                }
+            }
+            VectorRef::Sparse(vector) => {
+                for (index, value) in vector.indices.iter().zip(&vector.values) {
+                    hasher.update(index.to_le_bytes());
+                    hasher.update(value.to_le_bytes());
+                }
+            }
+        }
+    }
+
+    if let Ok(payload) = serde_json::to_vec(payload) {
+        hasher.update(payload);
+    }
+
+    let hash = hasher.finalize();
+    u64::from_le_bytes(hash[..8].try_into().expect("SHA256 is longer than 8 bytes"))
+}
+
+/// Combine digests of all points into a hex encoded checksum
+pub fn points_checksum(digests: impl IntoIterator<Item = u64>) -> String {
+    let sum = digests
+        .into_iter()
+        .fold(0u64, |sum, digest| sum.wrapping_add(digest));
+    format!("{sum:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
+    use serde_json::json;
+
+    use super::*;
+
+    fn digest(id: u64, vector: Vec<f32>, payload: serde_json::Value) -> u64 {
+        let vectors = NamedVectors::from([(DEFAULT_VECTOR_NAME.to_string(), vector)]);
+        point_digest(PointIdType::NumId(id), &vectors, &Payload::from(payload))
+    }
+
+    #[test]
+    fn test_points_checksum() {
+        let first = digest(1, vec![0.1, 0.2], json!({"city": "Berlin"}));
+        let second = digest(2, vec![0.3, 0.4], json!({}));
+
+        // Order of points does not matter
+        assert_eq!(
+            points_checksum([first, second]),
+            points_checksum([second, first]),
+        );
+
+        // Any change of a point changes the checksum
+        let changes = [
+            digest(3, vec![0.1, 0.2], json!({"city": "Berlin"})),
+            digest(1, vec![0.1, 0.3], json!({"city": "Berlin"})),
+            digest(1, vec![0.1, 0.2], json!({"city": "London"})),
+        ];
+        for changed in changes {
+            assert_ne!(
+                points_checksum([first, second]),
+                points_checksum([changed, second]),
+            );
+        }
+
+        assert_eq!(points_checksum([]), "0000000000000000");
+    }
+}
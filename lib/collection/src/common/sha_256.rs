@@ -0,0 +1,76 @@
+use std::io::{self, Read};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::operations::types::CollectionResult;
+
+/// Compute SHA256 of the file as a lowercase hex string
+///
+/// This method performs blocking IO.
+pub fn hash_file(path: &Path) -> CollectionResult<String> {
+    let mut reader = HashingReader::new(std::fs::File::open(path)?);
+    reader.drain()?;
+    Ok(reader.finalize())
+}
+
+/// Compare hex encoded hashes, ignoring case and surrounding whitespace
+pub fn hashes_equal(left: &str, right: &str) -> bool {
+    left.trim().eq_ignore_ascii_case(right.trim())
+}
+
+/// Reader, which computes SHA256 of all data read through it
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Read the rest of the data, so that the hash covers the whole input
+    pub fn drain(&mut self) -> io::Result<u64> {
+        io::copy(self, &mut io::sink())
+    }
+
+    /// Lowercase hex string of the hash of data read so far
+    pub fn finalize(self) -> String {
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashing_reader() {
+        let mut reader = HashingReader::new(&b"hello world"[..]);
+        let mut head = [0; 5];
+        reader.read_exact(&mut head).unwrap();
+        assert_eq!(reader.drain().unwrap(), 6);
+        assert_eq!(
+            reader.finalize(),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+        );
+    }
+
+    #[test]
+    fn test_hashes_equal() {
+        assert!(hashes_equal("AbC1\n", "abc1"));
+        assert!(!hashes_equal("abc1", "abc2"));
+    }
+}
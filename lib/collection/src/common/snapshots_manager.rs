@@ -0,0 +1,401 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fmt, io};
+
+use futures::{StreamExt, TryStreamExt};
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectMeta, ObjectStore};
+use serde::Deserialize;
+use tempfile::TempPath;
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+
+use crate::common::sha_256;
+use crate::operations::snapshot_ops::{self, SnapshotDescription};
+use crate::operations::types::{CollectionError, CollectionResult};
+
+const SNAPSHOT_EXTENSION: &str = "snapshot";
+
+/// Where collection snapshots are stored
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotsStorageType {
+    /// Files in the `snapshots_path` directory
+    #[default]
+    Local,
+    /// Objects in a bucket of S3-compatible object storage
+    S3,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SnapshotsConfig {
+    #[serde(default)]
+    pub storage: SnapshotsStorageType,
+    /// Required if `storage` is `s3`
+    #[serde(default)]
+    pub s3: Option<S3Config>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct S3Config {
+    pub bucket: String,
+    #[serde(default)]
+    pub region: Option<String>,
+    /// If not set, credentials are taken from the standard `AWS_*` environment variables
+    #[serde(default)]
+    pub access_key: Option<String>,
+    #[serde(default)]
+    pub secret_key: Option<String>,
+    /// Endpoint of S3-compatible storage other than AWS, e.g. `http://localhost:9000`
+    #[serde(default)]
+    pub endpoint_url: Option<String>,
+}
+
+impl fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3Config")
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("access_key", &self.access_key.as_ref().map(|_| "<hidden>"))
+            .field("secret_key", &self.secret_key.as_ref().map(|_| "<hidden>"))
+            .field("endpoint_url", &self.endpoint_url)
+            .finish()
+    }
+}
+
+/// Stores collection snapshot files either on local disk or in object storage.
+///
+/// Snapshots are addressed by their local paths `<snapshots_path>/<collection>/<snapshot>`.
+/// In object storage, the `<collection>/<snapshot>` part of the path is used as an object key.
+#[derive(Clone)]
+pub enum SnapshotStorageManager {
+    Local,
+    S3(Arc<dyn ObjectStore>),
+}
+
+impl SnapshotStorageManager {
+    pub fn new(config: &SnapshotsConfig) -> CollectionResult<Self> {
+        match config.storage {
+            SnapshotsStorageType::Local => Ok(Self::Local),
+            SnapshotsStorageType::S3 => {
+                let s3_config = config.s3.as_ref().ok_or_else(|| {
+                    CollectionError::service_error(
+                        "S3 snapshot storage is enabled, but `snapshots_config.s3` is not set",
+                    )
+                })?;
+                Ok(Self::S3(Arc::new(build_s3_store(s3_config)?)))
+            }
+        }
+    }
+
+    pub async fn list_snapshots(
+        &self,
+        directory: &Path,
+    ) -> CollectionResult<Vec<SnapshotDescription>> {
+        match self {
+            Self::Local => snapshot_ops::list_snapshots_in_directory(directory).await,
+            Self::S3(store) => {
+                let prefix = ObjectPath::from(path_component(directory)?);
+                let objects: Vec<ObjectMeta> = store
+                    .list(Some(&prefix))
+                    .try_collect()
+                    .await
+                    .map_err(object_store_error)?;
+                Ok(objects
+                    .iter()
+                    .filter(|object| object.location.extension() == Some(SNAPSHOT_EXTENSION))
+                    .map(object_description)
+                    .collect())
+            }
+        }
+    }
+
+    /// Move a finished snapshot file from local `source_path` into the storage at `target_path`
+    ///
+    /// SHA256 of the snapshot is stored alongside it, in the `<snapshot>.checksum` file.
+    pub async fn store_file(
+        &self,
+        source_path: &Path,
+        target_path: &Path,
+    ) -> CollectionResult<SnapshotDescription> {
+        let source_path_clone = source_path.to_path_buf();
+        let checksum =
+            tokio::task::spawn_blocking(move || sha_256::hash_file(&source_path_clone)).await??;
+        let checksum_path = snapshot_ops::get_checksum_path(target_path);
+
+        match self {
+            Self::Local => {
+                // We can't move right away, because snapshot folder can be on another mounting point.
+                // We can't copy to the target location directly, because copy is not atomic.
+                // So we copy to the final location with a temporary name and then rename atomically.
+                let target_path_tmp_move = target_path.with_extension("tmp");
+
+                // Ensure that the temporary file is deleted on error
+                let _temp_path = TempPath::from_path(&target_path_tmp_move);
+                fs::copy(source_path, &target_path_tmp_move).await?;
+                fs::rename(&target_path_tmp_move, target_path).await?;
+
+                fs::write(&checksum_path, &checksum).await?;
+
+                snapshot_ops::get_snapshot_description(target_path).await
+            }
+            Self::S3(store) => {
+                let key = object_key(target_path)?;
+                let file = fs::File::open(source_path).await?;
+                upload_object(store.as_ref(), &key, file).await?;
+
+                let checksum_key = object_key(&checksum_path)?;
+                upload_object(store.as_ref(), &checksum_key, checksum.as_bytes()).await?;
+
+                let object = store.head(&key).await.map_err(object_store_error)?;
+                Ok(SnapshotDescription {
+                    checksum: Some(checksum),
+                    ..object_description(&object)
+                })
+            }
+        }
+    }
+
+    pub async fn delete_snapshot(&self, snapshot_path: &Path) -> CollectionResult<bool> {
+        match self {
+            Self::Local => {
+                fs::remove_file(snapshot_path).await?;
+
+                let checksum_path = snapshot_ops::get_checksum_path(snapshot_path);
+                if let Err(err) = fs::remove_file(&checksum_path).await {
+                    if err.kind() != io::ErrorKind::NotFound {
+                        return Err(err.into());
+                    }
+                }
+            }
+            Self::S3(store) => {
+                let key = object_key(snapshot_path)?;
+                // Deletion of missing objects succeeds in S3, so check it explicitly
+                store.head(&key).await.map_err(object_store_error)?;
+                store.delete(&key).await.map_err(object_store_error)?;
+
+                let checksum_key = object_key(&snapshot_ops::get_checksum_path(snapshot_path))?;
+                store
+                    .delete(&checksum_key)
+                    .await
+                    .map_err(object_store_error)?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Get the snapshot as a local file.
+    ///
+    /// Snapshots from object storage are downloaded into `temp_dir`,
+    /// the returned `TempPath` deletes the downloaded file once it is dropped.
+    pub async fn get_stored_file(
+        &self,
+        snapshot_path: &Path,
+        temp_dir: &Path,
+    ) -> CollectionResult<(PathBuf, Option<TempPath>)> {
+        match self {
+            Self::Local => Ok((snapshot_path.to_path_buf(), None)),
+            Self::S3(store) => {
+                let key = object_key(snapshot_path)?;
+                let temp_path = tempfile::Builder::new()
+                    .prefix("snapshot-download-")
+                    .suffix(&format!(".{SNAPSHOT_EXTENSION}"))
+                    .tempfile_in(temp_dir)?
+                    .into_temp_path();
+
+                let mut stream = store
+                    .get(&key)
+                    .await
+                    .map_err(object_store_error)?
+                    .into_stream();
+                let mut file = fs::File::create(&temp_path).await?;
+                while let Some(chunk) = stream.next().await {
+                    file.write_all(&chunk.map_err(object_store_error)?).await?;
+                }
+                file.flush().await?;
+
+                Ok((temp_path.to_path_buf(), Some(temp_path)))
+            }
+        }
+    }
+
+    /// Check that the snapshot is stored, return its full path
+    pub async fn get_snapshot_path(
+        &self,
+        snapshots_path: &Path,
+        snapshot_name: &str,
+    ) -> CollectionResult<PathBuf> {
+        let not_found = || CollectionError::NotFound {
+            what: format!("Snapshot {snapshot_name}"),
+        };
+
+        let snapshot_path = snapshots_path.join(snapshot_name);
+
+        match self {
+            Self::Local => {
+                let absolute_snapshot_path =
+                    snapshot_path.canonicalize().map_err(|_| not_found())?;
+
+                let absolute_snapshot_dir =
+                    snapshots_path
+                        .canonicalize()
+                        .map_err(|_| CollectionError::NotFound {
+                            what: format!("Snapshot directory: {}", snapshots_path.display()),
+                        })?;
+
+                if !absolute_snapshot_path.starts_with(absolute_snapshot_dir) {
+                    return Err(not_found());
+                }
+
+                if !snapshot_path.exists() {
+                    return Err(not_found());
+                }
+            }
+            Self::S3(store) => {
+                // Name must not point outside of the collection prefix
+                if Path::new(snapshot_name).file_name() != Some(snapshot_name.as_ref()) {
+                    return Err(not_found());
+                }
+                store
+                    .head(&object_key(&snapshot_path)?)
+                    .await
+                    .map_err(|_| not_found())?;
+            }
+        }
+
+        Ok(snapshot_path)
+    }
+}
+
+fn build_s3_store(config: &S3Config) -> CollectionResult<impl ObjectStore> {
+    let mut builder = AmazonS3Builder::from_env().with_bucket_name(&config.bucket);
+    if let Some(region) = &config.region {
+        builder = builder.with_region(region);
+    }
+    if let Some(access_key) = &config.access_key {
+        builder = builder.with_access_key_id(access_key);
+    }
+    if let Some(secret_key) = &config.secret_key {
+        builder = builder.with_secret_access_key(secret_key);
+    }
+    if let Some(endpoint_url) = &config.endpoint_url {
+        builder = builder
+            .with_endpoint(endpoint_url)
+            .with_allow_http(endpoint_url.starts_with("http://"));
+    }
+    builder.build().map_err(|err| {
+        CollectionError::service_error(format!("Can't configure S3 snapshot storage: {err}"))
+    })
+}
+
+/// Upload all data from `reader` into the object at `key`
+pub(crate) async fn upload_object(
+    store: &dyn ObjectStore,
+    key: &ObjectPath,
+    mut reader: impl AsyncRead + Unpin,
+) -> CollectionResult<()> {
+    let (multipart_id, mut writer) = store.put_multipart(key).await.map_err(object_store_error)?;
+    let upload = async {
+        tokio::io::copy(&mut reader, &mut writer).await?;
+        writer.shutdown().await
+    };
+    if let Err(err) = upload.await {
+        // Don't leave incomplete upload parts in the bucket
+        let _ = store.abort_multipart(key, &multipart_id).await;
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+fn path_component(path: &Path) -> CollectionResult<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            CollectionError::service_error(format!("Invalid snapshot path {}", path.display()))
+        })
+}
+
+/// Key of the snapshot object: `<collection>/<snapshot>`
+fn object_key(snapshot_path: &Path) -> CollectionResult<ObjectPath> {
+    let collection_dir = snapshot_path.parent().ok_or_else(|| {
+        CollectionError::service_error(format!("Invalid snapshot path {}", snapshot_path.display()))
+    })?;
+    Ok(ObjectPath::from(path_component(collection_dir)?).child(path_component(snapshot_path)?))
+}
+
+fn object_description(object: &ObjectMeta) -> SnapshotDescription {
+    SnapshotDescription {
+        name: object.location.filename().unwrap_or_default().to_string(),
+        creation_time: Some(object.last_modified.naive_utc()),
+        size: object.size as u64,
+        checksum: None,
+    }
+}
+
+fn object_store_error(err: object_store::Error) -> CollectionError {
+    match err {
+        object_store::Error::NotFound { path, .. } => CollectionError::NotFound {
+            what: format!("Snapshot {path}"),
+        },
+        err => CollectionError::service_error(format!("Snapshot object storage error: {err}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_key() {
+        let key = object_key(Path::new("./snapshots/my_collection/my_snapshot.snapshot")).unwrap();
+        assert_eq!(key.as_ref(), "my_collection/my_snapshot.snapshot");
+    }
+
+    #[tokio::test]
+    async fn test_local_storage() {
+        let temp_dir = tempfile::Builder::new()
+            .prefix("snapshots")
+            .tempdir()
+            .unwrap();
+        let snapshots_path = temp_dir.path().join("collection");
+        fs::create_dir_all(&snapshots_path).await.unwrap();
+
+        let source_path = temp_dir.path().join("source");
+        fs::write(&source_path, b"snapshot data").await.unwrap();
+
+        let manager = SnapshotStorageManager::new(&SnapshotsConfig::default()).unwrap();
+        let target_path = snapshots_path.join("test.snapshot");
+        let description = manager
+            .store_file(&source_path, &target_path)
+            .await
+            .unwrap();
+        assert_eq!(description.name, "test.snapshot");
+        assert_eq!(description.size, 13);
+        let checksum_path = snapshot_ops::get_checksum_path(&target_path);
+        assert_eq!(
+            description.checksum.as_deref(),
+            Some(fs::read_to_string(&checksum_path).await.unwrap().as_str()),
+        );
+
+        let snapshots = manager.list_snapshots(&snapshots_path).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+
+        let path = manager
+            .get_snapshot_path(&snapshots_path, "test.snapshot")
+            .await
+            .unwrap();
+        assert!(manager
+            .get_snapshot_path(&snapshots_path, "../source")
+            .await
+            .is_err());
+
+        assert!(manager.delete_snapshot(&path).await.unwrap());
+        assert!(!checksum_path.exists());
+        assert!(manager
+            .list_snapshots(&snapshots_path)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -8,16 +8,20 @@ use atomicwrites::AtomicFile;
 use atomicwrites::OverwriteBehavior::AllowOverwrite;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
-use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
+use segment::common::operation_error::OperationError;
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::vectors::{VectorRef, DEFAULT_VECTOR_NAME};
 use segment::index::sparse_index::sparse_index_config::{SparseIndexConfig, SparseIndexType};
 use segment::types::{
-    Distance, HnswConfig, Indexes, QuantizationConfig, SparseVectorDataConfig, VectorDataConfig,
-    VectorStorageType,
+    Distance, HnswConfig, Indexes, KvStoreType, OnDiskPayloadBackend, Payload, PayloadBlobsConfig,
+    PayloadCompressionConfig, PayloadStorageType, QuantizationConfig, SparseVectorDataConfig,
+    VectorDataConfig, VectorStorageType,
 };
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 use wal::WalOptions;
 
+use crate::common::maintenance_window::{is_maintenance_allowed, MaintenanceWindow};
 use crate::operations::config_diff::{DiffConfig, QuantizationConfigDiff};
 use crate::operations::types::{
     CollectionError, CollectionResult, SparseVectorParams, SparseVectorsConfig, VectorParams,
@@ -100,10 +104,54 @@ pub struct CollectionParams {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default = "default_on_disk_payload")]
     pub on_disk_payload: bool,
+    /// Storage backend of the payload, if `on_disk_payload` is enabled.
+    /// Applies to segments, created or optimized after the change.
+    /// Default: rocksdb
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_disk_payload_backend: Option<OnDiskPayloadBackend>,
     /// Configuration of the sparse vector storage
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[validate]
     pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
+    /// Time windows in UTC, during which heavy background work is allowed:
+    /// optimizations and automatic shard replication.
+    /// Outside of these windows such work is deferred.
+    /// If not set or empty - background work is allowed at any time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance_windows: Option<Vec<MaintenanceWindow>>,
+    /// Payload fields, which are stored compressed.
+    /// Applies to segments, created or optimized after the change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub payload_compression: Option<PayloadCompressionConfig>,
+    /// Payload fields with large values, which are stored in a separate blob file
+    /// and read only when the payload is requested.
+    /// Applies to segments, created or optimized after the change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_blobs: Option<PayloadBlobsConfig>,
+    /// Key-value storage backend of point IDs, in-memory payload and vectors.
+    /// Applies to segments, created or optimized after the change.
+    /// Default: rocksdb
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_store: Option<KvStoreType>,
+    /// If true - server records creation and last update time of points
+    /// into `__created_at` and `__updated_at` payload fields,
+    /// as milliseconds since unix epoch.
+    /// Updates of vectors alone do not change the update time.
+    /// Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub point_timestamps: Option<bool>,
+    /// If true - points are deleted in background once the time in their `__expire_at`
+    /// payload field, milliseconds since unix epoch, has passed.
+    /// An integer index on `__expire_at` is created, once expiration is enabled.
+    /// Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub point_expiration: Option<bool>,
+    /// If true - updates of points are rejected.
+    /// Collections, opened as a view of a snapshot, are read-only.
+    /// Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
 }
 
 impl Anonymize for CollectionParams {
@@ -116,7 +164,15 @@ impl Anonymize for CollectionParams {
             write_consistency_factor: self.write_consistency_factor,
             read_fan_out_factor: self.read_fan_out_factor,
             on_disk_payload: self.on_disk_payload,
+            on_disk_payload_backend: self.on_disk_payload_backend,
             sparse_vectors: self.sparse_vectors.anonymize(),
+            maintenance_windows: self.maintenance_windows.clone(),
+            payload_compression: self.payload_compression.clone(),
+            payload_blobs: self.payload_blobs.clone(),
+            kv_store: self.kv_store,
+            point_timestamps: self.point_timestamps,
+            point_expiration: self.point_expiration,
+            read_only: self.read_only,
         }
     }
 }
@@ -137,6 +193,66 @@ const fn default_on_disk_payload() -> bool {
     false
 }
 
+/// Restrictions on requests to the collection, to protect it from expensive queries
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash, Default,
+)]
+pub struct StrictModeConfig {
+    /// Whether strict mode is enforced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Max allowed `limit` parameter of search, recommend, scroll and other requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_query_limit: Option<usize>,
+    /// Max number of conditions in a single filter, including nested ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_filter_conditions: Option<usize>,
+    /// Allow filtering by payload fields without a payload index. Default: true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unindexed_filtering: Option<bool>,
+    /// Max number of requests in a batch, points in an update, or points sampled for a matrix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_batch_size: Option<usize>,
+}
+
+impl StrictModeConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+}
+
+/// Max number of requests per second to the collection, accepted by each peer
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash, Default,
+)]
+pub struct RateLimitConfig {
+    /// Max number of read requests per second. If none - reads are not limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub read_rps: Option<usize>,
+    /// Max number of write requests per second. If none - writes are not limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub write_rps: Option<usize>,
+}
+
+/// Query-time expansion of full-text conditions with `expand` enabled.
+/// Can be changed at any time, as it doesn't affect the payload index.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Default)]
+pub struct QueryExpansionConfig {
+    /// Synonyms of single words, e.g. `{"tv": ["television", "flat screen"]}`.
+    /// A word of the query also matches any of its synonyms. Words are matched case-insensitively,
+    /// synonyms are not symmetric unless listed in both directions.
+    #[serde(default)]
+    pub synonyms: HashMap<String, Vec<String>>,
+    /// Words, which are skipped in the query. Matched case-insensitively
+    #[serde(default)]
+    pub stop_words: Vec<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq)]
 pub struct CollectionConfig {
     #[validate]
@@ -149,6 +265,21 @@ pub struct CollectionConfig {
     pub wal_config: WalConfig,
     #[serde(default)]
     pub quantization_config: Option<QuantizationConfig>,
+    /// Arbitrary user metadata of the collection, e.g. model version and embedding provenance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Payload>,
+    /// Restrictions on requests to the collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
+    /// Max number of requests per second to the collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Query-time expansion of full-text conditions with synonyms and stop words
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub query_expansion: Option<QueryExpansionConfig>,
 }
 
 impl CollectionConfig {
@@ -193,10 +324,34 @@ impl CollectionParams {
             write_consistency_factor: default_write_consistency_factor(),
             read_fan_out_factor: None,
             on_disk_payload: default_on_disk_payload(),
+            on_disk_payload_backend: None,
             sparse_vectors: None,
+            maintenance_windows: None,
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
+            point_timestamps: None,
+            point_expiration: None,
+            read_only: None,
         }
     }
 
+    /// Type of payload storage for new segments
+    pub fn payload_storage_type(&self) -> PayloadStorageType {
+        if self.on_disk_payload {
+            self.on_disk_payload_backend
+                .unwrap_or_default()
+                .payload_storage_type()
+        } else {
+            PayloadStorageType::InMemory
+        }
+    }
+
+    /// Whether heavy background work is allowed right now
+    pub fn is_maintenance_allowed(&self) -> bool {
+        is_maintenance_allowed(self.maintenance_windows.as_deref().unwrap_or_default())
+    }
+
     pub fn get_distance(&self, vector_name: &str) -> CollectionResult<Distance> {
         match self.vectors.get_params(vector_name) {
             Some(params) => Ok(params.distance),
@@ -223,6 +378,48 @@ impl CollectionParams {
         }
     }
 
+    /// Check that the given vectors of a point match names, types and dimensions of the
+    /// vectors configured for this collection
+    pub fn check_point_vectors(&self, vectors: &NamedVectors) -> CollectionResult<()> {
+        for (vector_name, vector) in vectors.iter() {
+            let is_sparse = self
+                .sparse_vectors
+                .as_ref()
+                .is_some_and(|sparse_vectors| sparse_vectors.contains_key(vector_name));
+
+            match (vector, self.vectors.get_params(vector_name)) {
+                (VectorRef::Dense(vector), Some(params)) => {
+                    let expected_dim = params.size.get() as usize;
+                    if vector.len() != expected_dim {
+                        return Err(OperationError::WrongVector {
+                            expected_dim,
+                            received_dim: vector.len(),
+                        }
+                        .into());
+                    }
+                }
+                (VectorRef::Sparse(_), None) if is_sparse => {}
+                (VectorRef::Dense(_), None) if is_sparse => {
+                    return Err(CollectionError::BadInput {
+                        description: format!("Vector {vector_name} is expected to be sparse"),
+                    });
+                }
+                (VectorRef::Sparse(_), Some(_)) => {
+                    return Err(CollectionError::BadInput {
+                        description: format!("Vector {vector_name} is expected to be dense"),
+                    });
+                }
+                (_, None) => {
+                    return Err(OperationError::VectorNameNotExists {
+                        received_name: vector_name.to_string(),
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn get_vector_params_mut(&mut self, vector_name: &str) -> CollectionResult<&mut VectorParams> {
         self.vectors
             .get_params_mut(vector_name)
@@ -300,7 +497,10 @@ impl CollectionParams {
     ) -> CollectionResult<()> {
         for (vector_name, update_params) in update_vectors.0.iter() {
             let sparse_vector_params = self.get_sparse_vector_params_mut(vector_name)?;
-            let SparseVectorParams { index } = update_params.clone();
+            let SparseVectorParams {
+                index,
+                partition_by,
+            } = update_params.clone();
 
             if let Some(index) = index {
                 if let Some(existing_index) = &mut sparse_vector_params.index {
@@ -309,6 +509,10 @@ impl CollectionParams {
                     sparse_vector_params.index = Some(index);
                 }
             }
+
+            if let Some(partition_by) = partition_by {
+                sparse_vector_params.partition_by = Some(partition_by);
+            }
         }
         Ok(())
     }
@@ -363,6 +567,7 @@ impl CollectionParams {
                                     .and_then(|index| index.full_scan_threshold),
                                 index_type: SparseIndexType::MutableRam,
                             },
+                            partition_by: params.partition_by.clone(),
                         },
                     )
                 })
@@ -181,6 +181,8 @@ impl From<RecommendGroupsRequestInternal> for GroupRequest {
         let RecommendGroupsRequestInternal {
             positive,
             negative,
+            positive_weights,
+            negative_weights,
             strategy,
             filter,
             params,
@@ -201,6 +203,8 @@ impl From<RecommendGroupsRequestInternal> for GroupRequest {
         let recommend = RecommendRequestInternal {
             positive,
             negative,
+            positive_weights,
+            negative_weights,
             strategy,
             filter,
             params,
@@ -1,16 +1,24 @@
+use std::collections::HashMap;
 use std::hash::Hash;
 
 pub enum HashRing<T: Hash + Copy> {
-    Raw(hashring::HashRing<T>),
+    Raw {
+        ring: hashring::HashRing<T>,
+        shards: Vec<T>,
+    },
     Fair {
         ring: hashring::HashRing<(T, u32)>,
         scale: u32,
+        shards: Vec<T>,
     },
 }
 
-impl<T: Hash + Copy> HashRing<T> {
+impl<T: Hash + Copy + PartialEq> HashRing<T> {
     pub fn raw() -> Self {
-        Self::Raw(hashring::HashRing::new())
+        Self::Raw {
+            ring: hashring::HashRing::new(),
+            shards: Vec::new(),
+        }
     }
 
     /// Constructs a HashRing that tries to give all shards equal space on the ring.
@@ -20,24 +28,45 @@ impl<T: Hash + Copy> HashRing<T> {
         Self::Fair {
             ring: hashring::HashRing::new(),
             scale,
+            shards: Vec::new(),
         }
     }
 
     pub fn add(&mut self, shard: T) {
         match self {
-            HashRing::Raw(ring) => ring.add(shard),
-            HashRing::Fair { ring, scale } => {
+            HashRing::Raw { ring, shards } => {
+                ring.add(shard);
+                if !shards.contains(&shard) {
+                    shards.push(shard);
+                }
+            }
+            HashRing::Fair {
+                ring,
+                scale,
+                shards,
+            } => {
                 for i in 0..*scale {
                     ring.add((shard, i))
                 }
+                if !shards.contains(&shard) {
+                    shards.push(shard);
+                }
             }
         }
     }
 
     pub fn remove(&mut self, shard: &T) -> bool {
         match self {
-            HashRing::Raw(ring) => ring.remove(shard).is_some(),
-            HashRing::Fair { ring, scale } => {
+            HashRing::Raw { ring, shards } => {
+                shards.retain(|other| other != shard);
+                ring.remove(shard).is_some()
+            }
+            HashRing::Fair {
+                ring,
+                scale,
+                shards,
+            } => {
+                shards.retain(|other| other != shard);
                 let mut removed = false;
                 for i in 0..*scale {
                     if ring.remove(&(*shard, i)).is_some() {
@@ -51,24 +80,49 @@ impl<T: Hash + Copy> HashRing<T> {
 
     pub fn get<U: Hash>(&self, key: &U) -> Option<&T> {
         match self {
-            HashRing::Raw(ring) => ring.get(key),
+            HashRing::Raw { ring, .. } => ring.get(key),
             HashRing::Fair { ring, .. } => ring.get(key).map(|(shard, _)| shard),
         }
     }
 
+    /// Shards of the ring, in the order they were added
+    pub fn shards(&self) -> &[T] {
+        match self {
+            HashRing::Raw { shards, .. } | HashRing::Fair { shards, .. } => shards,
+        }
+    }
+
     pub fn len(&self) -> usize {
         match self {
-            HashRing::Raw(ring) => ring.len(),
+            HashRing::Raw { ring, .. } => ring.len(),
             HashRing::Fair { ring, .. } => ring.len(),
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match self {
-            HashRing::Raw(ring) => ring.is_empty(),
+            HashRing::Raw { ring, .. } => ring.is_empty(),
             HashRing::Fair { ring, .. } => ring.is_empty(),
         }
     }
+
+    /// Estimate the share of keys assigned to each shard, by probing the ring with `probes` keys.
+    ///
+    /// The underlying ring does not expose its node positions, so the share is sampled.
+    pub fn estimate_shares(&self, probes: u64) -> HashMap<T, f64>
+    where
+        T: Eq,
+    {
+        let mut hits: HashMap<T, u64> = HashMap::new();
+        for key in 0..probes {
+            if let Some(shard) = self.get(&key) {
+                *hits.entry(*shard).or_default() += 1;
+            }
+        }
+        hits.into_iter()
+            .map(|(shard, count)| (shard, count as f64 / probes as f64))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -90,4 +144,34 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_estimate_shares() {
+        let mut ring = HashRing::fair(100);
+        for shard in 0..4 {
+            ring.add(shard);
+        }
+
+        let shares = ring.estimate_shares(10_000);
+        assert_eq!(shares.len(), 4);
+        assert!((shares.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        for share in shares.values() {
+            assert!((0.15..0.35).contains(share), "unfair share: {share}");
+        }
+
+        assert!(HashRing::<u32>::raw().estimate_shares(100).is_empty());
+    }
+
+    #[test]
+    fn test_shards() {
+        let mut ring = HashRing::fair(10);
+        ring.add(5);
+        ring.add(7);
+        ring.add(8);
+        assert_eq!(ring.shards(), &[5, 7, 8]);
+
+        assert!(ring.remove(&7));
+        assert_eq!(ring.shards(), &[5, 8]);
+        assert!(!ring.remove(&7));
+    }
 }
@@ -15,6 +15,16 @@ pub enum WithLookupInterface {
     WithLookup(WithLookup),
 }
 
+impl WithLookupInterface {
+    /// Name of the collection to look up points in
+    pub fn collection_name(&self) -> &str {
+        match self {
+            WithLookupInterface::Collection(collection_name) => collection_name,
+            WithLookupInterface::WithLookup(with_lookup) => &with_lookup.collection_name,
+        }
+    }
+}
+
 impl From<WithLookupInterface> for WithLookup {
     fn from(with_lookup: WithLookupInterface) -> Self {
         match with_lookup {
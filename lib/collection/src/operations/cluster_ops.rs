@@ -1,10 +1,11 @@
+use std::borrow::Cow;
 use std::num::NonZeroU32;
 
 use common::validation::validate_move_shard_different_peers;
 use schemars::JsonSchema;
 use segment::types::ShardKey;
 use serde::{Deserialize, Serialize};
-use validator::{Validate, ValidationErrors};
+use validator::{Validate, ValidationError, ValidationErrors};
 
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::transfer::ShardTransferMethod;
@@ -20,10 +21,16 @@ pub enum ClusterOperations {
     AbortTransfer(AbortTransferOperation),
     /// Drop replica of a shard from a peer
     DropReplica(DropReplicaOperation),
+    /// Rebuild replica of a shard on a peer from other replicas, replacing its local data
+    RebuildReplica(RebuildReplicaOperation),
     /// Create a custom shard partition for a given key
     CreateShardingKey(CreateShardingKeyOperation),
     /// Drop a custom shard partition for a given key
     DropShardingKey(DropShardingKeyOperation),
+    /// Split data of a custom shard key across additional shards
+    SplitShardingKey(SplitShardingKeyOperation),
+    /// Change the number of replicas of a single shard or of all shards of a shard key
+    ScaleShardReplicas(ScaleShardReplicasOperation),
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -38,6 +45,19 @@ pub struct DropShardingKeyOperation {
     pub drop_sharding_key: DropShardingKey,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SplitShardingKeyOperation {
+    pub split_sharding_key: SplitShardingKey,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ScaleShardReplicasOperation {
+    #[validate]
+    pub scale_shard_replicas: ScaleShardReplicas,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct CreateShardingKey {
@@ -60,6 +80,56 @@ pub struct DropShardingKey {
     pub shard_key: ShardKey,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SplitShardingKey {
+    pub shard_key: ShardKey,
+    /// How many shards to add for this key
+    /// If not specified, the number of shards of the key is doubled
+    pub shards_number: Option<NonZeroU32>,
+    /// How many replicas to create for each new shard
+    /// If not specified, will use the default value from config
+    pub replication_factor: Option<NonZeroU32>,
+    /// Placement of the new shards of this key
+    /// List of peer ids, that can be used to place new shards for this key
+    /// If not specified, will be randomly placed among all peers
+    pub placement: Option<Vec<PeerId>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ScaleShardReplicas {
+    /// Shard to change the number of replicas for
+    /// Exactly one of `shard_id` or `shard_key` must be specified
+    pub shard_id: Option<ShardId>,
+    /// Shard key, all shards of which should change the number of replicas
+    /// Exactly one of `shard_id` or `shard_key` must be specified
+    pub shard_key: Option<ShardKey>,
+    /// Desired number of replicas of the shard
+    /// If not specified, the replication factor of the collection is used
+    pub replication_factor: Option<NonZeroU32>,
+}
+
+impl Validate for ScaleShardReplicas {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        if self.shard_id.is_some() != self.shard_key.is_some() {
+            return Ok(());
+        }
+
+        let mut errors = ValidationErrors::new();
+        errors.add("shard_id", {
+            let mut error = ValidationError::new("exactly_one_of");
+            error.add_param(Cow::from("other_field"), &"shard_key");
+            error.add_param(
+                Cow::from("message"),
+                &"exactly one of \"shard_id\" or \"shard_key\" must be specified",
+            );
+            error
+        });
+        Err(errors)
+    }
+}
+
 impl Validate for ClusterOperations {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
@@ -67,8 +137,11 @@ impl Validate for ClusterOperations {
             ClusterOperations::ReplicateShard(op) => op.validate(),
             ClusterOperations::AbortTransfer(op) => op.validate(),
             ClusterOperations::DropReplica(op) => op.validate(),
+            ClusterOperations::RebuildReplica(op) => op.validate(),
             ClusterOperations::CreateShardingKey(op) => op.validate(),
             ClusterOperations::DropShardingKey(op) => op.validate(),
+            ClusterOperations::SplitShardingKey(op) => op.validate(),
+            ClusterOperations::ScaleShardReplicas(op) => op.validate(),
         }
     }
 }
@@ -94,6 +167,13 @@ pub struct DropReplicaOperation {
     pub drop_replica: Replica,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct RebuildReplicaOperation {
+    #[validate]
+    pub rebuild_replica: Replica,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct AbortTransferOperation {
@@ -2,12 +2,16 @@ use std::num::NonZeroU32;
 
 use merge::Merge;
 use schemars::JsonSchema;
-use segment::types::{BinaryQuantization, HnswConfig, ProductQuantization, ScalarQuantization};
+use segment::types::{
+    BinaryQuantization, HnswConfig, KvStoreType, OnDiskPayloadBackend, PayloadBlobsConfig,
+    PayloadCompressionConfig, ProductQuantization, ScalarQuantization,
+};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use validator::{Validate, ValidationErrors};
 
+use crate::common::maintenance_window::MaintenanceWindow;
 use crate::config::{CollectionParams, WalConfig};
 use crate::operations::types::CollectionResult;
 use crate::optimizers_builder::OptimizersConfig;
@@ -93,7 +97,7 @@ pub struct WalConfigDiff {
     pub wal_segments_ahead: Option<usize>,
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Merge, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, Merge, PartialEq, Eq, Hash)]
 pub struct CollectionParamsDiff {
     /// Number of replicas for each shard
     pub replication_factor: Option<NonZeroU32>,
@@ -107,6 +111,37 @@ pub struct CollectionParamsDiff {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default)]
     pub on_disk_payload: Option<bool>,
+    /// Storage backend of the payload, if `on_disk_payload` is enabled.
+    /// Applies to segments, created or optimized after the change.
+    #[serde(default)]
+    pub on_disk_payload_backend: Option<OnDiskPayloadBackend>,
+    /// Time windows in UTC, during which heavy background work is allowed.
+    /// Empty list removes the restriction.
+    #[serde(default)]
+    pub maintenance_windows: Option<Vec<MaintenanceWindow>>,
+    /// Payload fields, which are stored compressed.
+    /// Applies to segments, created or optimized after the change.
+    #[serde(default)]
+    pub payload_compression: Option<PayloadCompressionConfig>,
+    /// Payload fields with large values, which are stored in a separate blob file.
+    /// Applies to segments, created or optimized after the change.
+    #[serde(default)]
+    pub payload_blobs: Option<PayloadBlobsConfig>,
+    /// Key-value storage backend of point IDs, in-memory payload and vectors.
+    /// Applies to segments, created or optimized after the change.
+    #[serde(default)]
+    pub kv_store: Option<KvStoreType>,
+    /// If true - server records creation and last update time of points
+    /// into `__created_at` and `__updated_at` payload fields.
+    #[serde(default)]
+    pub point_timestamps: Option<bool>,
+    /// If true - points are deleted in background once the time
+    /// in their `__expire_at` payload field has passed.
+    #[serde(default)]
+    pub point_expiration: Option<bool>,
+    /// If true - updates of points are rejected.
+    #[serde(default)]
+    pub read_only: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Merge)]
@@ -349,6 +384,14 @@ mod tests {
             write_consistency_factor: Some(NonZeroU32::new(2).unwrap()),
             read_fan_out_factor: None,
             on_disk_payload: None,
+            on_disk_payload_backend: None,
+            maintenance_windows: None,
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
+            point_timestamps: None,
+            point_expiration: None,
+            read_only: None,
         };
 
         let new_params = diff.update(&params).unwrap();
@@ -24,8 +24,8 @@ use super::consistency_params::ReadConsistency;
 use super::types::{
     BaseGroupRequest, ContextExamplePair, CoreSearchRequest, DiscoverRequestInternal, GroupsResult,
     PointGroup, QueryEnum, RecommendExample, RecommendGroupsRequestInternal, RecommendStrategy,
-    SearchGroupsRequestInternal, SparseIndexParams, SparseVectorParams, VectorParamsDiff,
-    VectorsConfigDiff,
+    Sample, ScrollQuery, SearchGroupsRequestInternal, SparseIndexParams, SparseVectorParams,
+    VectorParamsDiff, VectorsConfigDiff,
 };
 use crate::config::{
     default_replication_factor, default_write_consistency_factor, CollectionConfig,
@@ -50,8 +50,8 @@ use crate::operations::shard_key_selector::ShardKeySelector;
 use crate::operations::shard_selector_internal::ShardSelectorInternal;
 use crate::operations::types::{
     AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionStatus, CountResult,
-    LocalShardInfo, LookupLocation, OptimizersStatus, RecommendRequestInternal, Record,
-    RemoteShardInfo, SearchRequestInternal, ShardTransferInfo, UpdateResult, UpdateStatus,
+    FacetValueHit, LocalShardInfo, LookupLocation, OptimizersStatus, RecommendRequestInternal,
+    Record, RemoteShardInfo, SearchRequestInternal, ShardTransferInfo, UpdateResult, UpdateStatus,
     VectorParams, VectorsConfig,
 };
 use crate::optimizers_builder::OptimizersConfig;
@@ -138,6 +138,8 @@ pub fn try_record_from_grpc(
         payload,
         vector,
         shard_key: convert_shard_key_from_grpc_opt(point.shard_key),
+        order_value: point.order_value,
+        version: point.version,
     })
 }
 
@@ -278,6 +280,14 @@ impl TryFrom<api::grpc::qdrant::CollectionParamsDiff> for CollectionParamsDiff {
                 .transpose()?,
             read_fan_out_factor: value.read_fan_out_factor,
             on_disk_payload: value.on_disk_payload,
+            on_disk_payload_backend: None,
+            maintenance_windows: None,
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
+            point_timestamps: None,
+            point_expiration: None,
+            read_only: None,
         })
     }
 }
@@ -421,6 +431,7 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                     wal_segments_ahead: Some(config.wal_config.wal_segments_ahead as u64),
                 }),
                 quantization_config: config.quantization_config.map(|x| x.into()),
+                metadata: config.metadata.map(payload_to_proto).unwrap_or_default(),
             }),
             payload_schema: payload_schema
                 .into_iter()
@@ -439,6 +450,8 @@ impl From<Record> for api::grpc::qdrant::RetrievedPoint {
             payload: record.payload.map(payload_to_proto).unwrap_or_default(),
             vectors,
             shard_key: record.shard_key.map(convert_shard_key_to_grpc),
+            order_value: record.order_value,
+            version: record.version,
         }
     }
 }
@@ -569,6 +582,7 @@ impl From<api::grpc::qdrant::SparseVectorParams> for SparseVectorParams {
                     full_scan_threshold: index_config.full_scan_threshold.map(|v| v as usize),
                     on_disk: index_config.on_disk,
                 }),
+            partition_by: sparse_vector_params.partition_by,
         }
     }
 }
@@ -582,6 +596,7 @@ impl From<SparseVectorParams> for api::grpc::qdrant::SparseVectorParams {
                     on_disk: index_config.on_disk,
                 }
             }),
+            partition_by: sparse_vector_params.partition_by,
         }
     }
 }
@@ -671,6 +686,14 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                         .sharding_method
                         .map(sharding_method_from_proto)
                         .transpose()?,
+                    on_disk_payload_backend: None,
+                    maintenance_windows: None,
+                    payload_compression: None,
+                    payload_blobs: None,
+                    kv_store: None,
+                    point_timestamps: None,
+                    point_expiration: None,
+                    read_only: None,
                 },
             },
             hnsw_config: match config.hnsw_config {
@@ -692,6 +715,14 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     None
                 }
             },
+            metadata: if config.metadata.is_empty() {
+                None
+            } else {
+                Some(proto_to_payloads(config.metadata)?)
+            },
+            strict_mode_config: None,
+            rate_limit: None,
+            query_expansion: None,
         })
     }
 }
@@ -755,7 +786,8 @@ impl TryFrom<api::grpc::qdrant::PointStruct> for PointStruct {
         let converted_payload = proto_to_payloads(payload)?;
 
         let vector_struct: VectorStruct = match vectors {
-            None => return Err(Status::invalid_argument("Expected some vectors")),
+            // Points of collections without vectors may have no vectors
+            None => VectorStruct::default(),
             Some(vectors) => vectors.try_into()?,
         };
 
@@ -872,6 +904,7 @@ impl TryFrom<api::grpc::qdrant::UpdateResult> for UpdateResult {
                 }
                 _ => return Err(Status::invalid_argument("Malformed UpdateStatus type")),
             },
+            points: None,
         })
     }
 }
@@ -892,6 +925,24 @@ impl From<CountResult> for api::grpc::qdrant::CountResult {
     }
 }
 
+impl From<api::grpc::qdrant::FacetHit> for FacetValueHit {
+    fn from(value: api::grpc::qdrant::FacetHit) -> Self {
+        Self {
+            value: value.value,
+            count: value.count as usize,
+        }
+    }
+}
+
+impl From<FacetValueHit> for api::grpc::qdrant::FacetHit {
+    fn from(value: FacetValueHit) -> Self {
+        Self {
+            value: value.value,
+            count: value.count as u64,
+        }
+    }
+}
+
 impl TryFrom<api::grpc::qdrant::SearchPoints> for CoreSearchRequest {
     type Error = Status;
     fn try_from(value: api::grpc::qdrant::SearchPoints) -> Result<Self, Self::Error> {
@@ -910,6 +961,7 @@ impl TryFrom<api::grpc::qdrant::SearchPoints> for CoreSearchRequest {
             timeout: _,
             shard_key_selector: _,
             sparse_indices,
+            compact_response: _,
         } = value;
 
         let vector_struct =
@@ -959,6 +1011,7 @@ impl<'a> From<CollectionSearchRequest<'a>> for api::grpc::qdrant::SearchPoints {
             timeout: None,
             shard_key_selector: None,
             sparse_indices,
+            compact_response: None,
         }
     }
 }
@@ -976,6 +1029,8 @@ impl From<QueryEnum> for api::grpc::qdrant::QueryEnum {
                     api::grpc::qdrant::RecoQuery {
                         positives: named.query.positives.into_iter().map_into().collect(),
                         negatives: named.query.negatives.into_iter().map_into().collect(),
+                        positive_weights: named.query.positive_weights,
+                        negative_weights: named.query.negative_weights,
                     },
                 )),
             },
@@ -1107,9 +1162,11 @@ impl TryFrom<api::grpc::qdrant::CoreSearchPoints> for CoreSearchRequest {
                     }
                     api::grpc::qdrant::query_enum::Query::RecommendBestScore(query) => {
                         QueryEnum::RecommendBestScore(NamedQuery {
-                            query: RecoQuery::new(
+                            query: RecoQuery::with_weights(
                                 query.positives.into_iter().map(|v| v.into()).collect(),
                                 query.negatives.into_iter().map(|v| v.into()).collect(),
+                                query.positive_weights,
+                                query.negative_weights,
                             ),
                             using: value.vector_name,
                         })
@@ -1213,6 +1270,7 @@ impl TryFrom<api::grpc::qdrant::SearchPointGroups> for SearchGroupsRequestIntern
             timeout: None,
             shard_key_selector: None,
             sparse_indices: value.sparse_indices,
+            compact_response: None,
         };
 
         let SearchRequestInternal {
@@ -1319,6 +1377,55 @@ impl TryFrom<i32> for RecommendStrategy {
     }
 }
 
+impl From<api::grpc::qdrant::Sample> for Sample {
+    fn from(value: api::grpc::qdrant::Sample) -> Self {
+        match value {
+            api::grpc::qdrant::Sample::Random => Sample::Random,
+        }
+    }
+}
+
+impl From<Sample> for api::grpc::qdrant::Sample {
+    fn from(value: Sample) -> Self {
+        match value {
+            Sample::Random => api::grpc::qdrant::Sample::Random,
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::ScrollQuery> for ScrollQuery {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::ScrollQuery) -> Result<Self, Self::Error> {
+        use api::grpc::qdrant::scroll_query::Variant;
+
+        match value.variant {
+            Some(Variant::Sample(sample)) => {
+                let sample = api::grpc::qdrant::Sample::from_i32(sample).ok_or_else(|| {
+                    Status::invalid_argument(format!("Unknown sample type: {sample}"))
+                })?;
+                Ok(ScrollQuery::Sample(sample.into()))
+            }
+            None => Err(Status::invalid_argument("Scroll query is empty")),
+        }
+    }
+}
+
+impl From<ScrollQuery> for api::grpc::qdrant::ScrollQuery {
+    fn from(value: ScrollQuery) -> Self {
+        use api::grpc::qdrant::scroll_query::Variant;
+
+        let variant = match value {
+            ScrollQuery::Sample(sample) => {
+                Variant::Sample(api::grpc::qdrant::Sample::from(sample) as i32)
+            }
+        };
+        Self {
+            variant: Some(variant),
+        }
+    }
+}
+
 impl TryFrom<api::grpc::qdrant::PointId> for RecommendExample {
     type Error = Status;
 
@@ -1350,9 +1457,7 @@ impl TryFrom<api::grpc::qdrant::VectorExample> for RecommendExample {
                 api::grpc::qdrant::vector_example::Example::Id(id) => {
                     Ok(Self::PointId(id.try_into()?))
                 }
-                api::grpc::qdrant::vector_example::Example::Vector(vector) => {
-                    Ok(Self::Vector(vector.data))
-                }
+                api::grpc::qdrant::vector_example::Example::Vector(vector) => Ok(vector.into()),
             })
     }
 }
@@ -1382,6 +1487,8 @@ impl TryFrom<api::grpc::qdrant::RecommendPoints> for RecommendRequestInternal {
         Ok(RecommendRequestInternal {
             positive,
             negative,
+            positive_weights: Some(value.positive_weights).filter(|w| !w.is_empty()),
+            negative_weights: Some(value.negative_weights).filter(|w| !w.is_empty()),
             strategy: value.strategy.map(|s| s.try_into()).transpose()?,
             filter: value.filter.map(|f| f.try_into()).transpose()?,
             params: value.params.map(|p| p.into()),
@@ -1424,11 +1531,15 @@ impl TryFrom<api::grpc::qdrant::RecommendPointGroups> for RecommendGroupsRequest
             negative_vectors: value.negative_vectors,
             timeout: None, // Passed as query param
             shard_key_selector: None,
+            positive_weights: value.positive_weights,
+            negative_weights: value.negative_weights,
         };
 
         let RecommendRequestInternal {
             positive,
             negative,
+            positive_weights,
+            negative_weights,
             strategy,
             using,
             lookup_from,
@@ -1444,6 +1555,8 @@ impl TryFrom<api::grpc::qdrant::RecommendPointGroups> for RecommendGroupsRequest
         Ok(RecommendGroupsRequestInternal {
             positive,
             negative,
+            positive_weights,
+            negative_weights,
             strategy,
             using,
             lookup_from,
@@ -1504,6 +1617,7 @@ impl From<LocalShardInfo> for api::grpc::qdrant::LocalShardInfo {
             points_count: value.points_count as u64,
             state: value.state as i32,
             shard_key: value.shard_key.map(convert_shard_key_to_grpc),
+            checksum: value.checksum,
         }
     }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use schemars::JsonSchema;
+use segment::index::field_index::full_text_index::tokenizers::Tokenizer;
+use segment::types::{
+    Condition, Filter, Match, MatchText, Payload, PayloadContainer, PayloadFieldSchema,
+    PayloadKeyType, PayloadSchemaParams, PayloadSchemaType,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Word of a text payload value, matched by a full-text condition
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct TextHighlight {
+    /// Index of the text among the values of the field, if it has several
+    pub value_index: usize,
+    /// Offset of the first character of the match in the text
+    pub start: usize,
+    /// Offset of the character after the match in the text
+    pub end: usize,
+    /// Matched part of the text
+    pub text: String,
+}
+
+/// Highlights of matched words per payload field
+pub type Highlights = HashMap<PayloadKeyType, Vec<TextHighlight>>;
+
+/// Full-text conditions, which found points must or should satisfy
+fn text_conditions<'a>(filter: &'a Filter, conditions: &mut Vec<(&'a str, &'a MatchText)>) {
+    let matching = [&filter.must, &filter.should]
+        .into_iter()
+        .flatten()
+        .flatten();
+
+    for condition in matching {
+        match condition {
+            Condition::Field(field) => {
+                if let Some(Match::Text(text_match)) = &field.r#match {
+                    conditions.push((&field.key, text_match));
+                }
+            }
+            Condition::Filter(filter) => text_conditions(filter, conditions),
+            Condition::IsEmpty(_)
+            | Condition::IsNull(_)
+            | Condition::HasId(_)
+            | Condition::Nested(_) => {}
+        }
+    }
+}
+
+/// Byte ranges of `text` matched by the condition, in the same way as it is checked
+fn match_ranges(
+    text: &str,
+    text_match: &MatchText,
+    schema: Option<&PayloadFieldSchema>,
+) -> Vec<Range<usize>> {
+    let query = text_match.phrase().unwrap_or(&text_match.text);
+    match schema {
+        Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Text)) => {
+            Tokenizer::highlight(text, query, &Default::default())
+        }
+        Some(PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(params))) => {
+            Tokenizer::highlight(text, query, params)
+        }
+        // Without full-text index, the text is matched as a substring
+        _ if query.is_empty() => vec![],
+        _ => text
+            .match_indices(query)
+            .map(|(start, matched)| start..start + matched.len())
+            .collect(),
+    }
+}
+
+/// Highlights of the words in `payload`, which are matched by full-text conditions of `filter`
+pub fn highlight_payload(
+    filter: &Filter,
+    payload: &Payload,
+    schema: &HashMap<PayloadKeyType, PayloadFieldSchema>,
+) -> Highlights {
+    let mut conditions = Vec::new();
+    text_conditions(filter, &mut conditions);
+
+    let mut highlights = Highlights::new();
+    for (key, text_match) in conditions {
+        let values = payload.get_value(key).values();
+        let texts = values.into_iter().flat_map(|value| match value {
+            Value::String(text) => vec![text.as_str()],
+            Value::Array(array) => array.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        });
+
+        for (value_index, text) in texts.enumerate() {
+            let field_highlights = match_ranges(text, text_match, schema.get(key))
+                .into_iter()
+                .map(|range| TextHighlight {
+                    value_index,
+                    start: text[..range.start].chars().count(),
+                    end: text[..range.end].chars().count(),
+                    text: text[range].to_string(),
+                });
+            highlights
+                .entry(key.to_string())
+                .or_default()
+                .extend(field_highlights);
+        }
+    }
+
+    for field_highlights in highlights.values_mut() {
+        field_highlights.sort_by_key(|highlight| (highlight.value_index, highlight.start));
+        field_highlights.dedup();
+    }
+    highlights.retain(|_, field_highlights| !field_highlights.is_empty());
+    highlights
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::FieldCondition;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_highlight_payload() {
+        let payload: Payload = serde_json::from_value(json!({
+            "title": "Cheap TV, cheaper than ever",
+            "tags": ["TV", "smart tv"],
+        }))
+        .unwrap();
+        let schema = HashMap::from([(
+            "title".to_string(),
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Text),
+        )]);
+        let filter = Filter::new_must(Condition::Filter(Filter::new_should(Condition::Field(
+            FieldCondition::new_match(
+                "title",
+                Match::Text(MatchText::from("tv cheap".to_string())),
+            ),
+        ))))
+        .merge(&Filter::new_must(Condition::Field(
+            FieldCondition::new_match("tags", Match::Text(MatchText::from("tv".to_string()))),
+        )));
+
+        let highlights = highlight_payload(&filter, &payload, &schema);
+
+        let highlight = |value_index, start, end, text: &str| TextHighlight {
+            value_index,
+            start,
+            end,
+            text: text.to_string(),
+        };
+        // Indexed field is matched by words, other fields by substrings
+        assert_eq!(
+            highlights,
+            Highlights::from([
+                (
+                    "title".to_string(),
+                    vec![highlight(0, 0, 5, "Cheap"), highlight(0, 6, 8, "TV")],
+                ),
+                ("tags".to_string(), vec![highlight(1, 6, 8, "tv")]),
+            ]),
+        );
+    }
+}
@@ -2,9 +2,12 @@ pub mod cluster_ops;
 pub mod config_diff;
 pub mod consistency_params;
 pub mod conversions;
+pub mod highlight;
 pub mod operation_effect;
 pub mod payload_ops;
 pub mod point_ops;
+pub mod point_timestamps;
+pub mod query_expansion;
 pub mod shard_key_selector;
 pub mod shard_selector_internal;
 pub mod shared_storage_config;
@@ -12,6 +15,7 @@ pub mod snapshot_ops;
 pub mod types;
 pub mod validation;
 pub mod vector_ops;
+pub mod verification;
 
 use std::collections::HashMap;
 
@@ -46,6 +50,8 @@ pub enum CollectionUpdateOperations {
     VectorOperation(vector_ops::VectorOperations),
     PayloadOperation(payload_ops::PayloadOps),
     FieldIndexOperation(FieldIndexOperations),
+    /// Operations, applied in order under a single operation number, as one WAL entry
+    BatchOperation(Vec<CollectionUpdateOperations>),
 }
 
 /// A mapping of operation to shard.
@@ -106,6 +112,9 @@ impl Validate for CollectionUpdateOperations {
             CollectionUpdateOperations::VectorOperation(operation) => operation.validate(),
             CollectionUpdateOperations::PayloadOperation(operation) => operation.validate(),
             CollectionUpdateOperations::FieldIndexOperation(operation) => operation.validate(),
+            CollectionUpdateOperations::BatchOperation(operations) => operations
+                .iter()
+                .try_for_each(|operation| operation.validate()),
         }
     }
 }
@@ -156,10 +165,69 @@ impl SplitByShard for CollectionUpdateOperations {
             operation @ CollectionUpdateOperations::FieldIndexOperation(_) => {
                 OperationToShard::to_all(operation)
             }
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                split_batch_by_shard(operations, ring)
+            }
         }
     }
 }
 
+/// Split operations of a batch by shard, keeping their order within the batch of each shard
+///
+/// Operations, which go to all shards, are added to the batch of every shard of the ring.
+fn split_batch_by_shard(
+    operations: Vec<CollectionUpdateOperations>,
+    ring: &HashRing<ShardId>,
+) -> OperationToShard<CollectionUpdateOperations> {
+    let operations: Vec<_> = operations
+        .into_iter()
+        .map(|operation| operation.split_by_shard(ring))
+        .collect();
+
+    if operations
+        .iter()
+        .all(|operation| matches!(operation, OperationToShard::ToAll(_)))
+    {
+        let operations = operations
+            .into_iter()
+            .filter_map(|operation| match operation {
+                OperationToShard::ToAll(operation) => Some(operation),
+                OperationToShard::ByShard(_) => None,
+            })
+            .collect();
+        return OperationToShard::to_all(CollectionUpdateOperations::BatchOperation(operations));
+    }
+
+    let mut batches: Vec<(ShardId, Vec<CollectionUpdateOperations>)> = ring
+        .shards()
+        .iter()
+        .map(|shard_id| (*shard_id, Vec::new()))
+        .collect();
+    for operation in operations {
+        match operation {
+            OperationToShard::ByShard(by_shard) => {
+                for (shard_id, operation) in by_shard {
+                    if let Some((_, batch)) = batches.iter_mut().find(|(id, _)| *id == shard_id) {
+                        batch.push(operation);
+                    }
+                }
+            }
+            OperationToShard::ToAll(operation) => {
+                for (_, batch) in &mut batches {
+                    batch.push(operation.clone());
+                }
+            }
+        }
+    }
+
+    OperationToShard::by_shard(
+        batches
+            .into_iter()
+            .filter(|(_, batch)| !batch.is_empty())
+            .map(|(shard_id, batch)| (shard_id, CollectionUpdateOperations::BatchOperation(batch))),
+    )
+}
+
 impl CollectionUpdateOperations {
     pub fn is_write_operation(&self) -> bool {
         match self {
@@ -173,12 +241,27 @@ impl CollectionUpdateOperations {
             CollectionUpdateOperations::FieldIndexOperation(operation) => {
                 operation.is_write_operation()
             }
+            CollectionUpdateOperations::BatchOperation(operations) => operations
+                .iter()
+                .any(|operation| operation.is_write_operation()),
+        }
+    }
+
+    /// Single operations, which this operation consists of, in the order of application
+    pub fn single_operations(&self) -> Vec<&CollectionUpdateOperations> {
+        match self {
+            CollectionUpdateOperations::BatchOperation(operations) => operations
+                .iter()
+                .flat_map(|operation| operation.single_operations())
+                .collect(),
+            operation => vec![operation],
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use segment::types::Filter;
     use serde_json;
 
     use super::*;
@@ -193,4 +276,90 @@ mod tests {
         let json = serde_json::to_string_pretty(&op).unwrap();
         println!("{json}")
     }
+
+    fn delete_points(ids: impl IntoIterator<Item = u64>) -> CollectionUpdateOperations {
+        CollectionUpdateOperations::PointOperation(point_ops::PointOperations::DeletePoints {
+            ids: ids.into_iter().map(ExtendedPointId::from).collect(),
+        })
+    }
+
+    #[test]
+    fn test_batch_wal_roundtrip() {
+        let op = CollectionUpdateOperations::BatchOperation(vec![
+            delete_points([1, 2]),
+            CollectionUpdateOperations::PayloadOperation(payload_ops::PayloadOps::ClearPayload {
+                points: vec![3.into()],
+            }),
+        ]);
+
+        let bytes = serde_cbor::to_vec(&op).unwrap();
+        let CollectionUpdateOperations::BatchOperation(operations) =
+            serde_cbor::from_slice(&bytes).unwrap()
+        else {
+            panic!("batch expected");
+        };
+        assert!(matches!(
+            operations.as_slice(),
+            [
+                CollectionUpdateOperations::PointOperation(
+                    point_ops::PointOperations::DeletePoints { .. }
+                ),
+                CollectionUpdateOperations::PayloadOperation(
+                    payload_ops::PayloadOps::ClearPayload { .. }
+                ),
+            ],
+        ));
+    }
+
+    #[test]
+    fn test_split_batch_by_shard() {
+        let mut ring = HashRing::fair(100);
+        ring.add(0);
+        ring.add(1);
+        ring.add(2);
+
+        let op = CollectionUpdateOperations::BatchOperation(vec![
+            delete_points(0..100),
+            CollectionUpdateOperations::PayloadOperation(
+                payload_ops::PayloadOps::ClearPayloadByFilter(Filter::default()),
+            ),
+            delete_points([1]),
+        ]);
+
+        let OperationToShard::ByShard(by_shard) = op.split_by_shard(&ring) else {
+            panic!("operation by shard expected");
+        };
+        // Operation by filter goes to every shard
+        assert_eq!(by_shard.len(), 3);
+
+        let mut deleted_points = 0;
+        for (shard_id, operation) in by_shard {
+            let CollectionUpdateOperations::BatchOperation(operations) = operation else {
+                panic!("batch expected");
+            };
+            let kinds: Vec<_> = operations
+                .iter()
+                .map(|operation| match operation {
+                    CollectionUpdateOperations::PointOperation(
+                        point_ops::PointOperations::DeletePoints { ids },
+                    ) => {
+                        deleted_points += ids.len();
+                        "delete"
+                    }
+                    CollectionUpdateOperations::PayloadOperation(
+                        payload_ops::PayloadOps::ClearPayloadByFilter(_),
+                    ) => "clear",
+                    operation => panic!("unexpected operation {operation:?}"),
+                })
+                .collect();
+
+            // Order of the batch is kept within the batch of each shard
+            if shard_id == point_to_shard(1.into(), &ring) {
+                assert_eq!(kinds, ["delete", "clear", "delete"]);
+            } else {
+                assert_eq!(kinds, ["delete", "clear"]);
+            }
+        }
+        assert_eq!(deleted_points, 101);
+    }
 }
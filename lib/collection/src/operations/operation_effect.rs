@@ -1,4 +1,6 @@
-use segment::types::{Filter, PointIdType};
+use std::collections::HashSet;
+
+use segment::types::{Condition, Filter, HasIdCondition, PointIdType};
 
 use super::vector_ops;
 use crate::operations::payload_ops::PayloadOps;
@@ -38,16 +40,56 @@ impl EstimateOperationEffectArea for CollectionUpdateOperations {
                 payload_operation.estimate_effect_area()
             }
             CollectionUpdateOperations::FieldIndexOperation(_) => OperationEffectArea::Empty,
+            CollectionUpdateOperations::BatchOperation(operations) => batch_effect_area(operations),
         }
     }
 }
 
+/// Union of the effect areas of the operations of a batch
+fn batch_effect_area(operations: &[CollectionUpdateOperations]) -> OperationEffectArea {
+    let mut points = Vec::new();
+    let mut filters = Vec::new();
+    for operation in operations {
+        match operation.estimate_effect_area() {
+            OperationEffectArea::Empty => {}
+            OperationEffectArea::Points(ids) => points.extend(ids),
+            OperationEffectArea::Filter(filter) => filters.push(filter),
+        }
+    }
+
+    if filters.is_empty() {
+        return if points.is_empty() {
+            OperationEffectArea::Empty
+        } else {
+            OperationEffectArea::Points(points)
+        };
+    }
+
+    let mut conditions: Vec<_> = filters.into_iter().map(Condition::Filter).collect();
+    if !points.is_empty() {
+        conditions.push(Condition::HasId(HasIdCondition::from(
+            points.into_iter().collect::<HashSet<_>>(),
+        )));
+    }
+    OperationEffectArea::Filter(Filter {
+        should: Some(conditions),
+        must: None,
+        must_not: None,
+    })
+}
+
 impl EstimateOperationEffectArea for point_ops::PointOperations {
     fn estimate_effect_area(&self) -> OperationEffectArea {
         match self {
             point_ops::PointOperations::UpsertPoints(insert_operations) => {
                 insert_operations.estimate_effect_area()
             }
+            point_ops::PointOperations::UpsertPointsConditional(operation) => {
+                operation.points_op.estimate_effect_area()
+            }
+            point_ops::PointOperations::UpsertPointsIfVersion(operation) => {
+                operation.points_op.estimate_effect_area()
+            }
             point_ops::PointOperations::DeletePoints { ids } => {
                 OperationEffectArea::Points(ids.clone())
             }
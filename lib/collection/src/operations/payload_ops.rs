@@ -20,6 +20,10 @@ pub struct SetPayload {
     pub filter: Option<Filter>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shard_key: Option<ShardKeySelector>,
+    /// Assigns payload to the object at this nested key, e.g. `metadata.reviews[0]`,
+    /// instead of the root of the payload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<PayloadKeyType>,
 }
 
 /// This data structure is used inside shard operations queue
@@ -34,6 +38,9 @@ pub struct SetPayloadOp {
     pub points: Option<Vec<PointIdType>>,
     /// Assigns payload to each point that satisfy this filter condition
     pub filter: Option<Filter>,
+    /// Assigns payload to the object at this nested key, instead of the root of the payload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<PayloadKeyType>,
 }
 
 #[derive(Deserialize)]
@@ -42,6 +49,7 @@ struct SetPayloadShadow {
     pub points: Option<Vec<PointIdType>>,
     pub filter: Option<Filter>,
     pub shard_key: Option<ShardKeySelector>,
+    pub key: Option<PayloadKeyType>,
 }
 
 pub struct PointsSelectorValidationError;
@@ -65,6 +73,7 @@ impl TryFrom<SetPayloadShadow> for SetPayload {
                 points: value.points,
                 filter: value.filter,
                 shard_key: value.shard_key,
+                key: value.key,
             })
         } else {
             Err(PointsSelectorValidationError)
@@ -212,6 +221,7 @@ impl SplitByShard for SetPayloadOp {
                         points: Some(points),
                         payload: self.payload.clone(),
                         filter: self.filter.clone(),
+                        key: self.key.clone(),
                     }
                 })
             }
@@ -6,7 +6,7 @@ use schemars::JsonSchema;
 use segment::common::utils::transpose_map_into_named_vector;
 use segment::data_types::named_vectors::NamedVectors;
 use segment::data_types::vectors::{BatchVectorStruct, Vector, VectorStruct, DEFAULT_VECTOR_NAME};
-use segment::types::{Filter, Payload, PointIdType};
+use segment::types::{Filter, Payload, PointIdType, SeqNumberType};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -38,8 +38,8 @@ pub enum WriteOrdering {
 pub struct PointStruct {
     /// Point id
     pub id: PointIdType,
-    /// Vectors
-    #[serde(alias = "vectors")]
+    /// Vectors. May be omitted for collections without vectors
+    #[serde(default, alias = "vectors")]
     #[validate]
     pub vector: VectorStruct,
     /// Payload values (optional)
@@ -56,6 +56,8 @@ impl TryFrom<Record> for PointStruct {
             payload,
             vector,
             shard_key: _,
+            order_value: _,
+            version: _,
         } = record;
 
         if vector.is_none() {
@@ -139,6 +141,55 @@ impl Validate for PointsSelector {
     }
 }
 
+/// Copy points, matching the filter, into another collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CopyPoints {
+    /// Name of the collection to copy points into
+    #[validate(length(min = 1, max = 255))]
+    pub target_collection: String,
+    /// Copy only points, which satisfy this filter. If none - all points are copied
+    #[validate]
+    pub filter: Option<Filter>,
+    /// Mapping of vector names of the source collection to vector names of the target collection.
+    /// If specified, only vectors present in the mapping are copied.
+    /// Use an empty string as the name of a single unnamed vector.
+    #[serde(default)]
+    pub vectors: Option<HashMap<String, String>>,
+}
+
+impl CopyPoints {
+    /// Renames vectors of a copied point according to the mapping, if any
+    pub fn map_vectors(&self, vector: VectorStruct) -> VectorStruct {
+        let Some(mapping) = &self.vectors else {
+            return vector;
+        };
+        let vectors = match vector {
+            VectorStruct::Single(vector) => {
+                HashMap::from([(DEFAULT_VECTOR_NAME.to_string(), Vector::Dense(vector))])
+            }
+            VectorStruct::Multi(vectors) => vectors,
+        };
+        VectorStruct::Multi(
+            vectors
+                .into_iter()
+                .filter_map(|(name, vector)| {
+                    mapping
+                        .get(&name)
+                        .map(|target_name| (target_name.clone(), vector))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct CopyPointsResult {
+    /// Number of points copied into the target collection
+    pub copied: usize,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PointSyncOperation {
     /// Minimal id of the sync range
@@ -154,6 +205,15 @@ pub struct PointsBatch {
     pub batch: Batch,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shard_key: Option<ShardKeySelector>,
+    /// If specified, already existing points are only overwritten if they match this filter.
+    /// Points, which don't exist yet, are always inserted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub condition: Option<Filter>,
+    /// If specified, the operation is rejected if any of the points was modified after this
+    /// version. Versions are returned with retrieved points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<SeqNumberType>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, JsonSchema, Validate)]
@@ -162,6 +222,15 @@ pub struct PointsList {
     pub points: Vec<PointStruct>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shard_key: Option<ShardKeySelector>,
+    /// If specified, already existing points are only overwritten if they match this filter.
+    /// Points, which don't exist yet, are always inserted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub condition: Option<Filter>,
+    /// If specified, the operation is rejected if any of the points was modified after this
+    /// version. Versions are returned with retrieved points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<SeqNumberType>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
@@ -182,11 +251,29 @@ impl Validate for PointInsertOperations {
     }
 }
 
+/// Parts of an insert operation, see [`PointInsertOperations::decompose`]
+pub struct DecomposedInsertOperation {
+    pub shard_key: Option<ShardKeySelector>,
+    pub operation: PointInsertOperationsInternal,
+    pub condition: Option<Filter>,
+    pub if_version: Option<SeqNumberType>,
+}
+
 impl PointInsertOperations {
-    pub fn decompose(self) -> (Option<ShardKeySelector>, PointInsertOperationsInternal) {
+    pub fn decompose(self) -> DecomposedInsertOperation {
         match self {
-            PointInsertOperations::PointsBatch(batch) => (batch.shard_key, batch.batch.into()),
-            PointInsertOperations::PointsList(list) => (list.shard_key, list.points.into()),
+            PointInsertOperations::PointsBatch(batch) => DecomposedInsertOperation {
+                shard_key: batch.shard_key,
+                operation: batch.batch.into(),
+                condition: batch.condition,
+                if_version: batch.if_version,
+            },
+            PointInsertOperations::PointsList(list) => DecomposedInsertOperation {
+                shard_key: list.shard_key,
+                operation: list.points.into(),
+                condition: list.condition,
+                if_version: list.if_version,
+            },
         }
     }
 }
@@ -262,6 +349,36 @@ impl Validate for Batch {
     }
 }
 
+impl PointInsertOperationsInternal {
+    /// Convert operation into the list of individual points
+    pub fn into_point_vec(self) -> Vec<PointStruct> {
+        match self {
+            PointInsertOperationsInternal::PointsBatch(batch) => {
+                let all_vectors = batch.vectors.into_all_vectors(batch.ids.len());
+                let vectors_iter = batch.ids.into_iter().zip(all_vectors);
+                match batch.payloads {
+                    None => vectors_iter
+                        .map(|(id, vectors)| PointStruct {
+                            id,
+                            vector: vectors.into(),
+                            payload: None,
+                        })
+                        .collect(),
+                    Some(payloads) => vectors_iter
+                        .zip(payloads)
+                        .map(|((id, vectors), payload)| PointStruct {
+                            id,
+                            vector: vectors.into(),
+                            payload,
+                        })
+                        .collect(),
+                }
+            }
+            PointInsertOperationsInternal::PointsList(points) => points,
+        }
+    }
+}
+
 impl SplitByShard for PointInsertOperationsInternal {
     fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
         match self {
@@ -280,6 +397,8 @@ impl From<Batch> for PointInsertOperations {
         PointInsertOperations::PointsBatch(PointsBatch {
             batch,
             shard_key: None,
+            condition: None,
+            if_version: None,
         })
     }
 }
@@ -289,6 +408,8 @@ impl From<Vec<PointStruct>> for PointInsertOperations {
         PointInsertOperations::PointsList(PointsList {
             points,
             shard_key: None,
+            condition: None,
+            if_version: None,
         })
     }
 }
@@ -305,11 +426,60 @@ impl From<Vec<PointStruct>> for PointInsertOperationsInternal {
     }
 }
 
+/// Upsert, which only overwrites existing points if they match the condition
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ConditionalInsertOperationInternal {
+    pub points_op: PointInsertOperationsInternal,
+    /// Condition to check against the stored point, if it exists
+    pub condition: Filter,
+}
+
+impl SplitByShard for ConditionalInsertOperationInternal {
+    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+        let Self {
+            points_op,
+            condition,
+        } = self;
+        points_op
+            .split_by_shard(ring)
+            .map(|points_op| ConditionalInsertOperationInternal {
+                points_op,
+                condition: condition.clone(),
+            })
+    }
+}
+
+/// Upsert, which is rejected if any of the points was modified after the given version
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct VersionedInsertOperationInternal {
+    pub points_op: PointInsertOperationsInternal,
+    pub if_version: SeqNumberType,
+}
+
+impl SplitByShard for VersionedInsertOperationInternal {
+    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+        let Self {
+            points_op,
+            if_version,
+        } = self;
+        points_op
+            .split_by_shard(ring)
+            .map(|points_op| VersionedInsertOperationInternal {
+                points_op,
+                if_version,
+            })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum PointOperations {
     /// Insert or update points
     UpsertPoints(PointInsertOperationsInternal),
+    /// Insert points, or update existing points which match the condition
+    UpsertPointsConditional(ConditionalInsertOperationInternal),
+    /// Insert or update points, if none of them was modified after the given version
+    UpsertPointsIfVersion(VersionedInsertOperationInternal),
     /// Delete point if exists
     DeletePoints { ids: Vec<PointIdType> },
     /// Delete points by given filter criteria
@@ -322,6 +492,8 @@ impl PointOperations {
     pub fn is_write_operation(&self) -> bool {
         match self {
             PointOperations::UpsertPoints(_) => true,
+            PointOperations::UpsertPointsConditional(_) => true,
+            PointOperations::UpsertPointsIfVersion(_) => true,
             PointOperations::DeletePoints { .. } => false,
             PointOperations::DeletePointsByFilter(_) => false,
             PointOperations::SyncPoints(_) => true,
@@ -333,6 +505,8 @@ impl Validate for PointOperations {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
             PointOperations::UpsertPoints(upsert_points) => upsert_points.validate(),
+            PointOperations::UpsertPointsConditional(operation) => operation.points_op.validate(),
+            PointOperations::UpsertPointsIfVersion(operation) => operation.points_op.validate(),
             PointOperations::DeletePoints { ids: _ } => Ok(()),
             PointOperations::DeletePointsByFilter(_) => Ok(()),
             PointOperations::SyncPoints(_) => Ok(()),
@@ -458,6 +632,12 @@ impl SplitByShard for PointOperations {
             PointOperations::UpsertPoints(upsert_points) => upsert_points
                 .split_by_shard(ring)
                 .map(PointOperations::UpsertPoints),
+            PointOperations::UpsertPointsConditional(operation) => operation
+                .split_by_shard(ring)
+                .map(PointOperations::UpsertPointsConditional),
+            PointOperations::UpsertPointsIfVersion(operation) => operation
+                .split_by_shard(ring)
+                .map(PointOperations::UpsertPointsIfVersion),
             PointOperations::DeletePoints { ids } => split_iter_by_shard(ids, |id| *id, ring)
                 .map(|ids| PointOperations::DeletePoints { ids }),
             by_filter @ PointOperations::DeletePointsByFilter(_) => {
@@ -504,6 +684,8 @@ impl PointStruct {
 
 #[cfg(test)]
 mod tests {
+    use segment::data_types::vectors::VectorRef;
+
     use super::*;
 
     #[test]
@@ -532,4 +714,25 @@ mod tests {
         .into();
         assert!(batch.validate().is_err());
     }
+
+    #[test]
+    fn batch_into_point_vec() {
+        let batch: PointInsertOperationsInternal = Batch {
+            ids: vec![PointIdType::NumId(0), PointIdType::NumId(1)],
+            vectors: vec![vec![0.1], vec![0.2]].into(),
+            payloads: Some(vec![None, Some(Payload::default())]),
+        }
+        .into();
+
+        let points = batch.into_point_vec();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].id, PointIdType::NumId(0));
+        assert_eq!(points[1].id, PointIdType::NumId(1));
+        assert!(points[0].payload.is_none());
+        assert!(points[1].payload.is_some());
+        assert_eq!(
+            points[1].get_vectors().get(DEFAULT_VECTOR_NAME),
+            Some(VectorRef::Dense(&[0.2]))
+        );
+    }
 }
@@ -0,0 +1,162 @@
+use segment::types::{Condition, FieldCondition, Filter, Payload, Range};
+use serde_json::Value;
+
+use crate::operations::payload_ops::PayloadOps;
+use crate::operations::point_ops::{
+    ConditionalInsertOperationInternal, PointInsertOperationsInternal, PointOperations,
+    VersionedInsertOperationInternal,
+};
+use crate::operations::CollectionUpdateOperations;
+
+/// Payload key with the time of point creation, milliseconds since unix epoch
+pub const CREATED_AT_KEY: &str = "__created_at";
+
+/// Payload key with the time of the last point update, milliseconds since unix epoch
+pub const UPDATED_AT_KEY: &str = "__updated_at";
+
+/// Payload key with the expiration time of a point, milliseconds since unix epoch
+pub const EXPIRE_AT_KEY: &str = "__expire_at";
+
+/// Current time in milliseconds since unix epoch
+pub fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Filter, selecting points, which are expired by the given time
+pub fn expired_points_filter(timestamp: u64) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_range(
+        EXPIRE_AT_KEY,
+        Range {
+            lte: Some(timestamp as f64),
+            ..Default::default()
+        },
+    )))
+}
+
+fn stamp_payload(payload: &mut Payload, timestamp: u64, created: bool) {
+    if created {
+        payload
+            .0
+            .insert(CREATED_AT_KEY.to_string(), Value::from(timestamp));
+    }
+    payload
+        .0
+        .insert(UPDATED_AT_KEY.to_string(), Value::from(timestamp));
+}
+
+fn stamped(payload: Option<Payload>, timestamp: u64) -> Option<Payload> {
+    let mut payload = payload.unwrap_or_default();
+    stamp_payload(&mut payload, timestamp, true);
+    Some(payload)
+}
+
+/// Records the timestamp into the payload of points, affected by the operation.
+///
+/// Upserted points get both creation and update time. Creation time of already existing points
+/// is preserved by the segment updater.
+/// Payload updates only get the update time, updates of vectors alone and payload updates at
+/// a nested key are not tracked.
+///
+/// Operation is stamped once, before it is distributed to the replicas,
+/// so all replicas store the same values.
+pub fn stamp_operation(operation: &mut CollectionUpdateOperations, timestamp: u64) {
+    match operation {
+        CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(points)
+            | PointOperations::UpsertPointsConditional(ConditionalInsertOperationInternal {
+                points_op: points,
+                ..
+            })
+            | PointOperations::UpsertPointsIfVersion(VersionedInsertOperationInternal {
+                points_op: points,
+                ..
+            }),
+        ) => match points {
+            PointInsertOperationsInternal::PointsBatch(batch) => {
+                let payloads = batch
+                    .payloads
+                    .get_or_insert_with(|| vec![None; batch.ids.len()]);
+                for payload in payloads.iter_mut() {
+                    *payload = stamped(payload.take(), timestamp);
+                }
+            }
+            PointInsertOperationsInternal::PointsList(points) => {
+                for point in points.iter_mut() {
+                    point.payload = stamped(point.payload.take(), timestamp);
+                }
+            }
+        },
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(op))
+            if op.key.is_none() =>
+        {
+            stamp_payload(&mut op.payload, timestamp, false);
+        }
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::OverwritePayload(op))
+            if op.key.is_none() =>
+        {
+            // Creation time is restored from the stored point
+            stamp_payload(&mut op.payload, timestamp, true);
+        }
+        CollectionUpdateOperations::BatchOperation(operations) => {
+            for operation in operations {
+                stamp_operation(operation, timestamp);
+            }
+        }
+        CollectionUpdateOperations::PointOperation(_)
+        | CollectionUpdateOperations::VectorOperation(_)
+        | CollectionUpdateOperations::PayloadOperation(_)
+        | CollectionUpdateOperations::FieldIndexOperation(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::ExtendedPointId;
+    use serde_json::json;
+
+    use super::*;
+    use crate::operations::payload_ops::SetPayloadOp;
+    use crate::operations::point_ops::PointStruct;
+
+    #[test]
+    fn test_stamp_operation() {
+        let point: PointStruct = serde_json::from_value(json!({
+            "id": 1,
+            "vector": [1.0, 2.0],
+            "payload": {"city": "Berlin"}
+        }))
+        .unwrap();
+
+        let mut operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(PointInsertOperationsInternal::PointsList(vec![point])),
+        );
+        stamp_operation(&mut operation, 42);
+
+        let CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperationsInternal::PointsList(points),
+        )) = operation
+        else {
+            panic!("unexpected operation");
+        };
+        let payload = points[0].payload.as_ref().unwrap();
+        assert_eq!(payload.0["city"], json!("Berlin"));
+        assert_eq!(payload.0[CREATED_AT_KEY], json!(42));
+        assert_eq!(payload.0[UPDATED_AT_KEY], json!(42));
+
+        let mut operation =
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayloadOp {
+                payload: Payload::default(),
+                points: Some(vec![ExtendedPointId::NumId(1)]),
+                filter: None,
+                key: None,
+            }));
+        stamp_operation(&mut operation, 43);
+
+        let CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(op)) = operation
+        else {
+            panic!("unexpected operation");
+        };
+        assert!(!op.payload.contains_key(CREATED_AT_KEY));
+        assert_eq!(op.payload.0[UPDATED_AT_KEY], json!(43));
+    }
+}
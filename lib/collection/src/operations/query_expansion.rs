@@ -0,0 +1,272 @@
+use itertools::Itertools;
+use segment::types::{Condition, FieldCondition, Filter, Match, MatchText};
+
+use crate::config::QueryExpansionConfig;
+use crate::grouping::group_by::{GroupRequest, SourceRequest};
+use crate::operations::payload_ops::PayloadOps;
+use crate::operations::point_ops::{CopyPoints, PointOperations};
+use crate::operations::shard_selector_internal::ShardSelectorInternal;
+use crate::operations::types::{
+    CoreSearchRequestBatch, CountRequestInternal, DiscoverRequestInternal, FacetRequestInternal,
+    RecommendRequestInternal, ScrollRequestInternal, SearchMatrixRequestInternal,
+};
+use crate::operations::vector_ops::VectorOperations;
+use crate::operations::CollectionUpdateOperations;
+
+/// Request with filters, which may contain full-text conditions to expand
+pub trait ExpandableRequest {
+    fn filters_mut(&mut self) -> Vec<&mut Filter>;
+}
+
+impl QueryExpansionConfig {
+    fn is_stop_word(&self, word: &str) -> bool {
+        self.stop_words
+            .iter()
+            .any(|stop_word| stop_word.to_lowercase() == word)
+    }
+
+    fn synonyms(&self, word: &str) -> &[String] {
+        self.synonyms
+            .iter()
+            .find(|(key, _)| key.to_lowercase() == word)
+            .map(|(_, synonyms)| synonyms.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Rewrites full-text conditions with `expand` enabled into conditions on separate words
+    /// of the query, each of which matches the word or any of its synonyms.
+    pub fn expand_filter(&self, filter: &mut Filter) {
+        let conditions = [&mut filter.must, &mut filter.should, &mut filter.must_not]
+            .into_iter()
+            .flatten()
+            .flatten();
+
+        for condition in conditions {
+            match condition {
+                Condition::Field(field) => {
+                    if let Some(expanded) = self.expand_condition(field) {
+                        *condition = Condition::Filter(expanded);
+                    }
+                }
+                Condition::Filter(filter) => self.expand_filter(filter),
+                Condition::Nested(nested) => self.expand_filter(&mut nested.nested.filter),
+                Condition::IsEmpty(_) | Condition::IsNull(_) | Condition::HasId(_) => {}
+            }
+        }
+    }
+
+    /// Expanded filter of the full-text condition, or `None` if it should be kept as is.
+    ///
+    /// Phrases are never expanded, and neither are queries, which only consist of stop words.
+    fn expand_condition(&self, field: &FieldCondition) -> Option<Filter> {
+        let Some(Match::Text(text_match)) = &field.r#match else {
+            return None;
+        };
+        if !text_match.expand.unwrap_or(false) || text_match.phrase().is_some() {
+            return None;
+        }
+
+        let match_text = |text: &str| {
+            Condition::Field(FieldCondition {
+                r#match: Some(Match::Text(MatchText::from(text.to_string()))),
+                ..field.clone()
+            })
+        };
+
+        let words = text_match
+            .text
+            .split_whitespace()
+            .filter_map(|word| {
+                let normalized = word
+                    .trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase();
+                if normalized.is_empty() || self.is_stop_word(&normalized) {
+                    return None;
+                }
+
+                let synonyms = self.synonyms(&normalized);
+                if synonyms.is_empty() {
+                    return Some(match_text(word));
+                }
+
+                let alternatives = std::iter::once(word)
+                    .chain(synonyms.iter().map(String::as_str))
+                    .map(match_text)
+                    .collect_vec();
+                Some(Condition::Filter(Filter {
+                    should: Some(alternatives),
+                    must: None,
+                    must_not: None,
+                }))
+            })
+            .collect_vec();
+
+        if words.is_empty() {
+            return None;
+        }
+
+        Some(Filter {
+            should: None,
+            must: Some(words),
+            must_not: None,
+        })
+    }
+}
+
+impl ExpandableRequest for CoreSearchRequestBatch {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.searches
+            .iter_mut()
+            .filter_map(|search| search.filter.as_mut())
+            .collect()
+    }
+}
+
+impl ExpandableRequest for RecommendRequestInternal {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.filter.iter_mut().collect()
+    }
+}
+
+impl ExpandableRequest for DiscoverRequestInternal {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.filter.iter_mut().collect()
+    }
+}
+
+impl<R: ExpandableRequest> ExpandableRequest for Vec<(R, ShardSelectorInternal)> {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.iter_mut().flat_map(|(r, _)| r.filters_mut()).collect()
+    }
+}
+
+impl ExpandableRequest for ScrollRequestInternal {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.filter.iter_mut().collect()
+    }
+}
+
+impl ExpandableRequest for CountRequestInternal {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.filter.iter_mut().collect()
+    }
+}
+
+impl ExpandableRequest for FacetRequestInternal {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.filter.iter_mut().collect()
+    }
+}
+
+impl ExpandableRequest for SearchMatrixRequestInternal {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.filter.iter_mut().collect()
+    }
+}
+
+impl ExpandableRequest for GroupRequest {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        match &mut self.source {
+            SourceRequest::Search(request) => request.filter.iter_mut().collect(),
+            SourceRequest::Recommend(request) => request.filters_mut(),
+        }
+    }
+}
+
+impl ExpandableRequest for CopyPoints {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        self.filter.iter_mut().collect()
+    }
+}
+
+impl ExpandableRequest for CollectionUpdateOperations {
+    fn filters_mut(&mut self) -> Vec<&mut Filter> {
+        match self {
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(
+                filter,
+            ))
+            | CollectionUpdateOperations::VectorOperation(
+                VectorOperations::DeleteVectorsByFilter(filter, _),
+            )
+            | CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayloadByFilter(
+                filter,
+            )) => vec![filter],
+            CollectionUpdateOperations::PayloadOperation(
+                PayloadOps::SetPayload(op) | PayloadOps::OverwritePayload(op),
+            ) => op.filter.iter_mut().collect(),
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(op)) => {
+                op.filter.iter_mut().collect()
+            }
+            CollectionUpdateOperations::PointOperation(
+                PointOperations::UpsertPointsConditional(op),
+            ) => vec![&mut op.condition],
+            CollectionUpdateOperations::BatchOperation(operations) => operations
+                .iter_mut()
+                .flat_map(|operation| operation.filters_mut())
+                .collect(),
+            CollectionUpdateOperations::PointOperation(_)
+            | CollectionUpdateOperations::VectorOperation(_)
+            | CollectionUpdateOperations::PayloadOperation(_)
+            | CollectionUpdateOperations::FieldIndexOperation(_) => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn text_condition(text: &str, expand: Option<bool>) -> Condition {
+        Condition::Field(FieldCondition::new_match(
+            "title",
+            Match::Text(MatchText {
+                text: text.to_string(),
+                expand,
+            }),
+        ))
+    }
+
+    #[test]
+    fn test_expand_filter() {
+        let config = QueryExpansionConfig {
+            synonyms: HashMap::from([(
+                "TV".to_string(),
+                vec!["television".to_string(), "flat screen".to_string()],
+            )]),
+            stop_words: vec!["the".to_string()],
+        };
+
+        let mut filter = Filter::new_must(text_condition("The cheap tv", Some(true)));
+        config.expand_filter(&mut filter);
+        let expected = Filter::new_must(Condition::Filter(Filter {
+            should: None,
+            must: Some(vec![
+                text_condition("cheap", None),
+                Condition::Filter(Filter {
+                    should: Some(vec![
+                        text_condition("tv", None),
+                        text_condition("television", None),
+                        text_condition("flat screen", None),
+                    ]),
+                    must: None,
+                    must_not: None,
+                }),
+            ]),
+            must_not: None,
+        }));
+        assert_eq!(filter, expected);
+
+        // Not expanded without the flag, for phrases and for queries of only stop words
+        for condition in [
+            text_condition("cheap tv", None),
+            text_condition("\"cheap tv\"", Some(true)),
+            text_condition("the", Some(true)),
+        ] {
+            let mut filter = Filter::new_must_not(condition.clone());
+            config.expand_filter(&mut filter);
+            assert_eq!(filter, Filter::new_must_not(condition));
+        }
+    }
+}
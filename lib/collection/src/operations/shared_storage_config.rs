@@ -1,6 +1,8 @@
 use std::num::NonZeroUsize;
 use std::time::Duration;
 
+use crate::common::maintenance_hooks::MaintenanceHook;
+use crate::common::snapshots_manager::SnapshotsConfig;
 use crate::operations::types::NodeType;
 
 /// Default timeout for search requests.
@@ -21,6 +23,15 @@ pub struct SharedStorageConfig {
     pub search_timeout: Duration,
     pub update_concurrency: Option<NonZeroUsize>,
     pub is_distributed: bool,
+    /// If set, small upserts arriving within this window are coalesced into a single operation
+    pub update_batch_window: Option<Duration>,
+    /// If set, checksums of on-disk vectors are verified in background with this interval
+    pub scrub_interval: Option<Duration>,
+    /// If set, shard replicas with corrupted segments are disabled and recovered from other replicas
+    pub scrub_quarantine: bool,
+    pub snapshots_config: SnapshotsConfig,
+    /// Commands to run before and after optimizations, snapshots and shard transfers
+    pub maintenance_hooks: Vec<MaintenanceHook>,
 }
 
 impl Default for SharedStorageConfig {
@@ -33,6 +44,11 @@ impl Default for SharedStorageConfig {
             search_timeout: DEFAULT_SEARCH_TIMEOUT,
             update_concurrency: None,
             is_distributed: false,
+            update_batch_window: None,
+            scrub_interval: None,
+            scrub_quarantine: false,
+            snapshots_config: Default::default(),
+            maintenance_hooks: vec![],
         }
     }
 }
@@ -46,6 +62,11 @@ impl SharedStorageConfig {
         search_timeout: Option<Duration>,
         update_concurrency: Option<NonZeroUsize>,
         is_distributed: bool,
+        update_batch_window: Option<Duration>,
+        scrub_interval: Option<Duration>,
+        scrub_quarantine: bool,
+        snapshots_config: SnapshotsConfig,
+        maintenance_hooks: Vec<MaintenanceHook>,
     ) -> Self {
         let update_queue_size = update_queue_size.unwrap_or(match node_type {
             NodeType::Normal => DEFAULT_UPDATE_QUEUE_SIZE,
@@ -59,6 +80,11 @@ impl SharedStorageConfig {
             search_timeout: search_timeout.unwrap_or(DEFAULT_SEARCH_TIMEOUT),
             update_concurrency,
             is_distributed,
+            update_batch_window: update_batch_window.filter(|window| !window.is_zero()),
+            scrub_interval: scrub_interval.filter(|interval| !interval.is_zero()),
+            scrub_quarantine,
+            snapshots_config,
+            maintenance_hooks,
         }
     }
 }
@@ -70,6 +70,25 @@ pub struct SnapshotRecover {
     /// If set to `Replica`, the current state will be used as a source of truth, and after recovery if will be synchronized with the snapshot.
     #[serde(default)]
     pub priority: Option<SnapshotPriority>,
+
+    /// SHA256 of the snapshot file, the snapshot is verified against it before recovery.
+    /// If not set, the checksum stored alongside the snapshot in `<location>.checksum` is used, if it exists.
+    #[serde(default)]
+    pub checksum: Option<String>,
+
+    /// Verify checksum of the snapshot before recovery. Default: true
+    #[serde(default)]
+    pub verify_checksum: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct SnapshotViewRequest {
+    /// Name of the new read-only collection, to open the snapshot as
+    #[validate(
+        length(min = 1, max = 255),
+        custom = "common::validation::validate_collection_name"
+    )]
+    pub view_name: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
@@ -77,6 +96,9 @@ pub struct SnapshotDescription {
     pub name: String,
     pub creation_time: Option<NaiveDateTime>,
     pub size: u64,
+    /// SHA256 of the snapshot file, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
 }
 
 impl From<SnapshotDescription> for api::grpc::qdrant::SnapshotDescription {
@@ -85,6 +107,7 @@ impl From<SnapshotDescription> for api::grpc::qdrant::SnapshotDescription {
             name: value.name,
             creation_time: value.creation_time.map(date_time_to_proto),
             size: value.size as i64,
+            checksum: value.checksum,
         }
     }
 }
@@ -101,13 +124,31 @@ pub async fn get_snapshot_description(path: &Path) -> CollectionResult<SnapshotD
             })
     });
     let size = file_meta.len();
+    let checksum = read_checksum(&get_checksum_path(path)).await?;
     Ok(SnapshotDescription {
         name: name.to_string(),
         creation_time,
         size,
+        checksum,
     })
 }
 
+/// Path of the file with SHA256 of the snapshot: `<snapshot>.checksum`
+pub fn get_checksum_path(snapshot_path: &Path) -> PathBuf {
+    let mut checksum_path = snapshot_path.as_os_str().to_owned();
+    checksum_path.push(".checksum");
+    checksum_path.into()
+}
+
+/// Read the checksum file, if it exists
+pub async fn read_checksum(checksum_path: &Path) -> CollectionResult<Option<String>> {
+    match tokio::fs::read_to_string(checksum_path).await {
+        Ok(checksum) => Ok(Some(checksum.trim().to_string())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
 pub async fn list_snapshots_in_directory(
     directory: &Path,
 ) -> CollectionResult<Vec<SnapshotDescription>> {
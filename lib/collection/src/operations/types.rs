@@ -15,13 +15,15 @@ use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use segment::common::operation_error::OperationError;
 use segment::data_types::groups::GroupId;
+use segment::data_types::order_by::{OrderByInterface, OrderValue};
 use segment::data_types::vectors::{
     Named, NamedQuery, NamedVectorStruct, QueryVector, Vector, VectorElementType, VectorRef,
     VectorStruct, VectorType, DEFAULT_VECTOR_NAME,
 };
 use segment::types::{
-    Distance, Filter, Payload, PayloadIndexInfo, PayloadKeyType, PointIdType, QuantizationConfig,
-    ScoredPoint, SearchParams, SeqNumberType, ShardKey, WithPayloadInterface, WithVector,
+    Condition, Distance, Filter, Payload, PayloadFieldStatistics, PayloadIndexInfo,
+    PayloadKeyType, PointIdType, QuantizationConfig, ScoredPoint, SearchParams, SeqNumberType,
+    ShardKey, WithPayloadInterface, WithVector,
 };
 use segment::vector_storage::query::context_query::ContextQuery;
 use segment::vector_storage::query::discovery_query::DiscoveryQuery;
@@ -35,12 +37,14 @@ use tokio::sync::mpsc::error::SendError;
 use tokio::sync::oneshot::error::RecvError as OneshotRecvError;
 use tokio::task::JoinError;
 use tonic::codegen::http::uri::InvalidUri;
+use url::Url;
 use validator::{Validate, ValidationError, ValidationErrors};
 
 use super::config_diff::{self};
 use crate::config::{CollectionConfig, CollectionParams};
 use crate::lookup::types::WithLookupInterface;
 use crate::operations::config_diff::{HnswConfigDiff, QuantizationConfigDiff};
+use crate::operations::highlight::Highlights;
 use crate::operations::shard_key_selector::ShardKeySelector;
 use crate::save_on_disk;
 use crate::shards::replica_set::ReplicaState;
@@ -90,6 +94,13 @@ pub struct Record {
     /// Shard Key
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shard_key: Option<ShardKey>,
+    /// Value of the `order_by` field, if the point was retrieved with ordering
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_value: Option<OrderValue>,
+    /// Version of the point, increases with every modification of the point.
+    /// Can be used as `if_version` of an update to prevent overwriting concurrent changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<SeqNumberType>,
 }
 
 /// Current statistics and configuration of the collection
@@ -218,6 +229,10 @@ pub struct LocalShardInfo {
     pub points_count: usize,
     /// Is replica active
     pub state: ReplicaState,
+    /// Checksum of all points in the shard, equal for replicas with the same data.
+    /// Only computed on request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -234,9 +249,247 @@ pub struct RemoteShardInfo {
     pub state: ReplicaState,
 }
 
+/// Distribution of points between shards of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct CollectionShardsDistribution {
+    /// One hash ring per shard key, or a single ring if custom sharding is not used
+    pub rings: Vec<HashRingInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct HashRingInfo {
+    /// User-defined sharding key of the ring
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKey>,
+    /// Shards of the ring
+    pub shards: Vec<ShardDistributionInfo>,
+    /// Relative deviation of the largest shard from the average number of points.
+    /// `0` means points are perfectly balanced between shards.
+    pub imbalance: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardDistributionInfo {
+    /// Shard id
+    pub shard_id: ShardId,
+    /// Estimated share of point ids, which the hash ring assigns to this shard
+    pub ring_share: f64,
+    /// Number of points in the shard, if any of its replicas could be reached
+    pub points_count: Option<usize>,
+}
+
+/// Statistics of indexed payload fields of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionStatistics {
+    /// Number of shards with a replica on the requested peer. Statistics only cover these shards.
+    pub local_shards_count: usize,
+    /// Statistics of values of each indexed payload field
+    pub payload_fields: HashMap<PayloadKeyType, PayloadFieldStatistics>,
+}
+
+/// Parameters and usage of the named vectors of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionVectorsInfo {
+    /// Number of shards with a replica on the requested peer. Counts and sizes only cover these shards.
+    pub local_shards_count: usize,
+    /// Info of each vector of the collection. Default vector has an empty name.
+    pub vectors: BTreeMap<String, VectorInfo>,
+}
+
+/// Parameters and usage of a single vector of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct VectorInfo {
+    /// Parameters of the dense vector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<VectorParams>,
+    /// Parameters of the sparse vector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_params: Option<SparseVectorParams>,
+    /// Whether vectors of all points are in the vector index
+    pub index_status: VectorIndexStatus,
+    #[serde(flatten)]
+    pub usage: VectorUsage,
+}
+
+/// `indexed` - vectors of all points are in the vector index.
+/// `partially_indexed` - only part of the vectors is in the vector index, optimization is pending.
+/// `not_indexed` - no vectors are in the vector index, searches use full scan.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorIndexStatus {
+    Indexed,
+    PartiallyIndexed,
+    NotIndexed,
+}
+
+impl From<&VectorUsage> for VectorIndexStatus {
+    fn from(usage: &VectorUsage) -> Self {
+        if usage.indexed_vectors_count >= usage.points_count {
+            VectorIndexStatus::Indexed
+        } else if usage.indexed_vectors_count > 0 {
+            VectorIndexStatus::PartiallyIndexed
+        } else {
+            VectorIndexStatus::NotIndexed
+        }
+    }
+}
+
+/// Usage of a single vector
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct VectorUsage {
+    /// Approximate number of points, which have this vector
+    pub points_count: usize,
+    /// Approximate number of vectors in the vector index
+    pub indexed_vectors_count: usize,
+    /// Size of files with vectors, vector index and quantized vectors
+    pub storage_size_bytes: usize,
+    /// Share of values of recently inserted vectors, which are outside of the value range
+    /// quantization of optimized segments was trained on. About 0.02 if data does not drift.
+    /// Quantization is retrained on the next optimization of a segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization_drift: Option<f32>,
+}
+
+impl VectorUsage {
+    pub fn merge(&mut self, other: Self) {
+        self.points_count += other.points_count;
+        self.indexed_vectors_count += other.indexed_vectors_count;
+        self.storage_size_bytes += other.storage_size_bytes;
+        self.add_quantization_drift(other.quantization_drift);
+    }
+
+    /// Keep the largest drift, observed in any segment
+    pub fn add_quantization_drift(&mut self, drift: Option<f32>) {
+        self.quantization_drift = match (self.quantization_drift, drift) {
+            (Some(current), Some(drift)) => Some(current.max(drift)),
+            (current, drift) => current.or(drift),
+        };
+    }
+}
+
+/// Estimate the search quality with a quantization config on a sample of vectors, without
+/// changing the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct QuantizationEstimateRequest {
+    /// Quantization config to estimate
+    #[validate]
+    pub quantization_config: QuantizationConfig,
+    /// Vector to estimate quantization for. If missing, the default vector is used.
+    #[serde(default)]
+    pub using: Option<String>,
+    /// How many random points to sample and quantize. Default: 1000
+    #[serde(default)]
+    #[validate(range(min = 2))]
+    pub sample: Option<usize>,
+    /// How many of the sampled points to use as queries. Default: 100
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub queries: Option<usize>,
+    /// How many nearest neighbours to compare for each query. Default: 10
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub limit: Option<usize>,
+}
+
+pub const DEFAULT_QUANTIZATION_ESTIMATE_SAMPLE: usize = 1000;
+pub const DEFAULT_QUANTIZATION_ESTIMATE_QUERIES: usize = 100;
+pub const DEFAULT_QUANTIZATION_ESTIMATE_LIMIT: usize = 10;
+
+/// Search quality with a quantization config, compared to search over original vectors
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct QuantizationEstimate {
+    /// Number of sampled vectors, which were quantized
+    pub sampled_count: usize,
+    /// Number of queries, each searching the nearest neighbours among the sampled vectors
+    pub queries_count: usize,
+    /// Share of the exact nearest neighbours, found with quantized vectors, averaged over queries
+    pub recall_mean: f64,
+    /// Lowest recall among the queries
+    pub recall_min: f64,
+    /// Size of an original vector in bytes
+    pub vector_size_bytes: usize,
+    /// Size of a quantized vector in bytes
+    pub quantized_vector_size_bytes: usize,
+}
+
+/// Default number of points, read from a shard at once during export
+pub const DEFAULT_EXPORT_BATCH_SIZE: usize = 1000;
+
+/// `jsonl` - one point per line, in the format of the upsert points API.
+/// `parquet` - `id` and `payload` columns and a column per dense vector, as in points import.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Jsonl,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// Export points of the collection into files, one file per shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ExportPointsRequest {
+    /// Directory to write the files into. Examples:
+    /// - Local directory `file:///qdrant/export/`
+    /// - S3 prefix `s3://my-bucket/export/`, with credentials from `AWS_*` environment variables
+    pub location: Url,
+    /// Format of the files. Default: jsonl
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Only export points, which satisfy these conditions
+    #[serde(default)]
+    #[validate]
+    pub filter: Option<Filter>,
+    /// Select which payload to export. Default: all
+    #[serde(default)]
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Select which vectors to export. Default: all. Parquet files only contain dense vectors.
+    #[serde(default)]
+    pub with_vector: Option<WithVector>,
+    /// Number of points to read from a shard at once. Default: 1000
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ExportedFile {
+    /// URL of the file
+    pub location: String,
+    /// Number of points in the file
+    pub points_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ExportPointsResult {
+    /// Number of exported points
+    pub points_count: usize,
+    /// Written files, one per shard
+    pub files: Vec<ExportedFile>,
+}
+
 /// `Acknowledged` - Request is saved to WAL and will be process in a queue.
 /// `Completed` - Request is completed, changes are actual.
-#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum UpdateStatus {
     Acknowledged,
@@ -251,6 +504,31 @@ pub struct UpdateResult {
     pub operation_id: Option<SeqNumberType>,
     /// Update status
     pub status: UpdateStatus,
+    /// Status of each point of the upsert, in the order of the request.
+    /// Only present if requested with `point_status=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<Vec<PointUpsertResult>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PointUpsertStatus {
+    /// Point did not exist and was created
+    Created,
+    /// Point already existed and was overwritten
+    Updated,
+    /// Point was not applied, see `reason`
+    Rejected,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct PointUpsertResult {
+    pub id: PointIdType,
+    pub status: PointUpsertStatus,
+    /// Why the point was rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -265,6 +543,9 @@ pub struct ScrollRequest {
 }
 
 /// Scroll request - paginate over all points which matches given condition
+///
+/// Unless `order_by` or `query` is used, points are returned in strictly ascending order of their
+/// IDs across all shards and segments, so every page continues right after the previous one.
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct ScrollRequestInternal {
@@ -281,6 +562,11 @@ pub struct ScrollRequestInternal {
     /// Whether to return the point vector with the result?
     #[serde(default, alias = "with_vectors")]
     pub with_vector: WithVector,
+    /// Order the records by a payload field. Requires a range index on the field.
+    pub order_by: Option<OrderByInterface>,
+    /// Select points with a query instead of paginating over them, e.g. `{"sample": "random"}`.
+    /// Can't be combined with `offset` or `order_by`.
+    pub query: Option<ScrollQuery>,
 }
 
 impl Default for ScrollRequestInternal {
@@ -291,10 +577,27 @@ impl Default for ScrollRequestInternal {
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: WithVector::Bool(false),
+            order_by: None,
+            query: None,
         }
     }
 }
 
+/// Alternative way of selecting points in a scroll request
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollQuery {
+    /// Return a sample of the points, which satisfy the filter
+    Sample(Sample),
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Sample {
+    /// Uniformly distributed random sample
+    Random,
+}
+
 /// Result of the points read request
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -314,6 +617,116 @@ pub struct SearchRequest {
     /// Specify in which shards to look for the points, if not specified - look in all shards
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shard_key: Option<ShardKeySelector>,
+    /// Select which fields of found points to return, e.g. `["id", "score"]` for the smallest
+    /// response. If not specified - return all fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<ScoredPointField>>,
+    /// Return the words of text payload fields, which are matched by full-text conditions of the
+    /// filter. Only fields of the returned payload are highlighted. Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_highlights: Option<bool>,
+}
+
+/// Field of a found point
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoredPointField {
+    Id,
+    Version,
+    Score,
+    Payload,
+    Vector,
+    ShardKey,
+}
+
+impl ScoredPointField {
+    pub const ALL: &'static [Self] = &[
+        Self::Id,
+        Self::Version,
+        Self::Score,
+        Self::Payload,
+        Self::Vector,
+        Self::ShardKey,
+    ];
+}
+
+/// Search result with only the selected fields of the point
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct ScoredPointProjection {
+    /// Point id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<PointIdType>,
+    /// Point version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<SeqNumberType>,
+    /// Points vector distance to the query vector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<ScoreType>,
+    /// Payload - values assigned to the point
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Payload>,
+    /// Vector of the point
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<VectorStruct>,
+    /// Shard Key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKey>,
+    /// Words of text payload fields, matched by full-text conditions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<Highlights>,
+}
+
+impl ScoredPointProjection {
+    pub fn new(point: ScoredPoint, fields: &[ScoredPointField]) -> Self {
+        let ScoredPoint {
+            id,
+            version,
+            score,
+            payload,
+            vector,
+            shard_key,
+        } = point;
+        let selected = |field| fields.contains(&field);
+        Self {
+            id: selected(ScoredPointField::Id).then_some(id),
+            version: selected(ScoredPointField::Version).then_some(version),
+            score: selected(ScoredPointField::Score).then_some(score),
+            payload: payload.filter(|_| selected(ScoredPointField::Payload)),
+            vector: vector.filter(|_| selected(ScoredPointField::Vector)),
+            shard_key: shard_key.filter(|_| selected(ScoredPointField::ShardKey)),
+            highlights: None,
+        }
+    }
+}
+
+/// Search result, either complete or with the selected fields only
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(untagged)]
+pub enum SearchResultPoint {
+    Full(ScoredPoint),
+    Projection(ScoredPointProjection),
+}
+
+/// Keep only `fields` of the found points, if specified, and attach `highlights` of each point
+pub fn project_scored_points(
+    points: Vec<ScoredPoint>,
+    fields: Option<&[ScoredPointField]>,
+    highlights: Option<Vec<Highlights>>,
+) -> Vec<SearchResultPoint> {
+    if fields.is_none() && highlights.is_none() {
+        return points.into_iter().map(SearchResultPoint::Full).collect();
+    }
+
+    let fields = fields.unwrap_or(ScoredPointField::ALL);
+    let mut highlights = highlights.map(Vec::into_iter);
+    points
+        .into_iter()
+        .map(|point| {
+            let mut projection = ScoredPointProjection::new(point, fields);
+            projection.highlights = highlights.as_mut().and_then(Iterator::next);
+            SearchResultPoint::Projection(projection)
+        })
+        .collect()
 }
 
 /// Search request.
@@ -595,6 +1008,17 @@ pub struct RecommendRequestInternal {
     #[validate]
     pub negative: Vec<RecommendExample>,
 
+    /// Weights of the positive examples, in the same order as `positive`.
+    /// An example with weight 3.0 matters 3 times more than an example with weight 1.0.
+    /// If not specified - all positive examples have weight 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub positive_weights: Option<Vec<ScoreType>>,
+
+    /// Weights of the negative examples, in the same order as `negative`.
+    /// If not specified - all negative examples have weight 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negative_weights: Option<Vec<ScoreType>>,
+
     /// How to use positive and negative examples to find the results
     pub strategy: Option<RecommendStrategy>,
 
@@ -644,6 +1068,36 @@ pub struct RecommendRequestInternal {
 pub struct RecommendRequestBatch {
     #[validate]
     pub searches: Vec<RecommendRequest>,
+    /// Look only for points which satisfies this conditions, applied to every search of the batch
+    /// in addition to its own filter
+    #[validate]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Filter>,
+    /// Search params of searches of the batch, which don't specify their own
+    #[validate]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<SearchParams>,
+}
+
+impl RecommendRequestBatch {
+    /// Searches of the batch with shared filter and params applied
+    pub fn into_searches(self) -> Vec<RecommendRequest> {
+        let Self {
+            mut searches,
+            filter,
+            params,
+        } = self;
+        for search in &mut searches {
+            let request = &mut search.recommend_request;
+            apply_batch_filter_and_params(
+                &mut request.filter,
+                &mut request.params,
+                filter.as_ref(),
+                params,
+            );
+        }
+        searches
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -667,6 +1121,17 @@ pub struct RecommendGroupsRequestInternal {
     #[serde(default)]
     pub negative: Vec<RecommendExample>,
 
+    /// Weights of the positive examples, in the same order as `positive`.
+    /// An example with weight 3.0 matters 3 times more than an example with weight 1.0.
+    /// If not specified - all positive examples have weight 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub positive_weights: Option<Vec<ScoreType>>,
+
+    /// Weights of the negative examples, in the same order as `negative`.
+    /// If not specified - all negative examples have weight 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negative_weights: Option<Vec<ScoreType>>,
+
     /// How to use positive and negative examples to find the results
     #[serde(default)]
     pub strategy: Option<RecommendStrategy>,
@@ -794,6 +1259,63 @@ pub struct DiscoverRequestInternal {
 pub struct DiscoverRequestBatch {
     #[validate]
     pub searches: Vec<DiscoverRequest>,
+    /// Look only for points which satisfies this conditions, applied to every search of the batch
+    /// in addition to its own filter
+    #[validate]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Filter>,
+    /// Search params of searches of the batch, which don't specify their own
+    #[validate]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<SearchParams>,
+}
+
+impl DiscoverRequestBatch {
+    /// Searches of the batch with shared filter and params applied
+    pub fn into_searches(self) -> Vec<DiscoverRequest> {
+        let Self {
+            mut searches,
+            filter,
+            params,
+        } = self;
+        for search in &mut searches {
+            let request = &mut search.discover_request;
+            apply_batch_filter_and_params(
+                &mut request.filter,
+                &mut request.params,
+                filter.as_ref(),
+                params,
+            );
+        }
+        searches
+    }
+}
+
+/// Combine filter and params of a single request with the ones shared by its batch.
+///
+/// Both filters must be satisfied, params of the request take precedence over the shared ones.
+pub fn apply_batch_filter_and_params(
+    filter: &mut Option<Filter>,
+    params: &mut Option<SearchParams>,
+    batch_filter: Option<&Filter>,
+    batch_params: Option<SearchParams>,
+) {
+    if let Some(batch_filter) = batch_filter {
+        *filter = Some(match filter.take() {
+            None => batch_filter.clone(),
+            Some(own_filter) => Filter {
+                should: None,
+                must: Some(vec![
+                    Condition::Filter(own_filter),
+                    Condition::Filter(batch_filter.clone()),
+                ]),
+                must_not: None,
+            },
+        });
+    }
+    if params.is_none() {
+        *params = batch_params;
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
@@ -849,6 +1371,195 @@ pub struct CountResult {
     pub count: usize,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetRequest {
+    #[serde(flatten)]
+    #[validate]
+    pub facet_request: FacetRequestInternal,
+    /// Specify in which shards to look for the points, if not specified - look in all shards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKeySelector>,
+}
+
+/// Facet Request
+/// Counts the number of points for each distinct value of a keyword payload field.
+/// Only points which satisfy the filter are counted, if it is provided.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetRequestInternal {
+    /// Payload key to count the values of. Must have a keyword index
+    pub key: PayloadKeyType,
+    /// Max number of values to return, the most frequent first. Default: 10
+    #[validate(range(min = 1))]
+    pub limit: Option<usize>,
+    /// Look only for points which satisfies this conditions
+    #[validate]
+    pub filter: Option<Filter>,
+    /// If true, count exact number of points for each value. If false, counts are estimated faster
+    /// using the payload index. Default: false
+    #[serde(default)]
+    pub exact: bool,
+}
+
+pub const DEFAULT_FACET_LIMIT: usize = 10;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetValueHit {
+    /// Value of the payload field
+    pub value: String,
+    /// Number of points with this value
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetResult {
+    /// Values with the number of points, the most frequent first
+    pub hits: Vec<FacetValueHit>,
+}
+
+impl FacetResult {
+    /// Sort values by number of points in descending order, and keep at most `limit` of them
+    pub fn from_counts(
+        counts: impl IntoIterator<Item = (String, usize)>,
+        limit: Option<usize>,
+    ) -> Self {
+        let mut hits: Vec<_> = counts
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(value, count)| FacetValueHit { value, count })
+            .collect();
+        hits.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        if let Some(limit) = limit {
+            hits.truncate(limit);
+        }
+        Self { hits }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchMatrixRequest {
+    #[serde(flatten)]
+    #[validate]
+    pub search_request: SearchMatrixRequestInternal,
+    /// Specify in which shards to look for the points, if not specified - look in all shards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKeySelector>,
+}
+
+/// Search matrix request
+/// Samples points and computes the distances to the nearest neighbours of each sampled point
+/// within the same sample.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchMatrixRequestInternal {
+    /// Look only for points which satisfies this conditions
+    #[validate]
+    pub filter: Option<Filter>,
+    /// How many points to select and search within. Default: 10, max: 1000
+    #[validate(range(min = 2, max = 1000))]
+    pub sample: Option<usize>,
+    /// How many neighbours per sample to find. Default: 3
+    #[validate(range(min = 1))]
+    pub limit: Option<usize>,
+    /// Define which vector name to use for querying. If missing, the default vector is used.
+    pub using: Option<String>,
+}
+
+pub const DEFAULT_SEARCH_MATRIX_SAMPLE: usize = 10;
+pub const DEFAULT_SEARCH_MATRIX_LIMIT: usize = 3;
+
+/// Nearest neighbours of each sampled point, ordered by score
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatrix {
+    /// Sampled point ids
+    pub sample_ids: Vec<PointIdType>,
+    /// Nearest neighbours of each sampled point, in the same order as `sample_ids`
+    pub nearests: Vec<Vec<ScoredPoint>>,
+}
+
+/// Pair of points (a, b) with score
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchMatrixPair {
+    pub a: PointIdType,
+    pub b: PointIdType,
+    pub score: ScoreType,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchMatrixPairsResponse {
+    /// List of pairs of points with scores
+    pub pairs: Vec<SearchMatrixPair>,
+}
+
+impl From<SearchMatrix> for SearchMatrixPairsResponse {
+    fn from(matrix: SearchMatrix) -> Self {
+        let pairs = matrix
+            .sample_ids
+            .into_iter()
+            .zip(matrix.nearests)
+            .flat_map(|(a, nearest)| {
+                nearest.into_iter().map(move |hit| SearchMatrixPair {
+                    a,
+                    b: hit.id,
+                    score: hit.score,
+                })
+            })
+            .collect();
+        Self { pairs }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchMatrixOffsetsResponse {
+    /// Row indices of the matrix
+    pub offsets_row: Vec<u64>,
+    /// Column indices of the matrix
+    pub offsets_col: Vec<u64>,
+    /// Scores associated with matrix coordinates
+    pub scores: Vec<ScoreType>,
+    /// Ids of the points in order
+    pub ids: Vec<PointIdType>,
+}
+
+impl From<SearchMatrix> for SearchMatrixOffsetsResponse {
+    fn from(matrix: SearchMatrix) -> Self {
+        let id_to_offset: HashMap<PointIdType, u64> = matrix
+            .sample_ids
+            .iter()
+            .enumerate()
+            .map(|(offset, id)| (*id, offset as u64))
+            .collect();
+
+        let mut offsets_row = Vec::new();
+        let mut offsets_col = Vec::new();
+        let mut scores = Vec::new();
+        for (row, nearest) in matrix.nearests.into_iter().enumerate() {
+            for hit in nearest {
+                let Some(col) = id_to_offset.get(&hit.id) else {
+                    continue;
+                };
+                offsets_row.push(row as u64);
+                offsets_col.push(*col);
+                scores.push(hit.score);
+            }
+        }
+
+        Self {
+            offsets_row,
+            offsets_col,
+            scores,
+            ids: matrix.sample_ids,
+        }
+    }
+}
+
 #[derive(Error, Debug, Clone)]
 #[error("{0}")]
 pub enum CollectionError {
@@ -1273,12 +1984,18 @@ pub struct SparseVectorParams {
     /// Custom params for index. If none - values from collection configuration are used.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub index: Option<SparseIndexParams>,
+    /// Payload key to partition the sparse index by, e.g. a tenant id.
+    /// Searches with an exact `match` on this key only scan postings of points with the matched value.
+    /// Applies to segments, built after the key is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition_by: Option<PayloadKeyType>,
 }
 
 impl Anonymize for SparseVectorParams {
     fn anonymize(&self) -> Self {
         Self {
             index: self.index.anonymize(),
+            partition_by: self.partition_by.anonymize(),
         }
     }
 }
@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+
+use segment::types::{Condition, Filter, PayloadFieldSchema, PayloadKeyType, VERSION_FIELD};
+
+use crate::config::StrictModeConfig;
+use crate::grouping::group_by::{GroupRequest, SourceRequest};
+use crate::operations::payload_ops::PayloadOps;
+use crate::operations::point_ops::{
+    ConditionalInsertOperationInternal, CopyPoints, PointInsertOperationsInternal, PointOperations,
+    VersionedInsertOperationInternal,
+};
+use crate::operations::shard_selector_internal::ShardSelectorInternal;
+use crate::operations::types::{
+    CollectionError, CollectionResult, CoreSearchRequest, CoreSearchRequestBatch,
+    CountRequestInternal, DiscoverRequestInternal, FacetRequestInternal, RecommendRequestInternal,
+    ScrollRequestInternal, SearchMatrixRequestInternal, SearchRequestInternal,
+    DEFAULT_SEARCH_MATRIX_SAMPLE,
+};
+use crate::operations::vector_ops::VectorOperations;
+use crate::operations::CollectionUpdateOperations;
+
+/// Request, which can be checked against the strict mode config of a collection
+pub trait StrictModeVerification {
+    /// Max number of results, requested by the request
+    fn query_limit(&self) -> Option<usize>;
+
+    /// All filters, used by the request
+    fn filters(&self) -> Vec<&Filter>;
+
+    /// Number of requests in a batch, or points in an update
+    fn batch_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn check_strict_mode(
+        &self,
+        config: &StrictModeConfig,
+        payload_schema: &HashMap<PayloadKeyType, PayloadFieldSchema>,
+    ) -> CollectionResult<()> {
+        if !config.is_enabled() {
+            return Ok(());
+        }
+
+        if let (Some(max_limit), Some(limit)) = (config.max_query_limit, self.query_limit()) {
+            if limit > max_limit {
+                return Err(CollectionError::bad_request(format!(
+                    "Limit {limit} exceeds the max query limit {max_limit} of the collection",
+                )));
+            }
+        }
+
+        if let (Some(max_size), Some(size)) = (config.max_batch_size, self.batch_size()) {
+            if size > max_size {
+                return Err(CollectionError::bad_request(format!(
+                    "Batch size {size} exceeds the max batch size {max_size} of the collection",
+                )));
+            }
+        }
+
+        let unindexed_filtering = config.unindexed_filtering.unwrap_or(true);
+
+        for filter in self.filters() {
+            if let Some(max_conditions) = config.max_filter_conditions {
+                let conditions = count_conditions(filter);
+                if conditions > max_conditions {
+                    return Err(CollectionError::bad_request(format!(
+                        "Filter has {conditions} conditions, \
+                         which exceeds the max of {max_conditions} conditions of the collection",
+                    )));
+                }
+            }
+
+            if !unindexed_filtering {
+                let mut keys = Vec::new();
+                collect_filter_keys(filter, None, &mut keys);
+                if let Some(key) = keys
+                    .into_iter()
+                    .find(|key| !payload_schema.contains_key(key))
+                {
+                    return Err(CollectionError::bad_request(format!(
+                        "Filtering by unindexed field `{key}` is forbidden in strict mode, \
+                         create a payload index for it",
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn filter_conditions(filter: &Filter) -> impl Iterator<Item = &Condition> {
+    [&filter.must, &filter.should, &filter.must_not]
+        .into_iter()
+        .flatten()
+        .flatten()
+}
+
+/// Number of conditions in the filter, including the ones in nested filters
+fn count_conditions(filter: &Filter) -> usize {
+    filter_conditions(filter)
+        .map(|condition| match condition {
+            Condition::Filter(filter) => count_conditions(filter),
+            Condition::Nested(nested) => count_conditions(nested.filter()),
+            Condition::Field(_)
+            | Condition::IsEmpty(_)
+            | Condition::IsNull(_)
+            | Condition::HasId(_) => 1,
+        })
+        .sum()
+}
+
+/// Collects payload keys of field conditions, which require a payload index in strict mode.
+///
+/// Keys of nested conditions are prefixed with the array key of the parent, e.g. `country[].city`
+fn collect_filter_keys(filter: &Filter, prefix: Option<&str>, keys: &mut Vec<PayloadKeyType>) {
+    for condition in filter_conditions(filter) {
+        match condition {
+            Condition::Field(field) if field.key != VERSION_FIELD => match prefix {
+                Some(prefix) => keys.push(format!("{prefix}.{}", field.key)),
+                None => keys.push(field.key.clone()),
+            },
+            Condition::Filter(filter) => collect_filter_keys(filter, prefix, keys),
+            Condition::Nested(nested) => {
+                let array_key = match prefix {
+                    Some(prefix) => format!("{prefix}.{}", nested.array_key()),
+                    None => nested.array_key(),
+                };
+                collect_filter_keys(nested.filter(), Some(&array_key), keys);
+            }
+            Condition::Field(_)
+            | Condition::IsEmpty(_)
+            | Condition::IsNull(_)
+            | Condition::HasId(_) => {}
+        }
+    }
+}
+
+impl StrictModeVerification for CoreSearchRequest {
+    fn query_limit(&self) -> Option<usize> {
+        Some(self.limit)
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+}
+
+impl StrictModeVerification for CoreSearchRequestBatch {
+    fn query_limit(&self) -> Option<usize> {
+        self.searches.iter().filter_map(|s| s.query_limit()).max()
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.searches.iter().flat_map(|s| s.filters()).collect()
+    }
+
+    fn batch_size(&self) -> Option<usize> {
+        Some(self.searches.len())
+    }
+}
+
+impl StrictModeVerification for SearchRequestInternal {
+    fn query_limit(&self) -> Option<usize> {
+        Some(self.limit)
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+}
+
+impl StrictModeVerification for RecommendRequestInternal {
+    fn query_limit(&self) -> Option<usize> {
+        Some(self.limit)
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+}
+
+impl StrictModeVerification for DiscoverRequestInternal {
+    fn query_limit(&self) -> Option<usize> {
+        Some(self.limit)
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+}
+
+impl<R: StrictModeVerification> StrictModeVerification for Vec<(R, ShardSelectorInternal)> {
+    fn query_limit(&self) -> Option<usize> {
+        self.iter().filter_map(|(r, _)| r.query_limit()).max()
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.iter().flat_map(|(r, _)| r.filters()).collect()
+    }
+
+    fn batch_size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl StrictModeVerification for ScrollRequestInternal {
+    fn query_limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+}
+
+impl StrictModeVerification for CountRequestInternal {
+    fn query_limit(&self) -> Option<usize> {
+        None
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+}
+
+impl StrictModeVerification for FacetRequestInternal {
+    fn query_limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+}
+
+impl StrictModeVerification for SearchMatrixRequestInternal {
+    fn query_limit(&self) -> Option<usize> {
+        self.sample.max(self.limit)
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+
+    /// Every sampled point is searched separately
+    fn batch_size(&self) -> Option<usize> {
+        Some(self.sample.unwrap_or(DEFAULT_SEARCH_MATRIX_SAMPLE))
+    }
+}
+
+impl StrictModeVerification for GroupRequest {
+    fn query_limit(&self) -> Option<usize> {
+        Some(self.limit)
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        match &self.source {
+            SourceRequest::Search(request) => request.filters(),
+            SourceRequest::Recommend(request) => request.filters(),
+        }
+    }
+}
+
+impl StrictModeVerification for CopyPoints {
+    fn query_limit(&self) -> Option<usize> {
+        None
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        self.filter.iter().collect()
+    }
+}
+
+impl StrictModeVerification for CollectionUpdateOperations {
+    fn query_limit(&self) -> Option<usize> {
+        None
+    }
+
+    fn filters(&self) -> Vec<&Filter> {
+        match self {
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(
+                filter,
+            ))
+            | CollectionUpdateOperations::VectorOperation(
+                VectorOperations::DeleteVectorsByFilter(filter, _),
+            )
+            | CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayloadByFilter(
+                filter,
+            )) => vec![filter],
+            CollectionUpdateOperations::PayloadOperation(
+                PayloadOps::SetPayload(op) | PayloadOps::OverwritePayload(op),
+            ) => op.filter.iter().collect(),
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(op)) => {
+                op.filter.iter().collect()
+            }
+            CollectionUpdateOperations::PointOperation(
+                PointOperations::UpsertPointsConditional(op),
+            ) => vec![&op.condition],
+            CollectionUpdateOperations::BatchOperation(operations) => operations
+                .iter()
+                .flat_map(|operation| operation.filters())
+                .collect(),
+            CollectionUpdateOperations::PointOperation(_)
+            | CollectionUpdateOperations::VectorOperation(_)
+            | CollectionUpdateOperations::PayloadOperation(_)
+            | CollectionUpdateOperations::FieldIndexOperation(_) => vec![],
+        }
+    }
+
+    fn batch_size(&self) -> Option<usize> {
+        match self {
+            CollectionUpdateOperations::PointOperation(
+                PointOperations::UpsertPoints(points)
+                | PointOperations::UpsertPointsConditional(ConditionalInsertOperationInternal {
+                    points_op: points,
+                    ..
+                })
+                | PointOperations::UpsertPointsIfVersion(VersionedInsertOperationInternal {
+                    points_op: points,
+                    ..
+                }),
+            ) => Some(match points {
+                PointInsertOperationsInternal::PointsBatch(batch) => batch.ids.len(),
+                PointInsertOperationsInternal::PointsList(points) => points.len(),
+            }),
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints { ids }) => {
+                Some(ids.len())
+            }
+            CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(op)) => {
+                Some(op.points.len())
+            }
+            // Points of all operations of a batch, an operation without points counts as one
+            CollectionUpdateOperations::BatchOperation(operations) => Some(
+                operations
+                    .iter()
+                    .map(|operation| operation.batch_size().unwrap_or(1))
+                    .sum(),
+            ),
+            CollectionUpdateOperations::PointOperation(_)
+            | CollectionUpdateOperations::VectorOperation(_)
+            | CollectionUpdateOperations::PayloadOperation(_)
+            | CollectionUpdateOperations::FieldIndexOperation(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::{FieldCondition, Match, PayloadSchemaType, ValueVariants};
+
+    use super::*;
+
+    fn match_condition(key: &str) -> Condition {
+        Condition::Field(FieldCondition::new_match(
+            key,
+            Match::new_value(ValueVariants::Keyword("value".to_string())),
+        ))
+    }
+
+    #[test]
+    fn test_check_filters() {
+        let filter = Filter {
+            should: None,
+            must: Some(vec![
+                match_condition("city"),
+                Condition::new_nested("country", Filter::new_must(match_condition("name"))),
+            ]),
+            must_not: Some(vec![Condition::Filter(Filter::new_should(
+                match_condition("city"),
+            ))]),
+        };
+        assert_eq!(count_conditions(&filter), 3);
+
+        let mut keys = Vec::new();
+        collect_filter_keys(&filter, None, &mut keys);
+        assert_eq!(keys, vec!["city", "country[].name", "city"]);
+
+        let request = CountRequestInternal {
+            filter: Some(filter),
+            exact: true,
+        };
+        let mut config = StrictModeConfig {
+            enabled: Some(true),
+            max_filter_conditions: Some(3),
+            unindexed_filtering: Some(false),
+            ..Default::default()
+        };
+        let mut schema = HashMap::from([
+            ("city".to_string(), PayloadSchemaType::Keyword.into()),
+            (
+                "country[].name".to_string(),
+                PayloadSchemaType::Keyword.into(),
+            ),
+        ]);
+        assert!(request.check_strict_mode(&config, &schema).is_ok());
+
+        schema.remove("country[].name");
+        assert!(request.check_strict_mode(&config, &schema).is_err());
+
+        config.unindexed_filtering = None;
+        assert!(request.check_strict_mode(&config, &schema).is_ok());
+
+        config.max_filter_conditions = Some(2);
+        assert!(request.check_strict_mode(&config, &schema).is_err());
+
+        config.enabled = Some(false);
+        assert!(request.check_strict_mode(&config, &schema).is_ok());
+    }
+
+    #[test]
+    fn test_check_search_matrix_sample() {
+        let mut request = SearchMatrixRequestInternal {
+            filter: None,
+            sample: None,
+            limit: None,
+            using: None,
+        };
+        let config = StrictModeConfig {
+            enabled: Some(true),
+            max_batch_size: Some(100),
+            ..Default::default()
+        };
+        let schema = HashMap::new();
+        assert!(request.check_strict_mode(&config, &schema).is_ok());
+
+        request.sample = Some(100);
+        assert!(request.check_strict_mode(&config, &schema).is_ok());
+
+        request.sample = Some(101);
+        assert!(request.check_strict_mode(&config, &schema).is_err());
+    }
+}
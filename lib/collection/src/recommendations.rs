@@ -7,7 +7,7 @@ use segment::data_types::vectors::{
     DEFAULT_VECTOR_NAME,
 };
 use segment::types::{
-    Condition, ExtendedPointId, Filter, HasIdCondition, PointIdType, ScoredPoint,
+    Condition, ExtendedPointId, Filter, HasIdCondition, PointIdType, ScoreType, ScoredPoint,
 };
 use segment::vector_storage::query::reco_query::RecoQuery;
 use sparse::common::sparse_vector::SparseVector;
@@ -16,61 +16,62 @@ use tokio::sync::RwLockReadGuard;
 use crate::collection::Collection;
 use crate::common::batching::batch_requests;
 use crate::common::fetch_vectors::{
-    convert_to_vectors, convert_to_vectors_owned, resolve_referenced_vectors_batch,
-    ReferencedVectors,
+    convert_to_vector, convert_to_vector_owned, resolve_referenced_vectors_batch, ReferencedVectors,
 };
 use crate::common::retrieve_request_trait::RetrieveRequest;
 use crate::operations::consistency_params::ReadConsistency;
 use crate::operations::shard_selector_internal::ShardSelectorInternal;
 use crate::operations::types::{
     CollectionError, CollectionResult, CoreSearchRequest, CoreSearchRequestBatch, QueryEnum,
-    RecommendRequestInternal, RecommendStrategy, UsingVector,
+    RecommendExample, RecommendRequestInternal, RecommendStrategy, UsingVector,
 };
 
-fn avg_vectors<'a>(vectors: impl Iterator<Item = VectorRef<'a>>) -> CollectionResult<Vector> {
+fn avg_vectors<'a>(
+    vectors: impl Iterator<Item = (VectorRef<'a>, VectorElementType)>,
+) -> CollectionResult<Vector> {
     let mut avg_dense = VectorType::default();
     let mut avg_sparse = SparseVector::default();
-    let mut dense_count = 0;
-    let mut sparse_count = 0;
-    for vector in vectors {
+    let mut dense_weight: VectorElementType = 0.0;
+    let mut sparse_weight: VectorElementType = 0.0;
+    for (vector, weight) in vectors {
         match vector {
             VectorRef::Dense(vector) => {
-                dense_count += 1;
+                dense_weight += weight;
                 for i in 0..vector.len() {
                     if i >= avg_dense.len() {
-                        avg_dense.push(vector[i])
+                        avg_dense.push(vector[i] * weight)
                     } else {
-                        avg_dense[i] += vector[i];
+                        avg_dense[i] += vector[i] * weight;
                     }
                 }
             }
             VectorRef::Sparse(vector) => {
-                sparse_count += 1;
-                avg_sparse = vector.combine_aggregate(&avg_sparse, |v1, v2| v1 + v2);
+                sparse_weight += weight;
+                avg_sparse = vector.combine_aggregate(&avg_sparse, |v1, v2| v1 * weight + v2);
             }
         }
     }
 
-    match (dense_count, sparse_count) {
+    match (dense_weight > 0.0, sparse_weight > 0.0) {
         // TODO(sparse): what if vectors iterator is empty? We added CollectionError::BadRequest,
         // but it's not clear if it's the best solution.
         // Currently it's hard to return an zeroed vector, because we don't know its type: dense or sparse.
-        (0, 0) => Err(CollectionError::bad_input(
+        (false, false) => Err(CollectionError::bad_input(
             "Positive vectors should not be empty with `average` strategy".to_owned(),
         )),
-        (_, 0) => {
+        (true, false) => {
             for item in &mut avg_dense {
-                *item /= dense_count as VectorElementType;
+                *item /= dense_weight;
             }
             Ok(avg_dense.into())
         }
-        (0, _) => {
+        (false, true) => {
             for item in &mut avg_sparse.values {
-                *item /= sparse_count as VectorElementType;
+                *item /= sparse_weight;
             }
             Ok(avg_sparse.into())
         }
-        (_, _) => Err(CollectionError::bad_input(
+        (true, true) => Err(CollectionError::bad_input(
             "Can't average dense and sparse vectors together".to_owned(),
         )),
     }
@@ -95,6 +96,43 @@ fn merge_positive_and_negative_avg(positive: Vector, negative: Vector) -> Collec
     }
 }
 
+/// Check that example weights, if specified, match the examples and are positive
+fn validate_weights(
+    kind: &str,
+    examples: &[RecommendExample],
+    weights: Option<&[ScoreType]>,
+) -> CollectionResult<()> {
+    let Some(weights) = weights else {
+        return Ok(());
+    };
+
+    if weights.len() != examples.len() {
+        return Err(CollectionError::bad_request(format!(
+            "Number of {kind} weights ({}) must match the number of {kind} examples ({})",
+            weights.len(),
+            examples.len(),
+        )));
+    }
+
+    if let Some(weight) = weights.iter().find(|w| !w.is_finite() || **w <= 0.0) {
+        return Err(CollectionError::bad_request(format!(
+            "Weights of {kind} examples must be positive numbers, got {weight}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Pairs each example with its weight, or with 1.0 if weights are not specified
+fn with_weights<T>(
+    examples: impl IntoIterator<Item = T>,
+    weights: Option<Vec<ScoreType>>,
+) -> impl Iterator<Item = (T, ScoreType)> {
+    examples
+        .into_iter()
+        .zip(weights.into_iter().flatten().chain(std::iter::repeat(1.0)))
+}
+
 pub async fn recommend_by<'a, F, Fut>(
     request: RecommendRequestInternal,
     collection: &Collection,
@@ -134,6 +172,17 @@ pub fn recommend_into_core_search(
         .filter_map(|example| example.as_point_id())
         .collect_vec();
 
+    validate_weights(
+        "positive",
+        &request.positive,
+        request.positive_weights.as_deref(),
+    )?;
+    validate_weights(
+        "negative",
+        &request.negative,
+        request.negative_weights.as_deref(),
+    )?;
+
     let lookup_collection_name = request.lookup_from.as_ref().map(|x| &x.collection);
 
     for &point_id in &reference_vectors_ids {
@@ -278,25 +327,29 @@ fn recommend_by_avg_vector(
         using,
         positive,
         negative,
+        positive_weights,
+        negative_weights,
         lookup_from,
         ..
     } = request;
 
     let lookup_collection_name = lookup_from.as_ref().map(|x| &x.collection);
 
-    let positive_vectors = convert_to_vectors(
-        positive.iter(),
-        all_vectors_records_map,
-        &lookup_vector_name,
-        lookup_collection_name,
-    );
+    let to_weighted_vector = |(example, weight)| {
+        convert_to_vector(
+            example,
+            all_vectors_records_map,
+            &lookup_vector_name,
+            lookup_collection_name,
+        )
+        .map(|vector| (vector, weight))
+    };
+
+    let positive_vectors =
+        with_weights(positive.iter(), positive_weights).filter_map(to_weighted_vector);
 
-    let negative_vectors = convert_to_vectors(
-        negative.iter(),
-        all_vectors_records_map,
-        &lookup_vector_name,
-        lookup_collection_name,
-    );
+    let negative_vectors =
+        with_weights(negative.iter(), negative_weights).filter_map(to_weighted_vector);
 
     let vector_name = match using {
         None => DEFAULT_VECTOR_NAME.to_string(),
@@ -344,6 +397,8 @@ fn recommend_by_best_score(
     let RecommendRequestInternal {
         positive,
         negative,
+        positive_weights,
+        negative_weights,
         strategy: _,
         filter,
         params,
@@ -358,22 +413,34 @@ fn recommend_by_best_score(
 
     let lookup_collection_name = lookup_from.as_ref().map(|x| &x.collection);
 
-    let positive = convert_to_vectors_owned(
-        positive,
-        all_vectors_records_map,
-        &lookup_vector_name,
-        lookup_collection_name,
-    );
+    let is_weighted = positive_weights.is_some() || negative_weights.is_some();
 
-    let negative = convert_to_vectors_owned(
-        negative,
-        all_vectors_records_map,
-        &lookup_vector_name,
-        lookup_collection_name,
-    );
+    let to_weighted_vector = |(example, weight)| {
+        convert_to_vector_owned(
+            example,
+            all_vectors_records_map,
+            &lookup_vector_name,
+            lookup_collection_name,
+        )
+        .map(|vector| (vector, weight))
+    };
+
+    let (positive, positive_weights): (Vec<_>, Vec<_>) = with_weights(positive, positive_weights)
+        .filter_map(to_weighted_vector)
+        .unzip();
+
+    let (negative, negative_weights): (Vec<_>, Vec<_>) = with_weights(negative, negative_weights)
+        .filter_map(to_weighted_vector)
+        .unzip();
+
+    let query = if is_weighted {
+        RecoQuery::with_weights(positive, negative, positive_weights, negative_weights)
+    } else {
+        RecoQuery::new(positive, negative)
+    };
 
     let query = QueryEnum::RecommendBestScore(NamedQuery {
-        query: RecoQuery::new(positive, negative),
+        query,
         using: using.map(|x| match x {
             UsingVector::Name(name) => name,
         }),
@@ -399,11 +466,15 @@ fn recommend_by_best_score(
 
 #[cfg(test)]
 mod tests {
-    use segment::data_types::vectors::Vector;
+    use segment::data_types::vectors::{Vector, VectorRef};
     use sparse::common::sparse_vector::SparseVector;
 
     use super::avg_vectors;
 
+    fn unweighted(vectors: &[Vector]) -> impl Iterator<Item = (VectorRef<'_>, f32)> {
+        vectors.iter().map(|v| (v.to_vec_ref(), 1.0))
+    }
+
     #[test]
     fn test_avg_vectors() {
         let vectors: Vec<Vector> = vec![
@@ -412,7 +483,7 @@ mod tests {
             vec![1.0, 2.0, 3.0].into(),
         ];
         assert_eq!(
-            avg_vectors(vectors.iter().map(|v| v.to_vec_ref())).unwrap(),
+            avg_vectors(unweighted(&vectors)).unwrap(),
             vec![1.0, 2.0, 3.0].into(),
         );
 
@@ -425,7 +496,7 @@ mod tests {
                 .into(),
         ];
         assert_eq!(
-            avg_vectors(vectors.iter().map(|v| v.to_vec_ref())).unwrap(),
+            avg_vectors(unweighted(&vectors)).unwrap(),
             SparseVector::new(vec![0, 1, 2], vec![0.0, 0.55, 1.1])
                 .unwrap()
                 .into(),
@@ -437,6 +508,42 @@ mod tests {
                 .unwrap()
                 .into(),
         ];
-        assert!(avg_vectors(vectors.iter().map(|v| v.to_vec_ref())).is_err());
+        assert!(avg_vectors(unweighted(&vectors)).is_err());
+    }
+
+    #[test]
+    fn test_weighted_avg_vectors() {
+        let dense: Vec<Vector> = vec![vec![1.0, 2.0].into(), vec![5.0, 6.0].into()];
+        assert_eq!(
+            avg_vectors(
+                dense
+                    .iter()
+                    .zip([3.0, 1.0])
+                    .map(|(v, w)| (v.to_vec_ref(), w))
+            )
+            .unwrap(),
+            vec![2.0, 3.0].into(),
+        );
+
+        let sparse: Vec<Vector> = vec![
+            SparseVector::new(vec![0, 1], vec![1.0, 2.0])
+                .unwrap()
+                .into(),
+            SparseVector::new(vec![1, 2], vec![6.0, 4.0])
+                .unwrap()
+                .into(),
+        ];
+        assert_eq!(
+            avg_vectors(
+                sparse
+                    .iter()
+                    .zip([1.0, 3.0])
+                    .map(|(v, w)| (v.to_vec_ref(), w))
+            )
+            .unwrap(),
+            SparseVector::new(vec![0, 1, 2], vec![0.25, 5.0, 3.0])
+                .unwrap()
+                .into(),
+        );
     }
 }
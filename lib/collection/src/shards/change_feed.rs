@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use segment::types::SeqNumberType;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::operations::CollectionUpdateOperations;
+
+/// Number of operations, buffered for each subscriber.
+/// Subscribers, which fall behind further, are disconnected.
+const CHANGE_FEED_CAPACITY: usize = 1024;
+
+/// Operation, written into the WAL of a local shard
+#[derive(Debug, Serialize)]
+pub struct ShardChange {
+    /// Number of the operation in the WAL of the shard
+    pub operation_id: SeqNumberType,
+    pub operation: CollectionUpdateOperations,
+}
+
+pub type ChangeReceiver = broadcast::Receiver<Arc<ShardChange>>;
+
+/// Broadcasts operations of a local shard, once they are written into WAL
+///
+/// Operations, recovered from WAL on load, are not broadcast again.
+#[derive(Clone)]
+pub struct ChangeFeed {
+    sender: broadcast::Sender<Arc<ShardChange>>,
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANGE_FEED_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl ChangeFeed {
+    /// Must be called under the WAL lock, so operations are published in the WAL order
+    pub fn publish(&self, operation_id: SeqNumberType, operation: &CollectionUpdateOperations) {
+        // Operations are only copied if there is anyone to receive them
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let change = ShardChange {
+            operation_id,
+            operation: operation.clone(),
+        };
+        // Subscribers might be gone since the check
+        let _ = self.sender.send(Arc::new(change));
+    }
+
+    pub fn subscribe(&self) -> ChangeReceiver {
+        self.sender.subscribe()
+    }
+}
@@ -4,7 +4,7 @@ use std::time::Duration;
 
 use api::grpc::qdrant::qdrant_internal_client::QdrantInternalClient;
 use api::grpc::qdrant::WaitOnConsensusCommitRequest;
-use api::grpc::transport_channel_pool::{AddTimeout, TransportChannelPool};
+use api::grpc::transport_channel_pool::{AddTimeout, TransportChannelPool, WithCompression};
 use futures::future::try_join_all;
 use futures::Future;
 use tonic::codegen::InterceptedService;
@@ -141,10 +141,12 @@ impl ChannelService {
             .get(&peer_id)
             .ok_or_else(|| CollectionError::service_error("Address for peer ID is not found."))?
             .clone();
+        let compression = self.channel_pool.compression();
         self.channel_pool
             .with_channel(&address, |channel| {
                 let client = QdrantInternalClient::new(channel);
                 let client = client.max_decoding_message_size(usize::MAX);
+                let client = client.with_compression(compression);
                 f(client)
             })
             .await
@@ -1,25 +1,29 @@
 use api::grpc::conversions::{convert_shard_key_from_grpc_opt, payload_to_proto};
 use api::grpc::qdrant::points_selector::PointsSelectorOneOf;
+use api::grpc::qdrant::points_update_operation::{self, Operation, PointStructList};
 use api::grpc::qdrant::{
     ClearPayloadPoints, ClearPayloadPointsInternal, CreateFieldIndexCollection,
     CreateFieldIndexCollectionInternal, DeleteFieldIndexCollection,
     DeleteFieldIndexCollectionInternal, DeletePayloadPoints, DeletePayloadPointsInternal,
-    DeletePointVectors, DeletePoints, DeletePointsInternal, DeleteVectorsInternal, PointVectors,
-    PointsIdsList, PointsSelector, SetPayloadPoints, SetPayloadPointsInternal, SyncPoints,
-    SyncPointsInternal, UpdatePointVectors, UpdateVectorsInternal, UpsertPoints,
+    DeletePointVectors, DeletePoints, DeletePointsInternal, DeleteVectorsInternal, PointStruct,
+    PointVectors, PointsIdsList, PointsSelector, PointsUpdateOperation, SetPayloadPoints,
+    SetPayloadPointsInternal, SyncPoints, SyncPointsInternal, UpdateBatchInternal,
+    UpdateBatchPoints, UpdatePointVectors, UpdateVectorsInternal, UpsertPoints,
     UpsertPointsInternal, VectorsSelector,
 };
-use segment::types::{Filter, PayloadFieldSchema, PayloadSchemaParams, PointIdType, ScoredPoint};
+use segment::types::{
+    Filter, PayloadFieldSchema, PayloadSchemaParams, PointIdType, ScoredPoint, SeqNumberType,
+};
 use tonic::Status;
 
 use crate::operations::conversions::write_ordering_to_proto;
-use crate::operations::payload_ops::{DeletePayloadOp, SetPayloadOp};
+use crate::operations::payload_ops::{DeletePayloadOp, PayloadOps, SetPayloadOp};
 use crate::operations::point_ops::{
-    PointInsertOperationsInternal, PointSyncOperation, WriteOrdering,
+    PointInsertOperationsInternal, PointOperations, PointSyncOperation, WriteOrdering,
 };
-use crate::operations::types::CollectionResult;
-use crate::operations::vector_ops::UpdateVectorsOp;
-use crate::operations::CreateIndex;
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::vector_ops::{UpdateVectorsOp, VectorOperations};
+use crate::operations::{CollectionUpdateOperations, CreateIndex};
 use crate::shards::shard::ShardId;
 
 pub fn internal_sync_points(
@@ -46,10 +50,49 @@ pub fn internal_sync_points(
     })
 }
 
+fn points_to_grpc(
+    point_insert_operations: PointInsertOperationsInternal,
+) -> Result<Vec<PointStruct>, Status> {
+    match point_insert_operations {
+        PointInsertOperationsInternal::PointsBatch(batch) => batch.try_into(),
+        PointInsertOperationsInternal::PointsList(list) => list
+            .into_iter()
+            .map(|id| id.try_into())
+            .collect::<Result<Vec<_>, Status>>(),
+    }
+}
+
+fn ids_selector(ids: Vec<PointIdType>) -> PointsSelector {
+    PointsSelector {
+        points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
+            ids: ids.into_iter().map(|id| id.into()).collect(),
+        })),
+    }
+}
+
+fn filter_selector(filter: Filter) -> PointsSelector {
+    PointsSelector {
+        points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
+    }
+}
+
+/// Selector of a payload operation, point IDs take priority over the filter
+fn payload_points_selector(
+    points: Option<Vec<PointIdType>>,
+    filter: Option<Filter>,
+) -> Option<PointsSelector> {
+    match points {
+        Some(points) => Some(ids_selector(points)),
+        None => filter.map(filter_selector),
+    }
+}
+
 pub fn internal_upsert_points(
     shard_id: Option<ShardId>,
     collection_name: String,
     point_insert_operations: PointInsertOperationsInternal,
+    condition: Option<Filter>,
+    if_version: Option<SeqNumberType>,
     wait: bool,
     ordering: Option<WriteOrdering>,
 ) -> CollectionResult<UpsertPointsInternal> {
@@ -58,15 +101,11 @@ pub fn internal_upsert_points(
         upsert_points: Some(UpsertPoints {
             collection_name,
             wait: Some(wait),
-            points: match point_insert_operations {
-                PointInsertOperationsInternal::PointsBatch(batch) => batch.try_into()?,
-                PointInsertOperationsInternal::PointsList(list) => list
-                    .into_iter()
-                    .map(|id| id.try_into())
-                    .collect::<Result<Vec<_>, Status>>()?,
-            },
+            points: points_to_grpc(point_insert_operations)?,
             ordering: ordering.map(write_ordering_to_proto),
             shard_key_selector: None,
+            condition: condition.map(|condition| condition.into()),
+            if_version,
         }),
     })
 }
@@ -200,17 +239,7 @@ pub fn internal_set_payload(
     wait: bool,
     ordering: Option<WriteOrdering>,
 ) -> SetPayloadPointsInternal {
-    let points_selector = if let Some(points) = set_payload.points {
-        Some(PointsSelector {
-            points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
-                ids: points.into_iter().map(|id| id.into()).collect(),
-            })),
-        })
-    } else {
-        set_payload.filter.map(|filter| PointsSelector {
-            points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
-        })
-    };
+    let points_selector = payload_points_selector(set_payload.points, set_payload.filter);
 
     SetPayloadPointsInternal {
         shard_id,
@@ -221,6 +250,7 @@ pub fn internal_set_payload(
             points_selector,
             ordering: ordering.map(write_ordering_to_proto),
             shard_key_selector: None,
+            key: set_payload.key,
         }),
     }
 }
@@ -232,17 +262,7 @@ pub fn internal_delete_payload(
     wait: bool,
     ordering: Option<WriteOrdering>,
 ) -> DeletePayloadPointsInternal {
-    let points_selector = if let Some(points) = delete_payload.points {
-        Some(PointsSelector {
-            points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
-                ids: points.into_iter().map(|id| id.into()).collect(),
-            })),
-        })
-    } else {
-        delete_payload.filter.map(|filter| PointsSelector {
-            points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
-        })
-    };
+    let points_selector = payload_points_selector(delete_payload.points, delete_payload.filter);
 
     DeletePayloadPointsInternal {
         shard_id,
@@ -339,6 +359,10 @@ pub fn internal_create_index(
                     api::grpc::qdrant::FieldType::Text as i32,
                     Some(text_index_params.into()),
                 ),
+                PayloadSchemaParams::Keyword(keyword_index_params) => (
+                    api::grpc::qdrant::FieldType::Keyword as i32,
+                    Some(keyword_index_params.into()),
+                ),
             },
         })
         .map(|(field_type, field_params)| (Some(field_type), field_params))
@@ -375,6 +399,167 @@ pub fn internal_delete_index(
     }
 }
 
+fn upsert_operation_to_grpc(
+    point_insert_operations: PointInsertOperationsInternal,
+    condition: Option<Filter>,
+    if_version: Option<SeqNumberType>,
+) -> CollectionResult<Operation> {
+    Ok(Operation::Upsert(PointStructList {
+        points: points_to_grpc(point_insert_operations)?,
+        shard_key_selector: None,
+        condition: condition.map(|condition| condition.into()),
+        if_version,
+    }))
+}
+
+fn set_payload_to_grpc(set_payload: SetPayloadOp) -> points_update_operation::SetPayload {
+    points_update_operation::SetPayload {
+        payload: payload_to_proto(set_payload.payload),
+        points_selector: payload_points_selector(set_payload.points, set_payload.filter),
+        shard_key_selector: None,
+        key: set_payload.key,
+    }
+}
+
+/// Append operations of a batch in the form of gRPC batch operations, nested batches are flattened
+fn append_batch_operations(
+    operations: Vec<CollectionUpdateOperations>,
+    batch: &mut Vec<PointsUpdateOperation>,
+) -> CollectionResult<()> {
+    for operation in operations {
+        let operation = match operation {
+            CollectionUpdateOperations::PointOperation(point_operation) => match point_operation {
+                PointOperations::UpsertPoints(operation) => {
+                    upsert_operation_to_grpc(operation, None, None)?
+                }
+                PointOperations::UpsertPointsConditional(operation) => {
+                    upsert_operation_to_grpc(operation.points_op, Some(operation.condition), None)?
+                }
+                PointOperations::UpsertPointsIfVersion(operation) => {
+                    upsert_operation_to_grpc(operation.points_op, None, Some(operation.if_version))?
+                }
+                PointOperations::DeletePoints { ids } => {
+                    Operation::DeletePoints(points_update_operation::DeletePoints {
+                        points: Some(ids_selector(ids)),
+                        shard_key_selector: None,
+                    })
+                }
+                PointOperations::DeletePointsByFilter(filter) => {
+                    Operation::DeletePoints(points_update_operation::DeletePoints {
+                        points: Some(filter_selector(filter)),
+                        shard_key_selector: None,
+                    })
+                }
+                PointOperations::SyncPoints(_) => {
+                    return Err(CollectionError::bad_request(
+                        "Sync operation can't be a part of a batch".to_string(),
+                    ));
+                }
+            },
+            CollectionUpdateOperations::VectorOperation(vector_operation) => match vector_operation
+            {
+                VectorOperations::UpdateVectors(update_vectors) => {
+                    Operation::UpdateVectors(points_update_operation::UpdateVectors {
+                        points: update_vectors
+                            .points
+                            .into_iter()
+                            .map(|point| PointVectors {
+                                id: Some(point.id.into()),
+                                vectors: Some(point.vector.into()),
+                            })
+                            .collect(),
+                        shard_key_selector: None,
+                    })
+                }
+                VectorOperations::DeleteVectors(ids, vector_names) => {
+                    Operation::DeleteVectors(points_update_operation::DeleteVectors {
+                        points_selector: Some(ids_selector(ids.points)),
+                        vectors: Some(VectorsSelector {
+                            names: vector_names,
+                        }),
+                        shard_key_selector: None,
+                    })
+                }
+                VectorOperations::DeleteVectorsByFilter(filter, vector_names) => {
+                    Operation::DeleteVectors(points_update_operation::DeleteVectors {
+                        points_selector: Some(filter_selector(filter)),
+                        vectors: Some(VectorsSelector {
+                            names: vector_names,
+                        }),
+                        shard_key_selector: None,
+                    })
+                }
+            },
+            CollectionUpdateOperations::PayloadOperation(payload_operation) => {
+                match payload_operation {
+                    PayloadOps::SetPayload(set_payload) => {
+                        Operation::SetPayload(set_payload_to_grpc(set_payload))
+                    }
+                    PayloadOps::OverwritePayload(set_payload) => {
+                        Operation::OverwritePayload(set_payload_to_grpc(set_payload))
+                    }
+                    PayloadOps::DeletePayload(delete_payload) => {
+                        Operation::DeletePayload(points_update_operation::DeletePayload {
+                            keys: delete_payload.keys,
+                            points_selector: payload_points_selector(
+                                delete_payload.points,
+                                delete_payload.filter,
+                            ),
+                            shard_key_selector: None,
+                        })
+                    }
+                    PayloadOps::ClearPayload { points } => {
+                        Operation::ClearPayload(points_update_operation::ClearPayload {
+                            points: Some(ids_selector(points)),
+                            shard_key_selector: None,
+                        })
+                    }
+                    PayloadOps::ClearPayloadByFilter(filter) => {
+                        Operation::ClearPayload(points_update_operation::ClearPayload {
+                            points: Some(filter_selector(filter)),
+                            shard_key_selector: None,
+                        })
+                    }
+                }
+            }
+            CollectionUpdateOperations::FieldIndexOperation(_) => {
+                return Err(CollectionError::bad_request(
+                    "Field index operation can't be a part of a batch".to_string(),
+                ));
+            }
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                append_batch_operations(operations, batch)?;
+                continue;
+            }
+        };
+        batch.push(PointsUpdateOperation {
+            operation: Some(operation),
+        });
+    }
+    Ok(())
+}
+
+pub fn internal_update_batch(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    operations: Vec<CollectionUpdateOperations>,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> CollectionResult<UpdateBatchInternal> {
+    let mut batch = Vec::with_capacity(operations.len());
+    append_batch_operations(operations, &mut batch)?;
+
+    Ok(UpdateBatchInternal {
+        shard_id,
+        update_batch: Some(UpdateBatchPoints {
+            collection_name,
+            wait: Some(wait),
+            operations: batch,
+            ordering: ordering.map(write_ordering_to_proto),
+        }),
+    })
+}
+
 pub fn try_scored_point_from_grpc(
     point: api::grpc::qdrant::ScoredPoint,
     with_payload: bool,
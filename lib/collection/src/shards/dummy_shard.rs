@@ -1,16 +1,20 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
-    ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
+    ExtendedPointId, Filter, PayloadFieldStatistics, PayloadKeyType, ScoredPoint, WithPayload,
+    WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch,
-    CountRequestInternal, CountResult, PointRequestInternal, Record, UpdateResult,
+    CountRequestInternal, CountResult, FacetRequestInternal, FacetResult, PointRequestInternal,
+    Record, Sample, UpdateResult, VectorUsage,
 };
 use crate::operations::CollectionUpdateOperations;
 use crate::shards::shard_trait::ShardOperation;
@@ -49,6 +53,14 @@ impl DummyShard {
         }
     }
 
+    pub fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        HashMap::new()
+    }
+
+    pub fn get_vectors_usage(&self) -> HashMap<String, VectorUsage> {
+        HashMap::new()
+    }
+
     fn dummy<T>(&self) -> CollectionResult<T> {
         Err(CollectionError::service_error(self.message.to_string()))
     }
@@ -73,6 +85,8 @@ impl ShardOperation for DummyShard {
         _: &WithVector,
         _: Option<&Filter>,
         _: &Handle,
+        _: Option<&OrderBy>,
+        _: Option<Sample>,
     ) -> CollectionResult<Vec<Record>> {
         self.dummy()
     }
@@ -94,6 +108,14 @@ impl ShardOperation for DummyShard {
         self.dummy()
     }
 
+    async fn facet(
+        &self,
+        _: Arc<FacetRequestInternal>,
+        _: &Handle,
+    ) -> CollectionResult<FacetResult> {
+        self.dummy()
+    }
+
     async fn retrieve(
         &self,
         _: Arc<PointRequestInternal>,
@@ -1,23 +1,28 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PayloadFieldStatistics, PayloadKeyType, PointIdType, ScoredPoint,
+    WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 use tokio::sync::Mutex;
 
+use super::change_feed::ChangeReceiver;
 use super::update_tracker::UpdateTracker;
 use crate::operations::point_ops::{PointOperations, PointStruct, PointSyncOperation};
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch,
-    CountRequestInternal, CountResult, PointRequestInternal, Record, UpdateResult,
+    CountRequestInternal, CountResult, FacetRequestInternal, FacetResult, PointRequestInternal,
+    Record, Sample, UpdateResult, VectorUsage,
 };
 use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use crate::shards::local_shard::LocalShard;
+use crate::shards::local_shard_cursor::{CursorQuery, LocalShardCursor};
 use crate::shards::remote_shard::RemoteShard;
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
@@ -93,6 +98,8 @@ impl ForwardProxyShard {
                 &true.into(),
                 None,
                 runtime_handle,
+                None,
+                None,
             )
             .await?;
         let next_page_offset = if batch.len() < limit {
@@ -154,6 +161,32 @@ impl ForwardProxyShard {
         self.wrapped_shard.get_telemetry_data()
     }
 
+    pub fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        self.wrapped_shard.get_field_statistics()
+    }
+
+    pub fn get_vectors_usage(&self) -> HashMap<String, VectorUsage> {
+        self.wrapped_shard.get_vectors_usage()
+    }
+
+    pub fn subscribe_changes(&self) -> ChangeReceiver {
+        self.wrapped_shard.subscribe_changes()
+    }
+
+    pub async fn data_checksum(&self) -> CollectionResult<String> {
+        self.wrapped_shard.data_checksum().await
+    }
+
+    pub async fn cursor(
+        &self,
+        query: &CursorQuery,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<LocalShardCursor> {
+        self.wrapped_shard
+            .cursor(query, search_runtime_handle)
+            .await
+    }
+
     pub fn update_tracker(&self) -> &UpdateTracker {
         self.wrapped_shard.update_tracker()
     }
@@ -188,6 +221,8 @@ impl ShardOperation for ForwardProxyShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
+        sample: Option<Sample>,
     ) -> CollectionResult<Vec<Record>> {
         let local_shard = &self.wrapped_shard;
         local_shard
@@ -198,6 +233,8 @@ impl ShardOperation for ForwardProxyShard {
                 with_vector,
                 filter,
                 search_runtime_handle,
+                order_by,
+                sample,
             )
             .await
     }
@@ -223,6 +260,16 @@ impl ShardOperation for ForwardProxyShard {
         local_shard.count(request).await
     }
 
+    /// Forward read-only `facet` to `wrapped_shard`
+    async fn facet(
+        &self,
+        request: Arc<FacetRequestInternal>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<FacetResult> {
+        let local_shard = &self.wrapped_shard;
+        local_shard.facet(request, search_runtime_handle).await
+    }
+
     async fn retrieve(
         &self,
         request: Arc<PointRequestInternal>,
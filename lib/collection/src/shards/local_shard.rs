@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::mem::size_of;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
@@ -10,14 +10,14 @@ use common::panic;
 use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
 use parking_lot::{Mutex as ParkingMutex, RwLock};
-use segment::data_types::vectors::VectorElementType;
+use segment::data_types::vectors::{Vector, VectorElementType, VectorType};
 use segment::entry::entry_point::SegmentEntry as _;
 use segment::index::field_index::CardinalityEstimation;
 use segment::segment::Segment;
 use segment::segment_constructor::{build_segment, load_segment};
 use segment::types::{
-    CompressionRatio, Filter, PayloadIndexInfo, PayloadKeyType, PayloadStorageType, PointIdType,
-    QuantizationConfig, SegmentConfig, SegmentType,
+    CompressionRatio, Filter, PayloadFieldStatistics, PayloadIndexInfo, PayloadKeyType,
+    PointIdType, QuantizationConfig, SegmentConfig, SegmentType, SeqNumberType,
 };
 use segment::utils::mem::Mem;
 use tokio::fs::{copy, create_dir_all, remove_dir_all};
@@ -26,16 +26,20 @@ use tokio::sync::mpsc::Sender;
 use tokio::sync::{mpsc, oneshot, Mutex, RwLock as TokioRwLock};
 use wal::{Wal, WalOptions};
 
+use super::change_feed::{ChangeFeed, ChangeReceiver};
 use super::update_tracker::UpdateTracker;
+use super::upsert_batcher::UpsertBatcher;
 use crate::collection_manager::collection_updater::CollectionUpdater;
 use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
 use crate::collection_manager::optimizers::TrackerLog;
 use crate::common::file_utils::move_dir;
+use crate::common::maintenance_hooks::MaintenanceHooks;
+use crate::common::points_checksum::{point_digest, points_checksum};
 use crate::config::CollectionConfig;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{
     check_sparse_compatible_with_segment_config, CollectionError, CollectionInfoInternal,
-    CollectionResult, CollectionStatus, OptimizersStatus,
+    CollectionResult, CollectionStatus, OptimizersStatus, VectorUsage,
 };
 use crate::operations::CollectionUpdateOperations;
 use crate::optimizers_builder::{build_optimizers, clear_temp_segments};
@@ -48,6 +52,9 @@ use crate::wal::SerdeWal;
 
 pub type LockedWal = Arc<ParkingMutex<SerdeWal<CollectionUpdateOperations>>>;
 
+/// Number of recently inserted vectors to compare with the value range of quantized segments
+const QUANTIZATION_DRIFT_SAMPLE_SIZE: usize = 256;
+
 /// LocalShard
 ///
 /// LocalShard is an entity that can be moved between peers and contains some part of one collections data.
@@ -59,8 +66,10 @@ pub struct LocalShard {
     pub(super) shared_storage_config: Arc<SharedStorageConfig>,
     pub(super) wal: LockedWal,
     pub(super) update_handler: Arc<Mutex<UpdateHandler>>,
-    pub(super) update_sender: ArcSwap<Sender<UpdateSignal>>,
+    pub(super) update_sender: Arc<ArcSwap<Sender<UpdateSignal>>>,
     pub(super) update_tracker: UpdateTracker,
+    pub(super) upsert_batcher: Option<Arc<UpsertBatcher>>,
+    pub(super) change_feed: ChangeFeed,
     pub(super) path: PathBuf,
     pub(super) optimizers: Arc<Vec<Arc<Optimizer>>>,
     pub(super) optimizers_log: Arc<ParkingMutex<TrackerLog>>,
@@ -106,6 +115,7 @@ impl LocalShard {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         segment_holder: SegmentHolder,
         collection_config: Arc<TokioRwLock<CollectionConfig>>,
@@ -113,6 +123,7 @@ impl LocalShard {
         wal: SerdeWal<CollectionUpdateOperations>,
         optimizers: Arc<Vec<Arc<Optimizer>>>,
         shard_path: &Path,
+        maintenance_hooks: MaintenanceHooks,
         update_runtime: Handle,
     ) -> Self {
         let segment_holder = Arc::new(RwLock::new(segment_holder));
@@ -130,13 +141,35 @@ impl LocalShard {
             config.optimizer_config.flush_interval_sec,
             config.optimizer_config.max_optimization_threads,
         );
+        update_handler.maintenance_windows = config
+            .params
+            .maintenance_windows
+            .clone()
+            .unwrap_or_default();
+        update_handler.point_expiration = config.params.point_expiration.unwrap_or(false);
+        update_handler.maintenance_hooks = maintenance_hooks;
+
+        let change_feed = ChangeFeed::default();
+        update_handler.change_feed = change_feed.clone();
 
         let (update_sender, update_receiver) =
             mpsc::channel(shared_storage_config.update_queue_size);
-        update_handler.run_workers(update_receiver);
+        update_handler.run_workers(update_receiver, &update_sender);
 
         let update_tracker = segment_holder.read().update_tracker();
 
+        let update_sender = Arc::new(ArcSwap::from_pointee(update_sender));
+
+        let upsert_batcher = shared_storage_config.update_batch_window.map(|window| {
+            Arc::new(UpsertBatcher::new(
+                window,
+                locked_wal.clone(),
+                update_sender.clone(),
+                change_feed.clone(),
+                update_runtime.clone(),
+            ))
+        });
+
         drop(config); // release `shared_config` from borrow checker
 
         Self {
@@ -145,8 +178,10 @@ impl LocalShard {
             shared_storage_config,
             wal: locked_wal,
             update_handler: Arc::new(Mutex::new(update_handler)),
-            update_sender: ArcSwap::from_pointee(update_sender),
+            update_sender,
             update_tracker,
+            upsert_batcher,
+            change_feed,
             path: shard_path.to_owned(),
             update_runtime,
             optimizers,
@@ -260,6 +295,12 @@ impl LocalShard {
 
         drop(collection_config_read); // release `shared_config` from borrow checker
 
+        let maintenance_hooks = MaintenanceHooks::new(
+            &shared_storage_config.maintenance_hooks,
+            collection_id.clone(),
+        )
+        .for_shard(id);
+
         let collection = LocalShard::new(
             segment_holder,
             collection_config,
@@ -267,6 +308,7 @@ impl LocalShard {
             wal,
             optimizers,
             shard_path,
+            maintenance_hooks,
             update_runtime,
         )
         .await;
@@ -371,11 +413,10 @@ impl LocalShard {
             let segment_config = SegmentConfig {
                 vector_data: vector_params.clone(),
                 sparse_vector_data: sparse_vector_params.clone(),
-                payload_storage_type: if config.params.on_disk_payload {
-                    PayloadStorageType::OnDisk
-                } else {
-                    PayloadStorageType::InMemory
-                },
+                payload_storage_type: config.params.payload_storage_type(),
+                payload_compression: config.params.payload_compression.clone(),
+                payload_blobs: config.params.payload_blobs.clone(),
+                kv_store: config.params.kv_store,
             };
             let segment = thread::Builder::new()
                 .name(format!("shard-build-{collection_id}-{id}"))
@@ -415,6 +456,12 @@ impl LocalShard {
 
         drop(config); // release `shared_config` from borrow checker
 
+        let maintenance_hooks = MaintenanceHooks::new(
+            &shared_storage_config.maintenance_hooks,
+            collection_id.clone(),
+        )
+        .for_shard(id);
+
         let collection = LocalShard::new(
             segment_holder,
             collection_config,
@@ -422,6 +469,7 @@ impl LocalShard {
             wal,
             optimizers,
             shard_path,
+            maintenance_hooks,
             update_runtime,
         )
         .await;
@@ -522,7 +570,13 @@ impl LocalShard {
         );
         update_handler.optimizers = new_optimizers;
         update_handler.flush_interval_sec = config.optimizer_config.flush_interval_sec;
-        update_handler.run_workers(update_receiver);
+        update_handler.maintenance_windows = config
+            .params
+            .maintenance_windows
+            .clone()
+            .unwrap_or_default();
+        update_handler.point_expiration = config.params.point_expiration.unwrap_or(false);
+        update_handler.run_workers(update_receiver, &self.update_sender.load());
         self.update_sender.load().send(UpdateSignal::Nop).await?;
 
         Ok(())
@@ -733,6 +787,105 @@ impl LocalShard {
         }
     }
 
+    /// Statistics of indexed payload fields, combined over all segments of the shard
+    pub fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        let mut statistics: HashMap<PayloadKeyType, PayloadFieldStatistics> = HashMap::new();
+        for (_id, segment) in self.segments.read().iter() {
+            for (key, segment_statistics) in segment.get().read().get_field_statistics() {
+                statistics.entry(key).or_default().merge(segment_statistics);
+            }
+        }
+        statistics
+    }
+
+    /// Receive operations of this shard, as they are written into WAL
+    pub fn subscribe_changes(&self) -> ChangeReceiver {
+        self.change_feed.subscribe()
+    }
+
+    /// Usage of each vector, combined over all segments of the shard
+    pub fn get_vectors_usage(&self) -> HashMap<String, VectorUsage> {
+        let segments = self.segments.read();
+        let mut usage: HashMap<String, VectorUsage> = HashMap::new();
+        let mut recent_vectors: HashMap<String, Vec<VectorType>> = HashMap::new();
+        for (_id, segment) in segments.iter() {
+            let segment = segment.get();
+            let segment = segment.read();
+            for (vector_name, vector_info) in segment.info().vector_data {
+                let vector_usage = usage.entry(vector_name).or_default();
+                vector_usage.points_count += vector_info.num_vectors;
+                vector_usage.indexed_vectors_count += vector_info.num_indexed_vectors;
+            }
+            for (vector_name, size_bytes) in segment.get_vector_storage_size_bytes() {
+                usage.entry(vector_name).or_default().storage_size_bytes += size_bytes;
+            }
+
+            // Appendable segments hold the most recently inserted vectors
+            if segment.is_appendable() {
+                for vector_name in segment.config().vector_data.keys() {
+                    let sample = recent_vectors.entry(vector_name.clone()).or_default();
+                    // `iter_points` is not available for proxy segments, which are appendable too
+                    let limit = QUANTIZATION_DRIFT_SAMPLE_SIZE.saturating_sub(sample.len());
+                    for point_id in segment.read_filtered(None, Some(limit), None) {
+                        if let Ok(Some(Vector::Dense(vector))) =
+                            segment.vector(vector_name, point_id)
+                        {
+                            sample.push(vector);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Compare recent vectors with values, on which quantization of other segments was trained
+        for (vector_name, vectors) in &recent_vectors {
+            let Some(vector_usage) = usage.get_mut(vector_name) else {
+                continue;
+            };
+            for (_id, segment) in segments.iter() {
+                let drift = segment
+                    .get()
+                    .read()
+                    .get_quantization_drift(vector_name, vectors);
+                vector_usage.add_quantization_drift(drift);
+            }
+        }
+
+        usage
+    }
+
+    /// Checksum of all points in the shard, equal for replicas with the same data
+    ///
+    /// Reads every point of the shard, so it is about as expensive as a full scroll.
+    pub async fn data_checksum(&self) -> CollectionResult<String> {
+        let segments = self.segments.clone();
+        tokio::task::spawn_blocking(move || {
+            let segments = segments.read();
+            // During optimization a point might be in multiple segments, the latest version counts
+            let mut digests: HashMap<PointIdType, (SeqNumberType, u64)> = HashMap::new();
+            for (_id, segment) in segments.iter() {
+                let segment = segment.get();
+                let segment = segment.read();
+                for point_id in segment.read_filtered(None, None, None) {
+                    let version = segment.point_version(point_id).unwrap_or_default();
+                    if let Some((latest_version, _)) = digests.get(&point_id) {
+                        if *latest_version >= version {
+                            continue;
+                        }
+                    }
+                    let vectors = segment.all_vectors(point_id)?;
+                    let payload = segment.payload(point_id)?;
+                    let digest = point_digest(point_id, &vectors, &payload);
+                    digests.insert(point_id, (version, digest));
+                }
+            }
+            CollectionResult::Ok(points_checksum(
+                digests.into_values().map(|(_, digest)| digest),
+            ))
+        })
+        .await?
+    }
+
     /// Returns estimated size of vector data in bytes
     async fn estimate_vector_data_size(&self) -> usize {
         let info = self.local_shard_info().await;
@@ -779,6 +932,7 @@ impl LocalShard {
         let mut segments_count = 0;
         let mut status = CollectionStatus::Green;
         let mut schema: HashMap<PayloadKeyType, PayloadIndexInfo> = Default::default();
+        let mut non_appendable_infos = Vec::new();
         for (_idx, segment) in segments.iter() {
             segments_count += 1;
 
@@ -790,6 +944,16 @@ impl LocalShard {
             vectors_count += segment_info.num_vectors;
             indexed_vectors_count += segment_info.num_indexed_vectors;
             points_count += segment_info.num_points;
+            if !segment_info.is_appendable && segment_info.num_points > 0 {
+                non_appendable_infos.push((
+                    segment_info.num_points,
+                    segment_info
+                        .index_schema
+                        .keys()
+                        .cloned()
+                        .collect::<HashSet<_>>(),
+                ));
+            }
             for (key, val) in segment_info.index_schema {
                 schema
                     .entry(key)
@@ -797,6 +961,14 @@ impl LocalShard {
                     .or_insert(val);
             }
         }
+        // Indexes are built in background for non-appendable segments, report points left
+        for (num_points, indexed_fields) in non_appendable_infos {
+            for (key, val) in schema.iter_mut() {
+                if !indexed_fields.contains(key) {
+                    *val.points_pending.get_or_insert(0) += num_points;
+                }
+            }
+        }
         if !segments.failed_operation.is_empty() || segments.optimizer_errors.is_some() {
             status = CollectionStatus::Red;
         }
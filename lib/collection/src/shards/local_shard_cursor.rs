@@ -0,0 +1,184 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::try_join_all;
+use itertools::Itertools;
+use segment::types::{ExtendedPointId, Filter, ScoredPoint, WithPayload, WithVector};
+use tokio::runtime::Handle;
+
+use crate::collection_manager::holders::segment_holder::LockedSegmentHolder;
+use crate::collection_manager::segments_searcher::SegmentsSearcher;
+use crate::operations::types::{
+    CollectionResult, CoreSearchRequest, CoreSearchRequestBatch, Record,
+};
+use crate::shards::local_shard::LocalShard;
+
+/// Paging executor of a local shard
+///
+/// The search or the filter is evaluated once, when the cursor is created. Only IDs and scores of
+/// the found points are kept, their payload and vectors are read from the segments page by page.
+/// Points deleted in the meantime are skipped.
+pub struct LocalShardCursor {
+    segments: LockedSegmentHolder,
+    /// Found points, which are not paged out yet, in the order of the result
+    points: VecDeque<ScoredPoint>,
+}
+
+impl LocalShardCursor {
+    /// Found points, which are not paged out yet
+    pub fn points(&self) -> &VecDeque<ScoredPoint> {
+        &self.points
+    }
+
+    /// Drop the next `count` points without reading them
+    pub fn skip(&mut self, count: usize) {
+        self.points.drain(..count.min(self.points.len()));
+    }
+
+    /// Read the next `size` points with their payload and vectors
+    pub fn next_page(
+        &mut self,
+        size: usize,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        let page: Vec<_> = self.points.drain(..size.min(self.points.len())).collect();
+        let point_ids = page.iter().map(|point| point.id).collect_vec();
+
+        let records =
+            SegmentsSearcher::retrieve(&self.segments, &point_ids, with_payload, with_vector)?;
+        Ok(fill_page(page, records))
+    }
+}
+
+/// Point of a scroll cursor, which has no score
+pub(crate) fn unscored_point(id: ExtendedPointId) -> ScoredPoint {
+    ScoredPoint {
+        id,
+        version: 0,
+        score: 0.0,
+        payload: None,
+        vector: None,
+        shard_key: None,
+    }
+}
+
+/// Set payload and vectors of the page from the retrieved records
+///
+/// Points without a record were deleted after the cursor was created, they are skipped.
+pub(crate) fn fill_page(page: Vec<ScoredPoint>, records: Vec<Record>) -> Vec<ScoredPoint> {
+    let mut records: HashMap<_, _> = records
+        .into_iter()
+        .map(|record| (record.id, record))
+        .collect();
+
+    page.into_iter()
+        .filter_map(|mut point| {
+            let record = records.remove(&point.id)?;
+            point.payload = record.payload;
+            point.vector = record.vector;
+            Some(point)
+        })
+        .collect()
+}
+
+/// Points to page out with a cursor
+#[derive(Debug, Clone)]
+pub enum CursorQuery {
+    /// Found points of a search, in the order of their scores
+    ///
+    /// The first `request.offset` points are included, as with the regular search of a shard.
+    Search {
+        request: CoreSearchRequest,
+        timeout: Option<Duration>,
+    },
+    /// Points, which satisfy the filter, in ascending order of their IDs, starting at `offset`
+    ///
+    /// Scores of the points are not used, they are always 0.
+    Scroll {
+        offset: Option<ExtendedPointId>,
+        filter: Option<Filter>,
+    },
+}
+
+impl LocalShard {
+    /// Evaluate the query once, and create a cursor to page out its points
+    pub async fn cursor(
+        &self,
+        query: &CursorQuery,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<LocalShardCursor> {
+        let points = match query {
+            CursorQuery::Search { request, timeout } => {
+                self.search_points(request, search_runtime_handle, *timeout)
+                    .await?
+            }
+            CursorQuery::Scroll { offset, filter } => {
+                self.scroll_points(*offset, filter.as_ref(), search_runtime_handle)
+                    .await?
+            }
+        };
+
+        Ok(LocalShardCursor {
+            segments: self.segments.clone(),
+            points,
+        })
+    }
+
+    async fn search_points(
+        &self,
+        request: &CoreSearchRequest,
+        search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
+    ) -> CollectionResult<VecDeque<ScoredPoint>> {
+        let request = CoreSearchRequest {
+            with_payload: None,
+            with_vector: None,
+            ..request.clone()
+        };
+        let batch = Arc::new(CoreSearchRequestBatch {
+            searches: vec![request],
+        });
+        let points = self
+            .do_search(batch, search_runtime_handle, timeout)
+            .await?
+            .pop()
+            .unwrap_or_default();
+        Ok(points.into())
+    }
+
+    async fn scroll_points(
+        &self,
+        offset: Option<ExtendedPointId>,
+        filter: Option<&Filter>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<VecDeque<ScoredPoint>> {
+        let read_handles: Vec<_> = {
+            let segments_guard = self.segments.read();
+            segments_guard
+                .iter()
+                .map(|(_, segment)| {
+                    let segment = segment.clone();
+                    let filter = filter.cloned();
+                    search_runtime_handle.spawn_blocking(move || {
+                        segment
+                            .get()
+                            .read()
+                            .read_filtered(offset, None, filter.as_ref())
+                    })
+                })
+                .collect()
+        };
+        let all_points = try_join_all(read_handles).await?;
+
+        // The same point may be present in multiple segments during optimization
+        let points = all_points
+            .into_iter()
+            .kmerge()
+            .dedup()
+            .map(unscored_point)
+            .collect();
+        Ok(points)
+    }
+}
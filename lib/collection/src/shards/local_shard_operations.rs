@@ -1,9 +1,11 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use futures::future::try_join_all;
 use itertools::Itertools;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
@@ -12,19 +14,172 @@ use tokio::sync::oneshot;
 
 use crate::collection_manager::segments_searcher::SegmentsSearcher;
 use crate::common::stopping_guard::StoppingGuard;
+use crate::operations::point_ops::{PointInsertOperationsInternal, PointOperations};
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch,
-    CountRequestInternal, CountResult, PointRequestInternal, QueryEnum, Record, UpdateResult,
-    UpdateStatus,
+    CountRequestInternal, CountResult, FacetRequestInternal, FacetResult, PointRequestInternal,
+    QueryEnum, Record, Sample, UpdateResult, UpdateStatus, DEFAULT_FACET_LIMIT,
 };
 use crate::operations::CollectionUpdateOperations;
 use crate::optimizers_builder::DEFAULT_INDEXING_THRESHOLD_KB;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::shard_trait::ShardOperation;
+use crate::shards::upsert_batcher::{UpsertBatcher, UpsertResultReceiver};
 use crate::update_handler::{OperationData, UpdateSignal};
 
 impl LocalShard {
-    async fn do_search(
+    /// Try to merge a small upsert into the current batching window.
+    ///
+    /// Returns the operation back if it should be applied on its own.
+    async fn try_batch_upsert(
+        &self,
+        batcher: &Arc<UpsertBatcher>,
+        operation: CollectionUpdateOperations,
+        wait: bool,
+    ) -> Result<UpsertResultReceiver, CollectionUpdateOperations> {
+        let points = UpsertBatcher::batchable_points(operation)?;
+
+        // A point with invalid vectors would fail the whole batch, so apply it on its own
+        let all_valid = {
+            let collection_params = &self.collection_config.read().await.params;
+            points.iter().all(|point| {
+                collection_params
+                    .check_point_vectors(&point.get_vectors())
+                    .is_ok()
+            })
+        };
+
+        if !all_valid {
+            return Err(CollectionUpdateOperations::PointOperation(
+                PointOperations::UpsertPoints(PointInsertOperationsInternal::PointsList(points)),
+            ));
+        }
+
+        Ok(batcher.submit(points, wait))
+    }
+
+    /// Scroll points ordered by the value of a payload field, using its numeric index
+    async fn scroll_by_field(
+        &self,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        search_runtime_handle: &Handle,
+        order_by: &OrderBy,
+    ) -> CollectionResult<Vec<Record>> {
+        let segments = self.segments();
+        let read_handles: Vec<_> = {
+            let segments_guard = segments.read();
+            segments_guard
+                .iter()
+                .map(|(_, segment)| {
+                    let segment = segment.clone();
+                    let filter = filter.cloned();
+                    let order_by = order_by.clone();
+                    search_runtime_handle.spawn_blocking(move || {
+                        segment.get().read().read_ordered_filtered(
+                            Some(limit),
+                            filter.as_ref(),
+                            &order_by,
+                        )
+                    })
+                })
+                .collect()
+        };
+        let all_reads = try_join_all(read_handles)
+            .await?
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let top_records = all_reads
+            .into_iter()
+            .flatten()
+            .sorted_by(|(a_value, a_id), (b_value, b_id)| {
+                order_by
+                    .compare(*a_value, *b_value)
+                    .then_with(|| a_id.cmp(b_id))
+            })
+            .unique_by(|(_, point_id)| *point_id)
+            .take(limit)
+            .collect_vec();
+
+        let point_ids = top_records.iter().map(|(_, id)| *id).collect_vec();
+
+        let with_payload = WithPayload::from(with_payload_interface);
+        let mut records: HashMap<_, _> =
+            SegmentsSearcher::retrieve(segments, &point_ids, &with_payload, with_vector)?
+                .into_iter()
+                .map(|record| (record.id, record))
+                .collect();
+
+        let ordered_records = top_records
+            .into_iter()
+            .filter_map(|(value, point_id)| {
+                let mut record = records.remove(&point_id)?;
+                record.order_value = Some(value);
+                Some(record)
+            })
+            .collect();
+
+        Ok(ordered_records)
+    }
+
+    /// Scroll a uniform random sample of points, which satisfy the filter
+    async fn scroll_randomly(
+        &self,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Vec<Record>> {
+        let segments = self.segments();
+        let read_handles: Vec<_> = {
+            let segments_guard = segments.read();
+            segments_guard
+                .iter()
+                .map(|(_, segment)| {
+                    let segment = segment.clone();
+                    let filter = filter.cloned();
+                    search_runtime_handle.spawn_blocking(move || {
+                        segment
+                            .get()
+                            .read()
+                            .read_random_filtered(limit, filter.as_ref())
+                    })
+                })
+                .collect()
+        };
+        let all_samples = try_join_all(read_handles).await?;
+
+        // Merge samples of all segments by keeping the points with the largest sampling keys
+        let point_ids = all_samples
+            .into_iter()
+            .flatten()
+            .sorted_by(|(a_key, _), (b_key, _)| b_key.total_cmp(a_key))
+            .map(|(_, point_id)| point_id)
+            .unique()
+            .take(limit)
+            .collect_vec();
+
+        let with_payload = WithPayload::from(with_payload_interface);
+        let mut records: HashMap<_, _> =
+            SegmentsSearcher::retrieve(segments, &point_ids, &with_payload, with_vector)?
+                .into_iter()
+                .map(|record| (record.id, record))
+                .collect();
+
+        // Keep the random order of the sample
+        let sampled_records = point_ids
+            .into_iter()
+            .filter_map(|point_id| records.remove(&point_id))
+            .collect();
+
+        Ok(sampled_records)
+    }
+
+    pub(super) async fn do_search(
         &self,
         core_request: Arc<CoreSearchRequestBatch>,
         search_runtime_handle: &Handle,
@@ -111,6 +266,14 @@ impl ShardOperation for LocalShard {
         operation: CollectionUpdateOperations,
         wait: bool,
     ) -> CollectionResult<UpdateResult> {
+        let operation = match &self.upsert_batcher {
+            Some(batcher) => match self.try_batch_upsert(batcher, operation, wait).await {
+                Ok(receiver) => return receiver.await?,
+                Err(operation) => operation,
+            },
+            None => operation,
+        };
+
         let (callback_sender, callback_receiver) = if wait {
             let (tx, rx) = oneshot::channel();
             (Some(tx), Some(rx))
@@ -123,6 +286,7 @@ impl ShardOperation for LocalShard {
             let channel_permit = update_sender.reserve().await?;
             let mut wal_lock = self.wal.lock();
             let operation_id = wal_lock.write(&operation)?;
+            self.change_feed.publish(operation_id, &operation);
             channel_permit.send(UpdateSignal::Operation(OperationData {
                 op_num: operation_id,
                 operation,
@@ -137,11 +301,13 @@ impl ShardOperation for LocalShard {
             Ok(UpdateResult {
                 operation_id: Some(operation_id),
                 status: UpdateStatus::Completed,
+                points: None,
             })
         } else {
             Ok(UpdateResult {
                 operation_id: Some(operation_id),
                 status: UpdateStatus::Acknowledged,
+                points: None,
             })
         }
     }
@@ -154,7 +320,34 @@ impl ShardOperation for LocalShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
+        sample: Option<Sample>,
     ) -> CollectionResult<Vec<Record>> {
+        if let Some(order_by) = order_by {
+            return self
+                .scroll_by_field(
+                    limit,
+                    with_payload_interface,
+                    with_vector,
+                    filter,
+                    search_runtime_handle,
+                    order_by,
+                )
+                .await;
+        }
+
+        if let Some(Sample::Random) = sample {
+            return self
+                .scroll_randomly(
+                    limit,
+                    with_payload_interface,
+                    with_vector,
+                    filter,
+                    search_runtime_handle,
+                )
+                .await;
+        }
+
         // ToDo: Make faster points selection with a set
         let segments = self.segments();
         let read_handles: Vec<_> = {
@@ -175,10 +368,11 @@ impl ShardOperation for LocalShard {
         };
         let all_points = try_join_all(read_handles).await?;
 
+        // Each segment returns its IDs in ascending order, merge them into a single sorted page.
+        // The same point may be present in multiple segments during optimization.
         let point_ids = all_points
             .into_iter()
-            .flatten()
-            .sorted()
+            .kmerge()
             .dedup()
             .take(limit)
             .collect_vec();
@@ -216,6 +410,49 @@ impl ShardOperation for LocalShard {
         Ok(CountResult { count: total_count })
     }
 
+    async fn facet(
+        &self,
+        request: Arc<FacetRequestInternal>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<FacetResult> {
+        let segments = self.segments();
+        let read_handles: Vec<_> = {
+            let segments_guard = segments.read();
+            segments_guard
+                .iter()
+                .map(|(_, segment)| {
+                    let segment = segment.clone();
+                    let request = request.clone();
+                    search_runtime_handle.spawn_blocking(move || {
+                        segment.get().read().facet(
+                            &request.key,
+                            request.filter.as_ref(),
+                            request.exact,
+                        )
+                    })
+                })
+                .collect()
+        };
+        let all_counts = try_join_all(read_handles)
+            .await?
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for (value, count) in all_counts.into_iter().flatten() {
+            *counts.entry(value).or_insert(0) += count;
+        }
+
+        // Counts of all values are required to merge exact results of multiple shards
+        let limit = if request.exact {
+            None
+        } else {
+            Some(request.limit.unwrap_or(DEFAULT_FACET_LIMIT))
+        };
+
+        Ok(FacetResult::from_counts(counts, limit))
+    }
+
     async fn retrieve(
         &self,
         request: Arc<PointRequestInternal>,
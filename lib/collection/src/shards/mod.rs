@@ -1,9 +1,11 @@
+pub mod change_feed;
 pub mod channel_service;
 pub mod collection_shard_distribution;
 mod conversions;
 pub mod dummy_shard;
 pub mod forward_proxy_shard;
 pub mod local_shard;
+pub mod local_shard_cursor;
 pub mod local_shard_operations;
 pub mod proxy_shard;
 pub mod queue_proxy_shard;
@@ -19,6 +21,7 @@ pub mod shard_versioning;
 pub mod telemetry;
 pub mod transfer;
 pub mod update_tracker;
+pub mod upsert_batcher;
 
 use std::path::{Path, PathBuf};
 
@@ -1,28 +1,32 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PayloadFieldStatistics, PayloadKeyType, PointIdType, ScoredPoint,
+    WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 use tokio::sync::{oneshot, RwLock};
 use tokio::time::timeout;
 
+use super::change_feed::ChangeReceiver;
 use super::update_tracker::UpdateTracker;
 use crate::operations::operation_effect::{
     EstimateOperationEffectArea, OperationEffectArea, PointsOperationEffect,
 };
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch,
-    CountRequestInternal, CountResult, PointRequestInternal, Record, UpdateResult,
+    CountRequestInternal, CountResult, FacetRequestInternal, FacetResult, PointRequestInternal,
+    Record, Sample, UpdateResult, VectorUsage,
 };
 use crate::operations::CollectionUpdateOperations;
 use crate::shards::local_shard::LocalShard;
+use crate::shards::local_shard_cursor::{CursorQuery, LocalShardCursor};
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
 use crate::update_handler::UpdateSignal;
@@ -121,6 +125,32 @@ impl ProxyShard {
         self.wrapped_shard.get_telemetry_data()
     }
 
+    pub fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        self.wrapped_shard.get_field_statistics()
+    }
+
+    pub fn get_vectors_usage(&self) -> HashMap<String, VectorUsage> {
+        self.wrapped_shard.get_vectors_usage()
+    }
+
+    pub fn subscribe_changes(&self) -> ChangeReceiver {
+        self.wrapped_shard.subscribe_changes()
+    }
+
+    pub async fn data_checksum(&self) -> CollectionResult<String> {
+        self.wrapped_shard.data_checksum().await
+    }
+
+    pub async fn cursor(
+        &self,
+        query: &CursorQuery,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<LocalShardCursor> {
+        self.wrapped_shard
+            .cursor(query, search_runtime_handle)
+            .await
+    }
+
     pub fn update_tracker(&self) -> &UpdateTracker {
         self.wrapped_shard.update_tracker()
     }
@@ -181,6 +211,8 @@ impl ShardOperation for ProxyShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
+        sample: Option<Sample>,
     ) -> CollectionResult<Vec<Record>> {
         let local_shard = &self.wrapped_shard;
         local_shard
@@ -191,6 +223,8 @@ impl ShardOperation for ProxyShard {
                 with_vector,
                 filter,
                 search_runtime_handle,
+                order_by,
+                sample,
             )
             .await
     }
@@ -220,6 +254,16 @@ impl ShardOperation for ProxyShard {
         local_shard.count(request).await
     }
 
+    /// Forward read-only `facet` to `wrapped_shard`
+    async fn facet(
+        &self,
+        request: Arc<FacetRequestInternal>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<FacetResult> {
+        let local_shard = &self.wrapped_shard;
+        local_shard.facet(request, search_runtime_handle).await
+    }
+
     /// Forward read-only `retrieve` to `wrapped_shard`
     async fn retrieve(
         &self,
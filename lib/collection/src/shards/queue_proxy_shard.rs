@@ -1,25 +1,31 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
-    ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
+    ExtendedPointId, Filter, PayloadFieldStatistics, PayloadKeyType, ScoredPoint, WithPayload,
+    WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 use tokio::sync::Mutex;
 
+use super::change_feed::ChangeReceiver;
 use super::remote_shard::RemoteShard;
 use super::transfer::driver::MAX_RETRY_COUNT;
 use super::update_tracker::UpdateTracker;
 use crate::operations::point_ops::WriteOrdering;
 use crate::operations::types::{
     CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequestInternal, CountResult,
-    PointRequestInternal, Record, UpdateResult,
+    FacetRequestInternal, FacetResult, PointRequestInternal, Record, Sample, UpdateResult,
+    VectorUsage,
 };
 use crate::operations::CollectionUpdateOperations;
 use crate::shards::local_shard::LocalShard;
+use crate::shards::local_shard_cursor::{CursorQuery, LocalShardCursor};
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
 
@@ -111,6 +117,52 @@ impl QueueProxyShard {
             .get_telemetry_data()
     }
 
+    pub fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        self.inner
+            .as_ref()
+            .expect("Queue proxy has been finalized")
+            .wrapped_shard
+            .get_field_statistics()
+    }
+
+    pub fn get_vectors_usage(&self) -> HashMap<String, VectorUsage> {
+        self.inner
+            .as_ref()
+            .expect("Queue proxy has been finalized")
+            .wrapped_shard
+            .get_vectors_usage()
+    }
+
+    pub fn subscribe_changes(&self) -> ChangeReceiver {
+        self.inner
+            .as_ref()
+            .expect("Queue proxy has been finalized")
+            .wrapped_shard
+            .subscribe_changes()
+    }
+
+    pub async fn data_checksum(&self) -> CollectionResult<String> {
+        self.inner
+            .as_ref()
+            .expect("Queue proxy has been finalized")
+            .wrapped_shard
+            .data_checksum()
+            .await
+    }
+
+    pub async fn cursor(
+        &self,
+        query: &CursorQuery,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<LocalShardCursor> {
+        self.inner
+            .as_ref()
+            .expect("Queue proxy has been finalized")
+            .wrapped_shard
+            .cursor(query, search_runtime_handle)
+            .await
+    }
+
     pub fn update_tracker(&self) -> &UpdateTracker {
         self.inner
             .as_ref()
@@ -173,6 +225,8 @@ impl ShardOperation for QueueProxyShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
+        sample: Option<Sample>,
     ) -> CollectionResult<Vec<Record>> {
         self.inner
             .as_ref()
@@ -184,6 +238,8 @@ impl ShardOperation for QueueProxyShard {
                 with_vector,
                 filter,
                 search_runtime_handle,
+                order_by,
+                sample,
             )
             .await
     }
@@ -218,6 +274,19 @@ impl ShardOperation for QueueProxyShard {
             .await
     }
 
+    /// Forward read-only `facet` to `wrapped_shard`
+    async fn facet(
+        &self,
+        request: Arc<FacetRequestInternal>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<FacetResult> {
+        self.inner
+            .as_ref()
+            .expect("Queue proxy has been finalized")
+            .facet(request, search_runtime_handle)
+            .await
+    }
+
     /// Forward read-only `retrieve` to `wrapped_shard`
     async fn retrieve(
         &self,
@@ -402,6 +471,8 @@ impl ShardOperation for Inner {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
+        sample: Option<Sample>,
     ) -> CollectionResult<Vec<Record>> {
         let local_shard = &self.wrapped_shard;
         local_shard
@@ -412,6 +483,8 @@ impl ShardOperation for Inner {
                 with_vector,
                 filter,
                 search_runtime_handle,
+                order_by,
+                sample,
             )
             .await
     }
@@ -441,6 +514,16 @@ impl ShardOperation for Inner {
         local_shard.count(request).await
     }
 
+    /// Forward read-only `facet` to `wrapped_shard`
+    async fn facet(
+        &self,
+        request: Arc<FacetRequestInternal>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<FacetResult> {
+        let local_shard = &self.wrapped_shard;
+        local_shard.facet(request, search_runtime_handle).await
+    }
+
     /// Forward read-only `retrieve` to `wrapped_shard`
     async fn retrieve(
         &self,
@@ -3,6 +3,7 @@ use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
+use api::grpc::idempotency::{new_idempotency_key, request_with_idempotency_key};
 use api::grpc::qdrant::collections_internal_client::CollectionsInternalClient;
 use api::grpc::qdrant::points_internal_client::PointsInternalClient;
 use api::grpc::qdrant::qdrant_client::QdrantClient;
@@ -10,17 +11,18 @@ use api::grpc::qdrant::shard_snapshot_location::Location;
 use api::grpc::qdrant::shard_snapshots_client::ShardSnapshotsClient;
 use api::grpc::qdrant::{
     CollectionOperationResponse, CoreSearchBatchPointsInternal, CountPoints, CountPointsInternal,
-    GetCollectionInfoRequest, GetCollectionInfoRequestInternal, GetPoints, GetPointsInternal,
-    HealthCheckRequest, InitiateShardTransferRequest, RecoverShardSnapshotRequest,
-    RecoverSnapshotResponse, ScrollPoints, ScrollPointsInternal, ShardSnapshotLocation,
-    WaitForShardStateRequest,
+    FacetCounts, FacetCountsInternal, GetCollectionInfoRequest, GetCollectionInfoRequestInternal,
+    GetPoints, GetPointsInternal, HealthCheckRequest, InitiateShardTransferRequest,
+    RecoverShardSnapshotRequest, RecoverSnapshotResponse, ScrollPoints, ScrollPointsInternal,
+    ShardSnapshotLocation, WaitForShardStateRequest,
 };
-use api::grpc::transport_channel_pool::{AddTimeout, MAX_GRPC_CHANNEL_TIMEOUT};
+use api::grpc::transport_channel_pool::{AddTimeout, WithCompression, MAX_GRPC_CHANNEL_TIMEOUT};
 use async_trait::async_trait;
 use parking_lot::Mutex;
 use segment::common::operation_time_statistics::{
     OperationDurationsAggregator, ScopeDurationMeasurer,
 };
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
@@ -40,8 +42,8 @@ use crate::operations::point_ops::{PointOperations, WriteOrdering};
 use crate::operations::snapshot_ops::SnapshotPriority;
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequest, CoreSearchRequestBatch,
-    CountRequestInternal, CountResult, PointRequestInternal, Record, SearchRequestInternal,
-    UpdateResult,
+    CountRequestInternal, CountResult, FacetRequestInternal, FacetResult, PointRequestInternal,
+    Record, Sample, ScrollQuery, SearchRequestInternal, UpdateResult,
 };
 use crate::operations::vector_ops::VectorOperations;
 use crate::operations::{CollectionUpdateOperations, FieldIndexOperations};
@@ -50,7 +52,7 @@ use crate::shards::conversions::{
     internal_clear_payload, internal_clear_payload_by_filter, internal_create_index,
     internal_delete_index, internal_delete_payload, internal_delete_points,
     internal_delete_points_by_filter, internal_set_payload, internal_sync_points,
-    internal_upsert_points, try_scored_point_from_grpc,
+    internal_update_batch, internal_upsert_points, try_scored_point_from_grpc,
 };
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_trait::ShardOperation;
@@ -60,6 +62,12 @@ use crate::shards::CollectionId;
 /// Timeout for transferring and recovering a shard snapshot on a remote peer.
 const SHARD_SNAPSHOT_TRANSFER_RECOVER_TIMEOUT: Duration = MAX_GRPC_CHANNEL_TIMEOUT;
 
+/// How many times to retry forwarding an update to the leader on transient failures.
+const FORWARD_UPDATE_RETRIES: u32 = 3;
+
+/// Base delay between retries of forwarded updates, grows linearly with each attempt.
+const FORWARD_UPDATE_BACKOFF: Duration = Duration::from_millis(200);
+
 /// RemoteShard
 ///
 /// Remote Shard is a representation of a shard that is located on a remote peer.
@@ -112,11 +120,13 @@ impl RemoteShard {
         f: impl Fn(PointsInternalClient<InterceptedService<Channel, AddTimeout>>) -> O,
     ) -> CollectionResult<T> {
         let current_address = self.current_address()?;
+        let compression = self.channel_service.channel_pool.compression();
         self.channel_service
             .channel_pool
             .with_channel(&current_address, |channel| {
                 let client = PointsInternalClient::new(channel);
                 let client = client.max_decoding_message_size(usize::MAX);
+                let client = client.with_compression(compression);
                 f(client)
             })
             .await
@@ -128,11 +138,13 @@ impl RemoteShard {
         f: impl Fn(CollectionsInternalClient<InterceptedService<Channel, AddTimeout>>) -> O,
     ) -> CollectionResult<T> {
         let current_address = self.current_address()?;
+        let compression = self.channel_service.channel_pool.compression();
         self.channel_service
             .channel_pool
             .with_channel(&current_address, |channel| {
                 let client = CollectionsInternalClient::new(channel);
                 let client = client.max_decoding_message_size(usize::MAX);
+                let client = client.with_compression(compression);
                 f(client)
             })
             .await
@@ -146,6 +158,7 @@ impl RemoteShard {
         retries: usize,
     ) -> CollectionResult<T> {
         let current_address = self.current_address()?;
+        let compression = self.channel_service.channel_pool.compression();
         self.channel_service
             .channel_pool
             .with_channel_timeout(
@@ -153,6 +166,7 @@ impl RemoteShard {
                 |channel| {
                     let client = ShardSnapshotsClient::new(channel);
                     let client = client.max_decoding_message_size(usize::MAX);
+                    let client = client.with_compression(compression);
                     f(client)
                 },
                 timeout,
@@ -201,20 +215,43 @@ impl RemoteShard {
         Ok(res)
     }
 
+    /// Forward update to the remote leader replica.
+    ///
+    /// Transient failures are retried with the same idempotency key,
+    /// so the leader applies the operation at most once.
     pub async fn forward_update(
         &self,
         operation: CollectionUpdateOperations,
         wait: bool,
         ordering: WriteOrdering,
     ) -> CollectionResult<UpdateResult> {
-        self.execute_update_operation(
-            Some(self.id),
-            self.collection_id.clone(),
-            operation,
-            wait,
-            Some(ordering),
-        )
-        .await
+        let idempotency_key = new_idempotency_key();
+        let mut attempt = 0;
+
+        loop {
+            let result = self
+                .execute_update_operation(
+                    Some(self.id),
+                    self.collection_id.clone(),
+                    operation.clone(),
+                    wait,
+                    Some(ordering),
+                    Some(&idempotency_key),
+                )
+                .await;
+
+            match result {
+                Err(err) if err.is_transient() && attempt < FORWARD_UPDATE_RETRIES => {
+                    attempt += 1;
+                    log::debug!(
+                        "Failed to forward update to peer {}, retrying ({attempt}/{FORWARD_UPDATE_RETRIES}): {err}",
+                        self.peer_id,
+                    );
+                    tokio::time::sleep(FORWARD_UPDATE_BACKOFF * attempt).await;
+                }
+                result => return result,
+            }
+        }
     }
 
     pub async fn execute_update_operation(
@@ -224,6 +261,7 @@ impl RemoteShard {
         operation: CollectionUpdateOperations,
         wait: bool,
         ordering: Option<WriteOrdering>,
+        idempotency_key: Option<&str>,
     ) -> CollectionResult<UpdateResult> {
         let mut timer = ScopeDurationMeasurer::new(&self.telemetry_update_durations);
         timer.set_success(false);
@@ -235,11 +273,60 @@ impl RemoteShard {
                         shard_id,
                         collection_name,
                         point_insert_operations,
+                        None,
+                        None,
+                        wait,
+                        ordering,
+                    )?;
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .upsert(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
+                PointOperations::UpsertPointsConditional(operation) => {
+                    let request = &internal_upsert_points(
+                        shard_id,
+                        collection_name,
+                        operation.points_op,
+                        Some(operation.condition),
+                        None,
+                        wait,
+                        ordering,
+                    )?;
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .upsert(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
+                PointOperations::UpsertPointsIfVersion(operation) => {
+                    let request = &internal_upsert_points(
+                        shard_id,
+                        collection_name,
+                        operation.points_op,
+                        None,
+                        Some(operation.if_version),
                         wait,
                         ordering,
                     )?;
                     self.with_points_client(|mut client| async move {
-                        client.upsert(tonic::Request::new(request.clone())).await
+                        client
+                            .upsert(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
+                            .await
                     })
                     .await?
                     .into_inner()
@@ -248,7 +335,12 @@ impl RemoteShard {
                     let request =
                         &internal_delete_points(shard_id, collection_name, ids, wait, ordering);
                     self.with_points_client(|mut client| async move {
-                        client.delete(tonic::Request::new(request.clone())).await
+                        client
+                            .delete(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
+                            .await
                     })
                     .await?
                     .into_inner()
@@ -262,7 +354,12 @@ impl RemoteShard {
                         ordering,
                     );
                     self.with_points_client(|mut client| async move {
-                        client.delete(tonic::Request::new(request.clone())).await
+                        client
+                            .delete(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
+                            .await
                     })
                     .await?
                     .into_inner()
@@ -276,7 +373,12 @@ impl RemoteShard {
                         ordering,
                     )?;
                     self.with_points_client(|mut client| async move {
-                        client.sync(tonic::Request::new(request.clone())).await
+                        client
+                            .sync(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
+                            .await
                     })
                     .await?
                     .into_inner()
@@ -293,7 +395,10 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .update_vectors(tonic::Request::new(request.clone()))
+                            .update_vectors(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -310,7 +415,10 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .delete_vectors(tonic::Request::new(request.clone()))
+                            .delete_vectors(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -327,7 +435,10 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .delete_vectors(tonic::Request::new(request.clone()))
+                            .delete_vectors(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -345,7 +456,10 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .set_payload(tonic::Request::new(request.clone()))
+                            .set_payload(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -361,7 +475,10 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .delete_payload(tonic::Request::new(request.clone()))
+                            .delete_payload(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -372,7 +489,10 @@ impl RemoteShard {
                         &internal_clear_payload(shard_id, collection_name, points, wait, ordering);
                     self.with_points_client(|mut client| async move {
                         client
-                            .clear_payload(tonic::Request::new(request.clone()))
+                            .clear_payload(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -388,7 +508,10 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .clear_payload(tonic::Request::new(request.clone()))
+                            .clear_payload(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -404,7 +527,10 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .overwrite_payload(tonic::Request::new(request.clone()))
+                            .overwrite_payload(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -423,7 +549,10 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .create_field_index(tonic::Request::new(request.clone()))
+                            .create_field_index(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
@@ -439,13 +568,30 @@ impl RemoteShard {
                     );
                     self.with_points_client(|mut client| async move {
                         client
-                            .delete_field_index(tonic::Request::new(request.clone()))
+                            .delete_field_index(request_with_idempotency_key(
+                                request.clone(),
+                                idempotency_key,
+                            ))
                             .await
                     })
                     .await?
                     .into_inner()
                 }
             },
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                let request =
+                    &internal_update_batch(shard_id, collection_name, operations, wait, ordering)?;
+                self.with_points_client(|mut client| async move {
+                    client
+                        .update_batch(request_with_idempotency_key(
+                            request.clone(),
+                            idempotency_key,
+                        ))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
         };
         match point_operation_response.result {
             None => Err(CollectionError::service_error(
@@ -545,8 +691,16 @@ impl ShardOperation for RemoteShard {
     ) -> CollectionResult<UpdateResult> {
         // targets the shard explicitly
         let shard_id = Some(self.id);
-        self.execute_update_operation(shard_id, self.collection_id.clone(), operation, wait, None)
-            .await
+        self.execute_update_operation(
+            shard_id,
+            self.collection_id.clone(),
+            operation,
+            wait,
+            None,
+            // Not retried, so there is nothing to deduplicate
+            None,
+        )
+        .await
     }
 
     async fn scroll_by(
@@ -557,6 +711,8 @@ impl ShardOperation for RemoteShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
+        sample: Option<Sample>,
     ) -> CollectionResult<Vec<Record>> {
         let scroll_points = ScrollPoints {
             collection_name: self.collection_id.clone(),
@@ -567,6 +723,8 @@ impl ShardOperation for RemoteShard {
             with_vectors: Some(with_vector.clone().into()),
             read_consistency: None,
             shard_key_selector: None,
+            order_by: order_by.map(|order_by| order_by.clone().into()),
+            query: sample.map(|sample| ScrollQuery::Sample(sample).into()),
         };
         let request = &ScrollPointsInternal {
             scroll_points: Some(scroll_points),
@@ -694,6 +852,37 @@ impl ShardOperation for RemoteShard {
         )
     }
 
+    async fn facet(
+        &self,
+        request: Arc<FacetRequestInternal>,
+        _search_runtime_handle: &Handle,
+    ) -> CollectionResult<FacetResult> {
+        let facet_counts = FacetCounts {
+            collection_name: self.collection_id.clone(),
+            key: request.key.clone(),
+            filter: request.filter.clone().map(|f| f.into()),
+            limit: request.limit.map(|limit| limit as u32),
+            exact: Some(request.exact),
+            read_consistency: None,
+            shard_key_selector: None,
+        };
+
+        let request = &FacetCountsInternal {
+            facet_counts: Some(facet_counts),
+            shard_id: Some(self.id),
+        };
+        let facet_response = self
+            .with_points_client(|mut client| async move {
+                client.facet(tonic::Request::new(request.clone())).await
+            })
+            .await?
+            .into_inner();
+
+        Ok(FacetResult {
+            hits: facet_response.hits.into_iter().map(Into::into).collect(),
+        })
+    }
+
     async fn retrieve(
         &self,
         request: Arc<PointRequestInternal>,
@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use segment::types::{ScoredPoint, WithPayload, WithPayloadInterface, WithVector};
+
+use super::ShardReplicaSet;
+use crate::operations::types::{
+    CollectionResult, CoreSearchRequest, CoreSearchRequestBatch, PointRequestInternal,
+};
+use crate::shards::local_shard_cursor::{fill_page, unscored_point, CursorQuery, LocalShardCursor};
+
+/// Cursor over the points of a single shard
+pub enum ShardCursor {
+    /// Pages are read from the segments of the local replica
+    Local(LocalShardCursor),
+    /// There is no active local replica, so the payload and vectors of the found points are
+    /// retrieved from another replica page by page
+    Remote(VecDeque<ScoredPoint>),
+}
+
+impl ShardCursor {
+    /// Found points, which are not paged out yet
+    pub fn points(&self) -> &VecDeque<ScoredPoint> {
+        match self {
+            ShardCursor::Local(cursor) => cursor.points(),
+            ShardCursor::Remote(points) => points,
+        }
+    }
+
+    /// Drop the next `count` points without reading them
+    pub fn skip(&mut self, count: usize) {
+        match self {
+            ShardCursor::Local(cursor) => cursor.skip(count),
+            ShardCursor::Remote(points) => {
+                points.drain(..count.min(points.len()));
+            }
+        }
+    }
+}
+
+impl ShardReplicaSet {
+    /// Evaluate the query once, and create a cursor to page out its points
+    ///
+    /// An active local replica pages out the points by itself. Otherwise the points are found by
+    /// another replica, which transfers only their IDs and scores at once.
+    pub async fn cursor(&self, query: &CursorQuery) -> CollectionResult<ShardCursor> {
+        if self.peer_is_active(&self.this_peer_id()) {
+            let local = self.local.read().await;
+            if let Some(local) = local.as_ref() {
+                if let Some(cursor) = local.cursor(query, &self.search_runtime).await? {
+                    return Ok(ShardCursor::Local(cursor));
+                }
+            }
+        }
+
+        let points = match query {
+            CursorQuery::Search { request, timeout } => {
+                let request = CoreSearchRequest {
+                    with_payload: None,
+                    with_vector: None,
+                    ..request.clone()
+                };
+                let batch = Arc::new(CoreSearchRequestBatch {
+                    searches: vec![request],
+                });
+                self.core_search(batch, None, false, *timeout)
+                    .await?
+                    .pop()
+                    .unwrap_or_default()
+            }
+            CursorQuery::Scroll { offset, filter } => self
+                .scroll_by(
+                    *offset,
+                    u32::MAX as usize,
+                    &false.into(),
+                    &false.into(),
+                    filter.as_ref(),
+                    None,
+                    false,
+                    None,
+                    None,
+                )
+                .await?
+                .into_iter()
+                .map(|record| unscored_point(record.id))
+                .collect(),
+        };
+        Ok(ShardCursor::Remote(points.into()))
+    }
+
+    /// Read the next `size` points of the cursor with their payload and vectors
+    pub async fn cursor_next_page(
+        &self,
+        cursor: &mut ShardCursor,
+        size: usize,
+        with_payload: &WithPayloadInterface,
+        with_vector: &WithVector,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        let points = match cursor {
+            ShardCursor::Local(cursor) => {
+                return cursor.next_page(size, &WithPayload::from(with_payload), with_vector);
+            }
+            ShardCursor::Remote(points) => points,
+        };
+
+        let page: Vec<_> = points.drain(..size.min(points.len())).collect();
+        let request = Arc::new(PointRequestInternal {
+            ids: page.iter().map(|point| point.id).collect(),
+            with_payload: Some(with_payload.clone()),
+            with_vector: with_vector.clone(),
+        });
+        let records = self
+            .retrieve(
+                request,
+                &WithPayload::from(with_payload),
+                with_vector,
+                None,
+                false,
+            )
+            .await?;
+        Ok(fill_page(page, records))
+    }
+}
@@ -1,3 +1,4 @@
+mod cursor;
 mod execute_read_operation;
 mod locally_disabled_peers;
 mod read_ops;
@@ -6,23 +7,27 @@ mod snapshots;
 mod update;
 
 use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU32;
 use std::ops::Deref as _;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
+pub use cursor::ShardCursor;
 use schemars::JsonSchema;
+use segment::types::{PayloadFieldStatistics, PayloadKeyType};
 use serde::{Deserialize, Serialize};
 use tokio::runtime::Handle;
 use tokio::sync::{Mutex, RwLock};
 
+use super::change_feed::ChangeReceiver;
 use super::local_shard::LocalShard;
 use super::remote_shard::RemoteShard;
 use super::transfer::ShardTransfer;
 use super::CollectionId;
 use crate::config::CollectionConfig;
 use crate::operations::shared_storage_config::SharedStorageConfig;
-use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::types::{CollectionError, CollectionResult, VectorUsage};
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::channel_service::ChannelService;
 use crate::shards::dummy_shard::DummyShard;
@@ -326,6 +331,26 @@ impl ShardReplicaSet {
         self.replica_state.read().get_peer_state(peer_id).copied()
     }
 
+    /// Desired number of replicas of this shard, if it differs from the collection default
+    pub fn replication_factor(&self) -> Option<NonZeroU32> {
+        self.replica_state.read().replication_factor
+    }
+
+    pub fn set_replication_factor(
+        &self,
+        replication_factor: Option<NonZeroU32>,
+    ) -> CollectionResult<()> {
+        self.replica_state.write_optional(|state| {
+            if state.replication_factor == replication_factor {
+                return None;
+            }
+            let mut new_state = state.clone();
+            new_state.replication_factor = replication_factor;
+            Some(new_state)
+        })?;
+        Ok(())
+    }
+
     pub async fn active_remote_shards(&self) -> Vec<PeerId> {
         let replica_state = self.replica_state.read();
         let this_peer_id = replica_state.this_peer_id;
@@ -453,6 +478,44 @@ impl ShardReplicaSet {
         }
     }
 
+    /// Delete data of the local shard and replace it with an empty one
+    ///
+    /// Replica state is not changed, the replica must be marked as dead beforehand, so it is
+    /// not queried and gets recovered from another replica.
+    pub async fn clear_local_shard(&self) -> CollectionResult<()> {
+        let mut local = self.local.write().await;
+
+        if let Some(removing_local) = local.take() {
+            // stop ongoing tasks and delete data
+            drop(removing_local);
+            LocalShard::clear(&self.shard_path).await?;
+        }
+
+        let local_shard_res = LocalShard::build(
+            self.shard_id,
+            self.collection_id.clone(),
+            &self.shard_path,
+            self.collection_config.clone(),
+            self.shared_storage_config.clone(),
+            self.update_runtime.clone(),
+        )
+        .await;
+
+        match local_shard_res {
+            Ok(local_shard) => {
+                *local = Some(Shard::Local(local_shard));
+                Ok(())
+            }
+            Err(err) => {
+                // Local shard is created again by the recovery transfer
+                *local = Some(Shard::Dummy(DummyShard::new(format!(
+                    "Failed to rebuild local replica: {err}"
+                ))));
+                Err(err)
+            }
+        }
+    }
+
     pub async fn set_local(
         &self,
         local: LocalShard,
@@ -678,6 +741,8 @@ impl ShardReplicaSet {
     where
         F: Fn(ShardId, PeerId) -> Vec<ShardTransfer>,
     {
+        self.quarantine_corrupted_local();
+
         let peers_to_notify: Vec<_> = self
             .locally_disabled_peers
             .write()
@@ -721,6 +786,37 @@ impl ShardReplicaSet {
         }
     }
 
+    /// Statistics of indexed payload fields of the local replica, if there is one
+    pub(crate) async fn get_local_field_statistics(
+        &self,
+    ) -> Option<HashMap<PayloadKeyType, PayloadFieldStatistics>> {
+        let local_shard = self.local.read().await;
+        local_shard
+            .as_ref()
+            .map(|local_shard| local_shard.get_field_statistics())
+    }
+
+    /// Usage of each vector by the local replica, if there is one
+    pub(crate) async fn get_local_vectors_usage(&self) -> Option<HashMap<String, VectorUsage>> {
+        let local_shard = self.local.read().await;
+        local_shard
+            .as_ref()
+            .map(|local_shard| local_shard.get_vectors_usage())
+    }
+
+    pub(crate) async fn subscribe_local_changes(&self) -> Option<ChangeReceiver> {
+        let local_shard = self.local.read().await;
+        local_shard.as_ref()?.subscribe_changes()
+    }
+
+    pub(crate) async fn local_data_checksum(&self) -> CollectionResult<Option<String>> {
+        let local_shard = self.local.read().await;
+        match local_shard.as_ref() {
+            Some(local_shard) => local_shard.data_checksum().await,
+            None => Ok(None),
+        }
+    }
+
     pub(crate) async fn health_check(&self, peer_id: PeerId) -> CollectionResult<()> {
         let remotes = self.remotes.read().await;
 
@@ -776,6 +872,55 @@ impl ShardReplicaSet {
         }
     }
 
+    /// Disable local replica, if background scrubbing found corrupted segments in it
+    ///
+    /// Consensus marks the disabled replica as dead, and it is recovered from another active
+    /// replica. The last active replica is kept, as there is nothing to recover it from.
+    fn quarantine_corrupted_local(&self) {
+        if !self.shared_storage_config.scrub_quarantine {
+            return;
+        }
+
+        let this_peer_id = self.this_peer_id();
+        if !self.peer_is_active(&this_peer_id) {
+            return;
+        }
+
+        let has_other_active_peer = self
+            .replica_state
+            .read()
+            .active_peers()
+            .into_iter()
+            .any(|peer_id| peer_id != this_peer_id && !self.is_locally_disabled(&peer_id));
+        if !has_other_active_peer {
+            return;
+        }
+
+        // Local shard is not awaited, corruption is checked again on the next sync
+        let Ok(local) = self.local.try_read() else {
+            return;
+        };
+        let Some(Shard::Local(local_shard)) = local.as_ref() else {
+            return;
+        };
+        let corrupted_segment = local_shard
+            .segments()
+            .read()
+            .corrupted_segments()
+            .next()
+            .map(|(segment_id, error)| (segment_id, error.to_string()));
+        drop(local);
+
+        if let Some((segment_id, error)) = corrupted_segment {
+            log::warn!(
+                "Quarantining replica {this_peer_id}/{}:{}, segment {segment_id} is corrupted: {error}",
+                self.collection_id,
+                self.shard_id,
+            );
+            self.add_locally_disabled(this_peer_id);
+        }
+    }
+
     // Make sure that locally disabled peers do not contradict the consensus
     fn update_locally_disabled(&self, peer_id_to_remove: PeerId) {
         // Check that we are not trying to disable the last active peer
@@ -823,6 +968,9 @@ pub struct ReplicaSetState {
     pub is_local: bool,
     pub this_peer_id: PeerId,
     peers: HashMap<PeerId, ReplicaState>,
+    /// Desired number of replicas of this shard, overrides the replication factor of the collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replication_factor: Option<NonZeroU32>,
 }
 
 impl ReplicaSetState {
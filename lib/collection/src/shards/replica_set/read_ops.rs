@@ -1,12 +1,16 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
 use futures::FutureExt as _;
+use segment::data_types::order_by::OrderBy;
 use segment::types::*;
 
 use super::ShardReplicaSet;
 use crate::operations::consistency_params::ReadConsistency;
 use crate::operations::types::*;
+use crate::shards::shard::PeerId;
+use crate::shards::shard_trait::ShardOperation as _;
 
 impl ShardReplicaSet {
     #[allow(clippy::too_many_arguments)]
@@ -19,16 +23,20 @@ impl ShardReplicaSet {
         filter: Option<&Filter>,
         read_consistency: Option<ReadConsistency>,
         local_only: bool,
+        order_by: Option<&OrderBy>,
+        sample: Option<Sample>,
     ) -> CollectionResult<Vec<Record>> {
         let with_payload_interface = Arc::new(with_payload_interface.clone());
         let with_vector = Arc::new(with_vector.clone());
         let filter = filter.map(|filter| Arc::new(filter.clone()));
+        let order_by = order_by.map(|order_by| Arc::new(order_by.clone()));
 
         self.execute_and_resolve_read_operation(
             |shard| {
                 let with_payload_interface = with_payload_interface.clone();
                 let with_vector = with_vector.clone();
                 let filter = filter.clone();
+                let order_by = order_by.clone();
                 let search_runtime = self.search_runtime.clone();
 
                 async move {
@@ -40,6 +48,8 @@ impl ShardReplicaSet {
                             &with_vector,
                             filter.as_deref(),
                             &search_runtime,
+                            order_by.as_deref(),
+                            sample,
                         )
                         .await
                 }
@@ -87,6 +97,25 @@ impl ShardReplicaSet {
         .await
     }
 
+    pub async fn facet(
+        &self,
+        request: Arc<FacetRequestInternal>,
+        read_consistency: Option<ReadConsistency>,
+        local_only: bool,
+    ) -> CollectionResult<FacetResult> {
+        self.execute_and_resolve_read_operation(
+            |shard| {
+                let request = request.clone();
+                let search_runtime = self.search_runtime.clone();
+
+                async move { shard.facet(request, &search_runtime).await }.boxed()
+            },
+            read_consistency,
+            local_only,
+        )
+        .await
+    }
+
     pub async fn retrieve(
         &self,
         request: Arc<PointRequestInternal>,
@@ -130,4 +159,38 @@ impl ShardReplicaSet {
             Some(shard) => Ok(Some(shard.get().count(request).await?)),
         }
     }
+
+    /// Exact number of points in every active replica of the shard
+    ///
+    /// Replicas are counted one after another, so concurrent updates can make the counts differ.
+    pub async fn count_active_replicas(&self) -> CollectionResult<HashMap<PeerId, usize>> {
+        let request = Arc::new(CountRequestInternal {
+            filter: None,
+            exact: true,
+        });
+        let active_peers: HashSet<_> = self
+            .replica_state
+            .read()
+            .active_peers()
+            .into_iter()
+            .collect();
+
+        let mut counts = HashMap::new();
+        if active_peers.contains(&self.this_peer_id()) {
+            if let Some(result) = self.count_local(request.clone()).await? {
+                counts.insert(self.this_peer_id(), result.count);
+            }
+        }
+
+        let remotes = self.remotes.read().await;
+        for remote in remotes
+            .iter()
+            .filter(|remote| active_peers.contains(&remote.peer_id))
+        {
+            let result = remote.count(request.clone()).await?;
+            counts.insert(remote.peer_id, result.count);
+        }
+
+        Ok(counts)
+    }
 }
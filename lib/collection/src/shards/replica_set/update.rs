@@ -419,6 +419,10 @@ mod tests {
             wal_config,
             hnsw_config: Default::default(),
             quantization_config: None,
+            metadata: None,
+            strict_mode_config: None,
+            rate_limit: None,
+            query_expansion: None,
         };
 
         let shared_config = Arc::new(RwLock::new(config.clone()));
@@ -4,7 +4,7 @@ use std::hash;
 use segment::types::{Payload, ScoredPoint};
 use tinyvec::TinyVec;
 
-use crate::operations::types::{CountResult, Record};
+use crate::operations::types::{CountResult, FacetResult, Record};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ResolveCondition {
@@ -41,6 +41,35 @@ impl Resolve for CountResult {
     }
 }
 
+impl Resolve for FacetResult {
+    fn resolve(results: Vec<Self>, condition: ResolveCondition) -> Self {
+        let replicas = results.len();
+
+        let mut counts_per_value: HashMap<String, Vec<usize>> = HashMap::new();
+        for result in results {
+            for hit in result.hits {
+                counts_per_value
+                    .entry(hit.value)
+                    .or_default()
+                    .push(hit.count);
+            }
+        }
+
+        let counts = counts_per_value.into_iter().map(|(value, mut counts)| {
+            // Replicas without the value did not count any points for it
+            counts.resize(replicas, 0);
+            counts.sort_unstable();
+            let count = match condition {
+                ResolveCondition::All => counts.first().copied().unwrap_or_default(),
+                ResolveCondition::Majority => counts.get(replicas / 2).copied().unwrap_or_default(),
+            };
+            (value, count)
+        });
+
+        FacetResult::from_counts(counts, None)
+    }
+}
+
 impl Resolve for Vec<Record> {
     fn resolve(records: Vec<Self>, condition: ResolveCondition) -> Self {
         let mut resolved = Resolver::resolve(records, |record| record.id, record_eq, condition);
@@ -407,6 +436,32 @@ mod test {
         );
     }
 
+    fn facet_result(hits: &[(&str, usize)]) -> FacetResult {
+        FacetResult::from_counts(
+            hits.iter()
+                .map(|(value, count)| (value.to_string(), *count)),
+            None,
+        )
+    }
+
+    #[test]
+    fn resolve_facet_results() {
+        let results = vec![
+            facet_result(&[("red", 10), ("green", 5), ("blue", 1)]),
+            facet_result(&[("red", 9), ("green", 5)]),
+            facet_result(&[("red", 10), ("green", 6), ("blue", 1)]),
+        ];
+
+        assert_eq!(
+            FacetResult::resolve(results.clone(), ResolveCondition::All),
+            facet_result(&[("red", 9), ("green", 5)]),
+        );
+        assert_eq!(
+            FacetResult::resolve(results, ResolveCondition::Majority),
+            facet_result(&[("red", 10), ("green", 5), ("blue", 1)]),
+        );
+    }
+
     fn data_simple() -> [i32; 9] {
         [1, 2, 3, 4, 5, 6, 7, 8, 9]
     }
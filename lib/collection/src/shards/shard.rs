@@ -1,12 +1,18 @@
 use core::marker::{Send, Sync};
+use std::collections::HashMap;
 use std::future::{self, Future};
 use std::path::Path;
 
+use segment::types::{PayloadFieldStatistics, PayloadKeyType};
+use tokio::runtime::Handle;
+
+use super::change_feed::ChangeReceiver;
 use super::update_tracker::UpdateTracker;
-use crate::operations::types::CollectionResult;
+use crate::operations::types::{CollectionResult, VectorUsage};
 use crate::shards::dummy_shard::DummyShard;
 use crate::shards::forward_proxy_shard::ForwardProxyShard;
 use crate::shards::local_shard::LocalShard;
+use crate::shards::local_shard_cursor::{CursorQuery, LocalShardCursor};
 use crate::shards::proxy_shard::ProxyShard;
 use crate::shards::queue_proxy_shard::QueueProxyShard;
 use crate::shards::shard_trait::ShardOperation;
@@ -74,6 +80,69 @@ impl Shard {
         telemetry
     }
 
+    pub fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        match self {
+            Shard::Local(local_shard) => local_shard.get_field_statistics(),
+            Shard::Proxy(proxy_shard) => proxy_shard.get_field_statistics(),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.get_field_statistics(),
+            Shard::QueueProxy(proxy_shard) => proxy_shard.get_field_statistics(),
+            Shard::Dummy(dummy_shard) => dummy_shard.get_field_statistics(),
+        }
+    }
+
+    pub fn get_vectors_usage(&self) -> HashMap<String, VectorUsage> {
+        match self {
+            Shard::Local(local_shard) => local_shard.get_vectors_usage(),
+            Shard::Proxy(proxy_shard) => proxy_shard.get_vectors_usage(),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.get_vectors_usage(),
+            Shard::QueueProxy(proxy_shard) => proxy_shard.get_vectors_usage(),
+            Shard::Dummy(dummy_shard) => dummy_shard.get_vectors_usage(),
+        }
+    }
+
+    /// Receive operations of the local shard, `None` if there is no local data
+    pub fn subscribe_changes(&self) -> Option<ChangeReceiver> {
+        match self {
+            Shard::Local(local_shard) => Some(local_shard.subscribe_changes()),
+            Shard::Proxy(proxy_shard) => Some(proxy_shard.subscribe_changes()),
+            Shard::ForwardProxy(proxy_shard) => Some(proxy_shard.subscribe_changes()),
+            Shard::QueueProxy(proxy_shard) => Some(proxy_shard.subscribe_changes()),
+            Shard::Dummy(_) => None,
+        }
+    }
+
+    /// Checksum of points of the local shard, `None` if there is no local data
+    pub async fn data_checksum(&self) -> CollectionResult<Option<String>> {
+        let checksum = match self {
+            Shard::Local(local_shard) => local_shard.data_checksum().await?,
+            Shard::Proxy(proxy_shard) => proxy_shard.data_checksum().await?,
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.data_checksum().await?,
+            Shard::QueueProxy(proxy_shard) => proxy_shard.data_checksum().await?,
+            Shard::Dummy(_) => return Ok(None),
+        };
+        Ok(Some(checksum))
+    }
+
+    /// Cursor over the points of the local shard, `None` if there is no local data
+    pub async fn cursor(
+        &self,
+        query: &CursorQuery,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Option<LocalShardCursor>> {
+        let cursor = match self {
+            Shard::Local(local_shard) => local_shard.cursor(query, search_runtime_handle).await?,
+            Shard::Proxy(proxy_shard) => proxy_shard.cursor(query, search_runtime_handle).await?,
+            Shard::ForwardProxy(proxy_shard) => {
+                proxy_shard.cursor(query, search_runtime_handle).await?
+            }
+            Shard::QueueProxy(proxy_shard) => {
+                proxy_shard.cursor(query, search_runtime_handle).await?
+            }
+            Shard::Dummy(_) => return Ok(None),
+        };
+        Ok(Some(cursor))
+    }
+
     pub async fn create_snapshot(
         &self,
         temp_path: &Path,
@@ -32,11 +32,18 @@ use crate::shards::CollectionId;
 
 const HASH_RING_SHARD_SCALE: u32 = 100;
 
+/// Number of keys used to estimate the share of each shard on the hash ring
+const HASH_RING_SHARE_PROBES: u64 = 10_000;
+
 const SHARD_TRANSFERS_FILE: &str = "shard_transfers";
 pub const SHARD_KEY_MAPPING_FILE: &str = "shard_key_mapping.json";
+const SHARD_KEY_SPLITS_FILE: &str = "shard_key_splits.json";
 
 pub type ShardKeyMapping = HashMap<ShardKey, HashSet<ShardId>>;
 
+/// New shards of each shard key, which are still being filled with the points of the key
+pub type ShardKeySplits = HashMap<ShardKey, HashSet<ShardId>>;
+
 pub struct ShardHolder {
     shards: HashMap<ShardId, ShardReplicaSet>,
     pub(crate) shard_transfers: SaveOnDisk<HashSet<ShardTransfer>>,
@@ -45,6 +52,11 @@ pub struct ShardHolder {
     // Duplicates the information from `key_mapping` for faster access
     // Do not require locking
     shard_id_to_key_mapping: HashMap<ShardId, ShardKey>,
+    // Shard keys, which are being split into additional shards.
+    // New shards are not used for routing and reads until the split is finished.
+    key_splits: SaveOnDisk<ShardKeySplits>,
+    // Hash rings of the split shard keys, including the new shards
+    split_rings: HashMap<ShardKey, HashRing<ShardId>>,
 }
 
 pub type LockedShardHolder = RwLock<ShardHolder>;
@@ -56,6 +68,8 @@ impl ShardHolder {
         let shard_transfers = SaveOnDisk::load_or_init(collection_path.join(SHARD_TRANSFERS_FILE))?;
         let key_mapping: SaveOnDisk<ShardKeyMapping> =
             SaveOnDisk::load_or_init(collection_path.join(SHARD_KEY_MAPPING_FILE))?;
+        let key_splits: SaveOnDisk<ShardKeySplits> =
+            SaveOnDisk::load_or_init(collection_path.join(SHARD_KEY_SPLITS_FILE))?;
         let mut shard_id_to_key_mapping = HashMap::new();
 
         for (shard_key, shard_ids) in key_mapping.read().iter() {
@@ -70,6 +84,8 @@ impl ShardHolder {
             rings,
             key_mapping,
             shard_id_to_key_mapping,
+            key_splits,
+            split_rings: HashMap::new(),
         })
     }
 
@@ -103,10 +119,7 @@ impl ShardHolder {
         shard_key: Option<ShardKey>,
     ) -> Result<(), CollectionError> {
         self.shards.insert(shard_id, shard);
-        self.rings
-            .entry(shard_key.clone())
-            .or_insert_with(|| HashRing::fair(HASH_RING_SHARD_SCALE))
-            .add(shard_id);
+        self.add_shard_to_rings(shard_id, shard_key.clone());
 
         if let Some(shard_key) = shard_key {
             self.key_mapping.write_optional(|key_mapping| {
@@ -128,6 +141,96 @@ impl ShardHolder {
         Ok(())
     }
 
+    /// Add shard to the hash ring of its shard key.
+    ///
+    /// New shards of a split shard key are only added to the ring of the split.
+    fn add_shard_to_rings(&mut self, shard_id: ShardId, shard_key: Option<ShardKey>) {
+        let split_shard_ids = shard_key
+            .as_ref()
+            .and_then(|shard_key| self.key_splits.read().get(shard_key).cloned());
+
+        if let (Some(shard_key), Some(split_shard_ids)) = (&shard_key, split_shard_ids) {
+            self.split_rings
+                .entry(shard_key.clone())
+                .or_insert_with(|| HashRing::fair(HASH_RING_SHARD_SCALE))
+                .add(shard_id);
+            if split_shard_ids.contains(&shard_id) {
+                return;
+            }
+        }
+
+        self.rings
+            .entry(shard_key)
+            .or_insert_with(|| HashRing::fair(HASH_RING_SHARD_SCALE))
+            .add(shard_id);
+    }
+
+    /// Start splitting the shard key into additional shards.
+    ///
+    /// New shards only receive copies of the update operations, until the split is finished
+    /// with [`ShardHolder::finish_shard_key_split`].
+    pub fn start_shard_key_split(
+        &mut self,
+        shard_key: ShardKey,
+        new_shards: Vec<(ShardId, ShardReplicaSet)>,
+    ) -> Result<(), CollectionError> {
+        let new_shard_ids: HashSet<_> = new_shards.iter().map(|(shard_id, _)| *shard_id).collect();
+
+        self.key_splits.write_optional(|key_splits| {
+            let mut copy_of_splits = key_splits.clone();
+            copy_of_splits
+                .entry(shard_key.clone())
+                .or_default()
+                .extend(new_shard_ids.iter().copied());
+            Some(copy_of_splits)
+        })?;
+
+        for (shard_id, replica_set) in new_shards {
+            self.add_shard(shard_id, replica_set, Some(shard_key.clone()))?;
+        }
+
+        self.rebuild_rings();
+        Ok(())
+    }
+
+    /// Start using the new shards of the split shard key for routing and reads
+    pub fn finish_shard_key_split(&mut self, shard_key: &ShardKey) -> Result<(), CollectionError> {
+        self.key_splits.write_optional(|key_splits| {
+            if !key_splits.contains_key(shard_key) {
+                return None;
+            }
+            let mut copy_of_splits = key_splits.clone();
+            copy_of_splits.remove(shard_key);
+            Some(copy_of_splits)
+        })?;
+
+        self.rebuild_rings();
+        Ok(())
+    }
+
+    /// Shard keys which are being split, with the new shards of each key
+    pub fn get_shard_key_splits(&self) -> ShardKeySplits {
+        self.key_splits.read().clone()
+    }
+
+    /// Hash ring of the split shard key, which includes the new shards
+    pub fn split_ring(&self, shard_key: &ShardKey) -> Option<&HashRing<ShardId>> {
+        self.split_rings.get(shard_key)
+    }
+
+    /// Whether the shard is a new shard of a split, which is not finished yet
+    fn is_split_shard(&self, shard_id: &ShardId) -> bool {
+        self.shard_id_to_key_mapping
+            .get(shard_id)
+            .and_then(|shard_key| {
+                self.key_splits
+                    .read()
+                    .get(shard_key)
+                    .map(|shard_ids| shard_ids.contains(shard_id))
+            })
+            .unwrap_or(false)
+    }
+
     pub async fn remove_shard_key(&mut self, shard_key: &ShardKey) -> Result<(), CollectionError> {
         let mut remove_shard_ids = Vec::new();
 
@@ -145,7 +248,17 @@ impl ShardHolder {
             }
         })?;
 
+        self.key_splits.write_optional(|key_splits| {
+            if !key_splits.contains_key(shard_key) {
+                return None;
+            }
+            let mut copy_of_splits = key_splits.clone();
+            copy_of_splits.remove(shard_key);
+            Some(copy_of_splits)
+        })?;
+
         self.rings.remove(&Some(shard_key.clone()));
+        self.split_rings.remove(shard_key);
         for shard_id in remove_shard_ids {
             self.drop_and_remove_shard(shard_id).await?;
             self.shard_id_to_key_mapping.remove(&shard_id);
@@ -154,18 +267,23 @@ impl ShardHolder {
     }
 
     fn rebuild_rings(&mut self) {
-        let mut rings = HashMap::new();
-        rings.insert(None, HashRing::fair(HASH_RING_SHARD_SCALE));
-        let ids_to_key = self.get_shard_id_to_key_mapping();
-        for shard_id in self.shards.keys() {
-            let shard_key = ids_to_key.get(shard_id).cloned();
-            rings
-                .entry(shard_key)
-                .or_insert_with(|| HashRing::fair(HASH_RING_SHARD_SCALE))
-                .add(*shard_id);
-        }
+        self.rings = HashMap::new();
+        self.rings
+            .insert(None, HashRing::fair(HASH_RING_SHARD_SCALE));
+        self.split_rings = HashMap::new();
+
+        let shards: Vec<_> = self
+            .shards
+            .keys()
+            .map(|shard_id| {
+                let shard_key = self.shard_id_to_key_mapping.get(shard_id).cloned();
+                (*shard_id, shard_key)
+            })
+            .collect();
 
-        self.rings = rings;
+        for (shard_id, shard_key) in shards {
+            self.add_shard_to_rings(shard_id, shard_key);
+        }
     }
 
     pub async fn apply_shards_state(
@@ -192,6 +310,19 @@ impl ShardHolder {
         Ok(())
     }
 
+    /// Estimated share of point ids, assigned to each shard by the hash ring of every shard key
+    pub fn hash_ring_shares(&self) -> Vec<(Option<ShardKey>, HashMap<ShardId, f64>)> {
+        self.rings
+            .iter()
+            .map(|(shard_key, ring)| {
+                (
+                    shard_key.clone(),
+                    ring.estimate_shares(HASH_RING_SHARE_PROBES),
+                )
+            })
+            .collect()
+    }
+
     /// Take shard
     ///
     /// remove shard and return ownership
@@ -254,11 +385,14 @@ impl ShardHolder {
                         .unwrap_or_default();
                     shard_ids
                         .into_iter()
+                        .filter(|shard_id| !self.is_split_shard(shard_id))
                         .map(|shard_id| (self.shards.get(&shard_id).unwrap(), operation.clone()))
                         .collect()
                 } else {
-                    self.all_shards()
-                        .map(|shard| (shard, operation.clone()))
+                    self.shards
+                        .iter()
+                        .filter(|(shard_id, _)| !self.is_split_shard(shard_id))
+                        .map(|(_, shard)| (shard, operation.clone()))
                         .collect()
                 }
             }
@@ -266,6 +400,42 @@ impl ShardHolder {
         Ok(shard_ops)
     }
 
+    /// Split operation between the new shards of a split shard key.
+    ///
+    /// New shards must receive the same updates as the shards they take the points from,
+    /// so the copied points stay up to date until the split is finished.
+    /// Returns no operations if the shard key is not being split.
+    pub fn split_by_new_shards<O: SplitByShard + Clone>(
+        &self,
+        operation: O,
+        shard_keys_selection: &Option<ShardKey>,
+    ) -> Vec<(&ShardReplicaSet, O)> {
+        let Some(shard_key) = shard_keys_selection else {
+            return vec![];
+        };
+        let Some(new_shard_ids) = self.key_splits.read().get(shard_key).cloned() else {
+            return vec![];
+        };
+        let Some(split_ring) = self.split_rings.get(shard_key) else {
+            return vec![];
+        };
+
+        match operation.split_by_shard(split_ring) {
+            OperationToShard::ByShard(by_shard) => by_shard
+                .into_iter()
+                .filter(|(shard_id, _)| new_shard_ids.contains(shard_id))
+                .filter_map(|(shard_id, operation)| {
+                    self.shards.get(&shard_id).map(|shard| (shard, operation))
+                })
+                .collect(),
+            OperationToShard::ToAll(operation) => new_shard_ids
+                .iter()
+                .filter_map(|shard_id| self.shards.get(shard_id))
+                .map(|shard| (shard, operation.clone()))
+                .collect(),
+        }
+    }
+
     pub fn register_start_shard_transfer(&self, transfer: ShardTransfer) -> CollectionResult<bool> {
         Ok(self
             .shard_transfers
@@ -335,12 +505,18 @@ impl ShardHolder {
             }
             ShardSelectorInternal::All => {
                 for (shard_id, shard) in self.shards.iter() {
+                    if self.is_split_shard(shard_id) {
+                        continue;
+                    }
                     let shard_key = self.shard_id_to_key_mapping.get(shard_id);
                     res.push((shard, shard_key));
                 }
             }
             ShardSelectorInternal::ShardKey(shard_key) => {
                 for shard_id in self.get_shard_ids_by_key(shard_key)? {
+                    if self.is_split_shard(&shard_id) {
+                        continue;
+                    }
                     if let Some(replica_set) = self.shards.get(&shard_id) {
                         res.push((replica_set, Some(shard_key)));
                     } else {
@@ -351,6 +527,9 @@ impl ShardHolder {
             ShardSelectorInternal::ShardKeys(shard_keys) => {
                 for shard_key in shard_keys {
                     for shard_id in self.get_shard_ids_by_key(shard_key)? {
+                        if self.is_split_shard(&shard_id) {
+                            continue;
+                        }
                         if let Some(replica_set) = self.shards.get(&shard_id) {
                             res.push((replica_set, Some(shard_key)));
                         } else {
@@ -2,6 +2,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
@@ -9,7 +10,7 @@ use tokio::runtime::Handle;
 
 use crate::operations::types::{
     CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequestInternal, CountResult,
-    PointRequestInternal, Record, UpdateResult,
+    FacetRequestInternal, FacetResult, PointRequestInternal, Record, Sample, UpdateResult,
 };
 use crate::operations::CollectionUpdateOperations;
 
@@ -21,6 +22,9 @@ pub trait ShardOperation {
         wait: bool,
     ) -> CollectionResult<UpdateResult>;
 
+    /// Read a page of points, which satisfy filtering condition.
+    ///
+    /// Unless `order_by` or `sample` is used, points are returned in ascending order of their IDs.
     #[allow(clippy::too_many_arguments)]
     async fn scroll_by(
         &self,
@@ -30,6 +34,8 @@ pub trait ShardOperation {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
+        sample: Option<Sample>,
     ) -> CollectionResult<Vec<Record>>;
 
     async fn info(&self) -> CollectionResult<CollectionInfo>;
@@ -43,6 +49,12 @@ pub trait ShardOperation {
 
     async fn count(&self, request: Arc<CountRequestInternal>) -> CollectionResult<CountResult>;
 
+    async fn facet(
+        &self,
+        request: Arc<FacetRequestInternal>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<FacetResult>;
+
     async fn retrieve(
         &self,
         request: Arc<PointRequestInternal>,
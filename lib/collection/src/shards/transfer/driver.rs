@@ -8,6 +8,7 @@ use tokio::time::sleep;
 use super::snapshot::transfer_snapshot;
 use super::stream_records::transfer_stream_records;
 use super::{ShardTransfer, ShardTransferConsensus, ShardTransferMethod};
+use crate::common::maintenance_hooks::{HookStage, MaintenanceHooks, MaintenanceOperation};
 use crate::common::stoppable_task_async::{spawn_async_cancellable, CancellableAsyncTaskHandle};
 use crate::operations::types::CollectionResult;
 use crate::shards::channel_service::ChannelService;
@@ -182,6 +183,7 @@ pub fn spawn_transfer_task<T, F>(
     snapshots_path: PathBuf,
     collection_name: String,
     temp_dir: PathBuf,
+    maintenance_hooks: MaintenanceHooks,
     on_finish: T,
     on_error: F,
 ) -> CancellableAsyncTaskHandle<bool>
@@ -192,6 +194,10 @@ where
     spawn_async_cancellable(move |cancel| async move {
         let mut result = Err(cancel::Error::Cancelled);
 
+        maintenance_hooks
+            .run(MaintenanceOperation::Transfer, HookStage::Before, None)
+            .await;
+
         for attempt in 0..MAX_RETRY_COUNT {
             let future = async {
                 if attempt > 0 {
@@ -250,6 +256,13 @@ where
         }
 
         let is_ok = matches!(result, Ok(Ok(())));
+        maintenance_hooks
+            .run(
+                MaintenanceOperation::Transfer,
+                HookStage::After,
+                Some(is_ok),
+            )
+            .await;
         is_ok
     })
 }
@@ -166,12 +166,18 @@ pub fn suggest_transfer_source(
 ///
 /// Requirements:
 /// 1. Peer should not have an active replica of the shard
-/// 2. Peer should have minimal number of active transfers
+/// 2. Peer should have minimal number of shard replicas, peers without any replicas are preferred
+///
+/// Ties are resolved by the lowest peer id, so that all peers agree on the same suggestion.
 pub fn suggest_peer_to_add_replica(
     shard_id: ShardId,
     shard_distribution: HashMap<ShardId, HashSet<PeerId>>,
+    known_peers: &HashSet<PeerId>,
 ) -> Option<PeerId> {
-    let mut peer_loads: HashMap<PeerId, usize> = HashMap::new();
+    let mut peer_loads: HashMap<PeerId, usize> = known_peers
+        .iter()
+        .map(|peer_id| (*peer_id, 0_usize))
+        .collect();
     for peers in shard_distribution.values() {
         for peer_id in peers {
             *peer_loads.entry(*peer_id).or_insert(0_usize) += 1;
@@ -186,7 +192,7 @@ pub fn suggest_peer_to_add_replica(
     }
 
     let mut candidates = peer_loads.into_iter().collect::<Vec<(PeerId, usize)>>();
-    candidates.sort_unstable_by_key(|(_, count)| *count);
+    candidates.sort_unstable_by_key(|(peer_id, count)| (*count, *peer_id));
     candidates.first().map(|(peer_id, _)| *peer_id)
 }
 
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use parking_lot::Mutex;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+use crate::operations::point_ops::{PointInsertOperationsInternal, PointOperations, PointStruct};
+use crate::operations::types::{CollectionError, CollectionResult, UpdateResult, UpdateStatus};
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::change_feed::ChangeFeed;
+use crate::shards::local_shard::LockedWal;
+use crate::update_handler::{OperationData, UpdateSignal};
+
+/// Upserts with more points than this are large enough to be applied on their own
+const MAX_BATCHED_UPSERT_POINTS: usize = 8;
+
+type UpsertResultSender = oneshot::Sender<CollectionResult<UpdateResult>>;
+
+pub type UpsertResultReceiver = oneshot::Receiver<CollectionResult<UpdateResult>>;
+
+/// Upserts collected during the current batching window
+struct PendingUpserts {
+    points: Vec<PointStruct>,
+    /// Callbacks of the requests, merged into this batch, with their `wait` flag
+    waiters: Vec<(bool, UpsertResultSender)>,
+}
+
+/// Coalesces small upserts, arriving within a short window, into a single operation.
+///
+/// The merged operation takes a single WAL entry and a single segment update,
+/// while every submitted request still gets its own response.
+/// Points are merged in the order of arrival, so the last upsert of a point wins.
+pub struct UpsertBatcher {
+    window: Duration,
+    wal: LockedWal,
+    update_sender: Arc<ArcSwap<Sender<UpdateSignal>>>,
+    change_feed: ChangeFeed,
+    pending: Mutex<Option<PendingUpserts>>,
+    runtime: Handle,
+}
+
+impl UpsertBatcher {
+    pub fn new(
+        window: Duration,
+        wal: LockedWal,
+        update_sender: Arc<ArcSwap<Sender<UpdateSignal>>>,
+        change_feed: ChangeFeed,
+        runtime: Handle,
+    ) -> Self {
+        Self {
+            window,
+            wal,
+            update_sender,
+            change_feed,
+            pending: Mutex::new(None),
+            runtime,
+        }
+    }
+
+    /// Extract points of a small upsert, which is worth batching.
+    ///
+    /// Returns the operation back if it should be applied on its own.
+    pub fn batchable_points(
+        operation: CollectionUpdateOperations,
+    ) -> Result<Vec<PointStruct>, CollectionUpdateOperations> {
+        match operation {
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(insert))
+                if insert_points_count(&insert) <= MAX_BATCHED_UPSERT_POINTS =>
+            {
+                Ok(insert.into_point_vec())
+            }
+            operation => Err(operation),
+        }
+    }
+
+    /// Add points to the current batch, starting a new batching window if there is none.
+    ///
+    /// Returned receiver resolves once the batch is written into WAL, or once it is applied
+    /// if `wait` is set.
+    pub fn submit(self: &Arc<Self>, points: Vec<PointStruct>, wait: bool) -> UpsertResultReceiver {
+        let (sender, receiver) = oneshot::channel();
+
+        let mut pending = self.pending.lock();
+        match pending.as_mut() {
+            Some(batch) => {
+                batch.points.extend(points);
+                batch.waiters.push((wait, sender));
+            }
+            None => {
+                *pending = Some(PendingUpserts {
+                    points,
+                    waiters: vec![(wait, sender)],
+                });
+
+                // Flush in a separate task, so the batch doesn't depend on the request,
+                // which opened the window, being polled till the end
+                let batcher = self.clone();
+                self.runtime.spawn(async move {
+                    tokio::time::sleep(batcher.window).await;
+                    batcher.flush().await;
+                });
+            }
+        }
+
+        receiver
+    }
+
+    async fn flush(&self) {
+        let update_sender = self.update_sender.load_full();
+
+        let channel_permit = match update_sender.reserve().await {
+            Ok(permit) => permit,
+            Err(err) => {
+                if let Some(batch) = self.pending.lock().take() {
+                    let err = CollectionError::from(err);
+                    notify_waiters(batch.waiters, || Err(err.clone()));
+                }
+                return;
+            }
+        };
+
+        let (callback_sender, callback_receiver) = oneshot::channel();
+
+        // Take the batch and write it into WAL under the same lock,
+        // so consecutive batches can't be reordered
+        let (operation_id, waiters) = {
+            let mut pending = self.pending.lock();
+            let Some(batch) = pending.take() else {
+                return;
+            };
+
+            let wait = batch.waiters.iter().any(|(wait, _)| *wait);
+
+            let operation =
+                CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                    PointInsertOperationsInternal::PointsList(batch.points),
+                ));
+
+            let mut wal = self.wal.lock();
+            let operation_id = match wal.write(&operation) {
+                Ok(operation_id) => operation_id,
+                Err(err) => {
+                    let err = CollectionError::from(err);
+                    notify_waiters(batch.waiters, || Err(err.clone()));
+                    return;
+                }
+            };
+            self.change_feed.publish(operation_id, &operation);
+            drop(wal);
+
+            channel_permit.send(UpdateSignal::Operation(OperationData {
+                op_num: operation_id,
+                operation,
+                sender: wait.then_some(callback_sender),
+                wait,
+            }));
+
+            (operation_id, batch.waiters)
+        };
+
+        let (waiting, acknowledged): (Vec<_>, Vec<_>) =
+            waiters.into_iter().partition(|(wait, _)| *wait);
+
+        notify_waiters(acknowledged, || {
+            Ok(UpdateResult {
+                operation_id: Some(operation_id),
+                status: UpdateStatus::Acknowledged,
+                points: None,
+            })
+        });
+
+        if waiting.is_empty() {
+            return;
+        }
+
+        let result = match callback_receiver.await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(err)) => Err(err),
+            Err(err) => Err(CollectionError::from(err)),
+        };
+
+        notify_waiters(waiting, || {
+            result.clone().map(|_| UpdateResult {
+                operation_id: Some(operation_id),
+                status: UpdateStatus::Completed,
+                points: None,
+            })
+        });
+    }
+}
+
+fn insert_points_count(insert: &PointInsertOperationsInternal) -> usize {
+    match insert {
+        PointInsertOperationsInternal::PointsBatch(batch) => batch.ids.len(),
+        PointInsertOperationsInternal::PointsList(points) => points.len(),
+    }
+}
+
+fn notify_waiters(
+    waiters: Vec<(bool, UpsertResultSender)>,
+    result: impl Fn() -> CollectionResult<UpdateResult>,
+) {
+    for (_, sender) in waiters {
+        // Receiver might be already dropped if request was cancelled
+        let _ = sender.send(result());
+    }
+}
@@ -46,6 +46,10 @@ impl Anonymize for CollectionConfig {
             optimizer_config: self.optimizer_config.clone(),
             wal_config: self.wal_config.clone(),
             quantization_config: self.quantization_config.clone(),
+            metadata: None,
+            strict_mode_config: self.strict_mode_config.clone(),
+            rate_limit: self.rate_limit.clone(),
+            query_expansion: self.query_expansion.clone(),
         }
     }
 }
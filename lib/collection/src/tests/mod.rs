@@ -1,5 +1,7 @@
+mod point_expiration_test;
 mod snapshot_test;
 mod sparse_vectors_validation_tests;
+mod upsert_batching_test;
 mod wal_recovery_test;
 
 use std::sync::Arc;
@@ -11,12 +13,12 @@ use parking_lot::{Mutex, RwLock};
 use tempfile::Builder;
 use tokio::time::{sleep, Instant};
 
-use crate::collection::Collection;
 use crate::collection_manager::fixtures::{
     get_indexing_optimizer, get_merge_optimizer, random_segment,
 };
 use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder, SegmentId};
 use crate::collection_manager::optimizers::TrackerStatus;
+use crate::common::maintenance_hooks::MaintenanceHooks;
 use crate::update_handler::{Optimizer, UpdateHandler};
 
 #[tokio::test]
@@ -53,6 +55,7 @@ async fn test_optimization_process() {
         optimizers.clone(),
         optimizers_log.clone(),
         segments.clone(),
+        MaintenanceHooks::default(),
         |_| {},
     );
 
@@ -74,6 +77,7 @@ async fn test_optimization_process() {
         optimizers.clone(),
         optimizers_log.clone(),
         segments.clone(),
+        MaintenanceHooks::default(),
         |_| {},
     );
 
@@ -118,6 +122,7 @@ async fn test_cancel_optimization() {
         optimizers.clone(),
         optimizers_log.clone(),
         segments.clone(),
+        MaintenanceHooks::default(),
         |_| {},
     );
 
@@ -152,27 +157,3 @@ async fn test_cancel_optimization() {
         }
     }
 }
-
-#[test]
-fn check_version_upgrade() {
-    assert!(!Collection::can_upgrade_storage(
-        &"0.3.1".parse().unwrap(),
-        &"0.4.0".parse().unwrap()
-    ));
-    assert!(!Collection::can_upgrade_storage(
-        &"0.4.0".parse().unwrap(),
-        &"0.5.0".parse().unwrap()
-    ));
-    assert!(!Collection::can_upgrade_storage(
-        &"0.4.0".parse().unwrap(),
-        &"0.4.2".parse().unwrap()
-    ));
-    assert!(Collection::can_upgrade_storage(
-        &"0.4.0".parse().unwrap(),
-        &"0.4.1".parse().unwrap()
-    ));
-    assert!(Collection::can_upgrade_storage(
-        &"0.4.1".parse().unwrap(),
-        &"0.4.2".parse().unwrap()
-    ));
-}
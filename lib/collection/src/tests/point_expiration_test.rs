@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use parking_lot::{Mutex, RwLock};
+use segment::types::PointIdType;
+use serde_json::json;
+use tempfile::Builder;
+use tokio::sync::mpsc;
+use wal::WalOptions;
+
+use crate::collection_manager::collection_updater::CollectionUpdater;
+use crate::collection_manager::fixtures::empty_segment;
+use crate::collection_manager::holders::segment_holder::{LockedSegmentHolder, SegmentHolder};
+use crate::operations::point_ops::{PointOperations, PointStruct};
+use crate::operations::point_timestamps::EXPIRE_AT_KEY;
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::change_feed::ChangeFeed;
+use crate::shards::local_shard::LockedWal;
+use crate::update_handler::{UpdateHandler, UpdateSignal};
+use crate::wal::SerdeWal;
+
+fn upsert_point(segments: &LockedSegmentHolder, wal: &LockedWal, point_id: u64, expire_at: u64) {
+    let operation = CollectionUpdateOperations::PointOperation(
+        vec![PointStruct {
+            id: point_id.into(),
+            vector: vec![1.0, 2.0, 3.0, 4.0].into(),
+            payload: Some(json!({ EXPIRE_AT_KEY: expire_at }).into()),
+        }]
+        .into(),
+    );
+    let op_num = wal.lock().write(&operation).unwrap();
+    CollectionUpdater::update(segments, op_num, operation).unwrap();
+}
+
+fn has_point(segments: &LockedSegmentHolder, point_id: PointIdType) -> bool {
+    segments
+        .read()
+        .iter()
+        .any(|(_, segment)| segment.get().read().has_point(point_id))
+}
+
+#[tokio::test]
+async fn test_remove_expired_points() {
+    let segments_dir = Builder::new().prefix("segments").tempdir().unwrap();
+    let wal_dir = Builder::new().prefix("wal").tempdir().unwrap();
+
+    let mut holder = SegmentHolder::default();
+    holder.add(empty_segment(segments_dir.path()));
+    let segments: LockedSegmentHolder = Arc::new(RwLock::new(holder));
+
+    let wal = SerdeWal::new(
+        wal_dir.path().to_str().unwrap(),
+        WalOptions {
+            segment_capacity: 1024 * 1024,
+            segment_queue_len: 0,
+        },
+    )
+    .unwrap();
+    let wal: LockedWal = Arc::new(Mutex::new(wal));
+
+    let (update_sender, mut update_receiver) = mpsc::channel(16);
+    let change_feed = ChangeFeed::default();
+    let mut changes = change_feed.subscribe();
+
+    upsert_point(&segments, &wal, 1, 1_000);
+    upsert_point(&segments, &wal, 2, u64::MAX / 2);
+
+    let op_num =
+        UpdateHandler::remove_expired_points(&segments, &wal, &update_sender, &change_feed)
+            .await
+            .unwrap()
+            .unwrap();
+
+    // Deletion is persisted as a regular operation, and sent to the update worker
+    assert_eq!(wal.lock().len(), 3);
+    let Some(UpdateSignal::Operation(operation)) = update_receiver.try_recv().ok() else {
+        panic!("Deletion of expired points is not sent to the update worker");
+    };
+    assert_eq!(operation.op_num, op_num);
+    assert!(has_point(&segments, 1.into()));
+
+    // Deletion is visible to the subscribers of the change feed
+    let change = changes.try_recv().unwrap();
+    assert_eq!(change.operation_id, op_num);
+    assert!(matches!(
+        change.operation,
+        CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(_)),
+    ));
+
+    let removed =
+        CollectionUpdater::update(&segments, operation.op_num, operation.operation).unwrap();
+    assert_eq!(removed, 1);
+    assert!(!has_point(&segments, 1.into()));
+    assert!(has_point(&segments, 2.into()));
+
+    // Nothing is written, if there is nothing to remove
+    let op_num =
+        UpdateHandler::remove_expired_points(&segments, &wal, &update_sender, &change_feed)
+            .await
+            .unwrap();
+    assert_eq!(op_num, None);
+    assert_eq!(wal.lock().len(), 3);
+    assert!(update_receiver.try_recv().is_err());
+    assert!(changes.try_recv().is_err());
+}
@@ -67,6 +67,10 @@ async fn _test_snapshot_collection(node_type: NodeType) {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        metadata: None,
+        strict_mode_config: None,
+        rate_limit: None,
+        query_expansion: None,
     };
 
     let snapshots_path = Builder::new().prefix("test_snapshots").tempdir().unwrap();
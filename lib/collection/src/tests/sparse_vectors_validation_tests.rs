@@ -63,6 +63,8 @@ fn validate_error_sparse_vector_points_batch() {
             payloads: None,
         },
         shard_key: None,
+        condition: None,
+        if_version: None,
     });
 }
 
@@ -71,6 +73,8 @@ fn validate_error_sparse_vector_points_list() {
     check_validation_error(PointsList {
         points: vec![wrong_point_struct()],
         shard_key: None,
+        condition: None,
+        if_version: None,
     });
 }
 
@@ -116,6 +120,8 @@ fn validate_error_sparse_vector_recommend_request_internal() {
     check_validation_error(RecommendRequestInternal {
         positive: vec![wrong_recommend_example()],
         negative: vec![wrong_recommend_example()],
+        positive_weights: None,
+        negative_weights: None,
         strategy: None,
         filter: None,
         params: None,
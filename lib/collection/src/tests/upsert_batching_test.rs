@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+use std::num::NonZeroU64;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::join_all;
+use segment::types::Distance;
+use tempfile::Builder;
+use tokio::runtime::Handle;
+use tokio::sync::RwLock;
+
+use crate::config::{CollectionConfig, CollectionParams, WalConfig};
+use crate::operations::point_ops::{PointOperations, PointStruct};
+use crate::operations::shared_storage_config::SharedStorageConfig;
+use crate::operations::types::{CountRequestInternal, UpdateStatus, VectorParams, VectorsConfig};
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::local_shard::LocalShard;
+use crate::shards::shard_trait::ShardOperation;
+use crate::tests::snapshot_test::TEST_OPTIMIZERS_CONFIG;
+
+fn create_collection_config() -> CollectionConfig {
+    let collection_params = CollectionParams {
+        vectors: VectorsConfig::Single(VectorParams {
+            size: NonZeroU64::new(4).unwrap(),
+            distance: Distance::Dot,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+        }),
+        ..CollectionParams::empty()
+    };
+
+    CollectionConfig {
+        params: collection_params,
+        optimizer_config: TEST_OPTIMIZERS_CONFIG.clone(),
+        wal_config: WalConfig {
+            wal_capacity_mb: 1,
+            wal_segments_ahead: 0,
+        },
+        hnsw_config: Default::default(),
+        quantization_config: Default::default(),
+        metadata: None,
+        strict_mode_config: None,
+        rate_limit: None,
+        query_expansion: None,
+    }
+}
+
+fn single_point_upsert(id: u64, vector: Vec<f32>) -> CollectionUpdateOperations {
+    CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        vec![PointStruct {
+            id: id.into(),
+            vector: vector.into(),
+            payload: None,
+        }]
+        .into(),
+    ))
+}
+
+async fn count_points(shard: &LocalShard) -> usize {
+    let request = CountRequestInternal {
+        filter: None,
+        exact: true,
+    };
+    shard.count(Arc::new(request)).await.unwrap().count
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrent_upserts_are_batched() {
+    let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+
+    let config = create_collection_config();
+    let shared_storage_config = Arc::new(SharedStorageConfig {
+        update_batch_window: Some(Duration::from_millis(50)),
+        ..Default::default()
+    });
+
+    let shard = LocalShard::build(
+        0,
+        "test".to_string(),
+        collection_dir.path(),
+        Arc::new(RwLock::new(config.clone())),
+        shared_storage_config.clone(),
+        Handle::current(),
+    )
+    .await
+    .unwrap();
+
+    let points_count = 20;
+
+    let updates = (0..points_count).map(|id| {
+        let wait = id % 2 == 0;
+        shard.update(single_point_upsert(id, vec![id as f32; 4]), wait)
+    });
+    // Point with a wrong dimension must not fail the batch of valid points
+    let invalid_update = shard.update(single_point_upsert(100, vec![1.0; 3]), true);
+
+    let (results, invalid_result) = futures::join!(join_all(updates), invalid_update);
+
+    assert!(invalid_result.is_err());
+
+    let mut operation_ids = HashSet::new();
+    for (id, result) in results.into_iter().enumerate() {
+        let result = result.unwrap();
+        let expected_status = if id % 2 == 0 {
+            UpdateStatus::Completed
+        } else {
+            UpdateStatus::Acknowledged
+        };
+        assert_eq!(result.status, expected_status);
+        operation_ids.insert(result.operation_id.unwrap());
+    }
+
+    // All valid upserts were written as a single operation
+    assert_eq!(operation_ids.len(), 1);
+
+    // Make sure acknowledged updates are applied too
+    shard
+        .update(single_point_upsert(0, vec![0.0; 4]), true)
+        .await
+        .unwrap();
+    assert_eq!(count_points(&shard).await, points_count as usize);
+
+    drop(shard);
+
+    // Batched points are recovered from WAL
+    let shard = LocalShard::load(
+        0,
+        "test".to_string(),
+        collection_dir.path(),
+        Arc::new(RwLock::new(config)),
+        shared_storage_config,
+        Handle::current(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(count_points(&shard).await, points_count as usize);
+}
@@ -42,6 +42,10 @@ fn create_collection_config() -> CollectionConfig {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        metadata: None,
+        strict_mode_config: None,
+        rate_limit: None,
+        query_expansion: None,
     }
 }
 
@@ -10,20 +10,26 @@ use parking_lot::Mutex;
 use segment::common::operation_error::OperationResult;
 use segment::types::SeqNumberType;
 use tokio::runtime::Handle;
-use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::mpsc::{self, Receiver, Sender, WeakSender};
 use tokio::sync::{oneshot, Mutex as TokioMutex};
 use tokio::task::JoinHandle;
 use tokio::time::error::Elapsed;
 use tokio::time::{timeout, Duration};
 
 use crate::collection_manager::collection_updater::CollectionUpdater;
-use crate::collection_manager::holders::segment_holder::LockedSegmentHolder;
+use crate::collection_manager::holders::segment_holder::{LockedSegment, LockedSegmentHolder};
 use crate::collection_manager::optimizers::segment_optimizer::SegmentOptimizer;
 use crate::collection_manager::optimizers::{Tracker, TrackerLog, TrackerStatus};
+use crate::collection_manager::payload_index_builder::{self, PAYLOAD_INDEX_BUILDER_NAME};
+use crate::common::maintenance_hooks::{HookStage, MaintenanceHooks, MaintenanceOperation};
+use crate::common::maintenance_window::{is_maintenance_allowed, MaintenanceWindow};
 use crate::common::stoppable_task::{spawn_stoppable, StoppableTaskHandle};
+use crate::operations::point_ops::PointOperations;
+use crate::operations::point_timestamps::{expired_points_filter, now_millis};
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::operations::CollectionUpdateOperations;
+use crate::shards::change_feed::ChangeFeed;
 use crate::shards::local_shard::LockedWal;
 use crate::wal::WalError;
 
@@ -32,6 +38,12 @@ use crate::wal::WalError;
 /// The longer the duration, the longer it  takes for panicked tasks to be reported.
 const OPTIMIZER_CLEANUP_INTERVAL: Duration = Duration::from_secs(5);
 
+/// Pause of the scrub worker between segments, to keep background verification low-priority
+const SCRUB_SEGMENT_PAUSE: Duration = Duration::from_secs(1);
+
+/// Interval at which the expiration worker deletes expired points, if point expiration is enabled
+const EXPIRED_POINTS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 pub type Optimizer = dyn SegmentOptimizer + Sync + Send;
 
 /// Information, required to perform operation and notify regarding the result
@@ -89,6 +101,14 @@ pub struct UpdateHandler {
     flush_worker: Option<JoinHandle<()>>,
     /// Sender to stop flush worker
     flush_stop: Option<oneshot::Sender<()>>,
+    /// Process that periodically verifies integrity of segments
+    scrub_worker: Option<JoinHandle<()>>,
+    /// Sender to stop scrub worker
+    scrub_stop: Option<oneshot::Sender<()>>,
+    /// Process that periodically deletes expired points
+    expiration_worker: Option<JoinHandle<()>>,
+    /// Sender to stop expiration worker
+    expiration_stop: Option<oneshot::Sender<()>>,
     runtime_handle: Handle,
     /// WAL, required for operations
     wal: LockedWal,
@@ -99,6 +119,15 @@ pub struct UpdateHandler {
     pub(super) max_ack_version: Arc<AtomicU64>,
     optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
     max_optimization_threads: usize,
+    /// Time windows, during which optimizations are allowed to start
+    /// Empty means no restrictions
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    /// Delete points in background, once the time in their `__expire_at` field has passed
+    pub point_expiration: bool,
+    /// Feed of the shard, which deletions of expired points are published to
+    pub change_feed: ChangeFeed,
+    /// Commands to run before and after each optimization
+    pub maintenance_hooks: MaintenanceHooks,
 }
 
 impl UpdateHandler {
@@ -122,16 +151,28 @@ impl UpdateHandler {
             optimizers_log,
             flush_worker: None,
             flush_stop: None,
+            scrub_worker: None,
+            scrub_stop: None,
+            expiration_worker: None,
+            expiration_stop: None,
             runtime_handle,
             wal,
             max_ack_version: Arc::new(u64::MAX.into()),
             flush_interval_sec,
             optimization_handles: Arc::new(TokioMutex::new(vec![])),
             max_optimization_threads,
+            maintenance_windows: vec![],
+            point_expiration: false,
+            change_feed: ChangeFeed::default(),
+            maintenance_hooks: MaintenanceHooks::default(),
         }
     }
 
-    pub fn run_workers(&mut self, update_receiver: Receiver<UpdateSignal>) {
+    pub fn run_workers(
+        &mut self,
+        update_receiver: Receiver<UpdateSignal>,
+        update_sender: &Sender<UpdateSignal>,
+    ) {
         let (tx, rx) = mpsc::channel(self.shared_storage_config.update_queue_size);
         self.optimizer_worker = Some(self.runtime_handle.spawn(Self::optimization_worker_fn(
             self.optimizers.clone(),
@@ -142,6 +183,8 @@ impl UpdateHandler {
             self.optimization_handles.clone(),
             self.optimizers_log.clone(),
             self.max_optimization_threads,
+            self.maintenance_windows.clone(),
+            self.maintenance_hooks.clone(),
         )));
         self.update_worker = Some(self.runtime_handle.spawn(Self::update_worker_fn(
             update_receiver,
@@ -158,6 +201,26 @@ impl UpdateHandler {
             flush_rx,
         )));
         self.flush_stop = Some(flush_tx);
+        if let Some(scrub_interval) = self.shared_storage_config.scrub_interval {
+            let (scrub_tx, scrub_rx) = oneshot::channel();
+            self.scrub_worker = Some(self.runtime_handle.spawn(Self::scrub_worker(
+                self.segments.clone(),
+                scrub_interval,
+                scrub_rx,
+            )));
+            self.scrub_stop = Some(scrub_tx);
+        }
+        if self.point_expiration {
+            let (expiration_tx, expiration_rx) = oneshot::channel();
+            self.expiration_worker = Some(self.runtime_handle.spawn(Self::expiration_worker(
+                self.segments.clone(),
+                self.wal.clone(),
+                update_sender.downgrade(),
+                self.change_feed.clone(),
+                expiration_rx,
+            )));
+            self.expiration_stop = Some(expiration_tx);
+        }
     }
 
     pub fn stop_flush_worker(&mut self) {
@@ -183,6 +246,21 @@ impl UpdateHandler {
         if let Some(handle) = maybe_handle {
             handle.await?;
         }
+        // Scrubbing is not related to updates, so it is stopped right away
+        if let Some(scrub_stop) = self.scrub_stop.take() {
+            let _ = scrub_stop.send(());
+        }
+        let maybe_handle = self.scrub_worker.take();
+        if let Some(handle) = maybe_handle {
+            handle.await?;
+        }
+        if let Some(expiration_stop) = self.expiration_stop.take() {
+            let _ = expiration_stop.send(());
+        }
+        let maybe_handle = self.expiration_worker.take();
+        if let Some(handle) = maybe_handle {
+            handle.await?;
+        }
 
         let mut opt_handles_guard = self.optimization_handles.lock().await;
         let opt_handles = std::mem::take(&mut *opt_handles_guard);
@@ -215,6 +293,55 @@ impl UpdateHandler {
         Ok(0)
     }
 
+    /// Submits deletion of points, expiration time of which has passed
+    ///
+    /// Deletion is sent through the update queue and written into WAL like any other update,
+    /// so it is ordered with the queued operations, published to the change feed and is not
+    /// reverted on recovery.
+    /// Returns number of the operation, or `None` if there is nothing to delete.
+    pub(crate) async fn remove_expired_points(
+        segments: &LockedSegmentHolder,
+        wal: &LockedWal,
+        update_sender: &Sender<UpdateSignal>,
+        change_feed: &ChangeFeed,
+    ) -> CollectionResult<Option<SeqNumberType>> {
+        let filter = expired_points_filter(now_millis());
+
+        // Avoid writing into WAL if there is nothing to delete
+        let has_expired_points = tokio::task::spawn_blocking({
+            let segments = segments.clone();
+            let filter = filter.clone();
+            move || {
+                segments.read().iter().any(|(_, segment)| {
+                    !segment
+                        .get()
+                        .read()
+                        .read_filtered(None, Some(1), Some(&filter))
+                        .is_empty()
+                })
+            }
+        })
+        .await?;
+        if !has_expired_points {
+            return Ok(None);
+        }
+
+        let operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::DeletePointsByFilter(filter),
+        );
+        let channel_permit = update_sender.reserve().await?;
+        let mut wal_lock = wal.lock();
+        let op_num = wal_lock.write(&operation)?;
+        change_feed.publish(op_num, &operation);
+        channel_permit.send(UpdateSignal::Operation(OperationData {
+            op_num,
+            operation,
+            wait: false,
+            sender: None,
+        }));
+        Ok(Some(op_num))
+    }
+
     /// Checks conditions for all optimizers until there is no suggested segment
     /// Starts a task for each optimization
     /// Returns handles for started tasks
@@ -222,6 +349,7 @@ impl UpdateHandler {
         optimizers: Arc<Vec<Arc<Optimizer>>>,
         optimizers_log: Arc<Mutex<TrackerLog>>,
         segments: LockedSegmentHolder,
+        maintenance_hooks: MaintenanceHooks,
         callback: F,
     ) -> Vec<StoppableTaskHandle<bool>>
     where
@@ -245,6 +373,7 @@ impl UpdateHandler {
                 let nsi = nonoptimal_segment_ids.clone();
                 scheduled_segment_ids.extend(&nsi);
                 let callback = callback.clone();
+                let maintenance_hooks = maintenance_hooks.clone();
 
                 let handle = spawn_stoppable(
                     // Stoppable task
@@ -256,8 +385,21 @@ impl UpdateHandler {
                             let tracker_handle = tracker.handle();
                             optimizers_log.lock().register(tracker);
 
-                            // Optimize and handle result
-                            match optimizer.as_ref().optimize(segments.clone(), nsi, stopped) {
+                            maintenance_hooks.run_blocking(
+                                MaintenanceOperation::Optimization,
+                                HookStage::Before,
+                                None,
+                            );
+                            let optimization_result =
+                                optimizer.as_ref().optimize(segments.clone(), nsi, stopped);
+                            maintenance_hooks.run_blocking(
+                                MaintenanceOperation::Optimization,
+                                HookStage::After,
+                                Some(optimization_result.is_ok()),
+                            );
+
+                            // Handle result of the optimization
+                            match optimization_result {
                                 // Perform some actions when optimization if finished
                                 Ok(result) => {
                                     tracker_handle.update(TrackerStatus::Done);
@@ -313,27 +455,100 @@ impl UpdateHandler {
         handles
     }
 
+    /// Starts a task building payload indexes, missing in non-appendable segments
+    ///
+    /// Only a single task is running at a time. Returns `None` if there is nothing to build
+    /// or the task is already running.
+    pub(crate) fn launch_payload_index_building<F>(
+        optimizers_log: Arc<Mutex<TrackerLog>>,
+        segments: LockedSegmentHolder,
+        callback: F,
+    ) -> Option<StoppableTaskHandle<bool>>
+    where
+        F: FnOnce(bool),
+        F: Send + 'static,
+    {
+        let mut optimizers_log_guard = optimizers_log.lock();
+        if optimizers_log_guard.is_running(PAYLOAD_INDEX_BUILDER_NAME) {
+            return None;
+        }
+
+        let missing = payload_index_builder::missing_field_indexes(&segments.read());
+        if missing.is_empty() {
+            return None;
+        }
+
+        let segment_ids = missing
+            .iter()
+            .map(|(segment_id, _, _)| *segment_id)
+            .unique()
+            .collect();
+        let tracker = Tracker::start(PAYLOAD_INDEX_BUILDER_NAME, segment_ids);
+        let tracker_handle = tracker.handle();
+        optimizers_log_guard.register(tracker);
+
+        let handle = spawn_stoppable(
+            move |stopped| {
+                for (segment_id, field, schema) in missing {
+                    let result = payload_index_builder::build_field_index(
+                        &segments, segment_id, &field, &schema, stopped,
+                    );
+                    match result {
+                        Ok(_) => {}
+                        Err(CollectionError::Cancelled { description }) => {
+                            debug!("Payload index building cancelled - {}", description);
+                            tracker_handle.update(TrackerStatus::Cancelled(description));
+                            return false;
+                        }
+                        Err(error) => {
+                            error!("Failed to build payload index for `{field}`: {error}");
+                            tracker_handle.update(TrackerStatus::Error(error.to_string()));
+                            segments.write().report_optimizer_error(error);
+                            return false;
+                        }
+                    }
+                }
+                tracker_handle.update(TrackerStatus::Done);
+                callback(true);
+                true
+            },
+            None,
+        );
+        Some(handle)
+    }
+
     pub(crate) async fn process_optimization(
         optimizers: Arc<Vec<Arc<Optimizer>>>,
         segments: LockedSegmentHolder,
         optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
         optimizers_log: Arc<Mutex<TrackerLog>>,
+        maintenance_hooks: MaintenanceHooks,
         sender: Sender<OptimizerSignal>,
     ) {
         let mut new_handles = Self::launch_optimization(
             optimizers.clone(),
-            optimizers_log,
+            optimizers_log.clone(),
             segments.clone(),
-            move |_optimization_result| {
-                // After optimization is finished, we still need to check if there are
-                // some further optimizations possible.
-                // If receiver is already dead - we do not care.
-                // If channel is full - optimization will be triggered by some other signal
-                let _ = sender.try_send(OptimizerSignal::Nop);
+            maintenance_hooks,
+            {
+                let sender = sender.clone();
+                move |_optimization_result| {
+                    // After optimization is finished, we still need to check if there are
+                    // some further optimizations possible.
+                    // If receiver is already dead - we do not care.
+                    // If channel is full - optimization will be triggered by some other signal
+                    let _ = sender.try_send(OptimizerSignal::Nop);
+                }
             },
         );
+        // Indexes created while building may be missing, check again once finished
+        let index_building_handle =
+            Self::launch_payload_index_building(optimizers_log, segments, move |_| {
+                let _ = sender.try_send(OptimizerSignal::Nop);
+            });
         let mut handles = optimization_handles.lock().await;
         handles.append(&mut new_handles);
+        handles.extend(index_building_handle);
     }
 
     /// Cleanup finalized optimization task handles
@@ -373,7 +588,12 @@ impl UpdateHandler {
         optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
         optimizers_log: Arc<Mutex<TrackerLog>>,
         max_handles: usize,
+        maintenance_windows: Vec<MaintenanceWindow>,
+        maintenance_hooks: MaintenanceHooks,
     ) {
+        // Optimizations were requested outside of maintenance windows
+        let mut deferred = false;
+
         loop {
             let receiver = timeout(OPTIMIZER_CLEANUP_INTERVAL, receiver.recv());
             let result = receiver.await;
@@ -384,10 +604,23 @@ impl UpdateHandler {
             match result {
                 // Channel closed or stop signal
                 Ok(None | Some(OptimizerSignal::Stop)) => break,
-                // Clean up interval
-                Err(Elapsed { .. }) => continue,
+                // Clean up interval, also start deferred optimizations once maintenance window opens
+                Err(Elapsed { .. }) => {
+                    if !deferred || !is_maintenance_allowed(&maintenance_windows) {
+                        continue;
+                    }
+                    deferred = false;
+                    // Trigger optimization as if forced with Nop
+                    let _ = sender.try_send(OptimizerSignal::Nop);
+                }
                 // Optimizer signal
                 Ok(Some(signal @ (OptimizerSignal::Nop | OptimizerSignal::Operation(_)))) => {
+                    // Defer optimizations until the next maintenance window
+                    if !is_maintenance_allowed(&maintenance_windows) {
+                        deferred = true;
+                        continue;
+                    }
+
                     // If not forcing with Nop, wait on next signal if we have too many handles
                     if signal != OptimizerSignal::Nop
                         && optimization_handles.lock().await.len() >= max_handles
@@ -406,6 +639,7 @@ impl UpdateHandler {
                         segments.clone(),
                         optimization_handles.clone(),
                         optimizers_log.clone(),
+                        maintenance_hooks.clone(),
                         sender.clone(),
                     )
                     .await;
@@ -546,6 +780,93 @@ impl UpdateHandler {
         }
     }
 
+    /// Periodically verify integrity of all segments, one segment at a time
+    ///
+    /// Detected corruption is reported as an optimizer error, and corrupted segments are
+    /// remembered in the segment holder, so the shard replica can be quarantined.
+    /// Periodically submits deletion of expired points
+    ///
+    /// Stops on signal, or once the update worker is gone.
+    async fn expiration_worker(
+        segments: LockedSegmentHolder,
+        wal: LockedWal,
+        update_sender: WeakSender<UpdateSignal>,
+        change_feed: ChangeFeed,
+        mut stop_receiver: oneshot::Receiver<()>,
+    ) {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(EXPIRED_POINTS_CHECK_INTERVAL) => {},
+                _ = &mut stop_receiver => {
+                    debug!("Stopping expiration worker.");
+                    return;
+                }
+            };
+
+            let Some(update_sender) = update_sender.upgrade() else {
+                debug!("Update worker is stopped, stopping expiration worker.");
+                return;
+            };
+            let removal =
+                Self::remove_expired_points(&segments, &wal, &update_sender, &change_feed);
+            match removal.await {
+                Ok(None) => {}
+                Ok(Some(op_num)) => debug!("Deleting expired points in operation {op_num}"),
+                Err(err) => error!("Failed to remove expired points: {err}"),
+            }
+        }
+    }
+
+    async fn scrub_worker(
+        segments: LockedSegmentHolder,
+        scrub_interval: Duration,
+        mut stop_receiver: oneshot::Receiver<()>,
+    ) {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(scrub_interval) => {},
+                _ = &mut stop_receiver => {
+                    debug!("Stopping scrub worker.");
+                    return;
+                }
+            };
+
+            trace!("Verifying integrity of segments");
+            let segment_ids: Vec<_> = segments.read().iter().map(|(id, _)| *id).collect();
+            for segment_id in segment_ids {
+                // Stop between segments, verification of a single segment is not interrupted
+                tokio::select! {
+                    _ = tokio::time::sleep(SCRUB_SEGMENT_PAUSE) => {},
+                    _ = &mut stop_receiver => {
+                        debug!("Stopping scrub worker.");
+                        return;
+                    }
+                };
+
+                let Some(segment) = segments.read().get(segment_id).cloned() else {
+                    continue;
+                };
+                let result = tokio::task::spawn_blocking(move || match segment {
+                    LockedSegment::Original(segment) => segment.read().check_integrity(),
+                    // Segment is under optimization, its data is replaced afterwards
+                    LockedSegment::Proxy(_) => Ok(()),
+                })
+                .await;
+
+                match result {
+                    Ok(Ok(())) => segments.write().report_segment_integrity(segment_id, None),
+                    Ok(Err(err)) => {
+                        error!("Integrity check of segment {segment_id} failed: {err}");
+                        let mut segments = segments.write();
+                        segments.report_segment_integrity(segment_id, Some(err.to_string()));
+                        segments.report_optimizer_error(err);
+                    }
+                    Err(err) => error!("Integrity check task failed: {err}"),
+                }
+            }
+        }
+    }
+
     /// Returns confirmed version after flush of all segments
     ///
     /// # Errors
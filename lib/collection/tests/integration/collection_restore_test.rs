@@ -98,6 +98,8 @@ async fn test_collection_payload_reloading_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: true.into(),
+                order_by: None,
+                query: None,
             },
             None,
             &ShardSelectorInternal::All,
@@ -169,6 +171,8 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Fields(vec![String::from("k2")])),
                 with_vector: true.into(),
+                order_by: None,
+                query: None,
             },
             None,
             &ShardSelectorInternal::All,
@@ -203,6 +207,8 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(PayloadSelectorExclude::new(vec!["k1".to_string()]).into()),
                 with_vector: false.into(),
+                order_by: None,
+                query: None,
             },
             None,
             &ShardSelectorInternal::All,
@@ -1,21 +1,27 @@
 use std::collections::HashSet;
 use std::fs::File;
 
+use collection::operations::config_diff::CollectionParamsDiff;
 use collection::operations::payload_ops::{PayloadOps, SetPayloadOp};
 use collection::operations::point_ops::{Batch, PointOperations, PointStruct, WriteOrdering};
+use collection::operations::point_timestamps::EXPIRE_AT_KEY;
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::{
-    CountRequestInternal, PointRequestInternal, RecommendRequestInternal, ScrollRequestInternal,
-    SearchRequestInternal, UpdateStatus,
+    CountRequestInternal, PointRequestInternal, RecommendRequestInternal, Sample, ScrollQuery,
+    ScrollRequestInternal, SearchMatrixOffsetsResponse, SearchMatrixPairsResponse,
+    SearchMatrixRequestInternal, SearchRequestInternal, UpdateStatus,
 };
-use collection::operations::CollectionUpdateOperations;
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use collection::recommendations::recommend_by;
 use collection::shards::replica_set::{ReplicaSetState, ReplicaState};
 use itertools::Itertools;
+use segment::data_types::order_by::{Direction, OrderBy, OrderByInterface};
 use segment::data_types::vectors::VectorStruct;
 use segment::types::{
-    Condition, FieldCondition, Filter, HasIdCondition, Payload, PointIdType, WithPayloadInterface,
+    Condition, FieldCondition, Filter, HasIdCondition, Payload, PayloadFieldSchema,
+    PayloadSchemaType, PointIdType, WithPayloadInterface,
 };
+use serde_json::json;
 use tempfile::Builder;
 
 use crate::common::{load_local_collection, simple_collection_fixture, N_SHARDS};
@@ -222,6 +228,7 @@ async fn test_collection_loading_with_shards(shard_number: u32) {
                 payload,
                 points: Some(vec![2.into(), 3.into()]),
                 filter: None,
+                key: None,
             }));
 
         collection
@@ -306,6 +313,88 @@ fn test_deserialization2() {
 }
 
 // Request to find points sent to all shards but they might not have a particular id, so they will return an error
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_update_batch() {
+    test_collection_update_batch_with_shards(1).await;
+    test_collection_update_batch_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_update_batch_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    {
+        let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: vec![0, 1, 2, 3, 4]
+                    .into_iter()
+                    .map(|x| x.into())
+                    .collect_vec(),
+                vectors: vec![
+                    vec![1.0, 0.0, 1.0, 1.0],
+                    vec![1.0, 0.0, 1.0, 0.0],
+                    vec![1.0, 1.0, 1.0, 1.0],
+                    vec![1.0, 1.0, 0.0, 1.0],
+                    vec![1.0, 0.0, 0.0, 0.0],
+                ]
+                .into(),
+                payloads: None,
+            }
+            .into(),
+        );
+        let payload: Payload = serde_json::from_str(r#"{"color":"red"}"#).unwrap();
+        let assign_payload =
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayloadOp {
+                payload,
+                points: Some(vec![1.into(), 3.into(), 4.into()]),
+                filter: None,
+                key: None,
+            }));
+        let delete_points =
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+                ids: vec![4.into()],
+            });
+
+        // Operations of the batch share the operation number, but are all applied in order
+        let batch = CollectionUpdateOperations::BatchOperation(vec![
+            insert_points,
+            assign_payload,
+            delete_points,
+        ]);
+        let result = collection
+            .update_from_client_simple(batch, true, WriteOrdering::default())
+            .await
+            .unwrap();
+        assert_eq!(result.status, UpdateStatus::Completed);
+    }
+
+    // Batch is read back from the WAL as a single operation
+    let collection_path = collection_dir.path();
+    let loaded_collection = load_local_collection(
+        "test".to_string(),
+        collection_path,
+        &collection_path.join("snapshots"),
+    )
+    .await;
+    let request = PointRequestInternal {
+        ids: vec![0.into(), 1.into(), 2.into(), 3.into(), 4.into()],
+        with_payload: Some(WithPayloadInterface::Bool(true)),
+        with_vector: false.into(),
+    };
+    let retrieved = loaded_collection
+        .retrieve(request, None, &ShardSelectorInternal::All)
+        .await
+        .unwrap();
+
+    let ids: HashSet<_> = retrieved.iter().map(|record| record.id).collect();
+    assert_eq!(ids, HashSet::from([0.into(), 1.into(), 2.into(), 3.into()]));
+    for record in retrieved {
+        let has_payload = !record.payload.unwrap_or_default().is_empty();
+        assert_eq!(has_payload, record.id == 1.into() || record.id == 3.into());
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_recommendation_api() {
     test_recommendation_api_with_shards(1).await;
@@ -410,6 +499,8 @@ async fn test_read_api_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: false.into(),
+                order_by: None,
+                query: None,
             },
             None,
             &ShardSelectorInternal::All,
@@ -493,6 +584,8 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(false)),
                 with_vector: false.into(),
+                order_by: None,
+                query: None,
             },
             None,
             &ShardSelectorInternal::All,
@@ -548,3 +641,383 @@ async fn test_collection_local_load_initializing_not_stuck() {
         }
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ordered_scroll_api() {
+    test_ordered_scroll_api_with_shards(1).await;
+    test_ordered_scroll_api_with_shards(N_SHARDS).await;
+}
+
+async fn test_ordered_scroll_api_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "price".to_string(),
+            field_schema: Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Integer)),
+        }),
+    );
+
+    collection
+        .update_from_client_simple(create_index, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0, 0.0],
+                vec![1.0, 1.0, 1.0, 1.0],
+                vec![1.0, 1.0, 0.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+            ]
+            .into(),
+            payloads: serde_json::from_str(
+                r#"[{ "price": 30 }, { "price": 10 }, { "price": [50, 20] }, { "price": 40 }, {}]"#,
+            )
+            .unwrap(),
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client_simple(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let shard_selection = ShardSelectorInternal::All;
+    let scroll_ordered = |direction, start_from| {
+        collection.scroll_by(
+            ScrollRequestInternal {
+                offset: None,
+                limit: Some(3),
+                filter: None,
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                with_vector: false.into(),
+                order_by: Some(OrderByInterface::Struct(OrderBy {
+                    key: "price".to_string(),
+                    direction: Some(direction),
+                    start_from,
+                })),
+                query: None,
+            },
+            None,
+            &shard_selection,
+        )
+    };
+
+    let result = scroll_ordered(Direction::Asc, None).await.unwrap();
+    let ids = result.points.iter().map(|p| p.id).collect_vec();
+    assert_eq!(ids, vec![1.into(), 2.into(), 0.into()]);
+    let values = result.points.iter().map(|p| p.order_value).collect_vec();
+    assert_eq!(values, vec![Some(10.0), Some(20.0), Some(30.0)]);
+    assert_eq!(result.next_page_offset, None);
+
+    let result = scroll_ordered(Direction::Desc, None).await.unwrap();
+    let ids = result.points.iter().map(|p| p.id).collect_vec();
+    assert_eq!(ids, vec![2.into(), 3.into(), 0.into()]);
+
+    let result = scroll_ordered(Direction::Asc, Some(25.0)).await.unwrap();
+    let ids = result.points.iter().map(|p| p.id).collect_vec();
+    assert_eq!(ids, vec![0.into(), 3.into(), 2.into()]);
+
+    // Offset can't be combined with `order_by`
+    let result = collection
+        .scroll_by(
+            ScrollRequestInternal {
+                offset: Some(1.into()),
+                limit: Some(3),
+                filter: None,
+                with_payload: None,
+                with_vector: false.into(),
+                order_by: Some(OrderByInterface::Key("price".to_string())),
+                query: None,
+            },
+            None,
+            &ShardSelectorInternal::All,
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_random_sample_scroll_api() {
+    test_random_sample_scroll_api_with_shards(1).await;
+    test_random_sample_scroll_api_with_shards(N_SHARDS).await;
+}
+
+async fn test_random_sample_scroll_api_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..50).map(|x| x.into()).collect_vec(),
+            vectors: (0..50)
+                .map(|x| vec![x as f32, 1.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(
+                (0..50)
+                    .map(|x| Some(serde_json::from_value(json!({ "even": x % 2 == 0 })).unwrap()))
+                    .collect_vec(),
+            ),
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client_simple(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let shard_selection = ShardSelectorInternal::All;
+    let sample = |limit, filter| {
+        collection.scroll_by(
+            ScrollRequestInternal {
+                offset: None,
+                limit: Some(limit),
+                filter,
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: false.into(),
+                order_by: None,
+                query: Some(ScrollQuery::Sample(Sample::Random)),
+            },
+            None,
+            &shard_selection,
+        )
+    };
+
+    let result = sample(10, None).await.unwrap();
+    assert_eq!(result.points.len(), 10);
+    assert_eq!(result.points.iter().map(|p| p.id).unique().count(), 10);
+    assert_eq!(result.next_page_offset, None);
+
+    // All matching points are returned if there are fewer than the limit
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "even",
+        true.into(),
+    )));
+    let result = sample(100, Some(filter)).await.unwrap();
+    assert_eq!(result.points.len(), 25);
+    for point in &result.points {
+        assert_eq!(point.payload.as_ref().unwrap().0["even"], json!(true));
+    }
+
+    // Offset can't be combined with sampling
+    let result = collection
+        .scroll_by(
+            ScrollRequestInternal {
+                offset: Some(1.into()),
+                limit: Some(3),
+                filter: None,
+                with_payload: None,
+                with_vector: false.into(),
+                order_by: None,
+                query: Some(ScrollQuery::Sample(Sample::Random)),
+            },
+            None,
+            &ShardSelectorInternal::All,
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scroll_by_id_order() {
+    test_scroll_by_id_order_with_shards(1).await;
+    test_scroll_by_id_order_with_shards(N_SHARDS).await;
+}
+
+async fn test_scroll_by_id_order_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    // Insert points in multiple batches and in mixed order, so they are spread between segments
+    for batch in [
+        (0..50).rev().step_by(2).collect_vec(),
+        (0..50).step_by(2).collect_vec(),
+    ] {
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: batch.iter().map(|&x: &u64| x.into()).collect_vec(),
+                vectors: batch
+                    .iter()
+                    .map(|&x| vec![x as f32, 1.0, 0.0, 1.0])
+                    .collect_vec()
+                    .into(),
+                payloads: None,
+            }
+            .into(),
+        );
+
+        collection
+            .update_from_client_simple(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+
+    let mut ids = Vec::new();
+    let mut offset = None;
+    loop {
+        let result = collection
+            .scroll_by(
+                ScrollRequestInternal {
+                    offset,
+                    limit: Some(7),
+                    filter: None,
+                    with_payload: Some(WithPayloadInterface::Bool(false)),
+                    with_vector: false.into(),
+                    order_by: None,
+                    query: None,
+                },
+                None,
+                &ShardSelectorInternal::All,
+            )
+            .await
+            .unwrap();
+
+        ids.extend(result.points.iter().map(|p| p.id));
+        offset = result.next_page_offset;
+        if offset.is_none() {
+            break;
+        }
+    }
+
+    let expected: Vec<PointIdType> = (0..50).map(|x: u64| x.into()).collect();
+    assert_eq!(ids, expected);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_points_matrix() {
+    test_search_points_matrix_with_shards(1).await;
+    test_search_points_matrix_with_shards(N_SHARDS).await;
+}
+
+async fn test_search_points_matrix_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..50).map(|x| x.into()).collect_vec(),
+            vectors: (0..50)
+                .map(|x| vec![x as f32, 1.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(
+                (0..50)
+                    .map(|x| Some(serde_json::from_value(json!({ "even": x % 2 == 0 })).unwrap()))
+                    .collect_vec(),
+            ),
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client_simple(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "even",
+        true.into(),
+    )));
+    let matrix = collection
+        .search_points_matrix(
+            SearchMatrixRequestInternal {
+                filter: Some(filter),
+                sample: Some(8),
+                limit: Some(2),
+                using: None,
+            },
+            ShardSelectorInternal::All,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(matrix.sample_ids.len(), 8);
+    assert_eq!(matrix.sample_ids.iter().unique().count(), 8);
+    assert_eq!(matrix.nearests.len(), 8);
+
+    let sample_ids: HashSet<_> = matrix.sample_ids.iter().copied().collect();
+    for (sample_id, nearest) in matrix.sample_ids.iter().zip(&matrix.nearests) {
+        assert_eq!(nearest.len(), 2);
+        for hit in nearest {
+            // Neighbours are searched only within the sample, excluding the point itself
+            assert_ne!(hit.id, *sample_id);
+            assert!(sample_ids.contains(&hit.id));
+            let PointIdType::NumId(id) = hit.id else {
+                panic!("unexpected point id {:?}", hit.id);
+            };
+            assert_eq!(id % 2, 0);
+        }
+    }
+
+    let offsets = SearchMatrixOffsetsResponse::from(matrix.clone());
+    assert_eq!(offsets.ids, matrix.sample_ids);
+    assert_eq!(offsets.offsets_row.len(), 16);
+    assert_eq!(offsets.offsets_col.len(), 16);
+    assert_eq!(offsets.scores.len(), 16);
+
+    let pairs = SearchMatrixPairsResponse::from(matrix);
+    assert_eq!(pairs.pairs.len(), 16);
+    for (i, pair) in pairs.pairs.iter().enumerate() {
+        assert_eq!(pair.a, offsets.ids[offsets.offsets_row[i] as usize]);
+        assert_eq!(pair.b, offsets.ids[offsets.offsets_col[i] as usize]);
+        assert_eq!(pair.score, offsets.scores[i]);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_point_expiration_index() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+    // Nothing is indexed while expiration is disabled
+    collection.ensure_point_expiration_index().await.unwrap();
+    let schema = collection.state().await.payload_index_schema.schema;
+    assert!(!schema.contains_key(EXPIRE_AT_KEY));
+
+    collection
+        .update_params_from_diff(CollectionParamsDiff {
+            point_expiration: Some(true),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    collection.ensure_point_expiration_index().await.unwrap();
+    let schema = collection.state().await.payload_index_schema.schema;
+    assert_eq!(
+        schema.get(EXPIRE_AT_KEY),
+        Some(&PayloadFieldSchema::FieldType(PayloadSchemaType::Integer)),
+    );
+
+    // An existing index of the field is kept
+    collection
+        .create_payload_index(
+            EXPIRE_AT_KEY.to_string(),
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Float),
+        )
+        .await
+        .unwrap();
+    collection.ensure_point_expiration_index().await.unwrap();
+    let schema = collection.state().await.payload_index_schema.schema;
+    assert_eq!(
+        schema.get(EXPIRE_AT_KEY),
+        Some(&PayloadFieldSchema::FieldType(PayloadSchemaType::Float)),
+    );
+}
@@ -60,6 +60,10 @@ pub async fn simple_collection_fixture(collection_path: &Path, shard_number: u32
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        metadata: None,
+        strict_mode_config: None,
+        rate_limit: None,
+        query_expansion: None,
     };
 
     let snapshot_path = collection_path.join("snapshots");
@@ -137,6 +137,8 @@ mod group_by {
                 score_threshold: None,
                 positive: vec![1.into(), 2.into(), 3.into()],
                 negative: Vec::new(),
+                positive_weights: None,
+                negative_weights: None,
                 using: None,
                 lookup_from: None,
             }),
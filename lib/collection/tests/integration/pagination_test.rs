@@ -4,7 +4,9 @@ use collection::operations::point_ops::{
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::SearchRequestInternal;
 use collection::operations::CollectionUpdateOperations;
-use segment::types::WithPayloadInterface;
+use collection::shards::local_shard_cursor::CursorQuery;
+use itertools::Itertools;
+use segment::types::{WithPayloadInterface, WithVector};
 use tempfile::Builder;
 
 use crate::common::{simple_collection_fixture, N_SHARDS};
@@ -122,3 +124,158 @@ async fn test_collection_paginated_search_with_shards(shard_number: u32) {
         assert_eq!(page_9_result[i], reference_result[page_size * 9 + i]);
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_scroll_cursor() {
+    test_collection_scroll_cursor_with_shards(1).await;
+    test_collection_scroll_cursor_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_scroll_cursor_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_collection_scroll_cursor")
+        .tempdir()
+        .unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let points = (0..100)
+        .map(|i| PointStruct {
+            id: i.into(),
+            vector: vec![i as f32, 0.0, 0.0, 0.0].into(),
+            payload: Some(serde_json::from_str(r#"{"number": "John Doe"}"#).unwrap()),
+        })
+        .collect();
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperationsInternal::PointsList(points),
+    ));
+    collection
+        .update_from_client_simple(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let query = CursorQuery::Scroll {
+        offset: Some(10.into()),
+        filter: None,
+    };
+    let mut cursor = collection
+        .cursor(
+            query,
+            WithPayloadInterface::Bool(true),
+            WithVector::Bool(false),
+            &ShardSelectorInternal::All,
+        )
+        .await
+        .unwrap();
+
+    let first_page = collection.cursor_next_page(&mut cursor, 30).await.unwrap();
+    assert_eq!(
+        first_page.iter().map(|point| point.id).collect_vec(),
+        (10..40).map(Into::into).collect_vec(),
+    );
+    assert!(first_page.iter().all(|point| point.payload.is_some()));
+    assert_eq!(cursor.peek().map(|point| point.id), Some(40.into()));
+
+    // Points deleted after the cursor was created are skipped
+    let delete_points = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+        ids: (40..50).map(Into::into).collect(),
+    });
+    collection
+        .update_from_client_simple(delete_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let mut rest = Vec::new();
+    while !cursor.is_exhausted() {
+        let page = collection.cursor_next_page(&mut cursor, 30).await.unwrap();
+        assert!(!page.is_empty());
+        rest.extend(page.into_iter().map(|point| point.id));
+    }
+    assert_eq!(rest, (50..100).map(Into::into).collect_vec());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_search_cursor() {
+    test_collection_search_cursor_with_shards(1).await;
+    test_collection_search_cursor_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_search_cursor_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_collection_search_cursor")
+        .tempdir()
+        .unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let points = (0..1000)
+        .map(|i| PointStruct {
+            id: i.into(),
+            vector: vec![i as f32, 0.0, 0.0, 0.0].into(),
+            payload: Some(serde_json::from_str(r#"{"number": "John Doe"}"#).unwrap()),
+        })
+        .collect();
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperationsInternal::PointsList(points),
+    ));
+    collection
+        .update_from_client_simple(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let search_request = SearchRequestInternal {
+        vector: vec![1.0, 0.0, 0.0, 0.0].into(),
+        filter: None,
+        limit: 95,
+        offset: Some(5),
+        with_payload: Some(WithPayloadInterface::Bool(true)),
+        with_vector: None,
+        params: None,
+        score_threshold: None,
+    };
+
+    let reference_result = collection
+        .search(
+            search_request.clone().into(),
+            None,
+            &ShardSelectorInternal::All,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(reference_result.len(), 95);
+
+    let query = CursorQuery::Search {
+        request: search_request.into(),
+        timeout: None,
+    };
+    let mut cursor = collection
+        .cursor(
+            query,
+            WithPayloadInterface::Bool(true),
+            WithVector::Bool(false),
+            &ShardSelectorInternal::All,
+        )
+        .await
+        .unwrap();
+
+    // Chunks continue the same search, in the order of the scores
+    let mut result = Vec::new();
+    while !cursor.is_exhausted() {
+        let page = collection.cursor_next_page(&mut cursor, 10).await.unwrap();
+        assert!(page.len() <= 10);
+        assert!(page.iter().all(|point| point.payload.is_some()));
+        result.extend(page);
+    }
+
+    assert_eq!(
+        result
+            .iter()
+            .map(|point| (point.id, point.score))
+            .collect_vec(),
+        reference_result
+            .iter()
+            .map(|point| (point.id, point.score))
+            .collect_vec(),
+    );
+}
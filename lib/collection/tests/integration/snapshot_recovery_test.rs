@@ -44,6 +44,10 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        metadata: None,
+        strict_mode_config: None,
+        rate_limit: None,
+        query_expansion: None,
     };
 
     let snapshots_path = Builder::new().prefix("test_snapshots").tempdir().unwrap();
@@ -116,6 +120,20 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
         .await
         .unwrap();
 
+    // Snapshot can also be unpacked while it is read sequentially, e.g. from a network stream
+    let stream_recover_dir = Builder::new()
+        .prefix("test_collection_stream_rec")
+        .tempdir()
+        .unwrap();
+    let archive_file =
+        std::fs::File::open(snapshots_path.path().join(&snapshot_description.name)).unwrap();
+    if let Err(err) =
+        Collection::restore_snapshot_from_reader(archive_file, stream_recover_dir.path(), 0, false)
+    {
+        panic!("Failed to restore snapshot from reader: {err}")
+    }
+    assert!(stream_recover_dir.path().join("config.json").exists());
+
     if let Err(err) = Collection::restore_snapshot(
         &snapshots_path.path().join(snapshot_description.name),
         recover_dir.path(),
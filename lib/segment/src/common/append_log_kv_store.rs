@@ -0,0 +1,394 @@
+//! Key-value store, which appends all changes to a single log file.
+//!
+//! Keys and value locations are kept in memory, values are read from the file on request.
+//! Unlike RocksDB, there is no background compaction: the log is rewritten with live records only
+//! on open or flush, once replaced and removed records take more space than live ones.
+//! An incomplete record at the end of the log, e.g. after a crash, is discarded on open,
+//! as the data is recovered from the WAL.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::common::kv_store::KvStore;
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::Flusher;
+
+const RECORD_PUT: u8 = 1;
+const RECORD_REMOVE: u8 = 0;
+
+/// Record tag, key length and value length
+const RECORD_HEADER_SIZE: u64 = 1 + 4 + 4;
+
+/// Don't rewrite the log, while garbage in it is small
+const MIN_GARBAGE_TO_COMPACT: u64 = 16 * 1024 * 1024;
+
+/// Location of a value in the log
+#[derive(Debug, Clone, Copy)]
+struct ValueRef {
+    offset: u64,
+    length: u32,
+}
+
+struct LogFile {
+    file: File,
+    length: u64,
+    index: BTreeMap<Vec<u8>, ValueRef>,
+    /// Size of records, which are referenced by the index
+    live_size: u64,
+}
+
+pub struct AppendLogKvStore {
+    path: PathBuf,
+    log: Arc<Mutex<LogFile>>,
+}
+
+impl AppendLogKvStore {
+    pub fn open(path: &Path) -> OperationResult<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(path)?;
+        let mut log = LogFile::load(file, path)?;
+        if log.needs_compaction() {
+            log.compact(path)?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            log: Arc::new(Mutex::new(log)),
+        })
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+}
+
+impl KvStore for AppendLogKvStore {
+    fn put(&self, key: &[u8], value: &[u8]) -> OperationResult<()> {
+        let mut log = self.log.lock();
+        let offset = log.append(RECORD_PUT, key, value)?;
+        let value_ref = ValueRef {
+            offset,
+            length: value.len() as u32,
+        };
+        log.live_size += record_size(key.len(), value.len());
+        if let Some(previous) = log.index.insert(key.to_vec(), value_ref) {
+            log.live_size -= record_size(key.len(), previous.length as usize);
+        }
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> OperationResult<Option<Vec<u8>>> {
+        let mut log = self.log.lock();
+        let Some(value_ref) = log.index.get(key).copied() else {
+            return Ok(None);
+        };
+        log.read(value_ref).map(Some)
+    }
+
+    fn remove(&self, key: &[u8]) -> OperationResult<()> {
+        let mut log = self.log.lock();
+        let Some(previous) = log.index.remove(key) else {
+            return Ok(());
+        };
+        log.live_size -= record_size(key.len(), previous.length as usize);
+        log.append(RECORD_REMOVE, key, &[])?;
+        Ok(())
+    }
+
+    fn for_each(
+        &self,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> OperationResult<bool>,
+    ) -> OperationResult<()> {
+        let mut log = self.log.lock();
+        let entries: Vec<_> = log
+            .index
+            .iter()
+            .map(|(key, value_ref)| (key.clone(), *value_ref))
+            .collect();
+        for (key, value_ref) in entries {
+            let value = log.read(value_ref)?;
+            if !f(&key, &value)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&self) -> OperationResult<()> {
+        let mut log = self.log.lock();
+        log.file.set_len(0)?;
+        log.length = 0;
+        log.index.clear();
+        log.live_size = 0;
+        Ok(())
+    }
+
+    fn flusher(&self) -> Flusher {
+        let log = self.log.clone();
+        let path = self.path.clone();
+        Box::new(move || {
+            let mut log = log.lock();
+            log.file.sync_data()?;
+            if log.needs_compaction() {
+                log.compact(&path)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl LogFile {
+    /// Read all records of the log into the index
+    fn load(file: File, path: &Path) -> OperationResult<Self> {
+        let file_length = file.metadata()?.len();
+        let mut reader = BufReader::new(&file);
+        let mut index = BTreeMap::new();
+        let mut live_size = 0;
+        let mut length = 0;
+
+        while length < file_length {
+            let mut header = [0u8; RECORD_HEADER_SIZE as usize];
+            if let Err(err) = reader.read_exact(&mut header) {
+                if err.kind() == ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(err.into());
+            }
+            let tag = header[0];
+            let key_length = u32::from_le_bytes(header[1..5].try_into().unwrap()) as usize;
+            let value_length = u32::from_le_bytes(header[5..9].try_into().unwrap());
+
+            let size = record_size(key_length, value_length as usize);
+            if length + size > file_length {
+                break;
+            }
+
+            let mut key = vec![0; key_length];
+            reader.read_exact(&mut key)?;
+            reader.seek_relative(i64::from(value_length))?;
+
+            match tag {
+                RECORD_PUT => {
+                    let value_ref = ValueRef {
+                        offset: length + RECORD_HEADER_SIZE + key_length as u64,
+                        length: value_length,
+                    };
+                    live_size += size;
+                    if let Some(previous) = index.insert(key, value_ref) {
+                        live_size -= record_size(key_length, previous.length as usize);
+                    }
+                }
+                RECORD_REMOVE => {
+                    if let Some(previous) = index.remove(&key) {
+                        live_size -= record_size(key_length, previous.length as usize);
+                    }
+                }
+                _ => {
+                    return Err(OperationError::service_error(format!(
+                        "unknown record tag {tag} at offset {length} of {path:?}",
+                    )));
+                }
+            }
+            length += size;
+        }
+        drop(reader);
+
+        if length < file_length {
+            log::warn!(
+                "Discarding incomplete record at offset {length} of {}",
+                path.display(),
+            );
+            file.set_len(length)?;
+        }
+
+        Ok(Self {
+            file,
+            length,
+            index,
+            live_size,
+        })
+    }
+
+    /// Append record, returns offset of its value
+    fn append(&mut self, tag: u8, key: &[u8], value: &[u8]) -> OperationResult<u64> {
+        let mut record = Vec::with_capacity(record_size(key.len(), value.len()) as usize);
+        record.push(tag);
+        record.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        record.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        record.extend_from_slice(key);
+        record.extend_from_slice(value);
+
+        let offset = self.length + RECORD_HEADER_SIZE + key.len() as u64;
+        self.file.seek(SeekFrom::Start(self.length))?;
+        self.file.write_all(&record)?;
+        self.length += record.len() as u64;
+        Ok(offset)
+    }
+
+    fn read(&mut self, value_ref: ValueRef) -> OperationResult<Vec<u8>> {
+        let mut value = vec![0; value_ref.length as usize];
+        self.file.seek(SeekFrom::Start(value_ref.offset))?;
+        self.file.read_exact(&mut value)?;
+        Ok(value)
+    }
+
+    fn needs_compaction(&self) -> bool {
+        let garbage = self.length - self.live_size;
+        garbage > MIN_GARBAGE_TO_COMPACT && garbage > self.live_size
+    }
+
+    /// Rewrite the log with live records only
+    fn compact(&mut self, path: &Path) -> OperationResult<()> {
+        let tmp_path = path.with_extension("compacting");
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        let mut index = BTreeMap::new();
+        let mut length = 0;
+
+        let entries: Vec<_> = self
+            .index
+            .iter()
+            .map(|(key, value_ref)| (key.clone(), *value_ref))
+            .collect();
+        for (key, value_ref) in entries {
+            let value = self.read(value_ref)?;
+            writer.write_all(&[RECORD_PUT])?;
+            writer.write_all(&(key.len() as u32).to_le_bytes())?;
+            writer.write_all(&(value.len() as u32).to_le_bytes())?;
+            writer.write_all(&key)?;
+            writer.write_all(&value)?;
+            let offset = length + RECORD_HEADER_SIZE + key.len() as u64;
+            length += record_size(key.len(), value.len());
+            index.insert(
+                key,
+                ValueRef {
+                    offset,
+                    length: value_ref.length,
+                },
+            );
+        }
+
+        let file = writer.into_inner().map_err(|err| err.into_error())?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp_path, path)?;
+
+        self.file = OpenOptions::new().read(true).write(true).open(path)?;
+        self.length = length;
+        self.live_size = length;
+        self.index = index;
+        Ok(())
+    }
+}
+
+fn record_size(key_length: usize, value_length: usize) -> u64 {
+    RECORD_HEADER_SIZE + key_length as u64 + value_length as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    fn collect(store: &AppendLogKvStore) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut pairs = vec![];
+        store
+            .for_each(&mut |key, value| {
+                pairs.push((key.to_vec(), value.to_vec()));
+                Ok(true)
+            })
+            .unwrap();
+        pairs
+    }
+
+    #[test]
+    fn test_put_remove_and_reopen() {
+        let dir = Builder::new().prefix("append_log").tempdir().unwrap();
+        let path = dir.path().join("kv_store").join("payload.log");
+
+        let store = AppendLogKvStore::open(&path).unwrap();
+        store.put(b"b", b"value-b").unwrap();
+        store.put(b"a", b"value-a").unwrap();
+        store.put(b"c", b"value-c").unwrap();
+        store.put(b"a", b"value-a2").unwrap();
+        store.remove(b"c").unwrap();
+
+        assert_eq!(store.get(b"a").unwrap(), Some(b"value-a2".to_vec()));
+        assert_eq!(store.get(b"c").unwrap(), None);
+        store.flusher()().unwrap();
+        drop(store);
+
+        let store = AppendLogKvStore::open(&path).unwrap();
+        assert_eq!(
+            collect(&store),
+            vec![
+                (b"a".to_vec(), b"value-a2".to_vec()),
+                (b"b".to_vec(), b"value-b".to_vec()),
+            ],
+        );
+
+        store.clear().unwrap();
+        assert!(collect(&store).is_empty());
+        assert_eq!(fs::metadata(&path).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_incomplete_record_is_discarded() {
+        let dir = Builder::new().prefix("append_log").tempdir().unwrap();
+        let path = dir.path().join("mapping.log");
+
+        let store = AppendLogKvStore::open(&path).unwrap();
+        store.put(b"key", b"value").unwrap();
+        store.put(b"other", b"value").unwrap();
+        store.flusher()().unwrap();
+        drop(store);
+
+        let length = fs::metadata(&path).unwrap().len();
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(length - 2).unwrap();
+        drop(file);
+
+        let store = AppendLogKvStore::open(&path).unwrap();
+        assert_eq!(collect(&store), vec![(b"key".to_vec(), b"value".to_vec())]);
+
+        // Log stays readable after new records are appended
+        store.put(b"new", b"value").unwrap();
+        drop(store);
+        let store = AppendLogKvStore::open(&path).unwrap();
+        assert_eq!(collect(&store).len(), 2);
+    }
+
+    #[test]
+    fn test_compaction() {
+        let dir = Builder::new().prefix("append_log").tempdir().unwrap();
+        let path = dir.path().join("vector.log");
+
+        let store = AppendLogKvStore::open(&path).unwrap();
+        let value = vec![7u8; 1024 * 1024];
+        for _ in 0..20 {
+            store.put(b"replaced", &value).unwrap();
+        }
+        store.put(b"kept", b"value").unwrap();
+        store.flusher()().unwrap();
+
+        let expected_size = record_size(8, value.len()) + record_size(4, 5);
+        assert_eq!(fs::metadata(&path).unwrap().len(), expected_size);
+        assert_eq!(store.get(b"replaced").unwrap(), Some(value));
+        assert_eq!(store.get(b"kept").unwrap(), Some(b"value".to_vec()));
+
+        store.put(b"after", b"compaction").unwrap();
+        drop(store);
+        let store = AppendLogKvStore::open(&path).unwrap();
+        assert_eq!(collect(&store).len(), 3);
+    }
+}
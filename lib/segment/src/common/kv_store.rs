@@ -0,0 +1,95 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use rocksdb::DB;
+
+use crate::common::append_log_kv_store::AppendLogKvStore;
+use crate::common::operation_error::OperationResult;
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::Flusher;
+use crate::types::KvStoreType;
+
+/// Directory inside of the segment, which contains files of key-value stores,
+/// not backed by RocksDB
+pub const KV_STORE_PATH: &str = "kv_store";
+
+pub type KvStoreRef = Arc<dyn KvStore>;
+
+/// Storage of key-value pairs of a single segment component, e.g. payload or ID mapping.
+///
+/// Implementations are not required to persist changes until `flusher` is called.
+pub trait KvStore: Send + Sync {
+    fn put(&self, key: &[u8], value: &[u8]) -> OperationResult<()>;
+
+    fn get(&self, key: &[u8]) -> OperationResult<Option<Vec<u8>>>;
+
+    fn remove(&self, key: &[u8]) -> OperationResult<()>;
+
+    /// Call `f` for each stored pair in key order, stop if it returns `false`
+    fn for_each(
+        &self,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> OperationResult<bool>,
+    ) -> OperationResult<()>;
+
+    /// Remove all stored pairs
+    fn clear(&self) -> OperationResult<()>;
+
+    fn flusher(&self) -> Flusher;
+}
+
+/// Open store of the segment column with the configured backend
+pub fn open_kv_store(
+    kv_store_type: KvStoreType,
+    database: Arc<RwLock<DB>>,
+    segment_path: &Path,
+    column_name: &str,
+) -> OperationResult<KvStoreRef> {
+    match kv_store_type {
+        KvStoreType::Rocksdb => Ok(Arc::new(DatabaseColumnWrapper::new(database, column_name))),
+        KvStoreType::AppendLog => Ok(Arc::new(AppendLogKvStore::open(&kv_store_path(
+            segment_path,
+            column_name,
+        ))?)),
+    }
+}
+
+pub fn kv_store_path(segment_path: &Path, column_name: &str) -> PathBuf {
+    segment_path
+        .join(KV_STORE_PATH)
+        .join(format!("{column_name}.log"))
+}
+
+impl KvStore for DatabaseColumnWrapper {
+    fn put(&self, key: &[u8], value: &[u8]) -> OperationResult<()> {
+        DatabaseColumnWrapper::put(self, key, value)
+    }
+
+    fn get(&self, key: &[u8]) -> OperationResult<Option<Vec<u8>>> {
+        self.get_pinned(key, |value| value.to_vec())
+    }
+
+    fn remove(&self, key: &[u8]) -> OperationResult<()> {
+        DatabaseColumnWrapper::remove(self, key)
+    }
+
+    fn for_each(
+        &self,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> OperationResult<bool>,
+    ) -> OperationResult<()> {
+        for (key, value) in self.lock_db().iter()? {
+            if !f(&key, &value)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&self) -> OperationResult<()> {
+        self.recreate_column_family()
+    }
+
+    fn flusher(&self) -> Flusher {
+        DatabaseColumnWrapper::flusher(self)
+    }
+}
@@ -0,0 +1,269 @@
+//! Upgrade of storage, created by older versions of the application
+//!
+//! Every storage keeps the version it was written with. On load, migrations matching the stored
+//! version are applied in place, and the version file is updated once all of them succeeded.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use semver::Version;
+
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::version::StorageVersion;
+
+/// Directory inside of the storage, where files are saved before being migrated
+pub const MIGRATION_BACKUP_DIR: &str = "migration_backup";
+
+/// Single upgrade step of a storage format
+pub struct StorageMigration {
+    /// First storage version, which requires the migration
+    pub since: Version,
+    /// First storage version, which does not require the migration anymore
+    pub before: Version,
+    /// Files in the storage directory, which are modified by the migration
+    pub files: &'static [&'static str],
+    pub migrate: fn(&Path) -> OperationResult<()>,
+}
+
+impl StorageMigration {
+    fn is_required(&self, stored_version: &Version) -> bool {
+        &self.since <= stored_version && stored_version < &self.before
+    }
+}
+
+/// Upgrade storage at `path` to the current version of `V`
+///
+/// Applies all `migrations`, required for the stored version, in order. Files modified by them
+/// are backed up first and restored if a migration fails. If the upgrade was interrupted, the
+/// backup is restored on the next attempt, so migrations are always applied to the original files.
+/// Backups are kept in [`MIGRATION_BACKUP_DIR`] after a successful upgrade.
+///
+/// Returns the stored version, if the storage was upgraded.
+pub fn migrate_storage<V: StorageVersion>(
+    path: &Path,
+    min_version: &Version,
+    migrations: &[StorageMigration],
+) -> OperationResult<Option<Version>> {
+    let stored_version: Version = V::load(path)?.parse()?;
+    let app_version: Version = V::current().parse()?;
+
+    if stored_version == app_version {
+        return Ok(None);
+    }
+
+    if stored_version > app_version {
+        return Err(OperationError::service_error(format!(
+            "Data version {stored_version} is newer than application version {app_version}. \
+            Please upgrade the application. Compatibility is not guaranteed."
+        )));
+    }
+
+    if &stored_version < min_version {
+        return Err(OperationError::service_error(format!(
+            "Storage version {stored_version} can't be upgraded to {app_version}, \
+            oldest supported version is {min_version}. Upgrade with an older version of Qdrant first."
+        )));
+    }
+
+    info!(
+        "Migrating storage {} from {stored_version} to {app_version}",
+        path.display(),
+    );
+
+    let required: Vec<_> = migrations
+        .iter()
+        .filter(|migration| migration.is_required(&stored_version))
+        .collect();
+
+    if !required.is_empty() {
+        let backup_path = path
+            .join(MIGRATION_BACKUP_DIR)
+            .join(stored_version.to_string());
+        if backup_path.exists() {
+            // Previous upgrade was interrupted, start over from the original files
+            restore_backup(path, &backup_path)?;
+        } else {
+            let files: BTreeSet<_> = required
+                .iter()
+                .flat_map(|migration| migration.files.iter().copied())
+                .collect();
+            create_backup(path, &backup_path, &files)?;
+        }
+
+        for migration in required {
+            if let Err(err) = (migration.migrate)(path) {
+                restore_backup(path, &backup_path)?;
+                return Err(err);
+            }
+        }
+    }
+
+    V::save(path)?;
+    Ok(Some(stored_version))
+}
+
+/// Copy `files` of the storage into `backup_path`
+///
+/// Backup is first written to a temporary directory, so an existing backup is always complete.
+fn create_backup(path: &Path, backup_path: &Path, files: &BTreeSet<&str>) -> OperationResult<()> {
+    let mut tmp_backup_path = backup_path.as_os_str().to_owned();
+    tmp_backup_path.push(".tmp");
+    let tmp_backup_path = PathBuf::from(tmp_backup_path);
+    if tmp_backup_path.exists() {
+        fs::remove_dir_all(&tmp_backup_path)?;
+    }
+    fs::create_dir_all(&tmp_backup_path)?;
+
+    for file in files {
+        let file_path = path.join(file);
+        if file_path.exists() {
+            fs::copy(&file_path, tmp_backup_path.join(file))?;
+        }
+    }
+
+    fs::rename(&tmp_backup_path, backup_path)?;
+    Ok(())
+}
+
+fn restore_backup(path: &Path, backup_path: &Path) -> OperationResult<()> {
+    for entry in fs::read_dir(backup_path)? {
+        let entry = entry?;
+        fs::copy(entry.path(), path.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::common::version::VERSION_FILE;
+
+    const STATE_FILE: &str = "state.json";
+
+    struct TestVersion;
+
+    impl StorageVersion for TestVersion {
+        fn current() -> String {
+            "0.6.0".to_string()
+        }
+    }
+
+    fn append_v3(path: &Path) -> OperationResult<()> {
+        let state = fs::read_to_string(path.join(STATE_FILE))?;
+        fs::write(path.join(STATE_FILE), format!("{state} v3"))?;
+        Ok(())
+    }
+
+    fn append_v5(path: &Path) -> OperationResult<()> {
+        let state = fs::read_to_string(path.join(STATE_FILE))?;
+        fs::write(path.join(STATE_FILE), format!("{state} v5"))?;
+        Ok(())
+    }
+
+    fn failing(path: &Path) -> OperationResult<()> {
+        fs::write(path.join(STATE_FILE), "corrupted")?;
+        Err(OperationError::service_error("migration failed"))
+    }
+
+    const MIGRATIONS: &[StorageMigration] = &[
+        StorageMigration {
+            since: Version::new(0, 3, 0),
+            before: Version::new(0, 4, 0),
+            files: &[STATE_FILE],
+            migrate: append_v3,
+        },
+        StorageMigration {
+            since: Version::new(0, 3, 0),
+            before: Version::new(0, 6, 0),
+            files: &[STATE_FILE],
+            migrate: append_v5,
+        },
+    ];
+
+    const MIN_VERSION: Version = Version::new(0, 3, 0);
+
+    fn init_storage(path: &Path, version: &str) {
+        fs::write(path.join(VERSION_FILE), version).unwrap();
+        fs::write(path.join(STATE_FILE), "state").unwrap();
+    }
+
+    #[test]
+    fn test_migrate_storage() {
+        let dir = Builder::new().prefix("storage").tempdir().unwrap();
+
+        init_storage(dir.path(), "0.3.1");
+        let migrated =
+            migrate_storage::<TestVersion>(dir.path(), &MIN_VERSION, MIGRATIONS).unwrap();
+        assert_eq!(migrated, Some(Version::new(0, 3, 1)));
+        assert_eq!(
+            fs::read_to_string(dir.path().join(STATE_FILE)).unwrap(),
+            "state v3 v5",
+        );
+        assert_eq!(TestVersion::load(dir.path()).unwrap(), "0.6.0");
+
+        let backup_path = dir.path().join(MIGRATION_BACKUP_DIR).join("0.3.1");
+        assert_eq!(
+            fs::read_to_string(backup_path.join(STATE_FILE)).unwrap(),
+            "state",
+        );
+
+        // Storage is up to date now
+        let migrated =
+            migrate_storage::<TestVersion>(dir.path(), &MIN_VERSION, MIGRATIONS).unwrap();
+        assert_eq!(migrated, None);
+    }
+
+    #[test]
+    fn test_migrate_storage_skips_versions() {
+        let dir = Builder::new().prefix("storage").tempdir().unwrap();
+
+        init_storage(dir.path(), "0.4.2");
+        migrate_storage::<TestVersion>(dir.path(), &MIN_VERSION, MIGRATIONS).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join(STATE_FILE)).unwrap(),
+            "state v5",
+        );
+    }
+
+    #[test]
+    fn test_migrate_storage_incompatible() {
+        let dir = Builder::new().prefix("storage").tempdir().unwrap();
+
+        init_storage(dir.path(), "0.2.0");
+        assert!(migrate_storage::<TestVersion>(dir.path(), &MIN_VERSION, MIGRATIONS).is_err());
+
+        init_storage(dir.path(), "0.7.0");
+        assert!(migrate_storage::<TestVersion>(dir.path(), &MIN_VERSION, MIGRATIONS).is_err());
+    }
+
+    #[test]
+    fn test_migrate_storage_restores_backup() {
+        let dir = Builder::new().prefix("storage").tempdir().unwrap();
+
+        let migrations = [StorageMigration {
+            since: Version::new(0, 3, 0),
+            before: Version::new(0, 6, 0),
+            files: &[STATE_FILE],
+            migrate: failing,
+        }];
+
+        init_storage(dir.path(), "0.5.0");
+        assert!(migrate_storage::<TestVersion>(dir.path(), &MIN_VERSION, &migrations).is_err());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(STATE_FILE)).unwrap(),
+            "state",
+        );
+        assert_eq!(TestVersion::load(dir.path()).unwrap(), "0.5.0");
+
+        // Retry with working migrations starts from the original files
+        migrate_storage::<TestVersion>(dir.path(), &MIN_VERSION, MIGRATIONS).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join(STATE_FILE)).unwrap(),
+            "state v5",
+        );
+    }
+}
@@ -1,7 +1,10 @@
 pub mod anonymize;
+pub mod append_log_kv_store;
 pub mod arc_atomic_ref_cell_iterator;
 pub mod cpu;
 pub mod error_logging;
+pub mod kv_store;
+pub mod migration;
 pub mod mmap_type;
 pub mod operation_error;
 pub mod operation_time_statistics;
@@ -3,23 +3,23 @@ use std::mem;
 
 use parking_lot::Mutex;
 
+use crate::common::kv_store::KvStoreRef;
 use crate::common::operation_error::OperationResult;
-use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, LockedDatabaseColumnWrapper};
 use crate::common::Flusher;
 
-/// Wrapper around `DatabaseColumnWrapper` that ensures, that keys that were removed from the
+/// Wrapper around `KvStore` that ensures, that keys that were removed from the
 /// database are only persisted on flush explicitly.
 ///
 /// This might be required to guarantee consistency of the database component.
 /// E.g. copy-on-write implementation should guarantee that data in the `write` component is
 /// persisted before it is removed from the `copy` component.
 pub struct DatabaseColumnScheduledDeleteWrapper {
-    db: DatabaseColumnWrapper,
+    db: KvStoreRef,
     deleted_pending_persistence: Mutex<HashSet<Vec<u8>>>,
 }
 
 impl DatabaseColumnScheduledDeleteWrapper {
-    pub fn new(db: DatabaseColumnWrapper) -> Self {
+    pub fn new(db: KvStoreRef) -> Self {
         Self {
             db,
             deleted_pending_persistence: Mutex::new(HashSet::new()),
@@ -32,7 +32,7 @@ impl DatabaseColumnScheduledDeleteWrapper {
         V: AsRef<[u8]>,
     {
         self.deleted_pending_persistence.lock().remove(key.as_ref());
-        self.db.put(key, value)
+        self.db.put(key.as_ref(), value.as_ref())
     }
 
     pub fn remove<K>(&self, key: K) -> OperationResult<()>
@@ -50,13 +50,16 @@ impl DatabaseColumnScheduledDeleteWrapper {
         let wrapper = self.db.clone();
         Box::new(move || {
             for id in ids_to_delete {
-                wrapper.remove(id)?;
+                wrapper.remove(&id)?;
             }
             wrapper.flusher()()
         })
     }
 
-    pub fn lock_db(&self) -> LockedDatabaseColumnWrapper {
-        self.db.lock_db()
+    pub fn for_each(
+        &self,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> OperationResult<bool>,
+    ) -> OperationResult<()> {
+        self.db.for_each(f)
     }
 }
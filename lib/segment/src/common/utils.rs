@@ -51,6 +51,13 @@ impl<T> MultiValue<T> {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Single(opt) => opt.is_none(),
+            Self::Multiple(vec) => vec.is_empty(),
+        }
+    }
+
     pub fn values(self) -> Vec<T> {
         match self {
             Self::Single(opt) => opt.into_iter().collect(),
@@ -320,6 +327,57 @@ pub fn remove_value_from_json_map(
     }
 }
 
+/// Update objects at a given JSON path of a JSON map
+///
+/// Missing objects on the path are created, values which are not objects are replaced by objects.
+/// Elements of arrays are only updated if they exist: `arr[1]` updates the second element,
+/// `arr[]` updates all elements.
+pub fn update_json_map_at_path(
+    path: &str,
+    json_map: &mut serde_json::Map<String, Value>,
+    update: &mut impl FnMut(&mut serde_json::Map<String, Value>),
+) {
+    let (element, rest_path) = match path.split_once('.') {
+        Some((element, rest_path)) => (element, Some(rest_path)),
+        None => (path, None),
+    };
+
+    match parse_array_path(element) {
+        Some((array_element_path, array_index)) => {
+            if let Some(Value::Array(array)) = json_map.get_mut(array_element_path) {
+                for (i, value) in array.iter_mut().enumerate() {
+                    if array_index.map_or(true, |array_index| i == array_index as usize) {
+                        update_json_value_at_path(rest_path, value, update);
+                    }
+                }
+            }
+        }
+        None => {
+            let value = json_map
+                .entry(element)
+                .or_insert_with(|| Value::Object(Default::default()));
+            update_json_value_at_path(rest_path, value, update);
+        }
+    }
+}
+
+fn update_json_value_at_path(
+    path: Option<&str>,
+    value: &mut Value,
+    update: &mut impl FnMut(&mut serde_json::Map<String, Value>),
+) {
+    if !value.is_object() {
+        *value = Value::Object(Default::default());
+    }
+    let Value::Object(map) = value else {
+        unreachable!("value is replaced by an object");
+    };
+    match path {
+        Some(path) => update_json_map_at_path(path, map, update),
+        None => update(map),
+    }
+}
+
 /// Check if a path is included in a list of patterns
 ///
 /// Basically, it checks if either the pattern or path is a prefix of the other.
@@ -500,6 +558,63 @@ mod tests {
         assert!(get_value_from_json_map("a.b.c.d", &map).check_is_empty());
     }
 
+    #[test]
+    fn test_update_json_map_at_path() {
+        let mut map = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "a": {
+                    "b": 1
+                },
+                "arr": [
+                    { "c": 1 },
+                    { "c": 2 },
+                    3
+                ]
+            }
+            "#,
+        )
+        .unwrap();
+        let mut set_x = |map: &mut serde_json::Map<String, Value>| {
+            map.insert("x".to_string(), Value::Number(10.into()));
+        };
+
+        update_json_map_at_path("a", &mut map, &mut set_x);
+        update_json_map_at_path("arr[1]", &mut map, &mut set_x);
+        // missing objects are created, other values are replaced
+        update_json_map_at_path("new.nested", &mut map, &mut set_x);
+        update_json_map_at_path("a.b", &mut map, &mut set_x);
+        // missing array elements are skipped
+        update_json_map_at_path("arr[5]", &mut map, &mut set_x);
+
+        let expected = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "a": {
+                    "b": { "x": 10 },
+                    "x": 10
+                },
+                "arr": [
+                    { "c": 1 },
+                    { "c": 2, "x": 10 },
+                    3
+                ],
+                "new": {
+                    "nested": { "x": 10 }
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(map, expected);
+
+        update_json_map_at_path("arr[]", &mut map, &mut set_x);
+        assert_eq!(
+            get_value_from_json_map("arr[].x", &map).values(),
+            vec![&Value::Number(10.into()); 3],
+        );
+    }
+
     #[test]
     fn test_is_empty() {
         let map = serde_json::from_str::<serde_json::Map<String, Value>>(
@@ -62,6 +62,9 @@ impl From<SegmentConfigV5> for SegmentConfig {
             vector_data,
             sparse_vector_data: Default::default(),
             payload_storage_type: old_segment.payload_storage_type,
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         }
     }
 }
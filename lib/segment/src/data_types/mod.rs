@@ -1,5 +1,7 @@
 pub mod groups;
+pub mod index;
 pub mod named_vectors;
+pub mod order_by;
 pub mod text_index;
 pub mod tiny_map;
 pub mod vectors;
@@ -0,0 +1,76 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{FloatPayloadType, PayloadKeyType, Range};
+
+/// Value of the `order_by` field the point was ordered by
+pub type OrderValue = FloatPayloadType;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct OrderBy {
+    /// Payload key to order by
+    pub key: PayloadKeyType,
+    /// Direction of ordering: `asc` or `desc`. Default is ascending.
+    pub direction: Option<Direction>,
+    /// Which payload value to start scrolling from. Default is the lowest value for `asc` and the highest for `desc`
+    pub start_from: Option<OrderValue>,
+}
+
+impl OrderBy {
+    pub fn direction(&self) -> Direction {
+        self.direction.unwrap_or_default()
+    }
+
+    /// Range of values which should be visited, given the `start_from` value and direction
+    pub fn value_range(&self) -> Range {
+        match (self.start_from, self.direction()) {
+            (None, _) => Range::default(),
+            (Some(start_from), Direction::Asc) => Range {
+                gte: Some(start_from),
+                ..Default::default()
+            },
+            (Some(start_from), Direction::Desc) => Range {
+                lte: Some(start_from),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Compare two order values according to the direction
+    pub fn compare(&self, a: OrderValue, b: OrderValue) -> std::cmp::Ordering {
+        let ordering = a.total_cmp(&b);
+        match self.direction() {
+            Direction::Asc => ordering,
+            Direction::Desc => ordering.reverse(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum OrderByInterface {
+    Key(PayloadKeyType),
+    Struct(OrderBy),
+}
+
+impl From<OrderByInterface> for OrderBy {
+    fn from(order_by: OrderByInterface) -> Self {
+        match order_by {
+            OrderByInterface::Key(key) => OrderBy {
+                key,
+                direction: None,
+                start_from: None,
+            },
+            OrderByInterface::Struct(order_by) => order_by,
+        }
+    }
+}
@@ -15,6 +15,17 @@ pub enum TokenizerType {
     #[default]
     Word,
     Multilingual,
+    Ngram,
+}
+
+/// Language hint of the multilingual tokenizer
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenizerLanguage {
+    Chinese,
+    Japanese,
+    Korean,
+    Thai,
 }
 
 #[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
@@ -41,4 +52,68 @@ pub struct TextIndexParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// If true, lowercase all tokens. Default: true
     pub lowercase: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Reduce tokens to their stems, so different forms of a word match each other.
+    /// Not applied with the prefix and ngram tokenizers. Default: no stemming
+    pub stemmer: Option<StemmerParams>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Tokens to exclude from the index and from queries. Default: no stop words
+    pub stopwords: Option<StopwordsParams>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// Languages of the text, used by the multilingual tokenizer to choose word segmentation
+    /// of scripts shared by several languages, such as Chinese and Japanese.
+    /// Default: detect the language of each text
+    pub languages: Vec<TokenizerLanguage>,
+}
+
+/// Snowball stemming algorithm
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct StemmerParams {
+    pub language: StemmerLanguage,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StemmerLanguage {
+    Arabic,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Greek,
+    Hungarian,
+    Italian,
+    Norwegian,
+    Portuguese,
+    Romanian,
+    Russian,
+    Spanish,
+    Swedish,
+    Tamil,
+    Turkish,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct StopwordsParams {
+    /// Languages to use built-in stop words lists of
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<StopwordsLanguage>,
+    /// Additional stop words. Compared with tokens after lowercasing, if it is enabled
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopwordsLanguage {
+    English,
 }
@@ -225,6 +225,13 @@ impl VectorStruct {
     }
 }
 
+/// No vectors, used by points of collections without vectors
+impl Default for VectorStruct {
+    fn default() -> Self {
+        VectorStruct::Multi(HashMap::new())
+    }
+}
+
 impl Validate for VectorStruct {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
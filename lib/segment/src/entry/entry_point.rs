@@ -2,15 +2,18 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 
+use common::types::ScoreType;
+
 use crate::common::operation_error::{OperationResult, SegmentFailedState};
 use crate::data_types::named_vectors::NamedVectors;
-use crate::data_types::vectors::{QueryVector, Vector};
+use crate::data_types::order_by::{OrderBy, OrderValue};
+use crate::data_types::vectors::{QueryVector, Vector, VectorType};
 use crate::index::field_index::CardinalityEstimation;
 use crate::telemetry::SegmentTelemetry;
 use crate::types::{
-    Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
-    ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType, WithPayload,
-    WithVector,
+    Filter, Payload, PayloadFieldSchema, PayloadFieldStatistics, PayloadKeyType, PayloadKeyTypeRef,
+    PointIdType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType,
+    WithPayload, WithVector,
 };
 
 /// Define all operations which can be performed with Segment or Segment-like entity.
@@ -114,6 +117,8 @@ pub trait SegmentEntry {
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_>;
 
     /// Paginate over points which satisfies filtering condition starting with `offset` id including.
+    ///
+    /// Returned IDs are sorted in ascending order.
     fn read_filtered<'a>(
         &'a self,
         offset: Option<PointIdType>,
@@ -121,12 +126,49 @@ pub trait SegmentEntry {
         filter: Option<&'a Filter>,
     ) -> Vec<PointIdType>;
 
+    /// Read points which satisfy filtering condition, ordered by the value of `order_by` payload field.
+    ///
+    /// Requires a numeric index on the `order_by` key. Each point is returned once,
+    /// with its first value in the requested direction.
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>>;
+
+    /// Read a uniform random sample of at most `limit` points, which satisfy filtering condition.
+    ///
+    /// Each point is returned with its random sampling key, so samples of multiple segments
+    /// can be merged by keeping the points with the largest keys.
+    fn read_random_filtered<'a>(
+        &'a self,
+        limit: usize,
+        filter: Option<&'a Filter>,
+    ) -> Vec<(ScoreType, PointIdType)>;
+
+    /// Count points which satisfy filtering condition, per distinct value of the keyword field `key`.
+    ///
+    /// If `exact` is `false`, counts may be estimated from the payload index instead of
+    /// checking every point.
+    fn facet<'a>(
+        &'a self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&'a Filter>,
+        exact: bool,
+    ) -> OperationResult<HashMap<String, usize>>;
+
     /// Read points in [from; to) range
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType>;
 
     /// Check if there is point with `point_id` in this segment.
     fn has_point(&self, point_id: PointIdType) -> bool;
 
+    /// Select points, which are present in this segment, ordered as their data is laid out in storage.
+    ///
+    /// Reading points in this order turns random storage access into a mostly sequential one.
+    fn select_points_in_storage_order(&self, point_ids: &[PointIdType]) -> Vec<PointIdType>;
+
     /// Estimate available point count in this segment for given filter.
     fn estimate_point_count<'a>(&'a self, filter: Option<&'a Filter>) -> CardinalityEstimation;
 
@@ -184,6 +226,16 @@ pub trait SegmentEntry {
     /// Get indexed fields
     fn get_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema>;
 
+    /// Get statistics of values of indexed payload fields
+    fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics>;
+
+    /// Get size of the files with vectors, vector index and quantized vectors of each vector
+    fn get_vector_storage_size_bytes(&self) -> HashMap<String, usize>;
+
+    /// Get share of values of `vectors` outside of the value range, on which quantization of the
+    /// vector was trained. `None` if the vector is not quantized.
+    fn get_quantization_drift(&self, vector_name: &str, vectors: &[VectorType]) -> Option<f32>;
+
     /// Checks if segment errored during last operations
     fn check_error(&self) -> Option<SegmentFailedState>;
 
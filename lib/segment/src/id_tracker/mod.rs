@@ -1,4 +1,5 @@
 pub mod id_tracker_base;
+pub mod point_id_filter;
 pub mod simple_id_tracker;
 
 pub use id_tracker_base::*;
@@ -0,0 +1,110 @@
+use std::hash::{Hash, Hasher};
+
+use bitvec::prelude::BitVec;
+use seahash::SeaHasher;
+
+use crate::types::PointIdType;
+
+/// Number of filter bits per point id, gives ~1% false positive rate with `NUM_HASHES`
+const BITS_PER_POINT: usize = 10;
+
+const NUM_HASHES: usize = 7;
+
+/// Capacity of a filter for a new or small segment
+const MIN_CAPACITY: usize = 1024;
+
+/// Bloom filter over point ids of a segment.
+///
+/// Tells if a segment definitely doesn't contain a point, without looking up the id mapping.
+/// Removed points are not cleared from the filter, they only produce false positives.
+#[derive(Debug, Clone)]
+pub struct PointIdFilter {
+    bits: BitVec,
+    capacity: usize,
+    inserted: usize,
+}
+
+impl PointIdFilter {
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(MIN_CAPACITY);
+        Self {
+            bits: BitVec::repeat(false, capacity * BITS_PER_POINT),
+            capacity,
+            inserted: 0,
+        }
+    }
+
+    /// Build a filter for the given ids, with some space left for inserting new ones
+    pub fn from_ids(ids: impl Iterator<Item = PointIdType>, count: usize) -> Self {
+        let mut filter = Self::with_capacity(count * 2);
+        for id in ids {
+            filter.insert(id);
+        }
+        filter
+    }
+
+    /// Whether the filter reached its capacity, so the false positive rate would grow
+    /// with further insertions
+    pub fn is_full(&self) -> bool {
+        self.inserted >= self.capacity
+    }
+
+    pub fn insert(&mut self, id: PointIdType) {
+        for position in self.bit_positions(id) {
+            self.bits.set(position, true);
+        }
+        self.inserted += 1;
+    }
+
+    /// Returns `false` if the id was never inserted, `true` if it probably was
+    pub fn may_contain(&self, id: PointIdType) -> bool {
+        self.bit_positions(id).all(|position| self.bits[position])
+    }
+
+    /// Derive all bit positions of the id from a single hash, using double hashing
+    fn bit_positions(&self, id: PointIdType) -> impl Iterator<Item = usize> {
+        let mut hasher = SeaHasher::new();
+        id.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let h1 = hash as u32 as u64;
+        let h2 = (hash >> 32) | 1;
+        let bits_count = self.bits.len() as u64;
+        (0..NUM_HASHES as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bits_count) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_no_false_negatives() {
+        let ids: Vec<_> = (0..10_000)
+            .map(PointIdType::NumId)
+            .chain((0..1_000).map(|i| PointIdType::Uuid(Uuid::from_u128(i))))
+            .collect();
+        let filter = PointIdFilter::from_ids(ids.iter().copied(), ids.len());
+
+        assert!(ids.iter().all(|id| filter.may_contain(*id)));
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let points_count = 10_000;
+        let mut filter = PointIdFilter::with_capacity(points_count);
+        for id in 0..points_count as u64 {
+            filter.insert(PointIdType::NumId(id));
+        }
+        assert!(filter.is_full());
+
+        let false_positives = (points_count as u64..2 * points_count as u64)
+            .filter(|&id| filter.may_contain(PointIdType::NumId(id)))
+            .count();
+
+        assert!(false_positives < points_count / 50, "{false_positives}");
+    }
+}
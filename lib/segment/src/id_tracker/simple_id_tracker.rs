@@ -9,10 +9,12 @@ use rocksdb::DB;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::common::kv_store::KvStoreRef;
 use crate::common::operation_error::OperationResult;
 use crate::common::rocksdb_buffered_delete_wrapper::DatabaseColumnScheduledDeleteWrapper;
 use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, DB_MAPPING_CF, DB_VERSIONS_CF};
 use crate::common::Flusher;
+use crate::id_tracker::point_id_filter::PointIdFilter;
 use crate::id_tracker::IdTracker;
 use crate::types::{ExtendedPointId, PointIdType, SeqNumberType};
 
@@ -62,24 +64,35 @@ pub struct SimpleIdTracker {
     internal_to_version: Vec<SeqNumberType>,
     external_to_internal_num: BTreeMap<u64, PointOffsetType>,
     external_to_internal_uuid: BTreeMap<Uuid, PointOffsetType>,
+    /// Allows to skip mapping lookups of the points, which are not in this segment
+    point_filter: PointIdFilter,
     mapping_db_wrapper: DatabaseColumnScheduledDeleteWrapper,
     versions_db_wrapper: DatabaseColumnScheduledDeleteWrapper,
 }
 
 impl SimpleIdTracker {
     pub fn open(store: Arc<RwLock<DB>>) -> OperationResult<Self> {
+        Self::open_with_stores(
+            Arc::new(DatabaseColumnWrapper::new(store.clone(), DB_MAPPING_CF)),
+            Arc::new(DatabaseColumnWrapper::new(store, DB_VERSIONS_CF)),
+        )
+    }
+
+    /// Open tracker, which persists point ID mapping and versions in the given stores
+    pub fn open_with_stores(
+        mapping_store: KvStoreRef,
+        versions_store: KvStoreRef,
+    ) -> OperationResult<Self> {
         let mut deleted = BitVec::new();
         let mut internal_to_external: Vec<PointIdType> = Default::default();
         let mut external_to_internal_num: BTreeMap<u64, PointOffsetType> = Default::default();
         let mut external_to_internal_uuid: BTreeMap<Uuid, PointOffsetType> = Default::default();
 
-        let mapping_db_wrapper = DatabaseColumnScheduledDeleteWrapper::new(
-            DatabaseColumnWrapper::new(store.clone(), DB_MAPPING_CF),
-        );
-        for (key, val) in mapping_db_wrapper.lock_db().iter()? {
-            let external_id = Self::restore_key(&key);
+        let mapping_db_wrapper = DatabaseColumnScheduledDeleteWrapper::new(mapping_store);
+        mapping_db_wrapper.for_each(&mut |key, val| {
+            let external_id = Self::restore_key(key);
             let internal_id: PointOffsetType =
-                bincode::deserialize::<PointOffsetType>(&val).unwrap();
+                bincode::deserialize::<PointOffsetType>(val).unwrap();
             if internal_id as usize >= internal_to_external.len() {
                 internal_to_external.resize(internal_id as usize + 1, PointIdType::NumId(u64::MAX));
             }
@@ -117,15 +130,14 @@ impl SimpleIdTracker {
                     external_to_internal_uuid.insert(uuid, internal_id);
                 }
             }
-        }
+            Ok(true)
+        })?;
 
         let mut internal_to_version: Vec<SeqNumberType> = Default::default();
-        let versions_db_wrapper = DatabaseColumnScheduledDeleteWrapper::new(
-            DatabaseColumnWrapper::new(store, DB_VERSIONS_CF),
-        );
-        for (key, val) in versions_db_wrapper.lock_db().iter()? {
-            let external_id = Self::restore_key(&key);
-            let version: SeqNumberType = bincode::deserialize(&val).unwrap();
+        let versions_db_wrapper = DatabaseColumnScheduledDeleteWrapper::new(versions_store);
+        versions_db_wrapper.for_each(&mut |key, val| {
+            let external_id = Self::restore_key(key);
+            let version: SeqNumberType = bincode::deserialize(val).unwrap();
             let internal_id = match external_id {
                 PointIdType::NumId(idx) => external_to_internal_num.get(&idx).copied(),
                 PointIdType::Uuid(uuid) => external_to_internal_uuid.get(&uuid).copied(),
@@ -141,7 +153,8 @@ impl SimpleIdTracker {
                     external_id
                 );
             }
-        }
+            Ok(true)
+        })?;
 
         #[cfg(debug_assertions)]
         {
@@ -155,15 +168,24 @@ impl SimpleIdTracker {
             }
         }
 
-        Ok(SimpleIdTracker {
+        let mut id_tracker = SimpleIdTracker {
             deleted,
             internal_to_external,
             internal_to_version,
             external_to_internal_num,
             external_to_internal_uuid,
+            point_filter: PointIdFilter::with_capacity(0),
             mapping_db_wrapper,
             versions_db_wrapper,
-        })
+        };
+        id_tracker.rebuild_point_filter();
+        Ok(id_tracker)
+    }
+
+    fn rebuild_point_filter(&mut self) {
+        let points_count =
+            self.external_to_internal_num.len() + self.external_to_internal_uuid.len();
+        self.point_filter = PointIdFilter::from_ids(self.iter_external(), points_count);
     }
 
     fn store_key(external_id: &PointIdType) -> Vec<u8> {
@@ -215,6 +237,9 @@ impl IdTracker for SimpleIdTracker {
     }
 
     fn internal_id(&self, external_id: PointIdType) -> Option<PointOffsetType> {
+        if !self.point_filter.may_contain(external_id) {
+            return None;
+        }
         match external_id {
             PointIdType::NumId(idx) => self.external_to_internal_num.get(&idx).copied(),
             PointIdType::Uuid(uuid) => self.external_to_internal_uuid.get(&uuid).copied(),
@@ -255,6 +280,13 @@ impl IdTracker for SimpleIdTracker {
         self.internal_to_external[internal_id] = external_id;
         self.deleted.set(internal_id, false);
 
+        if self.point_filter.is_full() {
+            // Grow the filter to keep false positive rate low, new point is already in the mapping
+            self.rebuild_point_filter();
+        } else {
+            self.point_filter.insert(external_id);
+        }
+
         self.persist_key(&external_id, internal_id)?;
         Ok(())
     }
@@ -439,6 +471,45 @@ mod tests {
         assert_eq!(last.len(), 7);
     }
 
+    #[test]
+    fn test_point_filter_grows() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        let mut id_tracker = SimpleIdTracker::open(db.clone()).unwrap();
+
+        // Insert more points than the initial filter capacity
+        let points_count = 5_000;
+        for id in 0..points_count {
+            id_tracker
+                .set_link((id as u64).into(), id as PointOffsetType)
+                .unwrap();
+        }
+
+        for id in 0..points_count {
+            assert_eq!(
+                id_tracker.internal_id((id as u64).into()),
+                Some(id as PointOffsetType)
+            );
+        }
+        assert_eq!(id_tracker.internal_id((points_count as u64).into()), None);
+
+        id_tracker.drop(0.into()).unwrap();
+        assert_eq!(id_tracker.internal_id(0.into()), None);
+
+        // Filter is restored on load
+        id_tracker.mapping_flusher()().unwrap();
+        drop(id_tracker);
+        let id_tracker = SimpleIdTracker::open(db).unwrap();
+        for id in 1..points_count {
+            assert_eq!(
+                id_tracker.internal_id((id as u64).into()),
+                Some(id as PointOffsetType)
+            );
+        }
+        assert_eq!(id_tracker.internal_id(0.into()), None);
+    }
+
     #[test]
     fn test_mixed_types_iterator() {
         let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
@@ -9,7 +9,9 @@ use super::{CardinalityEstimation, PayloadFieldIndex, PrimaryCondition, ValueInd
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::telemetry::PayloadIndexTelemetry;
-use crate::types::{FieldCondition, Match, MatchValue, PayloadKeyType, ValueVariants};
+use crate::types::{
+    FieldCondition, Match, MatchValue, PayloadFieldStatistics, PayloadKeyType, ValueVariants,
+};
 
 mod memory {
     use bitvec::vec::BitVec;
@@ -191,6 +193,19 @@ impl BinaryIndex {
         }
     }
 
+    pub fn get_statistics(&self) -> PayloadFieldStatistics {
+        let trues_count = self.memory.trues_count();
+        let falses_count = self.memory.falses_count();
+        PayloadFieldStatistics {
+            points_count: self.memory.indexed_count(),
+            values_count: trues_count + falses_count,
+            distinct_values_count: Some(
+                usize::from(trues_count > 0) + usize::from(falses_count > 0),
+            ),
+            ..Default::default()
+        }
+    }
+
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
         let binary_item = self.memory.get(point_id);
         binary_item.has_true() as usize + binary_item.has_false() as usize
@@ -9,11 +9,14 @@ use crate::common::Flusher;
 use crate::index::field_index::binary_index::BinaryIndex;
 use crate::index::field_index::full_text_index::text_index::FullTextIndex;
 use crate::index::field_index::geo_index::GeoMapIndex;
+use crate::index::field_index::null_index::NullIndex;
 use crate::index::field_index::numeric_index::NumericIndex;
+use crate::index::field_index::values_count_index::ValuesCountIndex;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
-    FieldCondition, FloatPayloadType, IntPayloadType, Match, MatchText, PayloadKeyType,
+    FieldCondition, FloatPayloadType, IntPayloadType, Match, PayloadFieldStatistics,
+    PayloadKeyType, Range,
 };
 
 pub trait PayloadFieldIndex {
@@ -122,6 +125,8 @@ pub enum FieldIndex {
     GeoIndex(GeoMapIndex),
     FullTextIndex(FullTextIndex),
     BinaryIndex(BinaryIndex),
+    NullIndex(NullIndex),
+    ValuesCountIndex(ValuesCountIndex),
 }
 
 impl FieldIndex {
@@ -144,9 +149,11 @@ impl FieldIndex {
             FieldIndex::FloatIndex(_) => None,
             FieldIndex::GeoIndex(_) => None,
             FieldIndex::BinaryIndex(_) => None,
+            FieldIndex::NullIndex(_) => None,
+            FieldIndex::ValuesCountIndex(_) => None,
             FieldIndex::FullTextIndex(full_text_index) => match &condition.r#match {
-                Some(Match::Text(MatchText { text })) => {
-                    let query = full_text_index.parse_query(text);
+                Some(Match::Text(text_match)) => {
+                    let query = full_text_index.parse_text_match(text_match);
                     for value in full_text_index.get_values(payload_value) {
                         let document = full_text_index.parse_document(&value);
                         if query.check_match(&document) {
@@ -169,6 +176,8 @@ impl FieldIndex {
             FieldIndex::GeoIndex(payload_field_index) => payload_field_index,
             FieldIndex::BinaryIndex(payload_field_index) => payload_field_index,
             FieldIndex::FullTextIndex(payload_field_index) => payload_field_index,
+            FieldIndex::NullIndex(payload_field_index) => payload_field_index,
+            FieldIndex::ValuesCountIndex(payload_field_index) => payload_field_index,
         }
     }
 
@@ -182,6 +191,8 @@ impl FieldIndex {
             FieldIndex::GeoIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::BinaryIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::FullTextIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::NullIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::ValuesCountIndex(ref mut payload_field_index) => payload_field_index,
         }
     }
 
@@ -194,6 +205,8 @@ impl FieldIndex {
             FieldIndex::GeoIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::BinaryIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::FullTextIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::NullIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::ValuesCountIndex(ref mut payload_field_index) => payload_field_index.load(),
         }
     }
 
@@ -206,6 +219,8 @@ impl FieldIndex {
             FieldIndex::GeoIndex(index) => index.clear(),
             FieldIndex::BinaryIndex(index) => index.clear(),
             FieldIndex::FullTextIndex(index) => index.clear(),
+            FieldIndex::NullIndex(index) => index.clear(),
+            FieldIndex::ValuesCountIndex(index) => index.clear(),
         }
     }
 
@@ -218,6 +233,8 @@ impl FieldIndex {
             FieldIndex::GeoIndex(index) => index.recreate(),
             FieldIndex::BinaryIndex(index) => index.recreate(),
             FieldIndex::FullTextIndex(index) => index.recreate(),
+            FieldIndex::NullIndex(index) => index.recreate(),
+            FieldIndex::ValuesCountIndex(index) => index.recreate(),
         }
     }
 
@@ -280,6 +297,12 @@ impl FieldIndex {
             FieldIndex::FullTextIndex(ref mut payload_field_index) => {
                 payload_field_index.add_point(id, payload)
             }
+            FieldIndex::NullIndex(ref mut payload_field_index) => {
+                payload_field_index.add_point(id, payload)
+            }
+            FieldIndex::ValuesCountIndex(ref mut payload_field_index) => {
+                payload_field_index.add_point(id, payload)
+            }
         }
     }
 
@@ -292,6 +315,8 @@ impl FieldIndex {
             FieldIndex::GeoIndex(index) => index.remove_point(point_id),
             FieldIndex::BinaryIndex(index) => index.remove_point(point_id),
             FieldIndex::FullTextIndex(index) => index.remove_point(point_id),
+            FieldIndex::NullIndex(index) => index.remove_point(point_id),
+            FieldIndex::ValuesCountIndex(index) => index.remove_point(point_id),
         }
     }
 
@@ -304,6 +329,46 @@ impl FieldIndex {
             FieldIndex::GeoIndex(index) => index.get_telemetry_data(),
             FieldIndex::BinaryIndex(index) => index.get_telemetry_data(),
             FieldIndex::FullTextIndex(index) => index.get_telemetry_data(),
+            FieldIndex::NullIndex(index) => index.get_telemetry_data(),
+            FieldIndex::ValuesCountIndex(index) => index.get_telemetry_data(),
+        }
+    }
+
+    pub fn get_statistics(&self) -> PayloadFieldStatistics {
+        match self {
+            FieldIndex::IntIndex(index) => index.get_statistics(),
+            FieldIndex::IntMapIndex(index) => index.get_statistics(),
+            FieldIndex::KeywordIndex(index) => index.get_statistics(),
+            FieldIndex::FloatIndex(index) => index.get_statistics(),
+            FieldIndex::GeoIndex(index) => index.get_statistics(),
+            FieldIndex::BinaryIndex(index) => index.get_statistics(),
+            FieldIndex::FullTextIndex(index) => index.get_statistics(),
+            FieldIndex::NullIndex(index) => index.get_statistics(),
+            FieldIndex::ValuesCountIndex(index) => index.get_statistics(),
+        }
+    }
+
+    /// Iterate over numeric values within `range` along with the point offsets, in ascending order.
+    ///
+    /// Returns `None` if the index does not keep numeric values in sorted order.
+    pub fn stream_range(
+        &self,
+        range: &Range,
+    ) -> Option<Box<dyn DoubleEndedIterator<Item = (FloatPayloadType, PointOffsetType)> + '_>> {
+        match self {
+            FieldIndex::IntIndex(index) => Some(Box::new(
+                index
+                    .stream_range(range)
+                    .map(|(value, idx)| (value as FloatPayloadType, idx)),
+            )),
+            FieldIndex::FloatIndex(index) => Some(index.stream_range(range)),
+            FieldIndex::IntMapIndex(_)
+            | FieldIndex::KeywordIndex(_)
+            | FieldIndex::GeoIndex(_)
+            | FieldIndex::FullTextIndex(_)
+            | FieldIndex::BinaryIndex(_)
+            | FieldIndex::NullIndex(_)
+            | FieldIndex::ValuesCountIndex(_) => None,
         }
     }
 
@@ -316,6 +381,8 @@ impl FieldIndex {
             FieldIndex::GeoIndex(index) => index.values_count(point_id),
             FieldIndex::BinaryIndex(index) => index.values_count(point_id),
             FieldIndex::FullTextIndex(index) => index.values_count(point_id),
+            FieldIndex::NullIndex(index) => usize::from(!index.values_is_empty(point_id)),
+            FieldIndex::ValuesCountIndex(index) => index.values_count(point_id),
         }
     }
 
@@ -328,6 +395,8 @@ impl FieldIndex {
             FieldIndex::GeoIndex(index) => index.values_is_empty(point_id),
             FieldIndex::BinaryIndex(index) => index.values_is_empty(point_id),
             FieldIndex::FullTextIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::NullIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::ValuesCountIndex(index) => index.values_is_empty(point_id),
         }
     }
 }
@@ -0,0 +1,65 @@
+//! Okapi BM25 relevance of full-text matches.
+//!
+//! Lets a full-text index rank documents by the query tokens, not only filter them.
+
+use common::types::ScoreType;
+
+/// Parameters of the BM25 scoring function
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bm25Params {
+    /// Saturation of the term frequency. Higher values give more weight to repeated tokens.
+    pub k1: f32,
+    /// Normalization by the document length. `0` disables it, `1` normalizes fully.
+    pub b: f32,
+}
+
+impl Default for Bm25Params {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+impl Bm25Params {
+    /// Inverse document frequency of a token, found in `doc_freq` out of `docs_count` documents.
+    /// Always positive, so a match never lowers the score.
+    pub fn idf(&self, doc_freq: usize, docs_count: usize) -> ScoreType {
+        let doc_freq = doc_freq as f32;
+        let docs_count = docs_count as f32;
+        (1.0 + (docs_count - doc_freq + 0.5) / (doc_freq + 0.5)).ln()
+    }
+
+    /// Score of a single token in a document, without the idf factor
+    pub fn term_score(&self, term_freq: usize, doc_len: usize, avg_doc_len: f32) -> ScoreType {
+        let term_freq = term_freq as f32;
+        let length_norm = if avg_doc_len > 0.0 {
+            1.0 - self.b + self.b * doc_len as f32 / avg_doc_len
+        } else {
+            1.0
+        };
+        term_freq * (self.k1 + 1.0) / (term_freq + self.k1 * length_norm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bm25_monotonicity() {
+        let params = Bm25Params::default();
+
+        // Rare tokens are more relevant
+        assert!(params.idf(1, 100) > params.idf(10, 100));
+        assert!(params.idf(100, 100) > 0.0);
+
+        // Repeated tokens are more relevant, but saturate
+        let once = params.term_score(1, 10, 10.0);
+        let twice = params.term_score(2, 10, 10.0);
+        assert!(twice > once);
+        assert!(twice < 2.0 * once);
+        assert!(params.term_score(1000, 10, 10.0) < params.k1 + 1.0);
+
+        // Same token in a shorter document is more relevant
+        assert!(params.term_score(1, 5, 10.0) > params.term_score(1, 20, 10.0));
+    }
+}
@@ -1,8 +1,9 @@
 use std::collections::{BTreeSet, HashMap};
 
-use common::types::PointOffsetType;
+use common::types::{PointOffsetType, ScoreType, ScoredPointOffset};
 use serde::{Deserialize, Serialize};
 
+use super::bm25::Bm25Params;
 use super::posting_list::PostingList;
 use super::postings_iterator::intersect_postings_iterator;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition, PrimaryCondition};
@@ -10,15 +11,27 @@ use crate::types::{FieldCondition, Match, MatchText, PayloadKeyType};
 
 pub type TokenId = u32;
 
+/// Marks a break in the token sequence of a document, e.g. between values of a multi-value field.
+/// Phrases never match across it.
+pub const PHRASE_GAP: TokenId = TokenId::MAX;
+
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
 pub struct Document {
     tokens: Vec<TokenId>,
+    /// Tokens in order of appearance, used to match phrases.
+    /// Empty for documents, indexed before phrase matching was supported.
+    #[serde(default)]
+    sequence: Vec<TokenId>,
 }
 
 impl Document {
-    pub fn new(mut tokens: Vec<TokenId>) -> Self {
+    pub fn new(tokens: Vec<TokenId>) -> Self {
+        Self::with_sequence(tokens, vec![])
+    }
+
+    pub fn with_sequence(mut tokens: Vec<TokenId>, sequence: Vec<TokenId>) -> Self {
         tokens.sort_unstable();
-        Self { tokens }
+        Self { tokens, sequence }
     }
 
     pub fn len(&self) -> usize {
@@ -36,11 +49,47 @@ impl Document {
     pub fn check(&self, token: TokenId) -> bool {
         self.tokens.binary_search(&token).is_ok()
     }
+
+    /// Check that the phrase tokens appear in the document next to each other
+    /// and in the same order.
+    ///
+    /// Documents without token sequence can't be verified,
+    /// so only presence of the tokens is checked.
+    pub fn check_phrase(&self, phrase: &[TokenId]) -> bool {
+        if self.sequence.is_empty() {
+            return phrase.iter().all(|&token| self.check(token));
+        }
+        if phrase.is_empty() {
+            return true;
+        }
+        self.sequence
+            .windows(phrase.len())
+            .any(|window| window == phrase)
+    }
+
+    /// Number of occurrences of the token in the document
+    pub fn term_frequency(&self, token: TokenId) -> usize {
+        if self.sequence.is_empty() {
+            return usize::from(self.check(token));
+        }
+        self.sequence.iter().filter(|&&t| t == token).count()
+    }
+
+    /// Number of tokens in the document, including repeated ones
+    pub fn length(&self) -> usize {
+        if self.sequence.is_empty() {
+            return self.tokens.len();
+        }
+        self.sequence.iter().filter(|&&t| t != PHRASE_GAP).count()
+    }
 }
 
 #[derive(Debug)]
 pub struct ParsedQuery {
     pub tokens: Vec<Option<TokenId>>,
+    /// Query tokens in order, if the query is a phrase.
+    /// Only set if all phrase tokens are in the vocabulary.
+    pub phrase: Option<Vec<TokenId>>,
 }
 
 impl ParsedQuery {
@@ -53,6 +102,10 @@ impl ParsedQuery {
             .iter()
             // unwrap crash safety: all tokens exist in the vocabulary if it passes the above check
             .all(|query_token| document.check(query_token.unwrap()))
+            && self
+                .phrase
+                .as_ref()
+                .map_or(true, |phrase| document.check_phrase(phrase))
     }
 }
 
@@ -62,6 +115,8 @@ pub struct InvertedIndex {
     pub vocab: HashMap<String, TokenId>,
     pub point_to_docs: Vec<Option<Document>>,
     pub points_count: usize,
+    /// Sum of lengths of all indexed documents
+    total_length: usize,
 }
 
 impl InvertedIndex {
@@ -69,7 +124,14 @@ impl InvertedIndex {
         Default::default()
     }
 
-    pub fn document_from_tokens(&mut self, tokens: &BTreeSet<String>) -> Document {
+    /// Build a document from its unique tokens and their sequence.
+    ///
+    /// `sequence` refers to tokens by their position in `tokens`, with `PHRASE_GAP` marking breaks.
+    pub fn document_from_tokens(
+        &mut self,
+        tokens: &BTreeSet<String>,
+        sequence: &[u32],
+    ) -> Document {
         let mut document_tokens = vec![];
         for token in tokens {
             // check if in vocab
@@ -84,11 +146,27 @@ impl InvertedIndex {
             document_tokens.push(vocab_idx);
         }
 
-        Document::new(document_tokens)
+        let document_sequence = sequence
+            .iter()
+            .map(|&position| {
+                document_tokens
+                    .get(position as usize)
+                    .copied()
+                    .unwrap_or(PHRASE_GAP)
+            })
+            .collect();
+
+        Document::with_sequence(document_tokens, document_sequence)
+    }
+
+    /// Sum of lengths of all indexed documents
+    pub fn total_length(&self) -> usize {
+        self.total_length
     }
 
     pub fn index_document(&mut self, idx: PointOffsetType, document: Document) {
         self.points_count += 1;
+        self.total_length += document.length();
         if self.point_to_docs.len() <= idx as usize {
             self.point_to_docs
                 .resize_with(idx as usize + 1, Default::default);
@@ -123,6 +201,7 @@ impl InvertedIndex {
         };
 
         self.points_count -= 1;
+        self.total_length -= removed_doc.length();
 
         for removed_token in removed_doc.tokens() {
             // unwrap safety: posting list exists and contains the document id
@@ -154,7 +233,50 @@ impl InvertedIndex {
             // Empty request -> no matches
             return Box::new(vec![].into_iter());
         }
-        intersect_postings_iterator(postings)
+        let candidates = intersect_postings_iterator(postings);
+        match &query.phrase {
+            None => candidates,
+            Some(phrase) => Box::new(candidates.filter(move |&idx| {
+                self.point_to_docs
+                    .get(idx as usize)
+                    .and_then(|document| document.as_ref())
+                    .map_or(false, |document| document.check_phrase(phrase))
+            })),
+        }
+    }
+
+    /// Score documents, which contain any of the query tokens, by BM25 relevance to the query.
+    ///
+    /// Unknown query tokens are ignored, repeated query tokens are counted once.
+    pub fn score_bm25(&self, query: &ParsedQuery, params: &Bm25Params) -> Vec<ScoredPointOffset> {
+        let avg_doc_len = if self.points_count > 0 {
+            self.total_length as f32 / self.points_count as f32
+        } else {
+            0.0
+        };
+
+        let mut scores: HashMap<PointOffsetType, ScoreType> = HashMap::new();
+        for token in query.tokens.iter().flatten() {
+            let Some(Some(posting)) = self.postings.get(*token as usize) else {
+                continue;
+            };
+            let idf = params.idf(posting.len(), self.points_count);
+            for idx in posting.iter() {
+                // unwrap safety: posting lists only contain indexed documents
+                let document = self.point_to_docs[idx as usize].as_ref().unwrap();
+                let term_score = params.term_score(
+                    document.term_frequency(*token),
+                    document.length(),
+                    avg_doc_len,
+                );
+                *scores.entry(idx).or_default() += idf * term_score;
+            }
+        }
+
+        scores
+            .into_iter()
+            .map(|(idx, score)| ScoredPointOffset { idx, score })
+            .collect()
     }
 
     pub fn estimate_cardinality(
@@ -193,7 +315,13 @@ impl InvertedIndex {
         // Smallest posting is the largest possible cardinality
         let smallest_posting = postings.iter().map(|posting| posting.len()).min().unwrap();
 
-        return if postings.len() == 1 {
+        // Phrase with repeated token, e.g. "very very", is not guaranteed to match
+        let is_phrase = query
+            .phrase
+            .as_ref()
+            .map_or(false, |phrase| phrase.len() > 1);
+
+        return if postings.len() == 1 && !is_phrase {
             CardinalityEstimation {
                 primary_clauses: vec![PrimaryCondition::Condition(condition.clone())],
                 min: smallest_posting,
@@ -242,6 +370,7 @@ impl InvertedIndex {
                         key: key.clone(),
                         r#match: Some(Match::Text(MatchText {
                             text: token.clone(),
+                            expand: None,
                         })),
                         range: None,
                         geo_bounding_box: None,
@@ -1,8 +1,10 @@
+pub mod bm25;
 mod inverted_index;
 mod posting_list;
 mod postings_iterator;
+mod stop_words;
 pub mod text_index;
-mod tokenizers;
+pub mod tokenizers;
 
 #[cfg(test)]
 mod tests;
@@ -158,6 +158,9 @@ fn test_prefix_search() {
         min_token_len: None,
         max_token_len: None,
         lowercase: None,
+        stemmer: None,
+        stopwords: None,
+        languages: vec![],
     };
 
     let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
@@ -194,3 +197,72 @@ fn test_prefix_search() {
 
     assert_eq!(res.len(), 0);
 }
+
+#[test]
+fn test_ngram_search() {
+    let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+    let config = TextIndexParams {
+        r#type: TextIndexType::Text,
+        tokenizer: TokenizerType::Ngram,
+        ..Default::default()
+    };
+
+    let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+    let mut index = FullTextIndex::new(db, config, "text");
+    index.recreate().unwrap();
+
+    let texts = get_texts();
+
+    for (i, text) in texts.iter().enumerate() {
+        index
+            .add_many(i as PointOffsetType, vec![text.to_string()])
+            .unwrap();
+    }
+
+    // Matches a part in the middle of a word
+    let res: Vec<_> = index.query("OBOT").collect();
+
+    let query = index.parse_query("OBOT");
+
+    for idx in res.iter() {
+        let doc = index.get_doc(*idx).unwrap();
+        assert!(query.check_match(doc));
+    }
+
+    assert_eq!(res.len(), 3);
+
+    let res: Vec<_> = index.query("q231").collect();
+
+    assert_eq!(res.len(), 0);
+}
+
+#[test]
+fn test_statistics() {
+    let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+    let config = TextIndexParams {
+        r#type: TextIndexType::Text,
+        tokenizer: TokenizerType::Word,
+        ..Default::default()
+    };
+
+    let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+    let mut index = FullTextIndex::new(db, config, "text");
+    index.recreate().unwrap();
+
+    index
+        .add_many(0, vec!["Rain, Rain, Go Away".to_string()])
+        .unwrap();
+    index.add_many(1, vec!["Robot Dreams".to_string()]).unwrap();
+
+    let statistics = index.get_statistics();
+    assert_eq!(statistics.points_count, 2);
+    assert_eq!(statistics.distinct_values_count, Some(5));
+    // Repeated tokens are counted
+    assert_eq!(statistics.tokens_count, Some(6));
+    assert_eq!(statistics.avg_document_length, Some(3.0));
+
+    index.remove_point(0).unwrap();
+    let statistics = index.get_statistics();
+    assert_eq!(statistics.tokens_count, Some(2));
+    assert_eq!(statistics.avg_document_length, Some(2.0));
+}
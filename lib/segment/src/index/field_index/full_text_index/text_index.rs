@@ -1,7 +1,7 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 
-use common::types::PointOffsetType;
+use common::types::{PointOffsetType, ScoredPointOffset};
 use parking_lot::RwLock;
 use rocksdb::DB;
 use serde::{Deserialize, Serialize};
@@ -11,15 +11,17 @@ use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
 use crate::data_types::text_index::TextIndexParams;
+use crate::index::field_index::full_text_index::bm25::Bm25Params;
 use crate::index::field_index::full_text_index::inverted_index::{
-    Document, InvertedIndex, ParsedQuery,
+    Document, InvertedIndex, ParsedQuery, PHRASE_GAP,
 };
 use crate::index::field_index::full_text_index::tokenizers::Tokenizer;
 use crate::index::field_index::{
     CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, ValueIndexer,
 };
+use crate::spaces::tools::peek_top_largest_iterable;
 use crate::telemetry::PayloadIndexTelemetry;
-use crate::types::{FieldCondition, Match, PayloadKeyType};
+use crate::types::{FieldCondition, Match, MatchText, PayloadFieldStatistics, PayloadKeyType};
 
 pub struct FullTextIndex {
     inverted_index: InvertedIndex,
@@ -36,12 +38,17 @@ impl FullTextIndex {
         bincode::deserialize(data).unwrap()
     }
 
-    fn serialize_document_tokens(&self, tokens: BTreeSet<String>) -> OperationResult<Vec<u8>> {
+    fn serialize_document_tokens(
+        &self,
+        tokens: BTreeSet<String>,
+        sequence: Vec<u32>,
+    ) -> OperationResult<Vec<u8>> {
         #[derive(Serialize)]
         struct StoredDocument {
             tokens: BTreeSet<String>,
+            sequence: Vec<u32>,
         }
-        let doc = StoredDocument { tokens };
+        let doc = StoredDocument { tokens, sequence };
         serde_cbor::to_vec(&doc).map_err(|e| {
             OperationError::service_error(format!("Failed to serialize document: {e}"))
         })
@@ -51,12 +58,14 @@ impl FullTextIndex {
         #[derive(Deserialize)]
         struct StoredDocument {
             tokens: BTreeSet<String>,
+            #[serde(default)]
+            sequence: Vec<u32>,
         }
         serde_cbor::from_slice::<StoredDocument>(data)
             .map_err(|e| {
                 OperationError::service_error(format!("Failed to deserialize document: {e}"))
             })
-            .map(|doc| index.document_from_tokens(&doc.tokens))
+            .map(|doc| index.document_from_tokens(&doc.tokens, &doc.sequence))
     }
 
     fn storage_cf_name(field: &str) -> String {
@@ -89,6 +98,18 @@ impl FullTextIndex {
         }
     }
 
+    /// Distinct values are the distinct tokens ever indexed, they are not forgotten on removal
+    pub fn get_statistics(&self) -> PayloadFieldStatistics {
+        let mut statistics = PayloadFieldStatistics {
+            points_count: self.inverted_index.points_count,
+            values_count: self.inverted_index.points_count,
+            distinct_values_count: Some(self.inverted_index.vocab.len()),
+            ..Default::default()
+        };
+        statistics.set_tokens_count(self.inverted_index.total_length());
+        statistics
+    }
+
     pub fn recreate(&self) -> OperationResult<()> {
         self.db_wrapper.recreate_column_family()
     }
@@ -100,6 +121,30 @@ impl FullTextIndex {
         });
         ParsedQuery {
             tokens: tokens.into_iter().collect(),
+            phrase: None,
+        }
+    }
+
+    /// Parse query, which matches only documents with all tokens of the text next to each other
+    pub fn parse_phrase_query(&self, text: &str) -> ParsedQuery {
+        let mut tokens = HashSet::new();
+        let mut phrase = vec![];
+        Tokenizer::tokenize_query(text, &self.config, |token| {
+            let token_id = self.inverted_index.vocab.get(token).copied();
+            tokens.insert(token_id);
+            phrase.push(token_id);
+        });
+        ParsedQuery {
+            tokens: tokens.into_iter().collect(),
+            phrase: phrase.into_iter().collect(),
+        }
+    }
+
+    /// Parse query of the full-text match condition, either a phrase or a set of tokens
+    pub fn parse_text_match(&self, text_match: &MatchText) -> ParsedQuery {
+        match text_match.phrase() {
+            Some(phrase) => self.parse_phrase_query(phrase),
+            None => self.parse_query(&text_match.text),
         }
     }
 
@@ -110,15 +155,39 @@ impl FullTextIndex {
                 document_tokens.push(*token_id);
             }
         });
-        Document::new(document_tokens)
+        let mut sequence = vec![];
+        Tokenizer::tokenize_query(text, &self.config, |token| {
+            let token_id = self.inverted_index.vocab.get(token).copied();
+            sequence.push(token_id.unwrap_or(PHRASE_GAP));
+        });
+        Document::with_sequence(document_tokens, sequence)
     }
 
     #[cfg(test)]
     pub fn query(&self, query: &str) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
-        let parsed_query = self.parse_query(query);
+        let parsed_query = self.parse_text_match(&MatchText::from(query.to_owned()));
         self.inverted_index.filter(&parsed_query)
     }
 
+    /// Rank documents by BM25 relevance to the text, instead of only filtering by it.
+    ///
+    /// Only documents with at least one token of the text and accepted by `filter` are returned.
+    pub fn search_bm25(
+        &self,
+        text: &str,
+        params: &Bm25Params,
+        top: usize,
+        filter: impl Fn(PointOffsetType) -> bool,
+    ) -> Vec<ScoredPointOffset> {
+        let query = self.parse_query(text);
+        let scored = self
+            .inverted_index
+            .score_bm25(&query, params)
+            .into_iter()
+            .filter(|scored| filter(scored.idx));
+        peek_top_largest_iterable(scored, top)
+    }
+
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
         // Maybe we want number of documents in the future?
         self.get_doc(point_id).map(|x| x.len()).unwrap_or(0)
@@ -136,18 +205,40 @@ impl ValueIndexer<String> for FullTextIndex {
         }
 
         let mut tokens: BTreeSet<String> = BTreeSet::new();
+        // Words of all values in order, tokenized the same way as phrase queries
+        let mut phrase_tokens: Vec<Option<String>> = vec![];
 
         for value in values {
             Tokenizer::tokenize_doc(&value, &self.config, |token| {
                 tokens.insert(token.to_owned());
             });
+            if !phrase_tokens.is_empty() {
+                phrase_tokens.push(None);
+            }
+            Tokenizer::tokenize_query(&value, &self.config, |token| {
+                phrase_tokens.push(Some(token.to_owned()));
+            });
         }
 
-        let document = self.inverted_index.document_from_tokens(&tokens);
+        tokens.extend(phrase_tokens.iter().flatten().cloned());
+        let token_positions: HashMap<&str, u32> = tokens
+            .iter()
+            .enumerate()
+            .map(|(position, token)| (token.as_str(), position as u32))
+            .collect();
+        let sequence: Vec<u32> = phrase_tokens
+            .iter()
+            .map(|token| match token {
+                Some(token) => token_positions[token.as_str()],
+                None => PHRASE_GAP,
+            })
+            .collect();
+
+        let document = self.inverted_index.document_from_tokens(&tokens, &sequence);
         self.inverted_index.index_document(idx, document);
 
         let db_idx = Self::store_key(&idx);
-        let db_document = self.serialize_document_tokens(tokens)?;
+        let db_document = self.serialize_document_tokens(tokens, sequence)?;
 
         self.db_wrapper.put(db_idx, db_document)?;
 
@@ -206,7 +297,7 @@ impl PayloadFieldIndex for FullTextIndex {
         condition: &FieldCondition,
     ) -> OperationResult<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
         if let Some(Match::Text(text_match)) = &condition.r#match {
-            let parsed_query = self.parse_query(&text_match.text);
+            let parsed_query = self.parse_text_match(text_match);
             return Ok(self.inverted_index.filter(&parsed_query));
         }
         Err(OperationError::service_error("failed to filter"))
@@ -217,7 +308,7 @@ impl PayloadFieldIndex for FullTextIndex {
         condition: &FieldCondition,
     ) -> OperationResult<CardinalityEstimation> {
         if let Some(Match::Text(text_match)) = &condition.r#match {
-            let parsed_query = self.parse_query(&text_match.text);
+            let parsed_query = self.parse_text_match(text_match);
             return Ok(self
                 .inverted_index
                 .estimate_cardinality(&parsed_query, condition));
@@ -244,13 +335,13 @@ mod tests {
     use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
     use crate::common::utils::MultiValue;
     use crate::data_types::text_index::{TextIndexType, TokenizerType};
-    use crate::types::MatchText;
 
     fn filter_request(text: &str) -> FieldCondition {
         FieldCondition {
             key: "text".to_owned(),
             r#match: Some(Match::Text(MatchText {
                 text: text.to_owned(),
+                expand: None,
             })),
             range: None,
             geo_bounding_box: None,
@@ -280,6 +371,9 @@ mod tests {
             min_token_len: None,
             max_token_len: None,
             lowercase: None,
+            stemmer: None,
+            stopwords: None,
+            languages: vec![],
         };
 
         {
@@ -350,4 +444,122 @@ mod tests {
             assert_eq!(search_res, vec![0, 1, 3, 4]);
         }
     }
+
+    #[test]
+    fn test_phrase_matching() {
+        let payloads: Vec<_> = vec![
+            serde_json::json!("The giant computer was silent"),
+            serde_json::json!("The computer was a giant one"),
+            serde_json::json!(["It was a giant", "computer of the future"]),
+            serde_json::json!("Giant computer, giant computer"),
+        ];
+
+        let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+        let config = TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Word,
+            min_token_len: None,
+            max_token_len: None,
+            lowercase: None,
+            stemmer: None,
+            stopwords: None,
+            languages: vec![],
+        };
+
+        {
+            let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+            let mut index = FullTextIndex::new(db, config.clone(), "text");
+            index.recreate().unwrap();
+
+            for (idx, payload) in payloads.iter().enumerate() {
+                index
+                    .add_point(idx as PointOffsetType, &MultiValue::one(payload))
+                    .unwrap();
+            }
+
+            let search_res: Vec<_> = index.query("giant computer").collect();
+            assert_eq!(search_res, vec![0, 1, 2, 3]);
+
+            // Phrase doesn't match across values of a multi-value field
+            let search_res: Vec<_> = index.query("\"giant computer\"").collect();
+            assert_eq!(search_res, vec![0, 3]);
+
+            let search_res: Vec<_> = index.query("\"computer giant\"").collect();
+            assert_eq!(search_res, vec![3]);
+
+            let search_res: Vec<_> = index.query("\"giant unknown\"").collect();
+            assert!(search_res.is_empty());
+
+            // Indexed condition check agrees with the filter
+            let query = index.parse_text_match(&MatchText::from("\"giant computer\"".to_owned()));
+            let document = index.parse_document("A giant computer");
+            assert!(query.check_match(&document));
+            let document = index.parse_document("A computer, giant");
+            assert!(!query.check_match(&document));
+
+            index.flusher()().unwrap();
+        }
+
+        {
+            let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+            let mut index = FullTextIndex::new(db, config, "text");
+            assert!(index.load().unwrap());
+
+            let search_res: Vec<_> = index.query("\"giant computer\"").collect();
+            assert_eq!(search_res, vec![0, 3]);
+        }
+    }
+
+    #[test]
+    fn test_bm25_search() {
+        let payloads: Vec<_> = vec![
+            serde_json::json!("Vector search engine"),
+            serde_json::json!("A search engine with filters, and a vector index for vector search"),
+            serde_json::json!("Engine of a car"),
+            serde_json::json!("Vector"),
+        ];
+
+        let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+        let config = TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Word,
+            min_token_len: None,
+            max_token_len: None,
+            lowercase: None,
+            stemmer: None,
+            stopwords: None,
+            languages: vec![],
+        };
+
+        let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+        let mut index = FullTextIndex::new(db, config, "text");
+        index.recreate().unwrap();
+
+        for (idx, payload) in payloads.iter().enumerate() {
+            index
+                .add_point(idx as PointOffsetType, &MultiValue::one(payload))
+                .unwrap();
+        }
+
+        let params = Bm25Params::default();
+
+        // Short document with both tokens is the most relevant, documents without tokens are skipped
+        let res = index.search_bm25("vector search", &params, 10, |_| true);
+        let ids: Vec<_> = res.iter().map(|scored| scored.idx).collect();
+        assert_eq!(ids[0], 0);
+        assert_eq!(ids.len(), 3);
+        assert!(!ids.contains(&2));
+        assert!(res.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        // Rare token outweighs the common one
+        let res = index.search_bm25("car engine", &params, 1, |_| true);
+        assert_eq!(res[0].idx, 2);
+
+        let res = index.search_bm25("vector search", &params, 10, |idx| idx != 0);
+        assert!(res.iter().all(|scored| scored.idx != 0));
+
+        assert!(index
+            .search_bm25("unknown", &params, 10, |_| true)
+            .is_empty());
+    }
 }
@@ -1,6 +1,19 @@
-use charabia::Tokenize;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
-use crate::data_types::text_index::{TextIndexParams, TokenizerType};
+use charabia::{Language, Script, TokenizerBuilder};
+use rust_stemmers::{Algorithm, Stemmer};
+
+use super::stop_words::is_builtin_stopword;
+use crate::data_types::text_index::{
+    StemmerLanguage, TextIndexParams, TokenizerLanguage, TokenizerType,
+};
+
+/// Length of ngrams, if the bounds are not configured, which keeps the index size linear in
+/// the length of the text
+const DEFAULT_NGRAM_LEN: usize = 3;
 
 struct WhiteSpaceTokenizer;
 
@@ -62,16 +75,87 @@ impl PrefixTokenizer {
     }
 }
 
+struct NgramTokenizer;
+
+impl NgramTokenizer {
+    /// All parts of each word, which are from `min_ngram` to `max_ngram` characters long.
+    /// Words shorter than `min_ngram` are kept whole.
+    fn tokenize<C: FnMut(&str)>(text: &str, min_ngram: usize, max_ngram: usize, mut callback: C) {
+        WordTokenizer::tokenize(text, |word| {
+            let bounds: Vec<usize> = word
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain([word.len()])
+                .collect();
+            let chars_count = bounds.len() - 1;
+            if chars_count < min_ngram {
+                callback(word);
+                return;
+            }
+            for n in min_ngram..=max_ngram.min(chars_count) {
+                for start in 0..=chars_count - n {
+                    callback(&word[bounds[start]..bounds[start + n]]);
+                }
+            }
+        });
+    }
+
+    /// For querying, it is enough to use ngrams of maximal length only, as all of them
+    /// must be present in a matching word.
+    /// E.g. with ngrams of 2 to 3 characters
+    ///
+    /// Docs. tokens: "hello" -> ["he", "el", "ll", "lo", "hel", "ell", "llo"]
+    /// Query tokens: "el"    -> ["el"]
+    /// Query tokens: "ell"   -> ["ell"]
+    /// Query tokens: "ello"  -> ["ell", "llo"]
+    fn tokenize_query<C: FnMut(&str)>(text: &str, max_ngram: usize, callback: C) {
+        Self::tokenize(text, max_ngram, max_ngram, callback);
+    }
+}
+
 struct MultilingualTokenizer;
 
 impl MultilingualTokenizer {
-    fn tokenize<C: FnMut(&str)>(text: &str, mut callback: C) {
-        text.tokenize().for_each(|token| {
+    /// Segment text into words, using dictionaries of the detected language.
+    ///
+    /// `languages` restrict detection for their scripts, other scripts are detected freely.
+    fn tokenize<C: FnMut(&str)>(text: &str, languages: &[TokenizerLanguage], mut callback: C) {
+        Self::tokenize_spans(text, languages, |token, _| callback(token));
+    }
+
+    /// Same as [`Self::tokenize`], but also passes the byte range of each word in `text`
+    fn tokenize_spans<C: FnMut(&str, Range<usize>)>(
+        text: &str,
+        languages: &[TokenizerLanguage],
+        mut callback: C,
+    ) {
+        let allow_list = Self::allow_list(languages);
+        let mut builder = TokenizerBuilder::default();
+        if !allow_list.is_empty() {
+            builder.allow_list(&allow_list);
+        }
+        let tokenizer = builder.build();
+
+        tokenizer.tokenize(text).for_each(|token| {
             if token.is_word() {
-                callback(token.lemma());
+                callback(token.lemma(), token.byte_start..token.byte_end);
             }
         });
     }
+
+    fn allow_list(languages: &[TokenizerLanguage]) -> HashMap<Script, Vec<Language>> {
+        let mut allow_list: HashMap<Script, Vec<Language>> = HashMap::new();
+        for language in languages {
+            let (script, language) = match language {
+                TokenizerLanguage::Chinese => (Script::Cj, Language::Cmn),
+                TokenizerLanguage::Japanese => (Script::Cj, Language::Jpn),
+                TokenizerLanguage::Korean => (Script::Hangul, Language::Kor),
+                TokenizerLanguage::Thai => (Script::Thai, Language::Tha),
+            };
+            allow_list.entry(script).or_default().push(language);
+        }
+        allow_list
+    }
 }
 
 pub struct Tokenizer;
@@ -81,6 +165,17 @@ impl Tokenizer {
         config: &'a TextIndexParams,
         mut callback: C,
     ) -> impl FnMut(&str) + 'a {
+        // Stems of word parts are meaningless, so prefix and ngram tokens are never stemmed
+        let stemmer = config
+            .stemmer
+            .filter(|_| {
+                !matches!(
+                    config.tokenizer,
+                    TokenizerType::Prefix | TokenizerType::Ngram
+                )
+            })
+            .map(|params| Stemmer::create(stemmer_algorithm(params.language)));
+
         move |token: &str| {
             if config
                 .min_token_len
@@ -96,26 +191,52 @@ impl Tokenizer {
             {
                 return;
             }
-            if config.lowercase.unwrap_or(true) {
-                callback(&token.to_lowercase());
+            let token = if config.lowercase.unwrap_or(true) {
+                Cow::Owned(token.to_lowercase())
             } else {
-                callback(token);
+                Cow::Borrowed(token)
+            };
+            if Self::is_stopword(config, &token) {
+                return;
+            }
+            match &stemmer {
+                Some(stemmer) => callback(&stemmer.stem(&token)),
+                None => callback(&token),
             }
         }
     }
 
+    fn is_stopword(config: &TextIndexParams, token: &str) -> bool {
+        let Some(stopwords) = &config.stopwords else {
+            return false;
+        };
+        stopwords
+            .languages
+            .iter()
+            .any(|&language| is_builtin_stopword(language, token))
+            || stopwords.custom.iter().any(|stopword| stopword == token)
+    }
+
     pub fn tokenize_doc<C: FnMut(&str)>(text: &str, config: &TextIndexParams, mut callback: C) {
         let token_filter = Self::doc_token_filter(config, &mut callback);
         match config.tokenizer {
             TokenizerType::Whitespace => WhiteSpaceTokenizer::tokenize(text, token_filter),
             TokenizerType::Word => WordTokenizer::tokenize(text, token_filter),
-            TokenizerType::Multilingual => MultilingualTokenizer::tokenize(text, token_filter),
+            TokenizerType::Multilingual => {
+                MultilingualTokenizer::tokenize(text, &config.languages, token_filter)
+            }
             TokenizerType::Prefix => PrefixTokenizer::tokenize(
                 text,
                 config.min_token_len.unwrap_or(1),
                 config.max_token_len.unwrap_or(usize::MAX),
                 token_filter,
             ),
+            TokenizerType::Ngram => NgramTokenizer::tokenize(
+                text,
+                config.min_token_len.unwrap_or(DEFAULT_NGRAM_LEN),
+                config.max_token_len.unwrap_or(DEFAULT_NGRAM_LEN),
+                token_filter,
+            ),
         }
     }
 
@@ -124,20 +245,118 @@ impl Tokenizer {
         match config.tokenizer {
             TokenizerType::Whitespace => WhiteSpaceTokenizer::tokenize(text, token_filter),
             TokenizerType::Word => WordTokenizer::tokenize(text, token_filter),
-            TokenizerType::Multilingual => MultilingualTokenizer::tokenize(text, token_filter),
+            TokenizerType::Multilingual => {
+                MultilingualTokenizer::tokenize(text, &config.languages, token_filter)
+            }
             TokenizerType::Prefix => PrefixTokenizer::tokenize_query(
                 text,
                 config.max_token_len.unwrap_or(usize::MAX),
                 token_filter,
             ),
+            TokenizerType::Ngram => NgramTokenizer::tokenize_query(
+                text,
+                config.max_token_len.unwrap_or(DEFAULT_NGRAM_LEN),
+                token_filter,
+            ),
+        }
+    }
+
+    /// Byte ranges of the words in `text`, which match any token of the `query`.
+    ///
+    /// Overlapping ranges, e.g. of several prefixes of the same word, are merged.
+    pub fn highlight(text: &str, query: &str, config: &TextIndexParams) -> Vec<Range<usize>> {
+        let mut query_tokens = HashSet::new();
+        Self::tokenize_query(query, config, |token| {
+            query_tokens.insert(token.to_owned());
+        });
+
+        let matched = Cell::new(false);
+        let mut token_filter = Self::doc_token_filter(config, |token: &str| {
+            if query_tokens.contains(token) {
+                matched.set(true);
+            }
+        });
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        Self::tokenize_spans(text, config, |token, span| {
+            matched.set(false);
+            token_filter(token);
+            if !matched.get() {
+                return;
+            }
+            match ranges.last_mut() {
+                Some(last) if span.start <= last.end => last.end = last.end.max(span.end),
+                _ => ranges.push(span),
+            }
+        });
+        ranges
+    }
+
+    /// Unfiltered document tokens along with their byte ranges in `text`
+    fn tokenize_spans<C: FnMut(&str, Range<usize>)>(
+        text: &str,
+        config: &TextIndexParams,
+        mut callback: C,
+    ) {
+        if config.tokenizer == TokenizerType::Multilingual {
+            return MultilingualTokenizer::tokenize_spans(text, &config.languages, callback);
+        }
+
+        // Tokens of other tokenizers are slices of the text
+        let text_start = text.as_ptr() as usize;
+        let with_span = |token: &str| {
+            let start = token.as_ptr() as usize - text_start;
+            callback(token, start..start + token.len());
+        };
+        match config.tokenizer {
+            TokenizerType::Whitespace => WhiteSpaceTokenizer::tokenize(text, with_span),
+            TokenizerType::Word => WordTokenizer::tokenize(text, with_span),
+            TokenizerType::Prefix => PrefixTokenizer::tokenize(
+                text,
+                config.min_token_len.unwrap_or(1),
+                config.max_token_len.unwrap_or(usize::MAX),
+                with_span,
+            ),
+            TokenizerType::Ngram => NgramTokenizer::tokenize(
+                text,
+                config.min_token_len.unwrap_or(DEFAULT_NGRAM_LEN),
+                config.max_token_len.unwrap_or(DEFAULT_NGRAM_LEN),
+                with_span,
+            ),
+            TokenizerType::Multilingual => unreachable!(),
         }
     }
 }
 
+fn stemmer_algorithm(language: StemmerLanguage) -> Algorithm {
+    match language {
+        StemmerLanguage::Arabic => Algorithm::Arabic,
+        StemmerLanguage::Danish => Algorithm::Danish,
+        StemmerLanguage::Dutch => Algorithm::Dutch,
+        StemmerLanguage::English => Algorithm::English,
+        StemmerLanguage::Finnish => Algorithm::Finnish,
+        StemmerLanguage::French => Algorithm::French,
+        StemmerLanguage::German => Algorithm::German,
+        StemmerLanguage::Greek => Algorithm::Greek,
+        StemmerLanguage::Hungarian => Algorithm::Hungarian,
+        StemmerLanguage::Italian => Algorithm::Italian,
+        StemmerLanguage::Norwegian => Algorithm::Norwegian,
+        StemmerLanguage::Portuguese => Algorithm::Portuguese,
+        StemmerLanguage::Romanian => Algorithm::Romanian,
+        StemmerLanguage::Russian => Algorithm::Russian,
+        StemmerLanguage::Spanish => Algorithm::Spanish,
+        StemmerLanguage::Swedish => Algorithm::Swedish,
+        StemmerLanguage::Tamil => Algorithm::Tamil,
+        StemmerLanguage::Turkish => Algorithm::Turkish,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data_types::text_index::TextIndexType;
+    use crate::data_types::text_index::{
+        StemmerParams, StopwordsLanguage, StopwordsParams, TextIndexType,
+    };
 
     #[test]
     fn test_whitespace_tokenizer() {
@@ -188,12 +407,34 @@ mod tests {
         assert_eq!(tokens.get(1), Some(&"мир".to_owned()));
     }
 
-    #[cfg(feature = "multiling-japanese")]
+    #[test]
+    fn test_ngram_tokenizer() {
+        let text = "hello, мир!";
+        let mut tokens = Vec::new();
+        NgramTokenizer::tokenize(text, 2, 3, |token| tokens.push(token.to_owned()));
+        assert_eq!(
+            tokens,
+            ["he", "el", "ll", "lo", "hel", "ell", "llo", "ми", "ир", "мир"],
+        );
+
+        let mut tokens = Vec::new();
+        NgramTokenizer::tokenize("a tv", 3, 3, |token| tokens.push(token.to_owned()));
+        assert_eq!(tokens, ["a", "tv"]);
+    }
+
+    #[test]
+    fn test_ngram_query_tokenizer() {
+        let text = "ello, ми";
+        let mut tokens = Vec::new();
+        NgramTokenizer::tokenize_query(text, 3, |token| tokens.push(token.to_owned()));
+        assert_eq!(tokens, ["ell", "llo", "ми"]);
+    }
+
     #[test]
     fn test_multilingual_tokenizer_japanese() {
         let text = "本日の日付は";
         let mut tokens = Vec::new();
-        MultilingualTokenizer::tokenize(text, |token| tokens.push(token.to_owned()));
+        MultilingualTokenizer::tokenize(text, &[], |token| tokens.push(token.to_owned()));
         eprintln!("tokens = {tokens:#?}");
         assert_eq!(tokens.len(), 4);
         assert_eq!(tokens.get(0), Some(&"本日".to_owned()));
@@ -202,12 +443,11 @@ mod tests {
         assert_eq!(tokens.get(3), Some(&"は".to_owned()));
     }
 
-    #[cfg(feature = "multiling-chinese")]
     #[test]
     fn test_multilingual_tokenizer_chinese() {
         let text = "今天是星期一";
         let mut tokens = Vec::new();
-        MultilingualTokenizer::tokenize(text, |token| tokens.push(token.to_owned()));
+        MultilingualTokenizer::tokenize(text, &[], |token| tokens.push(token.to_owned()));
         eprintln!("tokens = {tokens:#?}");
         assert_eq!(tokens.len(), 3);
         assert_eq!(tokens.get(0), Some(&"jīntiān".to_owned()));
@@ -215,11 +455,32 @@ mod tests {
         assert_eq!(tokens.get(2), Some(&"xīngqīyī".to_owned()));
     }
 
+    #[test]
+    fn test_multilingual_tokenizer_language_hints() {
+        // Kanji only text is written the same in Chinese and Japanese
+        let text = "日本語";
+        let is_cjk = |c: char| ('\u{4E00}'..='\u{9FFF}').contains(&c);
+
+        let mut tokens = Vec::new();
+        MultilingualTokenizer::tokenize(text, &[TokenizerLanguage::Japanese], |token| {
+            tokens.push(token.to_owned())
+        });
+        assert_eq!(tokens.concat(), text);
+
+        // Chinese text is normalized into pinyin
+        let mut tokens = Vec::new();
+        MultilingualTokenizer::tokenize(text, &[TokenizerLanguage::Chinese], |token| {
+            tokens.push(token.to_owned())
+        });
+        assert!(!tokens.is_empty());
+        assert!(tokens.iter().all(|token| !token.chars().any(is_cjk)));
+    }
+
     #[test]
     fn test_multilingual_tokenizer_thai() {
         let text = "มาทำงานกันเถอะ";
         let mut tokens = Vec::new();
-        MultilingualTokenizer::tokenize(text, |token| tokens.push(token.to_owned()));
+        MultilingualTokenizer::tokenize(text, &[], |token| tokens.push(token.to_owned()));
         eprintln!("tokens = {tokens:#?}");
         assert_eq!(tokens.len(), 4);
         assert_eq!(tokens.get(0), Some(&"มา".to_owned()));
@@ -232,7 +493,7 @@ mod tests {
     fn test_multilingual_tokenizer_english() {
         let text = "What are you waiting for?";
         let mut tokens = Vec::new();
-        MultilingualTokenizer::tokenize(text, |token| tokens.push(token.to_owned()));
+        MultilingualTokenizer::tokenize(text, &[], |token| tokens.push(token.to_owned()));
         eprintln!("tokens = {tokens:#?}");
         assert_eq!(tokens.len(), 5);
         assert_eq!(tokens.get(0), Some(&"what".to_owned()));
@@ -254,6 +515,9 @@ mod tests {
                 min_token_len: Some(1),
                 max_token_len: Some(4),
                 lowercase: Some(true),
+                stemmer: None,
+                stopwords: None,
+                languages: vec![],
             },
             |token| tokens.push(token.to_owned()),
         );
@@ -267,4 +531,67 @@ mod tests {
         assert_eq!(tokens.get(5), Some(&"ми".to_owned()));
         assert_eq!(tokens.get(6), Some(&"мир".to_owned()));
     }
+
+    #[test]
+    fn test_stemming_and_stopwords() {
+        let config = TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Word,
+            min_token_len: None,
+            max_token_len: None,
+            lowercase: Some(true),
+            stemmer: Some(StemmerParams {
+                language: StemmerLanguage::English,
+            }),
+            stopwords: Some(StopwordsParams {
+                languages: vec![StopwordsLanguage::English],
+                custom: vec!["robot".to_owned()],
+            }),
+            languages: vec![],
+        };
+
+        let mut doc_tokens = Vec::new();
+        Tokenizer::tokenize_doc("The Robot is Running to the cities", &config, |token| {
+            doc_tokens.push(token.to_owned())
+        });
+        assert_eq!(doc_tokens, vec!["run", "citi"]);
+
+        let mut query_tokens = Vec::new();
+        Tokenizer::tokenize_query("runs city", &config, |token| {
+            query_tokens.push(token.to_owned())
+        });
+        assert_eq!(query_tokens, vec!["run", "citi"]);
+    }
+
+    #[test]
+    fn test_highlight() {
+        let config = TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Word,
+            min_token_len: None,
+            max_token_len: None,
+            lowercase: Some(true),
+            stemmer: Some(StemmerParams {
+                language: StemmerLanguage::English,
+            }),
+            stopwords: None,
+            languages: vec![],
+        };
+        let text = "Robots are running, a robot runs!";
+        let ranges = Tokenizer::highlight(text, "robot run", &config);
+        let words: Vec<_> = ranges.into_iter().map(|range| &text[range]).collect();
+        assert_eq!(words, vec!["Robots", "running", "robot", "runs"]);
+
+        let config = TextIndexParams {
+            tokenizer: TokenizerType::Prefix,
+            min_token_len: Some(1),
+            max_token_len: Some(4),
+            stemmer: None,
+            ..config
+        };
+        let text = "Привет, мир! Hello";
+        let ranges = Tokenizer::highlight(text, "мир hel", &config);
+        let words: Vec<_> = ranges.into_iter().map(|range| &text[range]).collect();
+        assert_eq!(words, vec!["мир", "Hel"]);
+    }
 }
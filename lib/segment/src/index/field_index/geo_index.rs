@@ -22,7 +22,8 @@ use crate::index::field_index::{
 };
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
-    FieldCondition, GeoBoundingBox, GeoPoint, GeoRadius, PayloadKeyType, PolygonWrapper,
+    FieldCondition, GeoBoundingBox, GeoPoint, GeoRadius, PayloadFieldStatistics, PayloadKeyType,
+    PolygonWrapper,
 };
 
 /// Max number of sub-regions computed for an input geo query
@@ -527,6 +528,14 @@ impl GeoMapIndex {
         }
     }
 
+    pub fn get_statistics(&self) -> PayloadFieldStatistics {
+        PayloadFieldStatistics {
+            points_count: self.points_count(),
+            values_count: self.points_values_count(),
+            ..Default::default()
+        }
+    }
+
     fn get_iterator(&self, values: Vec<GeoHash>) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
         Box::new(
             values
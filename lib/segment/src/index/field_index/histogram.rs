@@ -121,7 +121,6 @@ impl<T: Numericable> Histogram<T> {
         self.total_count
     }
 
-    #[cfg(test)]
     pub fn borders(&self) -> &BTreeMap<Point<T>, Counts> {
         &self.borders
     }
@@ -7,18 +7,40 @@ use super::binary_index::BinaryIndex;
 use crate::index::field_index::full_text_index::text_index::FullTextIndex;
 use crate::index::field_index::geo_index::GeoMapIndex;
 use crate::index::field_index::map_index::MapIndex;
+use crate::index::field_index::null_index::NullIndex;
 use crate::index::field_index::numeric_index::NumericIndex;
+use crate::index::field_index::values_count_index::ValuesCountIndex;
 use crate::index::field_index::FieldIndex;
 use crate::types::{
     FloatPayloadType, IntPayloadType, PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType,
 };
 
 /// Selects index types based on field type
+///
+/// Every field also gets a null index, used for `is_empty` and `is_null` conditions,
+/// and a values count index, used for `values_count` conditions
 pub fn index_selector(
     field: &str,
     payload_schema: &PayloadFieldSchema,
     db: Arc<RwLock<DB>>,
     is_appendable: bool,
+) -> Vec<FieldIndex> {
+    let mut indexes = typed_index_selector(field, payload_schema, db.clone(), is_appendable);
+    indexes.push(FieldIndex::NullIndex(NullIndex::new(db.clone(), field)));
+    indexes.push(FieldIndex::ValuesCountIndex(ValuesCountIndex::new(
+        db,
+        field,
+        is_appendable,
+    )));
+    indexes
+}
+
+/// Selects value indexes based on field type
+fn typed_index_selector(
+    field: &str,
+    payload_schema: &PayloadFieldSchema,
+    db: Arc<RwLock<DB>>,
+    is_appendable: bool,
 ) -> Vec<FieldIndex> {
     match payload_schema {
         PayloadFieldSchema::FieldType(payload_type) => match payload_type {
@@ -54,6 +76,13 @@ pub fn index_selector(
             PayloadSchemaParams::Text(text_index_params) => vec![FieldIndex::FullTextIndex(
                 FullTextIndex::new(db, text_index_params.clone(), field),
             )],
+            PayloadSchemaParams::Keyword(_) => {
+                vec![FieldIndex::KeywordIndex(MapIndex::new(
+                    db,
+                    field,
+                    is_appendable,
+                ))]
+            }
         },
     }
 }
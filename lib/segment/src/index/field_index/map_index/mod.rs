@@ -25,8 +25,8 @@ use crate::index::field_index::{
 use crate::index::query_estimator::combine_should_estimations;
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
-    AnyVariants, FieldCondition, IntPayloadType, Match, MatchAny, MatchExcept, MatchValue,
-    PayloadKeyType, ValueVariants,
+    largest_tenants, AnyVariants, FieldCondition, IntPayloadType, Match, MatchAny, MatchExcept,
+    MatchValue, PayloadFieldStatistics, PayloadKeyType, TenantStatistics, ValueVariants,
 };
 
 pub enum MapIndex<N: Hash + Eq + Clone + Display + FromStr> {
@@ -116,6 +116,15 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> MapIndex<N> {
         }
     }
 
+    /// Iterate over distinct values, along with the number of points having each of them
+    pub fn iter_counts_per_value(&self) -> impl Iterator<Item = (&N, usize)> + '_ {
+        self.get_values_iterator().filter_map(|value| {
+            self.get_points_with_value_count(value)
+                .filter(|count| *count > 0)
+                .map(|count| (value, count))
+        })
+    }
+
     pub fn storage_cf_name(field: &str) -> String {
         format!("{field}_map")
     }
@@ -148,6 +157,23 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> MapIndex<N> {
         }
     }
 
+    pub fn get_statistics(&self) -> PayloadFieldStatistics {
+        PayloadFieldStatistics {
+            points_count: self.get_indexed_points(),
+            values_count: self.get_values_count(),
+            distinct_values_count: Some(self.get_unique_values_count()),
+            ..Default::default()
+        }
+    }
+
+    /// Number of points of the largest tenants, if values of the field are tenants
+    pub fn get_tenant_statistics(&self) -> Vec<TenantStatistics> {
+        largest_tenants(
+            self.iter_counts_per_value()
+                .map(|(value, count)| (value.to_string(), count)),
+        )
+    }
+
     pub fn encode_db_record(value: &N, idx: PointOffsetType) -> String {
         format!("{value}/{idx}")
     }
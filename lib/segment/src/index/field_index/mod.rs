@@ -11,8 +11,10 @@ pub mod geo_index;
 mod histogram;
 pub mod index_selector;
 pub mod map_index;
+pub mod null_index;
 pub mod numeric_index;
 mod stat_tools;
+pub mod values_count_index;
 
 pub mod binary_index;
 #[cfg(test)]
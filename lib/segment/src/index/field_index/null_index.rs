@@ -0,0 +1,275 @@
+use std::sync::Arc;
+
+use bitvec::vec::BitVec;
+use common::types::PointOffsetType;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde_json::Value;
+
+use super::{CardinalityEstimation, FieldIndex, PayloadBlockCondition, PayloadFieldIndex};
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::utils::MultiValue;
+use crate::common::Flusher;
+use crate::telemetry::PayloadIndexTelemetry;
+use crate::types::{FieldCondition, PayloadFieldStatistics, PayloadKeyType};
+
+/// Find the null index among the indexes of a field
+pub fn find_null_index(indexes: &[FieldIndex]) -> Option<&NullIndex> {
+    indexes.iter().find_map(|index| match index {
+        FieldIndex::NullIndex(null_index) => Some(null_index),
+        _ => None,
+    })
+}
+
+/// Flags of a single point, as stored in the database
+struct NullItem {
+    value: u8,
+}
+
+impl NullItem {
+    const HAS_VALUES: u8 = 0b0000_0001;
+    const IS_NULL: u8 = 0b0000_0010;
+
+    fn from_payload(payload: &MultiValue<&Value>) -> Self {
+        let mut value = 0;
+        if !payload.check_is_empty() {
+            value |= Self::HAS_VALUES;
+        }
+        if payload.check_is_null() {
+            value |= Self::IS_NULL;
+        }
+        Self { value }
+    }
+
+    fn has_values(&self) -> bool {
+        self.value & Self::HAS_VALUES != 0
+    }
+
+    fn is_null(&self) -> bool {
+        self.value & Self::IS_NULL != 0
+    }
+}
+
+/// Tracks which points have an empty or a null value of the field
+///
+/// Attached to every indexed field, so `is_empty` and `is_null` conditions do not need to read
+/// the payload. Unlike other indexes, it considers the raw payload values regardless of their type.
+pub struct NullIndex {
+    has_values: BitVec,
+    is_null: BitVec,
+    has_values_count: usize,
+    is_null_count: usize,
+    db_wrapper: DatabaseColumnWrapper,
+}
+
+impl NullIndex {
+    pub fn new(db: Arc<RwLock<DB>>, field_name: &str) -> NullIndex {
+        let store_cf_name = Self::storage_cf_name(field_name);
+        let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
+        Self {
+            has_values: BitVec::new(),
+            is_null: BitVec::new(),
+            has_values_count: 0,
+            is_null_count: 0,
+            db_wrapper,
+        }
+    }
+
+    fn storage_cf_name(field: &str) -> String {
+        format!("{}_null", field)
+    }
+
+    pub fn recreate(&self) -> OperationResult<()> {
+        self.db_wrapper.recreate_column_family()
+    }
+
+    fn set(&mut self, id: PointOffsetType, item: &NullItem) {
+        let idx = id as usize;
+        if idx >= self.has_values.len() {
+            self.has_values.resize(idx + 1, false);
+            self.is_null.resize(idx + 1, false);
+        }
+
+        let had_values = self.has_values.replace(idx, item.has_values());
+        self.has_values_count =
+            self.has_values_count + item.has_values() as usize - had_values as usize;
+
+        let was_null = self.is_null.replace(idx, item.is_null());
+        self.is_null_count = self.is_null_count + item.is_null() as usize - was_null as usize;
+    }
+
+    pub fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&Value>,
+    ) -> OperationResult<()> {
+        let item = NullItem::from_payload(payload);
+        self.set(id, &item);
+        if item.value == 0 {
+            self.db_wrapper.remove(id.to_be_bytes())?;
+        } else {
+            self.db_wrapper.put(id.to_be_bytes(), [item.value])?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        if (id as usize) >= self.has_values.len() {
+            return Ok(());
+        }
+        self.set(id, &NullItem { value: 0 });
+        self.db_wrapper.remove(id.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Check if the point has no value of the field, or only null and empty arrays
+    pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
+        !self
+            .has_values
+            .get(point_id as usize)
+            .map_or(false, |bit| *bit)
+    }
+
+    /// Check if the point has a null value of the field
+    pub fn values_is_null(&self, point_id: PointOffsetType) -> bool {
+        self.is_null
+            .get(point_id as usize)
+            .map_or(false, |bit| *bit)
+    }
+
+    /// Number of points with a non-empty value
+    pub fn has_values_count(&self) -> usize {
+        self.has_values_count
+    }
+
+    /// Number of points with a null value
+    pub fn is_null_count(&self) -> usize {
+        self.is_null_count
+    }
+
+    pub fn iter_is_null(&self) -> impl Iterator<Item = PointOffsetType> + '_ {
+        self.is_null.iter_ones().map(|idx| idx as PointOffsetType)
+    }
+
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        PayloadIndexTelemetry {
+            field_name: None,
+            points_count: self.has_values_count,
+            points_values_count: self.has_values_count + self.is_null_count,
+            histogram_bucket_size: None,
+        }
+    }
+
+    /// Values are counted by the typed indexes of the field, nothing to add here
+    pub fn get_statistics(&self) -> PayloadFieldStatistics {
+        PayloadFieldStatistics::default()
+    }
+}
+
+impl PayloadFieldIndex for NullIndex {
+    fn count_indexed_points(&self) -> usize {
+        self.has_values_count
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        if !self.db_wrapper.has_column_family()? {
+            return Ok(false);
+        }
+
+        let items: Vec<_> = self
+            .db_wrapper
+            .lock_db()
+            .iter()?
+            .map(|(key, value)| {
+                let idx = PointOffsetType::from_be_bytes(key.as_ref().try_into().unwrap());
+                debug_assert_eq!(value.len(), 1);
+                (idx, NullItem { value: value[0] })
+            })
+            .collect();
+        for (idx, item) in items {
+            self.set(idx, &item);
+        }
+        Ok(true)
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.db_wrapper.remove_column_family()
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.db_wrapper.flusher()
+    }
+
+    fn filter<'a>(
+        &'a self,
+        _condition: &'a FieldCondition,
+    ) -> OperationResult<Box<dyn Iterator<Item = PointOffsetType> + 'a>> {
+        Err(OperationError::service_error(
+            "null index does not support field conditions",
+        ))
+    }
+
+    fn estimate_cardinality(
+        &self,
+        _condition: &FieldCondition,
+    ) -> OperationResult<CardinalityEstimation> {
+        Err(OperationError::service_error(
+            "null index does not support field conditions",
+        ))
+    }
+
+    fn payload_blocks(
+        &self,
+        _threshold: usize,
+        _key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        Box::new(std::iter::empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+
+    const FIELD_NAME: &str = "field";
+
+    #[test]
+    fn test_null_index_load_from_disk() {
+        let tmp_dir = Builder::new().prefix("test_db").tempdir().unwrap();
+        let db = open_db_with_existing_cf(tmp_dir.path()).unwrap();
+
+        let values = [
+            json!(1),
+            json!(null),
+            json!([]),
+            json!([null, 2]),
+            json!("a"),
+            json!([null]),
+        ];
+
+        let mut index = NullIndex::new(db.clone(), FIELD_NAME);
+        index.recreate().unwrap();
+        for (idx, value) in values.iter().enumerate() {
+            index
+                .add_point(idx as PointOffsetType, &MultiValue::one(value))
+                .unwrap();
+        }
+        index.remove_point(4).unwrap();
+        index.flusher()().unwrap();
+        drop(index);
+
+        let mut index = NullIndex::new(db, FIELD_NAME);
+        assert!(index.load().unwrap());
+
+        let empty: Vec<_> = (0..8).filter(|&idx| index.values_is_empty(idx)).collect();
+        assert_eq!(empty, vec![1, 2, 4, 6, 7]);
+        assert_eq!(index.iter_is_null().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(index.has_values_count(), 3);
+        assert_eq!(index.is_null_count(), 1);
+    }
+}
@@ -8,7 +8,7 @@ use parking_lot::RwLock;
 use rocksdb::DB;
 
 use super::mutable_numeric_index::MutableNumericIndex;
-use super::{Encodable, NumericIndex, HISTOGRAM_MAX_BUCKET_SIZE, HISTOGRAM_PRECISION};
+use super::{Encodable, HISTOGRAM_MAX_BUCKET_SIZE, HISTOGRAM_PRECISION};
 use crate::common::operation_error::OperationResult;
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::index::field_index::histogram::{Histogram, Numericable, Point};
@@ -194,9 +194,8 @@ impl<'a, T: Encodable + Numericable> DoubleEndedIterator for NumericKeySortedVec
 }
 
 impl<T: Encodable + Numericable> ImmutableNumericIndex<T> {
-    pub(super) fn new(db: Arc<RwLock<DB>>, field: &str) -> Self {
-        let store_cf_name = NumericIndex::<T>::storage_cf_name(field);
-        let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
+    pub(super) fn new(db: Arc<RwLock<DB>>, store_cf_name: &str) -> Self {
+        let db_wrapper = DatabaseColumnWrapper::new(db, store_cf_name);
         Self {
             map: NumericKeySortedVec {
                 data: Default::default(),
@@ -235,6 +234,16 @@ impl<T: Encodable + Numericable> ImmutableNumericIndex<T> {
             .map(|NumericIndexKey { idx, .. }| idx)
     }
 
+    pub(super) fn orderable_values_range(
+        &self,
+        start_bound: Bound<NumericIndexKey<T>>,
+        end_bound: Bound<NumericIndexKey<T>>,
+    ) -> impl DoubleEndedIterator<Item = (T, PointOffsetType)> + '_ {
+        self.map
+            .values_range(start_bound, end_bound)
+            .map(|NumericIndexKey { key, idx, .. }| (key, idx))
+    }
+
     pub(super) fn load(&mut self) -> OperationResult<bool> {
         let mut mutable = MutableNumericIndex::<T> {
             map: Default::default(),
@@ -10,6 +10,7 @@ use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::sync::Arc;
 
 use common::types::PointOffsetType;
+use itertools::Itertools;
 use mutable_numeric_index::MutableNumericIndex;
 use parking_lot::RwLock;
 use rocksdb::DB;
@@ -30,7 +31,10 @@ use crate::index::key_encoding::{
     encode_i64_key_ascending,
 };
 use crate::telemetry::PayloadIndexTelemetry;
-use crate::types::{FieldCondition, FloatPayloadType, IntPayloadType, PayloadKeyType, Range};
+use crate::types::{
+    FieldCondition, FloatPayloadType, HistogramBucket, IntPayloadType, PayloadFieldStatistics,
+    PayloadKeyType, Range,
+};
 
 const HISTOGRAM_MAX_BUCKET_SIZE: usize = 10_000;
 const HISTOGRAM_PRECISION: f64 = 0.01;
@@ -87,10 +91,17 @@ pub enum NumericIndex<T: Encodable + Numericable> {
 
 impl<T: Encodable + Numericable> NumericIndex<T> {
     pub fn new(db: Arc<RwLock<DB>>, field: &str, is_appendable: bool) -> Self {
+        Self::new_with_cf_name(db, &Self::storage_cf_name(field), is_appendable)
+    }
+
+    /// Create an index, stored in the given column family
+    ///
+    /// Allows to keep numbers, derived from the payload, next to the regular numeric index.
+    pub fn new_with_cf_name(db: Arc<RwLock<DB>>, store_cf_name: &str, is_appendable: bool) -> Self {
         if is_appendable {
-            NumericIndex::Mutable(MutableNumericIndex::new(db, field))
+            NumericIndex::Mutable(MutableNumericIndex::new(db, store_cf_name))
         } else {
-            NumericIndex::Immutable(ImmutableNumericIndex::new(db, field))
+            NumericIndex::Immutable(ImmutableNumericIndex::new(db, store_cf_name))
         }
     }
 
@@ -228,6 +239,68 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         }
     }
 
+    fn range_bounds(range: &Range) -> (Bound<NumericIndexKey<T>>, Bound<NumericIndexKey<T>>) {
+        let start_bound = match range {
+            Range { gt: Some(gt), .. } => {
+                let v: T = T::from_f64(*gt);
+                Excluded(NumericIndexKey::new(v, PointOffsetType::MAX))
+            }
+            Range { gte: Some(gte), .. } => {
+                let v: T = T::from_f64(*gte);
+                Included(NumericIndexKey::new(v, PointOffsetType::MIN))
+            }
+            _ => Unbounded,
+        };
+
+        let end_bound = match range {
+            Range { lt: Some(lt), .. } => {
+                let v: T = T::from_f64(*lt);
+                Excluded(NumericIndexKey::new(v, PointOffsetType::MIN))
+            }
+            Range { lte: Some(lte), .. } => {
+                let v: T = T::from_f64(*lte);
+                Included(NumericIndexKey::new(v, PointOffsetType::MAX))
+            }
+            _ => Unbounded,
+        };
+
+        (start_bound, end_bound)
+    }
+
+    /// Iterate over indexed values within `range` together with their point offsets,
+    /// in ascending order of values. Reverse the iterator for descending order.
+    ///
+    /// Points with multiple values are yielded once per value.
+    pub fn stream_range(
+        &self,
+        range: &Range,
+    ) -> Box<dyn DoubleEndedIterator<Item = (T, PointOffsetType)> + '_> {
+        let (start_bound, end_bound) = Self::range_bounds(range);
+
+        if !check_boundaries(&start_bound, &end_bound) {
+            return Box::new(std::iter::empty());
+        }
+
+        match self {
+            NumericIndex::Mutable(index) => {
+                let start_bound = match start_bound {
+                    Included(k) => Included(k.encode()),
+                    Excluded(k) => Excluded(k.encode()),
+                    Unbounded => Unbounded,
+                };
+                let end_bound = match end_bound {
+                    Included(k) => Included(k.encode()),
+                    Excluded(k) => Excluded(k.encode()),
+                    Unbounded => Unbounded,
+                };
+                Box::new(index.orderable_values_range(start_bound, end_bound))
+            }
+            NumericIndex::Immutable(index) => {
+                Box::new(index.orderable_values_range(start_bound, end_bound))
+            }
+        }
+    }
+
     pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
         PayloadIndexTelemetry {
             field_name: None,
@@ -237,6 +310,24 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         }
     }
 
+    pub fn get_statistics(&self) -> PayloadFieldStatistics {
+        let mut values = self.stream_range(&Range::default());
+        let min = values.next().map(|(value, _)| value.to_f64());
+        let max = values.next_back().map(|(value, _)| value.to_f64()).or(min);
+
+        PayloadFieldStatistics {
+            points_count: self.get_points_count(),
+            values_count: self.get_histogram().get_total_count(),
+            distinct_values_count: None,
+            min,
+            max,
+            histogram: Some(histogram_buckets(self.get_histogram())),
+            tenants: None,
+            tokens_count: None,
+            avg_document_length: None,
+        }
+    }
+
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
         self.get_values(point_id).map(|x| x.len()).unwrap_or(0)
     }
@@ -248,6 +339,37 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
     }
 }
 
+/// Convert borders of the histogram into buckets between adjacent borders.
+///
+/// Each border is an indexed value itself, it is counted in the bucket it starts.
+/// The last border is counted in the last bucket.
+fn histogram_buckets<T: Numericable>(histogram: &Histogram<T>) -> Vec<HistogramBucket> {
+    let mut buckets: Vec<_> = histogram
+        .borders()
+        .iter()
+        .tuple_windows()
+        .map(|((from, counts), (to, _))| HistogramBucket {
+            from: from.val.to_f64(),
+            to: to.val.to_f64(),
+            count: counts.right + 1,
+        })
+        .collect();
+
+    match buckets.last_mut() {
+        Some(last) => last.count += 1,
+        None => {
+            if let Some((border, _)) = histogram.borders().iter().next() {
+                buckets.push(HistogramBucket {
+                    from: border.val.to_f64(),
+                    to: border.val.to_f64(),
+                    count: 1,
+                });
+            }
+        }
+    }
+    buckets
+}
+
 impl<T: Encodable + Numericable> PayloadFieldIndex for NumericIndex<T> {
     fn count_indexed_points(&self) -> usize {
         self.get_points_count()
@@ -274,29 +396,7 @@ impl<T: Encodable + Numericable> PayloadFieldIndex for NumericIndex<T> {
             .as_ref()
             .ok_or_else(|| OperationError::service_error("failed to get condition range"))?;
 
-        let start_bound = match cond_range {
-            Range { gt: Some(gt), .. } => {
-                let v: T = T::from_f64(*gt);
-                Excluded(NumericIndexKey::new(v, PointOffsetType::MAX))
-            }
-            Range { gte: Some(gte), .. } => {
-                let v: T = T::from_f64(*gte);
-                Included(NumericIndexKey::new(v, PointOffsetType::MIN))
-            }
-            _ => Unbounded,
-        };
-
-        let end_bound = match cond_range {
-            Range { lt: Some(lt), .. } => {
-                let v: T = T::from_f64(*lt);
-                Excluded(NumericIndexKey::new(v, PointOffsetType::MIN))
-            }
-            Range { lte: Some(lte), .. } => {
-                let v: T = T::from_f64(*lte);
-                Included(NumericIndexKey::new(v, PointOffsetType::MAX))
-            }
-            _ => Unbounded,
-        };
+        let (start_bound, end_bound) = Self::range_bounds(cond_range);
 
         // map.range
         // Panics if range start > end. Panics if range start == end and both bounds are Excluded.
@@ -7,7 +7,7 @@ use common::types::PointOffsetType;
 use parking_lot::RwLock;
 use rocksdb::DB;
 
-use super::{Encodable, NumericIndex, HISTOGRAM_MAX_BUCKET_SIZE, HISTOGRAM_PRECISION};
+use super::{Encodable, HISTOGRAM_MAX_BUCKET_SIZE, HISTOGRAM_PRECISION};
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::index::field_index::histogram::{Histogram, Numericable, Point};
@@ -22,9 +22,8 @@ pub struct MutableNumericIndex<T: Encodable + Numericable> {
 }
 
 impl<T: Encodable + Numericable> MutableNumericIndex<T> {
-    pub fn new(db: Arc<RwLock<DB>>, field: &str) -> Self {
-        let store_cf_name = NumericIndex::<T>::storage_cf_name(field);
-        let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
+    pub fn new(db: Arc<RwLock<DB>>, store_cf_name: &str) -> Self {
+        let db_wrapper = DatabaseColumnWrapper::new(db, store_cf_name);
         Self {
             map: BTreeMap::new(),
             db_wrapper,
@@ -55,6 +54,17 @@ impl<T: Encodable + Numericable> MutableNumericIndex<T> {
         self.map.range((start_bound, end_bound)).map(|(_, v)| *v)
     }
 
+    pub fn orderable_values_range(
+        &self,
+        start_bound: Bound<Vec<u8>>,
+        end_bound: Bound<Vec<u8>>,
+    ) -> impl DoubleEndedIterator<Item = (T, PointOffsetType)> + '_ {
+        self.map.range((start_bound, end_bound)).map(|(key, _)| {
+            let (idx, value) = T::decode_key(key);
+            (value, idx)
+        })
+    }
+
     fn add_value(&mut self, id: PointOffsetType, value: T) -> OperationResult<()> {
         let key = value.encode_key(id);
         self.db_wrapper.put(&key, id.to_be_bytes())?;
@@ -423,3 +423,38 @@ fn test_empty_cardinality(#[case] immutable: bool) {
         },
     );
 }
+
+#[rstest]
+#[case(true)]
+#[case(false)]
+fn test_numeric_index_statistics(#[case] immutable: bool) {
+    let (_temp_dir, index) = random_index(1000, 2, immutable);
+    let statistics = index.get_statistics();
+
+    assert_eq!(statistics.points_count, 1000);
+    assert_eq!(statistics.values_count, 2000);
+
+    let values: Vec<_> = index
+        .stream_range(&Range::default())
+        .map(|(v, _)| v)
+        .collect();
+    assert_eq!(statistics.min, values.first().copied());
+    assert_eq!(statistics.max, values.last().copied());
+
+    let histogram = statistics.histogram.unwrap();
+    assert_eq!(
+        histogram.iter().map(|bucket| bucket.count).sum::<usize>(),
+        2000
+    );
+    assert_eq!(histogram.first().unwrap().from, statistics.min.unwrap());
+    assert_eq!(histogram.last().unwrap().to, statistics.max.unwrap());
+    assert!(histogram
+        .iter()
+        .tuple_windows()
+        .all(|(left, right)| left.to == right.from));
+
+    let (_temp_dir, index) = random_index(0, 0, immutable);
+    let statistics = index.get_statistics();
+    assert_eq!(statistics.min, None);
+    assert_eq!(statistics.histogram, Some(vec![]));
+}
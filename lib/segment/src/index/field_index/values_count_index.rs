@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use common::types::PointOffsetType;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde_json::Value;
+
+use super::numeric_index::NumericIndex;
+use super::{
+    CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
+};
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::utils::MultiValue;
+use crate::common::Flusher;
+use crate::telemetry::PayloadIndexTelemetry;
+use crate::types::{
+    FieldCondition, IntPayloadType, PayloadFieldStatistics, PayloadKeyType, Range, ValuesCount,
+};
+
+/// Number of values of the field in each point, used for `values_count` conditions
+///
+/// Counts are kept in a numeric index, one per value of the field, the same way `values_count`
+/// is checked against the payload: arrays are counted by length, `null` as zero values.
+pub struct ValuesCountIndex {
+    counts: NumericIndex<IntPayloadType>,
+}
+
+impl ValuesCountIndex {
+    pub fn new(db: Arc<RwLock<DB>>, field: &str, is_appendable: bool) -> ValuesCountIndex {
+        let store_cf_name = Self::storage_cf_name(field);
+        Self {
+            counts: NumericIndex::new_with_cf_name(db, &store_cf_name, is_appendable),
+        }
+    }
+
+    fn storage_cf_name(field: &str) -> String {
+        format!("{field}_values_count")
+    }
+
+    fn count_range(values_count: &ValuesCount) -> Range {
+        Range {
+            lt: values_count.lt.map(|count| count as f64),
+            gt: values_count.gt.map(|count| count as f64),
+            gte: values_count.gte.map(|count| count as f64),
+            lte: values_count.lte.map(|count| count as f64),
+        }
+    }
+
+    /// Rewrite `values_count` condition into a range condition over the counts
+    fn count_condition(condition: &FieldCondition) -> OperationResult<FieldCondition> {
+        let values_count = condition.values_count.as_ref().ok_or_else(|| {
+            OperationError::service_error("values count index only supports values_count")
+        })?;
+        Ok(FieldCondition::new_range(
+            condition.key.clone(),
+            Self::count_range(values_count),
+        ))
+    }
+
+    /// Number of values, as counted by `values_count` condition
+    fn count(value: &Value) -> IntPayloadType {
+        match value {
+            Value::Null => 0,
+            Value::Array(array) => array.len() as IntPayloadType,
+            _ => 1,
+        }
+    }
+
+    pub fn recreate(&self) -> OperationResult<()> {
+        self.counts.recreate()
+    }
+
+    pub fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&Value>,
+    ) -> OperationResult<()> {
+        self.counts.remove_point(id)?;
+        let counts: Vec<_> = match payload {
+            MultiValue::Single(value) => value.iter().map(|value| Self::count(value)).collect(),
+            MultiValue::Multiple(values) => values.iter().map(|value| Self::count(value)).collect(),
+        };
+        if counts.is_empty() {
+            return Ok(());
+        }
+        self.counts.add_many(id, counts)
+    }
+
+    pub fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        self.counts.remove_point(id)
+    }
+
+    /// Check if any value of the point has a number of elements, matching the condition
+    pub fn check_values_count(
+        &self,
+        point_id: PointOffsetType,
+        values_count: &ValuesCount,
+    ) -> bool {
+        let range = Self::count_range(values_count);
+        self.counts.get_values(point_id).map_or(false, |counts| {
+            counts.iter().any(|&count| range.check_range(count as f64))
+        })
+    }
+
+    /// Total number of elements in all values of the point
+    pub fn values_count(&self, point_id: PointOffsetType) -> usize {
+        self.counts
+            .get_values(point_id)
+            .map_or(0, |counts| counts.iter().map(|&count| count as usize).sum())
+    }
+
+    pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
+        self.values_count(point_id) == 0
+    }
+
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        self.counts.get_telemetry_data()
+    }
+
+    /// Values are counted by the typed indexes of the field, nothing to add here
+    pub fn get_statistics(&self) -> PayloadFieldStatistics {
+        PayloadFieldStatistics::default()
+    }
+}
+
+impl PayloadFieldIndex for ValuesCountIndex {
+    fn count_indexed_points(&self) -> usize {
+        self.counts.count_indexed_points()
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        self.counts.load()
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.counts.clear()
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.counts.flusher()
+    }
+
+    fn filter<'a>(
+        &'a self,
+        condition: &'a FieldCondition,
+    ) -> OperationResult<Box<dyn Iterator<Item = PointOffsetType> + 'a>> {
+        let count_condition = Self::count_condition(condition)?;
+        // Numeric index borrows the condition, so the matched points are collected here
+        let points: Vec<_> = self.counts.filter(&count_condition)?.collect();
+        Ok(Box::new(points.into_iter()))
+    }
+
+    fn estimate_cardinality(
+        &self,
+        condition: &FieldCondition,
+    ) -> OperationResult<CardinalityEstimation> {
+        let mut estimation = self
+            .counts
+            .estimate_cardinality(&Self::count_condition(condition)?)?;
+        estimation.primary_clauses = vec![PrimaryCondition::Condition(condition.clone())];
+        Ok(estimation)
+    }
+
+    fn payload_blocks(
+        &self,
+        _threshold: usize,
+        _key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        Box::new(std::iter::empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+
+    const FIELD_NAME: &str = "field";
+
+    #[test]
+    fn test_values_count_index() {
+        let tmp_dir = Builder::new().prefix("test_db").tempdir().unwrap();
+        let db = open_db_with_existing_cf(tmp_dir.path()).unwrap();
+
+        let values = [
+            json!(null),
+            json!("a"),
+            json!([1, 2]),
+            json!([1, 2, 3]),
+            json!([]),
+            json!([1, 2, 3, 4]),
+        ];
+
+        let mut index = ValuesCountIndex::new(db.clone(), FIELD_NAME, true);
+        index.recreate().unwrap();
+        for (idx, value) in values.iter().enumerate() {
+            index
+                .add_point(idx as PointOffsetType, &MultiValue::one(value))
+                .unwrap();
+        }
+        index.remove_point(5).unwrap();
+        index.flusher()().unwrap();
+        drop(index);
+
+        let mut index = ValuesCountIndex::new(db, FIELD_NAME, false);
+        assert!(index.load().unwrap());
+
+        let condition = FieldCondition::new_values_count(
+            FIELD_NAME.to_string(),
+            ValuesCount {
+                lt: None,
+                gt: None,
+                gte: Some(2),
+                lte: None,
+            },
+        );
+        let mut points: Vec<_> = index.filter(&condition).unwrap().collect();
+        points.sort();
+        assert_eq!(points, vec![2, 3]);
+
+        let values_count = condition.values_count.as_ref().unwrap();
+        let checked: Vec<_> = (0..7)
+            .filter(|&idx| index.check_values_count(idx, values_count))
+            .collect();
+        assert_eq!(checked, vec![2, 3]);
+
+        let estimation = index.estimate_cardinality(&condition).unwrap();
+        assert!(estimation.min <= 2 && 2 <= estimation.max);
+        assert_eq!(index.count_indexed_points(), 5);
+    }
+}
@@ -806,6 +806,7 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
             filtered_sparse: Default::default(),
             unfiltered_exact: tm.exact_unfiltered.lock().get_statistics(),
             unfiltered_sparse: Default::default(),
+            sparse_statistics: None,
         }
     }
 
@@ -45,6 +45,9 @@ fn test_graph_connectivity() {
             },
         )]),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
         sparse_vector_data: Default::default(),
     };
 
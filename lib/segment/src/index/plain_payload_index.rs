@@ -288,6 +288,7 @@ impl VectorIndex for PlainIndex {
             filtered_sparse: Default::default(),
             unfiltered_exact: OperationDurationStatistics::default(),
             unfiltered_sparse: OperationDurationStatistics::default(),
+            sparse_statistics: None,
         }
     }
 
@@ -5,17 +5,18 @@ use serde_json::Value;
 
 use crate::common::utils::IndexesMap;
 use crate::id_tracker::IdTrackerSS;
+use crate::index::field_index::null_index::find_null_index;
 use crate::index::field_index::FieldIndex;
 use crate::index::query_optimization::optimized_filter::ConditionCheckerFn;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::payload_storage::query_checker::{
     check_field_condition, check_is_empty_condition, check_is_null_condition, check_payload,
-    select_nested_indexes,
+    check_version_condition, select_nested_indexes,
 };
 use crate::types::{
     AnyVariants, Condition, FieldCondition, FloatPayloadType, GeoBoundingBox, GeoPolygon,
-    GeoRadius, Match, MatchAny, MatchExcept, MatchText, MatchValue, OwnedPayloadRef,
-    PayloadContainer, Range, ValueVariants,
+    GeoRadius, Match, MatchAny, MatchExcept, MatchValue, OwnedPayloadRef, PayloadContainer, Range,
+    ValueVariants, VERSION_FIELD,
 };
 
 pub fn condition_converter<'a>(
@@ -25,6 +26,14 @@ pub fn condition_converter<'a>(
     id_tracker: &IdTrackerSS,
 ) -> ConditionCheckerFn<'a> {
     match condition {
+        // Versions are kept by the id tracker, which can't be borrowed by the checker
+        Condition::Field(field_condition) if field_condition.key == VERSION_FIELD => {
+            let segment_ids: HashSet<_> = id_tracker
+                .iter_ids()
+                .filter(|&point_id| check_version_condition(field_condition, id_tracker, point_id))
+                .collect();
+            Box::new(move |point_id| segment_ids.contains(&point_id))
+        }
         Condition::Field(field_condition) => field_indexes
             .get(&field_condition.key)
             .and_then(|indexes| {
@@ -42,9 +51,14 @@ pub fn condition_converter<'a>(
         // We can use index for `is_empty` condition effectively only when it is not empty.
         // If the index says it is "empty", we still need to check the payload.
         Condition::IsEmpty(is_empty) => {
-            let first_field_index = field_indexes
-                .get(&is_empty.is_empty.key)
-                .and_then(|indexes| indexes.first());
+            let indexes = field_indexes.get(&is_empty.is_empty.key);
+
+            // Null index tracks raw payload values, so it is exact
+            if let Some(null_index) = indexes.and_then(|indexes| find_null_index(indexes)) {
+                return Box::new(move |point_id| null_index.values_is_empty(point_id));
+            }
+
+            let first_field_index = indexes.and_then(|indexes| indexes.first());
 
             let fallback = Box::new(move |point_id| {
                 payload_provider.with_payload(point_id, |payload| {
@@ -58,11 +72,19 @@ pub fn condition_converter<'a>(
             }
         }
 
-        Condition::IsNull(is_null) => Box::new(move |point_id| {
-            payload_provider.with_payload(point_id, |payload| {
-                check_is_null_condition(is_null, &payload)
-            })
-        }),
+        Condition::IsNull(is_null) => {
+            let null_index = field_indexes
+                .get(&is_null.is_null.key)
+                .and_then(|indexes| find_null_index(indexes));
+            match null_index {
+                Some(null_index) => Box::new(move |point_id| null_index.values_is_null(point_id)),
+                None => Box::new(move |point_id| {
+                    payload_provider.with_payload(point_id, |payload| {
+                        check_is_null_condition(is_null, &payload)
+                    })
+                }),
+            }
+        }
         // ToDo: It might be possible to make this condition faster by using `VisitedPool` instead of HashSet
         Condition::HasId(has_id) => {
             let segment_ids: HashSet<_> = has_id
@@ -128,6 +150,21 @@ pub fn field_condition_index<'a>(
     index: &'a FieldIndex,
     field_condition: &FieldCondition,
 ) -> Option<ConditionCheckerFn<'a>> {
+    // Null index does not know the values, only whether they are present
+    if let FieldIndex::NullIndex(_) = index {
+        return None;
+    }
+
+    // Only the number of values is checked, if it is requested
+    if let Some(values_count) = field_condition.values_count {
+        return match index {
+            FieldIndex::ValuesCountIndex(index) => Some(Box::new(move |point_id| {
+                index.check_values_count(point_id, &values_count)
+            })),
+            _ => None,
+        };
+    }
+
     if let Some(checker) = field_condition
         .r#match
         .clone()
@@ -270,9 +307,9 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
             }
             _ => None,
         },
-        Match::Text(MatchText { text }) => match index {
+        Match::Text(text_match) => match index {
             FieldIndex::FullTextIndex(full_text_index) => {
-                let parsed_query = full_text_index.parse_query(&text);
+                let parsed_query = full_text_index.parse_text_match(&text_match);
                 Some(Box::new(move |point_id: PointOffsetType| {
                     full_text_index
                         .get_doc(point_id)
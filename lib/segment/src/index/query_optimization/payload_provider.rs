@@ -47,7 +47,12 @@ impl PayloadProvider {
             // Rewrite condition checking code to support error reporting.
             // Which may lead to slowdown and assumes a lot of changes.
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s
-                .read_payload(point_id)
+                .read_inline_payload(point_id)
+                .unwrap_or_else(|err| panic!("Payload storage is corrupted: {err}"))
+                .map(|x| x.into()),
+            // Warn: Possible panic here, same as for on-disk storage
+            PayloadStorageEnum::MmapPayloadStorage(s) => s
+                .read_inline_payload(point_id)
                 .unwrap_or_else(|err| panic!("Payload storage is corrupted: {err}"))
                 .map(|x| x.into()),
         };
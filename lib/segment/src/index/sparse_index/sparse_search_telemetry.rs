@@ -44,6 +44,7 @@ impl From<&SparseSearchesTelemetry> for VectorIndexSearchesTelemetry {
             filtered_sparse: value.filtered_sparse.lock().get_statistics(),
             unfiltered_sparse: value.unfiltered_sparse.lock().get_statistics(),
             unfiltered_exact: Default::default(),
+            sparse_statistics: None,
         }
     }
 }
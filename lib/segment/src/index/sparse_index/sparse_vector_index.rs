@@ -1,15 +1,21 @@
-use std::collections::HashSet;
-use std::fs::create_dir_all;
+use std::collections::{HashMap, HashSet};
+use std::fs::{create_dir_all, remove_dir_all};
+use std::mem::size_of;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
 use common::types::{PointOffsetType, ScoredPointOffset};
+use io::file_operations::{atomic_save_json, read_json};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sparse::common::sparse_vector::SparseVector;
+use sparse::common::types::DimId;
 use sparse::index::inverted_index::inverted_index_ram::InvertedIndexRam;
 use sparse::index::inverted_index::InvertedIndex;
+use sparse::index::posting_list::PostingElement;
 use sparse::index::search_context::SearchContext;
 
 use super::sparse_index_config::SparseIndexType;
@@ -23,13 +29,33 @@ use crate::index::sparse_index::sparse_index_config::SparseIndexConfig;
 use crate::index::sparse_index::sparse_search_telemetry::SparseSearchesTelemetry;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::{PayloadIndex, VectorIndex};
-use crate::telemetry::VectorIndexSearchesTelemetry;
-use crate::types::{Filter, SearchParams, DEFAULT_SPARSE_FULL_SCAN_THRESHOLD};
+use crate::telemetry::{SparseIndexStatistics, VectorIndexSearchesTelemetry};
+use crate::types::{
+    Condition, FieldCondition, Filter, Match, MatchValue, PayloadContainer, PayloadKeyType,
+    SearchParams, ValueVariants, DEFAULT_SPARSE_FULL_SCAN_THRESHOLD,
+};
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
 use crate::vector_storage::{
     check_deleted_condition, new_stoppable_raw_scorer, VectorStorage, VectorStorageEnum,
 };
 
+const PARTITIONS_DIR: &str = "partitions";
+const PARTITIONS_CONFIG_FILE: &str = "partitions.json";
+
+/// Persisted layout of the index partitions.
+/// Inverted index of the value at position `i` is stored in `partitions/{i}`.
+#[derive(Debug, Deserialize, Serialize)]
+struct PartitionsConfig {
+    key: PayloadKeyType,
+    values: Vec<ValueVariants>,
+}
+
+/// Inverted indexes of the points, grouped by the value of a payload key
+struct SparseIndexPartitions<TInvertedIndex: InvertedIndex> {
+    key: PayloadKeyType,
+    indexes: HashMap<ValueVariants, TInvertedIndex>,
+}
+
 pub struct SparseVectorIndex<TInvertedIndex: InvertedIndex> {
     pub config: SparseIndexConfig,
     pub id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
@@ -37,6 +63,10 @@ pub struct SparseVectorIndex<TInvertedIndex: InvertedIndex> {
     pub payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
     path: PathBuf,
     pub inverted_index: TInvertedIndex,
+    /// Payload key to build partitions by, if requested
+    partition_by: Option<PayloadKeyType>,
+    /// Per-value inverted indexes, only present for immutable indexes
+    partitions: Option<SparseIndexPartitions<TInvertedIndex>>,
     searches_telemetry: SparseSearchesTelemetry,
     is_appendable: bool,
 }
@@ -49,12 +79,38 @@ impl<TInvertedIndex: InvertedIndex> SparseVectorIndex<TInvertedIndex> {
         vector_storage: Arc<AtomicRefCell<VectorStorageEnum>>,
         payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
         path: &Path,
+    ) -> OperationResult<Self> {
+        Self::open_partitioned(
+            config,
+            None,
+            id_tracker,
+            vector_storage,
+            payload_index,
+            path,
+        )
+    }
+
+    /// Open a sparse vector index at a given path, partitioned by the given payload key.
+    ///
+    /// Partitions are only built for immutable indexes, as points of those never change payload.
+    pub fn open_partitioned(
+        config: SparseIndexConfig,
+        partition_by: Option<PayloadKeyType>,
+        id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+        vector_storage: Arc<AtomicRefCell<VectorStorageEnum>>,
+        payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
+        path: &Path,
     ) -> OperationResult<Self> {
         // create directory if it does not exist
         create_dir_all(path)?;
         let is_appendable = config.index_type == SparseIndexType::MutableRam;
 
         let config_path = SparseIndexConfig::get_config_path(path);
+        let partitions = if !is_appendable && config_path.exists() {
+            Self::load_partitions(path)?
+        } else {
+            None
+        };
         let (config, inverted_index) = if is_appendable {
             // RAM mutable case - build inverted index from scratch and use provided config
             let inverted_index = Self::build_inverted_index(
@@ -84,11 +140,146 @@ impl<TInvertedIndex: InvertedIndex> SparseVectorIndex<TInvertedIndex> {
             payload_index,
             path,
             inverted_index,
+            partition_by,
+            partitions,
             searches_telemetry,
             is_appendable,
         })
     }
 
+    fn partitions_config_path(path: &Path) -> PathBuf {
+        path.join(PARTITIONS_CONFIG_FILE)
+    }
+
+    fn partition_path(path: &Path, partition: usize) -> PathBuf {
+        path.join(PARTITIONS_DIR).join(partition.to_string())
+    }
+
+    fn load_partitions(
+        path: &Path,
+    ) -> OperationResult<Option<SparseIndexPartitions<TInvertedIndex>>> {
+        let partitions_config_path = Self::partitions_config_path(path);
+        if !partitions_config_path.exists() {
+            return Ok(None);
+        }
+
+        let PartitionsConfig { key, values } = read_json(&partitions_config_path)?;
+        let indexes = values
+            .into_iter()
+            .enumerate()
+            .map(|(partition, value)| {
+                let index = TInvertedIndex::open(&Self::partition_path(path, partition))?;
+                Ok((value, index))
+            })
+            .collect::<OperationResult<_>>()?;
+
+        Ok(Some(SparseIndexPartitions { key, indexes }))
+    }
+
+    /// Build an inverted index for every value of the `key` payload field.
+    ///
+    /// Points with multiple values are indexed in each of the corresponding partitions.
+    fn build_partitions(
+        &self,
+        key: &PayloadKeyType,
+        stopped: &AtomicBool,
+    ) -> OperationResult<SparseIndexPartitions<TInvertedIndex>> {
+        let borrowed_vector_storage = self.vector_storage.borrow();
+        let borrowed_id_tracker = self.id_tracker.borrow();
+        let payload_index = self.payload_index.borrow();
+        let deleted_bitslice = borrowed_vector_storage.deleted_vector_bitslice();
+
+        // Inverted index of each partition, along with the number of points in it
+        let mut ram_indexes: HashMap<ValueVariants, (InvertedIndexRam, usize)> = HashMap::new();
+        for id in borrowed_id_tracker.iter_ids_excluding(deleted_bitslice) {
+            check_process_stopped(stopped)?;
+            let Some(vector) = borrowed_vector_storage.get_vector_opt(id) else {
+                continue;
+            };
+            let vector: &SparseVector = vector.as_vec_ref().try_into()?;
+            if vector.is_empty() {
+                continue;
+            }
+
+            let payload = payload_index.payload(id)?;
+            let partition_values = payload
+                .get_value(key)
+                .values()
+                .into_iter()
+                .flat_map(|value| match value {
+                    Value::Array(values) => values.iter().collect(),
+                    value => vec![value],
+                })
+                .filter_map(value_to_partition)
+                .unique();
+
+            for value in partition_values {
+                let (ram_index, point_count) = ram_indexes
+                    .entry(value)
+                    .or_insert_with(|| (InvertedIndexRam::empty(), 0));
+                ram_index.upsert(id, vector.to_owned());
+                *point_count += 1;
+            }
+        }
+
+        let partitions_dir = self.path.join(PARTITIONS_DIR);
+        if partitions_dir.exists() {
+            remove_dir_all(&partitions_dir)?;
+        }
+
+        let mut values = Vec::with_capacity(ram_indexes.len());
+        let mut indexes = HashMap::with_capacity(ram_indexes.len());
+        for (partition, (value, (mut ram_index, point_count))) in
+            ram_indexes.into_iter().enumerate()
+        {
+            // the underlying upsert operation does not guarantee that the indexed vector count is correct
+            ram_index.vector_count = point_count;
+            let partition_path = Self::partition_path(&self.path, partition);
+            create_dir_all(&partition_path)?;
+            let index = TInvertedIndex::from_ram_index(ram_index, &partition_path)?;
+            index.save(&partition_path)?;
+            values.push(value.clone());
+            indexes.insert(value, index);
+        }
+
+        atomic_save_json(
+            &Self::partitions_config_path(&self.path),
+            &PartitionsConfig {
+                key: key.clone(),
+                values,
+            },
+        )?;
+
+        Ok(SparseIndexPartitions {
+            key: key.clone(),
+            indexes,
+        })
+    }
+
+    /// Select the partition, which contains all points that may match the filter
+    ///
+    /// Returns `Some(None)` if the filter requires a value, which no point has.
+    fn select_partition(&self, filter: &Filter) -> Option<Option<&TInvertedIndex>> {
+        let partitions = self.partitions.as_ref()?;
+        let value = filter
+            .must
+            .iter()
+            .flatten()
+            .find_map(|condition| match condition {
+                Condition::Field(FieldCondition {
+                    key,
+                    r#match: Some(Match::Value(MatchValue { value })),
+                    range: None,
+                    geo_bounding_box: None,
+                    geo_radius: None,
+                    geo_polygon: None,
+                    values_count: None,
+                }) if key == &partitions.key => Some(value),
+                _ => None,
+            })?;
+        Some(partitions.indexes.get(value))
+    }
+
     fn save_config(&self) -> OperationResult<()> {
         let config_path = SparseIndexConfig::get_config_path(&self.path);
         self.config.save(&config_path)
@@ -135,6 +326,16 @@ impl<TInvertedIndex: InvertedIndex> SparseVectorIndex<TInvertedIndex> {
 
     /// Returns the maximum number of results that can be returned by the index for a given sparse vector
     /// Warning: the cost of this function grows with the number of dimensions in the query vector
+    /// Distribution of the posting list lengths of the inverted index
+    pub fn statistics(&self) -> SparseIndexStatistics {
+        let mut lengths: Vec<_> = (0..self.inverted_index.posting_count() as DimId)
+            .filter_map(|dim_id| self.inverted_index.get(&dim_id))
+            .map(|posting_list| posting_list.len_to_end())
+            .collect();
+        lengths.sort_unstable();
+        SparseIndexStatistics::from_sorted_posting_lengths(&lengths, size_of::<PostingElement>())
+    }
+
     pub fn max_result_count(&self, query_vector: &SparseVector) -> usize {
         let mut unique_record_ids = HashSet::new();
         for dim_id in query_vector.indices.iter() {
@@ -233,6 +434,7 @@ impl<TInvertedIndex: InvertedIndex> SparseVectorIndex<TInvertedIndex> {
     // search using sparse vector inverted index
     fn search_sparse(
         &self,
+        inverted_index: &TInvertedIndex,
         sparse_vector: &SparseVector,
         filter: Option<&Filter>,
         top: usize,
@@ -246,12 +448,8 @@ impl<TInvertedIndex: InvertedIndex> SparseVectorIndex<TInvertedIndex> {
         let not_deleted_condition = |idx: PointOffsetType| -> bool {
             check_deleted_condition(idx, deleted_vectors, deleted_point_bitslice)
         };
-        let mut search_context = SearchContext::new(
-            sparse_vector.to_owned(),
-            top,
-            &self.inverted_index,
-            is_stopped,
-        );
+        let mut search_context =
+            SearchContext::new(sparse_vector.to_owned(), top, inverted_index, is_stopped);
 
         match filter {
             Some(filter) => {
@@ -292,12 +490,18 @@ impl<TInvertedIndex: InvertedIndex> SparseVectorIndex<TInvertedIndex> {
                 } else {
                     let _timer =
                         ScopeDurationMeasurer::new(&self.searches_telemetry.filtered_sparse);
-                    self.search_sparse(&vector, Some(filter), top, is_stopped)
+                    let inverted_index = match self.select_partition(filter) {
+                        Some(Some(partition_index)) => partition_index,
+                        // No point has the required value
+                        Some(None) => return Ok(vec![]),
+                        None => &self.inverted_index,
+                    };
+                    self.search_sparse(inverted_index, &vector, Some(filter), top, is_stopped)
                 }
             }
             None => {
                 let _timer = ScopeDurationMeasurer::new(&self.searches_telemetry.unfiltered_sparse);
-                self.search_sparse(&vector, filter, top, is_stopped)
+                self.search_sparse(&self.inverted_index, &vector, filter, top, is_stopped)
             }
         }
     }
@@ -367,6 +571,12 @@ impl<TInvertedIndex: InvertedIndex> VectorIndex for SparseVectorIndex<TInvertedI
             self.inverted_index.save(&self.path)?;
         }
 
+        // partitions are only worth maintaining for immutable indexes
+        self.partitions = match &self.partition_by {
+            Some(key) if !self.is_appendable => Some(self.build_partitions(key, stopped)?),
+            _ => None,
+        };
+
         // save config to mark successful build
         self.save_config()?;
         Ok(())
@@ -374,7 +584,10 @@ impl<TInvertedIndex: InvertedIndex> VectorIndex for SparseVectorIndex<TInvertedI
 
     fn get_telemetry_data(&self) -> VectorIndexSearchesTelemetry {
         let tm = &self.searches_telemetry;
-        tm.into()
+        VectorIndexSearchesTelemetry {
+            sparse_statistics: Some(self.statistics()),
+            ..tm.into()
+        }
     }
 
     fn files(&self) -> Vec<PathBuf> {
@@ -386,6 +599,14 @@ impl<TInvertedIndex: InvertedIndex> VectorIndex for SparseVectorIndex<TInvertedI
         let mut all_files = vec![];
         all_files.push(config_file);
         all_files.extend_from_slice(&TInvertedIndex::files(&self.path));
+        if let Some(partitions) = &self.partitions {
+            all_files.push(Self::partitions_config_path(&self.path));
+            for partition in 0..partitions.indexes.len() {
+                all_files.extend(TInvertedIndex::files(&Self::partition_path(
+                    &self.path, partition,
+                )));
+            }
+        }
         all_files
     }
 
@@ -418,3 +639,13 @@ impl<TInvertedIndex: InvertedIndex> VectorIndex for SparseVectorIndex<TInvertedI
         );
     }
 }
+
+/// Convert a payload value into a partition value, the same way `match` conditions compare them
+fn value_to_partition(value: &Value) -> Option<ValueVariants> {
+    match value {
+        Value::String(keyword) => Some(ValueVariants::Keyword(keyword.clone())),
+        Value::Number(number) => number.as_i64().map(ValueVariants::Integer),
+        Value::Bool(flag) => Some(ValueVariants::Bool(*flag)),
+        _ => None,
+    }
+}
@@ -1,11 +1,11 @@
 use std::collections::{HashMap, HashSet};
 use std::fs::create_dir_all;
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
-use common::types::PointOffsetType;
+use common::types::{PointOffsetType, ScoredPointOffset};
 use log::debug;
 use parking_lot::RwLock;
 use rocksdb::DB;
@@ -17,7 +17,9 @@ use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
 use crate::common::utils::{IndexesMap, JsonPathPayload, MultiValue};
 use crate::common::Flusher;
 use crate::id_tracker::IdTrackerSS;
+use crate::index::field_index::full_text_index::bm25::Bm25Params;
 use crate::index::field_index::index_selector::index_selector;
+use crate::index::field_index::null_index::{find_null_index, NullIndex};
 use crate::index::field_index::{
     CardinalityEstimation, FieldIndex, PayloadBlockCondition, PrimaryCondition,
 };
@@ -27,13 +29,14 @@ use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::index::struct_filter_context::StructFilterContext;
 use crate::index::visited_pool::VisitedPool;
 use crate::index::PayloadIndex;
+use crate::payload_storage::payload_dictionary::PayloadDictionary;
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::payload_storage::{FilterContext, PayloadStorage};
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     infer_collection_value_type, infer_value_type, Condition, FieldCondition, Filter,
     IsEmptyCondition, IsNullCondition, Payload, PayloadContainer, PayloadField, PayloadFieldSchema,
-    PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
+    PayloadFieldStatistics, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
 };
 
 pub const PAYLOAD_FIELD_INDEX_PATH: &str = "fields";
@@ -47,6 +50,11 @@ pub struct StructPayloadIndex {
     /// Indexes, associated with fields
     pub field_indexes: IndexesMap,
     config: PayloadConfig,
+    /// Indexed fields, which are being rebuilt with new parameters in background
+    ///
+    /// Their storage is overwritten by the new indexes, so they are not persisted in the config
+    /// until the rebuilt indexes are attached.
+    reindexed_fields: HashSet<PayloadKeyType>,
     /// Root of index persistence dir
     path: PathBuf,
     /// Used to select unique point ids
@@ -89,13 +97,27 @@ impl StructPayloadIndex {
         indexes
     }
 
+    fn null_index(&self, field: PayloadKeyTypeRef) -> Option<&NullIndex> {
+        self.field_indexes
+            .get(field)
+            .and_then(|indexes| find_null_index(indexes))
+    }
+
     fn config_path(&self) -> PathBuf {
         PayloadConfig::get_config_path(&self.path)
     }
 
     fn save_config(&self) -> OperationResult<()> {
         let config_path = self.config_path();
-        self.config.save(&config_path)
+        if self.reindexed_fields.is_empty() {
+            return self.config.save(&config_path);
+        }
+        // A field with partially rebuilt storage is built from scratch after a restart
+        let mut config = self.config.clone();
+        config
+            .indexed_fields
+            .retain(|field, _| !self.reindexed_fields.contains(field));
+        config.save(&config_path)
     }
 
     fn load_all_fields(&mut self, is_appendable: bool) -> OperationResult<()> {
@@ -155,6 +177,7 @@ impl StructPayloadIndex {
             id_tracker,
             field_indexes: Default::default(),
             config,
+            reindexed_fields: Default::default(),
             path: path.to_owned(),
             visited_pool: Default::default(),
             db,
@@ -201,9 +224,135 @@ impl StructPayloadIndex {
         Ok(())
     }
 
+    /// Create empty field indexes, to be filled with [`StructPayloadIndex::index_points`]
+    ///
+    /// If the field is already indexed with a schema of the same type, the new indexes share
+    /// storage with the current ones. Current indexes keep serving requests from memory until
+    /// the new ones are attached, but the field is not persisted in the config in the meantime.
+    pub fn init_field_indexes(
+        &mut self,
+        field: PayloadKeyTypeRef,
+        payload_schema: PayloadFieldSchema,
+    ) -> OperationResult<Vec<FieldIndex>> {
+        if let Some(current_schema) = self.config.indexed_fields.get(field) {
+            if current_schema.schema_type() != payload_schema.schema_type() {
+                return Err(OperationError::service_error(format!(
+                    "Can't rebuild index of `{field}` with a different type, drop it first"
+                )));
+            }
+            self.reindexed_fields.insert(field.to_owned());
+            self.save_config()?;
+        }
+
+        let field_indexes = index_selector(field, &payload_schema, self.db.clone(), true);
+        for index in &field_indexes {
+            index.recreate()?;
+        }
+        Ok(field_indexes)
+    }
+
+    /// Add payload values of the `points` to field indexes, which are not attached yet
+    pub fn index_points(
+        &self,
+        field: PayloadKeyTypeRef,
+        field_indexes: &mut [FieldIndex],
+        points: Range<PointOffsetType>,
+    ) -> OperationResult<()> {
+        let payload_storage = self.payload.borrow();
+        let id_tracker = self.id_tracker.borrow();
+        for point_id in points {
+            if id_tracker.is_deleted_point(point_id) {
+                continue;
+            }
+            let point_payload = payload_storage.payload(point_id)?;
+            let field_value = &point_payload.get_value(field);
+            for field_index in field_indexes.iter_mut() {
+                field_index.add_point(point_id, field_value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Attach field indexes, built with [`StructPayloadIndex::index_points`] outside of the
+    /// write lock
+    ///
+    /// Points deleted while the indexes were built are removed from them before attaching.
+    /// Indexes of a rebuilt field replace the current ones.
+    pub fn apply_field_indexes(
+        &mut self,
+        field: PayloadKeyTypeRef,
+        payload_schema: PayloadFieldSchema,
+        mut field_indexes: Vec<FieldIndex>,
+    ) -> OperationResult<bool> {
+        if self.config.indexed_fields.contains_key(field) && !self.reindexed_fields.contains(field)
+        {
+            // Built indexes share storage with the existing ones, so they are only discarded
+            return Ok(false);
+        }
+
+        let deleted_points = self
+            .id_tracker
+            .borrow()
+            .deleted_point_bitslice()
+            .iter_ones()
+            .collect::<Vec<_>>();
+        for field_index in field_indexes.iter_mut() {
+            for &point_id in &deleted_points {
+                field_index.remove_point(point_id as PointOffsetType)?;
+            }
+            field_index.flusher()()?;
+        }
+
+        // Replaced indexes are not cleared, as their storage is reused by the new ones
+        self.field_indexes.insert(field.into(), field_indexes);
+        self.config
+            .indexed_fields
+            .insert(field.to_owned(), payload_schema);
+        self.reindexed_fields.remove(field);
+        self.save_config()?;
+        Ok(true)
+    }
+
     /// Number of available points
     ///
     /// - excludes soft deleted points
+    /// Rank points by BM25 relevance of the full-text indexed `field` to the `text`.
+    ///
+    /// Allows using a full-text index as a scoring source, e.g. for keyword and vector hybrid search.
+    pub fn text_search(
+        &self,
+        field: PayloadKeyTypeRef,
+        text: &str,
+        filter: Option<&Filter>,
+        top: usize,
+    ) -> OperationResult<Vec<ScoredPointOffset>> {
+        let full_text_index = self
+            .field_indexes
+            .get(field)
+            .and_then(|indexes| {
+                indexes.iter().find_map(|index| match index {
+                    FieldIndex::FullTextIndex(full_text_index) => Some(full_text_index),
+                    _ => None,
+                })
+            })
+            .ok_or_else(|| {
+                OperationError::service_error(format!(
+                    "Full-text index is required for text search on field `{field}`"
+                ))
+            })?;
+
+        let id_tracker = self.id_tracker.borrow();
+        let filter_context = filter.map(|filter| self.filter_context(filter));
+        Ok(
+            full_text_index.search_bm25(text, &Bm25Params::default(), top, |idx| {
+                !id_tracker.is_deleted_point(idx)
+                    && filter_context
+                        .as_ref()
+                        .map_or(true, |context| context.check(idx))
+            }),
+        )
+    }
+
     pub fn available_point_count(&self) -> usize {
         self.id_tracker.borrow().available_point_count()
     }
@@ -239,6 +388,26 @@ impl StructPayloadIndex {
                 let full_path = JsonPathPayload::extend_or_new(nested_path, &field.key);
                 let full_path = full_path.path;
 
+                if let Some(null_index) = self.null_index(&full_path) {
+                    let empty_points =
+                        available_points.saturating_sub(null_index.has_values_count());
+                    if nested_path.is_some() {
+                        // Nested filter checks every array element separately, while the index
+                        // only knows if all elements are empty. It only gives a lower bound.
+                        return CardinalityEstimation {
+                            primary_clauses: vec![],
+                            min: empty_points,
+                            exp: empty_points.max(available_points / 2),
+                            max: available_points,
+                        };
+                    }
+                    return CardinalityEstimation::exact(empty_points).with_primary_clause(
+                        PrimaryCondition::IsEmpty(IsEmptyCondition {
+                            is_empty: PayloadField { key: full_path },
+                        }),
+                    );
+                }
+
                 let mut indexed_points = 0;
                 if let Some(field_indexes) = self.field_indexes.get(&full_path) {
                     for index in field_indexes {
@@ -268,6 +437,13 @@ impl StructPayloadIndex {
                 let full_path = JsonPathPayload::extend_or_new(nested_path, &field.key);
                 let full_path = full_path.path;
 
+                if let Some(null_index) = self.null_index(&full_path) {
+                    return CardinalityEstimation::exact(null_index.is_null_count())
+                        .with_primary_clause(PrimaryCondition::IsNull(IsNullCondition {
+                            is_null: PayloadField { key: full_path },
+                        }));
+                }
+
                 let mut indexed_points = 0;
                 if let Some(field_indexes) = self.field_indexes.get(&full_path) {
                     for index in field_indexes {
@@ -325,6 +501,39 @@ impl StructPayloadIndex {
             .collect()
     }
 
+    /// Compress payload, written from now on, with the dictionary
+    pub fn set_payload_dictionary(&mut self, dictionary: PayloadDictionary) {
+        self.payload.borrow_mut().set_dictionary(dictionary);
+    }
+
+    /// Whether the field is indexed as a tenant field, see [`PayloadFieldSchema::is_tenant`]
+    pub fn is_tenant_field(&self, field: PayloadKeyTypeRef) -> bool {
+        self.config
+            .indexed_fields
+            .get(field)
+            .map_or(false, PayloadFieldSchema::is_tenant)
+    }
+
+    /// Statistics of each indexed field, combined from all indexes of the field
+    pub fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        self.field_indexes
+            .iter()
+            .filter_map(|(name, indexes)| {
+                let mut statistics = indexes
+                    .iter()
+                    .map(|index| index.get_statistics())
+                    .reduce(PayloadFieldStatistics::complement)?;
+                if self.is_tenant_field(name) {
+                    statistics.tenants = indexes.iter().find_map(|index| match index {
+                        FieldIndex::KeywordIndex(index) => Some(index.get_tenant_statistics()),
+                        _ => None,
+                    });
+                }
+                Some((name.clone(), statistics))
+            })
+            .collect()
+    }
+
     pub fn restore_database_snapshot(
         snapshot_path: &Path,
         segment_path: &Path,
@@ -350,11 +559,12 @@ impl PayloadIndex for StructPayloadIndex {
         {
             // the field is already indexed with the same schema
             // no need to rebuild index and to save the config
-            if prev_schema == payload_schema {
+            if prev_schema == payload_schema && !self.reindexed_fields.contains(field) {
                 return Ok(());
             }
         }
         self.build_and_save(field, payload_schema)?;
+        self.reindexed_fields.remove(field);
         self.save_config()?;
 
         Ok(())
@@ -362,6 +572,7 @@ impl PayloadIndex for StructPayloadIndex {
 
     fn drop_index(&mut self, field: PayloadKeyTypeRef) -> OperationResult<()> {
         self.config.indexed_fields.remove(field);
+        self.reindexed_fields.remove(field);
         let removed_indexes = self.field_indexes.remove(field);
 
         if let Some(indexes) = removed_indexes {
@@ -429,8 +640,22 @@ impl PayloadIndex for StructPayloadIndex {
                             )
                         }
                         PrimaryCondition::Ids(ids) => Box::new(ids.iter().copied()),
-                        PrimaryCondition::IsEmpty(_) => points_iterator_ref.iter_ids(), /* there are no fast index for IsEmpty */
-                        PrimaryCondition::IsNull(_) => points_iterator_ref.iter_ids(),  /* no fast index for IsNull too */
+                        PrimaryCondition::IsEmpty(IsEmptyCondition { is_empty: field }) => {
+                            match self.null_index(&field.key) {
+                                Some(null_index) => Box::new(
+                                    points_iterator_ref
+                                        .iter_ids()
+                                        .filter(|&id| null_index.values_is_empty(id)),
+                                ),
+                                None => points_iterator_ref.iter_ids(),
+                            }
+                        }
+                        PrimaryCondition::IsNull(IsNullCondition { is_null: field }) => {
+                            match self.null_index(&field.key) {
+                                Some(null_index) => Box::new(null_index.iter_is_null()),
+                                None => points_iterator_ref.iter_ids(),
+                            }
+                        }
                     }
                 })
                 .filter(|&id| !visited_list.check_and_update_visited(id))
@@ -476,6 +701,10 @@ impl PayloadIndex for StructPayloadIndex {
     fn assign(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
         for (field, field_index) in &mut self.field_indexes {
             let field_value = &payload.get_value(field);
+            // Fields, not present in the assigned payload, keep their indexed values
+            if field_value.is_empty() {
+                continue;
+            }
             for index in field_index {
                 index.add_point(point_id, field_value)?;
             }
@@ -548,6 +777,8 @@ impl PayloadIndex for StructPayloadIndex {
     }
 
     fn files(&self) -> Vec<PathBuf> {
-        vec![self.config_path()]
+        let mut files = vec![self.config_path()];
+        files.extend(self.payload.borrow().files());
+        files
     }
 }
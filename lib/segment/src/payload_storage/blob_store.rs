@@ -0,0 +1,195 @@
+//! Storage of large payload values outside of the primary payload storage.
+//!
+//! Values of blob fields are appended to a separate file of the segment, while the payload record
+//! only keeps a reference to them. Blobs are read only when the full payload is requested.
+//! Replaced and deleted blobs are not reclaimed, the file shrinks when the segment is optimized.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::hash::Hasher;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use seahash::SeaHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::Flusher;
+use crate::types::{Payload, PayloadKeyType};
+
+pub const PAYLOAD_BLOBS_FILE: &str = "payload_blobs.dat";
+
+/// Location of a single value in the blob file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobRef {
+    pub offset: u64,
+    pub length: u64,
+    /// Hash of the value, used to avoid writing the same value again on payload updates
+    pub hash: u64,
+}
+
+/// References to values of blob fields of a single point
+pub type BlobRefs = BTreeMap<PayloadKeyType, BlobRef>;
+
+struct BlobFile {
+    file: File,
+    length: u64,
+}
+
+/// Append-only file of blob values
+pub struct BlobStore {
+    path: PathBuf,
+    file: Arc<Mutex<BlobFile>>,
+    /// Top-level payload keys, which values are stored as blobs
+    fields: Vec<PayloadKeyType>,
+}
+
+impl BlobStore {
+    pub fn open(segment_path: &Path, fields: Vec<PayloadKeyType>) -> OperationResult<Self> {
+        let path = segment_path.join(PAYLOAD_BLOBS_FILE);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(&path)?;
+        let length = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file: Arc::new(Mutex::new(BlobFile { file, length })),
+            fields,
+        })
+    }
+
+    pub fn is_blob_field(&self, key: &str) -> bool {
+        self.fields.iter().any(|field| field == key)
+    }
+
+    /// Move values of blob fields out of the payload into the blob file.
+    ///
+    /// Values, equal to the ones referenced by `previous`, are not written again.
+    pub fn offload(&self, payload: &mut Payload, previous: &BlobRefs) -> OperationResult<BlobRefs> {
+        let mut refs = BlobRefs::new();
+        for field in &self.fields {
+            let Some(value) = payload.0.remove(field) else {
+                continue;
+            };
+            let data = serde_json::to_vec(&value)?;
+            let hash = hash_blob(&data);
+            let blob_ref = match previous.get(field) {
+                Some(blob_ref) if blob_ref.hash == hash && blob_ref.length == data.len() as u64 => {
+                    *blob_ref
+                }
+                _ => self.append(&data, hash)?,
+            };
+            refs.insert(field.clone(), blob_ref);
+        }
+        Ok(refs)
+    }
+
+    /// Read referenced blob values back into the payload
+    pub fn load(&self, payload: &mut Payload, refs: &BlobRefs) -> OperationResult<()> {
+        for (field, blob_ref) in refs {
+            let data = self.read(blob_ref)?;
+            payload
+                .0
+                .insert(field.clone(), serde_json::from_slice(&data)?);
+        }
+        Ok(())
+    }
+
+    fn append(&self, data: &[u8], hash: u64) -> OperationResult<BlobRef> {
+        let mut blob_file = self.file.lock();
+        let offset = blob_file.length;
+        blob_file.file.seek(SeekFrom::Start(offset))?;
+        blob_file.file.write_all(data)?;
+        blob_file.length += data.len() as u64;
+        Ok(BlobRef {
+            offset,
+            length: data.len() as u64,
+            hash,
+        })
+    }
+
+    fn read(&self, blob_ref: &BlobRef) -> OperationResult<Vec<u8>> {
+        let mut blob_file = self.file.lock();
+        if blob_ref.offset + blob_ref.length > blob_file.length {
+            return Err(OperationError::service_error(format!(
+                "payload blob at offset {} is out of bounds of {:?}",
+                blob_ref.offset, self.path,
+            )));
+        }
+        let mut data = vec![0; blob_ref.length as usize];
+        blob_file.file.seek(SeekFrom::Start(blob_ref.offset))?;
+        blob_file.file.read_exact(&mut data)?;
+        Ok(data)
+    }
+
+    /// Remove all blobs
+    pub fn wipe(&self) -> OperationResult<()> {
+        let mut blob_file = self.file.lock();
+        blob_file.file.set_len(0)?;
+        blob_file.length = 0;
+        Ok(())
+    }
+
+    pub fn flusher(&self) -> Flusher {
+        let file = self.file.clone();
+        Box::new(move || {
+            file.lock().file.sync_data()?;
+            Ok(())
+        })
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+}
+
+fn hash_blob(data: &[u8]) -> u64 {
+    let mut hasher = SeaHasher::new();
+    hasher.write(data);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn test_offload_and_load() {
+        let dir = Builder::new().prefix("blob_store").tempdir().unwrap();
+        let store = BlobStore::open(dir.path(), vec!["image".to_string()]).unwrap();
+
+        let original: Payload = json!({
+            "name": "cat",
+            "image": "a".repeat(1000),
+        })
+        .into();
+
+        let mut payload = original.clone();
+        let refs = store.offload(&mut payload, &BlobRefs::new()).unwrap();
+        assert_eq!(payload, json!({ "name": "cat" }).into());
+        assert_eq!(refs.len(), 1);
+
+        // Unchanged value is not written again
+        let mut same_payload = original.clone();
+        let same_refs = store.offload(&mut same_payload, &refs).unwrap();
+        assert_eq!(same_refs, refs);
+
+        let mut changed_payload: Payload = json!({ "image": "b".repeat(10) }).into();
+        let changed_refs = store.offload(&mut changed_payload, &refs).unwrap();
+        assert_ne!(changed_refs, refs);
+
+        store.flusher()().unwrap();
+        drop(store);
+
+        let store = BlobStore::open(dir.path(), vec!["image".to_string()]).unwrap();
+        store.load(&mut payload, &refs).unwrap();
+        assert_eq!(payload, original);
+    }
+}
@@ -4,7 +4,7 @@ use serde_json::Value;
 
 use crate::types::{
     AnyVariants, FieldCondition, GeoBoundingBox, GeoPoint, GeoPolygon, GeoRadius, Match, MatchAny,
-    MatchExcept, MatchText, MatchValue, Range, ValueVariants, ValuesCount,
+    MatchExcept, MatchValue, Range, ValueVariants, ValuesCount,
 };
 
 pub trait ValueChecker {
@@ -80,8 +80,10 @@ impl ValueChecker for Match {
                 }
                 _ => false,
             },
-            Match::Text(MatchText { text }) => match payload {
-                Value::String(stored) => stored.contains(text),
+            Match::Text(text_match) => match payload {
+                Value::String(stored) => {
+                    stored.contains(text_match.phrase().unwrap_or(&text_match.text))
+                }
                 _ => false,
             },
             Match::Any(MatchAny { any }) => match (payload, any) {
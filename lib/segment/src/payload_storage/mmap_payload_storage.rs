@@ -0,0 +1,426 @@
+//! Payload storage in memory-mapped files, without RocksDB.
+//!
+//! Records are written to fixed-size pages of the pages file, each record starts at the beginning
+//! of a page and occupies whole pages. A point's record is updated in place as long as the new
+//! version fits into the pages of the old one, otherwise it is written after the last record.
+//! Pages of moved and deleted records are not reused, the file shrinks when the segment is
+//! optimized.
+//!
+//! Pointers file keeps the location of the record of each point:
+//!
+//! ```text
+//! [first page: u32][number of pages: u32]
+//! ```
+//!
+//! A point without a record has zero pages. The record itself is prefixed with its length,
+//! so a record, updated in place, is consistent with its pointer even if only the pages were
+//! persisted.
+
+use std::cmp::max;
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+
+use common::types::PointOffsetType;
+use memory::mmap_ops::{create_and_ensure_length, open_write_mmap};
+use serde_json::Value;
+
+use crate::common::mmap_type::MmapSlice;
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::Flusher;
+use crate::payload_storage::blob_store::{BlobRefs, BlobStore};
+use crate::payload_storage::payload_codec::{decode_record, encode_payload, encode_record};
+use crate::payload_storage::payload_dictionary::PayloadDictionary;
+use crate::payload_storage::PayloadStorage;
+use crate::types::{Payload, PayloadCompressionConfig, PayloadKeyTypeRef};
+
+pub const PAYLOAD_PAGES_FILE: &str = "payload_pages.dat";
+pub const PAYLOAD_POINTERS_FILE: &str = "payload_pointers.dat";
+
+/// Size of a page of the pages file, records are aligned to it
+pub const PAYLOAD_PAGE_SIZE: usize = 256;
+
+/// Number of pages and pointers, allocated when the storage is created
+const INITIAL_CAPACITY: usize = 64;
+
+const LENGTH_SIZE: usize = size_of::<u32>();
+
+/// Location of the record of a single point in the pages file
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct RecordPointer {
+    page: u32,
+    page_count: u32,
+}
+
+impl RecordPointer {
+    fn is_empty(&self) -> bool {
+        self.page_count == 0
+    }
+
+    fn end_page(&self) -> usize {
+        self.page as usize + self.page_count as usize
+    }
+
+    fn byte_range(&self) -> std::ops::Range<usize> {
+        let start = self.page as usize * PAYLOAD_PAGE_SIZE;
+        start..start + self.page_count as usize * PAYLOAD_PAGE_SIZE
+    }
+}
+
+/// Open memory-mapped file as a slice of `T`, the file is created with `initial_len` if missing
+fn open_slice<T>(path: &Path, initial_len: usize) -> OperationResult<MmapSlice<T>> {
+    if !path.exists() {
+        create_and_ensure_length(path, initial_len * size_of::<T>())?;
+    }
+    Ok(unsafe { MmapSlice::try_from(open_write_mmap(path)?)? })
+}
+
+/// Grow the file of `slice` to fit at least `len` elements
+///
+/// File is at least doubled, so the number of remappings is logarithmic.
+fn ensure_len<T>(path: &Path, slice: &mut MmapSlice<T>, len: usize) -> OperationResult<()> {
+    if slice.len() >= len {
+        return Ok(());
+    }
+    // Changes must be persisted by the old mapping, before it is replaced
+    slice.flusher()()?;
+    let new_len = max(len, slice.len() * 2);
+    create_and_ensure_length(path, new_len * size_of::<T>())?;
+    *slice = unsafe { MmapSlice::try_from(open_write_mmap(path)?)? };
+    Ok(())
+}
+
+/// Memory-mapped implementation of `PayloadStorage`.
+/// Does not keep payload in memory, except for the pages cached by the OS
+pub struct MmapPayloadStorage {
+    pages_path: PathBuf,
+    pointers_path: PathBuf,
+    pages: MmapSlice<u8>,
+    pointers: MmapSlice<RecordPointer>,
+    /// First page after the last record, new records are written here
+    next_page: usize,
+    compression: Option<PayloadCompressionConfig>,
+    dictionary: Option<PayloadDictionary>,
+    blobs: Option<BlobStore>,
+}
+
+impl MmapPayloadStorage {
+    pub fn open(segment_path: &Path) -> OperationResult<Self> {
+        Self::open_with_options(segment_path, None, None, None)
+    }
+
+    /// Open storage, which writes configured payload fields compressed
+    /// and moves values of blob fields to the blob store
+    pub fn open_with_options(
+        segment_path: &Path,
+        compression: Option<PayloadCompressionConfig>,
+        dictionary: Option<PayloadDictionary>,
+        blobs: Option<BlobStore>,
+    ) -> OperationResult<Self> {
+        let pages_path = segment_path.join(PAYLOAD_PAGES_FILE);
+        let pointers_path = segment_path.join(PAYLOAD_POINTERS_FILE);
+        let pages: MmapSlice<u8> = open_slice(&pages_path, INITIAL_CAPACITY * PAYLOAD_PAGE_SIZE)?;
+        let pointers: MmapSlice<RecordPointer> = open_slice(&pointers_path, INITIAL_CAPACITY)?;
+
+        let next_page = pointers
+            .iter()
+            .map(RecordPointer::end_page)
+            .max()
+            .unwrap_or(0);
+        if next_page * PAYLOAD_PAGE_SIZE > pages.len() {
+            return Err(OperationError::service_error(format!(
+                "payload pointers reference page {next_page}, which is out of bounds of {}",
+                pages_path.display(),
+            )));
+        }
+
+        Ok(Self {
+            pages_path,
+            pointers_path,
+            pages,
+            pointers,
+            next_page,
+            compression,
+            dictionary,
+            blobs,
+        })
+    }
+
+    fn pointer(&self, point_id: PointOffsetType) -> Option<RecordPointer> {
+        self.pointers
+            .get(point_id as usize)
+            .copied()
+            .filter(|pointer| !pointer.is_empty())
+    }
+
+    fn set_pointer(
+        &mut self,
+        point_id: PointOffsetType,
+        pointer: RecordPointer,
+    ) -> OperationResult<()> {
+        let idx = point_id as usize;
+        ensure_len(&self.pointers_path, &mut self.pointers, idx + 1)?;
+        self.pointers[idx] = pointer;
+        Ok(())
+    }
+
+    fn record(&self, pointer: RecordPointer) -> OperationResult<&[u8]> {
+        let pages = &self.pages[pointer.byte_range()];
+        let length = u32::from_le_bytes(pages[..LENGTH_SIZE].try_into().unwrap()) as usize;
+        pages
+            .get(LENGTH_SIZE..LENGTH_SIZE + length)
+            .ok_or_else(|| OperationError::service_error("corrupted payload record length"))
+    }
+
+    fn write_record(&mut self, point_id: PointOffsetType, record: &[u8]) -> OperationResult<()> {
+        let length = u32::try_from(record.len())
+            .map_err(|_| OperationError::service_error("payload is too large"))?;
+        let page_count = (LENGTH_SIZE + record.len()).div_ceil(PAYLOAD_PAGE_SIZE);
+
+        let pointer = match self.pointer(point_id) {
+            // Update in place, if the new version fits
+            Some(pointer) if pointer.page_count as usize >= page_count => pointer,
+            _ => {
+                let page = self.next_page;
+                let end_page = page + page_count;
+                ensure_len(
+                    &self.pages_path,
+                    &mut self.pages,
+                    end_page * PAYLOAD_PAGE_SIZE,
+                )?;
+                self.next_page = end_page;
+                RecordPointer {
+                    page: page as u32,
+                    page_count: page_count as u32,
+                }
+            }
+        };
+
+        let start = pointer.byte_range().start;
+        self.pages[start..start + LENGTH_SIZE].copy_from_slice(&length.to_le_bytes());
+        self.pages[start + LENGTH_SIZE..start + LENGTH_SIZE + record.len()].copy_from_slice(record);
+        self.set_pointer(point_id, pointer)
+    }
+
+    pub fn update_storage(
+        &mut self,
+        point_id: PointOffsetType,
+        payload: &Payload,
+    ) -> OperationResult<()> {
+        let record = match &self.blobs {
+            None => encode_payload(payload, self.compression.as_ref(), self.dictionary.as_ref())?,
+            Some(blobs) => {
+                let previous_refs = self
+                    .read_record(point_id)?
+                    .map(|(_, blob_refs)| blob_refs)
+                    .unwrap_or_default();
+                let mut payload = payload.clone();
+                let blob_refs = blobs.offload(&mut payload, &previous_refs)?;
+                encode_record(
+                    &payload,
+                    &blob_refs,
+                    self.compression.as_ref(),
+                    self.dictionary.as_ref(),
+                )?
+            }
+        };
+        self.write_record(point_id, &record)
+    }
+
+    pub fn remove_from_storage(&mut self, point_id: PointOffsetType) -> OperationResult<()> {
+        if self.pointer(point_id).is_some() {
+            self.set_pointer(point_id, RecordPointer::default())?;
+        }
+        Ok(())
+    }
+
+    fn read_record(
+        &self,
+        point_id: PointOffsetType,
+    ) -> OperationResult<Option<(Payload, BlobRefs)>> {
+        self.pointer(point_id)
+            .map(|pointer| decode_record(self.record(pointer)?, self.dictionary.as_ref()))
+            .transpose()
+    }
+
+    /// Compress payload, written from now on, with the dictionary
+    pub fn set_dictionary(&mut self, dictionary: PayloadDictionary) {
+        self.dictionary = Some(dictionary);
+    }
+
+    /// Read full payload of the point, including values of blob fields
+    pub fn read_payload(&self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
+        let Some((mut payload, blob_refs)) = self.read_record(point_id)? else {
+            return Ok(None);
+        };
+        if let Some(blobs) = &self.blobs {
+            blobs.load(&mut payload, &blob_refs)?;
+        }
+        Ok(Some(payload))
+    }
+
+    /// Read payload of the point without blob fields, which are not available for filtering
+    pub fn read_inline_payload(
+        &self,
+        point_id: PointOffsetType,
+    ) -> OperationResult<Option<Payload>> {
+        Ok(self.read_record(point_id)?.map(|(payload, _)| payload))
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files = vec![self.pages_path.clone(), self.pointers_path.clone()];
+        if let Some(dictionary) = &self.dictionary {
+            files.extend(dictionary.files());
+        }
+        if let Some(blobs) = &self.blobs {
+            files.extend(blobs.files());
+        }
+        files
+    }
+
+    pub fn iter<F>(&self, mut callback: F) -> OperationResult<()>
+    where
+        F: FnMut(PointOffsetType, &Payload) -> OperationResult<bool>,
+    {
+        for (point_id, pointer) in self.pointers.iter().enumerate() {
+            if pointer.is_empty() {
+                continue;
+            }
+            let (payload, _) = decode_record(self.record(*pointer)?, self.dictionary.as_ref())?;
+            let do_continue = callback(point_id as PointOffsetType, &payload)?;
+            if !do_continue {
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PayloadStorage for MmapPayloadStorage {
+    fn assign_all(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
+        self.update_storage(point_id, payload)
+    }
+
+    fn assign(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
+        match self.read_payload(point_id)? {
+            Some(mut point_payload) => {
+                point_payload.merge(payload);
+                self.update_storage(point_id, &point_payload)
+            }
+            None => self.update_storage(point_id, payload),
+        }
+    }
+
+    fn payload(&self, point_id: PointOffsetType) -> OperationResult<Payload> {
+        Ok(self.read_payload(point_id)?.unwrap_or_default())
+    }
+
+    fn delete(
+        &mut self,
+        point_id: PointOffsetType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<Vec<Value>> {
+        match self.read_payload(point_id)? {
+            Some(mut payload) => {
+                let res = payload.remove(key);
+                if !res.is_empty() {
+                    self.update_storage(point_id, &payload)?;
+                }
+                Ok(res)
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    fn drop(&mut self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
+        let payload = self.read_payload(point_id)?;
+        self.remove_from_storage(point_id)?;
+        Ok(payload)
+    }
+
+    fn wipe(&mut self) -> OperationResult<()> {
+        if let Some(blobs) = &self.blobs {
+            blobs.wipe()?;
+        }
+        self.pointers.fill(RecordPointer::default());
+        self.next_page = 0;
+        Ok(())
+    }
+
+    fn flusher(&self) -> Flusher {
+        // Pages and blobs must be persisted before the pointers, which reference them
+        let blobs_flusher = self.blobs.as_ref().map(|blobs| blobs.flusher());
+        let pages_flusher = self.pages.flusher();
+        let pointers_flusher = self.pointers.flusher();
+        Box::new(move || {
+            if let Some(blobs_flusher) = blobs_flusher {
+                blobs_flusher()?;
+            }
+            pages_flusher()?;
+            pointers_flusher()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn test_mmap_payload_storage() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+        let small: Payload = json!({ "name": "John Doe", "age": 52 }).into();
+        let large: Payload = json!({ "text": "a".repeat(PAYLOAD_PAGE_SIZE * 3) }).into();
+
+        {
+            let mut storage = MmapPayloadStorage::open(dir.path()).unwrap();
+            storage.assign_all(0, &small).unwrap();
+            storage.assign_all(1, &small).unwrap();
+
+            // Smaller update is done in place
+            let pointer = storage.pointer(0).unwrap();
+            storage.delete(0, "age").unwrap();
+            assert_eq!(storage.pointer(0), Some(pointer));
+
+            // Larger update moves the record, beyond the initial capacity of the file
+            for point_id in 0..INITIAL_CAPACITY as PointOffsetType {
+                storage.assign(point_id + 2, &large).unwrap();
+            }
+            let pointer = storage.pointer(1).unwrap();
+            storage.assign(1, &large).unwrap();
+            assert!(storage.pointer(1).unwrap().page > pointer.page);
+
+            storage.drop(2).unwrap();
+            storage.flusher()().unwrap();
+        }
+
+        let mut storage = MmapPayloadStorage::open(dir.path()).unwrap();
+        assert_eq!(
+            storage.payload(0).unwrap(),
+            json!({ "name": "John Doe" }).into(),
+        );
+        let mut expected = small.clone();
+        expected.merge(&large);
+        assert_eq!(storage.payload(1).unwrap(), expected);
+        assert_eq!(storage.payload(2).unwrap(), Payload::default());
+        assert_eq!(storage.payload(3).unwrap(), large);
+
+        let mut count = 0;
+        storage
+            .iter(|_, _| {
+                count += 1;
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(count, INITIAL_CAPACITY + 1);
+
+        storage.wipe().unwrap();
+        assert_eq!(storage.payload(1).unwrap(), Payload::default());
+        storage.assign(5, &small).unwrap();
+        assert_eq!(storage.payload(5).unwrap(), small);
+    }
+}
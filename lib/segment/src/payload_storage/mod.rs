@@ -1,7 +1,11 @@
+pub mod blob_store;
 pub mod condition_checker;
 pub mod in_memory_payload_storage;
 pub mod in_memory_payload_storage_impl;
+pub mod mmap_payload_storage;
 pub mod on_disk_payload_storage;
+pub mod payload_codec;
+pub mod payload_dictionary;
 mod payload_storage_base;
 pub mod payload_storage_enum;
 pub mod query_checker;
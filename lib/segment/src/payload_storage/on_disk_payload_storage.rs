@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use common::types::PointOffsetType;
@@ -5,27 +6,49 @@ use parking_lot::RwLock;
 use rocksdb::DB;
 use serde_json::Value;
 
-use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::kv_store::KvStoreRef;
+use crate::common::operation_error::OperationResult;
 use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, DB_PAYLOAD_CF};
 use crate::common::Flusher;
+use crate::payload_storage::blob_store::{BlobRefs, BlobStore};
+use crate::payload_storage::payload_codec::{decode_record, encode_payload, encode_record};
+use crate::payload_storage::payload_dictionary::PayloadDictionary;
 use crate::payload_storage::PayloadStorage;
-use crate::types::{Payload, PayloadKeyTypeRef};
+use crate::types::{Payload, PayloadCompressionConfig, PayloadKeyTypeRef};
 
 /// On-disk implementation of `PayloadStorage`.
 /// Persists all changes to disk using `store`, does not keep payload in memory
 pub struct OnDiskPayloadStorage {
-    db_wrapper: DatabaseColumnWrapper,
+    store: KvStoreRef,
+    compression: Option<PayloadCompressionConfig>,
+    dictionary: Option<PayloadDictionary>,
+    blobs: Option<BlobStore>,
 }
 
 impl OnDiskPayloadStorage {
     pub fn open(database: Arc<RwLock<DB>>) -> OperationResult<Self> {
-        let db_wrapper = DatabaseColumnWrapper::new(database, DB_PAYLOAD_CF);
-        Ok(OnDiskPayloadStorage { db_wrapper })
+        let store = Arc::new(DatabaseColumnWrapper::new(database, DB_PAYLOAD_CF));
+        Self::open_with_options(store, None, None, None)
+    }
+
+    /// Open storage, which writes configured payload fields compressed
+    /// and moves values of blob fields to the blob store
+    pub fn open_with_options(
+        store: KvStoreRef,
+        compression: Option<PayloadCompressionConfig>,
+        dictionary: Option<PayloadDictionary>,
+        blobs: Option<BlobStore>,
+    ) -> OperationResult<Self> {
+        Ok(OnDiskPayloadStorage {
+            store,
+            compression,
+            dictionary,
+            blobs,
+        })
     }
 
     pub fn remove_from_storage(&self, point_id: PointOffsetType) -> OperationResult<()> {
-        self.db_wrapper
-            .remove(serde_cbor::to_vec(&point_id).unwrap())
+        self.store.remove(&serde_cbor::to_vec(&point_id).unwrap())
     }
 
     pub fn update_storage(
@@ -33,34 +56,81 @@ impl OnDiskPayloadStorage {
         point_id: PointOffsetType,
         payload: &Payload,
     ) -> OperationResult<()> {
-        self.db_wrapper.put(
-            serde_cbor::to_vec(&point_id).unwrap(),
-            serde_cbor::to_vec(payload).unwrap(),
-        )
+        let record = match &self.blobs {
+            None => encode_payload(payload, self.compression.as_ref(), self.dictionary.as_ref())?,
+            Some(blobs) => {
+                let previous_refs = self
+                    .read_record(point_id)?
+                    .map(|(_, blob_refs)| blob_refs)
+                    .unwrap_or_default();
+                let mut payload = payload.clone();
+                let blob_refs = blobs.offload(&mut payload, &previous_refs)?;
+                encode_record(
+                    &payload,
+                    &blob_refs,
+                    self.compression.as_ref(),
+                    self.dictionary.as_ref(),
+                )?
+            }
+        };
+        self.store
+            .put(&serde_cbor::to_vec(&point_id).unwrap(), &record)
     }
 
-    pub fn read_payload(&self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
+    fn read_record(
+        &self,
+        point_id: PointOffsetType,
+    ) -> OperationResult<Option<(Payload, BlobRefs)>> {
         let key = serde_cbor::to_vec(&point_id).unwrap();
-        self.db_wrapper
-            .get_pinned(&key, |raw| serde_cbor::from_slice(raw))?
+        self.store
+            .get(&key)?
+            .map(|record| decode_record(&record, self.dictionary.as_ref()))
             .transpose()
-            .map_err(OperationError::from)
+    }
+
+    /// Compress payload, written from now on, with the dictionary
+    pub fn set_dictionary(&mut self, dictionary: PayloadDictionary) {
+        self.dictionary = Some(dictionary);
+    }
+
+    /// Read full payload of the point, including values of blob fields
+    pub fn read_payload(&self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
+        let Some((mut payload, blob_refs)) = self.read_record(point_id)? else {
+            return Ok(None);
+        };
+        if let Some(blobs) = &self.blobs {
+            blobs.load(&mut payload, &blob_refs)?;
+        }
+        Ok(Some(payload))
+    }
+
+    /// Read payload of the point without blob fields, which are not available for filtering
+    pub fn read_inline_payload(
+        &self,
+        point_id: PointOffsetType,
+    ) -> OperationResult<Option<Payload>> {
+        Ok(self.read_record(point_id)?.map(|(payload, _)| payload))
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files = vec![];
+        if let Some(dictionary) = &self.dictionary {
+            files.extend(dictionary.files());
+        }
+        if let Some(blobs) = &self.blobs {
+            files.extend(blobs.files());
+        }
+        files
     }
 
     pub fn iter<F>(&self, mut callback: F) -> OperationResult<()>
     where
         F: FnMut(PointOffsetType, &Payload) -> OperationResult<bool>,
     {
-        for (key, val) in self.db_wrapper.lock_db().iter()? {
-            let do_continue = callback(
-                serde_cbor::from_slice(&key)?,
-                &serde_cbor::from_slice(&val)?,
-            )?;
-            if !do_continue {
-                return Ok(());
-            }
-        }
-        Ok(())
+        self.store.for_each(&mut |key, val| {
+            let (payload, _) = decode_record(val, self.dictionary.as_ref())?;
+            callback(serde_cbor::from_slice(key)?, &payload)
+        })
     }
 }
 
@@ -115,10 +185,24 @@ impl PayloadStorage for OnDiskPayloadStorage {
     }
 
     fn wipe(&mut self) -> OperationResult<()> {
-        self.db_wrapper.recreate_column_family()
+        if let Some(blobs) = &self.blobs {
+            blobs.wipe()?;
+        }
+        self.store.clear()
     }
 
     fn flusher(&self) -> Flusher {
-        self.db_wrapper.flusher()
+        let db_flusher = self.store.flusher();
+        match &self.blobs {
+            None => db_flusher,
+            Some(blobs) => {
+                // Blobs must be persisted before the records, which reference them
+                let blobs_flusher = blobs.flusher();
+                Box::new(move || {
+                    blobs_flusher()?;
+                    db_flusher()
+                })
+            }
+        }
     }
 }
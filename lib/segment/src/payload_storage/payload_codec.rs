@@ -0,0 +1,331 @@
+//! Serialization of payload records in payload storage.
+//!
+//! Payload without compressed fields is stored as plain CBOR, same as before compression was
+//! introduced. A record with compressed fields has the following layout:
+//!
+//! ```text
+//! [COMPRESSED_PAYLOAD_MARKER][plain part length: u32 LE][plain part: CBOR][compressed part: zstd(CBOR)]
+//! ```
+//!
+//! Marker is never a valid first byte of a CBOR map, so both formats can be read from
+//! the same storage and changing the compression config doesn't require a rewrite.
+//! If the segment has a trained [`PayloadDictionary`], the compressed part is compressed with it
+//! and the record starts with [`DICTIONARY_COMPRESSED_PAYLOAD_MARKER`] instead.
+//!
+//! Record of a point with values in the blob store is prefixed with references to them:
+//!
+//! ```text
+//! [BLOB_REFS_MARKER][references length: u32 LE][references: CBOR][payload record]
+//! ```
+
+use serde_json::Map;
+
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::payload_storage::blob_store::BlobRefs;
+use crate::payload_storage::payload_dictionary::PayloadDictionary;
+use crate::types::{Payload, PayloadCompressionConfig};
+
+/// CBOR "break" stop code, which can't start a data item
+const COMPRESSED_PAYLOAD_MARKER: u8 = 0xff;
+
+/// Reserved CBOR simple value, which can't start a data item
+const BLOB_REFS_MARKER: u8 = 0xfe;
+
+/// Reserved CBOR simple value, which can't start a data item
+const DICTIONARY_COMPRESSED_PAYLOAD_MARKER: u8 = 0xfd;
+
+const LENGTH_SIZE: usize = std::mem::size_of::<u32>();
+
+/// Split payload into values of compressed and plain fields
+fn split_payload(
+    payload: &Payload,
+    compression: &PayloadCompressionConfig,
+) -> (
+    Map<String, serde_json::Value>,
+    Map<String, serde_json::Value>,
+) {
+    payload
+        .0
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .partition(|(key, _)| compression.fields.contains(key))
+}
+
+/// Serialized values of compressed fields of the payload, used to train a dictionary
+///
+/// Returns `None` if the payload has no compressed fields.
+pub fn compression_sample(
+    payload: &Payload,
+    compression: &PayloadCompressionConfig,
+) -> OperationResult<Option<Vec<u8>>> {
+    let (compressed, _) = split_payload(payload, compression);
+    if compressed.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_cbor::to_vec(&compressed)?))
+}
+
+pub fn encode_payload(
+    payload: &Payload,
+    compression: Option<&PayloadCompressionConfig>,
+    dictionary: Option<&PayloadDictionary>,
+) -> OperationResult<Vec<u8>> {
+    let Some(compression) = compression else {
+        return Ok(serde_cbor::to_vec(payload)?);
+    };
+
+    let (compressed, plain) = split_payload(payload, compression);
+
+    if compressed.is_empty() {
+        return Ok(serde_cbor::to_vec(payload)?);
+    }
+
+    let plain = serde_cbor::to_vec(&plain)?;
+    let compressed = serde_cbor::to_vec(&compressed)?;
+    let (marker, compressed) = match dictionary {
+        Some(dictionary) => (
+            DICTIONARY_COMPRESSED_PAYLOAD_MARKER,
+            dictionary.compress(&compressed)?,
+        ),
+        None => (
+            COMPRESSED_PAYLOAD_MARKER,
+            zstd::bulk::compress(&compressed, compression.level()).map_err(|err| {
+                OperationError::service_error(format!("failed to compress payload: {err}"))
+            })?,
+        ),
+    };
+
+    let plain_len = u32::try_from(plain.len())
+        .map_err(|_| OperationError::service_error("payload is too large"))?;
+
+    let mut record = Vec::with_capacity(1 + LENGTH_SIZE + plain.len() + compressed.len());
+    record.push(marker);
+    record.extend_from_slice(&plain_len.to_le_bytes());
+    record.extend_from_slice(&plain);
+    record.extend_from_slice(&compressed);
+    Ok(record)
+}
+
+/// Encode payload record together with references to the point's blobs
+pub fn encode_record(
+    payload: &Payload,
+    blob_refs: &BlobRefs,
+    compression: Option<&PayloadCompressionConfig>,
+    dictionary: Option<&PayloadDictionary>,
+) -> OperationResult<Vec<u8>> {
+    let payload_record = encode_payload(payload, compression, dictionary)?;
+    if blob_refs.is_empty() {
+        return Ok(payload_record);
+    }
+
+    let refs = serde_cbor::to_vec(blob_refs)?;
+    let refs_len = u32::try_from(refs.len())
+        .map_err(|_| OperationError::service_error("too many payload blobs"))?;
+
+    let mut record = Vec::with_capacity(1 + LENGTH_SIZE + refs.len() + payload_record.len());
+    record.push(BLOB_REFS_MARKER);
+    record.extend_from_slice(&refs_len.to_le_bytes());
+    record.extend_from_slice(&refs);
+    record.extend_from_slice(&payload_record);
+    Ok(record)
+}
+
+/// Decode payload record and references to the point's blobs, blob values are not loaded
+pub fn decode_record(
+    record: &[u8],
+    dictionary: Option<&PayloadDictionary>,
+) -> OperationResult<(Payload, BlobRefs)> {
+    if record.first() != Some(&BLOB_REFS_MARKER) {
+        return Ok((decode_payload(record, dictionary)?, BlobRefs::new()));
+    }
+
+    let (refs, payload_record) = split_prefixed(record)
+        .ok_or_else(|| OperationError::service_error("corrupted payload blob references"))?;
+    Ok((
+        decode_payload(payload_record, dictionary)?,
+        serde_cbor::from_slice(refs)?,
+    ))
+}
+
+/// Split `[marker][length: u32 LE][part][rest]` record into the part and the rest
+fn split_prefixed(record: &[u8]) -> Option<(&[u8], &[u8])> {
+    let part_start = 1 + LENGTH_SIZE;
+    let part_len = record
+        .get(1..part_start)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_le_bytes)? as usize;
+    let part_end = part_start + part_len;
+    Some((record.get(part_start..part_end)?, &record[part_end..]))
+}
+
+pub fn decode_payload(
+    record: &[u8],
+    dictionary: Option<&PayloadDictionary>,
+) -> OperationResult<Payload> {
+    let marker = match record.first() {
+        Some(&marker)
+            if marker == COMPRESSED_PAYLOAD_MARKER
+                || marker == DICTIONARY_COMPRESSED_PAYLOAD_MARKER =>
+        {
+            marker
+        }
+        _ => return Ok(serde_cbor::from_slice(record)?),
+    };
+
+    let (plain, compressed) = split_prefixed(record)
+        .ok_or_else(|| OperationError::service_error("corrupted compressed payload record"))?;
+
+    let mut payload: Map<_, _> = serde_cbor::from_slice(plain)?;
+
+    let decompressed = if marker == DICTIONARY_COMPRESSED_PAYLOAD_MARKER {
+        dictionary
+            .ok_or_else(|| {
+                OperationError::service_error(
+                    "payload record is compressed with a dictionary, which is missing",
+                )
+            })?
+            .decompress(compressed)?
+    } else {
+        zstd::stream::decode_all(compressed).map_err(|err| {
+            OperationError::service_error(format!("failed to decompress payload: {err}"))
+        })?
+    };
+    let compressed: Map<_, _> = serde_cbor::from_slice(&decompressed)?;
+    payload.extend(compressed);
+
+    Ok(Payload(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::payload_storage::blob_store::BlobRef;
+
+    fn compression(fields: &[&str]) -> PayloadCompressionConfig {
+        PayloadCompressionConfig {
+            fields: fields.iter().map(|field| field.to_string()).collect(),
+            level: None,
+            dictionary: None,
+        }
+    }
+
+    #[test]
+    fn test_compressed_payload_roundtrip() {
+        let description = "repetitive metadata ".repeat(100);
+        let payload: Payload = json!({
+            "city": "Berlin",
+            "description": description,
+            "tags": ["a", "b"],
+        })
+        .into();
+
+        let plain = encode_payload(&payload, None, None).unwrap();
+        let compressed =
+            encode_payload(&payload, Some(&compression(&["description", "tags"])), None).unwrap();
+
+        assert_eq!(compressed[0], COMPRESSED_PAYLOAD_MARKER);
+        assert!(compressed.len() * 5 < plain.len());
+
+        assert_eq!(decode_payload(&plain, None).unwrap(), payload);
+        assert_eq!(decode_payload(&compressed, None).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_no_compressed_fields_stored_plain() {
+        let payload: Payload = json!({ "city": "Berlin" }).into();
+        let encoded = encode_payload(&payload, Some(&compression(&["description"])), None).unwrap();
+
+        assert_eq!(encoded, serde_cbor::to_vec(&payload).unwrap());
+        assert_eq!(decode_payload(&encoded, None).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_corrupted_record() {
+        let payload: Payload = json!({ "description": "text" }).into();
+        let encoded = encode_payload(&payload, Some(&compression(&["description"])), None).unwrap();
+
+        assert!(decode_payload(&encoded[..3], None).is_err());
+        assert!(decode_payload(&encoded[..LENGTH_SIZE + 2], None).is_err());
+    }
+
+    #[test]
+    fn test_record_with_blob_refs() {
+        let payload: Payload = json!({ "city": "Berlin" }).into();
+        let blob_refs = BlobRefs::from([(
+            "image".to_string(),
+            BlobRef {
+                offset: 10,
+                length: 20,
+                hash: 30,
+            },
+        )]);
+
+        let record = encode_record(&payload, &BlobRefs::new(), None, None).unwrap();
+        assert_eq!(record, encode_payload(&payload, None, None).unwrap());
+        assert_eq!(
+            decode_record(&record, None).unwrap(),
+            (payload.clone(), BlobRefs::new())
+        );
+
+        let record =
+            encode_record(&payload, &blob_refs, Some(&compression(&["city"])), None).unwrap();
+        assert_eq!(record[0], BLOB_REFS_MARKER);
+        assert_eq!(decode_record(&record, None).unwrap(), (payload, blob_refs));
+    }
+
+    #[test]
+    fn test_dictionary_compressed_payload() {
+        let dir = tempfile::Builder::new()
+            .prefix("dictionary")
+            .tempdir()
+            .unwrap();
+        let config = compression(&["metadata"]);
+
+        let payloads: Vec<Payload> = (0..1000)
+            .map(|i| {
+                json!({
+                    "id": i,
+                    "metadata": {
+                        "author": format!("author-{}", i % 10),
+                        "source": "crawler",
+                        "license": "cc-by-sa",
+                        "language": "en",
+                    },
+                })
+                .into()
+            })
+            .collect();
+        let samples: Vec<_> = payloads
+            .iter()
+            .filter_map(|payload| compression_sample(payload, &config).unwrap())
+            .collect();
+        let dictionary = PayloadDictionary::train(
+            dir.path(),
+            &samples,
+            PayloadCompressionConfig::DEFAULT_LEVEL,
+        )
+        .unwrap();
+
+        let payload = &payloads[0];
+        let without_dictionary = encode_payload(payload, Some(&config), None).unwrap();
+        let with_dictionary = encode_payload(payload, Some(&config), Some(&dictionary)).unwrap();
+
+        assert_eq!(with_dictionary[0], DICTIONARY_COMPRESSED_PAYLOAD_MARKER);
+        assert!(with_dictionary.len() < without_dictionary.len());
+        assert_eq!(
+            &decode_payload(&with_dictionary, Some(&dictionary)).unwrap(),
+            payload
+        );
+        assert!(decode_payload(&with_dictionary, None).is_err());
+
+        let loaded = PayloadDictionary::load(dir.path(), PayloadCompressionConfig::DEFAULT_LEVEL)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            &decode_payload(&with_dictionary, Some(&loaded)).unwrap(),
+            payload
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Zstd dictionary, trained on the payload of a segment.
+//!
+//! Values of compressed fields are often small on their own, but similar across points, e.g. JSON
+//! documents with the same structure. Such values are compressed poorly one by one, while a shared
+//! dictionary captures their common parts. Dictionary is trained when the segment is built by an
+//! optimizer and stored in the segment, records compressed with it can't be read without it.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+
+use crate::common::operation_error::{OperationError, OperationResult};
+
+pub const PAYLOAD_DICTIONARY_FILE: &str = "payload_dictionary.dat";
+
+/// Maximal size of a trained dictionary
+const MAX_DICTIONARY_SIZE: usize = 64 * 1024;
+
+/// Number of points, which payloads are sampled to train a dictionary
+pub const DICTIONARY_SAMPLE_COUNT: usize = 10_000;
+
+pub struct PayloadDictionary {
+    path: PathBuf,
+    encoder: EncoderDictionary<'static>,
+    decoder: DecoderDictionary<'static>,
+}
+
+impl PayloadDictionary {
+    fn new(path: PathBuf, data: &[u8], level: i32) -> Self {
+        Self {
+            path,
+            encoder: EncoderDictionary::copy(data, level),
+            decoder: DecoderDictionary::copy(data),
+        }
+    }
+
+    /// Load dictionary of the segment, returns `None` if it was never trained
+    pub fn load(segment_path: &Path, level: i32) -> OperationResult<Option<Self>> {
+        let path = segment_path.join(PAYLOAD_DICTIONARY_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read(&path)?;
+        Ok(Some(Self::new(path, &data, level)))
+    }
+
+    /// Train dictionary on `samples` of data to compress and store it in the segment
+    pub fn train(segment_path: &Path, samples: &[Vec<u8>], level: i32) -> OperationResult<Self> {
+        let data = zstd::dict::from_samples(samples, MAX_DICTIONARY_SIZE).map_err(|err| {
+            OperationError::service_error(format!("failed to train payload dictionary: {err}"))
+        })?;
+        let path = segment_path.join(PAYLOAD_DICTIONARY_FILE);
+        fs::write(&path, &data)?;
+        Ok(Self::new(path, &data, level))
+    }
+
+    pub fn compress(&self, data: &[u8]) -> OperationResult<Vec<u8>> {
+        zstd::bulk::Compressor::with_prepared_dictionary(&self.encoder)
+            .and_then(|mut compressor| compressor.compress(data))
+            .map_err(|err| {
+                OperationError::service_error(format!("failed to compress payload: {err}"))
+            })
+    }
+
+    pub fn decompress(&self, data: &[u8]) -> OperationResult<Vec<u8>> {
+        let mut decompressed = vec![];
+        zstd::stream::read::Decoder::with_prepared_dictionary(data, &self.decoder)
+            .and_then(|mut decoder| decoder.read_to_end(&mut decompressed))
+            .map_err(|err| {
+                OperationError::service_error(format!("failed to decompress payload: {err}"))
+            })?;
+        Ok(decompressed)
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+}
@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
 use common::types::PointOffsetType;
+use serde_json::Value;
 
 use crate::common::utils::IndexesMap;
 use crate::id_tracker::IdTrackerSS;
@@ -14,7 +15,7 @@ use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::payload_storage::ConditionChecker;
 use crate::types::{
     Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, OwnedPayloadRef, Payload,
-    PayloadContainer, PayloadKeyType,
+    PayloadContainer, PayloadKeyType, VERSION_FIELD,
 };
 
 fn check_condition<F>(checker: &F, condition: &Condition) -> bool
@@ -98,6 +99,10 @@ where
     R: AsRef<Vec<FieldIndex>>,
 {
     let checker = |condition: &Condition| match condition {
+        Condition::Field(field_condition) if field_condition.key == VERSION_FIELD => id_tracker
+            .map_or(false, |id_tracker| {
+                check_version_condition(field_condition, id_tracker, point_id)
+            }),
         Condition::Field(field_condition) => {
             check_field_condition(field_condition, get_payload().deref(), field_indexes)
         }
@@ -130,6 +135,19 @@ where
     check_filter(&checker, query)
 }
 
+/// Checks condition on the system version field against the version of the point
+pub fn check_version_condition(
+    field_condition: &FieldCondition,
+    id_tracker: &IdTrackerSS,
+    point_id: PointOffsetType,
+) -> bool {
+    id_tracker
+        .internal_version(point_id)
+        .map_or(false, |version| {
+            field_condition.check(&Value::from(version))
+        })
+}
+
 pub fn check_is_empty_condition(
     is_empty: &IsEmptyCondition,
     payload: &impl PayloadContainer,
@@ -238,6 +256,11 @@ impl ConditionChecker for SimpleConditionChecker {
                                 .unwrap_or_else(|err| panic!("Payload storage is corrupted: {err}"))
                                 .map(|x| x.into())
                         }
+                        // Warn: Possible panic here, same as for on-disk storage
+                        PayloadStorageEnum::MmapPayloadStorage(s) => s
+                            .read_payload(point_id)
+                            .unwrap_or_else(|err| panic!("Payload storage is corrupted: {err}"))
+                            .map(|x| x.into()),
                     };
 
                     payload_ref_cell
@@ -300,6 +323,8 @@ mod tests {
         id_tracker.set_link(1.into(), 1).unwrap();
         id_tracker.set_link(2.into(), 2).unwrap();
         id_tracker.set_link(10.into(), 10).unwrap();
+        id_tracker.set_internal_version(1, 5).unwrap();
+        id_tracker.set_internal_version(2, 7).unwrap();
         payload_storage.assign_all(0, &payload).unwrap();
 
         let payload_checker = SimpleConditionChecker::new(
@@ -556,5 +581,15 @@ mod tests {
             must_not: None,
         };
         assert!(payload_checker.check(2, &query));
+
+        let changed_since = Filter::new_must(Condition::Field(FieldCondition::new_range(
+            VERSION_FIELD,
+            Range {
+                gt: Some(5.0),
+                ..Default::default()
+            },
+        )));
+        assert!(!payload_checker.check(1, &changed_since));
+        assert!(payload_checker.check(2, &changed_since));
     }
 }
@@ -1,53 +1,143 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use common::types::PointOffsetType;
 use parking_lot::RwLock;
 use rocksdb::DB;
 
+use crate::common::kv_store::KvStoreRef;
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, DB_PAYLOAD_CF};
-use crate::types::Payload;
+use crate::payload_storage::blob_store::{BlobRefs, BlobStore};
+use crate::payload_storage::payload_codec::{decode_record, encode_payload, encode_record};
+use crate::payload_storage::payload_dictionary::PayloadDictionary;
+use crate::types::{Payload, PayloadCompressionConfig};
 
 /// In-memory implementation of `PayloadStorage`.
 /// Persists all changes to disk using `store`, but only uses this storage during the initial load
 pub struct SimplePayloadStorage {
     pub(crate) payload: HashMap<PointOffsetType, Payload>,
-    pub(crate) db_wrapper: DatabaseColumnWrapper,
+    pub(crate) store: KvStoreRef,
+    compression: Option<PayloadCompressionConfig>,
+    dictionary: Option<PayloadDictionary>,
+    pub(crate) blobs: Option<BlobStore>,
+    /// References to values of blob fields, which are not kept in memory
+    pub(crate) blob_refs: HashMap<PointOffsetType, BlobRefs>,
 }
 
 impl SimplePayloadStorage {
     pub fn open(database: Arc<RwLock<DB>>) -> OperationResult<Self> {
-        let mut payload_map: HashMap<PointOffsetType, Payload> = Default::default();
+        let store = Arc::new(DatabaseColumnWrapper::new(database, DB_PAYLOAD_CF));
+        Self::open_with_options(store, None, None, None)
+    }
 
-        let db_wrapper = DatabaseColumnWrapper::new(database, DB_PAYLOAD_CF);
+    /// Open storage, which persists configured payload fields compressed
+    /// and moves values of blob fields to the blob store.
+    /// Payload in memory is always kept decompressed.
+    pub fn open_with_options(
+        store: KvStoreRef,
+        compression: Option<PayloadCompressionConfig>,
+        dictionary: Option<PayloadDictionary>,
+        blobs: Option<BlobStore>,
+    ) -> OperationResult<Self> {
+        let mut payload_map: HashMap<PointOffsetType, Payload> = Default::default();
+        let mut blob_refs_map: HashMap<PointOffsetType, BlobRefs> = Default::default();
 
-        for (key, val) in db_wrapper.lock_db().iter()? {
-            let point_id: PointOffsetType = serde_cbor::from_slice(&key)
+        store.for_each(&mut |key, val| {
+            let point_id: PointOffsetType = serde_cbor::from_slice(key)
                 .map_err(|_| OperationError::service_error("cannot deserialize point id"))?;
-            let payload: Payload = serde_cbor::from_slice(&val)
+            let (payload, blob_refs) = decode_record(val, dictionary.as_ref())
                 .map_err(|_| OperationError::service_error("cannot deserialize payload"))?;
             payload_map.insert(point_id, payload);
-        }
+            if !blob_refs.is_empty() {
+                blob_refs_map.insert(point_id, blob_refs);
+            }
+            Ok(true)
+        })?;
 
         Ok(SimplePayloadStorage {
             payload: payload_map,
-            db_wrapper,
+            store,
+            compression,
+            dictionary,
+            blobs,
+            blob_refs: blob_refs_map,
         })
     }
 
     pub(crate) fn update_storage(&self, point_id: &PointOffsetType) -> OperationResult<()> {
         match self.payload.get(point_id) {
-            None => self
-                .db_wrapper
-                .remove(serde_cbor::to_vec(&point_id).unwrap()),
-            Some(payload) => self.db_wrapper.put(
-                serde_cbor::to_vec(&point_id).unwrap(),
-                serde_cbor::to_vec(payload).unwrap(),
-            ),
+            None => self.store.remove(&serde_cbor::to_vec(&point_id).unwrap()),
+            Some(payload) => {
+                let record = match self.blob_refs.get(point_id) {
+                    None => encode_payload(
+                        payload,
+                        self.compression.as_ref(),
+                        self.dictionary.as_ref(),
+                    )?,
+                    Some(blob_refs) => encode_record(
+                        payload,
+                        blob_refs,
+                        self.compression.as_ref(),
+                        self.dictionary.as_ref(),
+                    )?,
+                };
+                self.store
+                    .put(&serde_cbor::to_vec(&point_id).unwrap(), &record)
+            }
+        }
+    }
+
+    /// Compress payload, written from now on, with the dictionary
+    pub fn set_dictionary(&mut self, dictionary: PayloadDictionary) {
+        self.dictionary = Some(dictionary);
+    }
+
+    /// Move values of blob fields to the blob store, returns payload without them
+    pub(crate) fn offload_blobs<'a>(
+        &mut self,
+        point_id: PointOffsetType,
+        payload: &'a Payload,
+    ) -> OperationResult<Cow<'a, Payload>> {
+        let Some(blobs) = &self.blobs else {
+            return Ok(Cow::Borrowed(payload));
+        };
+        let mut payload = payload.clone();
+        let blob_refs = self.blob_refs.entry(point_id).or_default();
+        let new_refs = blobs.offload(&mut payload, blob_refs)?;
+        blob_refs.extend(new_refs);
+        if blob_refs.is_empty() {
+            self.blob_refs.remove(&point_id);
+        }
+        Ok(Cow::Owned(payload))
+    }
+
+    /// Read values of the point's blob fields into the payload
+    pub(crate) fn load_blobs(
+        &self,
+        point_id: PointOffsetType,
+        payload: &mut Payload,
+    ) -> OperationResult<()> {
+        if let (Some(blobs), Some(blob_refs)) = (&self.blobs, self.blob_refs.get(&point_id)) {
+            blobs.load(payload, blob_refs)?;
+        }
+        Ok(())
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files = vec![];
+        if let Some(dictionary) = &self.dictionary {
+            files.extend(dictionary.files());
+        }
+        if let Some(blobs) = &self.blobs {
+            files.extend(blobs.files());
         }
+        files
     }
 
+    /// Payload of the point in memory, without values of blob fields
     pub fn payload_ptr(&self, point_id: PointOffsetType) -> Option<&Payload> {
         self.payload.get(&point_id)
     }
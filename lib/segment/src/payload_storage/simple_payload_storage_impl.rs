@@ -5,16 +5,18 @@ use serde_json::Value;
 
 use crate::common::operation_error::OperationResult;
 use crate::common::Flusher;
+use crate::payload_storage::blob_store::BlobRefs;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
 use crate::payload_storage::PayloadStorage;
 use crate::types::{Payload, PayloadKeyTypeRef};
 
 impl PayloadStorage for SimplePayloadStorage {
     fn assign(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
+        let payload = self.offload_blobs(point_id, payload)?;
         match self.payload.get_mut(&point_id) {
-            Some(point_payload) => point_payload.merge(payload),
+            Some(point_payload) => point_payload.merge(&payload),
             None => {
-                self.payload.insert(point_id, payload.to_owned());
+                self.payload.insert(point_id, payload.into_owned());
             }
         }
 
@@ -24,10 +26,12 @@ impl PayloadStorage for SimplePayloadStorage {
     }
 
     fn payload(&self, point_id: PointOffsetType) -> OperationResult<Payload> {
-        match self.payload.get(&point_id) {
-            Some(payload) => Ok(payload.to_owned()),
-            None => Ok(Default::default()),
-        }
+        let mut payload = match self.payload.get(&point_id) {
+            Some(payload) => payload.to_owned(),
+            None => Default::default(),
+        };
+        self.load_blobs(point_id, &mut payload)?;
+        Ok(payload)
     }
 
     fn delete(
@@ -35,6 +39,27 @@ impl PayloadStorage for SimplePayloadStorage {
         point_id: PointOffsetType,
         key: PayloadKeyTypeRef,
     ) -> OperationResult<Vec<Value>> {
+        if let Some(blobs) = self.blobs.as_ref().filter(|blobs| blobs.is_blob_field(key)) {
+            let Some(blob_ref) = self
+                .blob_refs
+                .get_mut(&point_id)
+                .and_then(|blob_refs| blob_refs.remove(key))
+            else {
+                return Ok(vec![]);
+            };
+            let mut removed = Payload::default();
+            blobs.load(&mut removed, &BlobRefs::from([(key.to_owned(), blob_ref)]))?;
+            if self
+                .blob_refs
+                .get(&point_id)
+                .map_or(false, |refs| refs.is_empty())
+            {
+                self.blob_refs.remove(&point_id);
+            }
+            self.update_storage(&point_id)?;
+            return Ok(removed.remove(key));
+        }
+
         match self.payload.get_mut(&point_id) {
             Some(payload) => {
                 let res = payload.remove(key);
@@ -48,27 +73,53 @@ impl PayloadStorage for SimplePayloadStorage {
     }
 
     fn drop(&mut self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
-        let res = self.payload.remove(&point_id);
+        let mut res = self.payload.remove(&point_id);
+        if let Some(payload) = res.as_mut() {
+            self.load_blobs(point_id, payload)?;
+        }
+        self.blob_refs.remove(&point_id);
         self.update_storage(&point_id)?;
         Ok(res)
     }
 
     fn wipe(&mut self) -> OperationResult<()> {
         self.payload = HashMap::new();
-        self.db_wrapper.recreate_column_family()
+        self.blob_refs = HashMap::new();
+        if let Some(blobs) = &self.blobs {
+            blobs.wipe()?;
+        }
+        self.store.clear()
     }
 
     fn flusher(&self) -> Flusher {
-        self.db_wrapper.flusher()
+        let db_flusher = self.store.flusher();
+        match &self.blobs {
+            None => db_flusher,
+            Some(blobs) => {
+                // Blobs must be persisted before the records, which reference them
+                let blobs_flusher = blobs.flusher();
+                Box::new(move || {
+                    blobs_flusher()?;
+                    db_flusher()
+                })
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use tempfile::Builder;
 
     use super::*;
-    use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+    use crate::common::append_log_kv_store::AppendLogKvStore;
+    use crate::common::kv_store::kv_store_path;
+    use crate::common::rocksdb_wrapper::{
+        open_db, DatabaseColumnWrapper, DB_PAYLOAD_CF, DB_VECTOR_CF,
+    };
+    use crate::payload_storage::blob_store::BlobStore;
 
     #[test]
     fn test_wipe() {
@@ -119,4 +170,73 @@ mod tests {
         let pload = storage.payload(100).unwrap();
         assert_eq!(pload, payload);
     }
+
+    #[test]
+    fn test_blob_fields() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let open_storage = || {
+            let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+            let blobs = BlobStore::open(dir.path(), vec!["document".to_string()]).unwrap();
+            let store = Arc::new(DatabaseColumnWrapper::new(db, DB_PAYLOAD_CF));
+            SimplePayloadStorage::open_with_options(store, None, None, Some(blobs)).unwrap()
+        };
+
+        let payload: Payload = serde_json::json!({
+            "title": "Foundation",
+            "document": "Psychohistory ".repeat(100),
+        })
+        .into();
+
+        {
+            let mut storage = open_storage();
+            storage.assign(100, &payload).unwrap();
+            storage.assign(200, &payload).unwrap();
+
+            // Blob values are not kept in memory
+            assert_eq!(
+                storage.payload_ptr(100).unwrap(),
+                &serde_json::json!({ "title": "Foundation" }).into()
+            );
+            assert_eq!(storage.payload(100).unwrap(), payload);
+
+            let removed = storage.delete(200, "document").unwrap();
+            assert_eq!(removed, vec![payload.0["document"].clone()]);
+            assert_eq!(
+                storage.payload(200).unwrap(),
+                serde_json::json!({ "title": "Foundation" }).into()
+            );
+
+            storage.flusher()().unwrap();
+        }
+
+        let storage = open_storage();
+        assert_eq!(storage.payload(100).unwrap(), payload);
+        assert!(storage.payload(200).unwrap().0.get("document").is_none());
+    }
+
+    #[test]
+    fn test_append_log_store() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let open_storage = || {
+            let store = AppendLogKvStore::open(&kv_store_path(dir.path(), DB_PAYLOAD_CF)).unwrap();
+            SimplePayloadStorage::open_with_options(Arc::new(store), None, None, None).unwrap()
+        };
+
+        let payload: Payload = serde_json::json!({ "name": "John Doe" }).into();
+        {
+            let mut storage = open_storage();
+            storage.assign(100, &payload).unwrap();
+            storage.assign(200, &payload).unwrap();
+            storage.drop(200).unwrap();
+            storage.flusher()().unwrap();
+        }
+
+        let mut storage = open_storage();
+        assert_eq!(storage.payload(100).unwrap(), payload);
+        assert!(storage.payload(200).unwrap().is_empty());
+
+        storage.wipe().unwrap();
+        drop(storage);
+        assert!(open_storage().payload(100).unwrap().is_empty());
+    }
 }
@@ -1,21 +1,26 @@
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
 use atomic_refcell::AtomicRefCell;
-use common::types::{PointOffsetType, ScoredPointOffset};
+use common::fixed_length_priority_queue::FixedLengthPriorityQueue;
+use common::types::{PointOffsetType, ScoreType, ScoredPointOffset};
 use io::file_operations::{atomic_save_json, read_json};
+use itertools::Itertools;
 use memory::mmap_ops;
 use parking_lot::{Mutex, RwLock};
+use rand::Rng as _;
 use rocksdb::DB;
 use sparse::common::sparse_vector::SparseVector;
 use tar::Builder;
 use uuid::Uuid;
 
+use crate::common::kv_store::KV_STORE_PATH;
 use crate::common::operation_error::OperationError::TypeInferenceError;
 use crate::common::operation_error::{
     get_service_error, OperationError, OperationResult, SegmentFailedState,
@@ -25,18 +30,20 @@ use crate::common::{
     check_named_vectors, check_query_vectors, check_stopped, check_vector, check_vector_name,
 };
 use crate::data_types::named_vectors::NamedVectors;
-use crate::data_types::vectors::{QueryVector, Vector};
+use crate::data_types::order_by::{Direction, OrderBy, OrderValue};
+use crate::data_types::vectors::{QueryVector, Vector, VectorType};
 use crate::entry::entry_point::SegmentEntry;
 use crate::id_tracker::IdTrackerSS;
-use crate::index::field_index::CardinalityEstimation;
+use crate::index::field_index::{CardinalityEstimation, FieldIndex};
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::{PayloadIndex, VectorIndex, VectorIndexEnum};
 use crate::spaces::tools::peek_top_smallest_iterable;
 use crate::telemetry::SegmentTelemetry;
 use crate::types::{
-    Filter, Payload, PayloadFieldSchema, PayloadIndexInfo, PayloadKeyType, PayloadKeyTypeRef,
-    PayloadSchemaType, PointIdType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo,
-    SegmentState, SegmentType, SeqNumberType, VectorDataInfo, WithPayload, WithVector,
+    Condition, FieldCondition, Filter, Payload, PayloadContainer, PayloadFieldSchema,
+    PayloadFieldStatistics, PayloadIndexInfo, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
+    PointIdType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentState, SegmentType,
+    SeqNumberType, VectorDataInfo, WithPayload, WithVector,
 };
 use crate::utils;
 use crate::utils::fs::find_symlink;
@@ -719,6 +726,49 @@ impl Segment {
         self.id_tracker.borrow().total_point_count()
     }
 
+    /// Create empty indexes for the `key` field, which are not yet attached to the segment
+    ///
+    /// Together with [`Segment::index_field_points`] allows building an index in chunks,
+    /// only requiring read access to the segment. An existing index of the field with the same
+    /// value type keeps being used until the new one is attached.
+    pub fn init_field_index(
+        &mut self,
+        key: PayloadKeyTypeRef,
+        field_schema: &PayloadFieldSchema,
+    ) -> OperationResult<Vec<FieldIndex>> {
+        self.payload_index
+            .borrow_mut()
+            .init_field_indexes(key, field_schema.clone())
+    }
+
+    /// Add payload values of the `points` to indexes, created with [`Segment::init_field_index`]
+    pub fn index_field_points(
+        &self,
+        key: PayloadKeyTypeRef,
+        field_indexes: &mut [FieldIndex],
+        points: Range<PointOffsetType>,
+    ) -> OperationResult<()> {
+        self.payload_index
+            .borrow()
+            .index_points(key, field_indexes, points)
+    }
+
+    /// Attach indexes, previously built with [`Segment::index_field_points`]
+    ///
+    /// Does not change the segment version, as the index does not change the data.
+    /// Returns `false` if the field got indexed in the meantime and built indexes were discarded.
+    /// Replaces the current indexes of the field, if it was initialized for a rebuild.
+    pub fn apply_field_index(
+        &mut self,
+        key: PayloadKeyTypeRef,
+        field_schema: PayloadFieldSchema,
+        field_indexes: Vec<FieldIndex>,
+    ) -> OperationResult<bool> {
+        self.payload_index
+            .borrow_mut()
+            .apply_field_indexes(key, field_schema, field_indexes)
+    }
+
     pub fn prefault_mmap_pages(&self) {
         let tasks: Vec<_> = self
             .vector_data
@@ -733,6 +783,61 @@ impl Segment {
             ))
             .spawn(move || tasks.iter().for_each(mmap_ops::PrefaultMmapPages::exec));
     }
+
+    /// Verify checksums of on-disk vectors, to detect data corruption
+    pub fn verify_checksums(&self) -> OperationResult<()> {
+        for (vector_name, vector_data) in &self.vector_data {
+            vector_data
+                .vector_storage
+                .borrow()
+                .verify_checksums()
+                .map_err(|err| {
+                    OperationError::service_error(format!(
+                        "Segment {} vector {vector_name}: {err}",
+                        self.current_path.display(),
+                    ))
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Verify on-disk data and invariants of the segment, to detect data corruption
+    ///
+    /// Checks vector checksums, that point id mappings are consistent in both directions, and
+    /// that every point has a place in each vector storage.
+    pub fn check_integrity(&self) -> OperationResult<()> {
+        self.verify_checksums()?;
+
+        let id_tracker = self.id_tracker.borrow();
+        let inconsistency = |description: String| OperationError::InconsistentStorage {
+            description: format!("Segment {}: {description}", self.current_path.display()),
+        };
+
+        for internal_id in id_tracker.iter_ids() {
+            let Some(external_id) = id_tracker.external_id(internal_id) else {
+                return Err(inconsistency(format!(
+                    "internal id {internal_id} has no external id"
+                )));
+            };
+            if id_tracker.internal_id(external_id) != Some(internal_id) {
+                return Err(inconsistency(format!(
+                    "point {external_id} is not mapped back to internal id {internal_id}"
+                )));
+            }
+        }
+
+        let total_point_count = id_tracker.total_point_count();
+        for (vector_name, vector_data) in &self.vector_data {
+            let total_vector_count = vector_data.vector_storage.borrow().total_vector_count();
+            if total_vector_count < total_point_count {
+                return Err(inconsistency(format!(
+                    "vector {vector_name} has {total_vector_count} entries \
+                     for {total_point_count} points"
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// This is a basic implementation of `SegmentEntry`,
@@ -1078,6 +1183,180 @@ impl SegmentEntry for Segment {
         }
     }
 
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>> {
+        let payload_index = self.payload_index.borrow();
+        let id_tracker = self.id_tracker.borrow();
+
+        let range = order_by.value_range();
+        let values_stream = payload_index
+            .field_indexes
+            .get(&order_by.key)
+            .and_then(|indexes| indexes.iter().find_map(|index| index.stream_range(&range)))
+            .ok_or_else(|| OperationError::ValidationError {
+                description: format!(
+                    "There is no range index for the `order_by` key: `{}`. Please create one to use `order_by`.",
+                    order_by.key
+                ),
+            })?;
+
+        let values_stream: Box<dyn Iterator<Item = _>> = match order_by.direction() {
+            Direction::Asc => Box::new(values_stream),
+            Direction::Desc => Box::new(values_stream.rev()),
+        };
+
+        let filter_context = filter.map(|filter| payload_index.filter_context(filter));
+        let mut visited = HashSet::new();
+
+        let page = values_stream
+            .filter(|(_, internal_id)| {
+                filter_context
+                    .as_ref()
+                    .map_or(true, |filter_context| filter_context.check(*internal_id))
+            })
+            // Points with multiple values should only be returned once
+            .filter(|(_, internal_id)| visited.insert(*internal_id))
+            .filter_map(|(value, internal_id)| {
+                id_tracker
+                    .external_id(internal_id)
+                    .map(|external_id| (value, external_id))
+            })
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+
+        Ok(page)
+    }
+
+    fn read_random_filtered<'a>(
+        &'a self,
+        limit: usize,
+        filter: Option<&'a Filter>,
+    ) -> Vec<(ScoreType, PointIdType)> {
+        if limit == 0 {
+            return vec![];
+        }
+
+        let payload_index = self.payload_index.borrow();
+        let id_tracker = self.id_tracker.borrow();
+
+        let internal_ids: Box<dyn Iterator<Item = PointOffsetType>> = match filter {
+            None => Box::new(
+                id_tracker
+                    .iter_from(None)
+                    .map(|(_, internal_id)| internal_id),
+            ),
+            Some(filter) => Box::new(payload_index.query_points(filter).into_iter()),
+        };
+
+        // Reservoir sampling: assign a random key to every point and keep the largest ones
+        let mut rng = rand::thread_rng();
+        let mut reservoir = FixedLengthPriorityQueue::new(limit);
+        for internal_id in internal_ids {
+            reservoir.push(ScoredPointOffset {
+                idx: internal_id,
+                score: rng.gen(),
+            });
+        }
+
+        reservoir
+            .into_vec()
+            .into_iter()
+            .filter_map(|sample| {
+                id_tracker
+                    .external_id(sample.idx)
+                    .map(|external_id| (sample.score, external_id))
+            })
+            .collect()
+    }
+
+    fn facet<'a>(
+        &'a self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&'a Filter>,
+        exact: bool,
+    ) -> OperationResult<HashMap<String, usize>> {
+        let payload_index = self.payload_index.borrow();
+        let id_tracker = self.id_tracker.borrow();
+
+        let keyword_index = payload_index.field_indexes.get(key).and_then(|indexes| {
+            indexes.iter().find_map(|index| match index {
+                FieldIndex::KeywordIndex(index) => Some(index),
+                _ => None,
+            })
+        });
+
+        let internal_ids = || -> Box<dyn Iterator<Item = PointOffsetType>> {
+            match filter {
+                None => Box::new(
+                    id_tracker
+                        .iter_from(None)
+                        .map(|(_, internal_id)| internal_id),
+                ),
+                Some(filter) => Box::new(payload_index.query_points(filter).into_iter()),
+            }
+        };
+
+        let mut counts = HashMap::new();
+
+        let Some(keyword_index) = keyword_index else {
+            // Segment is not indexed yet (e.g. a temporary segment of the optimizer),
+            // read values from the payload storage instead
+            for internal_id in internal_ids() {
+                let payload = payload_index.payload(internal_id)?;
+                let values: HashSet<_> = payload
+                    .get_value(key)
+                    .values()
+                    .into_iter()
+                    .flat_map(|value| match value {
+                        serde_json::Value::Array(values) => values.iter().collect(),
+                        value => vec![value],
+                    })
+                    .filter_map(|value| value.as_str())
+                    .collect();
+                for value in values {
+                    *counts.entry(value.to_string()).or_insert(0) += 1;
+                }
+            }
+            return Ok(counts);
+        };
+
+        if exact {
+            for internal_id in internal_ids() {
+                let values: HashSet<_> = keyword_index
+                    .get_values(internal_id)
+                    .unwrap_or_default()
+                    .iter()
+                    .collect();
+                for value in values {
+                    *counts.entry(value.to_string()).or_insert(0) += 1;
+                }
+            }
+            return Ok(counts);
+        }
+
+        for (value, count) in keyword_index.iter_counts_per_value() {
+            let count = match filter {
+                None => count,
+                Some(filter) => {
+                    let value_filter = Filter::new_must(Condition::Field(
+                        FieldCondition::new_match(key, value.to_string().into()),
+                    ));
+                    payload_index
+                        .estimate_cardinality(&filter.merge(&value_filter))
+                        .exp
+                }
+            };
+            if count > 0 {
+                counts.insert(value.to_string(), count);
+            }
+        }
+        Ok(counts)
+    }
+
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType> {
         let id_tracker = self.id_tracker.borrow();
         let iterator = id_tracker.iter_from(from).map(|x| x.0);
@@ -1091,6 +1370,20 @@ impl SegmentEntry for Segment {
         self.id_tracker.borrow().internal_id(point_id).is_some()
     }
 
+    fn select_points_in_storage_order(&self, point_ids: &[PointIdType]) -> Vec<PointIdType> {
+        let id_tracker = self.id_tracker.borrow();
+        point_ids
+            .iter()
+            .filter_map(|&point_id| {
+                id_tracker
+                    .internal_id(point_id)
+                    .map(|internal_id| (internal_id, point_id))
+            })
+            .sorted_unstable_by_key(|(internal_id, _)| *internal_id)
+            .map(|(_, point_id)| point_id)
+            .collect()
+    }
+
     fn available_point_count(&self) -> usize {
         self.id_tracker.borrow().available_point_count()
     }
@@ -1373,6 +1666,42 @@ impl SegmentEntry for Segment {
         self.payload_index.borrow().indexed_fields()
     }
 
+    fn get_field_statistics(&self) -> HashMap<PayloadKeyType, PayloadFieldStatistics> {
+        self.payload_index.borrow().get_field_statistics()
+    }
+
+    fn get_vector_storage_size_bytes(&self) -> HashMap<String, usize> {
+        self.vector_data
+            .iter()
+            .map(|(vector_name, vector_data)| {
+                let mut files = vector_data.vector_storage.borrow().files();
+                files.extend(vector_data.vector_index.borrow().files());
+                if let Some(quantized_vectors) = &vector_data.quantized_vectors {
+                    files.extend(quantized_vectors.borrow().files());
+                }
+                // Files may be replaced concurrently, so missing files are not counted
+                let size_bytes = files
+                    .iter()
+                    .filter_map(|file| fs::metadata(file).ok())
+                    .map(|metadata| metadata.len() as usize)
+                    .sum();
+                (vector_name.clone(), size_bytes)
+            })
+            .collect()
+    }
+
+    fn get_quantization_drift(&self, vector_name: &str, vectors: &[VectorType]) -> Option<f32> {
+        let quantized_vectors = self
+            .vector_data
+            .get(vector_name)?
+            .quantized_vectors
+            .as_ref()?;
+        let quantized_vectors = quantized_vectors.borrow();
+        quantized_vectors
+            .value_range()?
+            .out_of_range_share(vectors.iter().map(|vector| vector.as_slice()))
+    }
+
     fn check_error(&self) -> Option<SegmentFailedState> {
         self.error_status.clone()
     }
@@ -1513,6 +1842,26 @@ impl SegmentEntry for Segment {
             )?;
         }
 
+        // Key-value stores, which are not backed by RocksDB
+        let kv_store_path = self.current_path.join(KV_STORE_PATH);
+        if kv_store_path.is_dir() {
+            for entry in fs::read_dir(&kv_store_path)? {
+                let file = entry?.path();
+                if file
+                    .extension()
+                    .map_or(true, |extension| extension != "log")
+                {
+                    continue;
+                }
+                utils::tar::append_file_relative_to_base(
+                    &mut builder,
+                    &self.current_path,
+                    &file,
+                    &files,
+                )?;
+            }
+        }
+
         utils::tar::append_file(
             &mut builder,
             &self.current_path.join(SEGMENT_STATE_FILE),
@@ -1570,13 +1919,16 @@ impl Drop for Segment {
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
     use tempfile::Builder;
 
     use super::*;
     use crate::common::operation_error::OperationError::PointIdError;
     use crate::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
     use crate::segment_constructor::{build_segment, load_segment};
-    use crate::types::{Distance, Indexes, SegmentConfig, VectorDataConfig, VectorStorageType};
+    use crate::types::{
+        Distance, Indexes, KvStoreType, SegmentConfig, VectorDataConfig, VectorStorageType,
+    };
 
     // no longer valid since users are now allowed to store arbitrary json objects.
     // TODO(gvelo): add tests for invalid payload types on indexed fields.
@@ -1629,6 +1981,9 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1702,6 +2057,9 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         };
 
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -1768,8 +2126,10 @@ mod tests {
         assert!(results_with_invalid_filter.is_empty());
     }
 
-    #[test]
-    fn test_snapshot() {
+    #[rstest]
+    #[case::rocksdb(None)]
+    #[case::append_log(Some(KvStoreType::AppendLog))]
+    fn test_snapshot(#[case] kv_store: Option<KvStoreType>) {
         let data = r#"
         {
             "name": "John Doe",
@@ -1794,6 +2154,9 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store,
         };
 
         let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
@@ -1886,6 +2249,9 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         };
 
         let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
@@ -1918,6 +2284,9 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2013,6 +2382,9 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2048,6 +2420,73 @@ mod tests {
         // assert_eq!(segment_info.num_vectors, 1);
     }
 
+    #[test]
+    fn test_facet() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let config = SegmentConfig {
+            vector_data: HashMap::from([(
+                DEFAULT_VECTOR_NAME.to_owned(),
+                VectorDataConfig {
+                    size: 1,
+                    distance: Distance::Dot,
+                    storage_type: VectorStorageType::Memory,
+                    index: Indexes::Plain {},
+                    quantization_config: None,
+                },
+            )]),
+            sparse_vector_data: Default::default(),
+            payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
+        };
+        let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+        let payloads = [
+            serde_json::json!({"color": "red"}),
+            serde_json::json!({"color": ["red", "green"]}),
+            serde_json::json!({"color": ["green", "green"]}),
+            serde_json::json!({"color": 42}),
+            serde_json::json!({}),
+        ];
+        for (idx, payload) in payloads.into_iter().enumerate() {
+            let op_num = idx as SeqNumberType;
+            let point_id = (idx as u64).into();
+            segment
+                .upsert_point(op_num, point_id, only_default_vector(&[1.0]))
+                .unwrap();
+            segment
+                .set_payload(op_num, point_id, &payload.into())
+                .unwrap();
+        }
+        segment.delete_point(10, 0.into()).unwrap();
+
+        let expected = HashMap::from([("red".to_string(), 1), ("green".to_string(), 2)]);
+        let red_filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "color",
+            "red".to_string().into(),
+        )));
+        let expected_red = HashMap::from([("red".to_string(), 1), ("green".to_string(), 1)]);
+
+        // Not indexed, values are read from payload
+        assert_eq!(segment.facet("color", None, true).unwrap(), expected);
+        assert_eq!(
+            segment.facet("color", Some(&red_filter), true).unwrap(),
+            expected_red,
+        );
+
+        segment
+            .create_field_index(11, "color", Some(&PayloadSchemaType::Keyword.into()))
+            .unwrap();
+
+        assert_eq!(segment.facet("color", None, true).unwrap(), expected);
+        assert_eq!(segment.facet("color", None, false).unwrap(), expected);
+        assert_eq!(
+            segment.facet("color", Some(&red_filter), true).unwrap(),
+            expected_red,
+        );
+    }
+
     #[test]
     fn test_point_vector_count_multivec() {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -2077,6 +2516,9 @@ mod tests {
             ]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2183,6 +2625,9 @@ mod tests {
             ]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
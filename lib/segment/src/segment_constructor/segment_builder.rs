@@ -3,15 +3,25 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 
+use common::types::PointOffsetType;
+use itertools::Itertools;
+use serde_json::Value;
+
 use super::get_vector_storage_path;
 use crate::common::error_logging::LogError;
 use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
 use crate::entry::entry_point::SegmentEntry;
 use crate::index::hnsw_index::max_rayon_threads;
 use crate::index::{PayloadIndex, VectorIndex};
+use crate::payload_storage::payload_codec::compression_sample;
+use crate::payload_storage::payload_dictionary::{
+    PayloadDictionary, DICTIONARY_SAMPLE_COUNT, PAYLOAD_DICTIONARY_FILE,
+};
 use crate::segment::Segment;
 use crate::segment_constructor::{build_segment, load_segment};
-use crate::types::{Indexes, PayloadFieldSchema, PayloadKeyType, SegmentConfig};
+use crate::types::{
+    Indexes, Payload, PayloadContainer, PayloadFieldSchema, PayloadKeyType, SegmentConfig,
+};
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
 use crate::vector_storage::VectorStorage;
 
@@ -54,6 +64,149 @@ impl SegmentBuilder {
     /// * `bool` - if `true` - data successfully added, if `false` - process was interrupted
     ///
     pub fn update_from(&mut self, other: &Segment, stopped: &AtomicBool) -> OperationResult<bool> {
+        self.update(&[other], stopped)
+    }
+
+    /// Update current segment builder with all (not deleted) vectors and payload from `segments`
+    ///
+    /// If any of the indexed fields is a tenant field, points are added in the order of their
+    /// tenants, so points of the same tenant are stored close to each other.
+    ///
+    /// If payload compression with a dictionary is configured, the dictionary is trained on
+    /// payloads of the first update, before any payload is written.
+    ///
+    /// # Result
+    ///
+    /// * `bool` - if `true` - data successfully added, if `false` - process was interrupted
+    ///
+    pub fn update(&mut self, segments: &[&Segment], stopped: &AtomicBool) -> OperationResult<bool> {
+        for segment in segments {
+            for (field, payload_schema) in segment.payload_index.borrow().indexed_fields() {
+                self.indexed_fields.insert(field, payload_schema);
+            }
+        }
+
+        let tenant_fields: Vec<_> = self
+            .indexed_fields
+            .iter()
+            .filter(|(_, payload_schema)| payload_schema.is_tenant())
+            .map(|(field, _)| field.clone())
+            .sorted()
+            .collect();
+
+        let mut points: Vec<(usize, PointOffsetType)> = segments
+            .iter()
+            .enumerate()
+            .flat_map(|(segment_idx, segment)| {
+                let id_tracker = segment.id_tracker.borrow();
+                id_tracker
+                    .iter_ids()
+                    .map(|internal_id| (segment_idx, internal_id))
+                    .collect_vec()
+            })
+            .collect();
+
+        if !tenant_fields.is_empty() {
+            let mut keyed_points = Vec::with_capacity(points.len());
+            for (segment_idx, internal_id) in points {
+                check_process_stopped(stopped)?;
+                let payload = segments[segment_idx]
+                    .payload_index
+                    .borrow()
+                    .payload(internal_id)?;
+                let tenants: Vec<_> = tenant_fields
+                    .iter()
+                    .map(|field| Self::tenant(&payload, field))
+                    .collect();
+                keyed_points.push((tenants, (segment_idx, internal_id)));
+            }
+            // Stable sort keeps the original order of points within a tenant
+            keyed_points.sort_by(|(a, _), (b, _)| a.cmp(b));
+            points = keyed_points.into_iter().map(|(_, point)| point).collect();
+        }
+
+        self.train_payload_dictionary(segments, &points, stopped)?;
+
+        let runs = points.into_iter().group_by(|(segment_idx, _)| *segment_idx);
+        for (segment_idx, run) in &runs {
+            let internal_ids: Vec<_> = run.map(|(_, internal_id)| internal_id).collect();
+            self.update_from_ids(segments[segment_idx], &internal_ids, stopped)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Train payload compression dictionary on evenly spaced samples of `points`, if configured
+    ///
+    /// Failure to train a dictionary, e.g. if there are too few samples, is not an error:
+    /// payload is compressed without a dictionary in this case.
+    fn train_payload_dictionary(
+        &mut self,
+        segments: &[&Segment],
+        points: &[(usize, PointOffsetType)],
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        let Some(segment) = &self.segment else {
+            return Ok(());
+        };
+        let Some(compression) = segment.segment_config.payload_compression.clone() else {
+            return Ok(());
+        };
+        if !compression.use_dictionary()
+            || points.is_empty()
+            || self.temp_path.join(PAYLOAD_DICTIONARY_FILE).exists()
+        {
+            return Ok(());
+        }
+
+        let step = points.len().div_ceil(DICTIONARY_SAMPLE_COUNT);
+        let mut samples = Vec::with_capacity(points.len() / step + 1);
+        for &(segment_idx, internal_id) in points.iter().step_by(step) {
+            check_process_stopped(stopped)?;
+            let payload = segments[segment_idx]
+                .payload_index
+                .borrow()
+                .payload(internal_id)?;
+            if let Some(sample) = compression_sample(&payload, &compression)? {
+                samples.push(sample);
+            }
+        }
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        match PayloadDictionary::train(&self.temp_path, &samples, compression.level()) {
+            Ok(dictionary) => segment
+                .payload_index
+                .borrow_mut()
+                .set_payload_dictionary(dictionary),
+            Err(err) => log::warn!("Compressing payload without a dictionary: {err}"),
+        }
+        Ok(())
+    }
+
+    /// First tenant of the point, used to group points of the same tenant
+    fn tenant(payload: &Payload, field: &str) -> Option<String> {
+        payload
+            .get_value(field)
+            .values()
+            .into_iter()
+            .find_map(|value| match value {
+                Value::String(tenant) => Some(tenant.clone()),
+                Value::Array(values) => values
+                    .iter()
+                    .find_map(|value| value.as_str().map(ToOwned::to_owned)),
+                _ => None,
+            })
+    }
+
+    /// Add points with given internal ids of `other` segment, in the order of `other_ids`
+    fn update_from_ids(
+        &mut self,
+        other: &Segment,
+        other_ids: &[PointOffsetType],
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
         let self_segment = match &mut self.segment {
             Some(segment) => segment,
             None => {
@@ -103,7 +256,7 @@ impl SegmentBuilder {
             })?;
             let internal_range = vector_storage.update_from(
                 other_vector_storage,
-                &mut other_id_tracker.iter_ids(),
+                &mut other_ids.iter().copied(),
                 stopped,
             )?;
             match new_internal_range.clone() {
@@ -119,7 +272,7 @@ impl SegmentBuilder {
         }
 
         if let Some(new_internal_range) = new_internal_range {
-            let internal_id_iter = new_internal_range.zip(other_id_tracker.iter_ids());
+            let internal_id_iter = new_internal_range.zip(other_ids.iter().copied());
 
             for (new_internal_id, old_internal_id) in internal_id_iter {
                 check_process_stopped(stopped)?;
@@ -174,11 +327,7 @@ impl SegmentBuilder {
             }
         }
 
-        for (field, payload_schema) in other.payload_index.borrow().indexed_fields() {
-            self.indexed_fields.insert(field, payload_schema);
-        }
-
-        Ok(true)
+        Ok(())
     }
 
     pub fn build(mut self, stopped: &AtomicBool) -> Result<Segment, OperationError> {
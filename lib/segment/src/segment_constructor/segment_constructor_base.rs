@@ -5,14 +5,17 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
-use log::info;
 use parking_lot::Mutex;
 use semver::Version;
 use serde::Deserialize;
 use uuid::Uuid;
 
+use crate::common::kv_store::open_kv_store;
+use crate::common::migration::{migrate_storage, StorageMigration};
 use crate::common::operation_error::{OperationError, OperationResult};
-use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+use crate::common::rocksdb_wrapper::{
+    open_db, DB_MAPPING_CF, DB_PAYLOAD_CF, DB_VECTOR_CF, DB_VERSIONS_CF,
+};
 use crate::common::version::StorageVersion;
 use crate::data_types::vectors::DEFAULT_VECTOR_NAME;
 use crate::id_tracker::simple_id_tracker::SimpleIdTracker;
@@ -24,18 +27,21 @@ use crate::index::sparse_index::sparse_index_config::SparseIndexType;
 use crate::index::sparse_index::sparse_vector_index::SparseVectorIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::VectorIndexEnum;
+use crate::payload_storage::blob_store::BlobStore;
+use crate::payload_storage::mmap_payload_storage::MmapPayloadStorage;
 use crate::payload_storage::on_disk_payload_storage::OnDiskPayloadStorage;
+use crate::payload_storage::payload_dictionary::PayloadDictionary;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
 use crate::segment::{Segment, SegmentVersion, VectorData, SEGMENT_STATE_FILE};
 use crate::types::{
-    Distance, Indexes, PayloadStorageType, SegmentConfig, SegmentState, SegmentType, SeqNumberType,
-    VectorStorageType,
+    Distance, Indexes, PayloadCompressionConfig, PayloadStorageType, SegmentConfig, SegmentState,
+    SegmentType, SeqNumberType, VectorStorageType,
 };
 use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
 use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage;
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
-use crate::vector_storage::simple_sparse_vector_storage::open_simple_sparse_vector_storage;
-use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
+use crate::vector_storage::simple_sparse_vector_storage::open_simple_sparse_vector_storage_with_store;
+use crate::vector_storage::simple_vector_storage::open_simple_vector_storage_with_store;
 use crate::vector_storage::VectorStorage;
 
 pub const PAYLOAD_INDEX_PATH: &str = "payload_index";
@@ -84,12 +90,57 @@ fn create_segment(
     let database = open_db(segment_path, &vector_db_names)
         .map_err(|err| OperationError::service_error(format!("RocksDB open error: {err}")))?;
 
+    let kv_store_type = config.kv_store.unwrap_or_default();
+    let open_store = |column_name: &str| {
+        open_kv_store(kv_store_type, database.clone(), segment_path, column_name)
+    };
+
+    let payload_blobs = config
+        .payload_blobs
+        .as_ref()
+        .map(|blobs_config| BlobStore::open(segment_path, blobs_config.fields.clone()))
+        .transpose()?;
+
+    // Dictionary is loaded regardless of the config, records compressed with it can't be read
+    // without it
+    let payload_dictionary = PayloadDictionary::load(
+        segment_path,
+        config
+            .payload_compression
+            .as_ref()
+            .map_or(PayloadCompressionConfig::DEFAULT_LEVEL, |compression| {
+                compression.level()
+            }),
+    )?;
+
     let payload_storage = match config.payload_storage_type {
-        PayloadStorageType::InMemory => sp(SimplePayloadStorage::open(database.clone())?.into()),
-        PayloadStorageType::OnDisk => sp(OnDiskPayloadStorage::open(database.clone())?.into()),
+        PayloadStorageType::InMemory => sp(SimplePayloadStorage::open_with_options(
+            open_store(DB_PAYLOAD_CF)?,
+            config.payload_compression.clone(),
+            payload_dictionary,
+            payload_blobs,
+        )?
+        .into()),
+        PayloadStorageType::OnDisk => sp(OnDiskPayloadStorage::open_with_options(
+            open_store(DB_PAYLOAD_CF)?,
+            config.payload_compression.clone(),
+            payload_dictionary,
+            payload_blobs,
+        )?
+        .into()),
+        PayloadStorageType::Mmap => sp(MmapPayloadStorage::open_with_options(
+            segment_path,
+            config.payload_compression.clone(),
+            payload_dictionary,
+            payload_blobs,
+        )?
+        .into()),
     };
 
-    let id_tracker = sp(SimpleIdTracker::open(database.clone())?);
+    let id_tracker = sp(SimpleIdTracker::open_with_stores(
+        open_store(DB_MAPPING_CF)?,
+        open_store(DB_VERSIONS_CF)?,
+    )?);
 
     let appendable_flag = config
         .vector_data
@@ -121,9 +172,8 @@ fn create_segment(
             // In memory
             VectorStorageType::Memory => {
                 let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
-                open_simple_vector_storage(
-                    database.clone(),
-                    &db_column_name,
+                open_simple_vector_storage_with_store(
+                    open_store(&db_column_name)?,
                     vector_config.size,
                     vector_config.distance,
                 )?
@@ -207,7 +257,8 @@ fn create_segment(
         let vector_index_path = get_vector_index_path(segment_path, vector_name);
 
         let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
-        let vector_storage = open_simple_sparse_vector_storage(database.clone(), &db_column_name)?;
+        let vector_storage =
+            open_simple_sparse_vector_storage_with_store(open_store(&db_column_name)?)?;
 
         // Warn when number of points between ID tracker and storage differs
         let point_count = id_tracker.borrow().total_point_count();
@@ -220,22 +271,26 @@ fn create_segment(
         }
 
         let vector_index = match sparse_vector_config.index.index_type {
-            SparseIndexType::Mmap => sp(VectorIndexEnum::SparseMmap(SparseVectorIndex::open(
-                sparse_vector_config.index,
-                id_tracker.clone(),
-                vector_storage.clone(),
-                payload_index.clone(),
-                &vector_index_path,
-            )?)),
-            SparseIndexType::MutableRam | SparseIndexType::ImmutableRam => {
-                sp(VectorIndexEnum::SparseRam(SparseVectorIndex::open(
+            SparseIndexType::Mmap => sp(VectorIndexEnum::SparseMmap(
+                SparseVectorIndex::open_partitioned(
                     sparse_vector_config.index,
+                    sparse_vector_config.partition_by.clone(),
                     id_tracker.clone(),
                     vector_storage.clone(),
                     payload_index.clone(),
                     &vector_index_path,
-                )?))
-            }
+                )?,
+            )),
+            SparseIndexType::MutableRam | SparseIndexType::ImmutableRam => sp(
+                VectorIndexEnum::SparseRam(SparseVectorIndex::open_partitioned(
+                    sparse_vector_config.index,
+                    sparse_vector_config.partition_by.clone(),
+                    id_tracker.clone(),
+                    vector_storage.clone(),
+                    payload_index.clone(),
+                    &vector_index_path,
+                )?),
+            ),
         };
 
         vector_data.insert(
@@ -292,35 +347,7 @@ pub fn load_segment(path: &Path) -> OperationResult<Option<Segment>> {
         return Ok(None);
     }
 
-    let stored_version: Version = SegmentVersion::load(path)?.parse()?;
-    let app_version: Version = SegmentVersion::current().parse()?;
-
-    if stored_version != app_version {
-        info!("Migrating segment {} -> {}", stored_version, app_version,);
-
-        if stored_version > app_version {
-            return Err(OperationError::service_error(format!(
-                "Data version {stored_version} is newer than application version {app_version}. \
-                Please upgrade the application. Compatibility is not guaranteed."
-            )));
-        }
-
-        if stored_version.major == 0 && stored_version.minor < 3 {
-            return Err(OperationError::service_error(format!(
-                "Segment version({stored_version}) is not compatible with current version({app_version})"
-            )));
-        }
-
-        if stored_version.major == 0 && stored_version.minor == 3 {
-            let segment_state = load_segment_state_v3(path)?;
-            Segment::save_state(&segment_state, path)?;
-        } else if stored_version.major == 0 && stored_version.minor <= 5 {
-            let segment_state = load_segment_state_v5(path)?;
-            Segment::save_state(&segment_state, path)?;
-        }
-
-        SegmentVersion::save(path)?
-    }
+    migrate_storage::<SegmentVersion>(path, &MIN_SEGMENT_VERSION, &SEGMENT_MIGRATIONS)?;
 
     let segment_state = Segment::load_state(path)?;
 
@@ -358,6 +385,25 @@ pub fn build_segment(path: &Path, config: &SegmentConfig, ready: bool) -> Operat
     Ok(segment)
 }
 
+/// Oldest segment version, which can be loaded
+const MIN_SEGMENT_VERSION: Version = Version::new(0, 3, 0);
+
+/// Upgrades of segment storage, each converts the state of older segments into the current format
+const SEGMENT_MIGRATIONS: [StorageMigration; 2] = [
+    StorageMigration {
+        since: Version::new(0, 3, 0),
+        before: Version::new(0, 4, 0),
+        files: &[SEGMENT_STATE_FILE],
+        migrate: |path| Segment::save_state(&load_segment_state_v3(path)?, path),
+    },
+    StorageMigration {
+        since: Version::new(0, 4, 0),
+        before: Version::new(0, 6, 0),
+        files: &[SEGMENT_STATE_FILE],
+        migrate: |path| Segment::save_state(&load_segment_state_v5(path)?, path),
+    },
+];
+
 /// Load v0.3.* segment data and migrate to current version
 #[allow(deprecated)]
 fn load_segment_state_v3(segment_path: &Path) -> OperationResult<SegmentState> {
@@ -33,6 +33,9 @@ pub fn build_simple_segment(
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         },
         true,
     )
@@ -72,6 +75,9 @@ pub fn build_multivec_segment(
             vector_data: vectors_config,
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         },
         true,
     )
@@ -70,6 +70,55 @@ pub struct VectorIndexSearchesTelemetry {
 
     #[serde(skip_serializing_if = "OperationDurationStatistics::is_empty")]
     pub unfiltered_exact: OperationDurationStatistics,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub sparse_statistics: Option<SparseIndexStatistics>,
+}
+
+/// Size of the posting lists of a sparse vector index
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+pub struct SparseIndexStatistics {
+    /// Number of dimensions with at least one indexed vector
+    pub dimensions_count: usize,
+    /// Total number of elements in all posting lists
+    pub postings_count: usize,
+    /// Memory occupied by the elements of posting lists, in RAM or mapped from disk
+    pub postings_size_bytes: usize,
+    /// Average number of elements in a non-empty posting list
+    pub posting_length_avg: f64,
+    /// Median number of elements in a non-empty posting list
+    pub posting_length_p50: usize,
+    /// 90th percentile of the number of elements in a non-empty posting list
+    pub posting_length_p90: usize,
+    /// 99th percentile of the number of elements in a non-empty posting list
+    pub posting_length_p99: usize,
+    /// Number of elements in the longest posting list
+    pub posting_length_max: usize,
+}
+
+impl SparseIndexStatistics {
+    /// Statistics of posting lists with the given lengths.
+    /// Lengths must be sorted in ascending order, empty posting lists are ignored.
+    pub fn from_sorted_posting_lengths(lengths: &[usize], element_size: usize) -> Self {
+        let lengths = &lengths[lengths.partition_point(|&length| length == 0)..];
+        let Some(&posting_length_max) = lengths.last() else {
+            return Self::default();
+        };
+        let percentile = |p: usize| lengths[(lengths.len() - 1) * p / 100];
+        let postings_count = lengths.iter().sum::<usize>();
+
+        Self {
+            dimensions_count: lengths.len(),
+            postings_count,
+            postings_size_bytes: postings_count * element_size,
+            posting_length_avg: postings_count as f64 / lengths.len() as f64,
+            posting_length_p50: percentile(50),
+            posting_length_p90: percentile(90),
+            posting_length_p99: percentile(99),
+            posting_length_max,
+        }
+    }
 }
 
 impl Anonymize for SegmentTelemetry {
@@ -116,6 +165,7 @@ impl Anonymize for PayloadIndexInfo {
             data_type: self.data_type,
             params: self.params.clone(),
             points: self.points.anonymize(),
+            points_pending: self.points_pending.map(|pending| pending.anonymize()),
         }
     }
 }
@@ -126,6 +176,9 @@ impl Anonymize for SegmentConfig {
             vector_data: self.vector_data.anonymize(),
             sparse_vector_data: self.sparse_vector_data.anonymize(),
             payload_storage_type: self.payload_storage_type,
+            payload_compression: self.payload_compression.clone(),
+            payload_blobs: self.payload_blobs.clone(),
+            kv_store: self.kv_store,
         }
     }
 }
@@ -146,6 +199,7 @@ impl Anonymize for SparseVectorDataConfig {
     fn anonymize(&self) -> Self {
         SparseVectorDataConfig {
             index: self.index.anonymize(),
+            partition_by: self.partition_by.anonymize(),
         }
     }
 }
@@ -163,6 +217,18 @@ impl Anonymize for VectorIndexSearchesTelemetry {
             filtered_exact: self.filtered_exact.anonymize(),
             filtered_sparse: self.filtered_sparse.anonymize(),
             unfiltered_exact: self.filtered_exact.anonymize(),
+            sparse_statistics: self.sparse_statistics.anonymize(),
+        }
+    }
+}
+
+impl Anonymize for SparseIndexStatistics {
+    fn anonymize(&self) -> Self {
+        SparseIndexStatistics {
+            dimensions_count: self.dimensions_count.anonymize(),
+            postings_count: self.postings_count.anonymize(),
+            postings_size_bytes: self.postings_size_bytes.anonymize(),
+            ..self.clone()
         }
     }
 }
@@ -25,6 +25,7 @@ use crate::common::utils::{
     check_exclude_pattern, check_include_pattern, filter_json_values, get_value_from_json_map,
     MultiValue,
 };
+use crate::data_types::index::KeywordIndexParams;
 use crate::data_types::text_index::TextIndexParams;
 use crate::data_types::vectors::{VectorElementType, VectorStruct, VectorType};
 use crate::index::sparse_index::sparse_index_config::{SparseIndexConfig, SparseIndexType};
@@ -239,6 +240,9 @@ pub struct PayloadIndexInfo {
     pub params: Option<PayloadSchemaParams>,
     /// Number of points indexed with this index
     pub points: usize,
+    /// Number of points, for which the index is still being built in background
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points_pending: Option<usize>,
 }
 
 impl PayloadIndexInfo {
@@ -248,18 +252,166 @@ impl PayloadIndexInfo {
                 data_type,
                 params: None,
                 points: points_count,
+                points_pending: None,
             },
             PayloadFieldSchema::FieldParams(schema_params) => match schema_params {
                 PayloadSchemaParams::Text(_) => PayloadIndexInfo {
                     data_type: PayloadSchemaType::Text,
                     params: Some(schema_params),
                     points: points_count,
+                    points_pending: None,
+                },
+                PayloadSchemaParams::Keyword(_) => PayloadIndexInfo {
+                    data_type: PayloadSchemaType::Keyword,
+                    params: Some(schema_params),
+                    points: points_count,
+                    points_pending: None,
                 },
             },
         }
     }
 }
 
+/// Statistics of values of an indexed payload field
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct PayloadFieldStatistics {
+    /// Number of points with at least one indexed value
+    pub points_count: usize,
+    /// Total number of indexed values
+    pub values_count: usize,
+    /// Number of distinct values, or distinct tokens for full-text fields.
+    /// Counted per segment, so it is an upper bound when aggregated over several segments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distinct_values_count: Option<usize>,
+    /// Smallest value of a numeric field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Largest value of a numeric field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// Approximate distribution of values of a numeric field, ordered by the lower bound.
+    /// Buckets of different segments may overlap.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<Vec<HistogramBucket>>,
+    /// Largest tenants of a tenant field, ordered by the number of points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenants: Option<Vec<TenantStatistics>>,
+    /// Total number of tokens in the documents of a full-text field, including repeated ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_count: Option<usize>,
+    /// Average number of tokens in a document of a full-text field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_document_length: Option<f64>,
+}
+
+/// Number of points of a single tenant
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct TenantStatistics {
+    pub tenant: String,
+    pub points_count: usize,
+}
+
+/// Max number of tenants, reported in the statistics of a tenant field
+pub const MAX_TENANT_STATISTICS: usize = 100;
+
+/// Number of values within an inclusive range
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct HistogramBucket {
+    pub from: f64,
+    pub to: f64,
+    pub count: usize,
+}
+
+impl PayloadFieldStatistics {
+    /// Fill in statistics, not provided by this index, from another index of the same field
+    pub fn complement(self, other: Self) -> Self {
+        Self {
+            points_count: self.points_count.max(other.points_count),
+            values_count: self.values_count.max(other.values_count),
+            distinct_values_count: self.distinct_values_count.or(other.distinct_values_count),
+            min: self.min.or(other.min),
+            max: self.max.or(other.max),
+            histogram: self.histogram.or(other.histogram),
+            tenants: self.tenants.or(other.tenants),
+            tokens_count: self.tokens_count.or(other.tokens_count),
+            avg_document_length: self.avg_document_length.or(other.avg_document_length),
+        }
+    }
+
+    /// Set the number of tokens of a full-text field, along with the average document length
+    pub fn set_tokens_count(&mut self, tokens_count: usize) {
+        self.tokens_count = Some(tokens_count);
+        self.avg_document_length = Some(if self.points_count > 0 {
+            tokens_count as f64 / self.points_count as f64
+        } else {
+            0.0
+        });
+    }
+
+    /// Combine statistics of the same field from different segments
+    pub fn merge(&mut self, other: Self) {
+        self.points_count += other.points_count;
+        self.values_count += other.values_count;
+        if let Some(count) = other.distinct_values_count {
+            *self.distinct_values_count.get_or_insert(0) += count;
+        }
+        self.min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.histogram = match (self.histogram.take(), other.histogram) {
+            (Some(mut a), Some(b)) => {
+                a.extend(b);
+                a.sort_by(|x, y| x.from.total_cmp(&y.from));
+                Some(a)
+            }
+            (a, b) => a.or(b),
+        };
+        self.tenants = match (self.tenants.take(), other.tenants) {
+            (Some(a), Some(b)) => {
+                let mut points_per_tenant: HashMap<String, usize> = HashMap::new();
+                for tenant in a.into_iter().chain(b) {
+                    *points_per_tenant.entry(tenant.tenant).or_default() += tenant.points_count;
+                }
+                Some(largest_tenants(points_per_tenant.into_iter()))
+            }
+            (a, b) => a.or(b),
+        };
+        match (self.tokens_count, other.tokens_count) {
+            (Some(a), Some(b)) => self.set_tokens_count(a + b),
+            (None, Some(b)) => self.set_tokens_count(b),
+            (Some(a), None) => self.set_tokens_count(a),
+            (None, None) => {}
+        }
+    }
+}
+
+/// Keep the [`MAX_TENANT_STATISTICS`] largest tenants, ordered by the number of points
+pub fn largest_tenants(
+    points_per_tenant: impl Iterator<Item = (String, usize)>,
+) -> Vec<TenantStatistics> {
+    let mut tenants: Vec<_> = points_per_tenant
+        .map(|(tenant, points_count)| TenantStatistics {
+            tenant,
+            points_count,
+        })
+        .collect();
+    tenants.sort_by(|a, b| {
+        b.points_count
+            .cmp(&a.points_count)
+            .then_with(|| a.tenant.cmp(&b.tenant))
+    });
+    tenants.truncate(MAX_TENANT_STATISTICS);
+    tenants
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub struct VectorDataInfo {
@@ -606,14 +758,92 @@ pub enum PayloadStorageType {
     InMemory,
     // Store payload on disk only, read each time it is requested
     OnDisk,
+    // Store payload in memory-mapped files, read each time it is requested
+    Mmap,
 }
 
 impl PayloadStorageType {
     pub fn is_on_disk(&self) -> bool {
-        matches!(self, PayloadStorageType::OnDisk)
+        matches!(self, PayloadStorageType::OnDisk | PayloadStorageType::Mmap)
     }
 }
 
+/// Storage backend of payload, which is not kept in memory
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDiskPayloadBackend {
+    /// Payload is stored in RocksDB
+    #[default]
+    Rocksdb,
+    /// Payload is stored in memory-mapped files. Records are aligned to pages and updated
+    /// in place, if they fit, which needs less memory than RocksDB under heavy updates.
+    Mmap,
+}
+
+impl OnDiskPayloadBackend {
+    pub fn payload_storage_type(&self) -> PayloadStorageType {
+        match self {
+            OnDiskPayloadBackend::Rocksdb => PayloadStorageType::OnDisk,
+            OnDiskPayloadBackend::Mmap => PayloadStorageType::Mmap,
+        }
+    }
+}
+
+/// Key-value storage backend of segment data, which is not stored in dedicated files,
+/// e.g. point ID mapping, in-memory payload and vectors
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum KvStoreType {
+    /// Data is stored in RocksDB
+    #[default]
+    Rocksdb,
+    /// Changes are appended to a log file per data type, which is rewritten once it mostly
+    /// consists of outdated records. Avoids RocksDB compaction stalls under heavy updates.
+    AppendLog,
+}
+
+/// Compression of selected payload fields in payload storage
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct PayloadCompressionConfig {
+    /// Top-level payload keys, which values are stored compressed.
+    /// Compressed values are decompressed on each read, so fields used in filters
+    /// should be indexed or left uncompressed.
+    pub fields: Vec<PayloadKeyType>,
+    /// Zstd compression level. Higher levels compress better, but slower.
+    /// Default: 3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 22))]
+    pub level: Option<i32>,
+    /// Train a compression dictionary on the payload of each segment, when it is optimized.
+    /// Improves compression of small values with a similar structure.
+    /// Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dictionary: Option<bool>,
+}
+
+impl PayloadCompressionConfig {
+    pub const DEFAULT_LEVEL: i32 = 3;
+
+    pub fn level(&self) -> i32 {
+        self.level.unwrap_or(Self::DEFAULT_LEVEL)
+    }
+
+    pub fn use_dictionary(&self) -> bool {
+        self.dictionary.unwrap_or(false)
+    }
+}
+
+/// Payload fields with large values, e.g. documents or images, stored in a separate blob file
+/// of the segment and read only when the payload is requested.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct PayloadBlobsConfig {
+    /// Top-level payload keys, which values are stored as blobs.
+    /// Blob fields are not available for filtering and can't be indexed.
+    pub fields: Vec<PayloadKeyType>,
+}
+
 #[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct SegmentConfig {
@@ -624,6 +854,15 @@ pub struct SegmentConfig {
     pub sparse_vector_data: HashMap<String, SparseVectorDataConfig>,
     /// Defines payload storage type
     pub payload_storage_type: PayloadStorageType,
+    /// Payload fields, stored compressed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_compression: Option<PayloadCompressionConfig>,
+    /// Payload fields, stored in a separate blob file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_blobs: Option<PayloadBlobsConfig>,
+    /// Key-value storage backend. Default: RocksDB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_store: Option<KvStoreType>,
 }
 
 impl SegmentConfig {
@@ -754,6 +993,10 @@ impl VectorDataConfig {
 pub struct SparseVectorDataConfig {
     /// Sparse inverted index config
     pub index: SparseIndexConfig,
+    /// Payload key to partition the inverted index by.
+    /// Queries, which require an exact match on this key, only scan postings of the matched partition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition_by: Option<PayloadKeyType>,
 }
 
 impl SparseVectorDataConfig {
@@ -858,14 +1101,29 @@ pub trait PayloadContainer {
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub struct Payload(pub Map<String, Value>);
 
+/// JSON values are not hashable, so only keys are hashed, which is consistent with `Eq`
+impl std::hash::Hash for Payload {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.len().hash(state);
+        for key in self.0.keys() {
+            key.hash(state);
+        }
+    }
+}
+
 impl Payload {
     pub fn merge(&mut self, value: &Payload) {
-        for (key, value) in &value.0 {
-            match value {
-                Value::Null => self.0.remove(key),
-                _ => self.0.insert(key.to_owned(), value.to_owned()),
-            };
-        }
+        merge_map(&mut self.0, &value.0);
+    }
+
+    /// Merge the payload into the object at a nested key, e.g. `metadata.reviews[0]`
+    pub fn merge_by_key(&mut self, value: &Payload, key: &str) {
+        utils::update_json_map_at_path(key, &mut self.0, &mut |map| merge_map(map, &value.0));
+    }
+
+    /// Replace the object at a nested key with the payload
+    pub fn overwrite_by_key(&mut self, value: &Payload, key: &str) {
+        utils::update_json_map_at_path(key, &mut self.0, &mut |map| map.clone_from(&value.0));
     }
 
     pub fn remove(&mut self, path: &str) -> Vec<Value> {
@@ -889,6 +1147,16 @@ impl Payload {
     }
 }
 
+/// Set values of the object, `null` values remove keys
+fn merge_map(map: &mut Map<String, Value>, values: &Map<String, Value>) {
+    for (key, value) in values {
+        match value {
+            Value::Null => map.remove(key),
+            _ => map.insert(key.to_owned(), value.to_owned()),
+        };
+    }
+}
+
 impl PayloadContainer for Map<String, Value> {
     fn get_value(&self, path: &str) -> MultiValue<&Value> {
         get_value_from_json_map(path, self)
@@ -1040,6 +1308,7 @@ pub enum PayloadSchemaType {
 #[serde(untagged, rename_all = "snake_case")]
 pub enum PayloadSchemaParams {
     Text(TextIndexParams),
+    Keyword(KeywordIndexParams),
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
@@ -1055,6 +1324,31 @@ impl From<PayloadSchemaType> for PayloadFieldSchema {
     }
 }
 
+impl PayloadFieldSchema {
+    /// Type of the indexed values, regardless of the index parameters
+    pub fn schema_type(&self) -> PayloadSchemaType {
+        match self {
+            PayloadFieldSchema::FieldType(schema_type) => *schema_type,
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(_)) => {
+                PayloadSchemaType::Text
+            }
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(_)) => {
+                PayloadSchemaType::Keyword
+            }
+        }
+    }
+
+    /// Whether the field identifies a tenant, and points should be grouped by its values
+    pub fn is_tenant(&self) -> bool {
+        match self {
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(params)) => {
+                params.is_tenant.unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+}
+
 impl TryFrom<PayloadIndexInfo> for PayloadFieldSchema {
     type Error = String;
 
@@ -1063,6 +1357,9 @@ impl TryFrom<PayloadIndexInfo> for PayloadFieldSchema {
             (PayloadSchemaType::Text, Some(PayloadSchemaParams::Text(params))) => Ok(
                 PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(params)),
             ),
+            (PayloadSchemaType::Keyword, Some(PayloadSchemaParams::Keyword(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(params)),
+            ),
             (data_type, Some(_)) => Err(format!(
                 "Payload field with type {data_type:?} has unexpected params"
             )),
@@ -1117,7 +1414,7 @@ where
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
 #[serde(untagged)]
 pub enum ValueVariants {
     Keyword(String),
@@ -1140,15 +1437,31 @@ pub struct MatchValue {
 }
 
 /// Full-text match of the strings.
+/// Text in double quotes matches as a phrase.
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub struct MatchText {
     pub text: String,
+    /// Expand the query with the query expansion config of the collection:
+    /// skip its stop words and also match synonyms of the words. Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expand: Option<bool>,
 }
 
 impl From<String> for MatchText {
     fn from(text: String) -> Self {
-        MatchText { text }
+        MatchText { text, expand: None }
+    }
+}
+
+impl MatchText {
+    /// Text of the phrase, if the whole text is wrapped in double quotes, e.g. `"exact phrase"`.
+    /// Phrase only matches if its words appear next to each other and in the same order.
+    pub fn phrase(&self) -> Option<&str> {
+        self.text
+            .trim()
+            .strip_prefix('"')
+            .and_then(|text| text.strip_suffix('"'))
     }
 }
 
@@ -1193,7 +1506,10 @@ impl Match {
 
     #[cfg(test)]
     fn new_text(text: &str) -> Self {
-        Self::Text(MatchText { text: text.into() })
+        Self::Text(MatchText {
+            text: text.into(),
+            expand: None,
+        })
     }
 
     pub fn new_any(any: AnyVariants) -> Self {
@@ -1215,7 +1531,10 @@ impl From<MatchInterface> for Match {
     fn from(value: MatchInterface) -> Self {
         match value {
             MatchInterface::Value(value) => Self::Value(MatchValue { value: value.value }),
-            MatchInterface::Text(text) => Self::Text(MatchText { text: text.text }),
+            MatchInterface::Text(text) => Self::Text(MatchText {
+                text: text.text,
+                expand: text.expand,
+            }),
             MatchInterface::Any(any) => Self::Any(MatchAny { any: any.any }),
             MatchInterface::Except(except) => Self::Except(MatchExcept {
                 except: except.except,
@@ -1500,12 +1819,17 @@ impl TryFrom<GeoPolygonShadow> for GeoPolygon {
     }
 }
 
+/// System field with the version of a point - sequential number of the last operation,
+/// which changed it. Can be used in field conditions instead of a payload key,
+/// e.g. to select points changed since a known version.
+pub const VERSION_FIELD: &str = "__version";
+
 /// All possible payload filtering conditions
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq)]
 #[validate(schema(function = "validate_field_condition"))]
 #[serde(rename_all = "snake_case")]
 pub struct FieldCondition {
-    /// Payload key
+    /// Payload key, or `__version` to check the version of the point
     pub key: PayloadKeyType,
     /// Check if point has field with a given value
     pub r#match: Option<Match>,
@@ -0,0 +1,162 @@
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use io::file_operations::{atomic_save_bin, read_bin};
+
+use crate::common::operation_error::OperationResult;
+
+/// Size of a block of data, covered by a single checksum
+pub const CHECKSUM_BLOCK_SIZE: usize = 64 * 1024;
+
+const BLOCK_UNVERIFIED: u8 = 0;
+const BLOCK_VALID: u8 = 1;
+const BLOCK_CORRUPTED: u8 = 2;
+
+/// CRC32 checksums of consecutive fixed-size blocks of a file
+///
+/// Blocks are verified lazily: each block is checked the first time it is read, and the result
+/// is remembered until the checksums are reloaded.
+pub struct BlockChecksums {
+    checksums: Vec<u32>,
+    states: Vec<AtomicU8>,
+}
+
+impl BlockChecksums {
+    /// Compute checksums of all blocks of `data`, last block may be incomplete
+    pub fn compute(data: &[u8]) -> Self {
+        Self::from_checksums(
+            data.chunks(CHECKSUM_BLOCK_SIZE)
+                .map(crc32fast::hash)
+                .collect(),
+        )
+    }
+
+    fn from_checksums(checksums: Vec<u32>) -> Self {
+        // Checksums were just computed or loaded, so blocks can't be assumed valid
+        let states = checksums
+            .iter()
+            .map(|_| AtomicU8::new(BLOCK_UNVERIFIED))
+            .collect();
+        Self { checksums, states }
+    }
+
+    /// Load checksums, returns `None` if they were never stored for this file
+    pub fn load(path: &Path) -> OperationResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let checksums: Vec<u32> = read_bin(path)?;
+        Ok(Some(Self::from_checksums(checksums)))
+    }
+
+    pub fn save(&self, path: &Path) -> OperationResult<()> {
+        Ok(atomic_save_bin(path, &self.checksums)?)
+    }
+
+    /// Update checksums after data was appended to the file
+    ///
+    /// Last block is recomputed, as it may have been incomplete before.
+    pub fn extend(self, data: &[u8]) -> Self {
+        let mut checksums = self.checksums;
+        checksums.truncate(checksums.len().saturating_sub(1));
+        let start = (checksums.len() * CHECKSUM_BLOCK_SIZE).min(data.len());
+        checksums.extend(
+            data[start..]
+                .chunks(CHECKSUM_BLOCK_SIZE)
+                .map(crc32fast::hash),
+        );
+        Self::from_checksums(checksums)
+    }
+
+    /// Check if `data` is covered by these checksums
+    pub fn covers(&self, data: &[u8]) -> bool {
+        self.checksums.len() == data.len().div_ceil(CHECKSUM_BLOCK_SIZE)
+    }
+
+    fn check_block(&self, data: &[u8], block: usize) -> bool {
+        match self.states[block].load(Ordering::Relaxed) {
+            BLOCK_VALID => return true,
+            BLOCK_CORRUPTED => return false,
+            _ => {}
+        }
+
+        let start = block * CHECKSUM_BLOCK_SIZE;
+        let end = (start + CHECKSUM_BLOCK_SIZE).min(data.len());
+        let is_valid = crc32fast::hash(&data[start..end]) == self.checksums[block];
+        let state = if is_valid {
+            BLOCK_VALID
+        } else {
+            BLOCK_CORRUPTED
+        };
+        let previous = self.states[block].swap(state, Ordering::Relaxed);
+        if !is_valid && previous != BLOCK_CORRUPTED {
+            log::error!(
+                "Checksum mismatch in block {block}, bytes {start}..{end} of on-disk vector data"
+            );
+        }
+        is_valid
+    }
+
+    /// Verify blocks, overlapping with `range` of `data`, which were not verified yet
+    ///
+    /// Returns `false` if any of the blocks is corrupted.
+    pub fn check_range(&self, data: &[u8], range: Range<usize>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        let first_block = range.start / CHECKSUM_BLOCK_SIZE;
+        let last_block = (range.end - 1) / CHECKSUM_BLOCK_SIZE;
+        (first_block..=last_block).all(|block| self.check_block(data, block))
+    }
+
+    /// Verify all blocks of `data`, returns byte ranges of corrupted blocks
+    pub fn verify(&self, data: &[u8]) -> Vec<Range<usize>> {
+        (0..self.checksums.len())
+            .filter(|&block| !self.check_block(data, block))
+            .map(|block| {
+                let start = block * CHECKSUM_BLOCK_SIZE;
+                start..(start + CHECKSUM_BLOCK_SIZE).min(data.len())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn test_block_checksums() {
+        let dir = Builder::new().prefix("checksums").tempdir().unwrap();
+        let path = dir.path().join("checksums.dat");
+
+        let mut data: Vec<u8> = (0..CHECKSUM_BLOCK_SIZE * 3 + 100)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        // Checksums of appended data match the ones computed at once
+        let appended = BlockChecksums::compute(&data[..CHECKSUM_BLOCK_SIZE + 7]).extend(&data);
+        assert_eq!(appended.checksums, BlockChecksums::compute(&data).checksums);
+        appended.save(&path).unwrap();
+
+        let checksums = BlockChecksums::load(&path).unwrap().unwrap();
+        assert!(checksums.covers(&data));
+        assert!(checksums.verify(&data).is_empty());
+
+        // Flip a bit in the second block
+        data[CHECKSUM_BLOCK_SIZE + 10] ^= 1;
+        let checksums = BlockChecksums::load(&path).unwrap().unwrap();
+        assert!(checksums.check_range(&data, 0..CHECKSUM_BLOCK_SIZE));
+        assert!(!checksums.check_range(&data, CHECKSUM_BLOCK_SIZE - 5..CHECKSUM_BLOCK_SIZE + 5));
+        assert_eq!(
+            checksums.verify(&data),
+            vec![CHECKSUM_BLOCK_SIZE..CHECKSUM_BLOCK_SIZE * 2],
+        );
+
+        assert!(BlockChecksums::load(&dir.path().join("missing.dat"))
+            .unwrap()
+            .is_none());
+    }
+}
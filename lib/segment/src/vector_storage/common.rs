@@ -1,6 +1,7 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 
 static ASYNC_SCORER: AtomicBool = AtomicBool::new(false);
+static VERIFY_CHECKSUMS: AtomicBool = AtomicBool::new(false);
 
 pub fn set_async_scorer(async_scorer: bool) {
     ASYNC_SCORER.store(async_scorer, Ordering::Relaxed);
@@ -9,3 +10,12 @@ pub fn set_async_scorer(async_scorer: bool) {
 pub fn get_async_scorer() -> bool {
     ASYNC_SCORER.load(Ordering::Relaxed)
 }
+
+/// Verify checksums of on-disk vectors the first time they are read
+pub fn set_verify_checksums(verify_checksums: bool) {
+    VERIFY_CHECKSUMS.store(verify_checksums, Ordering::Relaxed);
+}
+
+pub fn get_verify_checksums() -> bool {
+    VERIFY_CHECKSUMS.load(Ordering::Relaxed)
+}
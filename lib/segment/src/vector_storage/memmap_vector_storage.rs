@@ -22,6 +22,7 @@ use crate::vector_storage::VectorStorage;
 
 const VECTORS_PATH: &str = "matrix.dat";
 const DELETED_PATH: &str = "deleted.dat";
+const CHECKSUMS_PATH: &str = "checksums.dat";
 
 /// Stores all vectors in mem-mapped file
 ///
@@ -32,6 +33,7 @@ const DELETED_PATH: &str = "deleted.dat";
 pub struct MemmapVectorStorage {
     vectors_path: PathBuf,
     deleted_path: PathBuf,
+    checksums_path: PathBuf,
     mmap_store: Option<MmapVectors>,
     distance: Distance,
 }
@@ -54,12 +56,20 @@ pub fn open_memmap_vector_storage_with_async_io(
 
     let vectors_path = path.join(VECTORS_PATH);
     let deleted_path = path.join(DELETED_PATH);
-    let mmap_store = MmapVectors::open(&vectors_path, &deleted_path, dim, with_async_io)?;
+    let checksums_path = path.join(CHECKSUMS_PATH);
+    let mmap_store = MmapVectors::open(
+        &vectors_path,
+        &deleted_path,
+        &checksums_path,
+        dim,
+        with_async_io,
+    )?;
 
     Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Memmap(
         Box::new(MemmapVectorStorage {
             vectors_path,
             deleted_path,
+            checksums_path,
             mmap_store: Some(mmap_store),
             distance,
         }),
@@ -79,6 +89,14 @@ impl MemmapVectorStorage {
         self.mmap_store.as_ref().unwrap()
     }
 
+    /// Verify checksums of all stored vectors
+    pub fn verify_checksums(&self) -> OperationResult<()> {
+        match &self.mmap_store {
+            Some(mmap_store) => mmap_store.verify_checksums(),
+            None => Ok(()),
+        }
+    }
+
     pub fn has_async_reader(&self) -> bool {
         self.mmap_store
             .as_ref()
@@ -152,10 +170,13 @@ impl VectorStorage for MemmapVectorStorage {
         vectors_file.flush()?;
         drop(vectors_file);
 
+        MmapVectors::update_checksums(&self.vectors_path, &self.checksums_path)?;
+
         // Load store with updated files
         self.mmap_store.replace(MmapVectors::open(
             &self.vectors_path,
             &self.deleted_path,
+            &self.checksums_path,
             dim,
             with_async_io,
         )?);
@@ -181,7 +202,11 @@ impl VectorStorage for MemmapVectorStorage {
     }
 
     fn files(&self) -> Vec<PathBuf> {
-        vec![self.vectors_path.clone(), self.deleted_path.clone()]
+        let mut files = vec![self.vectors_path.clone(), self.deleted_path.clone()];
+        if self.checksums_path.exists() {
+            files.push(self.checksums_path.clone());
+        }
+        files
     }
 
     fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
@@ -719,4 +744,56 @@ mod tests {
             assert!((orig - quant).abs() < 0.15);
         }
     }
+
+    #[test]
+    fn test_checksums_detect_corruption() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+        let points: Vec<Vec<VectorElementType>> =
+            (0..100).map(|i| vec![i as f32, 1.0, 0.0, 1.0]).collect();
+        {
+            let storage = open_memmap_vector_storage(dir.path(), 4, Distance::Dot).unwrap();
+            let dir2 = Builder::new().prefix("db_dir").tempdir().unwrap();
+            let db = open_db(dir2.path(), &[DB_VECTOR_CF]).unwrap();
+            let storage2 = open_simple_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+            {
+                let mut borrowed_storage2 = storage2.borrow_mut();
+                for (i, point) in points.iter().enumerate() {
+                    borrowed_storage2
+                        .insert_vector(i as PointOffsetType, point.as_slice().into())
+                        .unwrap();
+                }
+            }
+            let mut borrowed_storage = storage.borrow_mut();
+            // Append in two steps, so checksums of the last block are updated
+            borrowed_storage
+                .update_from(
+                    &storage2.borrow(),
+                    &mut Box::new(0..50),
+                    &Default::default(),
+                )
+                .unwrap();
+            borrowed_storage
+                .update_from(
+                    &storage2.borrow(),
+                    &mut Box::new(50..100),
+                    &Default::default(),
+                )
+                .unwrap();
+            assert!(borrowed_storage.verify_checksums().is_ok());
+            assert!(borrowed_storage
+                .files()
+                .iter()
+                .any(|file| file.file_name().unwrap() == CHECKSUMS_PATH));
+        }
+
+        // Flip a bit of a stored vector
+        let vectors_path = dir.path().join(VECTORS_PATH);
+        let mut data = std::fs::read(&vectors_path).unwrap();
+        data[100] ^= 1;
+        std::fs::write(&vectors_path, data).unwrap();
+
+        let storage = open_memmap_vector_storage(dir.path(), 4, Distance::Dot).unwrap();
+        assert!(storage.borrow().verify_checksums().is_err());
+    }
 }
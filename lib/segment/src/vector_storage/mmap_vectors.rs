@@ -12,13 +12,15 @@ use parking_lot::Mutex;
 
 use crate::common::error_logging::LogError;
 use crate::common::mmap_type::MmapBitSlice;
-use crate::common::operation_error::OperationResult;
+use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::Flusher;
 use crate::data_types::vectors::VectorElementType;
 #[cfg(target_os = "linux")]
 use crate::vector_storage::async_io::UringReader;
 #[cfg(not(target_os = "linux"))]
 use crate::vector_storage::async_io_mock::UringReader;
+use crate::vector_storage::checksums::BlockChecksums;
+use crate::vector_storage::common::get_verify_checksums;
 
 const HEADER_SIZE: usize = 4;
 const VECTORS_HEADER: &[u8; HEADER_SIZE] = b"data";
@@ -39,12 +41,15 @@ pub struct MmapVectors {
     deleted: MmapBitSlice,
     /// Current number of deleted vectors.
     pub deleted_count: usize,
+    /// Checksums of the vector data file, not available for storages created by older versions
+    checksums: Option<BlockChecksums>,
 }
 
 impl MmapVectors {
     pub fn open(
         vectors_path: &Path,
         deleted_path: &Path,
+        checksums_path: &Path,
         dim: usize,
         with_async_io: bool,
     ) -> OperationResult<Self> {
@@ -54,6 +59,18 @@ impl MmapVectors {
         let mmap = mmap_ops::open_read_mmap(vectors_path).describe("Open mmap for reading")?;
         let num_vectors = (mmap.len() - HEADER_SIZE) / dim / size_of::<VectorElementType>();
 
+        let checksums = match BlockChecksums::load(checksums_path)? {
+            Some(checksums) if checksums.covers(&mmap) => Some(checksums),
+            Some(_) => {
+                log::warn!(
+                    "Checksums don't match the size of {}, skipping verification",
+                    vectors_path.display(),
+                );
+                None
+            }
+            None => None,
+        };
+
         // Allocate/open deleted mmap
         let deleted_mmap_size = deleted_mmap_size(num_vectors);
         ensure_mmap_file_size(deleted_path, DELETED_HEADER, Some(deleted_mmap_size as u64))
@@ -87,9 +104,36 @@ impl MmapVectors {
             uring_reader: Mutex::new(uring_reader),
             deleted,
             deleted_count,
+            checksums,
         })
     }
 
+    /// Update checksums of the vector data file, after new vectors were appended to it
+    pub fn update_checksums(vectors_path: &Path, checksums_path: &Path) -> OperationResult<()> {
+        let mmap = mmap_ops::open_read_mmap(vectors_path).describe("Open mmap for reading")?;
+        let checksums = match BlockChecksums::load(checksums_path)? {
+            Some(checksums) => checksums.extend(&mmap),
+            None => BlockChecksums::compute(&mmap),
+        };
+        checksums.save(checksums_path)
+    }
+
+    /// Verify checksums of all vector data
+    pub fn verify_checksums(&self) -> OperationResult<()> {
+        let Some(checksums) = &self.checksums else {
+            return Ok(());
+        };
+        let corrupted = checksums.verify(&self.mmap);
+        if corrupted.is_empty() {
+            return Ok(());
+        }
+        Err(OperationError::service_error(format!(
+            "Vector data is corrupted, checksums don't match in {} blocks, bytes: {:?}",
+            corrupted.len(),
+            corrupted,
+        )))
+    }
+
     pub fn has_async_reader(&self) -> bool {
         self.uring_reader.lock().is_some()
     }
@@ -112,7 +156,14 @@ impl MmapVectors {
     }
 
     pub fn raw_vector_offset(&self, offset: usize) -> &[VectorElementType] {
-        let byte_slice = &self.mmap[offset..(offset + self.raw_size())];
+        let range = offset..(offset + self.raw_size());
+        if let Some(checksums) = &self.checksums {
+            // Mismatch is logged by the checksums, reads of corrupted data are not interrupted
+            if get_verify_checksums() {
+                checksums.check_range(&self.mmap, range.clone());
+            }
+        }
+        let byte_slice = &self.mmap[range];
         let arr: &[VectorElementType] = unsafe { transmute(byte_slice) };
         &arr[0..self.dim]
     }
@@ -1,6 +1,7 @@
 pub mod appendable_mmap_vector_storage;
 #[cfg(target_os = "linux")]
 pub mod async_raw_scorer;
+mod checksums;
 mod chunked_mmap_vectors;
 mod chunked_utils;
 pub mod chunked_vectors;
@@ -1,3 +1,4 @@
+pub mod quality_estimation;
 mod quantized_custom_query_scorer;
 mod quantized_mmap_storage;
 mod quantized_query_scorer;
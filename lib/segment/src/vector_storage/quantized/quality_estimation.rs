@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use bitvec::prelude::BitVec;
+use common::types::{PointOffsetType, ScoredPointOffset};
+
+use super::quantized_vectors::{QuantizedVectors, QUANTIZED_DATA_PATH};
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::data_types::vectors::VectorType;
+use crate::spaces::tools::peek_top_largest_iterable;
+use crate::types::{Distance, QuantizationConfig};
+
+/// Quality of searches over quantized vectors, compared to exact searches over original vectors
+#[derive(Debug, Clone)]
+pub struct QuantizationQuality {
+    /// Share of the exact nearest neighbours, also found with quantized vectors, for each query
+    pub recalls: Vec<f64>,
+    /// Size of a single quantized vector in bytes
+    pub quantized_vector_size_bytes: usize,
+}
+
+/// Quantize `vectors` and search the nearest neighbours of the first `queries_count` of them
+/// among all others, both with original and with quantized vectors.
+///
+/// Vectors are expected to be preprocessed for the `distance` already, as stored in a segment.
+/// Quantized data is written into `path`, which can be removed afterwards.
+#[allow(clippy::too_many_arguments)]
+pub fn estimate_quantization_quality(
+    vectors: &[VectorType],
+    distance: Distance,
+    quantization_config: &QuantizationConfig,
+    queries_count: usize,
+    limit: usize,
+    path: &Path,
+    max_threads: usize,
+    stopped: &AtomicBool,
+) -> OperationResult<QuantizationQuality> {
+    let Some(dim) = vectors.first().map(|vector| vector.len()) else {
+        return Err(OperationError::service_error(
+            "No vectors to estimate quantization quality on",
+        ));
+    };
+    if let Some(vector) = vectors.iter().find(|vector| vector.len() != dim) {
+        return Err(OperationError::WrongVector {
+            expected_dim: dim,
+            received_dim: vector.len(),
+        });
+    }
+
+    let quantized_vectors = QuantizedVectors::create_from_vectors(
+        vectors.iter().map(|vector| vector.as_slice()),
+        vectors.len(),
+        dim,
+        distance,
+        false,
+        quantization_config,
+        path,
+        max_threads,
+        stopped,
+    )?;
+    let quantized_data_size = std::fs::metadata(path.join(QUANTIZED_DATA_PATH))?.len() as usize;
+
+    let deleted = BitVec::repeat(false, vectors.len());
+    let mut recalls = Vec::with_capacity(queries_count);
+    for (query_idx, query) in vectors.iter().enumerate().take(queries_count) {
+        let query_idx = query_idx as PointOffsetType;
+
+        // The query itself is found by both searches, so it is excluded from the results
+        let exact_scores = vectors
+            .iter()
+            .enumerate()
+            .map(|(idx, vector)| ScoredPointOffset {
+                idx: idx as PointOffsetType,
+                score: distance.similarity(query, vector),
+            })
+            .filter(|scored| scored.idx != query_idx);
+        let exact: HashSet<_> = peek_top_largest_iterable(exact_scores, limit)
+            .into_iter()
+            .map(|scored| scored.idx)
+            .collect();
+
+        let scorer =
+            quantized_vectors.raw_scorer(query.clone().into(), &deleted, &deleted, stopped)?;
+        let found = scorer
+            .peek_top_all(limit + 1)
+            .into_iter()
+            .filter(|scored| scored.idx != query_idx)
+            .take(limit)
+            .filter(|scored| exact.contains(&scored.idx))
+            .count();
+
+        let recall = if exact.is_empty() {
+            1.0
+        } else {
+            found as f64 / exact.len() as f64
+        };
+        recalls.push(recall);
+    }
+
+    Ok(QuantizationQuality {
+        recalls,
+        quantized_vector_size_bytes: quantized_data_size / vectors.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::fixtures::index_fixtures::random_vector;
+    use crate::types::{
+        BinaryQuantization, BinaryQuantizationConfig, ScalarQuantization, ScalarQuantizationConfig,
+        ScalarType,
+    };
+
+    const DIM: usize = 64;
+    const NUM_VECTORS: usize = 500;
+
+    fn random_vectors(distance: Distance) -> Vec<VectorType> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..NUM_VECTORS)
+            .map(|_| distance.preprocess_vector(random_vector(&mut rng, DIM)))
+            .collect()
+    }
+
+    #[test]
+    fn test_scalar_quantization_quality() {
+        let dir = Builder::new()
+            .prefix("quantization_quality")
+            .tempdir()
+            .unwrap();
+        let vectors = random_vectors(Distance::Cosine);
+        let config = QuantizationConfig::Scalar(ScalarQuantization {
+            scalar: ScalarQuantizationConfig {
+                r#type: ScalarType::Int8,
+                quantile: None,
+                always_ram: None,
+            },
+        });
+
+        let quality = estimate_quantization_quality(
+            &vectors,
+            Distance::Cosine,
+            &config,
+            20,
+            10,
+            dir.path(),
+            1,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(quality.recalls.len(), 20);
+        let mean_recall = quality.recalls.iter().sum::<f64>() / quality.recalls.len() as f64;
+        assert!(mean_recall > 0.8, "mean recall {mean_recall} is too low");
+        // One byte per dimension, plus a correction term
+        assert!(quality.quantized_vector_size_bytes >= DIM);
+        assert!(quality.quantized_vector_size_bytes < DIM * 2);
+    }
+
+    #[test]
+    fn test_binary_quantization_quality_is_lower() {
+        let dir = Builder::new()
+            .prefix("quantization_quality")
+            .tempdir()
+            .unwrap();
+        let vectors = random_vectors(Distance::Dot);
+        let estimate = |config: QuantizationConfig| {
+            let quality = estimate_quantization_quality(
+                &vectors,
+                Distance::Dot,
+                &config,
+                20,
+                10,
+                dir.path(),
+                1,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+            quality.recalls.iter().sum::<f64>() / quality.recalls.len() as f64
+        };
+
+        let scalar_recall = estimate(QuantizationConfig::Scalar(ScalarQuantization {
+            scalar: ScalarQuantizationConfig {
+                r#type: ScalarType::Int8,
+                quantile: None,
+                always_ram: None,
+            },
+        }));
+        let binary_recall = estimate(QuantizationConfig::Binary(BinaryQuantization {
+            binary: BinaryQuantizationConfig { always_ram: None },
+        }));
+
+        assert!(binary_recall < scalar_recall);
+    }
+}
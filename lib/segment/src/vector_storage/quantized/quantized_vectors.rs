@@ -28,10 +28,64 @@ pub const QUANTIZED_CONFIG_PATH: &str = "quantized.config.json";
 pub const QUANTIZED_DATA_PATH: &str = "quantized.data";
 pub const QUANTIZED_META_PATH: &str = "quantized.meta.json";
 
+/// Number of vectors, sampled to find the range of values quantization is trained on
+const VALUE_RANGE_SAMPLE_SIZE: usize = 1000;
+/// Share of the sampled values, which are left out at each end of the value range
+const VALUE_RANGE_TAIL: f32 = 0.01;
+
 #[derive(Deserialize, Serialize, Clone)]
 pub struct QuantizedVectorsConfig {
     pub quantization_config: QuantizationConfig,
     pub vector_parameters: quantization::VectorParameters,
+    /// Range of values of the quantized vectors. Missing for vectors quantized by older versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_range: Option<ValueRange>,
+}
+
+/// Range between the lower and upper quantiles of values of vector components
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct ValueRange {
+    pub lower: VectorElementType,
+    pub upper: VectorElementType,
+}
+
+impl ValueRange {
+    /// Estimate the range on an evenly spaced sample of `count` vectors
+    fn from_sample<'a>(
+        vectors: impl Iterator<Item = &'a [VectorElementType]>,
+        count: usize,
+    ) -> Option<Self> {
+        let step = count.div_ceil(VALUE_RANGE_SAMPLE_SIZE).max(1);
+        let mut values: Vec<VectorElementType> = vectors.step_by(step).flatten().copied().collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable_by(|a, b| a.total_cmp(b));
+
+        let tail = (values.len() as f32 * VALUE_RANGE_TAIL) as usize;
+        Some(Self {
+            lower: values[tail],
+            upper: values[values.len() - 1 - tail],
+        })
+    }
+
+    /// Share of the values of `vectors` outside of this range.
+    ///
+    /// Values of the vectors the range is estimated on are outside in about 2% of cases, much
+    /// larger shares mean that the distribution of values has drifted since then.
+    pub fn out_of_range_share<'a>(
+        &self,
+        vectors: impl Iterator<Item = &'a [VectorElementType]>,
+    ) -> Option<f32> {
+        let (mut total, mut outside) = (0usize, 0usize);
+        for value in vectors.flatten() {
+            total += 1;
+            if *value < self.lower || *value > self.upper {
+                outside += 1;
+            }
+        }
+        (total > 0).then(|| outside as f32 / total as f32)
+    }
 }
 
 pub enum QuantizedVectorStorage {
@@ -76,6 +130,10 @@ impl QuantizedVectors {
         .build()
     }
 
+    pub fn value_range(&self) -> Option<&ValueRange> {
+        self.config.value_range.as_ref()
+    }
+
     pub fn save_to(&self, path: &Path) -> OperationResult<()> {
         let data_path = path.join(QUANTIZED_DATA_PATH);
         let meta_path = path.join(QUANTIZED_META_PATH);
@@ -131,11 +189,37 @@ impl QuantizedVectors {
     ) -> OperationResult<Arc<AtomicRefCell<Self>>> {
         let count = vector_storage.total_vector_count();
         let vectors = (0..count as PointOffsetType).map(|i| vector_storage.get_dense(i));
-        let on_disk_vector_storage = vector_storage.is_on_disk();
-        let distance = vector_storage.distance();
-        let dim = vector_storage.vector_dim();
+        let quantized_vectors = Self::create_from_vectors(
+            vectors,
+            count,
+            vector_storage.vector_dim(),
+            vector_storage.distance(),
+            vector_storage.is_on_disk(),
+            quantization_config,
+            path,
+            max_threads,
+            stopped,
+        )?;
+        Ok(Arc::new(AtomicRefCell::new(quantized_vectors)))
+    }
 
+    /// Quantize `count` vectors, which are not necessarily kept in a vector storage.
+    ///
+    /// Used to evaluate a quantization config on a sample of vectors.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_from_vectors<'a>(
+        vectors: impl Iterator<Item = &'a [VectorElementType]> + Clone + Send,
+        count: usize,
+        dim: usize,
+        distance: Distance,
+        on_disk_vector_storage: bool,
+        quantization_config: &QuantizationConfig,
+        path: &Path,
+        max_threads: usize,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Self> {
         let vector_parameters = Self::construct_vector_parameters(distance, dim, count);
+        let value_range = ValueRange::from_sample(vectors.clone(), count);
 
         let quantized_storage = match quantization_config {
             QuantizationConfig::Scalar(ScalarQuantization {
@@ -174,6 +258,7 @@ impl QuantizedVectors {
         let quantized_vectors_config = QuantizedVectorsConfig {
             quantization_config: quantization_config.clone(),
             vector_parameters,
+            value_range,
         };
 
         let quantized_vectors = QuantizedVectors {
@@ -185,7 +270,7 @@ impl QuantizedVectors {
 
         quantized_vectors.save_to(path)?;
         atomic_save_json(&path.join(QUANTIZED_CONFIG_PATH), &quantized_vectors.config)?;
-        Ok(Arc::new(AtomicRefCell::new(quantized_vectors)))
+        Ok(quantized_vectors)
     }
 
     pub fn config_exists(path: &Path) -> bool {
@@ -418,3 +503,34 @@ impl QuantizedVectors {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_range_drift() {
+        let vectors: Vec<Vec<VectorElementType>> = (0..2000)
+            .map(|i| (0..8).map(|j| ((i * 8 + j) % 100) as f32 / 100.0).collect())
+            .collect();
+        let range =
+            ValueRange::from_sample(vectors.iter().map(|v| v.as_slice()), vectors.len()).unwrap();
+        assert!(range.lower < 0.05 && range.upper > 0.95);
+
+        let share = range
+            .out_of_range_share(vectors.iter().map(|v| v.as_slice()))
+            .unwrap();
+        assert!(share < 0.05, "share {share} is too large without drift");
+
+        let drifted: Vec<Vec<VectorElementType>> = vectors
+            .iter()
+            .map(|v| v.iter().map(|x| x + 0.5).collect())
+            .collect();
+        let share = range
+            .out_of_range_share(drifted.iter().map(|v| v.as_slice()))
+            .unwrap();
+        assert!(share > 0.4, "share {share} is too small for drifted values");
+
+        assert_eq!(range.out_of_range_share(std::iter::empty()), None);
+    }
+}
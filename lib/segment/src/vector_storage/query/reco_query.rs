@@ -10,6 +10,10 @@ use crate::data_types::vectors::{QueryVector, Vector};
 pub struct RecoQuery<T> {
     pub positives: Vec<T>,
     pub negatives: Vec<T>,
+    /// Weights of the positive examples, aligned with `positives`. Empty means all weights are 1.0
+    pub positive_weights: Vec<ScoreType>,
+    /// Weights of the negative examples, aligned with `negatives`. Empty means all weights are 1.0
+    pub negative_weights: Vec<ScoreType>,
 }
 
 impl<T> RecoQuery<T> {
@@ -17,6 +21,27 @@ impl<T> RecoQuery<T> {
         Self {
             positives,
             negatives,
+            positive_weights: Vec::new(),
+            negative_weights: Vec::new(),
+        }
+    }
+
+    /// Creates a query where each example carries a weight.
+    ///
+    /// Empty weight lists mean that all examples of that kind have weight 1.0.
+    pub fn with_weights(
+        positives: Vec<T>,
+        negatives: Vec<T>,
+        positive_weights: Vec<ScoreType>,
+        negative_weights: Vec<ScoreType>,
+    ) -> Self {
+        debug_assert!(positive_weights.is_empty() || positive_weights.len() == positives.len());
+        debug_assert!(negative_weights.is_empty() || negative_weights.len() == negatives.len());
+        Self {
+            positives,
+            negatives,
+            positive_weights,
+            negative_weights,
         }
     }
 
@@ -30,9 +55,11 @@ impl<T, U> TransformInto<RecoQuery<U>, T, U> for RecoQuery<T> {
     where
         F: FnMut(T) -> OperationResult<U>,
     {
-        Ok(RecoQuery::new(
+        Ok(RecoQuery::with_weights(
             self.positives.into_iter().map(&mut f).try_collect()?,
             self.negatives.into_iter().map(&mut f).try_collect()?,
+            self.positive_weights,
+            self.negative_weights,
         ))
     }
 }
@@ -40,15 +67,41 @@ impl<T, U> TransformInto<RecoQuery<U>, T, U> for RecoQuery<T> {
 impl<T> Query<T> for RecoQuery<T> {
     fn score_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType {
         // get similarities to all positives
-        let positive_similarities = self.positives.iter().map(&similarity);
+        let positive_similarities = self
+            .positives
+            .iter()
+            .enumerate()
+            .map(|(i, example)| apply_weight(similarity(example), &self.positive_weights, i));
 
         // and all negatives
-        let negative_similarities = self.negatives.iter().map(&similarity);
+        let negative_similarities = self
+            .negatives
+            .iter()
+            .enumerate()
+            .map(|(i, example)| apply_weight(similarity(example), &self.negative_weights, i));
 
         merge_similarities(positive_similarities, negative_similarities)
     }
 }
 
+/// Scales a similarity by the weight of its example.
+///
+/// Positive similarities are multiplied and negative ones are divided by the weight,
+/// so a heavier example always gets closer to winning the max, regardless of the sign.
+#[inline]
+fn apply_weight(similarity: ScoreType, weights: &[ScoreType], index: usize) -> ScoreType {
+    match weights.get(index) {
+        Some(&weight) if weight != 1.0 => {
+            if similarity >= 0.0 {
+                similarity * weight
+            } else {
+                similarity / weight
+            }
+        }
+        _ => similarity,
+    }
+}
+
 #[inline]
 fn merge_similarities(
     positives: impl Iterator<Item = ScoreType>,
@@ -125,6 +178,35 @@ mod test {
         }
     }
 
+    #[rstest]
+    #[case::unweighted(vec![1.0, 1.0], Chosen::Negative, 4.0)]
+    #[case::heavier_positive(vec![3.0, 1.0], Chosen::Positive, 6.0)]
+    #[case::lighter_negative(vec![1.0, 0.25], Chosen::Positive, 2.0)]
+    fn score_weighted_query(
+        #[case] weights: Vec<ScoreType>,
+        #[case] chosen: Chosen,
+        #[case] expected: ScoreType,
+    ) {
+        let query = RecoQuery::with_weights(vec![2], vec![4], vec![weights[0]], vec![weights[1]]);
+
+        let score = query.score_by(|x: &isize| *x as ScoreType);
+
+        match chosen {
+            Chosen::Positive => assert_eq!(score, scaled_fast_sigmoid(expected)),
+            Chosen::Negative => assert_eq!(score, -scaled_fast_sigmoid(expected)),
+        }
+    }
+
+    #[test]
+    fn weight_favours_negative_similarities() {
+        // Both similarities are negative, a heavier positive must get closer to zero and win
+        let query = RecoQuery::with_weights(vec![-4], vec![-2], vec![4.0], vec![]);
+
+        let score = query.score_by(|x: &isize| *x as ScoreType);
+
+        assert_eq!(score, scaled_fast_sigmoid(-1.0));
+    }
+
     proptest! {
         /// Checks that the negative-chosen scores invert the order of the candidates
         #[test]
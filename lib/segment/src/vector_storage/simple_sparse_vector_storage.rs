@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use sparse::common::sparse_vector::SparseVector;
 
 use super::SparseVectorStorage;
+use crate::common::kv_store::KvStoreRef;
 use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
@@ -24,7 +25,7 @@ pub const SPARSE_VECTOR_DISTANCE: Distance = Distance::Dot;
 
 /// In-memory vector storage with on-update persistence using `store`
 pub struct SimpleSparseVectorStorage {
-    db_wrapper: DatabaseColumnWrapper,
+    store: KvStoreRef,
     update_buffer: StoredRecord,
     /// BitVec for deleted flags. Grows dynamically upto last set flag.
     deleted: BitVec,
@@ -45,17 +46,22 @@ struct StoredRecord {
 pub fn open_simple_sparse_vector_storage(
     database: Arc<RwLock<DB>>,
     database_column_name: &str,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let store = Arc::new(DatabaseColumnWrapper::new(database, database_column_name));
+    open_simple_sparse_vector_storage_with_store(store)
+}
+
+pub fn open_simple_sparse_vector_storage_with_store(
+    store: KvStoreRef,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
     let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
-    let db_wrapper = DatabaseColumnWrapper::new(database, database_column_name);
 
     let mut total_vector_count = 0;
     let mut total_sparse_size = 0;
-    db_wrapper.lock_db().iter()?;
-    for (key, value) in db_wrapper.lock_db().iter()? {
-        let point_id: PointOffsetType = bincode::deserialize(&key)
+    store.for_each(&mut |key, value| {
+        let point_id: PointOffsetType = bincode::deserialize(key)
             .map_err(|_| OperationError::service_error("cannot deserialize point id from db"))?;
-        let stored_record: StoredRecord = bincode::deserialize(&value)
+        let stored_record: StoredRecord = bincode::deserialize(value)
             .map_err(|_| OperationError::service_error("cannot deserialize record from db"))?;
 
         // Propagate deleted flag
@@ -65,11 +71,12 @@ pub fn open_simple_sparse_vector_storage(
         }
         total_vector_count = std::cmp::max(total_vector_count, point_id as usize + 1);
         total_sparse_size += stored_record.vector.values.len();
-    }
+        Ok(true)
+    })?;
 
     Ok(Arc::new(AtomicRefCell::new(
         VectorStorageEnum::SparseSimple(SimpleSparseVectorStorage {
-            db_wrapper,
+            store,
             update_buffer: StoredRecord {
                 deleted: false,
                 vector: SparseVector::default(),
@@ -119,9 +126,9 @@ impl SimpleSparseVectorStorage {
         }
 
         // Store updated record
-        self.db_wrapper.put(
-            bincode::serialize(&key).unwrap(),
-            bincode::serialize(&record).unwrap(),
+        self.store.put(
+            &bincode::serialize(&key).unwrap(),
+            &bincode::serialize(&record).unwrap(),
         )?;
 
         Ok(())
@@ -147,7 +154,9 @@ impl SparseVectorStorage for SimpleSparseVectorStorage {
     fn get_sparse(&self, key: PointOffsetType) -> OperationResult<SparseVector> {
         let bin_key = bincode::serialize(&key)
             .map_err(|_| OperationError::service_error("Cannot serialize sparse vector key"))?;
-        let data = self.db_wrapper.get(bin_key)?;
+        let data = self.store.get(&bin_key)?.ok_or_else(|| {
+            OperationError::service_error("Sparse vector is not found in the store")
+        })?;
         let record: StoredRecord = bincode::deserialize(&data).map_err(|_| {
             OperationError::service_error("Cannot deserialize sparse vector from db")
         })?;
@@ -213,7 +222,7 @@ impl VectorStorage for SimpleSparseVectorStorage {
     }
 
     fn flusher(&self) -> Flusher {
-        self.db_wrapper.flusher()
+        self.store.flusher()
     }
 
     fn files(&self) -> Vec<std::path::PathBuf> {
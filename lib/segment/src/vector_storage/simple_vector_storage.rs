@@ -14,6 +14,7 @@ use serde::{Deserialize, Serialize};
 use super::chunked_vectors::ChunkedVectors;
 use super::vector_storage_base::VectorStorage;
 use super::{DenseVectorStorage, VectorStorageEnum};
+use crate::common::kv_store::KvStoreRef;
 use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
@@ -27,7 +28,7 @@ pub struct SimpleVectorStorage {
     dim: usize,
     distance: Distance,
     vectors: ChunkedVectors<VectorElementType>,
-    db_wrapper: DatabaseColumnWrapper,
+    store: KvStoreRef,
     update_buffer: StoredRecord,
     /// BitVec for deleted flags. Grows dynamically upto last set flag.
     deleted: BitVec,
@@ -46,16 +47,23 @@ pub fn open_simple_vector_storage(
     database_column_name: &str,
     dim: usize,
     distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let store = Arc::new(DatabaseColumnWrapper::new(database, database_column_name));
+    open_simple_vector_storage_with_store(store, dim, distance)
+}
+
+pub fn open_simple_vector_storage_with_store(
+    store: KvStoreRef,
+    dim: usize,
+    distance: Distance,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
     let mut vectors = ChunkedVectors::new(dim);
     let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
 
-    let db_wrapper = DatabaseColumnWrapper::new(database, database_column_name);
-
-    for (key, value) in db_wrapper.lock_db().iter()? {
-        let point_id: PointOffsetType = bincode::deserialize(&key)
+    store.for_each(&mut |key, value| {
+        let point_id: PointOffsetType = bincode::deserialize(key)
             .map_err(|_| OperationError::service_error("cannot deserialize point id from db"))?;
-        let stored_record: StoredRecord = bincode::deserialize(&value)
+        let stored_record: StoredRecord = bincode::deserialize(value)
             .map_err(|_| OperationError::service_error("cannot deserialize record from db"))?;
 
         // Propagate deleted flag
@@ -64,7 +72,8 @@ pub fn open_simple_vector_storage(
             deleted_count += 1;
         }
         vectors.insert(point_id, &stored_record.vector)?;
-    }
+        Ok(true)
+    })?;
 
     debug!("Segment vectors: {}", vectors.len());
     debug!(
@@ -77,7 +86,7 @@ pub fn open_simple_vector_storage(
             dim,
             distance,
             vectors,
-            db_wrapper,
+            store,
             update_buffer: StoredRecord {
                 deleted: false,
                 vector: vec![0.; dim],
@@ -120,9 +129,9 @@ impl SimpleVectorStorage {
         }
 
         // Store updated record
-        self.db_wrapper.put(
-            bincode::serialize(&key).unwrap(),
-            bincode::serialize(&record).unwrap(),
+        self.store.put(
+            &bincode::serialize(&key).unwrap(),
+            &bincode::serialize(&record).unwrap(),
         )?;
 
         Ok(())
@@ -186,7 +195,7 @@ impl VectorStorage for SimpleVectorStorage {
     }
 
     fn flusher(&self) -> Flusher {
-        self.db_wrapper.flusher()
+        self.store.flusher()
     }
 
     fn files(&self) -> Vec<std::path::PathBuf> {
@@ -69,10 +69,7 @@ fn do_test_delete_points(storage: Arc<AtomicRefCell<VectorStorageEnum>>) {
     let vector: SparseVector = vec![(0, 1.0), (1, 1.0), (2, 1.0), (3, 1.0)]
         .try_into()
         .unwrap();
-    let query_vector = QueryVector::Recommend(RecoQuery {
-        positives: vec![vector.into()],
-        negatives: vec![],
-    });
+    let query_vector = QueryVector::Recommend(RecoQuery::new(vec![vector.into()], vec![]));
     // Because nearest search for raw scorer is incorrect,
     let closest = new_raw_scorer(
         query_vector,
@@ -171,10 +168,7 @@ fn do_test_update_from_delete_points(storage: Arc<AtomicRefCell<VectorStorageEnu
     let vector: SparseVector = vec![(0, 1.0), (1, 1.0), (2, 1.0), (3, 1.0)]
         .try_into()
         .unwrap();
-    let query_vector = QueryVector::Recommend(RecoQuery {
-        positives: vec![vector.into()],
-        negatives: vec![],
-    });
+    let query_vector = QueryVector::Recommend(RecoQuery::new(vec![vector.into()], vec![]));
     let closest = new_raw_scorer(
         query_vector,
         &borrowed_storage,
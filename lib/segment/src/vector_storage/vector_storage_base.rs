@@ -111,6 +111,18 @@ pub enum VectorStorageEnum {
     SparseSimple(SimpleSparseVectorStorage),
 }
 
+impl VectorStorageEnum {
+    /// Verify checksums of stored vectors, only available for immutable on-disk storage
+    pub fn verify_checksums(&self) -> OperationResult<()> {
+        match self {
+            VectorStorageEnum::Memmap(v) => v.verify_checksums(),
+            VectorStorageEnum::Simple(_)
+            | VectorStorageEnum::AppendableMemmap(_)
+            | VectorStorageEnum::SparseSimple(_) => Ok(()),
+        }
+    }
+}
+
 impl VectorStorage for VectorStorageEnum {
     fn vector_dim(&self) -> usize {
         match self {
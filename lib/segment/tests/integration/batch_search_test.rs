@@ -42,6 +42,9 @@ fn test_batch_and_single_request_equivalency() {
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
 
     let int_key = "int";
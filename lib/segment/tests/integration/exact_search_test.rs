@@ -50,6 +50,9 @@ fn exact_search_test() {
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
 
     let int_key = "int";
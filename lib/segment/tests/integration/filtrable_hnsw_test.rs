@@ -114,6 +114,9 @@ fn _test_filterable_hnsw(
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
 
     let int_key = "int";
@@ -145,6 +145,9 @@ pub fn build_segment_3(path: &Path) -> Segment {
             ]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
         },
         true,
     )
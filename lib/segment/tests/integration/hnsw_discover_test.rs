@@ -75,6 +75,9 @@ fn hnsw_discover_precision() {
             },
         )]),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
         sparse_vector_data: Default::default(),
     };
 
@@ -182,6 +185,9 @@ fn filtered_hnsw_discover_precision() {
             },
         )]),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
         sparse_vector_data: Default::default(),
     };
 
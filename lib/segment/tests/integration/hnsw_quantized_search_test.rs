@@ -66,6 +66,9 @@ fn hnsw_quantized_search_test(
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
 
     let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -26,8 +26,9 @@ use segment::types::PayloadFieldSchema::FieldType;
 use segment::types::PayloadSchemaType::{Integer, Keyword};
 use segment::types::{
     AnyVariants, Condition, Distance, FieldCondition, Filter, GeoBoundingBox, GeoLineString,
-    GeoPoint, GeoPolygon, GeoRadius, Indexes, IsEmptyCondition, Match, Payload, PayloadField,
-    PayloadSchemaType, Range, SegmentConfig, VectorDataConfig, VectorStorageType, WithPayload,
+    GeoPoint, GeoPolygon, GeoRadius, Indexes, IsEmptyCondition, IsNullCondition, Match, Payload,
+    PayloadField, PayloadSchemaType, Range, SegmentConfig, VectorDataConfig, VectorStorageType,
+    WithPayload,
 };
 use serde_json::json;
 use tempfile::Builder;
@@ -53,6 +54,9 @@ fn build_test_segments(path_struct: &Path, path_plain: &Path) -> (Segment, Segme
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
 
     let mut plain_segment = build_segment(path_plain, &config, true).unwrap();
@@ -158,6 +162,9 @@ fn build_test_segments_nested_payload(path_struct: &Path, path_plain: &Path) ->
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
 
     let mut plain_segment = build_segment(path_plain, &config, true).unwrap();
@@ -360,6 +367,80 @@ fn test_is_empty_conditions() {
     );
 }
 
+#[test]
+fn test_is_null_and_is_empty_conditions_use_index() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+            },
+        )]),
+        sparse_vector_data: Default::default(),
+        payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
+    };
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+    let payloads = [
+        json!({ STR_KEY: null }),
+        json!({ STR_KEY: [] }),
+        json!({ STR_KEY: "a" }),
+        json!({}),
+        json!({ STR_KEY: [null, "b"] }),
+        json!({ STR_KEY: 1 }),
+    ];
+    for (idx, payload) in payloads.into_iter().enumerate() {
+        let point_id = (idx as u64).into();
+        segment
+            .upsert_point(1, point_id, only_default_vector(&[0.0; DIM]))
+            .unwrap();
+        segment
+            .set_full_payload(1, point_id, &payload.into())
+            .unwrap();
+    }
+    segment
+        .create_field_index(2, STR_KEY, Some(&Keyword.into()))
+        .unwrap();
+
+    let is_empty = Filter::new_must(Condition::IsEmpty(IsEmptyCondition {
+        is_empty: PayloadField {
+            key: STR_KEY.to_string(),
+        },
+    }));
+    let is_null = Filter::new_must(Condition::IsNull(IsNullCondition {
+        is_null: PayloadField {
+            key: STR_KEY.to_string(),
+        },
+    }));
+
+    for (filter, expected) in [(is_empty, vec![0, 1, 3]), (is_null, vec![0])] {
+        let payload_index = segment.payload_index.borrow();
+        let estimation = payload_index.estimate_cardinality(&filter);
+        assert!(!estimation.primary_clauses.is_empty());
+        assert_eq!(estimation.min, expected.len());
+        assert_eq!(estimation.max, expected.len());
+
+        let internal_ids = payload_index.query_points(&filter);
+        let id_tracker = segment.id_tracker.borrow();
+        let point_ids = internal_ids
+            .into_iter()
+            .map(|internal_id| id_tracker.external_id(internal_id).unwrap())
+            .sorted()
+            .collect_vec();
+        let expected = expected.into_iter().map(|id: u64| id.into()).collect_vec();
+        assert_eq!(point_ids, expected);
+    }
+}
+
 #[test]
 fn test_cardinality_estimation() {
     let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
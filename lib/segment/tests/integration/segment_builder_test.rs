@@ -5,11 +5,17 @@ use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use segment::common::operation_error::OperationError;
+use segment::data_types::index::{KeywordIndexParams, KeywordIndexType};
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
+use segment::id_tracker::IdTracker;
 use segment::segment::Segment;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
-use segment::types::{Indexes, SegmentConfig, VectorDataConfig, VectorStorageType};
+use segment::types::{
+    Indexes, Payload, PayloadFieldSchema, PayloadSchemaParams, SegmentConfig, VectorDataConfig,
+    VectorStorageType,
+};
+use serde_json::json;
 use tempfile::Builder;
 
 use crate::fixtures::segment::{build_segment_1, build_segment_2, empty_segment};
@@ -70,6 +76,67 @@ fn test_building_new_segment() {
     assert_eq!(merged_segment.point_version(3.into()), Some(100));
 }
 
+#[test]
+fn test_building_segment_groups_tenants() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let stopped = AtomicBool::new(false);
+
+    let tenant_schema =
+        PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(KeywordIndexParams {
+            r#type: KeywordIndexType::Keyword,
+            is_tenant: Some(true),
+        }));
+    let tenants = ["a", "b", "c"];
+
+    let mut segments = vec![empty_segment(dir.path()), empty_segment(dir.path())];
+    for (idx, segment) in segments.iter_mut().enumerate() {
+        segment
+            .create_field_index(0, "tenant", Some(&tenant_schema))
+            .unwrap();
+        for point in 0..30u64 {
+            let point_id = (idx as u64 * 100 + point).into();
+            let vector = only_default_vector(&[point as f32, 0., 0., 1.]);
+            segment.upsert_point(1, point_id, vector).unwrap();
+            let payload: Payload = json!({ "tenant": tenants[point as usize % 3] }).into();
+            segment.set_full_payload(1, point_id, &payload).unwrap();
+        }
+    }
+
+    let mut builder =
+        SegmentBuilder::new(dir.path(), temp_dir.path(), &segments[0].segment_config).unwrap();
+    builder
+        .update(&[&segments[0], &segments[1]], &stopped)
+        .unwrap();
+    let merged_segment = builder.build(&stopped).unwrap();
+
+    assert_eq!(merged_segment.available_point_count(), 60);
+
+    // Internal ids of each tenant form a single continuous range
+    let id_tracker = merged_segment.id_tracker.borrow();
+    for tenant_idx in 0..tenants.len() {
+        let internal_ids: Vec<_> = (0..2u64)
+            .flat_map(|idx| (0..30u64).map(move |point| (idx, point)))
+            .filter(|(_, point)| *point as usize % 3 == tenant_idx)
+            .map(|(idx, point)| id_tracker.internal_id((idx * 100 + point).into()).unwrap())
+            .sorted()
+            .collect();
+        let first = internal_ids[0];
+        assert_eq!(
+            internal_ids,
+            (first..first + internal_ids.len() as u32).collect_vec(),
+        );
+    }
+
+    let statistics = merged_segment.get_field_statistics();
+    let tenant_statistics = statistics["tenant"].tenants.as_ref().unwrap();
+    assert_eq!(tenant_statistics.len(), 3);
+    assert!(tenant_statistics
+        .iter()
+        .all(|tenant| tenant.points_count == 20));
+}
+
 fn estimate_build_time(segment: &Segment, stop_delay_millis: u64) -> (u64, bool) {
     let stopped = Arc::new(AtomicBool::new(false));
 
@@ -89,6 +156,9 @@ fn estimate_build_time(segment: &Segment, stop_delay_millis: u64) -> (u64, bool)
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
 
     let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &segment_config).unwrap();
@@ -75,6 +75,24 @@ fn test_point_exclusion() {
     assert_eq!(&point_ids1, &point_ids2)
 }
 
+#[test]
+fn test_select_points_in_storage_order() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let mut segment = build_segment_1(dir.path());
+    segment.delete_point(6, 3.into()).unwrap();
+
+    // Points are stored in order of insertion, absent and deleted points are skipped
+    let selected = segment.select_points_in_storage_order(&[
+        5.into(),
+        100.into(),
+        3.into(),
+        1.into(),
+        4.into(),
+    ]);
+    assert_eq!(selected, vec![1.into(), 4.into(), 5.into()]);
+}
+
 #[test]
 fn test_named_vector_search() {
     let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -335,3 +353,24 @@ fn test_update_named_vector() {
     // check that nearests are the same
     assert_eq!(nearest_upsert.id, nearest_update.id);
 }
+
+#[test]
+fn test_check_integrity() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let mut segment = build_segment_3(dir.path());
+    segment.delete_point(10, 3.into()).unwrap();
+    segment.check_integrity().unwrap();
+
+    // Link two points to the same internal id
+    let internal_id = segment.id_tracker.borrow().internal_id(2.into()).unwrap();
+    segment
+        .id_tracker
+        .borrow_mut()
+        .set_link(1.into(), internal_id)
+        .unwrap();
+    assert!(matches!(
+        segment.check_integrity(),
+        Err(OperationError::InconsistentStorage { .. }),
+    ));
+}
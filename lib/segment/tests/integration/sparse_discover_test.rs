@@ -115,9 +115,13 @@ fn sparse_index_discover_test() {
                     full_scan_threshold: Some(DEFAULT_SPARSE_FULL_SCAN_THRESHOLD),
                     index_type: SparseIndexType::MutableRam,
                 },
+                partition_by: None,
             },
         )]),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
     let dense_config = SegmentConfig {
         vector_data: HashMap::from([(
@@ -131,6 +135,9 @@ fn sparse_index_discover_test() {
             },
         )]),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
         sparse_vector_data: Default::default(),
     };
 
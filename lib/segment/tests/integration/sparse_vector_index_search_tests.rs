@@ -1,5 +1,6 @@
 use std::cmp::max;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::atomic::AtomicBool;
 
 use common::types::PointOffsetType;
@@ -484,6 +485,45 @@ fn sparse_vector_index_plain_search() {
     );
 }
 
+#[test]
+fn sparse_vector_index_statistics() {
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let data_dir = Builder::new().prefix("data_dir").tempdir().unwrap();
+    let sparse_vector_index = fixture_sparse_index_ram(
+        &mut rnd,
+        NUM_VECTORS,
+        MAX_SPARSE_DIM,
+        LOW_FULL_SCAN_THRESHOLD,
+        data_dir.path(),
+        &stopped,
+    );
+
+    let lengths: Vec<_> = sparse_vector_index
+        .inverted_index
+        .postings
+        .iter()
+        .map(|posting_list| posting_list.elements.len())
+        .filter(|&length| length > 0)
+        .collect();
+
+    let statistics = sparse_vector_index.statistics();
+    assert_eq!(statistics.dimensions_count, lengths.len());
+    assert_eq!(statistics.postings_count, lengths.iter().sum::<usize>());
+    assert_eq!(
+        statistics.posting_length_max,
+        lengths.iter().copied().max().unwrap(),
+    );
+    assert!(statistics.posting_length_p50 <= statistics.posting_length_p90);
+    assert!(statistics.posting_length_p90 <= statistics.posting_length_p99);
+    assert!(statistics.posting_length_p99 <= statistics.posting_length_max);
+
+    // Statistics are reported in telemetry of the index
+    let telemetry = sparse_vector_index.get_telemetry_data();
+    assert_eq!(telemetry.sparse_statistics, Some(statistics));
+}
+
 #[test]
 fn handling_empty_sparse_vectors() {
     let stopped = AtomicBool::new(false);
@@ -532,9 +572,13 @@ fn sparse_vector_index_persistence_test() {
                     full_scan_threshold: Some(DEFAULT_SPARSE_FULL_SCAN_THRESHOLD),
                     index_type: SparseIndexType::MutableRam,
                 },
+                partition_by: None,
             },
         )]),
         payload_storage_type: Default::default(),
+        payload_compression: None,
+        payload_blobs: None,
+        kv_store: None,
     };
     let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -766,3 +810,107 @@ fn sparse_vector_index_files() {
     let mutable_index_files = sparse_vector_mutable_index.files();
     assert_eq!(mutable_index_files.len(), 1);
 }
+
+#[test]
+fn sparse_vector_index_partitioned_search() {
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+    let top = 10;
+    let partition_key = "tenant";
+    let tenants_count = 4;
+
+    let data_dir = Builder::new().prefix("data_dir").tempdir().unwrap();
+    let sparse_vector_ram_index = fixture_sparse_index_ram(
+        &mut rnd,
+        NUM_VECTORS,
+        MAX_SPARSE_DIM,
+        LOW_FULL_SCAN_THRESHOLD,
+        data_dir.path(),
+        &stopped,
+    );
+
+    // spread points across tenants
+    let mut payload_index = sparse_vector_ram_index.payload_index.borrow_mut();
+    for idx in 0..NUM_VECTORS {
+        let payload: Payload = json!({
+            partition_key: idx % tenants_count,
+        })
+        .into();
+        payload_index
+            .assign(idx as PointOffsetType, &payload)
+            .unwrap();
+    }
+    drop(payload_index);
+
+    let open_partitioned_index = |path: &Path| -> SparseVectorIndex<InvertedIndexMmap> {
+        let mut sparse_index_config = sparse_vector_ram_index.config;
+        sparse_index_config.index_type = SparseIndexType::Mmap;
+        SparseVectorIndex::open_partitioned(
+            sparse_index_config,
+            Some(partition_key.to_owned()),
+            sparse_vector_ram_index.id_tracker.clone(),
+            sparse_vector_ram_index.vector_storage.clone(),
+            sparse_vector_ram_index.payload_index.clone(),
+            path,
+        )
+        .unwrap()
+    };
+
+    let partitioned_index_dir = Builder::new().prefix("partitioned_dir").tempdir().unwrap();
+    let mut partitioned_index = open_partitioned_index(partitioned_index_dir.path());
+    partitioned_index.build_index(&stopped).unwrap();
+
+    assert_eq!(
+        partitioned_index.indexed_vector_count(),
+        sparse_vector_ram_index.indexed_vector_count()
+    );
+    // sparse index config + inverted index files for the whole index and each partition
+    assert_eq!(partitioned_index.files().len(), 4 + 2 * tenants_count);
+
+    let query_vector: QueryVector = random_sparse_vector(&mut rnd, MAX_SPARSE_DIM).into();
+
+    let check_partitioned_results = |partitioned_index: &SparseVectorIndex<InvertedIndexMmap>| {
+        for tenant in 0..tenants_count {
+            let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+                partition_key,
+                (tenant as i64).into(),
+            )));
+
+            let expected = sparse_vector_ram_index
+                .search(&[&query_vector], Some(&filter), top, None, &stopped)
+                .unwrap();
+            let partitioned = partitioned_index
+                .search(&[&query_vector], Some(&filter), top, None, &stopped)
+                .unwrap();
+
+            assert!(!expected[0].is_empty());
+            assert_eq!(expected, partitioned);
+        }
+
+        // no point belongs to an unknown tenant
+        let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            partition_key,
+            (tenants_count as i64).into(),
+        )));
+        let partitioned = partitioned_index
+            .search(&[&query_vector], Some(&filter), top, None, &stopped)
+            .unwrap();
+        assert!(partitioned[0].is_empty());
+
+        // searches without the partition key use the whole index
+        let expected = sparse_vector_ram_index
+            .search(&[&query_vector], None, top, None, &stopped)
+            .unwrap();
+        let partitioned = partitioned_index
+            .search(&[&query_vector], None, top, None, &stopped)
+            .unwrap();
+        assert_eq!(expected, partitioned);
+    };
+
+    check_partitioned_results(&partitioned_index);
+
+    // partitions are loaded from disk
+    drop(partitioned_index);
+    let partitioned_index = open_partitioned_index(partitioned_index_dir.path());
+    check_partitioned_results(&partitioned_index);
+}
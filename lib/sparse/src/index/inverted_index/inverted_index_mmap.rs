@@ -76,6 +76,10 @@ impl InvertedIndex for InvertedIndexMmap {
     fn vector_count(&self) -> usize {
         self.file_header.vector_count
     }
+
+    fn posting_count(&self) -> usize {
+        self.file_header.posting_count
+    }
 }
 
 impl InvertedIndexMmap {
@@ -78,6 +78,10 @@ impl InvertedIndex for InvertedIndexRam {
     fn vector_count(&self) -> usize {
         self.vector_count
     }
+
+    fn posting_count(&self) -> usize {
+        self.postings.len()
+    }
 }
 
 impl InvertedIndexRam {
@@ -34,4 +34,7 @@ pub trait InvertedIndex: Sized {
 
     /// Number of indexed vectors
     fn vector_count(&self) -> usize;
+
+    /// Number of posting lists, including empty ones
+    fn posting_count(&self) -> usize;
 }
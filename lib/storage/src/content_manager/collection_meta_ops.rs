@@ -1,19 +1,22 @@
 use std::collections::BTreeMap;
+use std::num::NonZeroU32;
 
-use collection::config::{CollectionConfig, ShardingMethod};
+use collection::config::{
+    CollectionConfig, QueryExpansionConfig, RateLimitConfig, ShardingMethod, StrictModeConfig,
+};
 use collection::operations::config_diff::{
-    CollectionParamsDiff, HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff,
+    CollectionParamsDiff, DiffConfig, HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff,
     WalConfigDiff,
 };
 use collection::operations::types::{
-    SparseVectorParams, SparseVectorsConfig, VectorsConfig, VectorsConfigDiff,
+    CollectionResult, SparseVectorParams, SparseVectorsConfig, VectorsConfig, VectorsConfigDiff,
 };
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId, ShardsPlacement};
 use collection::shards::transfer::{ShardTransfer, ShardTransferKey};
 use collection::shards::{replica_set, CollectionId};
 use schemars::JsonSchema;
-use segment::types::{PayloadFieldSchema, PayloadKeyType, QuantizationConfig, ShardKey};
+use segment::types::{Payload, PayloadFieldSchema, PayloadKeyType, QuantizationConfig, ShardKey};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -164,6 +167,21 @@ pub struct CreateCollection {
     /// Sparse vector data config.
     #[validate]
     pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
+    /// Arbitrary user metadata of the collection, e.g. model version and embedding provenance.
+    #[serde(default)]
+    pub metadata: Option<Payload>,
+    /// Restrictions on requests to the collection. If none - no restrictions are applied.
+    #[serde(default)]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
+    /// Max number of requests per second to the collection. If none - requests are not limited.
+    #[serde(default)]
+    #[validate]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Query-time expansion of full-text conditions with synonyms and stop words
+    #[serde(default)]
+    #[validate]
+    pub query_expansion: Option<QueryExpansionConfig>,
 }
 
 /// Operation for creating new collection and (optionally) specify index params
@@ -221,6 +239,21 @@ pub struct UpdateCollection {
     /// Map of sparse vector data parameters to update for each sparse vector.
     #[validate]
     pub sparse_vectors: Option<SparseVectorsConfig>,
+    /// User metadata to replace the current one. If none - it is left unchanged.
+    #[serde(default)]
+    pub metadata: Option<Payload>,
+    /// Strict mode config to replace the current one. If none - it is left unchanged.
+    #[serde(default)]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
+    /// Rate limits to replace the current ones. If none - they are left unchanged.
+    #[serde(default)]
+    #[validate]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Query expansion config to replace the current one. If none - it is left unchanged.
+    #[serde(default)]
+    #[validate]
+    pub query_expansion: Option<QueryExpansionConfig>,
 }
 
 /// Operation for updating parameters of the existing collection
@@ -243,6 +276,10 @@ impl UpdateCollectionOperation {
                 optimizers_config: None,
                 quantization_config: None,
                 sparse_vectors: None,
+                metadata: None,
+                strict_mode_config: None,
+                rate_limit: None,
+                query_expansion: None,
             },
             shard_replica_changes: None,
         }
@@ -323,6 +360,14 @@ pub struct SetShardReplicaState {
     pub from_state: Option<ReplicaState>,
 }
 
+/// Deletes local data of the shard replica and recovers it from other replicas
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct RebuildShardReplica {
+    pub collection_name: String,
+    pub shard_id: ShardId,
+    pub peer_id: PeerId,
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct CreateShardKey {
     pub collection_name: String,
@@ -336,6 +381,30 @@ pub struct DropShardKey {
     pub shard_key: ShardKey,
 }
 
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct SplitShardKey {
+    pub collection_name: String,
+    pub shard_key: ShardKey,
+    /// Placement of the new shards of the key
+    pub placement: ShardsPlacement,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct SetShardReplicationFactor {
+    pub collection_name: String,
+    pub shard_ids: Vec<ShardId>,
+    /// Desired number of replicas, `None` resets to the replication factor of the collection
+    pub replication_factor: Option<NonZeroU32>,
+}
+
+/// Lock options of the whole cluster, applied on all peers through consensus.
+/// If write is locked, all write operations and collection creation are forbidden
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default, PartialEq, Eq, Hash, Clone)]
+pub struct ClusterLocks {
+    pub write: bool,
+    pub error_message: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct CreatePayloadIndex {
     pub collection_name: String,
@@ -359,10 +428,14 @@ pub enum CollectionMetaOperations {
     ChangeAliases(ChangeAliasesOperation),
     TransferShard(CollectionId, ShardTransferOperations),
     SetShardReplicaState(SetShardReplicaState),
+    RebuildShardReplica(RebuildShardReplica),
     CreateShardKey(CreateShardKey),
     DropShardKey(DropShardKey),
+    SplitShardKey(SplitShardKey),
+    SetShardReplicationFactor(SetShardReplicationFactor),
     CreatePayloadIndex(CreatePayloadIndex),
     DropPayloadIndex(DropPayloadIndex),
+    SetClusterLocks(ClusterLocks),
     Nop { token: usize }, // Empty operation
 }
 
@@ -383,6 +456,73 @@ impl From<CollectionConfig> for CreateCollection {
             init_from: None,
             quantization_config: value.quantization_config,
             sparse_vectors: value.params.sparse_vectors,
+            metadata: value.metadata,
+            strict_mode_config: value.strict_mode_config,
+            rate_limit: value.rate_limit,
+            query_expansion: value.query_expansion,
         }
     }
 }
+
+/// Operation for creating a copy of an existing collection
+///
+/// The new collection gets the config of the existing one, except for the given overrides.
+/// Points and payload indexes are transferred between the collections internally.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CloneCollection {
+    /// Name of the new collection
+    #[validate(
+        length(min = 1, max = 255),
+        custom = "common::validation::validate_collection_name"
+    )]
+    pub to: String,
+    /// HNSW parameters to change in the new collection. If none - parameters of the existing
+    /// collection are used.
+    #[validate]
+    pub hnsw_config: Option<HnswConfigDiff>,
+    /// Quantization parameters of the new collection. If none - quantization of the existing
+    /// collection is used.
+    #[serde(default, alias = "quantization")]
+    #[validate]
+    pub quantization_config: Option<QuantizationConfigDiff>,
+}
+
+impl CloneCollection {
+    /// Operation to create the copy of the collection with the given config
+    pub fn into_operation(
+        self,
+        from: CollectionId,
+        config: CollectionConfig,
+    ) -> CollectionResult<CreateCollectionOperation> {
+        let Self {
+            to,
+            hnsw_config,
+            quantization_config,
+        } = self;
+
+        let hnsw_config = match hnsw_config {
+            Some(hnsw_diff) => hnsw_diff.update(&config.hnsw_config)?,
+            None => config.hnsw_config,
+        };
+        let quantization_config = match quantization_config {
+            Some(QuantizationConfigDiff::Scalar(scalar)) => {
+                Some(QuantizationConfig::Scalar(scalar))
+            }
+            Some(QuantizationConfigDiff::Product(product)) => {
+                Some(QuantizationConfig::Product(product))
+            }
+            Some(QuantizationConfigDiff::Binary(binary)) => {
+                Some(QuantizationConfig::Binary(binary))
+            }
+            Some(QuantizationConfigDiff::Disabled(_)) => None,
+            None => config.quantization_config.clone(),
+        };
+
+        let mut create_collection = CreateCollection::from(config);
+        create_collection.hnsw_config = Some(hnsw_config.into());
+        create_collection.quantization_config = quantization_config;
+        create_collection.init_from = Some(InitFrom { collection: from });
+        Ok(CreateCollectionOperation::new(to, create_collection))
+    }
+}
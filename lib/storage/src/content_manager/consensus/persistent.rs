@@ -70,6 +70,13 @@ impl Persistent {
         self.save()
     }
 
+    /// Check if raft state was already initialized in the storage
+    pub fn state_exists(storage_path: impl AsRef<Path>) -> bool {
+        let storage_path = storage_path.as_ref();
+        storage_path.join(STATE_FILE_NAME).exists()
+            || storage_path.join(STATE_FILE_NAME_CBOR).exists()
+    }
+
     /// Returns state and if it was initialized for the first time
     pub fn load_or_init(
         storage_path: impl AsRef<Path>,
@@ -24,6 +24,7 @@ use tokio::time::error::Elapsed;
 use tonic::transport::Uri;
 
 use super::alias_mapping::AliasMapping;
+use super::collection_meta_ops::ClusterLocks;
 use super::consensus_ops::{ConsensusOperations, SnapshotStatus};
 use super::errors::StorageError;
 use super::CollectionContainer;
@@ -53,6 +54,8 @@ pub struct SnapshotData {
 pub struct CollectionsSnapshot {
     pub collections: HashMap<CollectionId, collection_state::State>,
     pub aliases: AliasMapping,
+    #[serde(default)]
+    pub cluster_locks: ClusterLocks,
 }
 
 impl TryFrom<&[u8]> for SnapshotData {
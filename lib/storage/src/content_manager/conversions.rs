@@ -1,3 +1,4 @@
+use api::grpc::conversions::proto_to_payloads;
 use collection::operations::conversions::sharding_method_from_proto;
 use collection::operations::types::SparseVectorsConfig;
 use tonic::Status;
@@ -18,6 +19,8 @@ pub fn error_to_status(error: StorageError) -> tonic::Status {
         StorageError::BadRequest { .. } => tonic::Code::InvalidArgument,
         StorageError::Locked { .. } => tonic::Code::FailedPrecondition,
         StorageError::Timeout { .. } => tonic::Code::DeadlineExceeded,
+        StorageError::RateLimitExceeded { .. } => tonic::Code::ResourceExhausted,
+        StorageError::Forbidden { .. } => tonic::Code::PermissionDenied,
     };
     tonic::Status::new(error_code, format!("{error}"))
 }
@@ -55,6 +58,14 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
                     .sharding_method
                     .map(sharding_method_from_proto)
                     .transpose()?,
+                metadata: if value.metadata.is_empty() {
+                    None
+                } else {
+                    Some(proto_to_payloads(value.metadata)?)
+                },
+                strict_mode_config: None,
+                rate_limit: None,
+                query_expansion: None,
             },
         )))
     }
@@ -84,6 +95,14 @@ impl TryFrom<api::grpc::qdrant::UpdateCollection> for CollectionMetaOperations {
                         config.map.into_iter().map(|(k, v)| (k, v.into())).collect(),
                     )
                 }),
+                metadata: if value.metadata.is_empty() {
+                    None
+                } else {
+                    Some(proto_to_payloads(value.metadata)?)
+                },
+                strict_mode_config: None,
+                rate_limit: None,
+                query_expansion: None,
             },
         )))
     }
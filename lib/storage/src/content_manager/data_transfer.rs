@@ -1,12 +1,15 @@
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
 use collection::collection::Collection;
 use collection::operations::point_ops::{
-    PointInsertOperationsInternal, PointOperations, PointStruct, WriteOrdering,
+    CopyPoints, PointInsertOperationsInternal, PointOperations, PointStruct, WriteOrdering,
 };
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
-use collection::operations::types::{CollectionError, CollectionResult, ScrollRequestInternal};
+use collection::operations::types::{
+    CollectionError, CollectionResult, ScrollRequestInternal, UpdateResult,
+};
 use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
@@ -84,6 +87,8 @@ async fn replicate_shard_data(
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: WithVector::Bool(true),
+            order_by: None,
+            query: None,
         };
 
         let collections_read = collections.read().await;
@@ -129,6 +134,78 @@ async fn replicate_shard_data(
     Ok(())
 }
 
+/// Copies points, matching the filter of the request, from `source` into the target collection.
+///
+/// Points are read shard by shard in batches, so the data never leaves the cluster.
+/// Each batch is written with `upsert`, which is expected to apply the same checks as any other
+/// update of the target collection.
+/// Returns the number of copied points.
+pub async fn copy_points<F, Fut, E>(
+    source: &Collection,
+    request: &CopyPoints,
+    mut upsert: F,
+) -> Result<usize, E>
+where
+    F: FnMut(CollectionUpdateOperations) -> Fut,
+    Fut: Future<Output = Result<UpdateResult, E>>,
+    E: From<CollectionError>,
+{
+    let shard_ids: Vec<ShardId> = source.state().await.shards.into_keys().collect();
+    let mut copied = 0;
+
+    for shard_id in shard_ids {
+        let mut offset = None;
+
+        loop {
+            let scroll_request = ScrollRequestInternal {
+                offset,
+                limit: Some(MIGRATION_BATCH_SIZE),
+                filter: request.filter.clone(),
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: WithVector::Bool(true),
+                order_by: None,
+                query: None,
+            };
+
+            let scroll_result = source
+                .scroll_by(
+                    scroll_request,
+                    None,
+                    &ShardSelectorInternal::ShardId(shard_id),
+                )
+                .await?;
+
+            offset = scroll_result.next_page_offset;
+
+            if scroll_result.points.is_empty() {
+                break;
+            }
+
+            let records: Vec<_> = scroll_result
+                .points
+                .into_iter()
+                .map(|point| PointStruct {
+                    id: point.id,
+                    vector: request.map_vectors(point.vector.unwrap()),
+                    payload: point.payload,
+                })
+                .collect();
+            copied += records.len();
+
+            let upsert_request = CollectionUpdateOperations::PointOperation(
+                PointOperations::UpsertPoints(PointInsertOperationsInternal::PointsList(records)),
+            );
+            upsert(upsert_request).await?;
+
+            if offset.is_none() {
+                break;
+            }
+        }
+    }
+
+    Ok(copied)
+}
+
 async fn wait_all_shards_active(
     collections: Arc<RwLock<Collections>>,
     collection_name: &CollectionId,
@@ -24,6 +24,10 @@ pub enum StorageError {
     Locked { description: String },
     #[error("Timeout: {description}")]
     Timeout { description: String },
+    #[error("Rate limit exceeded: {description}")]
+    RateLimitExceeded { description: String },
+    #[error("Forbidden: {description}")]
+    Forbidden { description: String },
 }
 
 impl StorageError {
@@ -46,6 +50,12 @@ impl StorageError {
         }
     }
 
+    pub fn forbidden(description: impl Into<String>) -> StorageError {
+        StorageError::Forbidden {
+            description: description.into(),
+        }
+    }
+
     /// Used to override the `description` field of the resulting `StorageError`
     pub fn from_inconsistent_shard_failure(
         err: CollectionError,
@@ -109,6 +109,10 @@ pub mod consensus_ops {
                     hnsw_config: None,
                     quantization_config: None,
                     sparse_vectors: None,
+                    metadata: None,
+                    strict_mode_config: None,
+                    rate_limit: None,
+                    query_expansion: None,
                 },
             );
             operation
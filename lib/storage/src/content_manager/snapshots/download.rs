@@ -1,10 +1,12 @@
 use std::path::{Path, PathBuf};
 
-use futures::StreamExt;
+use collection::operations::snapshot_ops::{get_checksum_path, read_checksum};
+use futures::{StreamExt, TryStreamExt};
 use reqwest;
 use tempfile::TempPath;
 use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+use tokio_util::io::StreamReader;
 use url::Url;
 use uuid::Uuid;
 
@@ -58,6 +60,60 @@ async fn download_file(
     Ok(temp_path)
 }
 
+/// Open a remote snapshot at `url` for sequential reading, without downloading it to disk first
+pub async fn snapshot_reader(
+    client: &reqwest::Client,
+    url: &Url,
+) -> Result<impl AsyncRead + Send + Unpin + 'static, StorageError> {
+    let response = client.get(url.clone()).send().await?;
+
+    if !response.status().is_success() {
+        return Err(StorageError::bad_input(format!(
+            "Failed to download snapshot from {}: status - {}",
+            url,
+            response.status()
+        )));
+    }
+
+    let stream = response
+        .bytes_stream()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
+
+    Ok(StreamReader::new(stream))
+}
+
+/// Get SHA256 of the snapshot at `url` from the `<snapshot>.checksum` file stored alongside it
+///
+/// Returns `None` if there is no checksum file, e.g. for snapshots created by older versions.
+pub async fn download_checksum(
+    client: &reqwest::Client,
+    url: &Url,
+) -> Result<Option<String>, StorageError> {
+    match url.scheme() {
+        "file" => match url.to_file_path() {
+            Ok(local_path) => Ok(read_checksum(&get_checksum_path(&local_path)).await?),
+            // Invalid path is reported when reading the snapshot itself
+            Err(_) => Ok(None),
+        },
+        "http" | "https" => {
+            let mut checksum_url = url.clone();
+            checksum_url.set_path(&format!("{}.checksum", url.path()));
+
+            let response = client.get(checksum_url.clone()).send().await?;
+            if !response.status().is_success() {
+                log::debug!(
+                    "No snapshot checksum at {checksum_url}: status - {}",
+                    response.status(),
+                );
+                return Ok(None);
+            }
+
+            Ok(Some(response.text().await?.trim().to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
 /// Download a snapshot from the given URI.
 ///
 /// May returen a `TempPath` if a file was downloaded from a remote source. If it is dropped the
@@ -91,10 +91,7 @@ async fn _do_delete_collection_snapshot(
     snapshot_name: &str,
 ) -> Result<bool, StorageError> {
     let collection = dispatcher.get_collection(collection_name).await?;
-    let file_name = collection.get_snapshot_path(snapshot_name).await?;
-    log::info!("Deleting collection snapshot {:?}", file_name);
-    tokio::fs::remove_file(file_name).await?;
-    Ok(true)
+    Ok(collection.delete_snapshot(snapshot_name).await?)
 }
 
 pub async fn do_list_full_snapshots(
@@ -167,22 +164,29 @@ async fn _do_create_full_snapshot(
 
     let full_snapshot_path = snapshot_dir.join(&snapshot_name);
 
+    // Collection snapshots may be stored remotely, get local copies of them to archive
+    let download_dir = dispatcher.toc().snapshots_download_tempdir()?;
+    let mut collection_snapshot_files = Vec::with_capacity(created_snapshots.len());
+    for (collection_name, snapshot_details) in &created_snapshots {
+        let collection = dispatcher.get_collection(collection_name).await?;
+        let (snapshot_path, snapshot_temp_path) = collection
+            .get_snapshot_file(&snapshot_details.name, download_dir.path())
+            .await?;
+        collection_snapshot_files.push((
+            snapshot_details.name.clone(),
+            snapshot_path,
+            snapshot_temp_path,
+        ));
+    }
+
     let config_path_clone = config_path.clone();
     let full_snapshot_path_clone = full_snapshot_path.clone();
-    let created_snapshots_clone: Vec<_> = created_snapshots
-        .iter()
-        .map(|(x, y)| (x.to_string(), y.to_owned()))
-        .collect();
     let archiving = tokio::task::spawn_blocking(move || {
         // have to use std here, cause TarBuilder is not async
         let file = std::fs::File::create(&full_snapshot_path_clone)?;
         let mut builder = TarBuilder::new(file);
-        for (collection_name, snapshot_details) in created_snapshots_clone {
-            let snapshot_path = snapshot_dir
-                .join(collection_name)
-                .join(&snapshot_details.name);
-            builder.append_path_with_name(&snapshot_path, &snapshot_details.name)?;
-            std::fs::remove_file(snapshot_path)?;
+        for (snapshot_name, snapshot_path, _snapshot_temp_path) in &collection_snapshot_files {
+            builder.append_path_with_name(snapshot_path, snapshot_name)?;
         }
         builder.append_path_with_name(&config_path_clone, "config.json")?;
 
@@ -192,5 +196,10 @@ async fn _do_create_full_snapshot(
     archiving.await??;
     tokio::fs::remove_file(&config_path).await?;
 
+    for (collection_name, snapshot_details) in &created_snapshots {
+        let collection = dispatcher.get_collection(collection_name).await?;
+        collection.delete_snapshot(&snapshot_details.name).await?;
+    }
+
     Ok(get_snapshot_description(&full_snapshot_path).await?)
 }
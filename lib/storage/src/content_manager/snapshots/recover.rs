@@ -1,15 +1,25 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
 use collection::collection::Collection;
+use collection::common::sha_256::{hashes_equal, HashingReader};
 use collection::config::CollectionConfig;
+use collection::operations::config_diff::CollectionParamsDiff;
 use collection::operations::snapshot_ops::{SnapshotPriority, SnapshotRecover};
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::shards::shard_config::ShardType;
 use collection::shards::shard_versioning::latest_shard_paths;
+use tempfile::TempDir;
+use tokio::io::AsyncRead;
+use tokio_util::io::SyncIoBridge;
 
 use crate::content_manager::collection_meta_ops::{
-    CollectionMetaOperations, CreateCollectionOperation,
+    CollectionMetaOperations, CreateCollectionOperation, UpdateCollectionOperation,
+};
+use crate::content_manager::snapshots::download::{
+    download_checksum, download_snapshot, snapshot_reader,
 };
-use crate::content_manager::snapshots::download::download_snapshot;
 use crate::dispatcher::Dispatcher;
 use crate::{StorageError, TableOfContent};
 
@@ -64,31 +74,142 @@ pub async fn do_recover_from_snapshot(
     }
 }
 
+/// Recover collection from a snapshot archive, read sequentially from `reader`
+///
+/// The archive is unpacked as it is received, so the snapshot itself is never stored on disk.
+/// Unpacking completes before returning even if `wait` is false, as the reader may be bound to
+/// the lifetime of a request. Only the following recovery of shards runs in the background.
+pub async fn do_recover_from_snapshot_reader(
+    dispatcher: &Dispatcher,
+    collection_name: &str,
+    reader: impl AsyncRead + Send + Unpin + 'static,
+    priority: Option<SnapshotPriority>,
+    checksum: Option<String>,
+    wait: bool,
+) -> Result<bool, StorageError> {
+    let tmp_collection_dir =
+        unpack_snapshot_reader(dispatcher.toc(), collection_name, reader, checksum).await?;
+
+    let dispatch = dispatcher.clone();
+    let collection_name = collection_name.to_string();
+    let recovery = tokio::spawn(async move {
+        recover_from_unpacked_snapshot(dispatch, &collection_name, tmp_collection_dir, priority)
+            .await
+    });
+    if wait {
+        Ok(recovery.await??)
+    } else {
+        Ok(true)
+    }
+}
+
+/// Open a snapshot of `collection_name` as a new read-only collection `view_name`
+///
+/// The view is restored from the snapshot next to the source collection, which is left intact
+/// and keeps receiving updates. Updates of the view itself are rejected.
+/// Once not needed anymore, the view is deleted as a regular collection.
+pub async fn do_create_snapshot_view(
+    dispatcher: &Dispatcher,
+    collection_name: &str,
+    snapshot_name: &str,
+    view_name: &str,
+    wait: bool,
+) -> Result<bool, StorageError> {
+    let toc = dispatcher.toc();
+    if toc.get_collection(view_name).await.is_ok() {
+        return Err(StorageError::bad_input(format!(
+            "Collection `{view_name}` already exists!"
+        )));
+    }
+
+    let collection = toc.get_collection(collection_name).await?;
+    let temp_dir = toc.optional_temp_or_snapshot_temp_path()?;
+    let (snapshot_path, snapshot_temp_path) = collection
+        .get_snapshot_file(snapshot_name, &temp_dir)
+        .await?;
+
+    let dispatch = dispatcher.clone();
+    let view_name = view_name.to_string();
+    let view = tokio::spawn(async move {
+        let toc = dispatch.toc();
+        let tmp_collection_dir = unpack_snapshot_file(toc, &view_name, snapshot_path, None).await?;
+        // Temporary copy of a remotely stored snapshot is not needed once unpacked
+        drop(snapshot_temp_path);
+
+        // Create the view read-only before any data is recovered into it
+        let snapshot_config = CollectionConfig::load(tmp_collection_dir.path())?;
+        let operation = CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
+            view_name.clone(),
+            snapshot_config.into(),
+        ));
+        dispatch.submit_collection_meta_op(operation, None).await?;
+
+        let mut operation = UpdateCollectionOperation::new_empty(view_name.clone());
+        operation.update_collection.params = Some(CollectionParamsDiff {
+            read_only: Some(true),
+            ..Default::default()
+        });
+        dispatch
+            .submit_collection_meta_op(CollectionMetaOperations::UpdateCollection(operation), None)
+            .await?;
+
+        recover_from_unpacked_snapshot(dispatch, &view_name, tmp_collection_dir, None).await
+    });
+    if wait {
+        Ok(view.await??)
+    } else {
+        Ok(true)
+    }
+}
+
 async fn _do_recover_from_snapshot(
     dispatcher: Dispatcher,
     collection_name: &str,
     source: SnapshotRecover,
     client: &reqwest::Client,
 ) -> Result<bool, StorageError> {
-    let SnapshotRecover { location, priority } = source;
+    let SnapshotRecover {
+        location,
+        priority,
+        checksum,
+        verify_checksum,
+    } = source;
     let toc = dispatcher.toc();
 
-    let this_peer_id = toc.this_peer_id;
-
-    let is_distributed = toc.is_distributed();
+    let checksum = if verify_checksum.unwrap_or(true) {
+        match checksum {
+            Some(checksum) => Some(checksum),
+            None => download_checksum(client, &location).await?,
+        }
+    } else {
+        None
+    };
 
-    let download_dir = toc.snapshots_download_tempdir()?;
+    let tmp_collection_dir = match location.scheme() {
+        // Unpack remote snapshots while downloading, so they don't need temporary disk space
+        "http" | "https" => {
+            log::debug!("Streaming snapshot from {location}");
+            let reader = snapshot_reader(client, &location).await?;
+            unpack_snapshot_reader(toc, collection_name, reader, checksum).await?
+        }
+        _ => {
+            let download_dir = toc.snapshots_download_tempdir()?;
 
-    log::debug!(
-        "Downloading snapshot from {location} to {}",
-        download_dir.path().display(),
-    );
+            let (snapshot_path, _snapshot_temp_path) =
+                download_snapshot(client, location, download_dir.path()).await?;
 
-    let (snapshot_path, snapshot_temp_path) =
-        download_snapshot(client, location, download_dir.path()).await?;
+            unpack_snapshot_file(toc, collection_name, snapshot_path, checksum).await?
+        }
+    };
 
-    log::debug!("Snapshot downloaded to {}", snapshot_path.display());
+    recover_from_unpacked_snapshot(dispatcher, collection_name, tmp_collection_dir, priority).await
+}
 
+/// Create a temporary directory to unpack a snapshot of `collection_name` into
+fn create_recovery_dir(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<TempDir, StorageError> {
     let temp_storage_path = toc.optional_temp_or_storage_temp_path()?;
 
     let tmp_collection_dir = tempfile::Builder::new()
@@ -96,27 +217,122 @@ async fn _do_recover_from_snapshot(
         .tempdir_in(temp_storage_path)?;
 
     log::debug!(
-        "Recovering collection {collection_name} from snapshot {}",
-        snapshot_path.display(),
+        "Unpacking snapshot to {}",
+        tmp_collection_dir.path().display(),
     );
 
+    Ok(tmp_collection_dir)
+}
+
+/// Unpack snapshot archive from `reader` into `target_dir`
+///
+/// If `checksum` is given, SHA256 of the whole archive must match it.
+///
+/// This method performs blocking IO.
+fn unpack_snapshot(
+    reader: impl Read,
+    target_dir: &Path,
+    checksum: Option<&str>,
+    this_peer_id: PeerId,
+    is_distributed: bool,
+) -> Result<(), StorageError> {
+    let Some(checksum) = checksum else {
+        Collection::restore_snapshot_from_reader(reader, target_dir, this_peer_id, is_distributed)?;
+        return Ok(());
+    };
+
+    let mut reader = HashingReader::new(reader);
+    Collection::restore_snapshot_from_reader(
+        &mut reader,
+        target_dir,
+        this_peer_id,
+        is_distributed,
+    )?;
+
+    // Archive reader may stop before the padding at the end of the archive
+    reader.drain()?;
+    let actual_checksum = reader.finalize();
+    if !hashes_equal(&actual_checksum, checksum) {
+        return Err(StorageError::bad_input(format!(
+            "Snapshot checksum mismatch: expected {checksum}, got {actual_checksum}"
+        )));
+    }
+
+    log::debug!("Snapshot checksum {actual_checksum} verified");
+    Ok(())
+}
+
+async fn unpack_snapshot_file(
+    toc: &TableOfContent,
+    collection_name: &str,
+    snapshot_path: PathBuf,
+    checksum: Option<String>,
+) -> Result<TempDir, StorageError> {
     log::debug!(
-        "Unpacking snapshot to {}",
-        tmp_collection_dir.path().display(),
+        "Recovering collection {collection_name} from snapshot {}",
+        snapshot_path.display(),
     );
 
+    let tmp_collection_dir = create_recovery_dir(toc, collection_name)?;
+
+    let this_peer_id = toc.this_peer_id;
+    let is_distributed = toc.is_distributed();
     let tmp_collection_dir_clone = tmp_collection_dir.path().to_path_buf();
     let restoring = tokio::task::spawn_blocking(move || {
         // Unpack snapshot collection to the target folder
-        Collection::restore_snapshot(
-            &snapshot_path,
+        let archive_file = std::fs::File::open(&snapshot_path)?;
+        unpack_snapshot(
+            archive_file,
             &tmp_collection_dir_clone,
+            checksum.as_deref(),
             this_peer_id,
             is_distributed,
         )
     });
     restoring.await??;
 
+    Ok(tmp_collection_dir)
+}
+
+async fn unpack_snapshot_reader(
+    toc: &TableOfContent,
+    collection_name: &str,
+    reader: impl AsyncRead + Send + Unpin + 'static,
+    checksum: Option<String>,
+) -> Result<TempDir, StorageError> {
+    log::debug!("Recovering collection {collection_name} from snapshot stream");
+
+    let tmp_collection_dir = create_recovery_dir(toc, collection_name)?;
+
+    let this_peer_id = toc.this_peer_id;
+    let is_distributed = toc.is_distributed();
+    let tmp_collection_dir_clone = tmp_collection_dir.path().to_path_buf();
+    // Blocking tar reader pulls data from the async stream through the bridge
+    let reader = SyncIoBridge::new(reader);
+    let restoring = tokio::task::spawn_blocking(move || {
+        // Unpack snapshot collection to the target folder
+        unpack_snapshot(
+            reader,
+            &tmp_collection_dir_clone,
+            checksum.as_deref(),
+            this_peer_id,
+            is_distributed,
+        )
+    });
+    restoring.await??;
+
+    Ok(tmp_collection_dir)
+}
+
+async fn recover_from_unpacked_snapshot(
+    dispatcher: Dispatcher,
+    collection_name: &str,
+    tmp_collection_dir: TempDir,
+    priority: Option<SnapshotPriority>,
+) -> Result<bool, StorageError> {
+    let toc = dispatcher.toc();
+    let this_peer_id = toc.this_peer_id;
+
     let snapshot_config = CollectionConfig::load(tmp_collection_dir.path())?;
     snapshot_config.validate_and_warn();
 
@@ -301,12 +517,5 @@ async fn _do_recover_from_snapshot(
     // Remove tmp collection dir
     tokio::fs::remove_dir_all(&tmp_collection_dir).await?;
 
-    // Remove snapshot after recovery if downloaded
-    if let Some(path) = snapshot_temp_path {
-        if let Err(err) = path.close() {
-            log::error!("Failed to remove downloaded collection snapshot after recovery: {err}");
-        }
-    }
-
     Ok(true)
 }
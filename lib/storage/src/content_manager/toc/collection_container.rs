@@ -122,6 +122,7 @@ impl TableOfContent {
         consensus_manager::CollectionsSnapshot {
             collections,
             aliases: self.alias_persistence.read().await.state().clone(),
+            cluster_locks: self.get_cluster_locks(),
         }
     }
 
@@ -239,6 +240,8 @@ impl TableOfContent {
                 .await
                 .apply_state(data.aliases)?;
 
+            self.set_cluster_locks(data.cluster_locks)?;
+
             Ok(())
         })
     }
@@ -75,6 +75,10 @@ impl TableOfContent {
                 log::debug!("Set shard replica state {:?}", operation);
                 self.set_shard_replica_state(operation).await.map(|()| true)
             }
+            CollectionMetaOperations::RebuildShardReplica(operation) => {
+                log::debug!("Rebuild shard replica {:?}", operation);
+                self.rebuild_shard_replica(operation).await.map(|()| true)
+            }
             CollectionMetaOperations::Nop { .. } => Ok(true),
             CollectionMetaOperations::CreateShardKey(create_shard_key) => {
                 log::debug!("Create shard key {:?}", create_shard_key);
@@ -84,6 +88,16 @@ impl TableOfContent {
                 log::debug!("Drop shard key {:?}", drop_shard_key);
                 self.drop_shard_key(drop_shard_key).await.map(|()| true)
             }
+            CollectionMetaOperations::SplitShardKey(split_shard_key) => {
+                log::debug!("Split shard key {:?}", split_shard_key);
+                self.split_shard_key(split_shard_key).await.map(|()| true)
+            }
+            CollectionMetaOperations::SetShardReplicationFactor(operation) => {
+                log::debug!("Set shard replication factor {:?}", operation);
+                self.set_shard_replication_factor(operation)
+                    .await
+                    .map(|()| true)
+            }
             CollectionMetaOperations::CreatePayloadIndex(create_payload_index) => {
                 log::debug!("Create payload index {:?}", create_payload_index);
                 self.create_payload_index(create_payload_index)
@@ -96,6 +110,10 @@ impl TableOfContent {
                     .await
                     .map(|()| true)
             }
+            CollectionMetaOperations::SetClusterLocks(cluster_locks) => {
+                log::info!("Set cluster locks {:?}", cluster_locks);
+                self.set_cluster_locks(cluster_locks).map(|()| true)
+            }
         }
     }
 
@@ -111,6 +129,10 @@ impl TableOfContent {
             optimizers_config,
             quantization_config,
             sparse_vectors,
+            metadata,
+            strict_mode_config,
+            rate_limit,
+            query_expansion,
         } = operation.update_collection;
         let collection = self.get_collection(&operation.collection_name).await?;
         let mut recreate_optimizers = false;
@@ -121,6 +143,7 @@ impl TableOfContent {
         }
         if let Some(diff) = params {
             collection.update_params_from_diff(diff).await?;
+            collection.ensure_point_expiration_index().await?;
             recreate_optimizers = true;
         }
         if let Some(diff) = hnsw_config {
@@ -141,6 +164,20 @@ impl TableOfContent {
             collection.update_sparse_vectors_from_other(&diff).await?;
             recreate_optimizers = true;
         }
+        if let Some(metadata) = metadata {
+            collection.update_metadata(metadata).await?;
+        }
+        if let Some(strict_mode_config) = strict_mode_config {
+            collection
+                .update_strict_mode_config(strict_mode_config)
+                .await?;
+        }
+        if let Some(rate_limit) = rate_limit {
+            collection.update_rate_limit(rate_limit).await?;
+        }
+        if let Some(query_expansion) = query_expansion {
+            collection.update_query_expansion(query_expansion).await?;
+        }
         if let Some(changes) = replica_changes {
             collection.handle_replica_changes(changes).await?;
         }
@@ -162,6 +199,8 @@ impl TableOfContent {
                 .await
                 .remove_collection(collection_name)?;
 
+            self.collection_rate_limiters.lock().remove(collection_name);
+
             let path = self.get_collection_path(collection_name);
             drop(removed);
 
@@ -398,6 +437,17 @@ impl TableOfContent {
         Ok(())
     }
 
+    async fn rebuild_shard_replica(
+        &self,
+        operation: RebuildShardReplica,
+    ) -> Result<(), StorageError> {
+        self.get_collection(&operation.collection_name)
+            .await?
+            .rebuild_shard_replica(operation.shard_id, operation.peer_id)
+            .await?;
+        Ok(())
+    }
+
     async fn create_shard_key(&self, operation: CreateShardKey) -> Result<(), StorageError> {
         self.get_collection(&operation.collection_name)
             .await?
@@ -414,6 +464,25 @@ impl TableOfContent {
         Ok(())
     }
 
+    async fn split_shard_key(&self, operation: SplitShardKey) -> Result<(), StorageError> {
+        self.get_collection(&operation.collection_name)
+            .await?
+            .split_shard_key(operation.shard_key, operation.placement)
+            .await?;
+        Ok(())
+    }
+
+    async fn set_shard_replication_factor(
+        &self,
+        operation: SetShardReplicationFactor,
+    ) -> Result<(), StorageError> {
+        self.get_collection(&operation.collection_name)
+            .await?
+            .set_shard_replication_factor(&operation.shard_ids, operation.replication_factor)
+            .await?;
+        Ok(())
+    }
+
     async fn create_payload_index(
         &self,
         operation: CreatePayloadIndex,
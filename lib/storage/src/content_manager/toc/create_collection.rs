@@ -46,6 +46,10 @@ impl TableOfContent {
             init_from,
             quantization_config,
             sparse_vectors,
+            metadata,
+            strict_mode_config,
+            rate_limit,
+            query_expansion,
         } = operation;
 
         self.collections
@@ -125,6 +129,14 @@ impl TableOfContent {
                 },
             )?,
             read_fan_out_factor: None,
+            on_disk_payload_backend: None,
+            maintenance_windows: None,
+            payload_compression: None,
+            payload_blobs: None,
+            kv_store: None,
+            point_timestamps: None,
+            point_expiration: None,
+            read_only: None,
         };
         let wal_config = match wal_config_diff {
             None => self.storage_config.wal.clone(),
@@ -157,6 +169,10 @@ impl TableOfContent {
             optimizer_config: optimizers_config,
             hnsw_config,
             quantization_config,
+            metadata,
+            strict_mode_config,
+            rate_limit,
+            query_expansion,
         };
         let collection = Collection::new(
             collection_name.to_string(),
@@ -204,6 +220,11 @@ impl TableOfContent {
                 .await?;
         }
 
+        self.get_collection(collection_name)
+            .await?
+            .ensure_point_expiration_index()
+            .await?;
+
         if let Some(init_from) = init_from {
             self.run_data_initialization(init_from.collection, collection_name.to_string())
                 .await;
@@ -1,6 +1,9 @@
 use std::sync::atomic;
 
+use collection::operations::types::CollectionError;
+
 use super::TableOfContent;
+use crate::content_manager::collection_meta_ops::ClusterLocks;
 use crate::content_manager::errors::StorageError;
 
 pub const DEFAULT_WRITE_LOCK_ERROR_MESSAGE: &str = "Write operations are forbidden";
@@ -33,4 +36,35 @@ impl TableOfContent {
             .store(is_write_locked, atomic::Ordering::Relaxed);
         *self.lock_error_message.lock() = error_message;
     }
+
+    pub fn get_cluster_locks(&self) -> ClusterLocks {
+        self.cluster_locks.read().clone()
+    }
+
+    /// Returns an error if the write lock of the cluster is set
+    ///
+    /// Cluster locks are the same on all peers, so it's enough to check them on the peer, which
+    /// received the request. Updates forwarded between peers and shard transfers are not blocked.
+    pub fn check_cluster_write_lock(&self) -> Result<(), StorageError> {
+        let cluster_locks = self.cluster_locks.read();
+        if cluster_locks.write {
+            return Err(StorageError::Locked {
+                description: cluster_locks
+                    .error_message
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_WRITE_LOCK_ERROR_MESSAGE.to_string()),
+            });
+        }
+        Ok(())
+    }
+
+    pub(super) fn set_cluster_locks(
+        &self,
+        cluster_locks: ClusterLocks,
+    ) -> Result<(), StorageError> {
+        self.cluster_locks
+            .write(|locks| *locks = cluster_locks)
+            .map_err(CollectionError::from)?;
+        Ok(())
+    }
 }
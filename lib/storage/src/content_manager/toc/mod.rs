@@ -3,6 +3,7 @@ mod collection_meta_ops;
 mod create_collection;
 mod locks;
 mod point_ops;
+mod rate_limiting;
 mod snapshots;
 mod temp_directories;
 pub mod transfer;
@@ -18,14 +19,15 @@ use std::time::Duration;
 
 use api::grpc::qdrant::qdrant_internal_client::QdrantInternalClient;
 use api::grpc::qdrant::WaitOnConsensusCommitRequest;
-use api::grpc::transport_channel_pool::AddTimeout;
+use api::grpc::transport_channel_pool::{AddTimeout, WithCompression};
 use collection::collection::{Collection, RequestShardTransfer};
 use collection::config::{default_replication_factor, CollectionConfig};
 use collection::operations::types::*;
+use collection::save_on_disk::SaveOnDisk;
 use collection::shards::channel_service::ChannelService;
-use collection::shards::replica_set;
 use collection::shards::replica_set::{AbortShardTransfer, ReplicaState};
 use collection::shards::shard::{PeerId, ShardId};
+use collection::shards::{replica_set, CollectionId};
 use collection::telemetry::CollectionTelemetry;
 use futures::future::try_join_all;
 use futures::Future;
@@ -36,9 +38,10 @@ use tonic::codegen::InterceptedService;
 use tonic::transport::Channel;
 use tonic::Status;
 
+use self::rate_limiting::CollectionRateLimiters;
 use self::transfer::ShardTransferDispatcher;
 use crate::content_manager::alias_mapping::AliasPersistence;
-use crate::content_manager::collection_meta_ops::CreateCollectionOperation;
+use crate::content_manager::collection_meta_ops::{ClusterLocks, CreateCollectionOperation};
 use crate::content_manager::collections_ops::{Checker, Collections};
 use crate::content_manager::consensus::operation_sender::OperationSender;
 use crate::content_manager::errors::StorageError;
@@ -47,6 +50,7 @@ use crate::types::{PeerAddressById, StorageConfig};
 use crate::ConsensusOperations;
 
 pub const ALIASES_PATH: &str = "aliases";
+pub const CLUSTER_LOCKS_FILE: &str = "cluster_locks.json";
 pub const COLLECTIONS_DIR: &str = "collections";
 pub const FULL_SNAPSHOT_FILE_NAME: &str = "full-snapshot";
 
@@ -66,6 +70,8 @@ pub struct TableOfContent {
     consensus_proposal_sender: Option<OperationSender>,
     is_write_locked: AtomicBool,
     lock_error_message: parking_lot::Mutex<Option<String>>,
+    /// Locks of the whole cluster, set through consensus
+    cluster_locks: SaveOnDisk<ClusterLocks>,
     /// Prevent DDoS of too many concurrent updates in distributed mode.
     /// One external update usually triggers multiple internal updates, which breaks internal
     /// timings. For example, the health check timing and consensus timing.
@@ -77,6 +83,8 @@ pub struct TableOfContent {
     collection_create_lock: Mutex<()>,
     /// Dispatcher for shard transfer to access consensus.
     shard_transfer_dispatcher: parking_lot::Mutex<Option<ShardTransferDispatcher>>,
+    /// Rate limiters of collections with configured rate limits, created on first request
+    collection_rate_limiters: parking_lot::Mutex<HashMap<CollectionId, CollectionRateLimiters>>,
 }
 
 impl TableOfContent {
@@ -160,6 +168,9 @@ impl TableOfContent {
         let alias_path = Path::new(&storage_config.storage_path).join(ALIASES_PATH);
         let alias_persistence =
             AliasPersistence::open(alias_path).expect("Can't open database by the provided config");
+        let cluster_locks_path = Path::new(&storage_config.storage_path).join(CLUSTER_LOCKS_FILE);
+        let cluster_locks =
+            SaveOnDisk::load_or_init(cluster_locks_path).expect("Can't load cluster locks");
 
         let rate_limiter = match storage_config.performance.update_rate_limit {
             Some(limit) => Some(Semaphore::new(limit)),
@@ -191,9 +202,11 @@ impl TableOfContent {
             consensus_proposal_sender,
             is_write_locked: AtomicBool::new(false),
             lock_error_message: parking_lot::Mutex::new(None),
+            cluster_locks,
             update_rate_limiter: rate_limiter,
             collection_create_lock: Default::default(),
             shard_transfer_dispatcher: Default::default(),
+            collection_rate_limiters: Default::default(),
         }
     }
 
@@ -660,11 +673,13 @@ impl TableOfContent {
             .get(&peer_id)
             .ok_or_else(|| CollectionError::service_error("Address for peer ID is not found."))?
             .clone();
+        let compression = self.channel_service.channel_pool.compression();
         self.channel_service
             .channel_pool
             .with_channel(&address, |channel| {
                 let client = QdrantInternalClient::new(channel);
                 let client = client.max_decoding_message_size(usize::MAX);
+                let client = client.with_compression(compression);
                 f(client)
             })
             .await
@@ -1,18 +1,21 @@
 use std::time::Duration;
 
+use collection::collection::cursor::CollectionCursor;
 use collection::collection::Collection;
 use collection::grouping::group_by::GroupRequest;
 use collection::grouping::GroupBy;
 use collection::operations::consistency_params::ReadConsistency;
-use collection::operations::point_ops::WriteOrdering;
+use collection::operations::point_ops::{CopyPoints, CopyPointsResult, WriteOrdering};
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::*;
 use collection::operations::CollectionUpdateOperations;
+use collection::shards::local_shard_cursor::CursorQuery;
 use collection::{discovery, recommendations};
 use futures::future::try_join_all;
-use segment::types::{ScoredPoint, ShardKey};
+use segment::types::{ScoredPoint, ShardKey, WithPayloadInterface};
 
 use super::TableOfContent;
+use crate::content_manager::data_transfer;
 use crate::content_manager::errors::StorageError;
 
 impl TableOfContent {
@@ -29,12 +32,17 @@ impl TableOfContent {
     pub async fn recommend(
         &self,
         collection_name: &str,
-        request: RecommendRequestInternal,
+        mut request: RecommendRequestInternal,
         read_consistency: Option<ReadConsistency>,
         shard_selector: ShardSelectorInternal,
         timeout: Option<Duration>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        if !shard_selector.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
         recommendations::recommend_by(
             request,
             &collection,
@@ -60,11 +68,14 @@ impl TableOfContent {
     pub async fn recommend_batch(
         &self,
         collection_name: &str,
-        requests: Vec<(RecommendRequestInternal, ShardSelectorInternal)>,
+        mut requests: Vec<(RecommendRequestInternal, ShardSelectorInternal)>,
         read_consistency: Option<ReadConsistency>,
         timeout: Option<Duration>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        self.check_rate_limit(&collection, false).await?;
+        collection.check_strict_mode(&requests).await?;
+        collection.expand_query(&mut requests).await;
         recommendations::recommend_batch_by(
             requests,
             &collection,
@@ -93,12 +104,17 @@ impl TableOfContent {
     pub async fn core_search_batch(
         &self,
         collection_name: &str,
-        request: CoreSearchRequestBatch,
+        mut request: CoreSearchRequestBatch,
         read_consistency: Option<ReadConsistency>,
         shard_selection: ShardSelectorInternal,
         timeout: Option<Duration>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
         collection
             .core_search_batch(request, read_consistency, shard_selection, timeout)
             .await
@@ -120,17 +136,86 @@ impl TableOfContent {
     pub async fn count(
         &self,
         collection_name: &str,
-        request: CountRequestInternal,
+        mut request: CountRequestInternal,
         read_consistency: Option<ReadConsistency>,
         shard_selection: ShardSelectorInternal,
     ) -> Result<CountResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
         collection
             .count(request, read_consistency, &shard_selection)
             .await
             .map_err(|err| err.into())
     }
 
+    /// Count points per distinct value of a keyword payload field.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we count
+    /// * `request` - [`FacetRequestInternal`]
+    /// * `shard_selection` - which local shard to use
+    ///
+    /// # Result
+    ///
+    /// The most frequent values with the number of points having them.
+    ///
+    pub async fn facet(
+        &self,
+        collection_name: &str,
+        mut request: FacetRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: ShardSelectorInternal,
+    ) -> Result<FacetResult, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
+        collection
+            .facet(request, read_consistency, &shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Sample points and compute distances to their nearest neighbours within the sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `request` - [`SearchMatrixRequestInternal`]
+    /// * `shard_selection` - which local shard to use
+    /// * `timeout` - how long to wait for the search
+    ///
+    /// # Result
+    ///
+    /// Sampled point ids with the nearest neighbours of each of them.
+    ///
+    pub async fn search_points_matrix(
+        &self,
+        collection_name: &str,
+        mut request: SearchMatrixRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: ShardSelectorInternal,
+        timeout: Option<Duration>,
+    ) -> Result<SearchMatrix, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
+        collection
+            .search_points_matrix(request, shard_selection, read_consistency, timeout)
+            .await
+            .map_err(|err| err.into())
+    }
+
     /// Return specific points by IDs
     ///
     /// # Arguments
@@ -150,6 +235,9 @@ impl TableOfContent {
         shard_selection: ShardSelectorInternal,
     ) -> Result<Vec<Record>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
         collection
             .retrieve(request, read_consistency, &shard_selection)
             .await
@@ -159,12 +247,17 @@ impl TableOfContent {
     pub async fn group(
         &self,
         collection_name: &str,
-        request: GroupRequest,
+        mut request: GroupRequest,
         read_consistency: Option<ReadConsistency>,
         shard_selection: ShardSelectorInternal,
         timeout: Option<Duration>,
     ) -> Result<GroupsResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
 
         let collection_by_name = |name| self.get_collection_opt(name);
 
@@ -183,12 +276,17 @@ impl TableOfContent {
     pub async fn discover(
         &self,
         collection_name: &str,
-        request: DiscoverRequestInternal,
+        mut request: DiscoverRequestInternal,
         read_consistency: Option<ReadConsistency>,
         shard_selector: ShardSelectorInternal,
         timeout: Option<Duration>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        if !shard_selector.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
         discovery::discover(
             request,
             &collection,
@@ -204,11 +302,14 @@ impl TableOfContent {
     pub async fn discover_batch(
         &self,
         collection_name: &str,
-        requests: Vec<(DiscoverRequestInternal, ShardSelectorInternal)>,
+        mut requests: Vec<(DiscoverRequestInternal, ShardSelectorInternal)>,
         read_consistency: Option<ReadConsistency>,
         timeout: Option<Duration>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        self.check_rate_limit(&collection, false).await?;
+        collection.check_strict_mode(&requests).await?;
+        collection.expand_query(&mut requests).await;
 
         discovery::discover_batch(
             requests,
@@ -235,17 +336,172 @@ impl TableOfContent {
     pub async fn scroll(
         &self,
         collection_name: &str,
-        request: ScrollRequestInternal,
+        mut request: ScrollRequestInternal,
         read_consistency: Option<ReadConsistency>,
         shard_selection: ShardSelectorInternal,
     ) -> Result<ScrollResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
         collection
             .scroll_by(request, read_consistency, &shard_selection)
             .await
             .map_err(|err| err.into())
     }
 
+    /// Search once, and create a cursor to page out the found points in the order of their scores
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `request` - [`CoreSearchRequest`], `limit` is the total number of points to page out
+    /// * `shard_selection` - which local shard to use
+    /// * `timeout` - how long to wait for the search
+    ///
+    /// # Result
+    ///
+    /// Cursor to read with [`TableOfContent::cursor_next_page`]
+    pub async fn search_cursor(
+        &self,
+        collection_name: &str,
+        request: CoreSearchRequest,
+        shard_selection: ShardSelectorInternal,
+        timeout: Option<Duration>,
+    ) -> Result<CollectionCursor, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        let mut batch = CoreSearchRequestBatch {
+            searches: vec![request],
+        };
+        collection.check_strict_mode(&batch).await?;
+        collection.expand_query(&mut batch).await;
+        let Some(request) = batch.searches.pop() else {
+            return Err(StorageError::service_error("Search request is lost"));
+        };
+
+        let with_payload = request
+            .with_payload
+            .clone()
+            .unwrap_or(WithPayloadInterface::Bool(false));
+        let with_vector = request.with_vector.clone().unwrap_or_default();
+        let query = CursorQuery::Search { request, timeout };
+        collection
+            .cursor(query, with_payload, with_vector, &shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Evaluate the filter once, and create a cursor to page out the matching points in ascending
+    /// order of their IDs
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we scroll
+    /// * `request` - [`ScrollRequestInternal`], `limit` is ignored
+    /// * `shard_selection` - which local shard to use
+    ///
+    /// # Result
+    ///
+    /// Cursor to read with [`TableOfContent::cursor_next_page`]
+    pub async fn scroll_cursor(
+        &self,
+        collection_name: &str,
+        mut request: ScrollRequestInternal,
+        shard_selection: ShardSelectorInternal,
+    ) -> Result<CollectionCursor, StorageError> {
+        if request.order_by.is_some() || request.query.is_some() {
+            return Err(StorageError::bad_input(
+                "`order_by` and `query` are not supported by a scroll cursor",
+            ));
+        }
+
+        let collection = self.get_collection(collection_name).await?;
+        if !shard_selection.is_shard_id() {
+            self.check_rate_limit(&collection, false).await?;
+        }
+        collection.check_strict_mode(&request).await?;
+        collection.expand_query(&mut request).await;
+
+        let ScrollRequestInternal {
+            offset,
+            filter,
+            with_payload,
+            with_vector,
+            ..
+        } = request;
+        let with_payload = with_payload.unwrap_or(WithPayloadInterface::Bool(true));
+        let query = CursorQuery::Scroll { offset, filter };
+        collection
+            .cursor(query, with_payload, with_vector, &shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Read the next `size` points of a cursor
+    ///
+    /// An empty page is returned only once the cursor is exhausted.
+    pub async fn cursor_next_page(
+        &self,
+        cursor: &mut CollectionCursor,
+        size: usize,
+    ) -> Result<Vec<ScoredPoint>, StorageError> {
+        // Aliases are resolved once, when the cursor is created
+        let collection = self.get_collection(cursor.collection_name()).await?;
+        collection
+            .cursor_next_page(cursor, size)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Copy points, matching the filter, into another collection
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - which collection to copy points from
+    /// * `request` - [`CopyPoints`]
+    ///
+    /// # Result
+    ///
+    /// Number of copied points
+    pub async fn copy_points(
+        &self,
+        collection_name: &str,
+        mut request: CopyPoints,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> Result<CopyPointsResult, StorageError> {
+        let source = self.get_collection(collection_name).await?;
+        let target = self.get_collection(&request.target_collection).await?;
+        if source.name() == target.name() {
+            return Err(StorageError::bad_input(
+                "Can't copy points into the same collection",
+            ));
+        }
+        source.check_strict_mode(&request).await?;
+        source.expand_query(&mut request).await;
+
+        // Every batch goes through the regular update of the target collection, so it is subject
+        // to the write locks, the rate limiter and the strict mode of the target
+        let target_name = target.name();
+        drop(target);
+        let copied = data_transfer::copy_points(&source, &request, |operation| {
+            self.update(
+                &target_name,
+                operation,
+                wait,
+                ordering,
+                ShardSelectorInternal::Empty,
+            )
+        })
+        .await?;
+        Ok(CopyPointsResult { copied })
+    }
+
     async fn _update_shard_keys(
         collection: &Collection,
         shard_keys: Vec<ShardKey>,
@@ -272,12 +528,17 @@ impl TableOfContent {
     pub async fn update(
         &self,
         collection_name: &str,
-        operation: CollectionUpdateOperations,
+        mut operation: CollectionUpdateOperations,
         wait: bool,
         ordering: WriteOrdering,
         shard_selector: ShardSelectorInternal,
     ) -> Result<UpdateResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        if !shard_selector.is_shard_id() {
+            self.check_rate_limit(&collection, true).await?;
+        }
+        collection.check_strict_mode(&operation).await?;
+        collection.expand_query(&mut operation).await;
 
         // Ordered operation flow:
         //
@@ -316,6 +577,9 @@ impl TableOfContent {
         };
         if operation.is_write_operation() {
             self.check_write_lock()?;
+            if !shard_selector.is_shard_id() {
+                self.check_cluster_write_lock()?;
+            }
         }
         let res = match shard_selector {
             ShardSelectorInternal::Empty => {
@@ -0,0 +1,119 @@
+use std::time::Instant;
+
+use collection::collection::Collection;
+use collection::config::RateLimitConfig;
+
+use super::TableOfContent;
+use crate::content_manager::errors::StorageError;
+
+/// Token bucket, which allows bursts of up to one second worth of requests
+#[derive(Debug)]
+pub(super) struct RateLimiter {
+    /// Number of requests per second, also the max number of tokens in the bucket
+    rps: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(rps: usize) -> Self {
+        Self {
+            rps: rps as f64,
+            tokens: rps as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token from the bucket, returns `false` if there are no tokens left
+    fn try_consume(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rps).min(self.rps);
+        self.last_refill = now;
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// Rate limiters of a single collection, created from its config
+#[derive(Debug)]
+pub(super) struct CollectionRateLimiters {
+    config: RateLimitConfig,
+    read: Option<RateLimiter>,
+    write: Option<RateLimiter>,
+}
+
+impl CollectionRateLimiters {
+    fn new(config: RateLimitConfig) -> Self {
+        Self {
+            read: config.read_rps.map(RateLimiter::new),
+            write: config.write_rps.map(RateLimiter::new),
+            config,
+        }
+    }
+}
+
+impl TableOfContent {
+    /// Returns an error if the request exceeds the rate limit of the collection
+    ///
+    /// Limits are applied on the peer, which received the request from the client.
+    /// Requests forwarded between peers should not be checked again.
+    pub(super) async fn check_rate_limit(
+        &self,
+        collection: &Collection,
+        write: bool,
+    ) -> Result<(), StorageError> {
+        let collection_name = collection.name();
+        let Some(config) = collection.rate_limit().await else {
+            self.collection_rate_limiters
+                .lock()
+                .remove(&collection_name);
+            return Ok(());
+        };
+
+        let mut rate_limiters = self.collection_rate_limiters.lock();
+        let limiters = rate_limiters
+            .entry(collection_name.clone())
+            .or_insert_with(|| CollectionRateLimiters::new(config.clone()));
+        // Config was updated, start over with the new limits
+        if limiters.config != config {
+            *limiters = CollectionRateLimiters::new(config);
+        }
+
+        let (limiter, kind) = if write {
+            (&mut limiters.write, "write")
+        } else {
+            (&mut limiters.read, "read")
+        };
+        match limiter {
+            Some(limiter) if !limiter.try_consume() => Err(StorageError::RateLimitExceeded {
+                description: format!(
+                    "too many {kind} requests to collection {collection_name}, try again later",
+                ),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(2);
+        assert!(limiter.try_consume());
+        assert!(limiter.try_consume());
+        assert!(!limiter.try_consume());
+
+        // Bucket is refilled over time
+        limiter.last_refill -= std::time::Duration::from_millis(600);
+        assert!(limiter.try_consume());
+        assert!(!limiter.try_consume());
+    }
+}
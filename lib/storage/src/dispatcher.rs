@@ -56,6 +56,7 @@ impl Dispatcher {
             let op = match operation {
                 CollectionMetaOperations::CreateCollection(mut op) => {
                     self.toc.check_write_lock()?;
+                    self.toc.check_cluster_write_lock()?;
                     if !op.is_distribution_set() {
                         match op.create_collection.sharding_method.unwrap_or_default() {
                             ShardingMethod::Auto => {
@@ -96,8 +97,14 @@ impl Dispatcher {
                 }
                 CollectionMetaOperations::CreateShardKey(op) => {
                     self.toc.check_write_lock()?;
+                    self.toc.check_cluster_write_lock()?;
                     CollectionMetaOperations::CreateShardKey(op)
                 }
+                CollectionMetaOperations::SplitShardKey(op) => {
+                    self.toc.check_write_lock()?;
+                    self.toc.check_cluster_write_lock()?;
+                    CollectionMetaOperations::SplitShardKey(op)
+                }
 
                 op => op,
             };
@@ -134,6 +141,7 @@ impl Dispatcher {
         } else {
             if let CollectionMetaOperations::CreateCollection(_) = &operation {
                 self.toc.check_write_lock()?;
+                self.toc.check_cluster_write_lock()?;
             }
             self.toc.perform_collection_meta_op(operation).await
         }
@@ -3,6 +3,8 @@ use std::num::NonZeroUsize;
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
+use collection::common::maintenance_hooks::MaintenanceHook;
+use collection::common::snapshots_manager::SnapshotsConfig;
 use collection::config::WalConfig;
 use collection::operations::shared_storage_config::SharedStorageConfig;
 use collection::operations::types::NodeType;
@@ -27,6 +29,14 @@ pub struct PerformanceConfig {
     pub update_rate_limit: Option<usize>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub search_timeout_sec: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_batch_window_ms: Option<u64>,
+    #[serde(default)]
+    pub verify_vector_checksums: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scrub_interval_sec: Option<u64>,
+    #[serde(default)]
+    pub scrub_quarantine: bool,
 }
 
 const fn default_max_optimization_threads() -> usize {
@@ -72,6 +82,12 @@ pub struct StorageConfig {
     pub recovery_mode: Option<String>,
     #[serde(default)]
     pub update_concurrency: Option<NonZeroUsize>,
+    /// Where to store collection snapshots
+    #[serde(default)]
+    pub snapshots_config: SnapshotsConfig,
+    /// Commands to run before and after optimizations, snapshots and shard transfers
+    #[serde(default)]
+    pub maintenance_hooks: Vec<MaintenanceHook>,
 }
 
 impl StorageConfig {
@@ -86,6 +102,13 @@ impl StorageConfig {
                 .map(|x| Duration::from_secs(x as u64)),
             self.update_concurrency,
             is_distributed,
+            self.performance
+                .update_batch_window_ms
+                .map(Duration::from_millis),
+            self.performance.scrub_interval_sec.map(Duration::from_secs),
+            self.performance.scrub_quarantine,
+            self.snapshots_config.clone(),
+            self.maintenance_hooks.clone(),
         )
     }
 }
@@ -110,6 +133,25 @@ pub struct PeerInfo {
     // pub last_responded_millis: usize
 }
 
+/// New address of a peer, e.g. after it was rescheduled to another host
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct PeerUriUpdate {
+    /// URI of the internal gRPC API of the peer, e.g. `http://qdrant-1.qdrant-headless:6335`
+    #[validate(length(min = 1))]
+    pub uri: String,
+}
+
+/// Replicas of all collections to move from old peers to newly joined ones, e.g. for an upgrade
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct PeerMigration {
+    /// Peers to move all replicas away from
+    #[validate(length(min = 1))]
+    pub from_peers: Vec<PeerId>,
+    /// Peers to replicate shards to, they take over all reads and writes of the old peers
+    #[validate(length(min = 1))]
+    pub to_peers: Vec<PeerId>,
+}
+
 /// Summary information about the current raft state
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 pub struct RaftInfo {
@@ -47,6 +47,10 @@ fn test_alias_operation() {
             max_optimization_threads: 1,
             update_rate_limit: None,
             search_timeout_sec: None,
+            update_batch_window_ms: None,
+            verify_vector_checksums: false,
+            scrub_interval_sec: None,
+            scrub_quarantine: false,
         },
         hnsw_index: Default::default(),
         quantization: None,
@@ -58,6 +62,7 @@ fn test_alias_operation() {
         async_scorer: false,
         update_concurrency: Some(NonZeroUsize::new(2).unwrap()),
         // update_concurrency: None,
+        snapshots_config: Default::default(),
     };
 
     let search_runtime = Runtime::new().unwrap();
@@ -106,6 +111,10 @@ fn test_alias_operation() {
                         init_from: None,
                         quantization_config: None,
                         sharding_method: None,
+                        metadata: None,
+                        strict_mode_config: None,
+                        rate_limit: None,
+                        query_expansion: None,
                     },
                 )),
                 None,
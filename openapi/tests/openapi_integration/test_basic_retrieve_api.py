@@ -114,6 +114,51 @@ def test_exclude_payload():
         assert 'city' not in result['payload']
 
 
+def test_search_response_fields():
+    search = {
+        "vector": [0.2, 0.1, 0.9, 0.7],
+        "limit": 3,
+        "with_payload": True,
+    }
+
+    full_response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=search,
+    )
+    assert full_response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={**search, "response_fields": ["id", "score"]},
+    )
+    assert response.ok
+    expected = [
+        {"id": point["id"], "score": point["score"]}
+        for point in full_response.json()['result']
+    ]
+    assert response.json()['result'] == expected
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search/batch',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "searches": [
+                {**search, "response_fields": ["id"]},
+                search,
+            ]
+        },
+    )
+    assert response.ok
+    ids_result, full_result = response.json()['result']
+    assert ids_result == [{"id": point["id"]} for point in full_response.json()['result']]
+    assert full_result == full_response.json()['result']
+
+
 def test_is_empty_condition():
     response = request_with_validation(
         api='/collections/{collection_name}/points/search',
@@ -0,0 +1,83 @@
+import time
+
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_clone_source'
+clone_name = 'test_collection_clone'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=clone_name)
+    drop_collection(collection_name=collection_name)
+
+
+def count_points(collection_name):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/count',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"exact": True},
+    )
+    assert response.ok
+    return response.json()['result']['count']
+
+
+def test_clone_collection():
+    response = request_with_validation(
+        api='/collections/{collection_name}/clone',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "to": clone_name,
+            "hnsw_config": {"m": 32},
+            "quantization_config": {
+                "scalar": {
+                    "type": "int8",
+                }
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': clone_name},
+    )
+    assert response.ok
+    config = response.json()['result']['config']
+    assert config['hnsw_config']['m'] == 32
+    assert config['quantization_config']['scalar']['type'] == 'int8'
+    assert config['params']['vectors']['size'] == 4
+
+    # Points are copied in the background
+    for _ in range(30):
+        if count_points(clone_name) == count_points(collection_name):
+            break
+        time.sleep(0.1)
+    assert count_points(clone_name) == count_points(collection_name)
+
+    # The source collection is not changed
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()['result']['config']['quantization_config'] is None
+
+
+def test_clone_collection_to_existing():
+    response = request_with_validation(
+        api='/collections/{collection_name}/clone',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"to": collection_name},
+    )
+    assert not response.ok
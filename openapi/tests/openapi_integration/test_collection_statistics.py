@@ -0,0 +1,53 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_statistics'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+
+    for field_name, field_schema in [("city", "keyword"), ("count", "integer")]:
+        response = request_with_validation(
+            api='/collections/{collection_name}/index',
+            method="PUT",
+            path_params={'collection_name': collection_name},
+            query_params={'wait': 'true'},
+            body={
+                "field_name": field_name,
+                "field_schema": field_schema
+            }
+        )
+        assert response.ok
+
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_collection_statistics():
+    response = request_with_validation(
+        api='/collections/{collection_name}/statistics',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+
+    result = response.json()['result']
+    assert result['local_shards_count'] == 1
+
+    city = result['payload_fields']['city']
+    assert city['points_count'] == 4
+    assert city['values_count'] == 7
+    # Distinct values are counted per segment
+    assert city['distinct_values_count'] >= 3
+    assert 'histogram' not in city
+
+    count = result['payload_fields']['count']
+    assert count['points_count'] == 1
+    assert count['values_count'] == 1
+    assert count['min'] == 0
+    assert count['max'] == 0
+    assert sum(bucket['count'] for bucket in count['histogram']) == 1
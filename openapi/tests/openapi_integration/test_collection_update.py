@@ -191,3 +191,350 @@ def test_edit_collection_params(on_disk_vectors, on_disk_payload):
     assert config["quantization_config"]["scalar"]["type"] == "int8"
     assert config["quantization_config"]["scalar"]["quantile"] == 0.99
     assert config["quantization_config"]["scalar"]["always_ram"]
+
+
+def test_edit_maintenance_windows():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "params": {
+                "maintenance_windows": [
+                    {"start": "22:00:00", "end": "06:00:00"},
+                ],
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    config = response.json()["result"]["config"]
+    assert config["params"]["maintenance_windows"] == [{"start": "22:00:00", "end": "06:00:00"}]
+
+    # Empty list removes the restriction
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "params": {
+                "maintenance_windows": [],
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    config = response.json()["result"]["config"]
+    assert config["params"]["maintenance_windows"] == []
+
+
+def test_edit_collection_metadata():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert "metadata" not in response.json()["result"]["config"]
+
+    metadata = {
+        "model": "text-embedding-3-small",
+        "version": 2,
+        "provenance": {"dataset": "wiki", "chunk_size": 512},
+    }
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "metadata": metadata,
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()["result"]["config"]["metadata"] == metadata
+
+    # Metadata is replaced as a whole
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "metadata": {"version": 3},
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()["result"]["config"]["metadata"] == {"version": 3}
+
+
+def test_point_timestamps():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "params": {
+                "point_timestamps": True,
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 100, "vector": [0.1, 0.2, 0.3, 0.4], "payload": {"city": "Berlin"}},
+            ]
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': collection_name, 'id': 100},
+    )
+    assert response.ok
+    payload = response.json()["result"]["payload"]
+    assert payload["city"] == "Berlin"
+    created_at = payload["__created_at"]
+    assert payload["__updated_at"] == created_at
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "payload": {"city": "London"},
+            "points": [100],
+        }
+    )
+    assert response.ok
+
+    # Creation time survives both payload updates and upserts of the same point
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 100, "vector": [0.4, 0.3, 0.2, 0.1], "payload": {"city": "Paris"}},
+            ]
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "filter": {
+                "must": [
+                    {"key": "__created_at", "range": {"gte": created_at}},
+                ]
+            },
+            "with_payload": True,
+        }
+    )
+    assert response.ok
+    points = response.json()["result"]["points"]
+    assert [point["id"] for point in points] == [100]
+    payload = points[0]["payload"]
+    assert payload["city"] == "Paris"
+    assert payload["__created_at"] == created_at
+    assert payload["__updated_at"] >= created_at
+
+
+def test_edit_point_expiration():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "params": {
+                "point_expiration": True,
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()["result"]["config"]["params"]["point_expiration"] is True
+
+
+def test_strict_mode():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "strict_mode_config": {
+                "enabled": True,
+                "max_query_limit": 5,
+                "unindexed_filtering": False,
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()["result"]["config"]["strict_mode_config"]["max_query_limit"] == 5
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 10,
+        }
+    )
+    assert response.status_code == 400
+    assert "limit" in response.json()["status"]["error"]
+
+    search_by_city = {
+        "vector": [0.2, 0.1, 0.9, 0.7],
+        "limit": 5,
+        "filter": {
+            "must": [
+                {"key": "city", "match": {"value": "London"}},
+            ]
+        },
+    }
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=search_by_city,
+    )
+    assert response.status_code == 400
+    assert "unindexed field `city`" in response.json()["status"]["error"]
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "field_name": "city",
+            "field_schema": "keyword",
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=search_by_city,
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "strict_mode_config": {
+                "enabled": False,
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 10,
+        }
+    )
+    assert response.ok
+
+
+def test_rate_limit():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "rate_limit": {
+                "read_rps": 1,
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()["result"]["config"]["rate_limit"] == {"read_rps": 1}
+
+    statuses = []
+    for _ in range(3):
+        response = request_with_validation(
+            api='/collections/{collection_name}/points/scroll',
+            method="POST",
+            path_params={'collection_name': collection_name},
+            body={"limit": 1},
+        )
+        statuses.append(response.status_code)
+    assert 429 in statuses
+
+    # Writes are not limited
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": [1]},
+    )
+    assert response.ok
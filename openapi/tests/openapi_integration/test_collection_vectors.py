@@ -0,0 +1,47 @@
+import pytest
+
+from .helpers.collection_setup import drop_collection, multivec_collection_setup
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_vectors'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    multivec_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_collection_vectors():
+    response = request_with_validation(
+        api='/collections/{collection_name}/vectors',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+
+    result = response.json()['result']
+    assert result['local_shards_count'] == 1
+    assert set(result['vectors'].keys()) == {"image", "text", "sparse-image", "sparse-text"}
+
+    image = result['vectors']['image']
+    assert image['params']['size'] == 4
+    assert 'sparse_params' not in image
+    # Points 7 and 8 only have sparse vectors
+    assert image['points_count'] == 6
+    # Collection is too small to build the vector index
+    assert image['index_status'] == "not_indexed"
+    assert image['indexed_vectors_count'] == 0
+    assert image['storage_size_bytes'] >= 0
+    # Vectors are not quantized
+    assert 'quantization_drift' not in image
+
+    text = result['vectors']['text']
+    assert text['params']['size'] == 8
+    assert text['points_count'] == 6
+
+    sparse_image = result['vectors']['sparse-image']
+    assert 'params' not in sparse_image
+    assert sparse_image['sparse_params'] is not None
+    assert sparse_image['points_count'] >= 2
@@ -0,0 +1,112 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_copy_source'
+target_collection_name = 'test_collection_copy_target'
+
+
+@pytest.fixture(autouse=True)
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    drop_collection(collection_name=target_collection_name)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PUT",
+        path_params={'collection_name': target_collection_name},
+        body={
+            "vectors": {
+                "image": {
+                    "size": 4,
+                    "distance": "Dot",
+                },
+            },
+        }
+    )
+    assert response.ok
+
+    yield
+    drop_collection(collection_name=collection_name)
+    drop_collection(collection_name=target_collection_name)
+
+
+def test_copy_points():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/copy',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "target_collection": target_collection_name,
+            "filter": {
+                "must": [
+                    {"key": "city", "match": {"value": "London"}},
+                ]
+            },
+            "vectors": {"": "image"},
+        }
+    )
+    assert response.ok
+    assert response.json()["result"]["copied"] == 2
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': target_collection_name},
+        body={
+            "with_payload": True,
+            "with_vector": True,
+        }
+    )
+    assert response.ok
+    points = response.json()["result"]["points"]
+    assert [point["id"] for point in points] == [2, 4]
+    assert points[0]["payload"] == {"city": ["Berlin", "London"]}
+    assert set(points[0]["vector"].keys()) == {"image"}
+
+
+def test_copy_points_into_same_collection():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/copy',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "target_collection": collection_name,
+        }
+    )
+    assert response.status_code == 400
+
+
+def test_copy_points_respects_target_strict_mode():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': target_collection_name},
+        body={
+            "strict_mode_config": {
+                "enabled": True,
+                "max_batch_size": 1,
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/copy',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "target_collection": target_collection_name,
+            "filter": {
+                "must": [
+                    {"key": "city", "match": {"value": "London"}},
+                ]
+            },
+            "vectors": {"": "image"},
+        }
+    )
+    assert response.status_code == 400
+    assert "max batch size" in response.json()["status"]["error"]
@@ -299,3 +299,54 @@ def test_null_offset():
         },
     )
     assert response.ok, response.json()
+
+
+def test_discover_sparse():
+    # Examples can mix point ids and raw sparse vectors
+    response = request_with_validation(
+        api="/collections/{collection_name}/points/discover",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "target": 9,
+            "context": [
+                {
+                    "positive": {"indices": [1, 2], "values": [0.5, 0.5]},
+                    "negative": {"indices": [66], "values": [1.0]},
+                },
+            ],
+            "using": "sparse-text",
+            "limit": 8,
+        },
+    )
+    assert response.ok, response.json()
+
+    scored_points = response.json()["result"]
+    assert [point["id"] for point in scored_points] == [10]
+
+
+def test_context_sparse():
+    response = request_with_validation(
+        api="/collections/{collection_name}/points/discover",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "context": [
+                {
+                    "positive": {"indices": [1, 2, 3], "values": [0.1, 0.2, 0.3]},
+                    "negative": {"indices": [66, 12], "values": [0.5, 0.5]},
+                },
+            ],
+            "using": "sparse-text",
+            "limit": 8,
+        },
+    )
+    assert response.ok, response.json()
+
+    scored_points = response.json()["result"]
+    assert sorted(point["id"] for point in scored_points) == [9, 10]
+
+    # Points closer to the positive example are not penalized
+    scores = {point["id"]: point["score"] for point in scored_points}
+    assert scores[10] == 0.0
+    assert scores[9] < 0.0
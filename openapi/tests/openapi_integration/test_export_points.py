@@ -0,0 +1,55 @@
+import uuid
+
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_export_points'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def export_points(body):
+    return request_with_validation(
+        api='/collections/{collection_name}/export',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=body,
+    )
+
+
+@pytest.mark.parametrize("export_format", ["jsonl", "parquet"])
+def test_export_points(export_format):
+    location = f"file:///tmp/qdrant-export-{uuid.uuid4()}/"
+    response = export_points({
+        "location": location,
+        "format": export_format,
+        "filter": {
+            "must": [{"key": "city", "match": {"value": "Berlin"}}]
+        },
+        "with_payload": ["city"],
+    })
+    assert response.ok
+
+    result = response.json()['result']
+    assert result['points_count'] == 3
+    assert len(result['files']) == 1
+    exported_file = result['files'][0]
+    assert exported_file['points_count'] == 3
+    assert exported_file['location'].startswith(location)
+    assert exported_file['location'].endswith(f".{export_format}")
+
+    # Files are not overwritten by a repeated export
+    response = export_points({"location": location, "format": export_format})
+    assert response.status_code == 400
+
+
+def test_export_points_unsupported_location():
+    response = export_points({"location": "ftp://example.com/export/"})
+    assert response.status_code == 400
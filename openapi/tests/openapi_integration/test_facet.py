@@ -0,0 +1,124 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_facet'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "field_name": "city",
+            "field_schema": "keyword"
+        }
+    )
+    assert response.ok
+
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+@pytest.mark.parametrize("exact", [True, False])
+def test_facet(exact):
+    response = request_with_validation(
+        api='/collections/{collection_name}/facet',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "key": "city",
+            "exact": exact,
+        }
+    )
+    assert response.ok
+    assert response.json()['result']['hits'] == [
+        {"value": "Berlin", "count": 3},
+        {"value": "London", "count": 2},
+        {"value": "Moscow", "count": 2},
+    ]
+
+
+def test_facet_with_filter_and_limit():
+    response = request_with_validation(
+        api='/collections/{collection_name}/facet',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "key": "city",
+            "exact": True,
+            "limit": 2,
+            "filter": {
+                "must": [
+                    {
+                        "key": "city",
+                        "match": {
+                            "value": "London"
+                        }
+                    }
+                ]
+            }
+        }
+    )
+    assert response.ok
+    assert response.json()['result']['hits'] == [
+        {"value": "London", "count": 2},
+        {"value": "Berlin", "count": 1},
+    ]
+
+
+def test_facet_without_index():
+    response = request_with_validation(
+        api='/collections/{collection_name}/facet',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "key": "count",
+        }
+    )
+    assert response.status_code == 400
+    assert "There is no keyword index for the facet key" in response.json()['status']['error']
+
+
+def test_facet_with_index_params():
+    params_collection_name = 'test_collection_facet_params'
+    basic_collection_setup(collection_name=params_collection_name)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': params_collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "field_name": "city",
+            "field_schema": {
+                "type": "keyword",
+                "is_tenant": True,
+            }
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/facet',
+        method="POST",
+        path_params={'collection_name': params_collection_name},
+        body={
+            "key": "city",
+            "exact": True,
+        }
+    )
+    assert response.ok, response.text
+    assert response.json()['result']['hits'] == [
+        {"value": "Berlin", "count": 3},
+        {"value": "London", "count": 2},
+        {"value": "Moscow", "count": 2},
+    ]
+
+    drop_collection(collection_name=params_collection_name)
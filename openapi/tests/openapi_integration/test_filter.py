@@ -67,3 +67,35 @@ def test_just_key():
     error = response.json()["status"]["error"]
     assert "Validation error in JSON body" in error
     assert "At least one field condition must be specified" in error
+
+
+def test_version_filter():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 100, "vector": [0.1, 0.2, 0.3, 0.4], "payload": {}},
+            ]
+        }
+    )
+    assert response.ok
+    operation_id = response.json()['result']['operation_id']
+
+    # Only the point, changed by the last operation, has the latest version
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "filter": {
+                "must": [
+                    {"key": "__version", "range": {"gte": operation_id}},
+                ]
+            },
+        }
+    )
+    assert response.ok
+    assert [point['id'] for point in response.json()['result']['points']] == [100]
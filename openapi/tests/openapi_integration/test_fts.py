@@ -274,3 +274,98 @@ def test_scroll_with_prefix():
 
     assert response.ok
     assert len(response.json()['result']) == 3
+
+
+def scroll_titles(text, expand):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "limit": 10,
+            "with_payload": True,
+            "filter": {
+                "must": [
+                    {
+                        "key": "title",
+                        "match": {
+                            "text": text,
+                            "expand": expand,
+                        }
+                    }
+                ]
+            }
+        }
+    )
+    assert response.ok
+    return sorted(point['payload']['title'] for point in response.json()['result']['points'])
+
+
+def test_query_expansion():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "query_expansion": {
+                "synonyms": {
+                    "automaton": ["robot"],
+                    "android": ["robot"],
+                },
+                "stop_words": ["the"],
+            }
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()['result']['config']['query_expansion']['stop_words'] == ["the"]
+
+    assert scroll_titles("the automaton", False) == []
+    assert scroll_titles("the automaton", True) == [
+        "Little Lost Robot",
+        "Robot AL-76 Goes Astray",
+        "Robot Dreams",
+    ]
+    assert scroll_titles("Android dreams", True) == ["Robot Dreams"]
+
+
+def test_search_highlights():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [1., 1., 1., 1.],
+            "limit": 10,
+            "with_payload": True,
+            "filter": {
+                "must": [
+                    {
+                        "key": "title",
+                        "match": {
+                            "text": "ROBO",
+                        }
+                    }
+                ]
+            },
+            "response_fields": ["id"],
+            "with_highlights": True,
+        }
+    )
+    assert response.ok
+
+    result = response.json()['result']
+    assert len(result) == 3
+    for point in result:
+        title = texts[point['id']]
+        start = title.index("Robo")
+        assert 'payload' not in point
+        assert point['highlights'] == {
+            "title": [{"value_index": 0, "start": start, "end": start + 4, "text": "Robo"}]
+        }
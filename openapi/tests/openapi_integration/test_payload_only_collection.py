@@ -0,0 +1,87 @@
+import pytest
+
+from .helpers.collection_setup import drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_payload_only_collection'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    drop_collection(collection_name=collection_name)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        body={}
+    )
+    assert response.ok
+
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_payload_only_collection():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "field_name": "country",
+            "field_schema": "keyword",
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 1, "payload": {"country": "Germany", "capital": "Berlin"}},
+                {"id": 2, "payload": {"country": "France", "capital": "Paris"}},
+                {"id": 3, "payload": {"country": "Germany", "capital": "Bonn"}},
+            ]
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "filter": {
+                "must": [
+                    {"key": "country", "match": {"value": "Germany"}},
+                ]
+            },
+            "with_payload": True,
+        }
+    )
+    assert response.ok
+    points = response.json()["result"]["points"]
+    assert [point["id"] for point in points] == [1, 3]
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/count',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "exact": True,
+        }
+    )
+    assert response.ok
+    assert response.json()["result"]["count"] == 3
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': collection_name, 'id': 2},
+    )
+    assert response.ok
+    assert response.json()["result"]["payload"]["capital"] == "Paris"
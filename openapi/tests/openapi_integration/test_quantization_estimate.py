@@ -0,0 +1,62 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_quantization_estimate'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_estimate_scalar_quantization():
+    response = request_with_validation(
+        api='/collections/{collection_name}/quantization/estimate',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "quantization_config": {
+                "scalar": {
+                    "type": "int8",
+                }
+            },
+            "queries": 5,
+            "limit": 3,
+        }
+    )
+    assert response.ok
+
+    result = response.json()['result']
+    assert result['sampled_count'] == 10
+    assert result['queries_count'] == 5
+    assert 0.0 <= result['recall_min'] <= result['recall_mean'] <= 1.0
+    assert result['vector_size_bytes'] == 4 * 4
+    assert result['quantized_vector_size_bytes'] < result['vector_size_bytes']
+
+    # The collection is not quantized by the estimate
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()['result']['config']['quantization_config'] is None
+
+
+def test_estimate_quantization_of_missing_vector():
+    response = request_with_validation(
+        api='/collections/{collection_name}/quantization/estimate',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "quantization_config": {
+                "binary": {}
+            },
+            "using": "missing",
+        }
+    )
+    assert response.status_code == 400
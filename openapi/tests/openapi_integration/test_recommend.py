@@ -113,6 +113,42 @@ def test_single_vs_batch():
         assert single_response.json()["result"] == batch_response.json()["result"][i]
 
 
+def test_batch_shared_filter_and_params():
+    shared_filter = {"must": [{"key": "city", "match": {"value": "Berlin"}}]}
+    own_filter = {"must_not": [{"key": "city", "match": {"value": "London"}}]}
+    searches = [
+        {"positive": [1], "limit": 10},
+        {"positive": [2], "limit": 10, "filter": own_filter},
+    ]
+
+    batch_response = request_with_validation(
+        api="/collections/{collection_name}/points/recommend/batch",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "searches": searches,
+            "filter": shared_filter,
+            "params": {"exact": True},
+        },
+    )
+    assert batch_response.ok
+
+    # Shared filter is combined with the own filter of each search
+    expected_filters = [
+        shared_filter,
+        {"must": [own_filter, shared_filter]},
+    ]
+    for i, search in enumerate(searches):
+        single_response = request_with_validation(
+            api="/collections/{collection_name}/points/recommend",
+            method="POST",
+            path_params={"collection_name": collection_name},
+            body={**search, "filter": expected_filters[i], "params": {"exact": True}},
+        )
+        assert single_response.ok
+        assert single_response.json()["result"] == batch_response.json()["result"][i]
+
+
 def test_without_positives():
     def req_with_positives(positive, strategy=None):
         if strategy is None:
@@ -263,3 +299,118 @@ def test_raw_vectors():
     assert len(response_raw.json()["result"]) == 4
 
     assert response_ids.json()["result"] == response_raw.json()["result"]
+
+
+@pytest.mark.parametrize("strategy", ["average_vector", "best_score"])
+def test_unit_weights_are_same_as_unweighted(strategy):
+    params = {
+        "positive": [1, 2],
+        "negative": [3, 4],
+        "strategy": strategy,
+        "exact": True,
+        "limit": 4,
+    }
+
+    response = request_with_validation(
+        api="/collections/{collection_name}/points/recommend",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body=params,
+    )
+    assert response.ok
+
+    weighted_response = request_with_validation(
+        api="/collections/{collection_name}/points/recommend",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            **params,
+            "positive_weights": [1.0, 1.0],
+            "negative_weights": [1.0, 1.0],
+        },
+    )
+    assert weighted_response.ok
+
+    assert response.json()["result"] == weighted_response.json()["result"]
+
+
+def test_weighted_average_is_same_as_repeated_examples():
+    weighted_response = request_with_validation(
+        api="/collections/{collection_name}/points/recommend",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "positive": [1, 2],
+            "positive_weights": [3.0, 1.0],
+            "strategy": "average_vector",
+            "exact": True,
+            "limit": 4,
+        },
+    )
+    assert weighted_response.ok
+
+    repeated_response = request_with_validation(
+        api="/collections/{collection_name}/points/recommend",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "positive": [1, 1, 1, 2],
+            "strategy": "average_vector",
+            "exact": True,
+            "limit": 4,
+        },
+    )
+    assert repeated_response.ok
+
+    # Scores may differ in the last digits, but the ids and order should be the same
+    weighted_ids = [result["id"] for result in weighted_response.json()["result"]]
+    repeated_ids = [result["id"] for result in repeated_response.json()["result"]]
+
+    assert weighted_ids == repeated_ids
+
+
+def test_weighted_best_score_prefers_heavier_example():
+    def top_id(positive_weights):
+        response = request_with_validation(
+            api="/collections/{collection_name}/points/recommend",
+            method="POST",
+            path_params={"collection_name": collection_name},
+            body={
+                "positive": [1, 2],
+                "positive_weights": positive_weights,
+                "strategy": "best_score",
+                "exact": True,
+                "limit": 1,
+            },
+        )
+        assert response.ok
+        return response.json()["result"][0]["id"]
+
+    # Point 3 is the closest to point 1, point 8 is the closest to point 2
+    assert top_id([1.0, 1.0]) == 3
+    assert top_id([1.0, 3.0]) == 8
+
+
+@pytest.mark.parametrize(
+    "weights",
+    [
+        {"positive_weights": [1.0]},
+        {"negative_weights": [1.0, 2.0, 3.0]},
+        {"positive_weights": [1.0, 0.0]},
+        {"positive_weights": [1.0, -2.0]},
+    ],
+)
+def test_invalid_weights(weights):
+    response = request_with_validation(
+        api="/collections/{collection_name}/points/recommend",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "positive": [1, 2],
+            "negative": [3, 4],
+            "strategy": "best_score",
+            "limit": 4,
+            **weights,
+        },
+    )
+    assert response.status_code == 400
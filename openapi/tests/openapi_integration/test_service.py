@@ -1,8 +1,10 @@
 import pytest
+import requests
 from datetime import datetime
 
 from .helpers.collection_setup import basic_collection_setup, drop_collection
 from .helpers.helpers import request_with_validation
+from .helpers.settings import QDRANT_HOST
 
 collection_name = 'test_collection_telemetry'
 
@@ -47,3 +49,37 @@ def test_telemetry():
     last_queried = datetime.fromisoformat(last_queried)
     # Assert today
     assert last_queried.date() == datetime.now().date()
+
+
+def test_openapi_spec():
+    response = request_with_validation(
+        api='/openapi.json',
+        method="GET",
+    )
+    assert response.ok
+
+    spec = response.json()
+    assert spec['openapi'].startswith('3.')
+    assert '/collections/{collection_name}/points/search' in spec['paths']
+    assert 'SearchRequest' in spec['components']['schemas']
+
+
+@pytest.mark.parametrize("encoding", ["gzip", "br", "zstd"])
+def test_response_compression(encoding):
+    response = requests.post(
+        f"{QDRANT_HOST}/collections/{collection_name}/points/scroll",
+        json={"limit": 10, "with_payload": True, "with_vector": True},
+        headers={"Accept-Encoding": encoding},
+        stream=True,
+    )
+    assert response.ok
+    assert response.headers['content-encoding'] == encoding
+
+    # Responses are not compressed, unless the client accepts it
+    response = requests.post(
+        f"{QDRANT_HOST}/collections/{collection_name}/points/scroll",
+        json={"limit": 10, "with_payload": True, "with_vector": True},
+        headers={"Accept-Encoding": "identity"},
+    )
+    assert response.ok
+    assert 'content-encoding' not in response.headers
@@ -3,6 +3,7 @@ import pytest
 
 from .helpers.collection_setup import basic_collection_setup, drop_collection
 from .helpers.helpers import request_with_validation
+from .helpers.settings import QDRANT_HOST
 
 collection_name = 'test_collection_snapshot'
 
@@ -33,6 +34,7 @@ def test_snapshot_operations():
     )
     assert response.ok
     snapshot_name = response.json()['result']['name']
+    assert len(response.json()['result']['checksum']) == 64
 
     # validate it exists
     response = request_with_validation(
@@ -261,3 +263,138 @@ def test_snapshot_invalid_file_uri():
     )
     assert response.status_code == 400
     assert response.json()["status"]["error"] == "Bad request: Snapshot file \"/whatever.snapshot\" does not exist"
+
+
+def test_snapshot_checksum_verification():
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok
+    snapshot_name = response.json()['result']['name']
+    checksum = response.json()['result']['checksum']
+
+    recovered_collection_name = f"{collection_name}_recovered"
+    location = f"{QDRANT_HOST}/collections/{collection_name}/snapshots/{snapshot_name}"
+
+    # Wrong checksum fails the recovery
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots/recover',
+        method="PUT",
+        path_params={'collection_name': recovered_collection_name},
+        body={
+            "location": location,
+            "checksum": "0" * 64,
+        }
+    )
+    assert response.status_code == 400
+    assert "checksum mismatch" in response.json()["status"]["error"]
+
+    # Verification can be skipped
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots/recover',
+        method="PUT",
+        path_params={'collection_name': recovered_collection_name},
+        body={
+            "location": location,
+            "checksum": "0" * 64,
+            "verify_checksum": False,
+        }
+    )
+    assert response.ok
+
+    # Checksum is taken from the file stored alongside the snapshot
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots/recover',
+        method="PUT",
+        path_params={'collection_name': recovered_collection_name},
+        body={
+            "location": location,
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots/recover',
+        method="PUT",
+        path_params={'collection_name': recovered_collection_name},
+        body={
+            "location": location,
+            "checksum": checksum,
+        }
+    )
+    assert response.ok
+
+    drop_collection(collection_name=recovered_collection_name)
+
+
+def test_snapshot_view():
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok
+    snapshot_name = response.json()['result']['name']
+
+    view_name = f"{collection_name}_view"
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots/{snapshot_name}/view',
+        method="POST",
+        path_params={'collection_name': collection_name, 'snapshot_name': snapshot_name},
+        query_params={'wait': 'true'},
+        body={"view_name": view_name},
+    )
+    assert response.ok
+
+    # Source collection keeps receiving updates
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": [1]},
+    )
+    assert response.ok
+
+    # View still contains the data of the snapshot
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': view_name, 'id': 1},
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': view_name},
+    )
+    assert response.ok
+    assert response.json()['result']['config']['params']['read_only'] is True
+
+    # View itself is read-only
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': view_name},
+        query_params={'wait': 'true'},
+        body={"points": [1]},
+    )
+    assert response.status_code == 400
+    assert "read-only" in response.json()["status"]["error"]
+
+    # View name can't clash with an existing collection
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots/{snapshot_name}/view',
+        method="POST",
+        path_params={'collection_name': collection_name, 'snapshot_name': snapshot_name},
+        query_params={'wait': 'true'},
+        body={"view_name": view_name},
+    )
+    assert response.status_code == 400
+
+    drop_collection(collection_name=view_name)
@@ -0,0 +1,58 @@
+import json
+
+import pytest
+import requests
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.settings import QDRANT_HOST
+
+collection_name = 'test_collection_stream'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def stream_points(body):
+    response = requests.post(
+        f"{QDRANT_HOST}/collections/{collection_name}/points/stream",
+        json=body,
+        stream=True,
+    )
+    assert response.ok, response.text
+    assert response.headers["Content-Type"] == "application/x-ndjson"
+    return [json.loads(line) for line in response.iter_lines() if line]
+
+
+def test_stream_points():
+    # Unsorted, duplicated and missing IDs
+    points = stream_points({
+        "ids": [5, 3, 100, 1, 3],
+        "with_payload": True,
+        "with_vector": True,
+    })
+
+    assert [point["id"] for point in points] == [1, 3, 5]
+    assert all(point["payload"] is not None for point in points)
+    assert all(point["vector"] is not None for point in points)
+
+
+def test_stream_points_without_payload():
+    points = stream_points({
+        "ids": list(range(1, 11)),
+        "with_payload": False,
+    })
+
+    assert [point["id"] for point in points] == list(range(1, 11))
+    assert all(point.get("payload") is None for point in points)
+
+
+def test_stream_points_missing_collection():
+    response = requests.post(
+        f"{QDRANT_HOST}/collections/i-do-not-exist/points/stream",
+        json={"ids": [1]},
+    )
+    assert response.status_code == 404
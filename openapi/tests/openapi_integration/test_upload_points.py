@@ -0,0 +1,70 @@
+import json
+
+import pytest
+import requests
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+from .helpers.settings import QDRANT_HOST
+
+collection_name = 'test_collection_upload'
+
+
+@pytest.fixture(autouse=True)
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def points_ndjson(ids):
+    for point_id in ids:
+        point = {
+            "id": point_id,
+            "vector": [0.1, 0.2, 0.3, 0.4],
+            "payload": {"uploaded": True},
+        }
+        yield (json.dumps(point) + "\n").encode()
+
+
+def count_uploaded():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/count',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "filter": {"must": [{"key": "uploaded", "match": {"value": True}}]},
+            "exact": True,
+        }
+    )
+    assert response.ok
+    return response.json()['result']['count']
+
+
+def test_upload_points():
+    # A generator is sent with chunked transfer encoding
+    response = requests.post(
+        f"{QDRANT_HOST}/collections/{collection_name}/points/upload",
+        params={"wait": "true", "batch_size": 7},
+        data=points_ndjson(range(100, 150)),
+        headers={"Content-Type": "application/x-ndjson"},
+    )
+    assert response.ok, response.text
+    assert response.json()['result']['points_count'] == 50
+
+    assert count_uploaded() == 50
+
+
+def test_upload_invalid_line():
+    body = b"".join(points_ndjson([100, 101])) + b'\n{"id": 102, "vector": "nope"}\n'
+    response = requests.post(
+        f"{QDRANT_HOST}/collections/{collection_name}/points/upload",
+        params={"wait": "true"},
+        data=body,
+        headers={"Content-Type": "application/x-ndjson"},
+    )
+    assert response.status_code == 400
+    assert "line 4" in response.json()['status']['error']
+
+    # Points are upserted in a single batch, which is rejected as a whole
+    assert count_uploaded() == 0
@@ -0,0 +1,68 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_upsert_point_status'
+
+
+@pytest.fixture(autouse=True)
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_upsert_point_status():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true', 'point_status': 'true'},
+        body={
+            "points": [
+                {"id": 1, "vector": [0.05, 0.61, 0.76, 0.74]},
+                {"id": 100, "vector": [0.19, 0.81, 0.75, 0.11]},
+                {"id": 101, "vector": [0.19, 0.81]},
+                {"id": 102, "vector": {"unknown": [0.19, 0.81, 0.75, 0.11]}},
+            ]
+        }
+    )
+    assert response.ok, response.json()
+
+    result = response.json()["result"]
+    assert result["status"] == "completed"
+
+    points = result["points"]
+    assert [point["id"] for point in points] == [1, 100, 101, 102]
+    assert [point["status"] for point in points] == ["updated", "created", "rejected", "rejected"]
+    assert "reason" not in points[0]
+    assert "reason" not in points[1]
+    assert points[2]["reason"]
+    assert points[3]["reason"]
+
+    # Rejected points are not inserted, valid ones are
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"ids": [100, 101, 102]}
+    )
+    assert response.ok
+    assert [point["id"] for point in response.json()["result"]] == [100]
+
+
+def test_upsert_without_point_status():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 100, "vector": [0.19, 0.81, 0.75, 0.11]},
+            ]
+        }
+    )
+    assert response.ok
+    assert "points" not in response.json()["result"]
@@ -1,14 +1,19 @@
 use actix_web::rt::time::Instant;
-use actix_web::{delete, get, post, web, Responder};
-use actix_web_validator::Query;
+use actix_web::{delete, get, post, put, web, Responder};
+use actix_web_validator::{Json, Query};
+use futures::TryFutureExt as _;
 use serde::Deserialize;
+use storage::content_manager::collection_meta_ops::{ClusterLocks, CollectionMetaOperations};
 use storage::content_manager::consensus_ops::ConsensusOperations;
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
+use storage::types::{PeerMigration, PeerUriUpdate};
+use tonic::transport::Uri;
 use validator::Validate;
 
-use crate::actix::helpers::process_response;
+use crate::actix::helpers::{self, process_response};
+use crate::common::peer_migration;
 
 #[derive(Debug, Deserialize, Validate)]
 struct QueryParams {
@@ -19,6 +24,13 @@ struct QueryParams {
     timeout: Option<u64>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+struct WaitTimeout {
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    timeout: Option<u64>,
+}
+
 #[get("/cluster")]
 async fn cluster_status(dispatcher: web::Data<Dispatcher>) -> impl Responder {
     let timing = Instant::now();
@@ -68,9 +80,110 @@ async fn remove_peer(
     process_response(response, timing)
 }
 
+/// Change the URI other peers use to reach the peer, its id and data stay the same
+#[put("/cluster/peer/{peer_id}/uri")]
+async fn update_peer_uri(
+    dispatcher: web::Data<Dispatcher>,
+    peer_id: web::Path<u64>,
+    update: Json<PeerUriUpdate>,
+    Query(params): Query<WaitTimeout>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let peer_id = peer_id.into_inner();
+
+    let Some(consensus_state) = dispatcher.consensus_state() else {
+        return process_response::<bool>(
+            Err(StorageError::BadRequest {
+                description: "Distributed mode disabled.".to_string(),
+            }),
+            timing,
+        );
+    };
+
+    if !consensus_state.peer_address_by_id().contains_key(&peer_id) {
+        return process_response::<bool>(
+            Err(StorageError::NotFound {
+                description: format!("Peer {peer_id} is not part of the cluster"),
+            }),
+            timing,
+        );
+    }
+
+    let uri: Uri = match update.uri.parse() {
+        Ok(uri) => uri,
+        Err(err) => {
+            return process_response::<bool>(
+                Err(StorageError::BadRequest {
+                    description: format!("Invalid peer URI {}: {err}", update.uri),
+                }),
+                timing,
+            );
+        }
+    };
+
+    // Same operation, as sent by a restarted peer with a new `--uri`
+    let response = consensus_state
+        .propose_consensus_op_with_await(
+            ConsensusOperations::AddPeer {
+                peer_id,
+                uri: uri.to_string(),
+            },
+            params.timeout.map(std::time::Duration::from_secs),
+        )
+        .await;
+    process_response(response, timing)
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct MigrationParams {
+    #[serde(default)]
+    wait: Option<bool>,
+}
+
+/// Replicate all collections to new peers, verify the replicas and drop them on the old peers
+#[post("/cluster/migrate")]
+async fn migrate_peers(
+    dispatcher: web::Data<Dispatcher>,
+    migration: Json<PeerMigration>,
+    Query(params): Query<MigrationParams>,
+) -> impl Responder {
+    let future = peer_migration::migrate_peers(dispatcher.into_inner(), migration.into_inner())
+        .map_err(Into::into);
+    helpers::time_or_accept(future, params.wait.unwrap_or(true)).await
+}
+
+#[post("/cluster/locks")]
+async fn put_cluster_locks(
+    dispatcher: web::Data<Dispatcher>,
+    toc: web::Data<TableOfContent>,
+    cluster_locks: web::Json<ClusterLocks>,
+    Query(params): Query<WaitTimeout>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let previous_locks = toc.get_cluster_locks();
+    let response = dispatcher
+        .submit_collection_meta_op(
+            CollectionMetaOperations::SetClusterLocks(cluster_locks.into_inner()),
+            params.timeout.map(std::time::Duration::from_secs),
+        )
+        .await
+        .map(|_| previous_locks);
+    process_response(response, timing)
+}
+
+#[get("/cluster/locks")]
+async fn get_cluster_locks(toc: web::Data<TableOfContent>) -> impl Responder {
+    let timing = Instant::now();
+    process_response(Ok(toc.get_cluster_locks()), timing)
+}
+
 // Configure services
 pub fn config_cluster_api(cfg: &mut web::ServiceConfig) {
     cfg.service(cluster_status)
         .service(remove_peer)
-        .service(recover_current_peer);
+        .service(update_peer_uri)
+        .service(migrate_peers)
+        .service(recover_current_peer)
+        .service(put_cluster_locks)
+        .service(get_cluster_locks);
 }
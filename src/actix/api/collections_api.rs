@@ -1,13 +1,16 @@
 use std::time::Duration;
 
 use actix_web::rt::time::Instant;
-use actix_web::{delete, get, patch, post, put, web, Responder};
+use actix_web::{delete, get, patch, post, put, web, HttpResponse, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::cluster_ops::ClusterOperations;
+use collection::operations::types::{ExportPointsRequest, QuantizationEstimateRequest};
+use futures::TryStreamExt;
 use serde::Deserialize;
 use storage::content_manager::collection_meta_ops::{
-    ChangeAliasesOperation, CollectionMetaOperations, CreateCollection, CreateCollectionOperation,
-    DeleteCollectionOperation, UpdateCollection, UpdateCollectionOperation,
+    ChangeAliasesOperation, CloneCollection, CollectionMetaOperations, CreateCollection,
+    CreateCollectionOperation, DeleteCollectionOperation, UpdateCollection,
+    UpdateCollectionOperation,
 };
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
@@ -15,8 +18,11 @@ use validator::Validate;
 
 use super::CollectionPath;
 use crate::actix::api::StrictCollectionPath;
-use crate::actix::helpers::process_response;
+use crate::actix::helpers::{process_response, storage_into_actix_error};
+use crate::common::auth::{check_named_collections, Claims};
+use crate::common::change_feed::do_subscribe_changes;
 use crate::common::collections::*;
+use crate::common::export::{do_export_collection, do_export_points};
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct WaitTimeout {
@@ -30,6 +36,12 @@ impl WaitTimeout {
     }
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct ClusterInfoParams {
+    /// Compute checksums of local shards, reads all points of the local shards
+    with_checksums: Option<bool>,
+}
+
 #[get("/collections")]
 async fn get_collections(toc: web::Data<TableOfContent>) -> impl Responder {
     let timing = Instant::now();
@@ -70,8 +82,12 @@ async fn create_collection(
     collection: Path<StrictCollectionPath>,
     operation: Json<CreateCollection>,
     Query(query): Query<WaitTimeout>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
+    if let Err(err) = check_named_collections(claims.as_deref(), &*operation) {
+        return process_response::<()>(Err(err), timing);
+    }
     let response = dispatcher
         .submit_collection_meta_op(
             CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
@@ -123,6 +139,28 @@ async fn delete_collection(
     process_response(response, timing)
 }
 
+#[post("/collections/{name}/clone")]
+async fn clone_collection(
+    dispatcher: web::Data<Dispatcher>,
+    collection: Path<CollectionPath>,
+    operation: Json<CloneCollection>,
+    Query(query): Query<WaitTimeout>,
+    claims: Option<web::ReqData<Claims>>,
+) -> impl Responder {
+    let timing = Instant::now();
+    if let Err(err) = check_named_collections(claims.as_deref(), &*operation) {
+        return process_response::<()>(Err(err), timing);
+    }
+    let response = do_clone_collection(
+        dispatcher.get_ref(),
+        &collection.name,
+        operation.into_inner(),
+        query.timeout(),
+    )
+    .await;
+    process_response(response, timing)
+}
+
 #[post("/collections/aliases")]
 async fn update_aliases(
     dispatcher: web::Data<Dispatcher>,
@@ -143,9 +181,57 @@ async fn update_aliases(
 async fn get_cluster_info(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
+    Query(params): Query<ClusterInfoParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_collection_cluster(
+        toc.get_ref(),
+        &collection.name,
+        params.with_checksums.unwrap_or_default(),
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/cluster/distribution")]
+async fn get_shards_distribution(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_collection_shards_distribution(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/statistics")]
+async fn get_collection_statistics(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_collection_statistics(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/vectors")]
+async fn get_collection_vectors(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_collection_vectors(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/quantization/estimate")]
+async fn estimate_quantization(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<QuantizationEstimateRequest>,
 ) -> impl Responder {
     let timing = Instant::now();
-    let response = do_get_collection_cluster(toc.get_ref(), &collection.name).await;
+    let response =
+        do_estimate_quantization(toc.get_ref(), &collection.name, request.into_inner()).await;
     process_response(response, timing)
 }
 
@@ -168,6 +254,43 @@ async fn update_collection_cluster(
     process_response(response, timing)
 }
 
+#[get("/collections/{name}/export")]
+async fn export_collection(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let stream = do_export_collection(toc.into_inner(), collection.name.clone())
+        .await
+        .map_err(storage_into_actix_error)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(stream.map_err(storage_into_actix_error)))
+}
+
+#[get("/collections/{name}/changes")]
+async fn subscribe_changes(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let stream = do_subscribe_changes(toc.into_inner(), collection.name.clone())
+        .await
+        .map_err(storage_into_actix_error)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(stream.map_err(storage_into_actix_error)))
+}
+
+#[post("/collections/{name}/export")]
+async fn export_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<ExportPointsRequest>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_export_points(toc.get_ref(), &collection.name, request.into_inner()).await;
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
     cfg.service(get_collections)
@@ -175,10 +298,18 @@ pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
         .service(create_collection)
         .service(update_collection)
         .service(delete_collection)
+        .service(clone_collection)
         .service(get_aliases)
         .service(get_collection_aliases)
         .service(update_aliases)
         .service(get_cluster_info)
+        .service(get_shards_distribution)
+        .service(get_collection_statistics)
+        .service(get_collection_vectors)
+        .service(estimate_quantization)
+        .service(export_collection)
+        .service(export_points)
+        .service(subscribe_changes)
         .service(update_collection_cluster);
 }
 
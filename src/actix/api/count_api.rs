@@ -8,6 +8,7 @@ use storage::content_manager::toc::TableOfContent;
 use super::CollectionPath;
 use crate::actix::api::read_params::ReadParams;
 use crate::actix::helpers::process_response;
+use crate::common::auth::{restrict_filter, Claims};
 use crate::common::points::do_count_points;
 
 #[post("/collections/{name}/points/count")]
@@ -16,13 +17,15 @@ async fn count_points(
     collection: Path<CollectionPath>,
     request: Json<CountRequest>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
     let CountRequest {
-        count_request,
+        mut count_request,
         shard_key,
     } = request.into_inner();
+    count_request.filter = restrict_filter(claims.as_deref(), count_request.filter);
 
     let shard_selector = match shard_key {
         None => ShardSelectorInternal::All,
@@ -8,6 +8,7 @@ use tokio::time::Instant;
 use crate::actix::api::read_params::ReadParams;
 use crate::actix::api::CollectionPath;
 use crate::actix::helpers::process_response;
+use crate::common::auth::{check_named_collections, Claims};
 use crate::common::points::do_discover_batch_points;
 
 #[post("/collections/{name}/points/discover")]
@@ -16,6 +17,7 @@ async fn discover_points(
     collection: Path<CollectionPath>,
     request: Json<DiscoverRequest>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
@@ -23,6 +25,9 @@ async fn discover_points(
         discover_request,
         shard_key,
     } = request.into_inner();
+    if let Err(err) = check_named_collections(claims.as_deref(), &discover_request) {
+        return process_response::<()>(Err(err), timing);
+    }
 
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
@@ -48,13 +53,18 @@ async fn discover_batch_points(
     collection: Path<CollectionPath>,
     request: Json<DiscoverRequestBatch>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
+    let request = request.into_inner();
+    if let Err(err) = check_named_collections(claims.as_deref(), &request) {
+        return process_response::<()>(Err(err), timing);
+    }
 
     let response = do_discover_batch_points(
         toc.get_ref(),
         &collection.name,
-        request.into_inner(),
+        request,
         params.consistency,
         params.timeout(),
     )
@@ -15,6 +15,7 @@ use storage::content_manager::toc::TableOfContent;
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
+use crate::common::auth::{check_named_collections, Claims};
 
 #[post("/collections/{name}/points/recommend")]
 async fn recommend_points(
@@ -22,6 +23,7 @@ async fn recommend_points(
     collection: Path<CollectionPath>,
     request: Json<RecommendRequest>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
@@ -29,6 +31,9 @@ async fn recommend_points(
         recommend_request,
         shard_key,
     } = request.into_inner();
+    if let Err(err) = check_named_collections(claims.as_deref(), &recommend_request) {
+        return process_response::<()>(Err(err), timing);
+    }
 
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
@@ -56,7 +61,7 @@ async fn do_recommend_batch_points(
     timeout: Option<Duration>,
 ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
     let requests = request
-        .searches
+        .into_searches()
         .into_iter()
         .map(|req| {
             let shard_selector = match req.shard_key {
@@ -78,13 +83,18 @@ async fn recommend_batch_points(
     collection: Path<CollectionPath>,
     request: Json<RecommendRequestBatch>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
+    let request = request.into_inner();
+    if let Err(err) = check_named_collections(claims.as_deref(), &request) {
+        return process_response::<()>(Err(err), timing);
+    }
 
     let response = do_recommend_batch_points(
         toc.get_ref(),
         &collection.name,
-        request.into_inner(),
+        request,
         params.consistency,
         params.timeout(),
     )
@@ -99,6 +109,7 @@ async fn recommend_point_groups(
     collection: Path<CollectionPath>,
     request: Json<RecommendGroupsRequest>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
@@ -106,6 +117,9 @@ async fn recommend_point_groups(
         recommend_group_request,
         shard_key,
     } = request.into_inner();
+    if let Err(err) = check_named_collections(claims.as_deref(), &recommend_group_request) {
+        return process_response::<()>(Err(err), timing);
+    }
 
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
@@ -1,9 +1,10 @@
 use actix_web::rt::time::Instant;
-use actix_web::{get, post, web, Responder};
+use actix_web::{get, post, web, HttpResponse, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::{PointRequest, PointRequestInternal, Record, ScrollRequest};
+use futures::TryStreamExt;
 use segment::types::{PointIdType, WithPayloadInterface};
 use serde::Deserialize;
 use storage::content_manager::errors::StorageError;
@@ -12,8 +13,9 @@ use validator::Validate;
 
 use super::read_params::ReadParams;
 use super::CollectionPath;
-use crate::actix::helpers::process_response;
-use crate::common::points::do_get_points;
+use crate::actix::helpers::{process_response, storage_into_actix_error};
+use crate::common::auth::{restrict_filter, Claims};
+use crate::common::points::{do_get_points, do_stream_points};
 
 #[derive(Deserialize, Validate)]
 struct PointPath {
@@ -113,19 +115,52 @@ async fn get_points(
     process_response(response, timing)
 }
 
+#[post("/collections/{name}/points/stream")]
+async fn stream_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<PointRequest>,
+    params: Query<ReadParams>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let PointRequest {
+        point_request,
+        shard_key,
+    } = request.into_inner();
+
+    let shard_selection = match shard_key {
+        None => ShardSelectorInternal::All,
+        Some(shard_keys) => ShardSelectorInternal::from(shard_keys),
+    };
+
+    let stream = do_stream_points(
+        toc.into_inner(),
+        collection.name.clone(),
+        point_request,
+        params.consistency,
+        shard_selection,
+    )
+    .await
+    .map_err(storage_into_actix_error)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(stream.map_err(storage_into_actix_error)))
+}
+
 #[post("/collections/{name}/points/scroll")]
 async fn scroll_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     request: Json<ScrollRequest>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
     let ScrollRequest {
-        scroll_request,
+        mut scroll_request,
         shard_key,
     } = request.into_inner();
+    scroll_request.filter = restrict_filter(claims.as_deref(), scroll_request.filter);
 
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
@@ -3,15 +3,20 @@ use actix_web::{post, web, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::{
-    CoreSearchRequest, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
+    project_scored_points, CoreSearchRequest, SearchGroupsRequest, SearchMatrix,
+    SearchMatrixOffsetsResponse, SearchMatrixPairsResponse, SearchMatrixRequest, SearchRequest,
+    SearchRequestBatch,
 };
+use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
+use crate::common::auth::{check_named_collections, restrict_filter, Claims};
 use crate::common::points::{
-    do_core_search_points, do_search_batch_points, do_search_point_groups,
+    do_core_search_points, do_highlight_points, do_search_batch_points, do_search_point_groups,
+    do_search_points_matrix,
 };
 
 #[post("/collections/{name}/points/search")]
@@ -20,27 +25,49 @@ async fn search_points(
     collection: Path<CollectionPath>,
     request: Json<SearchRequest>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
     let SearchRequest {
-        search_request,
+        mut search_request,
         shard_key,
+        response_fields,
+        with_highlights,
     } = request.into_inner();
+    search_request.filter = restrict_filter(claims.as_deref(), search_request.filter);
+    let highlight_filter = with_highlights
+        .unwrap_or(false)
+        .then(|| search_request.filter.clone());
 
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
         Some(shard_keys) => shard_keys.into(),
     };
 
-    let response = do_core_search_points(
-        toc.get_ref(),
-        &collection.name,
-        search_request.into(),
-        params.consistency,
-        shard_selection,
-        params.timeout(),
-    )
+    let response = async {
+        let points = do_core_search_points(
+            toc.get_ref(),
+            &collection.name,
+            search_request.into(),
+            params.consistency,
+            shard_selection,
+            params.timeout(),
+        )
+        .await?;
+        let highlights = match highlight_filter {
+            Some(filter) => Some(
+                do_highlight_points(toc.get_ref(), &collection.name, filter.as_ref(), &points)
+                    .await?,
+            ),
+            None => None,
+        };
+        Ok::<_, StorageError>(project_scored_points(
+            points,
+            response_fields.as_deref(),
+            highlights,
+        ))
+    }
     .await;
 
     process_response(response, timing)
@@ -52,18 +79,33 @@ async fn batch_search_points(
     collection: Path<CollectionPath>,
     request: Json<SearchRequestBatch>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
     let request = request.into_inner();
+    let response_options: Vec<_> = request
+        .searches
+        .iter()
+        .map(|req| {
+            let highlight_filter = req
+                .with_highlights
+                .unwrap_or(false)
+                .then(|| restrict_filter(claims.as_deref(), req.search_request.filter.clone()));
+            (req.response_fields.clone(), highlight_filter)
+        })
+        .collect();
     let requests = request
         .searches
         .into_iter()
         .map(|req| {
             let SearchRequest {
-                search_request,
+                mut search_request,
                 shard_key,
+                response_fields: _,
+                with_highlights: _,
             } = req;
+            search_request.filter = restrict_filter(claims.as_deref(), search_request.filter);
             let shard_selection = match shard_key {
                 None => ShardSelectorInternal::All,
                 Some(shard_keys) => shard_keys.into(),
@@ -74,13 +116,29 @@ async fn batch_search_points(
         })
         .collect();
 
-    let response = do_search_batch_points(
-        toc.get_ref(),
-        &collection.name,
-        requests,
-        params.consistency,
-        params.timeout(),
-    )
+    let response = async {
+        let batch = do_search_batch_points(
+            toc.get_ref(),
+            &collection.name,
+            requests,
+            params.consistency,
+            params.timeout(),
+        )
+        .await?;
+
+        let mut results = Vec::with_capacity(batch.len());
+        for (points, (fields, highlight_filter)) in batch.into_iter().zip(response_options) {
+            let highlights = match highlight_filter {
+                Some(filter) => Some(
+                    do_highlight_points(toc.get_ref(), &collection.name, filter.as_ref(), &points)
+                        .await?,
+                ),
+                None => None,
+            };
+            results.push(project_scored_points(points, fields.as_deref(), highlights));
+        }
+        Ok::<_, StorageError>(results)
+    }
     .await;
 
     process_response(response, timing)
@@ -92,13 +150,18 @@ async fn search_point_groups(
     collection: Path<CollectionPath>,
     request: Json<SearchGroupsRequest>,
     params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
     let SearchGroupsRequest {
-        search_group_request,
+        mut search_group_request,
         shard_key,
     } = request.into_inner();
+    search_group_request.filter = restrict_filter(claims.as_deref(), search_group_request.filter);
+    if let Err(err) = check_named_collections(claims.as_deref(), &search_group_request) {
+        return process_response::<()>(Err(err), timing);
+    }
 
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
@@ -118,9 +181,87 @@ async fn search_point_groups(
     process_response(response, timing)
 }
 
+/// Shared body of the search matrix endpoints, which differ only in the response format
+async fn search_points_matrix(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: SearchMatrixRequest,
+    params: &ReadParams,
+    claims: Option<&Claims>,
+) -> Result<SearchMatrix, StorageError> {
+    let SearchMatrixRequest {
+        mut search_request,
+        shard_key,
+    } = request;
+    search_request.filter = restrict_filter(claims, search_request.filter);
+
+    let shard_selection = match shard_key {
+        None => ShardSelectorInternal::All,
+        Some(shard_keys) => shard_keys.into(),
+    };
+
+    do_search_points_matrix(
+        toc,
+        collection_name,
+        search_request,
+        params.consistency,
+        shard_selection,
+        params.timeout(),
+    )
+    .await
+}
+
+#[post("/collections/{name}/points/search/matrix/pairs")]
+async fn search_points_matrix_pairs(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<SearchMatrixRequest>,
+    params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = search_points_matrix(
+        toc.get_ref(),
+        &collection.name,
+        request.into_inner(),
+        &params,
+        claims.as_deref(),
+    )
+    .await
+    .map(SearchMatrixPairsResponse::from);
+
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/search/matrix/offsets")]
+async fn search_points_matrix_offsets(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<SearchMatrixRequest>,
+    params: Query<ReadParams>,
+    claims: Option<web::ReqData<Claims>>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = search_points_matrix(
+        toc.get_ref(),
+        &collection.name,
+        request.into_inner(),
+        &params,
+        claims.as_deref(),
+    )
+    .await
+    .map(SearchMatrixOffsetsResponse::from);
+
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_search_api(cfg: &mut web::ServiceConfig) {
     cfg.service(search_points)
         .service(batch_search_points)
-        .service(search_point_groups);
+        .service(search_point_groups)
+        .service(search_points_matrix_pairs)
+        .service(search_points_matrix_offsets);
 }
@@ -9,6 +9,7 @@ use actix_web_validator::Json;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
+use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use tokio::sync::Mutex;
 
@@ -19,6 +20,11 @@ use crate::common::metrics::MetricsData;
 use crate::common::stacktrace::get_stack_trace;
 use crate::common::telemetry::TelemetryCollector;
 
+/// Specification of the REST API, generated from the route definitions in `openapi/` and the
+/// request and response types, see `tools/generate_openapi_models.sh`.
+/// `tests/openapi_routes_check.py` makes sure it describes every route of the service.
+const OPENAPI_SPEC: &str = include_str!("../../../docs/redoc/master/openapi.json");
+
 #[derive(Deserialize, Serialize, JsonSchema)]
 pub struct TelemetryParam {
     pub anonymize: Option<bool>,
@@ -43,6 +49,20 @@ async fn telemetry(
     process_response(Ok(telemetry_data), timing)
 }
 
+#[get("/usage")]
+async fn usage(
+    telemetry_collector: web::Data<Mutex<TelemetryCollector>>,
+    toc: web::Data<TableOfContent>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let usage_meter = telemetry_collector.lock().await.usage_meter.clone();
+    let result = match usage_meter {
+        Some(usage_meter) => Ok(usage_meter.report(toc.get_ref()).await),
+        None => Err(StorageError::bad_request("Usage metering is disabled")),
+    };
+    process_response(result, timing)
+}
+
 #[derive(Deserialize, Serialize, JsonSchema)]
 pub struct MetricsParam {
     pub anonymize: Option<bool>,
@@ -135,13 +155,22 @@ async fn kubernetes_healthz() -> impl Responder {
 }
 
 // Configure services
+#[get("/openapi.json")]
+async fn openapi_spec() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .body(OPENAPI_SPEC)
+}
+
 pub fn config_service_api(cfg: &mut web::ServiceConfig) {
     cfg.service(telemetry)
+        .service(usage)
         .service(metrics)
         .service(put_locks)
         .service(get_locks)
         .service(get_stacktrace)
         .service(healthz)
         .service(livez)
-        .service(readyz);
+        .service(readyz)
+        .service(openapi_spec);
 }
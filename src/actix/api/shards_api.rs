@@ -2,7 +2,7 @@ use actix_web::{post, put, web, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::cluster_ops::{
     ClusterOperations, CreateShardingKey, CreateShardingKeyOperation, DropShardingKey,
-    DropShardingKeyOperation,
+    DropShardingKeyOperation, SplitShardingKey, SplitShardingKeyOperation,
 };
 use storage::dispatcher::Dispatcher;
 use tokio::time::Instant;
@@ -79,6 +79,36 @@ async fn delete_shard_key(
     process_response(response, timing)
 }
 
+#[post("/collections/{name}/shards/split")]
+async fn split_shard_key(
+    dispatcher: web::Data<Dispatcher>,
+    collection: Path<CollectionPath>,
+    request: Json<SplitShardingKey>,
+    Query(query): Query<WaitTimeout>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let wait_timeout = query.timeout();
+
+    let dispatcher = dispatcher.into_inner();
+    let request = request.into_inner();
+
+    let operation = ClusterOperations::SplitShardingKey(SplitShardingKeyOperation {
+        split_sharding_key: request,
+    });
+
+    let response = do_update_collection_cluster(
+        &dispatcher,
+        collection.name.clone(),
+        operation,
+        wait_timeout,
+    )
+    .await;
+
+    process_response(response, timing)
+}
+
 pub fn config_shards_api(cfg: &mut web::ServiceConfig) {
-    cfg.service(create_shard_key).service(delete_shard_key);
+    cfg.service(create_shard_key)
+        .service(delete_shard_key)
+        .service(split_shard_key);
 }
@@ -1,27 +1,32 @@
+use std::io;
+
 use actix_files::NamedFile;
 use actix_multipart::form::tempfile::TempFile;
 use actix_multipart::form::MultipartForm;
+use actix_multipart::Multipart;
 use actix_web::rt::time::Instant;
 use actix_web::{delete, get, post, put, web, Responder, Result};
 use actix_web_validator as valid;
-use collection::common::file_utils::move_file;
+use collection::common::sha_256;
 use collection::operations::snapshot_ops::{
-    ShardSnapshotRecover, SnapshotPriority, SnapshotRecover,
+    ShardSnapshotRecover, SnapshotPriority, SnapshotRecover, SnapshotViewRequest,
 };
 use collection::shards::shard::ShardId;
-use futures::{FutureExt as _, TryFutureExt as _};
-use reqwest::Url;
+use futures::channel::mpsc;
+use futures::{FutureExt as _, SinkExt as _, StreamExt as _, TryFutureExt as _};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use storage::content_manager::errors::StorageError;
-use storage::content_manager::snapshots::recover::do_recover_from_snapshot;
+use storage::content_manager::snapshots::recover::{
+    do_create_snapshot_view, do_recover_from_snapshot, do_recover_from_snapshot_reader,
+};
 use storage::content_manager::snapshots::{
     do_create_full_snapshot, do_delete_collection_snapshot, do_delete_full_snapshot,
     do_list_full_snapshots, get_full_snapshot_path,
 };
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
-use uuid::Uuid;
+use tokio_util::io::StreamReader;
 use validator::Validate;
 
 use super::CollectionPath;
@@ -44,6 +49,8 @@ struct SnapshotPath {
 pub struct SnapshotUploadingParam {
     pub wait: Option<bool>,
     pub priority: Option<SnapshotPriority>,
+    /// Optional SHA256 checksum to verify the uploaded snapshot against
+    pub checksum: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
@@ -65,37 +72,61 @@ pub async fn do_get_full_snapshot(toc: &TableOfContent, snapshot_name: &str) ->
     Ok(NamedFile::open(file_name)?)
 }
 
-pub async fn do_save_uploaded_snapshot(
-    toc: &TableOfContent,
+/// Capacity of the channel passing uploaded snapshot chunks to the unpacking task
+const UPLOAD_CHANNEL_CAPACITY: usize = 16;
+
+/// Recover collection from the `snapshot` field of a multipart upload.
+///
+/// The snapshot is unpacked as it is received, without storing the uploaded file on disk.
+pub async fn do_recover_from_uploaded_snapshot(
+    dispatcher: &Dispatcher,
     collection_name: &str,
-    snapshot: TempFile,
-) -> std::result::Result<Url, StorageError> {
-    let filename = snapshot
-        .file_name
-        .unwrap_or_else(|| Uuid::new_v4().to_string());
-    let collection_snapshot_path = toc.snapshots_path_for_collection(collection_name);
-    if !collection_snapshot_path.exists() {
-        log::debug!(
-            "Creating missing collection snapshots directory for {}",
-            collection_name
+    mut multipart: Multipart,
+    priority: Option<SnapshotPriority>,
+    checksum: Option<String>,
+    wait: bool,
+) -> std::result::Result<bool, StorageError> {
+    while let Some(field) = multipart.next().await {
+        let mut field = field.map_err(|err| {
+            StorageError::bad_request(format!("Failed to read snapshot upload: {err}"))
+        })?;
+        if field.content_disposition().get_name() != Some("snapshot") {
+            continue;
+        }
+
+        // Multipart payload can't be moved to the blocking unpacking task, forward its chunks
+        let (mut sender, receiver) = mpsc::channel(UPLOAD_CHANNEL_CAPACITY);
+        let receiving = async move {
+            while let Some(chunk) = field.next().await {
+                let chunk = chunk.map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("Snapshot upload failed: {err}"),
+                    )
+                });
+                let failed = chunk.is_err();
+                // Sending fails if unpacking stopped, its error is reported instead
+                if sender.send(chunk).await.is_err() || failed {
+                    break;
+                }
+            }
+        };
+        let recovering = do_recover_from_snapshot_reader(
+            dispatcher,
+            collection_name,
+            StreamReader::new(receiver),
+            priority,
+            checksum,
+            wait,
         );
-        toc.create_snapshots_path(collection_name).await?;
-    }
-
-    let path = collection_snapshot_path.join(filename);
-
-    move_file(snapshot.file.path(), &path).await?;
 
-    let absolute_path = path.canonicalize()?;
-
-    let snapshot_location = Url::from_file_path(&absolute_path).map_err(|_| {
-        StorageError::service_error(format!(
-            "Failed to convert path to URL: {}",
-            absolute_path.display()
-        ))
-    })?;
+        let ((), recovered) = futures::join!(receiving, recovering);
+        return recovered;
+    }
 
-    Ok(snapshot_location)
+    Err(StorageError::bad_request(
+        "Snapshot upload must contain a `snapshot` field",
+    ))
 }
 
 // Actix specific code
@@ -109,12 +140,18 @@ pub async fn do_get_snapshot(
         .await
         .map_err(storage_into_actix_error)?;
 
-    let file_name = collection
-        .get_snapshot_path(snapshot_name)
+    let temp_dir = toc
+        .optional_temp_or_snapshot_temp_path()
+        .map_err(collection_into_actix_error)?;
+
+    // Temporary copy of a remotely stored snapshot is deleted after it is opened
+    let (snapshot_path, _snapshot_temp_path) = collection
+        .get_snapshot_file(snapshot_name, &temp_dir)
         .await
         .map_err(collection_into_actix_error)?;
 
-    Ok(NamedFile::open(file_name)?)
+    let file = std::fs::File::open(snapshot_path)?;
+    Ok(NamedFile::from_file(file, snapshot_name)?)
 }
 
 #[get("/collections/{name}/snapshots")]
@@ -147,37 +184,20 @@ async fn create_snapshot(
 #[post("/collections/{name}/snapshots/upload")]
 async fn upload_snapshot(
     dispatcher: web::Data<Dispatcher>,
-    http_client: web::Data<HttpClient>,
     collection: valid::Path<CollectionPath>,
-    MultipartForm(form): MultipartForm<SnapshottingForm>,
+    multipart: Multipart,
     params: valid::Query<SnapshotUploadingParam>,
 ) -> impl Responder {
     let timing = Instant::now();
-    let snapshot = form.snapshot;
     let wait = params.wait.unwrap_or(true);
 
-    let snapshot_location =
-        match do_save_uploaded_snapshot(dispatcher.get_ref(), &collection.name, snapshot).await {
-            Ok(location) => location,
-            Err(err) => return process_response::<()>(Err(err), timing),
-        };
-
-    let http_client = match http_client.client() {
-        Ok(http_client) => http_client,
-        Err(err) => return process_response::<()>(Err(err.into()), timing),
-    };
-
-    let snapshot_recover = SnapshotRecover {
-        location: snapshot_location,
-        priority: params.priority,
-    };
-
-    let response = do_recover_from_snapshot(
+    let response = do_recover_from_uploaded_snapshot(
         dispatcher.get_ref(),
         &collection.name,
-        snapshot_recover,
+        multipart,
+        params.priority,
+        params.checksum.clone(),
         wait,
-        http_client,
     )
     .await;
 
@@ -221,6 +241,31 @@ async fn recover_from_snapshot(
     }
 }
 
+#[post("/collections/{name}/snapshots/{snapshot_name}/view")]
+async fn create_snapshot_view(
+    dispatcher: web::Data<Dispatcher>,
+    path: web::Path<(String, String)>,
+    request: valid::Json<SnapshotViewRequest>,
+    params: valid::Query<SnapshottingParam>,
+) -> impl Responder {
+    let (collection_name, snapshot_name) = path.into_inner();
+    let timing = Instant::now();
+    let wait = params.wait.unwrap_or(true);
+    let response = do_create_snapshot_view(
+        dispatcher.get_ref(),
+        &collection_name,
+        &snapshot_name,
+        &request.view_name,
+        wait,
+    )
+    .await;
+    match response {
+        Err(_) => process_response(response, timing),
+        Ok(_) if wait => process_response(response, timing),
+        Ok(_) => accepted_response(timing),
+    }
+}
+
 #[get("/collections/{name}/snapshots/{snapshot_name}")]
 async fn get_snapshot(
     toc: web::Data<TableOfContent>,
@@ -358,7 +403,11 @@ async fn upload_shard_snapshot(
     MultipartForm(form): MultipartForm<SnapshottingForm>,
 ) -> impl Responder {
     let (collection, shard) = path.into_inner();
-    let SnapshotUploadingParam { wait, priority } = query.into_inner();
+    let SnapshotUploadingParam {
+        wait,
+        priority,
+        checksum,
+    } = query.into_inner();
 
     // - `recover_shard_snapshot_impl` is *not* cancel safe
     //   - but the task is *spawned* on the runtime and won't be cancelled, if request is cancelled
@@ -373,6 +422,18 @@ async fn upload_shard_snapshot(
 
         let collection = cancel::future::cancel_on_token(cancel.clone(), future).await??;
 
+        if let Some(checksum) = checksum {
+            let snapshot_path = form.snapshot.file.path().to_path_buf();
+            let snapshot_checksum =
+                tokio::task::spawn_blocking(move || sha_256::hash_file(&snapshot_path)).await??;
+            if !sha_256::hashes_equal(&snapshot_checksum, &checksum) {
+                return Err(StorageError::bad_input(format!(
+                    "Snapshot checksum mismatch: expected {checksum}, got {snapshot_checksum}"
+                ))
+                .into());
+            }
+        }
+
         // `recover_shard_snapshot_impl` is *not* cancel safe
         common::snapshots::recover_shard_snapshot_impl(
             &toc,
@@ -425,6 +486,7 @@ pub fn config_snapshots_api(cfg: &mut web::ServiceConfig) {
         .service(create_snapshot)
         .service(upload_snapshot)
         .service(recover_from_snapshot)
+        .service(create_snapshot_view)
         .service(get_snapshot)
         .service(list_full_snapshots)
         .service(create_full_snapshot)
@@ -2,7 +2,9 @@ use actix_web::rt::time::Instant;
 use actix_web::{delete, post, put, web, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::payload_ops::{DeletePayload, SetPayload};
-use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
+use collection::operations::point_ops::{
+    CopyPoints, PointInsertOperations, PointsSelector, WriteOrdering,
+};
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -12,11 +14,15 @@ use validator::Validate;
 
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
+use crate::common::auth::{check_named_collections, Claims};
+use crate::common::http_client::HttpClient;
+use crate::common::import::{do_import_points, do_upload_points, ImportPoints};
 use crate::common::points::{
     do_batch_update_points, do_clear_payload, do_create_index, do_delete_index, do_delete_payload,
     do_delete_points, do_delete_vectors, do_overwrite_payload, do_set_payload, do_update_vectors,
-    do_upsert_points, CreateFieldIndex, UpdateOperations,
+    do_upsert_points, do_upsert_points_with_status, CreateFieldIndex, UpdateOperations,
 };
+use crate::settings::ImportConfig;
 
 #[derive(Deserialize, Validate)]
 struct FieldPath {
@@ -31,11 +37,55 @@ pub struct UpdateParam {
     pub ordering: Option<WriteOrdering>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct UpsertParam {
+    pub wait: Option<bool>,
+    pub ordering: Option<WriteOrdering>,
+    /// If true, report status of each point and reject invalid points instead of failing the batch
+    pub point_status: Option<bool>,
+}
+
 #[put("/collections/{name}/points")]
 async fn upsert_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     operation: Json<PointInsertOperations>,
+    params: Query<UpsertParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = if params.point_status.unwrap_or(false) {
+        do_upsert_points_with_status(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        )
+        .await
+    } else {
+        do_upsert_points(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        )
+        .await
+    };
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/delete")]
+async fn delete_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<PointsSelector>,
     params: Query<UpdateParam>,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -43,7 +93,7 @@ async fn upsert_points(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_upsert_points(
+    let response = do_delete_points(
         toc.get_ref(),
         &collection.name,
         operation,
@@ -55,23 +105,85 @@ async fn upsert_points(
     process_response(response, timing)
 }
 
-#[post("/collections/{name}/points/delete")]
-async fn delete_points(
+#[post("/collections/{name}/points/copy")]
+async fn copy_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
-    operation: Json<PointsSelector>,
+    operation: Json<CopyPoints>,
     params: Query<UpdateParam>,
+    claims: Option<web::ReqData<Claims>>,
 ) -> impl Responder {
     let timing = Instant::now();
     let operation = operation.into_inner();
+    if let Err(err) = check_named_collections(claims.as_deref(), &operation) {
+        return process_response::<()>(Err(err), timing);
+    }
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_delete_points(
+    let response = toc
+        .copy_points(&collection.name, operation, wait, ordering)
+        .await;
+    process_response(response, timing)
+}
+
+#[put("/collections/{name}/points/import")]
+async fn import_points(
+    toc: web::Data<TableOfContent>,
+    http_client: web::Data<HttpClient>,
+    import_config: web::Data<ImportConfig>,
+    collection: Path<CollectionPath>,
+    request: Json<ImportPoints>,
+    params: Query<UpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let request = request.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let http_client = match http_client.client_without_redirects() {
+        Ok(http_client) => http_client,
+        Err(err) => return process_response::<()>(Err(err.into()), timing),
+    };
+
+    let response = do_import_points(
         toc.get_ref(),
         &collection.name,
-        operation,
-        None,
+        request,
+        http_client,
+        import_config.get_ref(),
+        wait,
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct UploadParam {
+    pub wait: Option<bool>,
+    pub ordering: Option<WriteOrdering>,
+    /// Number of points to upsert at once. Default: 1000
+    #[validate(range(min = 1))]
+    pub batch_size: Option<usize>,
+}
+
+#[post("/collections/{name}/points/upload")]
+async fn upload_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    body: web::Payload,
+    params: Query<UploadParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = do_upload_points(
+        toc.get_ref(),
+        &collection.name,
+        body,
+        params.batch_size,
         wait,
         ordering,
     )
@@ -297,6 +409,9 @@ async fn delete_field_index(
 pub fn config_update_api(cfg: &mut web::ServiceConfig) {
     cfg.service(upsert_points)
         .service(delete_points)
+        .service(copy_points)
+        .service(import_points)
+        .service(upload_points)
         .service(update_vectors)
         .service(delete_vectors)
         .service(set_payload)
@@ -10,8 +10,9 @@ use futures_util::future::LocalBoxFuture;
 
 use crate::common::auth::AuthKeys;
 
-const READ_ONLY_POST_PATTERNS: [&str; 11] = [
+const READ_ONLY_POST_PATTERNS: [&str; 15] = [
     "/collections/{name}/points",
+    "/collections/{name}/points/stream",
     "/collections/{name}/points/count",
     "/collections/{name}/points/search",
     "/collections/{name}/points/scroll",
@@ -22,8 +23,26 @@ const READ_ONLY_POST_PATTERNS: [&str; 11] = [
     "/collections/{name}/points/recommend/batch",
     "/collections/{name}/points/discover",
     "/collections/{name}/points/discover/batch",
+    "/collections/{name}/points/search/matrix/pairs",
+    "/collections/{name}/points/search/matrix/offsets",
+    "/collections/{name}/facet",
 ];
 
+/// Requests, which can be made with JWT tokens with a filter claim.
+/// The filter is added to the filter of the request by the handler.
+const FILTERED_PATTERNS: [&str; 7] = [
+    "/collections/{name}/points/count",
+    "/collections/{name}/points/search",
+    "/collections/{name}/points/scroll",
+    "/collections/{name}/points/search/groups",
+    "/collections/{name}/points/search/batch",
+    "/collections/{name}/points/search/matrix/pairs",
+    "/collections/{name}/points/search/matrix/offsets",
+];
+
+/// Requests under `/collections`, which are not addressed to a single collection
+const NON_COLLECTION_PATTERNS: [&str; 1] = ["/collections/aliases"];
+
 pub struct ApiKey {
     auth_keys: Option<AuthKeys>,
     whitelist: Vec<WhitelistItem>,
@@ -125,25 +144,34 @@ where
             return Box::pin(self.service.call(req));
         }
 
-        // Grab API key from request
-        let key =
-            // Request header
-            req.headers().get("api-key").and_then(|key| key.to_str().ok()).map(|key| key.to_string())
-                // Fall back to authentication header with bearer token
-                .or_else(|| {
-                    Authorization::<Bearer>::parse(&req).ok().map(|auth| auth.as_ref().token().into())
-                });
-
-        if let Some(key) = key {
-            let is_allowed = if let Some(ref auth_keys) = self.auth_keys {
-                auth_keys.can_write(&key) || (is_read_only(&req) && auth_keys.can_read(&key))
+        if let Some(key) = request_api_key(&req) {
+            if let Some(ref auth_keys) = self.auth_keys {
+                let is_read_only = is_read_only(&req);
+                let collection = if is_non_collection(&req) {
+                    None
+                } else {
+                    collection_name(path)
+                };
+                if auth_keys.can_access(&key, is_read_only, collection) {
+                    // Claims are used by handlers to check other collections, named in the body
+                    if let Some(claims) = auth_keys.scoped_claims(&key) {
+                        req.extensions_mut().insert(claims);
+                    }
+                    return Box::pin(self.service.call(req));
+                }
+
+                if let Some(claims) = auth_keys.validate_jwt(&key) {
+                    if claims.allows(is_read_only, collection)
+                        && (claims.filter.is_none() || is_filtered(&req))
+                    {
+                        // Claims are used by handlers to restrict the request
+                        req.extensions_mut().insert(claims);
+                        return Box::pin(self.service.call(req));
+                    }
+                }
             } else {
                 // This code path should not be reached
                 log::warn!("Auth for REST API is set up incorrectly. Denying access by default.");
-                false
-            };
-            if is_allowed {
-                return Box::pin(self.service.call(req));
             }
         }
 
@@ -155,13 +183,74 @@ where
     }
 }
 
+/// Grab API key from request
+pub(super) fn request_api_key(req: &ServiceRequest) -> Option<String> {
+    // Request header
+    req.headers()
+        .get("api-key")
+        .and_then(|key| key.to_str().ok())
+        .map(|key| key.to_string())
+        // Fall back to authentication header with bearer token
+        .or_else(|| {
+            Authorization::<Bearer>::parse(req)
+                .ok()
+                .map(|auth| auth.as_ref().token().into())
+        })
+}
+
+/// Name of the collection, the request is addressed to
+pub(super) fn collection_name(path: &str) -> Option<&str> {
+    path.strip_prefix("/collections/")?
+        .split('/')
+        .next()
+        .filter(|name| !name.is_empty())
+}
+
+fn is_non_collection(req: &ServiceRequest) -> bool {
+    req.match_pattern()
+        .is_some_and(|pattern| NON_COLLECTION_PATTERNS.contains(&pattern.as_str()))
+}
+
+fn is_filtered(req: &ServiceRequest) -> bool {
+    *req.method() == Method::POST
+        && req
+            .match_pattern()
+            .is_some_and(|pattern| FILTERED_PATTERNS.contains(&pattern.as_str()))
+}
+
 fn is_read_only(req: &ServiceRequest) -> bool {
     match *req.method() {
         Method::GET => true,
         Method::POST => req
             .match_pattern()
-            .map(|pattern| READ_ONLY_POST_PATTERNS.iter().any(|pat| &pattern == pat))
-            .unwrap_or_default(),
+            .is_some_and(|pattern| is_read_only_post_pattern(&pattern)),
         _ => false,
     }
 }
+
+fn is_read_only_post_pattern(pattern: &str) -> bool {
+    READ_ONLY_POST_PATTERNS.contains(&pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_search_matrix() {
+        assert!(is_read_only_post_pattern(
+            "/collections/{name}/points/search/matrix/pairs"
+        ));
+        assert!(is_read_only_post_pattern(
+            "/collections/{name}/points/search/matrix/offsets"
+        ));
+        assert!(!is_read_only_post_pattern(
+            "/collections/{name}/points/delete"
+        ));
+    }
+
+    #[test]
+    fn test_read_only_facet() {
+        assert!(is_read_only_post_pattern("/collections/{name}/facet"));
+    }
+}
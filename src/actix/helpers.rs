@@ -24,6 +24,8 @@ pub fn storage_into_actix_error(err: StorageError) -> Error {
         StorageError::BadRequest { .. } => error::ErrorBadRequest(format!("{err}")),
         StorageError::Locked { .. } => error::ErrorForbidden(format!("{err}")),
         StorageError::Timeout { .. } => error::ErrorRequestTimeout(format!("{err}")),
+        StorageError::RateLimitExceeded { .. } => error::ErrorTooManyRequests(format!("{err}")),
+        StorageError::Forbidden { .. } => error::ErrorForbidden(format!("{err}")),
     }
 }
 
@@ -64,6 +66,8 @@ where
                 StorageError::BadRequest { .. } => HttpResponse::BadRequest(),
                 StorageError::Locked { .. } => HttpResponse::Forbidden(),
                 StorageError::Timeout { .. } => HttpResponse::RequestTimeout(),
+                StorageError::RateLimitExceeded { .. } => HttpResponse::TooManyRequests(),
+                StorageError::Forbidden { .. } => HttpResponse::Forbidden(),
             };
 
             resp.json(ApiResponse::<()> {
@@ -190,6 +194,10 @@ impl From<StorageError> for HttpError {
             StorageError::Timeout { description } => {
                 (http::StatusCode::REQUEST_TIMEOUT, description)
             }
+            StorageError::RateLimitExceeded { description } => {
+                (http::StatusCode::TOO_MANY_REQUESTS, description)
+            }
+            StorageError::Forbidden { description } => (http::StatusCode::FORBIDDEN, description),
         };
 
         Self {
@@ -2,9 +2,10 @@
 pub mod actix_telemetry;
 pub mod api;
 mod api_key;
-mod certificate_helpers;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod helpers;
+mod request_size_limit;
+mod usage_metering;
 
 use std::io;
 use std::path::Path;
@@ -23,18 +24,21 @@ use crate::actix::api::cluster_api::config_cluster_api;
 use crate::actix::api::collections_api::config_collections_api;
 use crate::actix::api::count_api::count_points;
 use crate::actix::api::discovery_api::config_discovery_api;
+use crate::actix::api::facet_api::facet_points;
 use crate::actix::api::recommend_api::config_recommend_api;
-use crate::actix::api::retrieve_api::{get_point, get_points, scroll_points};
+use crate::actix::api::retrieve_api::{get_point, get_points, scroll_points, stream_points};
 use crate::actix::api::search_api::config_search_api;
 use crate::actix::api::service_api::config_service_api;
 use crate::actix::api::shards_api::config_shards_api;
 use crate::actix::api::snapshot_api::config_snapshots_api;
 use crate::actix::api::update_api::config_update_api;
 use crate::actix::api_key::{ApiKey, WhitelistItem};
+use crate::actix::request_size_limit::{RequestSizeLimit, RequestSizeLimits};
+use crate::actix::usage_metering::UsageMetering;
 use crate::common::auth::AuthKeys;
-use crate::common::health;
 use crate::common::http_client::HttpClient;
 use crate::common::telemetry::TelemetryCollector;
+use crate::common::{certificate_helpers, health};
 use crate::settings::{max_web_workers, Settings};
 
 const DEFAULT_STATIC_DIR: &str = "./static";
@@ -60,8 +64,10 @@ pub fn init(
             .await
             .actix_telemetry_collector
             .clone();
+        let usage_meter = telemetry_collector.lock().await.usage_meter.clone();
         let telemetry_collector_data = web::Data::from(telemetry_collector);
         let http_client = web::Data::new(HttpClient::from_settings(&settings)?);
+        let import_config = web::Data::new(settings.service.import.clone());
         let health_checker = web::Data::new(health_checker);
         let auth_keys = AuthKeys::try_create(&settings.service);
         let static_folder = settings
@@ -101,6 +107,7 @@ pub fn init(
         }
 
         let upload_dir = dispatcher_data.upload_dir().unwrap();
+        let request_size_limits = RequestSizeLimits::from_config(&settings.service);
 
         let mut server = HttpServer::new(move || {
             let cors = Cors::default()
@@ -112,11 +119,14 @@ pub fn init(
             let validate_query_config = actix_web_validator::QueryConfig::default()
                 .error_handler(|err, rec| validation_error_handler("query parameters", err, rec));
             let validate_json_config = actix_web_validator::JsonConfig::default()
-                .limit(settings.service.max_request_size_mb * 1024 * 1024)
+                .limit(request_size_limits.max_json())
                 .error_handler(|err, rec| validation_error_handler("JSON body", err, rec));
 
             let mut app = App::new()
+                // Executed after the api_key middleware, so only authorized requests are metered
+                .wrap(UsageMetering::new(usage_meter.clone()))
                 .wrap(Compress::default()) // Reads the `Accept-Encoding` header to negotiate which compression codec to use.
+                .wrap(RequestSizeLimit::new(request_size_limits))
                 // api_key middleware
                 // note: the last call to `wrap()` or `wrap_fn()` is executed first
                 .wrap(Condition::new(
@@ -132,6 +142,7 @@ pub fn init(
                 .app_data(toc_data.clone())
                 .app_data(telemetry_collector_data.clone())
                 .app_data(http_client.clone())
+                .app_data(import_config.clone())
                 .app_data(health_checker.clone())
                 .app_data(validate_path_config)
                 .app_data(validate_query_config)
@@ -150,8 +161,10 @@ pub fn init(
                 .configure(config_shards_api)
                 .service(get_point)
                 .service(get_points)
+                .service(stream_points)
                 .service(scroll_points)
-                .service(count_points);
+                .service(count_points)
+                .service(facet_points);
 
             if web_ui_available {
                 app = app.service(
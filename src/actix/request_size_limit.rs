@@ -0,0 +1,196 @@
+use std::future::{ready, Ready};
+
+use actix_web::body::{BoxBody, EitherBody};
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::PayloadError;
+use actix_web::http::{header, Method};
+use actix_web::{Error, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use futures_util::StreamExt;
+
+use crate::settings::ServiceConfig;
+
+const BYTES_IN_MB: usize = 1024 * 1024;
+
+const UPDATE_PATTERNS: [(Method, &str); 10] = [
+    (Method::PUT, "/collections/{name}/points"),
+    (Method::POST, "/collections/{name}/points/delete"),
+    (Method::PUT, "/collections/{name}/points/vectors"),
+    (Method::POST, "/collections/{name}/points/vectors/delete"),
+    (Method::POST, "/collections/{name}/points/payload"),
+    (Method::PUT, "/collections/{name}/points/payload"),
+    (Method::POST, "/collections/{name}/points/payload/delete"),
+    (Method::POST, "/collections/{name}/points/payload/clear"),
+    (Method::POST, "/collections/{name}/points/batch"),
+    (Method::PUT, "/collections/{name}/index"),
+];
+
+const SEARCH_PATTERNS: [&str; 10] = [
+    "/collections/{name}/points/search",
+    "/collections/{name}/points/search/batch",
+    "/collections/{name}/points/search/groups",
+    "/collections/{name}/points/search/matrix/offsets",
+    "/collections/{name}/points/search/matrix/pairs",
+    "/collections/{name}/points/recommend",
+    "/collections/{name}/points/recommend/batch",
+    "/collections/{name}/points/recommend/groups",
+    "/collections/{name}/points/discover",
+    "/collections/{name}/points/discover/batch",
+];
+
+const SNAPSHOT_UPLOAD_PATTERNS: [&str; 2] = [
+    "/collections/{name}/snapshots/upload",
+    "/collections/{collection}/shards/{shard}/snapshots/upload",
+];
+
+/// Streamed point uploads, which are not buffered as a whole
+const POINTS_UPLOAD_PATTERNS: [(Method, &str); 2] = [
+    (Method::POST, "/collections/{name}/points/upload"),
+    (Method::PUT, "/collections/{name}/points/import"),
+];
+
+/// Maximum sizes of request bodies in bytes
+#[derive(Clone, Copy, Debug)]
+pub struct RequestSizeLimits {
+    default: usize,
+    update: usize,
+    search: usize,
+    /// Unlimited if not set
+    snapshot_upload: Option<usize>,
+    /// Unlimited if not set
+    points_upload: Option<usize>,
+}
+
+impl RequestSizeLimits {
+    pub fn from_config(config: &ServiceConfig) -> Self {
+        let limits = &config.request_size_limits;
+        let default = config.max_request_size_mb;
+        Self {
+            default: default * BYTES_IN_MB,
+            update: limits.update_mb.unwrap_or(default) * BYTES_IN_MB,
+            search: limits.search_mb.unwrap_or(default) * BYTES_IN_MB,
+            snapshot_upload: limits.snapshot_upload_mb.map(|limit| limit * BYTES_IN_MB),
+            points_upload: limits.points_upload_mb.map(|limit| limit * BYTES_IN_MB),
+        }
+    }
+
+    /// Largest limit of JSON requests
+    pub fn max_json(&self) -> usize {
+        self.default.max(self.update).max(self.search)
+    }
+
+    fn limit(&self, req: &ServiceRequest) -> Option<usize> {
+        let Some(pattern) = req.match_pattern() else {
+            return Some(self.default);
+        };
+        if SNAPSHOT_UPLOAD_PATTERNS.contains(&pattern.as_str()) {
+            return self.snapshot_upload;
+        }
+        if POINTS_UPLOAD_PATTERNS
+            .iter()
+            .any(|(method, pat)| req.method() == method && &pattern == pat)
+        {
+            return self.points_upload;
+        }
+        if *req.method() == Method::POST && SEARCH_PATTERNS.contains(&pattern.as_str()) {
+            return Some(self.search);
+        }
+        if UPDATE_PATTERNS
+            .iter()
+            .any(|(method, pat)| req.method() == method && &pattern == pat)
+        {
+            return Some(self.update);
+        }
+        Some(self.default)
+    }
+}
+
+pub struct RequestSizeLimit {
+    limits: RequestSizeLimits,
+}
+
+impl RequestSizeLimit {
+    pub fn new(limits: RequestSizeLimits) -> Self {
+        Self { limits }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestSizeLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestSizeLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestSizeLimitMiddleware {
+            limits: self.limits,
+            service,
+        }))
+    }
+}
+
+pub struct RequestSizeLimitMiddleware<S> {
+    limits: RequestSizeLimits,
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestSizeLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        if let Some(limit) = self.limits.limit(&req) {
+            let content_length = req
+                .headers()
+                .get(header::CONTENT_LENGTH)
+                .and_then(|length| length.to_str().ok())
+                .and_then(|length| length.parse::<usize>().ok());
+
+            if content_length.is_some_and(|length| length > limit) {
+                return Box::pin(async move {
+                    Ok(req
+                        .into_response(HttpResponse::PayloadTooLarge().body(format!(
+                            "Request body is larger than the limit of {limit} bytes"
+                        )))
+                        .map_into_right_body())
+                });
+            }
+
+            // Body without content length is checked while it is received
+            let payload = req.take_payload();
+            req.set_payload(limit_payload(payload, limit));
+        }
+
+        let future = self.service.call(req);
+        Box::pin(async move { Ok(future.await?.map_into_left_body()) })
+    }
+}
+
+fn limit_payload(payload: Payload, limit: usize) -> Payload {
+    let mut received = 0;
+    let stream = payload.map(move |chunk| {
+        let chunk = chunk?;
+        received += chunk.len();
+        if received > limit {
+            return Err(PayloadError::Overflow);
+        }
+        Ok(chunk)
+    });
+    Payload::Stream {
+        payload: Box::pin(stream),
+    }
+}
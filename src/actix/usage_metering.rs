@@ -0,0 +1,92 @@
+use std::future::{ready, Ready};
+use std::sync::Arc;
+use std::time::Instant;
+
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::CONTENT_LENGTH;
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+
+use crate::actix::api_key::{collection_name, request_api_key};
+use crate::common::usage_metering::UsageMeter;
+
+/// Accounts requests to the API key they were made with
+///
+/// Must run after the API key middleware, so only authorized requests are accounted.
+/// Sizes of streamed request and response bodies are unknown, they are not accounted.
+pub struct UsageMetering {
+    usage_meter: Option<Arc<UsageMeter>>,
+}
+
+impl UsageMetering {
+    pub fn new(usage_meter: Option<Arc<UsageMeter>>) -> Self {
+        Self { usage_meter }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for UsageMetering
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = UsageMeteringMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(UsageMeteringMiddleware {
+            usage_meter: self.usage_meter.clone(),
+            service,
+        }))
+    }
+}
+
+pub struct UsageMeteringMiddleware<S> {
+    usage_meter: Option<Arc<UsageMeter>>,
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for UsageMeteringMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let Some(usage_meter) = self.usage_meter.clone() else {
+            return Box::pin(self.service.call(req));
+        };
+
+        let key = usage_meter.usage_key(
+            request_api_key(&req).as_deref(),
+            collection_name(req.path()).map(ToString::to_string),
+        );
+        let bytes_read = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok()?.parse().ok())
+            .unwrap_or(0);
+
+        let instant = Instant::now();
+        let future = self.service.call(req);
+        Box::pin(async move {
+            let response = future.await?;
+            let bytes_written = match response.response().body().size() {
+                BodySize::Sized(size) => size,
+                BodySize::None | BodySize::Stream => 0,
+            };
+            usage_meter.add_request(key, bytes_read, bytes_written, instant.elapsed());
+            Ok(response)
+        })
+    }
+}
@@ -1,5 +1,19 @@
+use std::fmt;
+
+use api::grpc::qdrant as grpc;
+use collection::operations::point_ops::CopyPoints;
+use collection::operations::types::{
+    DiscoverRequestBatch, DiscoverRequestInternal, RecommendGroupsRequestInternal,
+    RecommendRequestBatch, RecommendRequestInternal, SearchGroupsRequestInternal,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use segment::types::{Condition, Filter};
+use serde::{Deserialize, Serialize};
+use storage::content_manager::collection_meta_ops::{CloneCollection, CreateCollection};
+use storage::content_manager::errors::StorageError;
+
 use super::strings::ct_eq;
-use crate::settings::ServiceConfig;
+use crate::settings::{ApiKeyConfig, JwtConfig, ServiceConfig};
 
 /// The API keys used for auth
 #[derive(Clone, Debug)]
@@ -9,6 +23,12 @@ pub struct AuthKeys {
 
     /// A key allowing Read operations
     read_only: Option<String>,
+
+    /// Keys with restricted access
+    scoped: Vec<ApiKeyConfig>,
+
+    /// Validation of JWT tokens, used as keys
+    jwt: Option<JwtParser>,
 }
 
 impl AuthKeys {
@@ -20,10 +40,14 @@ impl AuthKeys {
             service_config.api_key.clone(),
             service_config.read_only_api_key.clone(),
         ) {
-            (None, None) => None,
+            (None, None) if service_config.api_keys.is_empty() && service_config.jwt.is_none() => {
+                None
+            }
             (read_write, read_only) => Some(Self {
                 read_write,
                 read_only,
+                scoped: service_config.api_keys.clone(),
+                jwt: service_config.jwt.as_ref().map(JwtParser::new),
             }),
         }
     }
@@ -45,4 +69,632 @@ impl AuthKeys {
             .map(|rw_key| ct_eq(rw_key, key))
             .unwrap_or_default()
     }
+
+    /// Check if a key is allowed to perform the request
+    ///
+    /// `collection` is the name of the collection the request is addressed to, if known.
+    pub fn can_access(&self, key: &str, is_read_only: bool, collection: Option<&str>) -> bool {
+        if self.can_write(key) || (is_read_only && self.can_read(key)) {
+            return true;
+        }
+
+        self.scoped.iter().any(|scoped| {
+            ct_eq(&scoped.key, key)
+                && (is_read_only || !scoped.read_only)
+                && scoped.allows_collection(collection)
+        })
+    }
+
+    /// Check if a key is restricted to a set of collections,
+    /// so the collection of the request must be known to check the access
+    pub fn is_collection_scoped(&self, key: &str) -> bool {
+        self.scoped
+            .iter()
+            .any(|scoped| scoped.collections.is_some() && ct_eq(&scoped.key, key))
+    }
+
+    /// Access of a key, which is restricted to a set of collections, in the form of claims,
+    /// so handlers check it the same way as the access of JWT tokens
+    ///
+    /// Returns None if the key is not restricted to collections.
+    pub fn scoped_claims(&self, key: &str) -> Option<Claims> {
+        if self.can_write(key) || self.can_read(key) {
+            return None;
+        }
+        self.scoped
+            .iter()
+            .find(|scoped| scoped.collections.is_some() && ct_eq(&scoped.key, key))
+            .map(|scoped| Claims {
+                read_only: scoped.read_only,
+                collections: scoped.collections.clone(),
+                ..Default::default()
+            })
+    }
+
+    /// Name of the key to report its usage by, never the key itself
+    ///
+    /// Returns None if the key is not valid.
+    pub fn key_name(&self, key: &str) -> Option<String> {
+        if self.can_write(key) {
+            return Some("api_key".to_string());
+        }
+        if self.can_read(key) {
+            return Some("read_only_api_key".to_string());
+        }
+        if let Some((index, scoped)) = self
+            .scoped
+            .iter()
+            .enumerate()
+            .find(|(_, scoped)| ct_eq(&scoped.key, key))
+        {
+            return Some(
+                scoped
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("api_keys[{index}]")),
+            );
+        }
+        self.validate_jwt(key).map(|_| "jwt".to_string())
+    }
+
+    /// Validate a JWT token and return its claims
+    ///
+    /// Returns None if JWT is not configured, or if the token is invalid or expired.
+    pub fn validate_jwt(&self, token: &str) -> Option<Claims> {
+        self.jwt.as_ref()?.decode(token)
+    }
+}
+
+/// Claims of a JWT token, which define the access granted by the token
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Claims {
+    /// Expiration time in seconds since unix epoch. Tokens without expiration never expire
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<u64>,
+
+    /// Allow only read operations
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// Names of accessible collections. All collections are accessible if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collections: Option<Vec<String>>,
+
+    /// Filter, which is implicitly added to every search, scroll and count request.
+    /// Tokens with a filter can't be used for any other requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Filter>,
+}
+
+impl Claims {
+    /// Check if the token allows the request
+    ///
+    /// `collection` is the name of the collection the request is addressed to, if known.
+    pub fn allows(&self, is_read_only: bool, collection: Option<&str>) -> bool {
+        let allows_collection = match &self.collections {
+            None => true,
+            Some(collections) => {
+                collection.is_some_and(|collection| collections.iter().any(|c| c == collection))
+            }
+        };
+        (is_read_only || !self.read_only) && allows_collection
+    }
+}
+
+/// Combine the filter of a request with the filter of the token claims,
+/// so the request only matches points permitted by the token
+pub fn restrict_filter(claims: Option<&Claims>, filter: Option<Filter>) -> Option<Filter> {
+    let Some(restriction) = claims.and_then(|claims| claims.filter.as_ref()) else {
+        return filter;
+    };
+    let restriction = Filter::new_must(Condition::Filter(restriction.clone()));
+    match filter {
+        None => Some(restriction),
+        Some(filter) => Some(filter.merge(&restriction)),
+    }
+}
+
+/// Request, which names collections other than the one it is addressed to
+///
+/// The auth middleware only knows the collection in the path of a REST request, or in the first
+/// field of a gRPC message. Access to the other collections is checked by the handlers.
+pub trait NamedCollections {
+    /// Collections, which are read by the request, e.g. to look up points
+    fn read_collections(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    /// Collections, which are created or written by the request
+    fn written_collections(&self) -> Vec<&str> {
+        Vec::new()
+    }
+}
+
+/// Check that the claims of a request allow access to all collections, named in its body
+pub fn check_named_collections(
+    claims: Option<&Claims>,
+    request: &impl NamedCollections,
+) -> Result<(), StorageError> {
+    let Some(claims) = claims else {
+        return Ok(());
+    };
+
+    // The filter of the token can't be applied to points, looked up by their ids
+    if claims.filter.is_some() && !request.read_collections().is_empty() {
+        return Err(StorageError::forbidden(
+            "Lookups in collections are not allowed with a filter in the token",
+        ));
+    }
+
+    let read = request
+        .read_collections()
+        .into_iter()
+        .map(|name| (name, true));
+    let written = request
+        .written_collections()
+        .into_iter()
+        .map(|name| (name, false));
+    for (collection, is_read_only) in read.chain(written) {
+        if !claims.allows(is_read_only, Some(collection)) {
+            return Err(StorageError::forbidden(format!(
+                "Access to collection `{collection}` is not allowed"
+            )));
+        }
+    }
+    Ok(())
+}
+
+impl NamedCollections for SearchGroupsRequestInternal {
+    fn read_collections(&self) -> Vec<&str> {
+        self.group_request
+            .with_lookup
+            .iter()
+            .map(|lookup| lookup.collection_name())
+            .collect()
+    }
+}
+
+impl NamedCollections for RecommendRequestInternal {
+    fn read_collections(&self) -> Vec<&str> {
+        self.lookup_from
+            .iter()
+            .map(|lookup| lookup.collection.as_str())
+            .collect()
+    }
+}
+
+impl NamedCollections for RecommendRequestBatch {
+    fn read_collections(&self) -> Vec<&str> {
+        self.searches
+            .iter()
+            .flat_map(|search| search.recommend_request.read_collections())
+            .collect()
+    }
+}
+
+impl NamedCollections for RecommendGroupsRequestInternal {
+    fn read_collections(&self) -> Vec<&str> {
+        let lookup_from = self
+            .lookup_from
+            .iter()
+            .map(|lookup| lookup.collection.as_str());
+        let with_lookup = self.group_request.with_lookup.iter();
+        lookup_from
+            .chain(with_lookup.map(|lookup| lookup.collection_name()))
+            .collect()
+    }
+}
+
+impl NamedCollections for DiscoverRequestInternal {
+    fn read_collections(&self) -> Vec<&str> {
+        self.lookup_from
+            .iter()
+            .map(|lookup| lookup.collection.as_str())
+            .collect()
+    }
+}
+
+impl NamedCollections for DiscoverRequestBatch {
+    fn read_collections(&self) -> Vec<&str> {
+        self.searches
+            .iter()
+            .flat_map(|search| search.discover_request.read_collections())
+            .collect()
+    }
+}
+
+impl NamedCollections for CopyPoints {
+    fn written_collections(&self) -> Vec<&str> {
+        vec![&self.target_collection]
+    }
+}
+
+impl NamedCollections for CloneCollection {
+    fn written_collections(&self) -> Vec<&str> {
+        vec![&self.to]
+    }
+}
+
+impl NamedCollections for CreateCollection {
+    fn read_collections(&self) -> Vec<&str> {
+        self.init_from
+            .iter()
+            .map(|init_from| init_from.collection.as_str())
+            .collect()
+    }
+}
+
+impl NamedCollections for grpc::SearchPointGroups {
+    fn read_collections(&self) -> Vec<&str> {
+        self.with_lookup
+            .iter()
+            .map(|lookup| lookup.collection.as_str())
+            .collect()
+    }
+}
+
+impl NamedCollections for grpc::RecommendPoints {
+    fn read_collections(&self) -> Vec<&str> {
+        self.lookup_from
+            .iter()
+            .map(|lookup| lookup.collection_name.as_str())
+            .collect()
+    }
+}
+
+impl NamedCollections for grpc::RecommendBatchPoints {
+    fn read_collections(&self) -> Vec<&str> {
+        self.recommend_points
+            .iter()
+            .flat_map(|recommend| recommend.read_collections())
+            .collect()
+    }
+}
+
+impl NamedCollections for grpc::RecommendPointGroups {
+    fn read_collections(&self) -> Vec<&str> {
+        let lookup_from = self
+            .lookup_from
+            .iter()
+            .map(|lookup| lookup.collection_name.as_str());
+        let with_lookup = self.with_lookup.iter();
+        lookup_from
+            .chain(with_lookup.map(|lookup| lookup.collection.as_str()))
+            .collect()
+    }
+}
+
+impl NamedCollections for grpc::DiscoverPoints {
+    fn read_collections(&self) -> Vec<&str> {
+        self.lookup_from
+            .iter()
+            .map(|lookup| lookup.collection_name.as_str())
+            .collect()
+    }
+}
+
+impl NamedCollections for grpc::DiscoverBatchPoints {
+    fn read_collections(&self) -> Vec<&str> {
+        self.discover_points
+            .iter()
+            .flat_map(|discover| discover.read_collections())
+            .collect()
+    }
+}
+
+impl NamedCollections for grpc::CreateCollection {
+    fn read_collections(&self) -> Vec<&str> {
+        self.init_from_collection.as_deref().into_iter().collect()
+    }
+}
+
+#[derive(Clone)]
+struct JwtParser {
+    keys: Vec<(DecodingKey, Validation)>,
+}
+
+impl JwtParser {
+    fn new(config: &JwtConfig) -> Self {
+        let mut keys = Vec::new();
+
+        if let Some(secret) = &config.hs256_secret {
+            keys.push((
+                DecodingKey::from_secret(secret.as_bytes()),
+                Self::validation(Algorithm::HS256),
+            ));
+        }
+
+        if let Some(public_key) = &config.rs256_public_key {
+            match DecodingKey::from_rsa_pem(public_key.as_bytes()) {
+                Ok(key) => keys.push((key, Self::validation(Algorithm::RS256))),
+                // Tokens signed with RS256 are rejected
+                Err(err) => log::error!("Invalid RS256 public key for JWT validation: {err}"),
+            }
+        }
+
+        Self { keys }
+    }
+
+    fn validation(algorithm: Algorithm) -> Validation {
+        let mut validation = Validation::new(algorithm);
+        // Expiration is optional, but is validated if present
+        validation.required_spec_claims.clear();
+        validation
+    }
+
+    fn decode(&self, token: &str) -> Option<Claims> {
+        self.keys.iter().find_map(|(key, validation)| {
+            jsonwebtoken::decode::<Claims>(token, key, validation)
+                .ok()
+                .map(|data| data.claims)
+        })
+    }
+}
+
+impl fmt::Debug for JwtParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtParser")
+            .field("keys", &self.keys.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::{FieldCondition, IsEmptyCondition, Match, PayloadField, ValueVariants};
+
+    use super::*;
+
+    const JWT_SECRET: &str = "jwt-secret";
+
+    fn auth_keys() -> AuthKeys {
+        AuthKeys {
+            read_write: Some("admin".to_string()),
+            read_only: None,
+            scoped: vec![
+                ApiKeyConfig {
+                    key: "reader".to_string(),
+                    name: None,
+                    read_only: true,
+                    collections: None,
+                },
+                ApiKeyConfig {
+                    key: "tenant".to_string(),
+                    name: Some("tenant_a".to_string()),
+                    read_only: false,
+                    collections: Some(vec!["tenant_a".to_string()]),
+                },
+            ],
+            jwt: Some(JwtParser::new(&JwtConfig {
+                hs256_secret: Some(JWT_SECRET.to_string()),
+                rs256_public_key: None,
+            })),
+        }
+    }
+
+    #[test]
+    fn test_scoped_keys() {
+        let auth_keys = auth_keys();
+
+        assert!(auth_keys.can_access("admin", false, None));
+        assert!(auth_keys.can_access("admin", true, Some("tenant_b")));
+
+        assert!(auth_keys.can_access("reader", true, Some("tenant_a")));
+        assert!(auth_keys.can_access("reader", true, None));
+        assert!(!auth_keys.can_access("reader", false, Some("tenant_a")));
+
+        assert!(auth_keys.can_access("tenant", false, Some("tenant_a")));
+        assert!(!auth_keys.can_access("tenant", true, Some("tenant_b")));
+        assert!(!auth_keys.can_access("tenant", true, None));
+        assert!(auth_keys.is_collection_scoped("tenant"));
+        assert!(!auth_keys.is_collection_scoped("reader"));
+
+        assert!(!auth_keys.can_access("unknown", true, None));
+    }
+
+    fn encode(claims: &Claims, secret: &str) -> String {
+        jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(Algorithm::HS256),
+            claims,
+            &jsonwebtoken::EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_jwt_claims() {
+        let auth_keys = auth_keys();
+        let now = chrono::Utc::now().timestamp() as u64;
+
+        let claims = Claims {
+            exp: Some(now + 3600),
+            read_only: true,
+            collections: Some(vec!["tenant_a".to_string()]),
+            filter: None,
+        };
+        let validated = auth_keys
+            .validate_jwt(&encode(&claims, JWT_SECRET))
+            .unwrap();
+        assert!(validated.allows(true, Some("tenant_a")));
+        assert!(!validated.allows(false, Some("tenant_a")));
+        assert!(!validated.allows(true, Some("tenant_b")));
+        assert!(!validated.allows(true, None));
+
+        // Token without expiration
+        let claims = Claims::default();
+        assert!(auth_keys
+            .validate_jwt(&encode(&claims, JWT_SECRET))
+            .is_some());
+
+        // Expired token
+        let claims = Claims {
+            exp: Some(now - 3600),
+            ..Default::default()
+        };
+        assert!(auth_keys
+            .validate_jwt(&encode(&claims, JWT_SECRET))
+            .is_none());
+
+        // Token signed with another secret
+        let claims = Claims::default();
+        assert!(auth_keys.validate_jwt(&encode(&claims, "other")).is_none());
+        assert!(auth_keys.validate_jwt("admin").is_none());
+    }
+
+    #[test]
+    fn test_key_name() {
+        let auth_keys = auth_keys();
+
+        assert_eq!(auth_keys.key_name("admin").as_deref(), Some("api_key"));
+        assert_eq!(auth_keys.key_name("reader").as_deref(), Some("api_keys[0]"));
+        assert_eq!(auth_keys.key_name("tenant").as_deref(), Some("tenant_a"));
+        let token = encode(&Claims::default(), JWT_SECRET);
+        assert_eq!(auth_keys.key_name(&token).as_deref(), Some("jwt"));
+        assert_eq!(auth_keys.key_name("unknown"), None);
+    }
+
+    #[test]
+    fn test_named_collections() {
+        let auth_keys = auth_keys();
+        assert!(auth_keys.scoped_claims("admin").is_none());
+        assert!(auth_keys.scoped_claims("reader").is_none());
+        let claims = auth_keys.scoped_claims("tenant").unwrap();
+        let read_only = Claims {
+            read_only: true,
+            collections: Some(vec!["tenant_a".to_string(), "tenant_b".to_string()]),
+            ..Default::default()
+        };
+
+        let copy: CopyPoints =
+            serde_json::from_value(serde_json::json!({ "target_collection": "tenant_b" })).unwrap();
+        assert!(check_named_collections(None, &copy).is_ok());
+        assert!(check_named_collections(Some(&Claims::default()), &copy).is_ok());
+        assert!(check_named_collections(Some(&claims), &copy).is_err());
+        // Copying writes into the target collection
+        assert!(check_named_collections(Some(&read_only), &copy).is_err());
+
+        let recommend: RecommendRequestInternal = serde_json::from_value(serde_json::json!({
+            "positive": [1],
+            "limit": 3,
+            "lookup_from": { "collection": "tenant_b" },
+        }))
+        .unwrap();
+        assert!(check_named_collections(Some(&claims), &recommend).is_err());
+        assert!(check_named_collections(Some(&read_only), &recommend).is_ok());
+
+        let search_groups: SearchGroupsRequestInternal =
+            serde_json::from_value(serde_json::json!({
+                "vector": [0.1, 0.2],
+                "group_by": "group",
+                "group_size": 1,
+                "limit": 2,
+                "with_lookup": "tenant_a",
+            }))
+            .unwrap();
+        assert!(check_named_collections(Some(&claims), &search_groups).is_ok());
+
+        let recommend_groups = grpc::RecommendPointGroups {
+            collection_name: "tenant_a".to_string(),
+            with_lookup: Some(grpc::WithLookup {
+                collection: "tenant_b".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(recommend_groups.read_collections(), vec!["tenant_b"]);
+        assert!(check_named_collections(Some(&claims), &recommend_groups).is_err());
+
+        let create = grpc::CreateCollection {
+            collection_name: "tenant_a".to_string(),
+            init_from_collection: Some("tenant_b".to_string()),
+            ..Default::default()
+        };
+        assert!(check_named_collections(Some(&claims), &create).is_err());
+    }
+
+    #[test]
+    fn test_jwt_named_collections() {
+        let auth_keys = auth_keys();
+        let claims = Claims {
+            collections: Some(vec!["tenant_a".to_string()]),
+            ..Default::default()
+        };
+        let claims = auth_keys
+            .validate_jwt(&encode(&claims, JWT_SECRET))
+            .unwrap();
+
+        let search_groups = |with_lookup: &str| -> SearchGroupsRequestInternal {
+            serde_json::from_value(serde_json::json!({
+                "vector": [0.1, 0.2],
+                "group_by": "group",
+                "group_size": 1,
+                "limit": 2,
+                "with_lookup": with_lookup,
+            }))
+            .unwrap()
+        };
+        assert!(check_named_collections(Some(&claims), &search_groups("tenant_a")).is_ok());
+        assert!(check_named_collections(Some(&claims), &search_groups("tenant_b")).is_err());
+
+        let recommend = grpc::RecommendPoints {
+            collection_name: "tenant_a".to_string(),
+            lookup_from: Some(grpc::LookupLocation {
+                collection_name: "tenant_b".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(check_named_collections(Some(&claims), &recommend).is_err());
+
+        // Points of lookups can't be restricted by the filter of the token
+        let filtered = Claims {
+            filter: Some(Filter::new_must(Condition::Field(
+                FieldCondition::new_match(
+                    "tenant",
+                    Match::new_value(ValueVariants::Keyword("a".to_string())),
+                ),
+            ))),
+            ..claims
+        };
+        assert!(check_named_collections(Some(&filtered), &search_groups("tenant_a")).is_err());
+        let search_groups = grpc::SearchPointGroups {
+            collection_name: "tenant_a".to_string(),
+            ..Default::default()
+        };
+        assert!(check_named_collections(Some(&filtered), &search_groups).is_ok());
+    }
+
+    #[test]
+    fn test_restrict_filter() {
+        let tenant = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "tenant",
+            Match::new_value(ValueVariants::Keyword("a".to_string())),
+        )));
+        let claims = Claims {
+            filter: Some(tenant.clone()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            restrict_filter(None, Some(tenant.clone())),
+            Some(tenant.clone())
+        );
+        assert_eq!(restrict_filter(Some(&Claims::default()), None), None);
+
+        let restricted = restrict_filter(Some(&claims), None).unwrap();
+        assert_eq!(
+            restricted.must,
+            Some(vec![Condition::Filter(tenant.clone())])
+        );
+
+        // Conditions of the request can't widen the filter of the token
+        let request = Filter::new_should(Condition::IsEmpty(IsEmptyCondition {
+            is_empty: PayloadField {
+                key: "tenant".to_string(),
+            },
+        }));
+        let restricted = restrict_filter(Some(&claims), Some(request.clone())).unwrap();
+        assert_eq!(restricted.should, request.should);
+        assert_eq!(restricted.must, Some(vec![Condition::Filter(tenant)]));
+    }
 }
@@ -132,6 +132,25 @@ fn load_certified_key(tls_config: &TlsConfig) -> Result<Arc<CertifiedKey>> {
 ///
 /// Uses TLS settings as configured in configuration by user.
 pub fn actix_tls_server_config(settings: &Settings) -> Result<ServerConfig> {
+    tls_server_config(settings, settings.service.verify_https_client_certificate)
+}
+
+/// Generate a tonic server configuration with TLS
+///
+/// Uses TLS settings as configured in configuration by user. Internal gRPC must verify
+/// client certificates against the CA, as peers authenticate each other with them.
+pub fn tonic_tls_server_config(
+    settings: &Settings,
+    verify_client_certificate: bool,
+) -> Result<ServerConfig> {
+    let mut config = tls_server_config(settings, verify_client_certificate)?;
+    // gRPC requires HTTP/2
+    config.alpn_protocols = vec![b"h2".to_vec()];
+    Ok(config)
+}
+
+/// Server configuration with a certificate, which is reloaded from disk once the TTL is reached
+fn tls_server_config(settings: &Settings, verify_client_certificate: bool) -> Result<ServerConfig> {
     let config = ServerConfig::builder().with_safe_defaults();
     let tls_config = settings
         .tls
@@ -140,7 +159,7 @@ pub fn actix_tls_server_config(settings: &Settings) -> Result<ServerConfig> {
         .map_err(Error::Io)?;
 
     // Verify client CA or not
-    let config = if settings.service.verify_https_client_certificate {
+    let config = if verify_client_certificate {
         let mut root_cert_store = RootCertStore::empty();
         let ca_certs: Vec<Vec<u8>> = with_buf_read(&tls_config.ca_cert, rustls_pemfile::certs)?;
         root_cert_store.add_parsable_certificates(&ca_certs[..]);
@@ -167,7 +186,7 @@ fn with_buf_read<T>(path: &str, f: impl FnOnce(&mut dyn BufRead) -> io::Result<T
     f(dyn_reader).map_err(|err| Error::ReadFile(err, path.into()))
 }
 
-/// Actix TLS errors.
+/// Server TLS errors.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("TLS file could not be opened: {1}")]
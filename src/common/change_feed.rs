@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use actix_web::web::Bytes;
+use collection::operations::CollectionUpdateOperations;
+use collection::shards::shard::ShardId;
+use futures::{Stream, StreamExt};
+use segment::types::SeqNumberType;
+use serde::Serialize;
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::toc::TableOfContent;
+
+/// Single line of the change stream
+#[derive(Serialize)]
+struct ChangeLine<'a> {
+    shard_id: ShardId,
+    /// Number of the operation in the WAL of the shard, increasing within each shard
+    operation_id: SeqNumberType,
+    operation: &'a CollectionUpdateOperations,
+}
+
+/// Stream operations, applied to the shards of the collection on this peer, in JSON Lines format
+///
+/// Each line is a single operation with its shard and operation number. The stream never ends by
+/// itself, it fails if the consumer can't keep up or if a shard is moved away from this peer.
+pub async fn do_subscribe_changes(
+    toc: Arc<TableOfContent>,
+    collection_name: String,
+) -> Result<impl Stream<Item = Result<Bytes, StorageError>>, StorageError> {
+    let collection = toc.get_collection(&collection_name).await?;
+    let changes = collection.subscribe_changes().await?;
+
+    Ok(changes.map(|change| {
+        let change = change?;
+        let line = ChangeLine {
+            shard_id: change.shard_id,
+            operation_id: change.change.operation_id,
+            operation: &change.change.operation,
+        };
+        let mut line = serde_json::to_vec(&line)
+            .map_err(|err| StorageError::service_error(format!("Can't serialize change: {err}")))?;
+        line.push(b'\n');
+        Ok(Bytes::from(line))
+    }))
+}
@@ -4,12 +4,14 @@ use api::grpc::models::{CollectionDescription, CollectionsResponse};
 use collection::config::ShardingMethod;
 use collection::operations::cluster_ops::{
     AbortTransferOperation, ClusterOperations, DropReplicaOperation, MoveShardOperation,
-    ReplicateShardOperation,
+    RebuildReplicaOperation, ReplicateShardOperation,
 };
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::snapshot_ops::SnapshotDescription;
 use collection::operations::types::{
-    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
+    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionShardsDistribution,
+    CollectionStatistics, CollectionVectorsInfo, CollectionsAliasesResponse, QuantizationEstimate,
+    QuantizationEstimateRequest,
 };
 use collection::shards::replica_set;
 use collection::shards::shard::{PeerId, ShardId, ShardsPlacement};
@@ -18,7 +20,8 @@ use itertools::Itertools;
 use rand::prelude::SliceRandom;
 use storage::content_manager::collection_meta_ops::ShardTransferOperations::{Abort, Start};
 use storage::content_manager::collection_meta_ops::{
-    CollectionMetaOperations, CreateShardKey, DropShardKey, UpdateCollectionOperation,
+    CloneCollection, CollectionMetaOperations, CreateShardKey, DropShardKey, RebuildShardReplica,
+    SetShardReplicationFactor, SplitShardKey, UpdateCollectionOperation,
 };
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
@@ -136,6 +139,7 @@ pub async fn do_create_snapshot(
             name: "".to_string(),
             creation_time: None,
             size: 0,
+            checksum: None,
         })
     }
 }
@@ -143,9 +147,67 @@ pub async fn do_create_snapshot(
 pub async fn do_get_collection_cluster(
     toc: &TableOfContent,
     name: &str,
+    with_checksums: bool,
 ) -> Result<CollectionClusterInfo, StorageError> {
     let collection = toc.get_collection(name).await?;
-    Ok(collection.cluster_info(toc.this_peer_id).await?)
+    Ok(collection
+        .cluster_info(toc.this_peer_id, with_checksums)
+        .await?)
+}
+
+pub async fn do_get_collection_shards_distribution(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<CollectionShardsDistribution, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.shards_distribution().await?)
+}
+
+pub async fn do_get_collection_statistics(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<CollectionStatistics, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.statistics().await?)
+}
+
+pub async fn do_get_collection_vectors(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<CollectionVectorsInfo, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.vectors_info().await?)
+}
+
+/// Create a copy of the collection, its points are transferred in the background
+pub async fn do_clone_collection(
+    dispatcher: &Dispatcher,
+    name: &str,
+    clone_collection: CloneCollection,
+    wait_timeout: Option<Duration>,
+) -> Result<bool, StorageError> {
+    // Don't hold the collection across the consensus operation, applying it needs the lock
+    let (from, config) = {
+        let collection = dispatcher.toc().get_collection(name).await?;
+        (collection.name(), collection.state().await.config)
+    };
+    let operation = clone_collection.into_operation(from, config)?;
+    dispatcher
+        .submit_collection_meta_op(
+            CollectionMetaOperations::CreateCollection(operation),
+            wait_timeout,
+        )
+        .await
+}
+
+pub async fn do_estimate_quantization(
+    toc: &TableOfContent,
+    name: &str,
+    request: QuantizationEstimateRequest,
+) -> Result<QuantizationEstimate, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    let temp_dir = toc.optional_temp_or_storage_temp_path()?;
+    Ok(collection.estimate_quantization(request, &temp_dir).await?)
 }
 
 pub async fn do_update_collection_cluster(
@@ -311,6 +373,29 @@ pub async fn do_update_collection_cluster(
                 )
                 .await
         }
+        ClusterOperations::RebuildReplica(RebuildReplicaOperation { rebuild_replica }) => {
+            if !collection.contains_shard(rebuild_replica.shard_id).await {
+                return Err(StorageError::BadRequest {
+                    description: format!(
+                        "Shard {} of {} does not exist",
+                        rebuild_replica.shard_id, collection_name
+                    ),
+                });
+            };
+
+            validate_peer_exists(rebuild_replica.peer_id)?;
+
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::RebuildShardReplica(RebuildShardReplica {
+                        collection_name,
+                        shard_id: rebuild_replica.shard_id,
+                        peer_id: rebuild_replica.peer_id,
+                    }),
+                    wait_timeout,
+                )
+                .await
+        }
         ClusterOperations::CreateShardingKey(create_sharding_key_op) => {
             let create_sharding_key = create_sharding_key_op.create_sharding_key;
 
@@ -420,6 +505,141 @@ pub async fn do_update_collection_cluster(
                 )
                 .await
         }
+        ClusterOperations::SplitShardingKey(split_sharding_key_op) => {
+            let split_sharding_key = split_sharding_key_op.split_sharding_key;
+            // Validate that:
+            // - proper sharding method is used
+            // - key does exist
+            //
+            // If placement suggested:
+            // - Peers exist
+
+            let state = collection.state().await;
+
+            match state.config.params.sharding_method.unwrap_or_default() {
+                ShardingMethod::Auto => {
+                    return Err(StorageError::bad_request(
+                        "Shard Key cannot be split with Auto sharding method",
+                    ));
+                }
+                ShardingMethod::Custom => {}
+            }
+
+            let shard_keys_mapping = state.shards_key_mapping;
+            let Some(key_shard_ids) = shard_keys_mapping.get(&split_sharding_key.shard_key) else {
+                return Err(StorageError::BadRequest {
+                    description: format!(
+                        "Sharding key {} does not exists for collection {}",
+                        split_sharding_key.shard_key, collection_name
+                    ),
+                });
+            };
+
+            // Double the number of shards of the key by default
+            let shard_number = split_sharding_key
+                .shards_number
+                .map(|shards_number| shards_number.get() as usize)
+                .unwrap_or(key_shard_ids.len());
+            let replication_factor = split_sharding_key
+                .replication_factor
+                .unwrap_or(state.config.params.replication_factor)
+                .get() as usize;
+
+            let peers_pool: Vec<_> = if let Some(placement) = split_sharding_key.placement {
+                if placement.is_empty() {
+                    return Err(StorageError::BadRequest {
+                        description: format!(
+                            "Sharding key {} placement cannot be empty. If you want to use random placement, do not specify placement",
+                            split_sharding_key.shard_key
+                        ),
+                    });
+                }
+
+                for peer_id in placement.iter().copied() {
+                    validate_peer_exists(peer_id)?;
+                }
+                placement
+            } else {
+                get_all_peer_ids()
+            };
+
+            let exact_placement =
+                generate_even_placement(peers_pool, shard_number, replication_factor);
+
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::SplitShardKey(SplitShardKey {
+                        collection_name,
+                        shard_key: split_sharding_key.shard_key,
+                        placement: exact_placement,
+                    }),
+                    wait_timeout,
+                )
+                .await
+        }
+        ClusterOperations::ScaleShardReplicas(scale_shard_replicas_op) => {
+            let scale_shard_replicas = scale_shard_replicas_op.scale_shard_replicas;
+            // Validate that:
+            // - shard or key does exist
+            // - there are enough peers to host the requested number of replicas
+
+            let state = collection.state().await;
+
+            let shard_ids: Vec<ShardId> = match (
+                scale_shard_replicas.shard_id,
+                scale_shard_replicas.shard_key,
+            ) {
+                (Some(shard_id), None) => {
+                    if !state.shards.contains_key(&shard_id) {
+                        return Err(StorageError::BadRequest {
+                            description: format!(
+                                "Shard {shard_id} does not exists for collection {collection_name}"
+                            ),
+                        });
+                    }
+                    vec![shard_id]
+                }
+                (None, Some(shard_key)) => {
+                    let Some(key_shard_ids) = state.shards_key_mapping.get(&shard_key) else {
+                        return Err(StorageError::BadRequest {
+                            description: format!(
+                                "Sharding key {shard_key} does not exists for collection {collection_name}"
+                            ),
+                        });
+                    };
+                    key_shard_ids.iter().copied().sorted().collect()
+                }
+                _ => {
+                    return Err(StorageError::bad_request(
+                        "Exactly one of shard_id or shard_key must be specified",
+                    ));
+                }
+            };
+
+            if let Some(replication_factor) = scale_shard_replicas.replication_factor {
+                let peers_count = get_all_peer_ids().len();
+                if replication_factor.get() as usize > peers_count {
+                    return Err(StorageError::BadRequest {
+                        description: format!(
+                            "Replication factor {replication_factor} is greater than the number of peers {peers_count}"
+                        ),
+                    });
+                }
+            }
+
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::SetShardReplicationFactor(
+                        SetShardReplicationFactor {
+                            collection_name,
+                            shard_ids,
+                            replication_factor: scale_shard_replicas.replication_factor,
+                        },
+                    ),
+                    wait_timeout,
+                )
+                .await
+        }
     }
 }
 
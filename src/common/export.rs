@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use actix_web::web::Bytes;
+use collection::operations::shard_selector_internal::ShardSelectorInternal;
+use collection::operations::types::{
+    CollectionInfo, ExportPointsRequest, ExportPointsResult, ScrollRequestInternal,
+};
+use futures::{Stream, StreamExt};
+use segment::data_types::vectors::VectorStruct;
+use segment::types::{
+    Payload, PayloadFieldSchema, PayloadKeyType, PointIdType, ShardKey, WithPayloadInterface,
+    WithVector,
+};
+use serde::Serialize;
+use storage::content_manager::collection_meta_ops::CreateCollection;
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::toc::TableOfContent;
+
+use crate::common::collections::do_get_collection;
+
+/// Version of the export format, increased only on incompatible changes
+const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Number of points, read from the collection at once
+const EXPORT_BATCH_SIZE: usize = 256;
+
+/// First line of the export, describes how to re-create the collection
+///
+/// Only contains data of the public API, so it can be applied to any version of the service.
+#[derive(Serialize)]
+struct ExportHeader {
+    format_version: u32,
+    collection_name: String,
+    /// Request to create a collection with the same configuration
+    create_collection: CreateCollection,
+    /// Payload indexes to create, field name to field schema
+    payload_schema: HashMap<PayloadKeyType, PayloadFieldSchema>,
+}
+
+impl ExportHeader {
+    fn new(collection_name: String, info: CollectionInfo) -> Self {
+        let payload_schema: HashMap<_, PayloadFieldSchema> = info
+            .payload_schema
+            .into_iter()
+            .filter_map(|(field, index_info)| Some((field, index_info.try_into().ok()?)))
+            .collect();
+        Self {
+            format_version: EXPORT_FORMAT_VERSION,
+            collection_name,
+            create_collection: info.config.into(),
+            payload_schema,
+        }
+    }
+}
+
+/// Single point of the export, in the format of the upsert points API
+#[derive(Serialize)]
+struct ExportedPoint {
+    id: PointIdType,
+    vector: VectorStruct,
+    payload: Payload,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shard_key: Option<ShardKey>,
+}
+
+fn json_line(value: &impl Serialize) -> Result<Vec<u8>, StorageError> {
+    let mut line = serde_json::to_vec(value)
+        .map_err(|err| StorageError::service_error(format!("Can't serialize export: {err}")))?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Export the collection as a logical dump in JSON Lines format
+///
+/// The first line contains the collection configuration and payload schema, each following line
+/// is a single point. Unlike snapshots, the dump does not depend on the storage format, so it
+/// can be loaded into an older version with the collection and points APIs.
+///
+/// Collection is checked before the stream is returned, errors while reading points are
+/// reported by the stream.
+pub async fn do_export_collection(
+    toc: Arc<TableOfContent>,
+    collection_name: String,
+) -> Result<impl Stream<Item = Result<Bytes, StorageError>>, StorageError> {
+    let info = do_get_collection(&toc, &collection_name, None).await?;
+    let header = json_line(&ExportHeader::new(collection_name.clone(), info))?;
+
+    // `None` state is the end of the stream, first page starts without an offset
+    let points = futures::stream::try_unfold(Some(None), move |offset| {
+        let toc = toc.clone();
+        let collection_name = collection_name.clone();
+        async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+            let request = ScrollRequestInternal {
+                offset,
+                limit: Some(EXPORT_BATCH_SIZE),
+                filter: None,
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: WithVector::Bool(true),
+                order_by: None,
+                query: None,
+            };
+            let result = toc
+                .scroll(&collection_name, request, None, ShardSelectorInternal::All)
+                .await?;
+
+            let mut lines = vec![];
+            for record in result.points {
+                lines.extend(json_line(&ExportedPoint {
+                    id: record.id,
+                    vector: record
+                        .vector
+                        .unwrap_or_else(|| VectorStruct::Multi(HashMap::new())),
+                    payload: record.payload.unwrap_or_default(),
+                    shard_key: record.shard_key,
+                })?);
+            }
+
+            let next_offset = result.next_page_offset.map(Some);
+            Ok(Some((Bytes::from(lines), next_offset)))
+        }
+    });
+
+    Ok(futures::stream::once(async { Ok(Bytes::from(header)) }).chain(points))
+}
+
+/// Export points of the collection into Parquet or JSONL files on local disk or in object storage
+///
+/// Unlike the streamed export, files are written on the server, one per shard, in parallel.
+pub async fn do_export_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: ExportPointsRequest,
+) -> Result<ExportPointsResult, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    let temp_dir = toc.optional_temp_or_storage_temp_path()?;
+    Ok(collection.export_points(request, &temp_dir).await?)
+}
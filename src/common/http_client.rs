@@ -34,19 +34,31 @@ impl HttpClient {
     }
 
     pub fn client(&self) -> Result<reqwest::Client> {
+        self.build(reqwest::Client::builder())
+    }
+
+    /// Client, which doesn't follow redirects, so it only reaches URLs which were checked
+    pub fn client_without_redirects(&self) -> Result<reqwest::Client> {
+        self.build(reqwest::Client::builder().redirect(reqwest::redirect::Policy::none()))
+    }
+
+    fn build(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::Client> {
         match &self.tls_config {
-            Some(tls_config) => https_client(tls_config, self.verify_https_client_certificate),
-            None => Ok(reqwest::Client::new()),
+            Some(tls_config) => {
+                https_client(builder, tls_config, self.verify_https_client_certificate)
+            }
+            None => Ok(builder.build()?),
         }
     }
 }
 
 fn https_client(
+    builder: reqwest::ClientBuilder,
     tls_config: &TlsConfig,
     verify_https_client_certificate: bool,
 ) -> Result<reqwest::Client> {
-    let mut builder = reqwest::Client::builder()
-        .add_root_certificate(https_client_ca_cert(tls_config.ca_cert.as_ref())?);
+    let mut builder =
+        builder.add_root_certificate(https_client_ca_cert(tls_config.ca_cert.as_ref())?);
 
     if verify_https_client_certificate {
         builder = builder.identity(https_client_identity(
@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use arrow_array::RecordBatch;
+use collection::operations::point_ops::{
+    PointInsertOperations, PointStruct, PointsList, WriteOrdering,
+};
+use futures::{Stream, StreamExt};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use schemars::JsonSchema;
+use segment::data_types::vectors::{Vector, VectorStruct, DEFAULT_VECTOR_NAME};
+use segment::types::{Payload, PointIdType};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::toc::TableOfContent;
+use tempfile::TempPath;
+use tokio::io::AsyncWriteExt;
+use url::Url;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::common::points::do_upsert_points;
+use crate::settings::ImportConfig;
+
+/// Number of points, upserted at once, if not specified in the request
+const DEFAULT_IMPORT_BATCH_SIZE: usize = 1000;
+
+/// Longest line of an NDJSON upload in bytes, a longer one is rejected instead of being buffered
+const MAX_UPLOAD_LINE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Import points from a Parquet file
+///
+/// Columns are mapped the same way, as points are exported over Arrow Flight:
+/// - `vector` and `vector.<name>` columns contain dense vectors as lists of numbers
+/// - `payload` column contains payloads as JSON objects
+/// - any other column is stored as a payload field with the name of the column
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct ImportPoints {
+    /// Location of the file, which must be allowed by the `service.import` config of the server
+    ///
+    /// Examples:
+    /// - URL `https://my-bucket.s3.amazonaws.com/points.parquet`
+    /// - Local path `file:///qdrant/import/points.parquet`
+    pub location: Url,
+    /// Column with ids of points, as unsigned integers or UUIDs. Default: `id`
+    #[serde(default)]
+    pub id_column: Option<String>,
+    /// Number of points to upsert at once. Default: 1000
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct ImportResult {
+    /// Number of imported points
+    pub points_count: usize,
+}
+
+/// Local path of the file at a `file://` location, which must be inside of the import directory
+fn local_import_path(config: &ImportConfig, location: &Url) -> Result<PathBuf, StorageError> {
+    let Some(local_dir) = &config.local_dir else {
+        return Err(StorageError::forbidden(
+            "Import from local files is disabled, set `service.import.local_dir` to enable it",
+        ));
+    };
+    let local_dir = Path::new(local_dir).canonicalize().map_err(|err| {
+        StorageError::service_error(format!("Can't access import directory {local_dir}: {err}"))
+    })?;
+
+    let local_path = location.to_file_path().map_err(|_| {
+        StorageError::bad_request("Invalid import URI, file path must be absolute or on localhost")
+    })?;
+    let outside_error = || {
+        StorageError::forbidden(format!(
+            "Import file {local_path:?} is outside of the import directory"
+        ))
+    };
+
+    // Checked before accessing the file, so files outside of the directory can't be probed.
+    // Dot segments are already removed from the path of the URL.
+    if !local_path.starts_with(&local_dir) {
+        return Err(outside_error());
+    }
+    let real_path = local_path.canonicalize().map_err(|_| {
+        StorageError::bad_request(format!("Import file {local_path:?} does not exist"))
+    })?;
+    // Symlinks may lead out of the directory
+    if !real_path.starts_with(&local_dir) {
+        return Err(outside_error());
+    }
+    Ok(real_path)
+}
+
+/// Check that a `http(s)://` location is allowed by the config
+fn check_remote_location(config: &ImportConfig, location: &Url) -> Result<(), StorageError> {
+    let is_allowed = config.allowed_urls.iter().any(|allowed| {
+        allowed.scheme() == location.scheme()
+            && allowed.host() == location.host()
+            && allowed.port_or_known_default() == location.port_or_known_default()
+            && location.path().starts_with(allowed.path())
+    });
+    if !is_allowed {
+        return Err(StorageError::forbidden(format!(
+            "Import from {location} is not allowed by `service.import.allowed_urls`"
+        )));
+    }
+    Ok(())
+}
+
+/// Get the local path of the file at `location`, downloading it into `download_dir` if it is remote
+///
+/// The returned `TempPath` deletes the downloaded file once it is dropped.
+async fn fetch_file(
+    client: &reqwest::Client,
+    config: &ImportConfig,
+    location: &Url,
+    download_dir: PathBuf,
+) -> Result<(PathBuf, Option<TempPath>), StorageError> {
+    match location.scheme() {
+        "file" => Ok((local_import_path(config, location)?, None)),
+        "http" | "https" => {
+            check_remote_location(config, location)?;
+
+            let path = download_dir.join(format!("{}.parquet", Uuid::new_v4()));
+            let temp_path = TempPath::from_path(&path);
+
+            let response = client.get(location.clone()).send().await?;
+            if !response.status().is_success() {
+                return Err(StorageError::bad_input(format!(
+                    "Failed to download {location}: status - {}",
+                    response.status()
+                )));
+            }
+
+            let mut file = tokio::fs::File::create(&path).await?;
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                file.write_all(&chunk?).await?;
+            }
+            file.flush().await?;
+
+            Ok((path, Some(temp_path)))
+        }
+        scheme => Err(StorageError::bad_request(format!(
+            "URL {location} with schema {scheme} is not supported"
+        ))),
+    }
+}
+
+/// Name of the vector stored in the column, if it is a vector column
+fn vector_name(column: &str) -> Option<&str> {
+    if column == "vector" {
+        Some(DEFAULT_VECTOR_NAME)
+    } else {
+        column.strip_prefix("vector.")
+    }
+}
+
+fn parse_point_id(value: Option<Value>, id_column: &str) -> Result<PointIdType, StorageError> {
+    let id = match value {
+        Some(Value::Number(number)) => number.as_u64().map(PointIdType::NumId),
+        Some(Value::String(id)) => match id.parse() {
+            Ok(id) => Some(PointIdType::NumId(id)),
+            Err(_) => Uuid::parse_str(&id).ok().map(PointIdType::Uuid),
+        },
+        _ => None,
+    };
+    id.ok_or_else(|| {
+        StorageError::bad_input(format!(
+            "Column {id_column} must contain unsigned integers or UUIDs"
+        ))
+    })
+}
+
+fn row_to_point(mut row: Map<String, Value>, id_column: &str) -> Result<PointStruct, StorageError> {
+    let id = parse_point_id(row.remove(id_column), id_column)?;
+
+    let mut vectors = HashMap::new();
+    let mut payload = Payload::default();
+    for (column, value) in row {
+        if column == "payload" {
+            let fields: Payload = match value {
+                Value::String(json) => serde_json::from_str(&json),
+                value => serde_json::from_value(value),
+            }
+            .map_err(|err| {
+                StorageError::bad_input(format!("Column payload must contain JSON objects: {err}"))
+            })?;
+            payload.merge(&fields);
+        } else if let Some(name) = vector_name(&column) {
+            let vector: Vec<f32> = serde_json::from_value(value).map_err(|err| {
+                StorageError::bad_input(format!(
+                    "Column {column} must contain lists of numbers: {err}"
+                ))
+            })?;
+            vectors.insert(name.to_string(), Vector::Dense(vector));
+        } else {
+            payload.0.insert(column, value);
+        }
+    }
+
+    let vector = match vectors.remove(DEFAULT_VECTOR_NAME) {
+        Some(Vector::Dense(vector)) if vectors.is_empty() => VectorStruct::Single(vector),
+        Some(vector) => {
+            vectors.insert(DEFAULT_VECTOR_NAME.to_string(), vector);
+            VectorStruct::Multi(vectors)
+        }
+        None => VectorStruct::Multi(vectors),
+    };
+
+    Ok(PointStruct {
+        id,
+        vector,
+        payload: Some(payload),
+    })
+}
+
+fn batch_to_points(batch: &RecordBatch, id_column: &str) -> Result<Vec<PointStruct>, StorageError> {
+    let rows = arrow_json::writer::record_batches_to_json_rows(&[batch])
+        .map_err(|err| StorageError::bad_input(format!("Can't read Parquet file: {err}")))?;
+    rows.into_iter()
+        .map(|row| row_to_point(row, id_column))
+        .collect()
+}
+
+/// Upsert points from a Parquet file, batch by batch
+///
+/// The file is read from local disk or downloaded from a URL, e.g. a pre-signed S3 URL.
+/// Only locations allowed by `config` are accessed, `client` must not follow redirects.
+pub async fn do_import_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: ImportPoints,
+    client: reqwest::Client,
+    config: &ImportConfig,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<ImportResult, StorageError> {
+    let ImportPoints {
+        location,
+        id_column,
+        batch_size,
+    } = request;
+    let id_column = id_column.unwrap_or_else(|| "id".to_string());
+
+    // Check that the collection exists before downloading the file
+    toc.get_collection(collection_name).await?;
+
+    let download_dir = toc.optional_temp_or_storage_temp_path()?;
+    let (path, _temp_path) = fetch_file(&client, config, &location, download_dir).await?;
+
+    let mut reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)
+        .and_then(|builder| {
+            builder
+                .with_batch_size(batch_size.unwrap_or(DEFAULT_IMPORT_BATCH_SIZE))
+                .build()
+        })
+        .map_err(|err| StorageError::bad_input(format!("Can't read Parquet file: {err}")))?;
+
+    let mut points_count = 0;
+    loop {
+        // Decoding is CPU and disk bound, so it is done outside of the async runtime
+        let (returned_reader, batch) = tokio::task::spawn_blocking(move || {
+            let batch = reader.next();
+            (reader, batch)
+        })
+        .await?;
+        reader = returned_reader;
+
+        let Some(batch) = batch else {
+            break;
+        };
+        let batch = batch
+            .map_err(|err| StorageError::bad_input(format!("Can't read Parquet file: {err}")))?;
+        let points = batch_to_points(&batch, &id_column)?;
+        points_count += points.len();
+        upsert_batch(toc, collection_name, points, wait, ordering).await?;
+    }
+
+    Ok(ImportResult { points_count })
+}
+
+async fn upsert_batch(
+    toc: &TableOfContent,
+    collection_name: &str,
+    points: Vec<PointStruct>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<(), StorageError> {
+    let operation = PointInsertOperations::PointsList(PointsList {
+        points,
+        shard_key: None,
+        condition: None,
+        if_version: None,
+    });
+    do_upsert_points(toc, collection_name, operation, None, wait, ordering).await?;
+    Ok(())
+}
+
+/// Splits chunks of a byte stream into blocks of complete lines
+#[derive(Default)]
+struct LineSplitter {
+    buffer: Vec<u8>,
+}
+
+impl LineSplitter {
+    /// Add a chunk and take the lines, which it completes, without the last line break
+    fn push(&mut self, chunk: &[u8]) -> Option<Vec<u8>> {
+        self.buffer.extend_from_slice(chunk);
+        let last_line_break = self.buffer.iter().rposition(|&byte| byte == b'\n')?;
+        let rest = self.buffer.split_off(last_line_break + 1);
+        let mut lines = std::mem::replace(&mut self.buffer, rest);
+        lines.truncate(last_line_break);
+        Some(lines)
+    }
+
+    /// Length of the incomplete line, which is buffered until its line break is received
+    fn pending_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Take the last line, which is not followed by a line break
+    fn finish(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+/// Parse a point from a line of NDJSON, empty lines are skipped
+fn parse_point_line(line: &[u8], line_number: usize) -> Result<Option<PointStruct>, StorageError> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    let point: PointStruct = serde_json::from_slice(line).map_err(|err| {
+        StorageError::bad_input(format!("Invalid point in line {line_number}: {err}"))
+    })?;
+    point.validate().map_err(|err| {
+        StorageError::bad_input(format!("Invalid point in line {line_number}: {err}"))
+    })?;
+    Ok(Some(point))
+}
+
+/// Upsert points, streamed as newline-delimited JSON, batch by batch
+///
+/// The body is not read further while a batch is upserted, so a fast client is slowed down to
+/// the speed of the updates. If a line is invalid, points of the previous batches stay upserted.
+pub async fn do_upload_points<B, E>(
+    toc: &TableOfContent,
+    collection_name: &str,
+    mut body: impl Stream<Item = Result<B, E>> + Unpin,
+    batch_size: Option<usize>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<ImportResult, StorageError>
+where
+    B: AsRef<[u8]>,
+    E: Display,
+{
+    let batch_size = batch_size.unwrap_or(DEFAULT_IMPORT_BATCH_SIZE);
+
+    let mut splitter = LineSplitter::default();
+    let mut points = Vec::with_capacity(batch_size);
+    let mut points_count = 0;
+    let mut line_number = 0;
+    let mut finished = false;
+
+    while !finished {
+        let lines = match body.next().await {
+            Some(chunk) => {
+                let chunk = chunk.map_err(|err| {
+                    StorageError::bad_input(format!("Failed to read request body: {err}"))
+                })?;
+                let lines = splitter.push(chunk.as_ref());
+                if splitter.pending_len() > MAX_UPLOAD_LINE_SIZE {
+                    let complete_lines = lines
+                        .as_ref()
+                        .map_or(0, |lines| lines.split(|&byte| byte == b'\n').count());
+                    return Err(StorageError::bad_input(format!(
+                        "Line {} is longer than the limit of {MAX_UPLOAD_LINE_SIZE} bytes",
+                        line_number + complete_lines + 1,
+                    )));
+                }
+                match lines {
+                    Some(lines) => lines,
+                    None => continue,
+                }
+            }
+            None => {
+                finished = true;
+                std::mem::take(&mut splitter).finish()
+            }
+        };
+
+        for line in lines.split(|&byte| byte == b'\n') {
+            line_number += 1;
+            if let Some(point) = parse_point_line(line, line_number)? {
+                points.push(point);
+            }
+            if points.len() >= batch_size {
+                points_count += points.len();
+                let batch = std::mem::replace(&mut points, Vec::with_capacity(batch_size));
+                upsert_batch(toc, collection_name, batch, wait, ordering).await?;
+            }
+        }
+    }
+
+    if !points.is_empty() {
+        points_count += points.len();
+        upsert_batch(toc, collection_name, points, wait, ordering).await?;
+    }
+
+    Ok(ImportResult { points_count })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::builder::{FixedSizeListBuilder, Float32Builder};
+    use arrow_array::{ArrayRef, Float64Array, StringArray, UInt64Array};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_line_splitter() {
+        let mut splitter = LineSplitter::default();
+        assert_eq!(
+            splitter.push(b"{\"id\": 1}\n{\"id\""),
+            Some(b"{\"id\": 1}".to_vec())
+        );
+        assert_eq!(splitter.push(b": 2"), None);
+        assert_eq!(splitter.pending_len(), 8);
+        assert_eq!(
+            splitter.push(b"}\n\n{\"id\": 3}\n{"),
+            Some(b"{\"id\": 2}\n\n{\"id\": 3}".to_vec()),
+        );
+        assert_eq!(splitter.pending_len(), 1);
+        assert_eq!(splitter.finish(), b"{".to_vec());
+    }
+
+    #[test]
+    fn test_parse_point_line() {
+        let point = parse_point_line(br#"{"id": 1, "vector": [0.1, 0.2]}"#, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(point.id, PointIdType::NumId(1));
+        assert_eq!(point.vector, VectorStruct::Single(vec![0.1, 0.2]));
+
+        assert!(parse_point_line(b"  \r", 2).unwrap().is_none());
+
+        let error = parse_point_line(br#"{"vector": [0.1]}"#, 3).unwrap_err();
+        assert!(error.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn test_batch_to_points() {
+        let mut vectors = FixedSizeListBuilder::new(Float32Builder::new(), 2);
+        vectors.values().append_slice(&[0.1, 0.2]);
+        vectors.append(true);
+        vectors.values().append_slice(&[0.3, 0.4]);
+        vectors.append(true);
+
+        let columns: Vec<(&str, ArrayRef)> = vec![
+            ("id", Arc::new(UInt64Array::from(vec![1, 2]))),
+            ("vector", Arc::new(vectors.finish())),
+            (
+                "payload",
+                Arc::new(StringArray::from(vec![Some(r#"{"city": "Berlin"}"#), None])),
+            ),
+            ("price", Arc::new(Float64Array::from(vec![Some(9.5), None]))),
+        ];
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+
+        let points = batch_to_points(&batch, "id").unwrap();
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].id, PointIdType::NumId(1));
+        assert_eq!(points[0].vector, VectorStruct::Single(vec![0.1, 0.2]));
+        assert_eq!(
+            points[0].payload,
+            Some(Payload::from(json!({"city": "Berlin", "price": 9.5}))),
+        );
+        assert_eq!(points[1].id, PointIdType::NumId(2));
+        assert_eq!(points[1].vector, VectorStruct::Single(vec![0.3, 0.4]));
+        assert_eq!(points[1].payload, Some(Payload::default()));
+
+        let error = batch_to_points(&batch, "missing").unwrap_err();
+        assert!(matches!(error, StorageError::BadInput { .. }));
+    }
+
+    #[test]
+    fn test_local_import_path() {
+        let root = tempfile::Builder::new()
+            .prefix("import_root")
+            .tempdir()
+            .unwrap();
+        // Canonical, so the paths are comparable with the canonical import directory
+        let root_path = root.path().canonicalize().unwrap();
+        let import_dir = root_path.join("import");
+        std::fs::create_dir(&import_dir).unwrap();
+        std::fs::write(import_dir.join("points.parquet"), b"").unwrap();
+        std::fs::write(root_path.join("secret.parquet"), b"").unwrap();
+
+        let url = |path: &Path| Url::from_file_path(path).unwrap();
+        let config = ImportConfig {
+            local_dir: Some(import_dir.to_string_lossy().into_owned()),
+            allowed_urls: vec![],
+        };
+
+        let path = local_import_path(&config, &url(&import_dir.join("points.parquet"))).unwrap();
+        assert!(path.ends_with("import/points.parquet"));
+
+        let error = local_import_path(&config, &url(&import_dir.join("missing.parquet")));
+        assert!(matches!(error, Err(StorageError::BadRequest { .. })));
+
+        let outside = url(&root_path.join("secret.parquet"));
+        let error = local_import_path(&config, &outside);
+        assert!(matches!(error, Err(StorageError::Forbidden { .. })));
+
+        let traversal = Url::parse(&format!("{}/../secret.parquet", url(&import_dir))).unwrap();
+        let error = local_import_path(&config, &traversal);
+        assert!(matches!(error, Err(StorageError::Forbidden { .. })));
+
+        #[cfg(unix)]
+        {
+            let link = import_dir.join("link.parquet");
+            std::os::unix::fs::symlink(root_path.join("secret.parquet"), &link).unwrap();
+            let error = local_import_path(&config, &url(&link));
+            assert!(matches!(error, Err(StorageError::Forbidden { .. })));
+        }
+
+        let error = local_import_path(&ImportConfig::default(), &outside);
+        assert!(matches!(error, Err(StorageError::Forbidden { .. })));
+    }
+
+    #[test]
+    fn test_check_remote_location() {
+        let config = ImportConfig {
+            local_dir: None,
+            allowed_urls: vec![Url::parse("https://bucket.s3.amazonaws.com/exports/").unwrap()],
+        };
+        let check = |location: &str| check_remote_location(&config, &Url::parse(location).unwrap());
+
+        assert!(check("https://bucket.s3.amazonaws.com/exports/points.parquet").is_ok());
+        assert!(check("https://bucket.s3.amazonaws.com:443/exports/a/points.parquet").is_ok());
+
+        assert!(check("http://bucket.s3.amazonaws.com/exports/points.parquet").is_err());
+        assert!(check("https://bucket.s3.amazonaws.com:8443/exports/points.parquet").is_err());
+        assert!(check("https://bucket.s3.amazonaws.com/secret/points.parquet").is_err());
+        assert!(check("https://bucket.s3.amazonaws.com/exports/../secret/points").is_err());
+        assert!(check("https://bucket.s3.amazonaws.com.evil.com/exports/points.parquet").is_err());
+        assert!(check("http://169.254.169.254/latest/meta-data/").is_err());
+
+        assert!(check_remote_location(
+            &ImportConfig::default(),
+            &Url::parse("https://bucket.s3.amazonaws.com/exports/points.parquet").unwrap()
+        )
+        .is_err());
+    }
+}
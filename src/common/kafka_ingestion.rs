@@ -0,0 +1,288 @@
+//! Streaming ingestion of update operations from Kafka topics
+//!
+//! Every configured topic is consumed without a consumer group: offsets are checkpointed in the
+//! storage directory instead, right after the consumed operations are applied to the collection.
+//! Operations are applied with `wait`, so they are in the WAL of the collection before the
+//! checkpoint moves past them. After a crash, at most the last batch is consumed again, which is
+//! harmless for upserts and deletes of the same points.
+//!
+//! Operations, which can never be applied, e.g. vectors of a wrong dimension, are logged and
+//! skipped. Otherwise a single bad message would stop its partition forever.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use collection::operations::point_ops::WriteOrdering;
+use collection::save_on_disk::SaveOnDisk;
+use futures::future;
+use rskafka::client::partition::{OffsetAt, UnknownTopicHandling};
+use rskafka::client::{Client, ClientBuilder};
+use rskafka::record::RecordAndOffset;
+use serde::{Deserialize, Serialize};
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::toc::TableOfContent;
+use tokio::runtime::Handle;
+use validator::Validate;
+
+use crate::common::points::{do_batch_update_points, UpdateOperation};
+use crate::settings::{KafkaIngestionConfig, KafkaStartOffset};
+
+/// Directory in the storage, where checkpoints of consumed offsets are stored
+const CHECKPOINTS_DIR: &str = "ingestion";
+
+/// Delay before the consumer reconnects after an error
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Next offset to consume, for each partition of the topic
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+struct KafkaCheckpoint {
+    offsets: BTreeMap<i32, i64>,
+}
+
+/// Start a consumer for each configured topic on the `runtime`
+///
+/// Consumers run until the service stops. Errors are logged and the consumer is restarted
+/// from the last checkpoint.
+pub fn spawn_kafka_ingestion(
+    toc: Arc<TableOfContent>,
+    configs: &[KafkaIngestionConfig],
+    runtime: &Handle,
+) {
+    for config in configs {
+        log::info!(
+            "Consuming Kafka topic {} into collection {}",
+            config.topic,
+            config.collection,
+        );
+        runtime.spawn(run_consumer(toc.clone(), config.clone()));
+    }
+}
+
+async fn run_consumer(toc: Arc<TableOfContent>, config: KafkaIngestionConfig) {
+    loop {
+        if let Err(err) = consume_topic(&toc, &config).await {
+            log::error!(
+                "Failed to consume Kafka topic {} into collection {}, retrying in {}s: {err:#}",
+                config.topic,
+                config.collection,
+                RETRY_DELAY.as_secs(),
+            );
+        }
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
+
+fn checkpoint_path(toc: &TableOfContent, config: &KafkaIngestionConfig) -> PathBuf {
+    PathBuf::from(toc.storage_path())
+        .join(CHECKPOINTS_DIR)
+        .join(format!("kafka-{}-{}.json", config.collection, config.topic))
+}
+
+async fn consume_topic(toc: &TableOfContent, config: &KafkaIngestionConfig) -> anyhow::Result<()> {
+    let client = ClientBuilder::new(config.brokers.clone())
+        .build()
+        .await
+        .context("can't connect to brokers")?;
+
+    let partitions = match &config.partitions {
+        Some(partitions) => partitions.clone(),
+        None => client
+            .list_topics()
+            .await?
+            .into_iter()
+            .find(|topic| topic.name == config.topic)
+            .map(|topic| topic.partitions.into_iter().collect())
+            .with_context(|| format!("topic {} does not exist", config.topic))?,
+    };
+
+    let path = checkpoint_path(toc, config);
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let checkpoint: SaveOnDisk<KafkaCheckpoint> = SaveOnDisk::load_or_init(path)?;
+
+    let partition_futures = partitions
+        .into_iter()
+        .map(|partition| consume_partition(toc, config, &client, &checkpoint, partition));
+    future::try_join_all(partition_futures).await?;
+    Ok(())
+}
+
+async fn consume_partition(
+    toc: &TableOfContent,
+    config: &KafkaIngestionConfig,
+    client: &Client,
+    checkpoint: &SaveOnDisk<KafkaCheckpoint>,
+    partition: i32,
+) -> anyhow::Result<()> {
+    let partition_client = client
+        .partition_client(config.topic.clone(), partition, UnknownTopicHandling::Error)
+        .await
+        .with_context(|| format!("can't consume partition {partition}"))?;
+
+    let checkpoint_offset = checkpoint.read().offsets.get(&partition).copied();
+    let mut offset = match checkpoint_offset {
+        Some(offset) => offset,
+        None => {
+            let start_at = match config.start_from {
+                KafkaStartOffset::Earliest => OffsetAt::Earliest,
+                KafkaStartOffset::Latest => OffsetAt::Latest,
+            };
+            partition_client.get_offset(start_at).await?
+        }
+    };
+
+    loop {
+        let (records, _high_watermark) = partition_client
+            .fetch_records(offset, 1..config.max_fetch_bytes, config.max_wait_ms)
+            .await
+            .with_context(|| format!("can't fetch partition {partition} at offset {offset}"))?;
+
+        // Compressed batches may start before the requested offset
+        let records: Vec<_> = records
+            .into_iter()
+            .filter(|record| record.offset >= offset)
+            .collect();
+        let Some(next_offset) = records.last().map(|record| record.offset + 1) else {
+            continue;
+        };
+
+        let operations: Vec<_> = records
+            .into_iter()
+            .filter_map(|record| {
+                let offset = record.offset;
+                parse_operation(record, partition).map(|operation| (offset, operation))
+            })
+            .collect();
+        for (operation_offset, operation) in operations {
+            let result = do_batch_update_points(
+                toc,
+                &config.collection,
+                vec![operation],
+                None,
+                true,
+                WriteOrdering::default(),
+            )
+            .await;
+            let Err(err) = result else {
+                continue;
+            };
+
+            // A missing collection fails every operation, but it may still be created
+            if !is_transient_error(&err) && toc.get_collection(&config.collection).await.is_ok() {
+                log::warn!(
+                    "Skipping operation at partition {partition}, offset {operation_offset}, \
+                     which can't be applied: {err}"
+                );
+                continue;
+            }
+
+            // Previous operations are applied, don't apply them again after the retry
+            checkpoint
+                .write(|checkpoint| checkpoint.offsets.insert(partition, operation_offset))?;
+            return Err(err).with_context(|| {
+                format!(
+                    "can't apply operation from partition {partition} at offset {operation_offset}"
+                )
+            });
+        }
+
+        checkpoint.write(|checkpoint| checkpoint.offsets.insert(partition, next_offset))?;
+        offset = next_offset;
+    }
+}
+
+/// Whether applying the operation may succeed, if it is retried later
+///
+/// Other errors are caused by the operation itself, so retrying it fails again.
+fn is_transient_error(err: &StorageError) -> bool {
+    match err {
+        StorageError::ServiceError { .. }
+        | StorageError::Locked { .. }
+        | StorageError::Timeout { .. }
+        | StorageError::RateLimitExceeded { .. } => true,
+        StorageError::BadInput { .. }
+        | StorageError::NotFound { .. }
+        | StorageError::BadRequest { .. }
+        | StorageError::Forbidden { .. } => false,
+    }
+}
+
+/// Parse an operation from a message, invalid messages are logged and skipped
+fn parse_operation(record: RecordAndOffset, partition: i32) -> Option<UpdateOperation> {
+    let offset = record.offset;
+    // Messages without a value are tombstones of compacted topics
+    let value = record.record.value?;
+    let operation = serde_json::from_slice::<UpdateOperation>(&value)
+        .map_err(|err| err.to_string())
+        .and_then(|operation| {
+            operation.validate().map_err(|err| err.to_string())?;
+            Ok(operation)
+        });
+    match operation {
+        Ok(operation) => Some(operation),
+        Err(err) => {
+            log::warn!(
+                "Skipping invalid operation at partition {partition}, offset {offset}: {err}"
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rskafka::record::Record;
+
+    use super::*;
+
+    fn record(value: Option<&str>) -> RecordAndOffset {
+        RecordAndOffset {
+            record: Record {
+                key: None,
+                value: value.map(|value| value.as_bytes().to_vec()),
+                headers: BTreeMap::new(),
+                timestamp: chrono::Utc::now(),
+            },
+            offset: 10,
+        }
+    }
+
+    #[test]
+    fn test_parse_operation() {
+        let upsert = r#"{"upsert": {"points": [{"id": 1, "vector": [0.1, 0.2]}]}}"#;
+        assert!(matches!(
+            parse_operation(record(Some(upsert)), 0),
+            Some(UpdateOperation::Upsert(_)),
+        ));
+
+        let delete = r#"{"delete": {"points": [1, 2]}}"#;
+        assert!(matches!(
+            parse_operation(record(Some(delete)), 0),
+            Some(UpdateOperation::Delete(_)),
+        ));
+
+        assert!(parse_operation(record(Some(r#"{"unknown": {}}"#)), 0).is_none());
+        assert!(parse_operation(record(Some("not json")), 0).is_none());
+        assert!(parse_operation(record(None), 0).is_none());
+    }
+
+    #[test]
+    fn test_is_transient_error() {
+        assert!(is_transient_error(&StorageError::service_error(
+            "disk is full"
+        )));
+        assert!(is_transient_error(&StorageError::Timeout {
+            description: "no response".to_string(),
+        }));
+        assert!(!is_transient_error(&StorageError::bad_input(
+            "wrong vector dimension"
+        )));
+        assert!(!is_transient_error(&StorageError::NotFound {
+            description: "no point with id 1".to_string(),
+        }));
+    }
+}
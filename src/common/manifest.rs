@@ -0,0 +1,189 @@
+//! Manifest of collections, created at the first start of a deployment
+//!
+//! Makes environments reproducible: the same manifest always results in the same collections,
+//! payload indexes and aliases. Collection configs use the format of the REST API:
+//!
+//! ```json
+//! {
+//!   "collections": [
+//!     {
+//!       "name": "products",
+//!       "config": {
+//!         "vectors": { "size": 384, "distance": "Cosine" },
+//!         "replication_factor": 2
+//!       },
+//!       "payload_indexes": [{ "field_name": "category", "field_schema": "keyword" }],
+//!       "aliases": ["products_current"]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Peers join the cluster through `--bootstrap` or peer discovery, access rules are defined by
+//! `service.api_keys`, so neither of them is part of the manifest.
+
+use std::fs;
+
+use anyhow::Context as _;
+use segment::types::{PayloadFieldSchema, PayloadKeyType};
+use serde::Deserialize;
+use storage::content_manager::collection_meta_ops::{
+    ChangeAliasesOperation, CollectionMetaOperations, CreateAlias, CreateCollection,
+    CreateCollectionOperation, CreatePayloadIndex,
+};
+use storage::content_manager::errors::StorageError;
+use storage::dispatcher::Dispatcher;
+use validator::Validate;
+
+#[derive(Debug, Deserialize)]
+pub struct ClusterManifest {
+    #[serde(default)]
+    pub collections: Vec<CollectionManifest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionManifest {
+    pub name: String,
+    /// Same parameters, as in the request to create a collection
+    pub config: CreateCollection,
+    #[serde(default)]
+    pub payload_indexes: Vec<PayloadIndexManifest>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayloadIndexManifest {
+    pub field_name: PayloadKeyType,
+    pub field_schema: PayloadFieldSchema,
+}
+
+/// Read and validate the manifest
+pub fn load_manifest(path: &str) -> anyhow::Result<ClusterManifest> {
+    let manifest =
+        fs::read_to_string(path).with_context(|| format!("Failed to read manifest {path}"))?;
+    let manifest =
+        parse_manifest(&manifest).with_context(|| format!("Failed to parse manifest {path}"))?;
+
+    for collection in &manifest.collections {
+        collection
+            .config
+            .validate()
+            .with_context(|| format!("Invalid config of collection {}", collection.name))?;
+    }
+
+    Ok(manifest)
+}
+
+fn parse_manifest(manifest: &str) -> serde_json::Result<ClusterManifest> {
+    serde_json::from_str(manifest)
+}
+
+/// Create collections of the manifest with their payload indexes and aliases
+///
+/// Collections, which already exist, are skipped, so an interrupted start can be repeated.
+pub async fn apply_manifest(
+    dispatcher: &Dispatcher,
+    manifest: ClusterManifest,
+) -> Result<(), StorageError> {
+    for collection in manifest.collections {
+        let CollectionManifest {
+            name,
+            config,
+            payload_indexes,
+            aliases,
+        } = collection;
+
+        if dispatcher.toc().get_collection(&name).await.is_ok() {
+            log::info!("Collection {name} of the manifest already exists, skipping");
+            continue;
+        }
+
+        log::info!("Creating collection {name} from the manifest");
+        dispatcher
+            .submit_collection_meta_op(
+                CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
+                    name.clone(),
+                    config,
+                )),
+                None,
+            )
+            .await?;
+
+        for index in payload_indexes {
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::CreatePayloadIndex(CreatePayloadIndex {
+                        collection_name: name.clone(),
+                        field_name: index.field_name,
+                        field_schema: index.field_schema,
+                    }),
+                    None,
+                )
+                .await?;
+        }
+
+        if !aliases.is_empty() {
+            let actions = aliases
+                .into_iter()
+                .map(|alias_name| {
+                    CreateAlias {
+                        collection_name: name.clone(),
+                        alias_name,
+                    }
+                    .into()
+                })
+                .collect();
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::ChangeAliases(ChangeAliasesOperation { actions }),
+                    None,
+                )
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = parse_manifest(
+            r#"{
+                "collections": [
+                    {
+                        "name": "products",
+                        "config": {
+                            "vectors": { "Image": { "size": 4, "distance": "Cosine" } }
+                        },
+                        "payload_indexes": [
+                            { "field_name": "category", "field_schema": "keyword" }
+                        ],
+                        "aliases": ["products_current"]
+                    },
+                    {
+                        "name": "logs",
+                        "config": { "vectors": { "size": 8, "distance": "Dot" } }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let [products, logs] = manifest.collections.as_slice() else {
+            panic!("expected two collections");
+        };
+        assert_eq!(products.name, "products");
+        assert!(products.config.validate().is_ok());
+        assert_eq!(products.payload_indexes[0].field_name, "category");
+        assert_eq!(products.aliases, ["products_current"]);
+        assert_eq!(logs.name, "logs");
+        assert!(logs.payload_indexes.is_empty());
+
+        assert!(parse_manifest(r#"{"collections": [{"name": "no_config"}]}"#).is_err());
+    }
+}
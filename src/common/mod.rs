@@ -1,13 +1,21 @@
+pub mod change_feed;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod collections;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod error_reporting;
+pub mod export;
 #[allow(dead_code)]
 pub mod health;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod helpers;
 pub mod http_client;
+pub mod import;
+#[cfg(feature = "kafka")]
+pub mod kafka_ingestion;
+pub mod manifest;
 pub mod metrics;
+pub mod peer_discovery;
+pub mod peer_migration;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod points;
 pub mod snapshots;
@@ -18,7 +26,9 @@ pub mod telemetry;
 pub mod telemetry_ops;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod telemetry_reporting;
+pub mod usage_metering;
 
 pub mod auth;
+pub mod certificate_helpers;
 
 pub mod strings;
@@ -0,0 +1,158 @@
+//! Discovery of the peer to bootstrap from through DNS, e.g. of a Kubernetes headless service
+//!
+//! The internal gRPC server of a peer only starts once the peer has joined or started a cluster,
+//! so any peer, which accepts connections, is a valid bootstrap peer. If there is none, the peer
+//! with the lowest address starts a new cluster and the other peers join it later.
+//!
+//! Peers are registered with their DNS names, not with the discovered addresses. Connections to
+//! other peers resolve the names again, so changed pod addresses are picked up once the old
+//! connections fail.
+
+use std::collections::BTreeSet;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context as _};
+use tokio::net::{lookup_host, TcpStream};
+use tonic::transport::Uri;
+
+use crate::settings::PeerDiscoveryConfig;
+
+/// Delay between attempts to discover a running peer
+const DISCOVERY_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Timeout of a connection to a discovered peer
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+fn scheme(enable_tls: bool) -> &'static str {
+    if enable_tls {
+        "https"
+    } else {
+        "http"
+    }
+}
+
+/// URI of this peer under the discovery name: `<hostname>.<dns_name>`
+///
+/// In a Kubernetes stateful set, the hostname is the name of the pod, which is stable across
+/// restarts, unlike its address.
+pub fn this_peer_uri(
+    config: &PeerDiscoveryConfig,
+    p2p_port: u16,
+    enable_tls: bool,
+) -> anyhow::Result<Uri> {
+    let hostname = std::env::var("HOSTNAME")
+        .context("Can't derive URI of this peer, set `--uri` or `HOSTNAME` variable")?;
+    let uri = format!(
+        "{}://{hostname}.{}:{p2p_port}",
+        scheme(enable_tls),
+        config.dns_name,
+    );
+    uri.parse()
+        .with_context(|| format!("Invalid URI of this peer: {uri}"))
+}
+
+/// Find a running peer to bootstrap from
+///
+/// Returns `None` if there are no running peers yet and this peer has the lowest address, so it
+/// should start a new cluster. Waits for other peers otherwise.
+pub async fn discover_bootstrap_peer(
+    config: &PeerDiscoveryConfig,
+    this_peer_uri: &Uri,
+    p2p_port: u16,
+    enable_tls: bool,
+) -> anyhow::Result<Option<Uri>> {
+    let this_host = this_peer_uri
+        .host()
+        .ok_or_else(|| anyhow!("URI of this peer {this_peer_uri} has no host"))?;
+    let this_port = this_peer_uri.port_u16().unwrap_or(p2p_port);
+    let deadline = Instant::now() + Duration::from_secs(config.timeout_sec);
+
+    loop {
+        // Addresses are resolved on every attempt, peers might still be starting up
+        let this_addresses = resolve(this_host, this_port).await.unwrap_or_default();
+        let addresses = resolve(&config.dns_name, p2p_port)
+            .await
+            .unwrap_or_default();
+
+        for address in addresses.difference(&this_addresses) {
+            if probe(*address).await {
+                let uri = format!("{}://{address}", scheme(enable_tls));
+                log::info!("Discovered running peer at {uri}");
+                return Ok(Some(uri.parse()?));
+            }
+        }
+
+        if is_first_peer(&this_addresses, &addresses) {
+            log::info!(
+                "No running peers found at {}, starting a new cluster",
+                config.dns_name,
+            );
+            return Ok(None);
+        }
+
+        if Instant::now() >= deadline {
+            return Err(anyhow!(
+                "No running peers found at {} in {}s, discovered addresses: {addresses:?}",
+                config.dns_name,
+                config.timeout_sec,
+            ));
+        }
+        log::debug!(
+            "Waiting for running peers at {}, discovered addresses: {addresses:?}",
+            config.dns_name,
+        );
+        tokio::time::sleep(DISCOVERY_RETRY_DELAY).await;
+    }
+}
+
+async fn resolve(host: &str, port: u16) -> std::io::Result<BTreeSet<SocketAddr>> {
+    Ok(lookup_host((host, port)).await?.collect())
+}
+
+/// Check if the peer accepts connections
+async fn probe(address: SocketAddr) -> bool {
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(address)).await,
+        Ok(Ok(_)),
+    )
+}
+
+/// This peer starts a new cluster if it is discovered and has the lowest address
+fn is_first_peer(this_addresses: &BTreeSet<SocketAddr>, addresses: &BTreeSet<SocketAddr>) -> bool {
+    let lowest_ip: Option<IpAddr> = addresses.iter().map(|address| address.ip()).min();
+    match lowest_ip {
+        Some(lowest_ip) => this_addresses
+            .iter()
+            .any(|address| address.ip() == lowest_ip),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(addresses: &[&str]) -> BTreeSet<SocketAddr> {
+        addresses
+            .iter()
+            .map(|address| address.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_is_first_peer() {
+        let all = addresses(&["10.0.0.7:6335", "10.0.0.12:6335", "10.0.0.3:6335"]);
+
+        assert!(is_first_peer(&addresses(&["10.0.0.3:6335"]), &all));
+        assert!(!is_first_peer(&addresses(&["10.0.0.7:6335"]), &all));
+
+        // This peer is not discovered yet
+        assert!(!is_first_peer(&addresses(&["10.0.0.1:6335"]), &all));
+        assert!(!is_first_peer(
+            &addresses(&["10.0.0.3:6335"]),
+            &addresses(&[])
+        ));
+        assert!(!is_first_peer(&addresses(&[]), &all));
+    }
+}
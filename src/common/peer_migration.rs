@@ -0,0 +1,311 @@
+//! Migration of all replicas from old peers to newly joined ones, e.g. for a blue-green upgrade
+//!
+//! The migration runs in three steps, each of them is submitted through consensus:
+//!
+//! 1. Every shard with replicas on the old peers is replicated to the new peers, one transfer at
+//!    a time. Shards, which already have enough replicas on the new peers, are skipped, so an
+//!    interrupted migration can be started again.
+//! 2. Exact point counts of all active replicas are compared.
+//! 3. Replicas on the old peers are dropped. All replicas of a collection are dropped in a single
+//!    consensus operation, so reads and writes of the collection switch to the new peers at once.
+//!
+//! Old peers keep their data until the last step, a failed migration leaves the cluster serving
+//! from the old peers. Afterwards the old peers are empty and can be removed from the cluster.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use collection::shards::replica_set::{self, ReplicaState};
+use collection::shards::shard::{PeerId, ShardId};
+use collection::shards::transfer::ShardTransfer;
+use storage::content_manager::collection_meta_ops::{
+    CollectionMetaOperations, ShardTransferOperations, UpdateCollectionOperation,
+};
+use storage::content_manager::errors::StorageError;
+use storage::dispatcher::Dispatcher;
+use storage::types::PeerMigration;
+
+/// Interval to check the state of a running shard transfer
+const TRANSFER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Replica counts may differ while updates are being applied, they are compared a few times
+const VERIFY_ATTEMPTS: usize = 5;
+const VERIFY_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+pub async fn migrate_peers(
+    dispatcher: Arc<Dispatcher>,
+    migration: PeerMigration,
+) -> Result<bool, StorageError> {
+    let Some(consensus_state) = dispatcher.consensus_state() else {
+        return Err(StorageError::bad_request("Distributed mode disabled"));
+    };
+
+    let from_peers: HashSet<PeerId> = migration.from_peers.into_iter().collect();
+    let to_peers: HashSet<PeerId> = migration.to_peers.into_iter().collect();
+    if !from_peers.is_disjoint(&to_peers) {
+        return Err(StorageError::bad_request(
+            "Peers to migrate from and to must be different",
+        ));
+    }
+    let known_peers = consensus_state.peer_address_by_id();
+    if let Some(peer_id) = from_peers
+        .iter()
+        .chain(&to_peers)
+        .find(|peer_id| !known_peers.contains_key(peer_id))
+    {
+        return Err(StorageError::BadRequest {
+            description: format!("Peer {peer_id} does not exist"),
+        });
+    }
+
+    let collection_names = dispatcher.toc().all_collections().await;
+    let mut planner = TargetPlanner::new(&to_peers);
+
+    // Collection guards are never held across consensus operations, applying them needs the lock
+    for collection_name in &collection_names {
+        replicate_collection(
+            &dispatcher,
+            collection_name,
+            &from_peers,
+            &to_peers,
+            &mut planner,
+        )
+        .await?;
+    }
+
+    for collection_name in &collection_names {
+        verify_collection(&dispatcher, collection_name, &to_peers).await?;
+    }
+
+    for collection_name in collection_names {
+        let changes: Vec<_> = dispatcher
+            .get_collection(&collection_name)
+            .await?
+            .state()
+            .await
+            .shards
+            .into_iter()
+            .flat_map(|(shard_id, shard_info)| {
+                shard_info
+                    .replicas
+                    .into_keys()
+                    .filter(|peer_id| from_peers.contains(peer_id))
+                    .map(move |peer_id| replica_set::Change::Remove(shard_id, peer_id))
+            })
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+
+        log::info!(
+            "Switching collection {collection_name} to peers {to_peers:?}, dropping {} replicas",
+            changes.len(),
+        );
+        let mut update_operation = UpdateCollectionOperation::new_empty(collection_name);
+        update_operation.set_shard_replica_changes(changes);
+        dispatcher
+            .submit_collection_meta_op(
+                CollectionMetaOperations::UpdateCollection(update_operation),
+                None,
+            )
+            .await?;
+    }
+
+    log::info!("Migration from peers {from_peers:?} to peers {to_peers:?} is finished");
+    Ok(true)
+}
+
+/// Replicate every shard to as many new peers, as it has replicas on the old peers
+async fn replicate_collection(
+    dispatcher: &Dispatcher,
+    collection_name: &str,
+    from_peers: &HashSet<PeerId>,
+    to_peers: &HashSet<PeerId>,
+    planner: &mut TargetPlanner,
+) -> Result<(), StorageError> {
+    let state = dispatcher
+        .get_collection(collection_name)
+        .await?
+        .state()
+        .await;
+    let mut shards: Vec<_> = state.shards.into_iter().collect();
+    shards.sort_unstable_by_key(|(shard_id, _)| *shard_id);
+
+    for (shard_id, shard_info) in shards {
+        let replicas = shard_info.replicas;
+        let old_replicas = replicas.keys().filter(|peer| from_peers.contains(peer));
+        let new_replicas = replicas.keys().filter(|peer| to_peers.contains(peer));
+        let missing = old_replicas.count().saturating_sub(new_replicas.count());
+        if missing == 0 {
+            continue;
+        }
+
+        // Old peers are the preferred source, they are going away anyway
+        let Some(source) = replicas
+            .iter()
+            .filter(|(_, state)| **state == ReplicaState::Active)
+            .map(|(peer_id, _)| *peer_id)
+            .min_by_key(|peer_id| (!from_peers.contains(peer_id), *peer_id))
+        else {
+            return Err(StorageError::service_error(format!(
+                "Shard {shard_id} of {collection_name} has no active replicas",
+            )));
+        };
+
+        let mut hosts: HashSet<PeerId> = replicas.keys().copied().collect();
+        for _ in 0..missing {
+            let Some(target) = planner.next_target(&hosts) else {
+                return Err(StorageError::bad_request(format!(
+                    "No new peer left for a replica of shard {shard_id} of {collection_name}",
+                )));
+            };
+            hosts.insert(target);
+
+            log::info!(
+                "Replicating shard {shard_id} of {collection_name} from peer {source} to {target}",
+            );
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::TransferShard(
+                        collection_name.to_string(),
+                        ShardTransferOperations::Start(ShardTransfer {
+                            shard_id,
+                            from: source,
+                            to: target,
+                            sync: true,
+                            method: None,
+                        }),
+                    ),
+                    None,
+                )
+                .await?;
+            wait_for_active_replica(dispatcher, collection_name, shard_id, target).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Wait until the transfer to the target peer is finished and its replica is active
+async fn wait_for_active_replica(
+    dispatcher: &Dispatcher,
+    collection_name: &str,
+    shard_id: ShardId,
+    target: PeerId,
+) -> Result<(), StorageError> {
+    loop {
+        let state = dispatcher
+            .get_collection(collection_name)
+            .await?
+            .state()
+            .await;
+        let replica_state = state
+            .shards
+            .get(&shard_id)
+            .and_then(|shard_info| shard_info.replicas.get(&target));
+        if replica_state == Some(&ReplicaState::Active) {
+            return Ok(());
+        }
+
+        let is_transferring = state
+            .transfers
+            .iter()
+            .any(|transfer| transfer.shard_id == shard_id && transfer.to == target);
+        if !is_transferring {
+            return Err(StorageError::service_error(format!(
+                "Transfer of shard {shard_id} of {collection_name} to peer {target} failed, \
+                 replica state is {replica_state:?}",
+            )));
+        }
+
+        tokio::time::sleep(TRANSFER_POLL_INTERVAL).await;
+    }
+}
+
+/// Check that every shard has an active replica on the new peers and all replicas are equal
+async fn verify_collection(
+    dispatcher: &Dispatcher,
+    collection_name: &str,
+    to_peers: &HashSet<PeerId>,
+) -> Result<(), StorageError> {
+    let state = dispatcher
+        .get_collection(collection_name)
+        .await?
+        .state()
+        .await;
+    let mut shard_ids: Vec<_> = state.shards.into_keys().collect();
+    shard_ids.sort_unstable();
+
+    for shard_id in shard_ids {
+        let mut attempt = 1;
+        loop {
+            let counts = dispatcher
+                .get_collection(collection_name)
+                .await?
+                .count_shard_replicas(shard_id)
+                .await?;
+            let has_new_replica = counts.keys().any(|peer_id| to_peers.contains(peer_id));
+            let is_consistent = counts.values().collect::<HashSet<_>>().len() <= 1;
+
+            if has_new_replica && is_consistent {
+                break;
+            }
+            if attempt >= VERIFY_ATTEMPTS {
+                return Err(StorageError::service_error(format!(
+                    "Replicas of shard {shard_id} of {collection_name} are inconsistent, \
+                     point counts: {counts:?}",
+                )));
+            }
+
+            attempt += 1;
+            tokio::time::sleep(VERIFY_RETRY_DELAY).await;
+        }
+    }
+
+    log::info!("Replicas of collection {collection_name} are consistent");
+    Ok(())
+}
+
+/// Spreads new replicas evenly across the new peers
+struct TargetPlanner {
+    assigned: HashMap<PeerId, usize>,
+}
+
+impl TargetPlanner {
+    fn new(to_peers: &HashSet<PeerId>) -> Self {
+        Self {
+            assigned: to_peers.iter().map(|peer_id| (*peer_id, 0)).collect(),
+        }
+    }
+
+    /// New peer with the fewest assigned replicas, which doesn't host the shard yet
+    fn next_target(&mut self, hosts: &HashSet<PeerId>) -> Option<PeerId> {
+        let (target, assigned) = self
+            .assigned
+            .iter_mut()
+            .filter(|(peer_id, _)| !hosts.contains(peer_id))
+            .min_by_key(|(peer_id, assigned)| (**assigned, **peer_id))?;
+        *assigned += 1;
+        Some(*target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_target() {
+        let mut planner = TargetPlanner::new(&HashSet::from([10, 11, 12]));
+
+        let targets: Vec<_> = (0..3)
+            .map(|_| planner.next_target(&HashSet::new()).unwrap())
+            .collect();
+        assert_eq!(targets, [10, 11, 12]);
+
+        // Peers, which already host the shard, are skipped
+        assert_eq!(planner.next_target(&HashSet::from([1, 10])), Some(11));
+        assert_eq!(planner.next_target(&HashSet::from([10, 11, 12])), None);
+    }
+}
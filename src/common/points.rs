@@ -1,29 +1,39 @@
+use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
+use actix_web::web::Bytes;
 use collection::common::batching::batch_requests;
 use collection::operations::consistency_params::ReadConsistency;
+use collection::operations::highlight::Highlights;
 use collection::operations::payload_ops::{
     DeletePayload, DeletePayloadOp, PayloadOps, SetPayload, SetPayloadOp,
 };
 use collection::operations::point_ops::{
-    FilterSelector, PointIdsList, PointInsertOperations, PointOperations, PointsSelector,
-    WriteOrdering,
+    ConditionalInsertOperationInternal, DecomposedInsertOperation, FilterSelector, PointIdsList,
+    PointInsertOperations, PointInsertOperationsInternal, PointOperations, PointsSelector,
+    VersionedInsertOperationInternal, WriteOrdering,
 };
 use collection::operations::shard_key_selector::ShardKeySelector;
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::{
     CoreSearchRequest, CoreSearchRequestBatch, CountRequestInternal, CountResult,
-    DiscoverRequestBatch, DiscoverRequestInternal, GroupsResult, PointRequestInternal,
-    RecommendGroupsRequestInternal, Record, ScrollRequestInternal, ScrollResult,
-    SearchGroupsRequestInternal, UpdateResult,
+    DiscoverRequestBatch, DiscoverRequestInternal, FacetRequestInternal, FacetResult, GroupsResult,
+    PointRequestInternal, PointUpsertResult, PointUpsertStatus, RecommendGroupsRequestInternal,
+    Record, ScrollRequestInternal, ScrollResult, SearchGroupsRequestInternal, SearchMatrix,
+    SearchMatrixRequestInternal, UpdateResult, UpdateStatus,
 };
 use collection::operations::vector_ops::{
     DeleteVectors, UpdateVectors, UpdateVectorsOp, VectorOperations,
 };
 use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use collection::shards::shard::ShardId;
+use futures::{Stream, StreamExt};
+use itertools::Itertools;
 use schemars::JsonSchema;
-use segment::types::{PayloadFieldSchema, PayloadKeyType, ScoredPoint};
+use segment::types::{
+    Filter, PayloadFieldSchema, PayloadKeyType, ScoredPoint, WithPayloadInterface,
+};
 use serde::{Deserialize, Serialize};
 use storage::content_manager::collection_meta_ops::{
     CollectionMetaOperations, CreatePayloadIndex, DropPayloadIndex,
@@ -43,49 +53,49 @@ pub struct CreateFieldIndex {
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct UpsertOperation {
     #[validate]
-    upsert: PointInsertOperations,
+    pub upsert: PointInsertOperations,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct DeleteOperation {
     #[validate]
-    delete: PointsSelector,
+    pub delete: PointsSelector,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct SetPayloadOperation {
     #[validate]
-    set_payload: SetPayload,
+    pub set_payload: SetPayload,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct OverwritePayloadOperation {
     #[validate]
-    overwrite_payload: SetPayload,
+    pub overwrite_payload: SetPayload,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct DeletePayloadOperation {
     #[validate]
-    delete_payload: DeletePayload,
+    pub delete_payload: DeletePayload,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct ClearPayloadOperation {
     #[validate]
-    clear_payload: PointsSelector,
+    pub clear_payload: PointsSelector,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct UpdateVectorsOperation {
     #[validate]
-    update_vectors: UpdateVectors,
+    pub update_vectors: UpdateVectors,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct DeleteVectorsOperation {
     #[validate]
-    delete_vectors: DeleteVectors,
+    pub delete_vectors: DeleteVectors,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema)]
@@ -153,6 +163,210 @@ fn get_shard_selector_for_update(
     }
 }
 
+/// Collection operation of an upsert, with the shard key it is addressed to
+fn upsert_operation(
+    operation: PointInsertOperations,
+) -> Result<(CollectionUpdateOperations, Option<ShardKeySelector>), StorageError> {
+    let DecomposedInsertOperation {
+        shard_key,
+        operation,
+        condition,
+        if_version,
+    } = operation.decompose();
+    let point_operation = match (condition, if_version) {
+        (Some(_), Some(_)) => {
+            return Err(StorageError::bad_request(
+                "Upsert can't have both a condition and a version",
+            ));
+        }
+        (Some(condition), None) => {
+            PointOperations::UpsertPointsConditional(ConditionalInsertOperationInternal {
+                points_op: operation,
+                condition,
+            })
+        }
+        (None, Some(if_version)) => {
+            PointOperations::UpsertPointsIfVersion(VersionedInsertOperationInternal {
+                points_op: operation,
+                if_version,
+            })
+        }
+        (None, None) => PointOperations::UpsertPoints(operation),
+    };
+    Ok((
+        CollectionUpdateOperations::PointOperation(point_operation),
+        shard_key,
+    ))
+}
+
+fn delete_points_operation(
+    points: PointsSelector,
+) -> (CollectionUpdateOperations, Option<ShardKeySelector>) {
+    let (point_operation, shard_key) = match points {
+        PointsSelector::PointIdsSelector(PointIdsList { points, shard_key }) => {
+            (PointOperations::DeletePoints { ids: points }, shard_key)
+        }
+        PointsSelector::FilterSelector(FilterSelector { filter, shard_key }) => {
+            (PointOperations::DeletePointsByFilter(filter), shard_key)
+        }
+    };
+    (
+        CollectionUpdateOperations::PointOperation(point_operation),
+        shard_key,
+    )
+}
+
+fn update_vectors_operation(
+    operation: UpdateVectors,
+) -> (CollectionUpdateOperations, Option<ShardKeySelector>) {
+    let UpdateVectors { points, shard_key } = operation;
+    let collection_operation = CollectionUpdateOperations::VectorOperation(
+        VectorOperations::UpdateVectors(UpdateVectorsOp { points }),
+    );
+    (collection_operation, shard_key)
+}
+
+/// Collection operations of a vectors deletion: by filter first, then by point IDs
+fn delete_vectors_operations(
+    operation: DeleteVectors,
+) -> Result<(Vec<CollectionUpdateOperations>, Option<ShardKeySelector>), StorageError> {
+    let DeleteVectors {
+        vector,
+        filter,
+        points,
+        shard_key,
+    } = operation;
+
+    let vector_names: Vec<_> = vector.into_iter().collect();
+
+    let mut collection_operations = Vec::with_capacity(2);
+
+    if let Some(filter) = filter {
+        collection_operations.push(CollectionUpdateOperations::VectorOperation(
+            VectorOperations::DeleteVectorsByFilter(filter, vector_names.clone()),
+        ));
+    }
+
+    if let Some(points) = points {
+        collection_operations.push(CollectionUpdateOperations::VectorOperation(
+            VectorOperations::DeleteVectors(points.into(), vector_names),
+        ));
+    }
+
+    if collection_operations.is_empty() {
+        return Err(StorageError::bad_request("No filter or points provided"));
+    }
+
+    Ok((collection_operations, shard_key))
+}
+
+fn set_payload_operation(
+    operation: SetPayload,
+) -> (CollectionUpdateOperations, Option<ShardKeySelector>) {
+    let SetPayload {
+        points,
+        payload,
+        filter,
+        shard_key,
+        key,
+    } = operation;
+
+    let collection_operation =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayloadOp {
+            payload,
+            points,
+            filter,
+            key,
+        }));
+    (collection_operation, shard_key)
+}
+
+fn overwrite_payload_operation(
+    operation: SetPayload,
+) -> (CollectionUpdateOperations, Option<ShardKeySelector>) {
+    let SetPayload {
+        points,
+        payload,
+        filter,
+        shard_key,
+        key,
+    } = operation;
+
+    let collection_operation =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::OverwritePayload(SetPayloadOp {
+            payload,
+            points,
+            filter,
+            key,
+        }));
+    (collection_operation, shard_key)
+}
+
+fn delete_payload_operation(
+    operation: DeletePayload,
+) -> (CollectionUpdateOperations, Option<ShardKeySelector>) {
+    let DeletePayload {
+        keys,
+        points,
+        filter,
+        shard_key,
+    } = operation;
+
+    let collection_operation =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(DeletePayloadOp {
+            keys,
+            points,
+            filter,
+        }));
+    (collection_operation, shard_key)
+}
+
+fn clear_payload_operation(
+    points: PointsSelector,
+) -> (CollectionUpdateOperations, Option<ShardKeySelector>) {
+    let (payload_operation, shard_key) = match points {
+        PointsSelector::PointIdsSelector(PointIdsList { points, shard_key }) => {
+            (PayloadOps::ClearPayload { points }, shard_key)
+        }
+        PointsSelector::FilterSelector(FilterSelector { filter, shard_key }) => {
+            (PayloadOps::ClearPayloadByFilter(filter), shard_key)
+        }
+    };
+    (
+        CollectionUpdateOperations::PayloadOperation(payload_operation),
+        shard_key,
+    )
+}
+
+impl UpdateOperation {
+    /// Collection operations of this operation, with the shard key they are addressed to
+    fn into_collection_operations(
+        self,
+    ) -> Result<(Vec<CollectionUpdateOperations>, Option<ShardKeySelector>), StorageError> {
+        let (collection_operation, shard_key) = match self {
+            UpdateOperation::Upsert(operation) => upsert_operation(operation.upsert)?,
+            UpdateOperation::Delete(operation) => delete_points_operation(operation.delete),
+            UpdateOperation::SetPayload(operation) => set_payload_operation(operation.set_payload),
+            UpdateOperation::OverwritePayload(operation) => {
+                overwrite_payload_operation(operation.overwrite_payload)
+            }
+            UpdateOperation::DeletePayload(operation) => {
+                delete_payload_operation(operation.delete_payload)
+            }
+            UpdateOperation::ClearPayload(operation) => {
+                clear_payload_operation(operation.clear_payload)
+            }
+            UpdateOperation::UpdateVectors(operation) => {
+                update_vectors_operation(operation.update_vectors)
+            }
+            UpdateOperation::DeleteVectors(operation) => {
+                return delete_vectors_operations(operation.delete_vectors);
+            }
+        };
+        Ok((vec![collection_operation], shard_key))
+    }
+}
+
 pub async fn do_upsert_points(
     toc: &TableOfContent,
     collection_name: &str,
@@ -161,9 +375,7 @@ pub async fn do_upsert_points(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let (shard_key, operation) = operation.decompose();
-    let collection_operation =
-        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(operation));
+    let (collection_operation, shard_key) = upsert_operation(operation)?;
 
     let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
 
@@ -177,23 +389,119 @@ pub async fn do_upsert_points(
     .await
 }
 
-pub async fn do_delete_points(
+/// Upsert points, which match the collection configuration, and report status of each point
+///
+/// Unlike `do_upsert_points`, invalid points do not fail the whole batch, but are reported as
+/// rejected. Valid points are reported as created or updated, depending on whether they existed
+/// before the operation.
+pub async fn do_upsert_points_with_status(
     toc: &TableOfContent,
     collection_name: &str,
-    points: PointsSelector,
+    operation: PointInsertOperations,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let (point_operation, shard_key) = match points {
-        PointsSelector::PointIdsSelector(PointIdsList { points, shard_key }) => {
-            (PointOperations::DeletePoints { ids: points }, shard_key)
-        }
-        PointsSelector::FilterSelector(FilterSelector { filter, shard_key }) => {
-            (PointOperations::DeletePointsByFilter(filter), shard_key)
+    let DecomposedInsertOperation {
+        shard_key,
+        operation,
+        condition,
+        if_version,
+    } = operation.decompose();
+    if condition.is_some() || if_version.is_some() {
+        return Err(StorageError::bad_request(
+            "Conditional upsert can't report status of each point",
+        ));
+    }
+    let points = operation.into_point_vec();
+
+    let rejections = toc
+        .get_collection(collection_name)
+        .await?
+        .check_points(&points)
+        .await;
+
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+
+    // Remember the order of the request, to report statuses in the same order
+    let requested: Vec<_> = points
+        .iter()
+        .map(|point| point.id)
+        .zip(rejections)
+        .collect();
+
+    let valid_points: Vec<_> = points
+        .into_iter()
+        .zip(&requested)
+        .filter(|(_, (_, rejection))| rejection.is_none())
+        .map(|(point, _)| point)
+        .collect();
+
+    let existing_ids: HashSet<_> = if valid_points.is_empty() {
+        HashSet::new()
+    } else {
+        let request = PointRequestInternal {
+            ids: valid_points.iter().map(|point| point.id).collect(),
+            with_payload: Some(WithPayloadInterface::Bool(false)),
+            with_vector: false.into(),
+        };
+        toc.retrieve(collection_name, request, None, shard_selector.clone())
+            .await?
+            .into_iter()
+            .map(|record| record.id)
+            .collect()
+    };
+
+    let mut result = if valid_points.is_empty() {
+        UpdateResult {
+            operation_id: None,
+            status: UpdateStatus::Completed,
+            points: None,
         }
+    } else {
+        let collection_operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(PointInsertOperationsInternal::PointsList(valid_points)),
+        );
+        toc.update(
+            collection_name,
+            collection_operation,
+            wait,
+            ordering,
+            shard_selector,
+        )
+        .await?
     };
-    let collection_operation = CollectionUpdateOperations::PointOperation(point_operation);
+
+    let statuses = requested
+        .into_iter()
+        .map(|(id, rejection)| {
+            let status = match (&rejection, existing_ids.contains(&id)) {
+                (Some(_), _) => PointUpsertStatus::Rejected,
+                (None, true) => PointUpsertStatus::Updated,
+                (None, false) => PointUpsertStatus::Created,
+            };
+            PointUpsertResult {
+                id,
+                status,
+                reason: rejection,
+            }
+        })
+        .collect();
+
+    result.points = Some(statuses);
+    Ok(result)
+}
+
+pub async fn do_delete_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    points: PointsSelector,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let (collection_operation, shard_key) = delete_points_operation(points);
+
     let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
 
     toc.update(
@@ -214,11 +522,7 @@ pub async fn do_update_vectors(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let UpdateVectors { points, shard_key } = operation;
-
-    let collection_operation = CollectionUpdateOperations::VectorOperation(
-        VectorOperations::UpdateVectors(UpdateVectorsOp { points }),
-    );
+    let (collection_operation, shard_key) = update_vectors_operation(operation);
 
     let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
 
@@ -240,23 +544,12 @@ pub async fn do_delete_vectors(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let DeleteVectors {
-        vector,
-        filter,
-        points,
-        shard_key,
-    } = operation;
-
-    let vector_names: Vec<_> = vector.into_iter().collect();
-
-    let mut result = None;
+    let (collection_operations, shard_key) = delete_vectors_operations(operation)?;
 
     let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
 
-    if let Some(filter) = filter {
-        let vectors_operation =
-            VectorOperations::DeleteVectorsByFilter(filter, vector_names.clone());
-        let collection_operation = CollectionUpdateOperations::VectorOperation(vectors_operation);
+    let mut result = None;
+    for collection_operation in collection_operations {
         result = Some(
             toc.update(
                 collection_name,
@@ -269,21 +562,6 @@ pub async fn do_delete_vectors(
         );
     }
 
-    if let Some(points) = points {
-        let vectors_operation = VectorOperations::DeleteVectors(points.into(), vector_names);
-        let collection_operation = CollectionUpdateOperations::VectorOperation(vectors_operation);
-        result = Some(
-            toc.update(
-                collection_name,
-                collection_operation,
-                wait,
-                ordering,
-                shard_selector,
-            )
-            .await?,
-        );
-    }
-
     result.ok_or_else(|| StorageError::bad_request("No filter or points provided"))
 }
 
@@ -295,19 +573,7 @@ pub async fn do_set_payload(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let SetPayload {
-        points,
-        payload,
-        filter,
-        shard_key,
-    } = operation;
-
-    let collection_operation =
-        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayloadOp {
-            payload,
-            points,
-            filter,
-        }));
+    let (collection_operation, shard_key) = set_payload_operation(operation);
 
     let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
 
@@ -329,19 +595,7 @@ pub async fn do_overwrite_payload(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let SetPayload {
-        points,
-        payload,
-        filter,
-        shard_key,
-    } = operation;
-
-    let collection_operation =
-        CollectionUpdateOperations::PayloadOperation(PayloadOps::OverwritePayload(SetPayloadOp {
-            payload,
-            points,
-            filter,
-        }));
+    let (collection_operation, shard_key) = overwrite_payload_operation(operation);
 
     let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
 
@@ -363,19 +617,7 @@ pub async fn do_delete_payload(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let DeletePayload {
-        keys,
-        points,
-        filter,
-        shard_key,
-    } = operation;
-
-    let collection_operation =
-        CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(DeletePayloadOp {
-            keys,
-            points,
-            filter,
-        }));
+    let (collection_operation, shard_key) = delete_payload_operation(operation);
 
     let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
 
@@ -397,16 +639,7 @@ pub async fn do_clear_payload(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let (point_operation, shard_key) = match points {
-        PointsSelector::PointIdsSelector(PointIdsList { points, shard_key }) => {
-            (PayloadOps::ClearPayload { points }, shard_key)
-        }
-        PointsSelector::FilterSelector(FilterSelector { filter, shard_key }) => {
-            (PayloadOps::ClearPayloadByFilter(filter), shard_key)
-        }
-    };
-
-    let collection_operation = CollectionUpdateOperations::PayloadOperation(point_operation);
+    let (collection_operation, shard_key) = clear_payload_operation(points);
 
     let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
 
@@ -420,6 +653,47 @@ pub async fn do_clear_payload(
     .await
 }
 
+/// Apply operations in order, as a single operation written to the WAL of each shard
+///
+/// Either all operations of the batch are accepted, or none of them. All operations must be
+/// addressed to the same shard key.
+pub async fn do_update_batch(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operations: Vec<UpdateOperation>,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let mut collection_operations = Vec::with_capacity(operations.len());
+    let mut batch_shard_key = None;
+    for (index, operation) in operations.into_iter().enumerate() {
+        let (operations, shard_key) = operation.into_collection_operations()?;
+        if index == 0 {
+            batch_shard_key = shard_key;
+        } else if shard_key != batch_shard_key {
+            return Err(StorageError::bad_request(
+                "All operations of a batch must have the same shard key",
+            ));
+        }
+        collection_operations.extend(operations);
+    }
+
+    let shard_selector = get_shard_selector_for_update(shard_selection, batch_shard_key);
+
+    toc.update(
+        collection_name,
+        CollectionUpdateOperations::BatchOperation(collection_operations),
+        wait,
+        ordering,
+        shard_selector,
+    )
+    .await
+}
+
+/// Apply operations in order, as a single operation, see `do_update_batch`
+///
+/// Returns the result of the batch once for each operation.
 pub async fn do_batch_update_points(
     toc: &TableOfContent,
     collection_name: &str,
@@ -428,100 +702,32 @@ pub async fn do_batch_update_points(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<Vec<UpdateResult>, StorageError> {
-    let mut results = Vec::with_capacity(operations.len());
-    for operation in operations {
-        let result = match operation {
-            UpdateOperation::Upsert(operation) => {
-                do_upsert_points(
-                    toc,
-                    collection_name,
-                    operation.upsert,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
-            }
-            UpdateOperation::Delete(operation) => {
-                do_delete_points(
-                    toc,
-                    collection_name,
-                    operation.delete,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
-            }
-            UpdateOperation::SetPayload(operation) => {
-                do_set_payload(
-                    toc,
-                    collection_name,
-                    operation.set_payload,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
-            }
-            UpdateOperation::OverwritePayload(operation) => {
-                do_overwrite_payload(
-                    toc,
-                    collection_name,
-                    operation.overwrite_payload,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
-            }
-            UpdateOperation::DeletePayload(operation) => {
-                do_delete_payload(
-                    toc,
-                    collection_name,
-                    operation.delete_payload,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
-            }
-            UpdateOperation::ClearPayload(operation) => {
-                do_clear_payload(
-                    toc,
-                    collection_name,
-                    operation.clear_payload,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
-            }
-            UpdateOperation::UpdateVectors(operation) => {
-                do_update_vectors(
-                    toc,
-                    collection_name,
-                    operation.update_vectors,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
-            }
-            UpdateOperation::DeleteVectors(operation) => {
-                do_delete_vectors(
-                    toc,
-                    collection_name,
-                    operation.delete_vectors,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
-            }
-        }?;
-        results.push(result);
+    if operations.is_empty() {
+        return Ok(Vec::new());
     }
+
+    let operations_count = operations.len();
+    let UpdateResult {
+        operation_id,
+        status,
+        points: _,
+    } = do_update_batch(
+        toc,
+        collection_name,
+        operations,
+        shard_selection,
+        wait,
+        ordering,
+    )
+    .await?;
+
+    let results = (0..operations_count)
+        .map(|_| UpdateResult {
+            operation_id,
+            status,
+            points: None,
+        })
+        .collect();
     Ok(results)
 }
 
@@ -747,6 +953,19 @@ pub async fn do_core_search_batch_points(
     .await
 }
 
+pub async fn do_highlight_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    filter: Option<&Filter>,
+    points: &[ScoredPoint],
+) -> Result<Vec<Highlights>, StorageError> {
+    let Some(filter) = filter else {
+        return Ok(vec![Highlights::new(); points.len()]);
+    };
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.highlight(filter, points))
+}
+
 pub async fn do_search_point_groups(
     toc: &TableOfContent,
     collection_name: &str,
@@ -809,7 +1028,7 @@ pub async fn do_discover_batch_points(
     timeout: Option<Duration>,
 ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
     let requests = request
-        .searches
+        .into_searches()
         .into_iter()
         .map(|req| {
             let shard_selector = match req.shard_key {
@@ -836,6 +1055,35 @@ pub async fn do_count_points(
         .await
 }
 
+pub async fn do_facet_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: FacetRequestInternal,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: ShardSelectorInternal,
+) -> Result<FacetResult, StorageError> {
+    toc.facet(collection_name, request, read_consistency, shard_selection)
+        .await
+}
+
+pub async fn do_search_points_matrix(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: SearchMatrixRequestInternal,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: ShardSelectorInternal,
+    timeout: Option<Duration>,
+) -> Result<SearchMatrix, StorageError> {
+    toc.search_points_matrix(
+        collection_name,
+        request,
+        read_consistency,
+        shard_selection,
+        timeout,
+    )
+    .await
+}
+
 pub async fn do_get_points(
     toc: &TableOfContent,
     collection_name: &str,
@@ -847,6 +1095,69 @@ pub async fn do_get_points(
         .await
 }
 
+/// Number of points, retrieved from the collection at once when streaming
+const STREAM_BATCH_SIZE: usize = 1024;
+
+/// Retrieve points by ID as a stream of JSON lines, one point per line
+///
+/// IDs are sorted and retrieved in batches, so large lists of IDs are read in storage order and
+/// only one batch of points is kept in memory. Points are returned in the order of their IDs,
+/// missing points are skipped.
+///
+/// Collection is checked before the stream is returned, errors while reading points are
+/// reported by the stream.
+pub async fn do_stream_points(
+    toc: Arc<TableOfContent>,
+    collection_name: String,
+    request: PointRequestInternal,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: ShardSelectorInternal,
+) -> Result<impl Stream<Item = Result<Bytes, StorageError>>, StorageError> {
+    toc.get_collection(&collection_name).await?;
+
+    let PointRequestInternal {
+        ids,
+        with_payload,
+        with_vector,
+    } = request;
+    let batches: Vec<_> = ids
+        .into_iter()
+        .sorted_unstable()
+        .dedup()
+        .chunks(STREAM_BATCH_SIZE)
+        .into_iter()
+        .map(Iterator::collect)
+        .collect();
+
+    let stream = futures::stream::iter(batches).then(move |ids: Vec<_>| {
+        let toc = toc.clone();
+        let collection_name = collection_name.clone();
+        let request = PointRequestInternal {
+            ids,
+            with_payload: with_payload.clone(),
+            with_vector: with_vector.clone(),
+        };
+        let shard_selection = shard_selection.clone();
+        async move {
+            let mut records = toc
+                .retrieve(&collection_name, request, read_consistency, shard_selection)
+                .await?;
+            records.sort_unstable_by_key(|record| record.id);
+
+            let mut lines = Vec::new();
+            for record in records {
+                serde_json::to_writer(&mut lines, &record).map_err(|err| {
+                    StorageError::service_error(format!("Can't serialize point: {err}"))
+                })?;
+                lines.push(b'\n');
+            }
+            Ok(Bytes::from(lines))
+        }
+    });
+
+    Ok(stream)
+}
+
 pub async fn do_scroll_points(
     toc: &TableOfContent,
     collection_name: &str,
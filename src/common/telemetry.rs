@@ -13,6 +13,7 @@ use crate::common::telemetry_ops::collections_telemetry::CollectionsTelemetry;
 use crate::common::telemetry_ops::requests_telemetry::{
     ActixTelemetryCollector, RequestsTelemetry, TonicTelemetryCollector,
 };
+use crate::common::usage_metering::UsageMeter;
 use crate::settings::Settings;
 
 pub struct TelemetryCollector {
@@ -22,6 +23,8 @@ pub struct TelemetryCollector {
     pub app_telemetry_collector: AppBuildTelemetryCollector,
     pub actix_telemetry_collector: Arc<Mutex<ActixTelemetryCollector>>,
     pub tonic_telemetry_collector: Arc<Mutex<TonicTelemetryCollector>>,
+    /// None if usage metering is disabled
+    pub usage_meter: Option<Arc<UsageMeter>>,
 }
 
 // Whole telemetry data
@@ -56,6 +59,7 @@ impl TelemetryCollector {
     }
 
     pub fn new(settings: Settings, dispatcher: Arc<Dispatcher>, id: Uuid) -> Self {
+        let usage_meter = UsageMeter::try_create(&settings).map(Arc::new);
         Self {
             process_id: id,
             settings,
@@ -67,6 +71,7 @@ impl TelemetryCollector {
             tonic_telemetry_collector: Arc::new(Mutex::new(TonicTelemetryCollector {
                 workers: Vec::new(),
             })),
+            usage_meter,
         }
     }
 
@@ -0,0 +1,268 @@
+//! Usage of the service per API key, e.g. for chargeback of a cluster shared by teams
+//!
+//! Requests are attributed to the name of the key they were made with, see
+//! [`AuthKeys::key_name`], and to the collection they are addressed to. Requests without a valid
+//! key, e.g. if authentication is disabled, are attributed to `anonymous`.
+//!
+//! Counters are kept in memory since the start of the peer, like telemetry. Reports are served
+//! at `/usage` and optionally appended to a file, so an external system can bill the difference
+//! between consecutive reports.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use collection::operations::shard_selector_internal::ShardSelectorInternal;
+use collection::operations::types::CountRequestInternal;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use segment::types::ShardKey;
+use serde::{Deserialize, Serialize};
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::toc::TableOfContent;
+use tokio::io::AsyncWriteExt;
+
+use crate::common::auth::AuthKeys;
+use crate::settings::Settings;
+
+/// Name of the requests without a valid API key
+const ANONYMOUS: &str = "anonymous";
+
+/// Origin of a request, usage is aggregated by it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UsageKey {
+    pub tenant: String,
+    pub collection: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct RequestUsage {
+    pub requests: u64,
+    /// Size of request bodies
+    pub bytes_read: u64,
+    /// Size of response bodies
+    pub bytes_written: u64,
+    /// Time from receiving a request until its response is ready
+    pub processing_time_micros: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct TenantUsage {
+    /// Name of the API key
+    pub tenant: String,
+    /// Collection of the requests. Requests, not addressed to a single collection, have none.
+    pub collection: Option<String>,
+    #[serde(flatten)]
+    pub usage: RequestUsage,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct StorageUsage {
+    pub collection: String,
+    /// Shard key of the points, none for collections without custom sharding
+    pub shard_key: Option<ShardKey>,
+    /// Approximate number of stored points
+    pub points_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct UsageReport {
+    /// Unix time of the report in seconds
+    pub timestamp: u64,
+    /// Requests since the start of the peer
+    pub tenants: Vec<TenantUsage>,
+    /// Points stored on the cluster
+    pub storage: Vec<StorageUsage>,
+}
+
+pub struct UsageMeter {
+    auth_keys: Option<AuthKeys>,
+    usage: Mutex<BTreeMap<UsageKey, RequestUsage>>,
+}
+
+impl UsageMeter {
+    /// Returns None if usage metering is disabled
+    pub fn try_create(settings: &Settings) -> Option<Self> {
+        settings.usage_metering.enabled.then(|| Self {
+            auth_keys: AuthKeys::try_create(&settings.service),
+            usage: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Key to account a request made with the given API key to
+    pub fn usage_key(&self, api_key: Option<&str>, collection: Option<String>) -> UsageKey {
+        let tenant = api_key
+            .zip(self.auth_keys.as_ref())
+            .and_then(|(api_key, auth_keys)| auth_keys.key_name(api_key))
+            .unwrap_or_else(|| ANONYMOUS.to_string());
+        UsageKey { tenant, collection }
+    }
+
+    pub fn add_request(
+        &self,
+        key: UsageKey,
+        bytes_read: u64,
+        bytes_written: u64,
+        processing_time: Duration,
+    ) {
+        let mut usage = self.usage.lock();
+        let usage = usage.entry(key).or_default();
+        usage.requests += 1;
+        usage.bytes_read += bytes_read;
+        usage.bytes_written += bytes_written;
+        usage.processing_time_micros += processing_time.as_micros() as u64;
+    }
+
+    /// Account a part of a response, which is streamed after the request was added
+    pub fn add_bytes_written(&self, key: &UsageKey, bytes_written: u64) {
+        if let Some(usage) = self.usage.lock().get_mut(key) {
+            usage.bytes_written += bytes_written;
+        }
+    }
+
+    fn tenants(&self) -> Vec<TenantUsage> {
+        self.usage
+            .lock()
+            .iter()
+            .map(|(key, usage)| TenantUsage {
+                tenant: key.tenant.clone(),
+                collection: key.collection.clone(),
+                usage: *usage,
+            })
+            .collect()
+    }
+
+    pub async fn report(&self, toc: &TableOfContent) -> UsageReport {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut collection_names = toc.all_collections().await;
+        collection_names.sort_unstable();
+        let mut storage = Vec::new();
+        for collection_name in collection_names {
+            match storage_usage(toc, &collection_name).await {
+                Ok(usage) => storage.extend(usage),
+                Err(err) => log::warn!("Can't count points of {collection_name} for usage: {err}"),
+            }
+        }
+
+        UsageReport {
+            timestamp,
+            tenants: self.tenants(),
+            storage,
+        }
+    }
+
+    /// Periodically append usage reports to a file, one JSON line per report
+    pub async fn run_export(
+        meter: Arc<Self>,
+        toc: Arc<TableOfContent>,
+        path: String,
+        interval: Duration,
+    ) {
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let report = meter.report(&toc).await;
+            let mut line = serde_json::to_vec(&report).unwrap();
+            line.push(b'\n');
+            if let Err(err) = append(&path, &line).await {
+                log::error!("Failed to export usage report to {path}: {err}");
+            }
+        }
+    }
+}
+
+async fn append(path: &str, line: &[u8]) -> std::io::Result<()> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(line).await?;
+    file.flush().await
+}
+
+/// Number of points per shard key, or of the whole collection without custom sharding
+async fn storage_usage(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<Vec<StorageUsage>, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    let mut shard_keys: Vec<_> = collection
+        .get_shard_keys()
+        .await
+        .into_iter()
+        .map(Some)
+        .collect();
+    if shard_keys.is_empty() {
+        shard_keys.push(None);
+    }
+    shard_keys.sort_unstable_by_key(|shard_key| shard_key.as_ref().map(ToString::to_string));
+
+    let mut usage = Vec::with_capacity(shard_keys.len());
+    for shard_key in shard_keys {
+        let request = CountRequestInternal {
+            filter: None,
+            exact: false,
+        };
+        let count = collection
+            .count(
+                request,
+                None,
+                &ShardSelectorInternal::from(shard_key.clone()),
+            )
+            .await?;
+        usage.push(StorageUsage {
+            collection: collection_name.to_string(),
+            shard_key,
+            points_count: count.count,
+        });
+    }
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ApiKeyConfig;
+
+    #[test]
+    fn test_add_request() {
+        let mut settings = Settings::new(None).unwrap();
+        settings.usage_metering.enabled = true;
+        settings.service.api_key = Some("admin".to_string());
+        settings.service.api_keys = vec![ApiKeyConfig {
+            key: "secret".to_string(),
+            name: Some("team_a".to_string()),
+            read_only: false,
+            collections: None,
+        }];
+        let meter = UsageMeter::try_create(&settings).unwrap();
+
+        let team_a = meter.usage_key(Some("secret"), Some("products".to_string()));
+        meter.add_request(team_a.clone(), 100, 10, Duration::from_micros(5));
+        meter.add_request(team_a.clone(), 50, 0, Duration::from_micros(5));
+        meter.add_bytes_written(&team_a, 20);
+        let admin = meter.usage_key(Some("admin"), None);
+        meter.add_request(admin, 0, 0, Duration::ZERO);
+        let anonymous = meter.usage_key(None, None);
+        meter.add_request(anonymous, 0, 0, Duration::ZERO);
+
+        let tenants = meter.tenants();
+        let names: Vec<_> = tenants.iter().map(|usage| usage.tenant.as_str()).collect();
+        assert_eq!(names, ["anonymous", "api_key", "team_a"]);
+        assert_eq!(tenants[2].collection.as_deref(), Some("products"));
+        assert_eq!(
+            tenants[2].usage,
+            RequestUsage {
+                requests: 2,
+                bytes_read: 150,
+                bytes_written: 30,
+                processing_time_micros: 10,
+            },
+        );
+    }
+}
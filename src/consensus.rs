@@ -26,8 +26,8 @@ use tokio::sync::watch;
 use tokio::time::sleep;
 use tonic::transport::{ClientTlsConfig, Uri};
 
-use crate::common::helpers;
 use crate::common::telemetry_ops::requests_telemetry::TonicTelemetryCollector;
+use crate::common::{certificate_helpers, helpers};
 use crate::settings::{ConsensusConfig, Settings};
 use crate::tonic::init_internal;
 
@@ -118,12 +118,9 @@ impl Consensus {
             })?;
 
         let server_tls = if settings.cluster.p2p.enable_tls {
-            let tls_config = settings
-                .tls
-                .clone()
-                .ok_or_else(Settings::tls_config_is_undefined_error)?;
-
-            Some(helpers::load_tls_internal_server_config(&tls_config)?)
+            Some(certificate_helpers::tonic_tls_server_config(
+                &settings, true,
+            )?)
         } else {
             None
         };
@@ -294,6 +291,7 @@ impl Consensus {
             Duration::from_secs(config.bootstrap_timeout_sec),
             cluster_uri,
             tls_config,
+            None,
         )
         .await
         .map_err(|err| anyhow!("Failed to create timeout channel: {}", err))?;
@@ -1092,6 +1090,7 @@ impl RaftMessageSender {
             bootstrap_timeout,
             bootstrap_uri,
             self.tls_config.clone(),
+            None,
         )
         .await
         .map_err(|err| anyhow::format_err!("Failed to create who-is channel: {}", err))?;
@@ -1237,6 +1236,10 @@ mod tests {
                             init_from: None,
                             quantization_config: None,
                             sharding_method: None,
+                            metadata: None,
+                            strict_mode_config: None,
+                            rate_limit: None,
+                            query_expansion: None,
                         },
                     )),
                     None,
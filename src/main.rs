@@ -19,6 +19,8 @@ use std::thread::JoinHandle;
 use std::time::Duration;
 
 use ::tonic::transport::Uri;
+use anyhow::Context as _;
+use api::grpc::dynamic_channel_pool::KeepAliveConfig;
 use api::grpc::transport_channel_pool::TransportChannelPool;
 use clap::Parser;
 use collection::shards::channel_service::ChannelService;
@@ -40,6 +42,8 @@ use crate::common::helpers::{
 };
 use crate::common::telemetry::TelemetryCollector;
 use crate::common::telemetry_reporting::TelemetryReporter;
+use crate::common::usage_metering::UsageMeter;
+use crate::common::{manifest, peer_discovery};
 use crate::greeting::welcome;
 use crate::migrations::single_to_cluster::handle_existing_collections;
 use crate::settings::Settings;
@@ -93,6 +97,12 @@ struct Args {
     #[arg(long, value_name = "PATH")]
     storage_snapshot: Option<String>,
 
+    /// Path to a JSON manifest with collections, payload indexes and aliases.
+    /// They are created at the first start of the deployment, by the peer which starts the cluster.
+    /// Format: <manifest_file_path>
+    #[arg(long, value_name = "PATH")]
+    manifest: Option<String>,
+
     /// Path to an alternative configuration file.
     /// Format: <config_file_path>
     ///
@@ -112,7 +122,7 @@ struct Args {
 }
 
 fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     // Run backtrace collector, expected to used by `rstack` crate
     if args.stacktrace {
@@ -137,6 +147,9 @@ fn main() -> anyhow::Result<()> {
 
     memory::madvise::set_global(settings.storage.mmap_advice);
     segment::vector_storage::common::set_async_scorer(settings.storage.async_scorer);
+    segment::vector_storage::common::set_verify_checksums(
+        settings.storage.performance.verify_vector_checksums,
+    );
 
     welcome(&settings);
 
@@ -150,6 +163,39 @@ fn main() -> anyhow::Result<()> {
     // Validate as soon as possible, but we must initialize logging first
     settings.validate_and_warn();
 
+    // Read the manifest early, to fail before anything is initialized
+    let cluster_manifest = args
+        .manifest
+        .as_deref()
+        .map(manifest::load_manifest)
+        .transpose()?;
+
+    // Find a peer to bootstrap from through DNS, unless this peer is already part of a cluster
+    if let Some(discovery) = &settings.cluster.discovery {
+        if settings.cluster.enabled
+            && args.bootstrap.is_none()
+            && !Persistent::state_exists(&settings.storage.storage_path)
+        {
+            let p2p_port = settings.cluster.p2p.port.expect("P2P port is not set");
+            let enable_tls = settings.cluster.p2p.enable_tls;
+            let this_peer_uri = match args.uri.take() {
+                Some(uri) => uri,
+                None => peer_discovery::this_peer_uri(discovery, p2p_port, enable_tls)?,
+            };
+            let discovery_runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            args.bootstrap =
+                discovery_runtime.block_on(peer_discovery::discover_bootstrap_peer(
+                    discovery,
+                    &this_peer_uri,
+                    p2p_port,
+                    enable_tls,
+                ))?;
+            args.uri = Some(this_peer_uri);
+        }
+    }
+
     // Saved state of the consensus.
     let persistent_consensus_state =
         Persistent::load_or_init(&settings.storage.storage_path, args.bootstrap.is_none())?;
@@ -217,12 +263,25 @@ fn main() -> anyhow::Result<()> {
 
         let tls_config = load_tls_client_config(&settings)?;
 
-        channel_service.channel_pool = Arc::new(TransportChannelPool::new(
+        let p2p = &settings.cluster.p2p;
+        let mut channel_pool = TransportChannelPool::new(
             p2p_grpc_timeout,
             connection_timeout,
-            settings.cluster.p2p.connection_pool_size,
+            p2p.connection_pool_size,
             tls_config,
-        ));
+        )
+        .with_retry_policy(p2p.retries, Duration::from_millis(p2p.retry_backoff_ms))
+        .with_compression(p2p.compression.encoding());
+
+        if let Some(keep_alive_interval_ms) = p2p.keep_alive_interval_ms {
+            channel_pool = channel_pool.with_keep_alive(KeepAliveConfig {
+                interval: Duration::from_millis(keep_alive_interval_ms),
+                timeout: Duration::from_millis(p2p.keep_alive_timeout_ms),
+                while_idle: true,
+            });
+        }
+
+        channel_service.channel_pool = Arc::new(channel_pool);
         channel_service.id_to_address = persistent_consensus_state.peer_address_by_id.clone();
     }
 
@@ -266,6 +325,8 @@ fn main() -> anyhow::Result<()> {
         )
         .into();
         let is_new_deployment = consensus_state.is_new_deployment();
+        // Only the peer, which starts a new cluster, applies the manifest
+        let apply_manifest = is_new_deployment && args.bootstrap.is_none();
 
         dispatcher = dispatcher.with_consensus(consensus_state.clone());
 
@@ -279,6 +340,7 @@ fn main() -> anyhow::Result<()> {
         let telemetry_collector =
             TelemetryCollector::new(settings.clone(), dispatcher_arc.clone(), reporting_id);
         let tonic_telemetry_collector = telemetry_collector.tonic_telemetry_collector.clone();
+        let usage_meter = telemetry_collector.usage_meter.clone();
 
         // `raft` crate uses `slog` crate so it is needed to use `slog_stdlog::StdLog` to forward
         // logs from it to `log` crate
@@ -342,11 +404,35 @@ fn main() -> anyhow::Result<()> {
             ));
         }
 
+        if let Some(cluster_manifest) = cluster_manifest.filter(|_| apply_manifest) {
+            let dispatcher_arc = dispatcher_arc.clone();
+            let consensus_state = consensus_state.clone();
+            runtime_handle.spawn(async move {
+                consensus_state.is_leader_established.await_ready();
+                if let Err(err) = manifest::apply_manifest(&dispatcher_arc, cluster_manifest).await
+                {
+                    log::error!("Failed to apply manifest: {err}");
+                }
+            });
+        }
+
         (telemetry_collector, dispatcher_arc, Some(health_checker))
     } else {
         log::info!("Distributed mode disabled");
         let dispatcher_arc = Arc::new(dispatcher);
 
+        // Storage without collections is considered to be started for the first time
+        if let Some(cluster_manifest) = cluster_manifest {
+            if runtime_handle
+                .block_on(toc_arc.all_collections())
+                .is_empty()
+            {
+                runtime_handle
+                    .block_on(manifest::apply_manifest(&dispatcher_arc, cluster_manifest))
+                    .context("Failed to apply manifest")?;
+            }
+        }
+
         // Monitoring and telemetry.
         let telemetry_collector =
             TelemetryCollector::new(settings.clone(), dispatcher_arc.clone(), reporting_id);
@@ -370,6 +456,40 @@ fn main() -> anyhow::Result<()> {
         log::info!("Telemetry reporting disabled");
     }
 
+    //
+    // Usage metering
+    //
+
+    if let Some(usage_meter) = &usage_meter {
+        log::info!("Usage metering enabled");
+
+        if let Some(export_path) = settings.usage_metering.export_path.clone() {
+            runtime_handle.spawn(UsageMeter::run_export(
+                usage_meter.clone(),
+                toc_arc.clone(),
+                export_path,
+                Duration::from_secs(settings.usage_metering.export_interval_sec),
+            ));
+        }
+    }
+
+    //
+    // Streaming ingestion
+    //
+
+    #[cfg(feature = "kafka")]
+    common::kafka_ingestion::spawn_kafka_ingestion(
+        toc_arc.clone(),
+        &settings.ingestion.kafka,
+        &runtime_handle,
+    );
+    #[cfg(not(feature = "kafka"))]
+    if !settings.ingestion.kafka.is_empty() {
+        log::warn!(
+            "Kafka ingestion is configured, but Qdrant is built without the `kafka` feature"
+        );
+    }
+
     // Helper to better log start errors
     let log_err_if_any = |server_name, result| match result {
         Err(err) => {
@@ -418,6 +538,7 @@ fn main() -> anyhow::Result<()> {
                     tonic::init(
                         dispatcher_arc,
                         tonic_telemetry_collector,
+                        usage_meter,
                         settings,
                         grpc_port,
                         runtime_handle,
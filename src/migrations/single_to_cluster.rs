@@ -54,6 +54,10 @@ pub async fn handle_existing_collections(
                 optimizers_config: Some(collection_state.config.optimizer_config.into()),
                 init_from: None,
                 quantization_config: collection_state.config.quantization_config,
+                metadata: collection_state.config.metadata,
+                strict_mode_config: collection_state.config.strict_mode_config,
+                rate_limit: collection_state.config.rate_limit,
+                query_expansion: collection_state.config.query_expansion,
             },
         );
 
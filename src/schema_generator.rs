@@ -2,16 +2,21 @@ use api::grpc::models::CollectionsResponse;
 use collection::operations::cluster_ops::ClusterOperations;
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::payload_ops::{DeletePayload, SetPayload};
-use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
+use collection::operations::point_ops::{
+    CopyPoints, CopyPointsResult, PointInsertOperations, PointsSelector, WriteOrdering,
+};
 use collection::operations::snapshot_ops::{
-    ShardSnapshotRecover, SnapshotDescription, SnapshotRecover,
+    ShardSnapshotRecover, SnapshotDescription, SnapshotRecover, SnapshotViewRequest,
 };
 use collection::operations::types::{
-    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
-    CountRequest, CountResult, DiscoverRequest, DiscoverRequestBatch, GroupsResult, PointGroup,
-    PointRequest, RecommendGroupsRequest, RecommendRequest, RecommendRequestBatch, Record,
-    ScrollRequest, ScrollResult, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
-    UpdateResult,
+    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionShardsDistribution,
+    CollectionStatistics, CollectionVectorsInfo, CollectionsAliasesResponse, CountRequest,
+    CountResult, DiscoverRequest, DiscoverRequestBatch, ExportPointsRequest, ExportPointsResult,
+    FacetRequest, FacetResult, GroupsResult, PointGroup, PointRequest, QuantizationEstimate,
+    QuantizationEstimateRequest, RecommendGroupsRequest, RecommendRequest, RecommendRequestBatch,
+    Record, ScrollRequest, ScrollResult, SearchGroupsRequest, SearchMatrixOffsetsResponse,
+    SearchMatrixPairsResponse, SearchMatrixRequest, SearchRequest, SearchRequestBatch,
+    SearchResultPoint, UpdateResult,
 };
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
 use schemars::gen::SchemaSettings;
@@ -19,13 +24,15 @@ use schemars::JsonSchema;
 use segment::types::ScoredPoint;
 use serde::{Deserialize, Serialize};
 use storage::content_manager::collection_meta_ops::{
-    ChangeAliasesOperation, CreateCollection, UpdateCollection,
+    ChangeAliasesOperation, CloneCollection, ClusterLocks, CreateCollection, UpdateCollection,
 };
-use storage::types::ClusterStatus;
+use storage::types::{ClusterStatus, PeerMigration, PeerUriUpdate};
 
 use crate::common::helpers::LocksOption;
+use crate::common::import::{ImportPoints, ImportResult};
 use crate::common::points::{CreateFieldIndex, UpdateOperations};
 use crate::common::telemetry::TelemetryData;
+use crate::common::usage_metering::UsageReport;
 
 mod actix;
 mod common;
@@ -78,6 +85,29 @@ struct AllDefinitions {
     b9: ShardSnapshotRecover,
     ba: DiscoverRequest,
     bb: DiscoverRequestBatch,
+    bc: CollectionShardsDistribution,
+    bd: FacetRequest,
+    be: FacetResult,
+    bf: SearchMatrixRequest,
+    bg: SearchMatrixPairsResponse,
+    bh: SearchMatrixOffsetsResponse,
+    bi: CollectionStatistics,
+    bj: ClusterLocks,
+    bk: SearchResultPoint,
+    bl: SnapshotViewRequest,
+    bm: CopyPoints,
+    bn: CopyPointsResult,
+    bo: ImportPoints,
+    bp: ImportResult,
+    bq: CollectionVectorsInfo,
+    br: QuantizationEstimateRequest,
+    bs: QuantizationEstimate,
+    bt: ExportPointsRequest,
+    bu: ExportPointsResult,
+    bv: PeerUriUpdate,
+    bw: PeerMigration,
+    bx: UsageReport,
+    by: CloneCollection,
 }
 
 fn save_schema<T: JsonSchema>() {
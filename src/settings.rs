@@ -1,13 +1,16 @@
 use std::{env, io};
 
 use api::grpc::transport_channel_pool::{
-    DEFAULT_CONNECT_TIMEOUT, DEFAULT_GRPC_TIMEOUT, DEFAULT_POOL_SIZE,
+    DEFAULT_BACKOFF, DEFAULT_CONNECT_TIMEOUT, DEFAULT_GRPC_TIMEOUT, DEFAULT_POOL_SIZE,
+    DEFAULT_RETRIES,
 };
 use collection::operations::validation;
 use config::{Config, ConfigError, Environment, File, FileFormat, Source};
 use segment::common::cpu::get_num_cpus;
 use serde::Deserialize;
 use storage::types::StorageConfig;
+use tonic::codec::CompressionEncoding;
+use url::Url;
 use validator::Validate;
 
 const DEFAULT_CONFIG: &str = include_str!("../config/config.yaml");
@@ -19,15 +22,30 @@ pub struct ServiceConfig {
     pub http_port: u16,
     pub grpc_port: Option<u16>, // None means that gRPC is disabled
     pub max_request_size_mb: usize,
+    /// Overrides of `max_request_size_mb` for specific kinds of requests
+    #[serde(default)]
+    pub request_size_limits: RequestSizeLimitsConfig,
     pub max_workers: Option<usize>,
     #[serde(default = "default_cors")]
     pub enable_cors: bool,
     #[serde(default)]
     pub enable_tls: bool,
+    /// Compression of gRPC responses, which is used if the client accepts it
+    #[serde(default = "default_grpc_compression")]
+    pub grpc_compression: GrpcCompression,
     #[serde(default)]
     pub verify_https_client_certificate: bool,
     pub api_key: Option<String>,
     pub read_only_api_key: Option<String>,
+    /// Additional API keys with restricted access
+    #[serde(default)]
+    pub api_keys: Vec<ApiKeyConfig>,
+    /// Keys to validate JWT tokens, which are accepted as api-keys
+    #[serde(default)]
+    pub jwt: Option<JwtConfig>,
+    /// Locations, points can be imported from
+    #[serde(default)]
+    pub import: ImportConfig,
 
     /// Directory where static files are served from.
     /// For example, the Web-UI should be placed here.
@@ -40,6 +58,75 @@ pub struct ServiceConfig {
     pub enable_static_content: Option<bool>,
 }
 
+/// API key, which can be restricted to read operations and to a set of collections
+#[derive(Debug, Deserialize, Clone)]
+pub struct ApiKeyConfig {
+    pub key: String,
+    /// Name of the key in usage reports. Default: `api_keys[<index of the key>]`
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Allow only read operations with this key
+    #[serde(default)]
+    pub read_only: bool,
+    /// Names of collections accessible with this key. All collections are accessible if not set.
+    /// Requests, which are not addressed to a single collection, are rejected if set.
+    #[serde(default)]
+    pub collections: Option<Vec<String>>,
+}
+
+impl ApiKeyConfig {
+    /// Check if the key allows access to the given collection
+    pub fn allows_collection(&self, collection: Option<&str>) -> bool {
+        match &self.collections {
+            None => true,
+            Some(collections) => {
+                collection.is_some_and(|collection| collections.iter().any(|c| c == collection))
+            }
+        }
+    }
+}
+
+/// Keys to validate signatures of JWT tokens
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct JwtConfig {
+    /// Shared secret for tokens signed with HS256
+    #[serde(default)]
+    pub hs256_secret: Option<String>,
+    /// PEM-encoded RSA public key for tokens signed with RS256
+    #[serde(default)]
+    pub rs256_public_key: Option<String>,
+}
+
+/// Locations, points can be imported from. Nothing can be imported, unless allowed here
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ImportConfig {
+    /// Directory, local `file://` imports are restricted to
+    #[serde(default)]
+    pub local_dir: Option<String>,
+    /// URLs, remote `http(s)://` imports are restricted to.
+    /// A URL is allowed if it has the same scheme, host and port as one of these,
+    /// and its path starts with the path of that one.
+    #[serde(default)]
+    pub allowed_urls: Vec<Url>,
+}
+
+/// Maximum sizes of request bodies in megabytes, `max_request_size_mb` is used if not set
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RequestSizeLimitsConfig {
+    /// Point updates: upsert, vector and payload updates, batch updates
+    #[serde(default)]
+    pub update_mb: Option<usize>,
+    /// Search, recommendation and discovery requests
+    #[serde(default)]
+    pub search_mb: Option<usize>,
+    /// Collection and shard snapshot uploads. Unlimited if not set
+    #[serde(default)]
+    pub snapshot_upload_mb: Option<usize>,
+    /// Streamed point uploads and imports of points. Unlimited if not set
+    #[serde(default)]
+    pub points_upload_mb: Option<usize>,
+}
+
 #[derive(Debug, Deserialize, Clone, Default, Validate)]
 pub struct ClusterConfig {
     pub enabled: bool, // disabled by default
@@ -55,6 +142,23 @@ pub struct ClusterConfig {
     #[serde(default)]
     #[validate]
     pub consensus: ConsensusConfig,
+    /// Discover peers to bootstrap from through DNS, instead of the `--bootstrap` argument
+    #[serde(default)]
+    #[validate]
+    pub discovery: Option<PeerDiscoveryConfig>,
+}
+
+/// Discovery of peers through DNS, e.g. records of a Kubernetes headless service
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct PeerDiscoveryConfig {
+    /// DNS name, which resolves into addresses of all peers.
+    /// All peers are expected to listen on the same internal port.
+    #[validate(length(min = 1))]
+    pub dns_name: String,
+    /// How long to wait for other peers to start, before failing the startup
+    #[serde(default = "default_discovery_timeout_sec")]
+    #[validate(range(min = 1))]
+    pub timeout_sec: u64,
 }
 
 #[derive(Debug, Deserialize, Clone, Validate)]
@@ -66,6 +170,24 @@ pub struct P2pConfig {
     pub connection_pool_size: usize,
     #[serde(default)]
     pub enable_tls: bool,
+    /// Interval of HTTP/2 keep-alive pings between peers. Keep-alive is disabled if not set.
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub keep_alive_interval_ms: Option<u64>,
+    /// How long to wait for a keep-alive ping acknowledgement before closing the connection.
+    #[serde(default = "default_keep_alive_timeout_ms")]
+    #[validate(range(min = 1))]
+    pub keep_alive_timeout_ms: u64,
+    /// Number of retries for failed internal requests.
+    #[serde(default = "default_p2p_retries")]
+    #[validate(range(max = 100))]
+    pub retries: usize,
+    /// Base delay of the exponential backoff between retries of internal requests.
+    #[serde(default = "default_p2p_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Compression of requests and responses between peers.
+    #[serde(default)]
+    pub compression: GrpcCompression,
 }
 
 impl Default for P2pConfig {
@@ -74,6 +196,29 @@ impl Default for P2pConfig {
             port: None,
             connection_pool_size: default_connection_pool_size(),
             enable_tls: false,
+            keep_alive_interval_ms: None,
+            keep_alive_timeout_ms: default_keep_alive_timeout_ms(),
+            retries: default_p2p_retries(),
+            retry_backoff_ms: default_p2p_retry_backoff_ms(),
+            compression: GrpcCompression::default(),
+        }
+    }
+}
+
+/// Compression algorithm of gRPC messages
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GrpcCompression {
+    #[default]
+    None,
+    Gzip,
+}
+
+impl GrpcCompression {
+    pub fn encoding(self) -> Option<CompressionEncoding> {
+        match self {
+            GrpcCompression::None => None,
+            GrpcCompression::Gzip => Some(CompressionEncoding::Gzip),
         }
     }
 }
@@ -114,6 +259,70 @@ pub struct TlsConfig {
     pub cert_ttl: Option<u64>,
 }
 
+#[derive(Debug, Deserialize, Clone, Default, Validate)]
+pub struct IngestionConfig {
+    /// Kafka topics to consume, requires the `kafka` feature
+    #[serde(default)]
+    #[validate]
+    pub kafka: Vec<KafkaIngestionConfig>,
+}
+
+/// Usage of the service by API keys, e.g. for chargeback of a shared cluster
+#[derive(Debug, Deserialize, Clone, Default, Validate)]
+pub struct UsageMeteringConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// File to periodically append usage reports to, as JSON lines
+    #[serde(default)]
+    pub export_path: Option<String>,
+    #[serde(default = "default_usage_export_interval_sec")]
+    #[validate(range(min = 1))]
+    pub export_interval_sec: u64,
+}
+
+const fn default_usage_export_interval_sec() -> u64 {
+    300
+}
+
+/// Apply update operations, consumed from a Kafka topic, to a collection
+///
+/// Each message is a single operation in the format of the batch update API,
+/// e.g. `{"upsert": {"points": [...]}}` or `{"delete": {"points": [...]}}`.
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct KafkaIngestionConfig {
+    #[validate(length(min = 1))]
+    pub collection: String,
+    /// Bootstrap brokers, e.g. `localhost:9092`
+    #[validate(length(min = 1))]
+    pub brokers: Vec<String>,
+    #[validate(length(min = 1))]
+    pub topic: String,
+    /// Partitions to consume. Default: all partitions of the topic
+    #[serde(default)]
+    pub partitions: Option<Vec<i32>>,
+    /// Where to start a partition without a checkpoint
+    #[serde(default)]
+    pub start_from: KafkaStartOffset,
+    /// Maximum size of messages, fetched from a partition at once
+    #[serde(default = "default_kafka_max_fetch_bytes")]
+    #[validate(range(min = 1))]
+    pub max_fetch_bytes: i32,
+    /// Maximum time to wait for new messages in a single fetch
+    #[serde(default = "default_kafka_max_wait_ms")]
+    #[validate(range(min = 1))]
+    pub max_wait_ms: i32,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KafkaStartOffset {
+    /// Oldest retained message
+    #[default]
+    Earliest,
+    /// Only messages, which arrive after the consumer has started
+    Latest,
+}
+
 #[derive(Debug, Deserialize, Clone, Validate)]
 pub struct Settings {
     #[serde(default = "default_log_level")]
@@ -129,6 +338,13 @@ pub struct Settings {
     pub telemetry_disabled: bool,
     #[validate]
     pub tls: Option<TlsConfig>,
+    /// Consumers of external message streams, which update collections
+    #[serde(default)]
+    #[validate]
+    pub ingestion: IngestionConfig,
+    #[serde(default)]
+    #[validate]
+    pub usage_metering: UsageMeteringConfig,
     /// A list of messages for errors that happened during loading the configuration. We collect
     /// them and store them here while loading because then our logger is not configured yet.
     /// We therefore need to log these messages later, after the logger is ready.
@@ -184,6 +400,10 @@ const fn default_cors() -> bool {
     true
 }
 
+const fn default_grpc_compression() -> GrpcCompression {
+    GrpcCompression::Gzip
+}
+
 fn default_log_level() -> String {
     "INFO".to_string()
 }
@@ -205,6 +425,10 @@ const fn default_bootstrap_timeout_sec() -> u64 {
     15
 }
 
+const fn default_discovery_timeout_sec() -> u64 {
+    300
+}
+
 const fn default_max_message_queue_size() -> usize {
     100
 }
@@ -213,6 +437,18 @@ const fn default_connection_pool_size() -> usize {
     DEFAULT_POOL_SIZE
 }
 
+const fn default_keep_alive_timeout_ms() -> u64 {
+    20_000
+}
+
+const fn default_p2p_retries() -> usize {
+    DEFAULT_RETRIES
+}
+
+const fn default_p2p_retry_backoff_ms() -> u64 {
+    DEFAULT_BACKOFF.as_millis() as u64
+}
+
 const fn default_message_timeout_tics() -> u64 {
     10
 }
@@ -222,6 +458,15 @@ const fn default_tls_cert_ttl() -> Option<u64> {
     Some(3600)
 }
 
+const fn default_kafka_max_fetch_bytes() -> i32 {
+    // Default 1 MiB
+    1024 * 1024
+}
+
+const fn default_kafka_max_wait_ms() -> i32 {
+    500
+}
+
 impl Settings {
     #[allow(dead_code)]
     pub fn new(custom_config_path: Option<String>) -> Result<Self, ConfigError> {
@@ -17,7 +17,7 @@ use storage::content_manager::conversions::error_to_status;
 use storage::dispatcher::Dispatcher;
 use tonic::{Request, Response, Status};
 
-use super::validate;
+use super::{check_named_collections, validate};
 use crate::common::collections::*;
 use crate::tonic::api::collections_common::get;
 
@@ -129,6 +129,7 @@ impl Collections for CollectionsService {
         request: Request<CreateCollection>,
     ) -> Result<Response<CollectionOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_named_collections(&request)?;
         self.perform_operation(request).await
     }
 
@@ -177,9 +178,14 @@ impl Collections for CollectionsService {
         request: Request<CollectionClusterInfoRequest>,
     ) -> Result<Response<CollectionClusterInfoResponse>, Status> {
         validate(request.get_ref())?;
+        let CollectionClusterInfoRequest {
+            collection_name,
+            with_checksums,
+        } = request.into_inner();
         let response = do_get_collection_cluster(
             self.dispatcher.toc(),
-            request.into_inner().collection_name.as_str(),
+            &collection_name,
+            with_checksums.unwrap_or_default(),
         )
         .await
         .map_err(error_to_status)?
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PutResult, SchemaResult, Ticket,
+};
+use collection::common::arrow_points::{points_schema, records_to_batch, selected_dense_vectors};
+use collection::operations::shard_selector_internal::ShardSelectorInternal;
+use collection::operations::types::{PointRequestInternal, Record, ScrollRequestInternal};
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use segment::types::{Filter, PointIdType, WithPayloadInterface, WithVector};
+use serde::Deserialize;
+use storage::content_manager::conversions::error_to_status;
+use storage::content_manager::toc::TableOfContent;
+use storage::dispatcher::Dispatcher;
+use tonic::{Request, Response, Status, Streaming};
+
+use super::validate;
+use crate::common::points::{do_get_points, do_scroll_points};
+
+/// Number of points in each record batch, if not specified in the ticket
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+/// Read request, passed as JSON in the ticket of `DoGet`
+#[derive(Debug, Deserialize)]
+struct PointsTicket {
+    /// Name of the collection to read points from
+    collection_name: String,
+    /// Read points with these ids, instead of scrolling over the collection
+    #[serde(default)]
+    ids: Option<Vec<PointIdType>>,
+    /// Only scroll over points, which satisfy these conditions
+    #[serde(default)]
+    filter: Option<Filter>,
+    /// Select which payload to return. Default: all
+    #[serde(default)]
+    with_payload: Option<WithPayloadInterface>,
+    /// Select which dense vectors to return. Default: all
+    #[serde(default = "default_with_vector")]
+    with_vector: WithVector,
+    /// Number of points in each record batch
+    #[serde(default)]
+    batch_size: Option<usize>,
+}
+
+const fn default_with_vector() -> WithVector {
+    WithVector::Bool(true)
+}
+
+/// Arrow Flight service for bulk reads of points.
+///
+/// Points are streamed as record batches with an `id` column, a `payload` column with the
+/// payload as JSON and a fixed size list column per dense vector. Sparse vectors are not exported.
+pub struct FlightPointsService {
+    dispatcher: Arc<Dispatcher>,
+}
+
+impl FlightPointsService {
+    pub fn new(dispatcher: Arc<Dispatcher>) -> Self {
+        Self { dispatcher }
+    }
+}
+
+/// Name and dimension of the dense vectors to export
+async fn exported_vectors(
+    toc: &TableOfContent,
+    collection_name: &str,
+    with_vector: &WithVector,
+) -> Result<Vec<(String, usize)>, Status> {
+    let collection = toc
+        .get_collection(collection_name)
+        .await
+        .map_err(error_to_status)?;
+    let vectors_config = collection.state().await.config.params.vectors;
+    Ok(selected_dense_vectors(&vectors_config, with_vector))
+}
+
+#[tonic::async_trait]
+impl FlightService for FlightPointsService {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("Handshake is not supported"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("ListFlights is not supported"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetFlightInfo is not supported"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("GetSchema is not supported"))
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let ticket: PointsTicket = serde_json::from_slice(&request.get_ref().ticket)
+            .map_err(|err| Status::invalid_argument(format!("Invalid ticket: {err}")))?;
+        let PointsTicket {
+            collection_name,
+            ids,
+            filter,
+            with_payload,
+            with_vector,
+            batch_size,
+        } = ticket;
+
+        let toc = self.dispatcher.toc().clone();
+        let vectors = exported_vectors(&toc, &collection_name, &with_vector).await?;
+        let schema = points_schema(&vectors);
+
+        let records: BoxStream<'static, Result<Vec<Record>, Status>> = match ids {
+            Some(ids) => {
+                let request = PointRequestInternal {
+                    ids,
+                    with_payload,
+                    with_vector,
+                };
+                stream::once(async move {
+                    do_get_points(
+                        &toc,
+                        &collection_name,
+                        request,
+                        None,
+                        ShardSelectorInternal::All,
+                    )
+                    .await
+                    .map_err(error_to_status)
+                })
+                .boxed()
+            }
+            None => {
+                let request = ScrollRequestInternal {
+                    offset: None,
+                    limit: Some(batch_size.unwrap_or(DEFAULT_BATCH_SIZE)),
+                    filter,
+                    with_payload,
+                    with_vector,
+                    order_by: None,
+                    query: None,
+                };
+                validate(&request)?;
+
+                // Scroll page by page, each continuing from the offset of the previous one
+                stream::try_unfold(Some(request), move |request| {
+                    let toc = toc.clone();
+                    let collection_name = collection_name.clone();
+                    async move {
+                        let Some(request) = request else {
+                            return Ok(None);
+                        };
+                        let next_page = request.clone();
+                        let result = do_scroll_points(
+                            &toc,
+                            &collection_name,
+                            request,
+                            None,
+                            ShardSelectorInternal::All,
+                        )
+                        .await
+                        .map_err(error_to_status)?;
+                        let next_page =
+                            result.next_page_offset.map(|offset| ScrollRequestInternal {
+                                offset: Some(offset),
+                                ..next_page
+                            });
+                        Ok(Some((result.points, next_page)))
+                    }
+                })
+                .boxed()
+            }
+        };
+
+        let batch_schema = schema.clone();
+        let batches = records.map(move |records| {
+            let records = records.map_err(FlightError::Tonic)?;
+            records_to_batch(batch_schema.clone(), &vectors, &records).map_err(FlightError::Arrow)
+        });
+
+        let flight_data = FlightDataEncoderBuilder::new()
+            .with_schema(schema)
+            .build(batches)
+            .map(|flight_data| flight_data.map_err(Status::from));
+
+        Ok(Response::new(flight_data.boxed()))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("DoPut is not supported"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("DoAction is not supported"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented("ListActions is not supported"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("DoExchange is not supported"))
+    }
+}
@@ -1,16 +1,21 @@
 pub mod collections_api;
 mod collections_common;
 pub mod collections_internal_api;
+pub mod flight_api;
 pub mod points_api;
 mod points_common;
 pub mod points_internal_api;
 pub mod raft_api;
 pub mod snapshots_api;
 
+use api::grpc::qdrant::Filter;
 use collection::operations::validation;
-use tonic::Status;
+use storage::content_manager::conversions::error_to_status;
+use tonic::{Request, Status};
 use validator::Validate;
 
+use crate::common::auth::{self, restrict_filter, Claims, NamedCollections};
+
 /// Validate the given request and fail on error.
 ///
 /// Returns validation error on failure.
@@ -20,6 +25,24 @@ fn validate(request: &impl Validate) -> Result<(), Status> {
     })
 }
 
+/// Combine the filter of a request with the filter of the JWT token, the request was made with.
+fn restrict_request_filter<T>(
+    request: &Request<T>,
+    filter: Option<Filter>,
+) -> Result<Option<Filter>, Status> {
+    let Some(claims) = request.extensions().get::<Claims>() else {
+        return Ok(filter);
+    };
+    let filter = filter.map(TryInto::try_into).transpose()?;
+    Ok(restrict_filter(Some(claims), filter).map(Into::into))
+}
+
+/// Check that the request is allowed to access all collections, named in its message.
+fn check_named_collections<T: NamedCollections>(request: &Request<T>) -> Result<(), Status> {
+    let claims = request.extensions().get::<Claims>();
+    auth::check_named_collections(claims, request.get_ref()).map_err(error_to_status)
+}
+
 /// Validate the given request. Returns validation error on failure.
 fn validate_and_log(request: &impl Validate) {
     if let Err(ref err) = request.validate() {
@@ -5,14 +5,15 @@ use api::grpc::qdrant::points_server::Points;
 use api::grpc::qdrant::{
     ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
     DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints,
-    DiscoverBatchPoints, DiscoverBatchResponse, DiscoverPoints, DiscoverResponse, GetPoints,
-    GetResponse, PointsOperationResponse, RecommendBatchPoints, RecommendBatchResponse,
-    RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
-    ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse,
-    SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, UpdateBatchPoints,
-    UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
+    DiscoverBatchPoints, DiscoverBatchResponse, DiscoverPoints, DiscoverResponse, FacetCounts,
+    FacetResponse, GetPoints, GetResponse, PointsOperationResponse, RecommendBatchPoints,
+    RecommendBatchResponse, RecommendGroupsResponse, RecommendPointGroups, RecommendPoints,
+    RecommendResponse, ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse,
+    SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse, SearchStreamPoints,
+    SetPayloadPoints, UpdateBatchPoints, UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
 };
 use collection::operations::types::CoreSearchRequest;
+use futures::stream::BoxStream;
 use storage::dispatcher::Dispatcher;
 use tonic::{Request, Response, Status};
 
@@ -20,13 +21,17 @@ use super::points_common::{
     delete_vectors, discover, discover_batch, recommend_groups, search_groups, update_batch,
     update_vectors,
 };
-use super::validate;
+use super::{check_named_collections, restrict_request_filter, validate};
+use crate::common::auth::{restrict_filter, Claims};
 use crate::tonic::api::points_common::{
     clear_payload, convert_shard_selector_for_read, core_search_batch, count, create_field_index,
-    delete, delete_field_index, delete_payload, get, overwrite_payload, recommend, recommend_batch,
-    scroll, search, set_payload, upsert,
+    delete, delete_field_index, delete_payload, facet, get, overwrite_payload, recommend,
+    recommend_batch, scroll, scroll_stream, search, search_stream, set_payload, upsert,
 };
 
+/// Number of points in each of the responses of a streamed search, if not specified
+const DEFAULT_SEARCH_STREAM_CHUNK_SIZE: u64 = 1000;
+
 pub struct PointsService {
     dispatcher: Arc<Dispatcher>,
 }
@@ -137,7 +142,43 @@ impl Points for PointsService {
         request: Request<SearchPoints>,
     ) -> Result<Response<SearchResponse>, Status> {
         validate(request.get_ref())?;
-        search(self.dispatcher.as_ref(), request.into_inner(), None).await
+        let filter = restrict_request_filter(&request, request.get_ref().filter.clone())?;
+        let search_points = SearchPoints {
+            filter,
+            ..request.into_inner()
+        };
+        search(self.dispatcher.as_ref(), search_points, None).await
+    }
+
+    type SearchStreamStream = BoxStream<'static, Result<SearchResponse, Status>>;
+
+    async fn search_stream(
+        &self,
+        request: Request<SearchStreamPoints>,
+    ) -> Result<Response<Self::SearchStreamStream>, Status> {
+        validate(request.get_ref())?;
+        let Some(search_points) = request.get_ref().search_points.clone() else {
+            return Err(Status::invalid_argument("search_points is required"));
+        };
+        let filter = restrict_request_filter(&request, search_points.filter.clone())?;
+        let search_points = SearchPoints {
+            collection_name: request.get_ref().collection_name.clone(),
+            filter,
+            ..search_points
+        };
+        let chunk_size = request
+            .get_ref()
+            .chunk_size
+            .unwrap_or(DEFAULT_SEARCH_STREAM_CHUNK_SIZE);
+
+        let chunks = search_stream(
+            self.dispatcher.toc().clone(),
+            search_points,
+            chunk_size as usize,
+            None,
+        )
+        .await?;
+        Ok(Response::new(chunks))
     }
 
     async fn search_batch(
@@ -145,6 +186,7 @@ impl Points for PointsService {
         request: Request<SearchBatchPoints>,
     ) -> Result<Response<SearchBatchResponse>, Status> {
         validate(request.get_ref())?;
+        let claims = request.extensions().get::<Claims>().cloned();
         let SearchBatchPoints {
             collection_name,
             search_points,
@@ -160,7 +202,9 @@ impl Points for PointsService {
             let shard_key = search_point.shard_key_selector.take();
 
             let shard_selector = convert_shard_selector_for_read(None, shard_key);
-            let core_search_request = CoreSearchRequest::try_from(search_point)?;
+            let mut core_search_request = CoreSearchRequest::try_from(search_point)?;
+            core_search_request.filter =
+                restrict_filter(claims.as_ref(), core_search_request.filter);
 
             requests.push((core_search_request, shard_selector));
         }
@@ -180,7 +224,13 @@ impl Points for PointsService {
         request: Request<SearchPointGroups>,
     ) -> Result<Response<SearchGroupsResponse>, Status> {
         validate(request.get_ref())?;
-        search_groups(self.dispatcher.as_ref(), request.into_inner(), None).await
+        check_named_collections(&request)?;
+        let filter = restrict_request_filter(&request, request.get_ref().filter.clone())?;
+        let search_points = SearchPointGroups {
+            filter,
+            ..request.into_inner()
+        };
+        search_groups(self.dispatcher.as_ref(), search_points, None).await
     }
 
     async fn scroll(
@@ -188,7 +238,29 @@ impl Points for PointsService {
         request: Request<ScrollPoints>,
     ) -> Result<Response<ScrollResponse>, Status> {
         validate(request.get_ref())?;
-        scroll(self.dispatcher.as_ref(), request.into_inner(), None).await
+        let filter = restrict_request_filter(&request, request.get_ref().filter.clone())?;
+        let scroll_points = ScrollPoints {
+            filter,
+            ..request.into_inner()
+        };
+        scroll(self.dispatcher.as_ref(), scroll_points, None).await
+    }
+
+    type ScrollStreamStream = BoxStream<'static, Result<ScrollResponse, Status>>;
+
+    async fn scroll_stream(
+        &self,
+        request: Request<ScrollPoints>,
+    ) -> Result<Response<Self::ScrollStreamStream>, Status> {
+        validate(request.get_ref())?;
+        let filter = restrict_request_filter(&request, request.get_ref().filter.clone())?;
+        let scroll_points = ScrollPoints {
+            filter,
+            ..request.into_inner()
+        };
+
+        let pages = scroll_stream(self.dispatcher.toc().clone(), scroll_points, None).await?;
+        Ok(Response::new(pages))
     }
 
     async fn recommend(
@@ -196,6 +268,7 @@ impl Points for PointsService {
         request: Request<RecommendPoints>,
     ) -> Result<Response<RecommendResponse>, Status> {
         validate(request.get_ref())?;
+        check_named_collections(&request)?;
         recommend(self.dispatcher.as_ref(), request.into_inner()).await
     }
 
@@ -204,16 +277,21 @@ impl Points for PointsService {
         request: Request<RecommendBatchPoints>,
     ) -> Result<Response<RecommendBatchResponse>, Status> {
         validate(request.get_ref())?;
+        check_named_collections(&request)?;
         let RecommendBatchPoints {
             collection_name,
             recommend_points,
             read_consistency,
             timeout,
+            filter,
+            params,
         } = request.into_inner();
         recommend_batch(
             self.dispatcher.as_ref(),
             collection_name,
             recommend_points,
+            filter,
+            params,
             read_consistency,
             timeout.map(Duration::from_secs),
         )
@@ -225,6 +303,7 @@ impl Points for PointsService {
         request: Request<RecommendPointGroups>,
     ) -> Result<Response<RecommendGroupsResponse>, Status> {
         validate(request.get_ref())?;
+        check_named_collections(&request)?;
         recommend_groups(self.dispatcher.as_ref(), request.into_inner()).await
     }
 
@@ -233,6 +312,7 @@ impl Points for PointsService {
         request: Request<DiscoverPoints>,
     ) -> Result<Response<DiscoverResponse>, Status> {
         validate(request.get_ref())?;
+        check_named_collections(&request)?;
         discover(self.dispatcher.as_ref(), request.into_inner()).await
     }
 
@@ -241,16 +321,21 @@ impl Points for PointsService {
         request: Request<DiscoverBatchPoints>,
     ) -> Result<Response<DiscoverBatchResponse>, Status> {
         validate(request.get_ref())?;
+        check_named_collections(&request)?;
         let DiscoverBatchPoints {
             collection_name,
             discover_points,
             read_consistency,
             timeout,
+            filter,
+            params,
         } = request.into_inner();
         discover_batch(
             self.dispatcher.as_ref(),
             collection_name,
             discover_points,
+            filter,
+            params,
             read_consistency,
             timeout.map(Duration::from_secs),
         )
@@ -262,6 +347,19 @@ impl Points for PointsService {
         request: Request<CountPoints>,
     ) -> Result<Response<CountResponse>, Status> {
         validate(request.get_ref())?;
-        count(self.dispatcher.as_ref(), request.into_inner(), None).await
+        let filter = restrict_request_filter(&request, request.get_ref().filter.clone())?;
+        let count_points = CountPoints {
+            filter,
+            ..request.into_inner()
+        };
+        count(self.dispatcher.as_ref(), count_points, None).await
+    }
+
+    async fn facet(
+        &self,
+        request: Request<FacetCounts>,
+    ) -> Result<Response<FacetResponse>, Status> {
+        validate(request.get_ref())?;
+        facet(self.dispatcher.as_ref(), request.into_inner(), None).await
     }
 }
@@ -1,38 +1,48 @@
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use api::grpc::conversions::proto_to_payloads;
 use api::grpc::qdrant::payload_index_params::IndexParams;
 use api::grpc::qdrant::points_update_operation::{ClearPayload, Operation, PointStructList};
 use api::grpc::qdrant::{
-    points_update_operation, BatchResult, ClearPayloadPoints, CoreSearchPoints, CountPoints,
-    CountResponse, CreateFieldIndexCollection, DeleteFieldIndexCollection, DeletePayloadPoints,
-    DeletePointVectors, DeletePoints, DiscoverBatchResponse, DiscoverPoints, DiscoverResponse,
-    FieldType, GetPoints, GetResponse, PayloadIndexParams, PointsOperationResponse, PointsSelector,
-    ReadConsistency as ReadConsistencyGrpc, RecommendBatchResponse, RecommendGroupsResponse,
-    RecommendPointGroups, RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse,
-    SearchBatchResponse, SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse,
+    points_update_operation, BatchResult, ClearPayloadPoints, CompactScoredPoints,
+    CoreSearchPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
+    DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints,
+    DiscoverBatchResponse, DiscoverPoints, DiscoverResponse, FacetCounts, FacetResponse, FieldType,
+    Filter as FilterGrpc, GetPoints, GetResponse, PayloadIndexParams, PointStruct,
+    PointVectors as PointVectorsGrpc, PointsOperationResponse, PointsSelector,
+    PointsUpdateOperation, ReadConsistency as ReadConsistencyGrpc, RecommendBatchResponse,
+    RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
+    ScrollPoints, ScrollResponse, SearchBatchResponse, SearchGroupsResponse,
+    SearchParams as SearchParamsGrpc, SearchPointGroups, SearchPoints, SearchResponse,
     SetPayloadPoints, SyncPoints, UpdateBatchPoints, UpdateBatchResponse, UpdatePointVectors,
-    UpsertPoints,
+    UpsertPoints, Value, VectorsSelector,
 };
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::conversions::{
     try_discover_request_from_grpc, try_points_selector_from_grpc, write_ordering_from_proto,
 };
-use collection::operations::payload_ops::DeletePayload;
+use collection::operations::payload_ops::{DeletePayload, SetPayload};
 use collection::operations::point_ops::{
     self, PointInsertOperations, PointOperations, PointSyncOperation, PointsList,
 };
 use collection::operations::shard_key_selector::ShardKeySelector;
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::{
-    default_exact_count, CoreSearchRequest, CoreSearchRequestBatch, PointRequestInternal,
-    QueryEnum, RecommendExample, ScrollRequestInternal,
+    apply_batch_filter_and_params, default_exact_count, CoreSearchRequest, CoreSearchRequestBatch,
+    FacetRequestInternal, PointRequestInternal, QueryEnum, RecommendExample, Record, ScrollQuery,
+    ScrollRequestInternal,
 };
 use collection::operations::vector_ops::{DeleteVectors, PointVectors, UpdateVectors};
 use collection::operations::CollectionUpdateOperations;
 use collection::shards::shard::ShardId;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use segment::data_types::order_by::{OrderBy, OrderByInterface};
 use segment::types::{
     ExtendedPointId, Filter, PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType,
+    ScoredPoint,
 };
 use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::toc::TableOfContent;
@@ -40,10 +50,13 @@ use storage::dispatcher::Dispatcher;
 use tonic::{Response, Status};
 
 use crate::common::points::{
-    do_clear_payload, do_core_search_points, do_count_points, do_create_index,
-    do_create_index_internal, do_delete_index, do_delete_index_internal, do_delete_payload,
-    do_delete_points, do_delete_vectors, do_get_points, do_overwrite_payload, do_scroll_points,
-    do_search_batch_points, do_set_payload, do_update_vectors, do_upsert_points, CreateFieldIndex,
+    do_batch_update_points, do_clear_payload, do_core_search_points, do_count_points,
+    do_create_index, do_create_index_internal, do_delete_index, do_delete_index_internal,
+    do_delete_payload, do_delete_points, do_delete_vectors, do_facet_points, do_get_points,
+    do_overwrite_payload, do_scroll_points, do_search_batch_points, do_set_payload,
+    do_update_batch, do_update_vectors, do_upsert_points, ClearPayloadOperation, CreateFieldIndex,
+    DeleteOperation, DeletePayloadOperation, DeleteVectorsOperation, OverwritePayloadOperation,
+    SetPayloadOperation, UpdateOperation, UpdateVectorsOperation, UpsertOperation,
 };
 
 fn extract_points_selector(
@@ -89,6 +102,188 @@ pub(crate) fn convert_shard_selector_for_read(
     }
 }
 
+fn upsert_operation_from_grpc(
+    points: Vec<PointStruct>,
+    shard_key_selector: Option<api::grpc::qdrant::ShardKeySelector>,
+    condition: Option<FilterGrpc>,
+    if_version: Option<u64>,
+) -> Result<PointInsertOperations, Status> {
+    let points = points
+        .into_iter()
+        .map(|point| point.try_into())
+        .collect::<Result<_, _>>()?;
+    Ok(PointInsertOperations::PointsList(PointsList {
+        points,
+        shard_key: shard_key_selector.map(ShardKeySelector::from),
+        condition: condition
+            .map(|condition| condition.try_into())
+            .transpose()?,
+        if_version,
+    }))
+}
+
+fn points_selector_from_grpc(
+    points: Option<PointsSelector>,
+    shard_key_selector: Option<api::grpc::qdrant::ShardKeySelector>,
+) -> Result<point_ops::PointsSelector, Status> {
+    match points {
+        None => Err(Status::invalid_argument("PointSelector is missing")),
+        Some(p) => try_points_selector_from_grpc(p, shard_key_selector),
+    }
+}
+
+fn update_vectors_from_grpc(
+    points: Vec<PointVectorsGrpc>,
+    shard_key_selector: Option<api::grpc::qdrant::ShardKeySelector>,
+) -> Result<UpdateVectors, Status> {
+    // Build list of operation points
+    let mut op_points = Vec::with_capacity(points.len());
+    for point in points {
+        let id = match point.id {
+            Some(id) => id.try_into()?,
+            None => return Err(Status::invalid_argument("id is expected")),
+        };
+        let vector = match point.vectors {
+            Some(vectors) => vectors.try_into()?,
+            None => return Err(Status::invalid_argument("vectors is expected")),
+        };
+        op_points.push(PointVectors { id, vector });
+    }
+
+    Ok(UpdateVectors {
+        points: op_points,
+        shard_key: shard_key_selector.map(ShardKeySelector::from),
+    })
+}
+
+fn delete_vectors_from_grpc(
+    points_selector: Option<PointsSelector>,
+    vectors: Option<VectorsSelector>,
+    shard_key_selector: Option<api::grpc::qdrant::ShardKeySelector>,
+) -> Result<DeleteVectors, Status> {
+    let (points, filter) = extract_points_selector(points_selector)?;
+    let vector_names = match vectors {
+        Some(vectors) => vectors.names,
+        None => return Err(Status::invalid_argument("vectors is expected")),
+    };
+
+    Ok(DeleteVectors {
+        points,
+        filter,
+        vector: vector_names.into_iter().collect(),
+        shard_key: shard_key_selector.map(ShardKeySelector::from),
+    })
+}
+
+fn set_payload_from_grpc(
+    payload: HashMap<String, Value>,
+    points_selector: Option<PointsSelector>,
+    shard_key_selector: Option<api::grpc::qdrant::ShardKeySelector>,
+    key: Option<String>,
+) -> Result<SetPayload, Status> {
+    let (points, filter) = extract_points_selector(points_selector)?;
+    Ok(SetPayload {
+        payload: proto_to_payloads(payload)?,
+        points,
+        filter,
+        shard_key: shard_key_selector.map(ShardKeySelector::from),
+        key,
+    })
+}
+
+fn delete_payload_from_grpc(
+    keys: Vec<String>,
+    points_selector: Option<PointsSelector>,
+    shard_key_selector: Option<api::grpc::qdrant::ShardKeySelector>,
+) -> Result<DeletePayload, Status> {
+    let (points, filter) = extract_points_selector(points_selector)?;
+    Ok(DeletePayload {
+        keys,
+        points,
+        filter,
+        shard_key: shard_key_selector.map(ShardKeySelector::from),
+    })
+}
+
+/// Convert an operation of a gRPC batch into the operation of a REST batch
+fn update_operation_from_grpc(operation: PointsUpdateOperation) -> Result<UpdateOperation, Status> {
+    let operation = operation
+        .operation
+        .ok_or(Status::invalid_argument("Operation is missing"))?;
+    let operation = match operation {
+        Operation::Upsert(PointStructList {
+            points,
+            shard_key_selector,
+            condition,
+            if_version,
+        }) => UpdateOperation::Upsert(UpsertOperation {
+            upsert: upsert_operation_from_grpc(points, shard_key_selector, condition, if_version)?,
+        }),
+        Operation::DeleteDeprecated(points) => UpdateOperation::Delete(DeleteOperation {
+            delete: try_points_selector_from_grpc(points, None)?,
+        }),
+        Operation::DeletePoints(points_update_operation::DeletePoints {
+            points,
+            shard_key_selector,
+        }) => UpdateOperation::Delete(DeleteOperation {
+            delete: points_selector_from_grpc(points, shard_key_selector)?,
+        }),
+        Operation::SetPayload(points_update_operation::SetPayload {
+            payload,
+            points_selector,
+            shard_key_selector,
+            key,
+        }) => UpdateOperation::SetPayload(SetPayloadOperation {
+            set_payload: set_payload_from_grpc(payload, points_selector, shard_key_selector, key)?,
+        }),
+        Operation::OverwritePayload(points_update_operation::SetPayload {
+            payload,
+            points_selector,
+            shard_key_selector,
+            key,
+        }) => UpdateOperation::OverwritePayload(OverwritePayloadOperation {
+            overwrite_payload: set_payload_from_grpc(
+                payload,
+                points_selector,
+                shard_key_selector,
+                key,
+            )?,
+        }),
+        Operation::DeletePayload(points_update_operation::DeletePayload {
+            keys,
+            points_selector,
+            shard_key_selector,
+        }) => UpdateOperation::DeletePayload(DeletePayloadOperation {
+            delete_payload: delete_payload_from_grpc(keys, points_selector, shard_key_selector)?,
+        }),
+        Operation::ClearPayload(ClearPayload {
+            points,
+            shard_key_selector,
+        }) => UpdateOperation::ClearPayload(ClearPayloadOperation {
+            clear_payload: points_selector_from_grpc(points, shard_key_selector)?,
+        }),
+        Operation::ClearPayloadDeprecated(selector) => {
+            UpdateOperation::ClearPayload(ClearPayloadOperation {
+                clear_payload: try_points_selector_from_grpc(selector, None)?,
+            })
+        }
+        Operation::UpdateVectors(points_update_operation::UpdateVectors {
+            points,
+            shard_key_selector,
+        }) => UpdateOperation::UpdateVectors(UpdateVectorsOperation {
+            update_vectors: update_vectors_from_grpc(points, shard_key_selector)?,
+        }),
+        Operation::DeleteVectors(points_update_operation::DeleteVectors {
+            points_selector,
+            vectors,
+            shard_key_selector,
+        }) => UpdateOperation::DeleteVectors(DeleteVectorsOperation {
+            delete_vectors: delete_vectors_from_grpc(points_selector, vectors, shard_key_selector)?,
+        }),
+    };
+    Ok(operation)
+}
+
 pub async fn upsert(
     toc: &TableOfContent,
     upsert_points: UpsertPoints,
@@ -100,15 +295,10 @@ pub async fn upsert(
         points,
         ordering,
         shard_key_selector,
+        condition,
+        if_version,
     } = upsert_points;
-    let points = points
-        .into_iter()
-        .map(|point| point.try_into())
-        .collect::<Result<_, _>>()?;
-    let operation = PointInsertOperations::PointsList(PointsList {
-        points,
-        shard_key: shard_key_selector.map(ShardKeySelector::from),
-    });
+    let operation = upsert_operation_from_grpc(points, shard_key_selector, condition, if_version)?;
     let timing = Instant::now();
     let result = do_upsert_points(
         toc,
@@ -189,10 +379,7 @@ pub async fn delete(
         shard_key_selector,
     } = delete_points;
 
-    let points_selector = match points {
-        None => return Err(Status::invalid_argument("PointSelector is missing")),
-        Some(p) => try_points_selector_from_grpc(p, shard_key_selector)?,
-    };
+    let points_selector = points_selector_from_grpc(points, shard_key_selector)?;
 
     let timing = Instant::now();
     let result = do_delete_points(
@@ -223,24 +410,7 @@ pub async fn update_vectors(
         shard_key_selector,
     } = update_point_vectors;
 
-    // Build list of operation points
-    let mut op_points = Vec::with_capacity(points.len());
-    for point in points {
-        let id = match point.id {
-            Some(id) => id.try_into()?,
-            None => return Err(Status::invalid_argument("id is expected")),
-        };
-        let vector = match point.vectors {
-            Some(vectors) => vectors.try_into()?,
-            None => return Err(Status::invalid_argument("vectors is expected")),
-        };
-        op_points.push(PointVectors { id, vector });
-    }
-
-    let operation = UpdateVectors {
-        points: op_points,
-        shard_key: shard_key_selector.map(ShardKeySelector::from),
-    };
+    let operation = update_vectors_from_grpc(points, shard_key_selector)?;
 
     let timing = Instant::now();
     let result = do_update_vectors(
@@ -272,18 +442,7 @@ pub async fn delete_vectors(
         shard_key_selector,
     } = delete_point_vectors;
 
-    let (points, filter) = extract_points_selector(points_selector)?;
-    let vector_names = match vectors {
-        Some(vectors) => vectors.names,
-        None => return Err(Status::invalid_argument("vectors is expected")),
-    };
-
-    let operation = DeleteVectors {
-        points,
-        filter,
-        vector: vector_names.into_iter().collect(),
-        shard_key: shard_key_selector.map(ShardKeySelector::from),
-    };
+    let operation = delete_vectors_from_grpc(points_selector, vectors, shard_key_selector)?;
 
     let timing = Instant::now();
     let result = do_delete_vectors(
@@ -313,15 +472,10 @@ pub async fn set_payload(
         points_selector,
         ordering,
         shard_key_selector,
+        key,
     } = set_payload_points;
 
-    let (points, filter) = extract_points_selector(points_selector)?;
-    let operation = collection::operations::payload_ops::SetPayload {
-        payload: proto_to_payloads(payload)?,
-        points,
-        filter,
-        shard_key: shard_key_selector.map(ShardKeySelector::from),
-    };
+    let operation = set_payload_from_grpc(payload, points_selector, shard_key_selector, key)?;
 
     let timing = Instant::now();
     let result = do_set_payload(
@@ -351,15 +505,10 @@ pub async fn overwrite_payload(
         points_selector,
         ordering,
         shard_key_selector,
+        key,
     } = set_payload_points;
 
-    let (points, filter) = extract_points_selector(points_selector)?;
-    let operation = collection::operations::payload_ops::SetPayload {
-        payload: proto_to_payloads(payload)?,
-        points,
-        filter,
-        shard_key: shard_key_selector.map(ShardKeySelector::from),
-    };
+    let operation = set_payload_from_grpc(payload, points_selector, shard_key_selector, key)?;
 
     let timing = Instant::now();
     let result = do_overwrite_payload(
@@ -391,13 +540,7 @@ pub async fn delete_payload(
         shard_key_selector,
     } = delete_payload_points;
 
-    let (points, filter) = extract_points_selector(points_selector)?;
-    let operation = DeletePayload {
-        keys,
-        points,
-        filter,
-        shard_key: shard_key_selector.map(ShardKeySelector::from),
-    };
+    let operation = delete_payload_from_grpc(keys, points_selector, shard_key_selector)?;
 
     let timing = Instant::now();
     let result = do_delete_payload(
@@ -428,10 +571,7 @@ pub async fn clear_payload(
         shard_key_selector,
     } = clear_payload_points;
 
-    let points_selector = match points {
-        None => return Err(Status::invalid_argument("PointSelector is missing")),
-        Some(p) => try_points_selector_from_grpc(p, shard_key_selector)?,
-    };
+    let points_selector = points_selector_from_grpc(points, shard_key_selector)?;
 
     let timing = Instant::now();
     let result = do_clear_payload(
@@ -461,209 +601,63 @@ pub async fn update_batch(
         ordering,
     } = update_batch_points;
 
+    let operations = operations
+        .into_iter()
+        .map(update_operation_from_grpc)
+        .collect::<Result<_, _>>()?;
+
     let timing = Instant::now();
-    let mut results = Vec::with_capacity(operations.len());
-    for op in operations {
-        let operation = op
-            .operation
-            .ok_or(Status::invalid_argument("Operation is missing"))?;
-        let collection_name = collection_name.clone();
-        let ordering = ordering.clone();
-        let result = match operation {
-            points_update_operation::Operation::Upsert(PointStructList {
-                points,
-                shard_key_selector,
-            }) => {
-                upsert(
-                    toc,
-                    UpsertPoints {
-                        collection_name,
-                        points,
-                        wait,
-                        ordering,
-                        shard_key_selector,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::DeleteDeprecated(points) => {
-                delete(
-                    toc,
-                    DeletePoints {
-                        collection_name,
-                        wait,
-                        points: Some(points),
-                        ordering,
-                        shard_key_selector: None,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::SetPayload(
-                points_update_operation::SetPayload {
-                    payload,
-                    points_selector,
-                    shard_key_selector,
-                },
-            ) => {
-                set_payload(
-                    toc,
-                    SetPayloadPoints {
-                        collection_name,
-                        wait,
-                        payload,
-                        points_selector,
-                        ordering,
-                        shard_key_selector,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::OverwritePayload(
-                points_update_operation::SetPayload {
-                    payload,
-                    points_selector,
-                    shard_key_selector,
-                },
-            ) => {
-                overwrite_payload(
-                    toc,
-                    SetPayloadPoints {
-                        collection_name,
-                        wait,
-                        payload,
-                        points_selector,
-                        ordering,
-                        shard_key_selector,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::DeletePayload(
-                points_update_operation::DeletePayload {
-                    keys,
-                    points_selector,
-                    shard_key_selector,
-                },
-            ) => {
-                delete_payload(
-                    toc,
-                    DeletePayloadPoints {
-                        collection_name,
-                        wait,
-                        keys,
-                        points_selector,
-                        ordering,
-                        shard_key_selector,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::ClearPayload(ClearPayload {
-                points,
-                shard_key_selector,
-            }) => {
-                clear_payload(
-                    toc,
-                    ClearPayloadPoints {
-                        collection_name,
-                        wait,
-                        points,
-                        ordering,
-                        shard_key_selector,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::UpdateVectors(
-                points_update_operation::UpdateVectors {
-                    points,
-                    shard_key_selector,
-                },
-            ) => {
-                update_vectors(
-                    toc,
-                    UpdatePointVectors {
-                        collection_name,
-                        wait,
-                        points,
-                        ordering,
-                        shard_key_selector,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::DeleteVectors(
-                points_update_operation::DeleteVectors {
-                    points_selector,
-                    vectors,
-                    shard_key_selector,
-                },
-            ) => {
-                delete_vectors(
-                    toc,
-                    DeletePointVectors {
-                        collection_name,
-                        wait,
-                        points_selector,
-                        vectors,
-                        ordering,
-                        shard_key_selector,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            Operation::ClearPayloadDeprecated(selector) => {
-                clear_payload(
-                    toc,
-                    ClearPayloadPoints {
-                        collection_name,
-                        wait,
-                        points: Some(selector),
-                        ordering,
-                        shard_key_selector: None,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            Operation::DeletePoints(points_update_operation::DeletePoints {
-                points,
-                shard_key_selector,
-            }) => {
-                delete(
-                    toc,
-                    DeletePoints {
-                        collection_name,
-                        wait,
-                        points,
-                        ordering,
-                        shard_key_selector,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-        }?;
-        results.push(result);
-    }
+    let results = do_batch_update_points(
+        toc,
+        &collection_name,
+        operations,
+        shard_selection,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
     Ok(Response::new(UpdateBatchResponse {
-        result: results
-            .into_iter()
-            .map(|response| response.into_inner().result.unwrap())
-            .collect(),
+        result: results.into_iter().map(Into::into).collect(),
         time: timing.elapsed().as_secs_f64(),
     }))
 }
 
+/// Apply the operations of a batch to a single shard, as a single operation
+pub async fn update_batch_internal(
+    toc: &TableOfContent,
+    update_batch_points: UpdateBatchPoints,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let UpdateBatchPoints {
+        collection_name,
+        wait,
+        operations,
+        ordering,
+    } = update_batch_points;
+
+    let operations = operations
+        .into_iter()
+        .map(update_operation_from_grpc)
+        .collect::<Result<_, _>>()?;
+
+    let timing = Instant::now();
+    let result = do_update_batch(
+        toc,
+        &collection_name,
+        operations,
+        shard_selection,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
 fn convert_field_type(
     field_type: Option<i32>,
     field_index_params: Option<PayloadIndexParams>,
@@ -688,6 +682,21 @@ fn convert_field_type(
                 ))
             }
         },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::KeywordIndexParams(keyword_index_params)),
+            }),
+        ) => match v {
+            FieldType::Keyword => Some(PayloadFieldSchema::FieldParams(
+                PayloadSchemaParams::Keyword(keyword_index_params.into()),
+            )),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
         (Some(v), None | Some(PayloadIndexParams { index_params: None })) => match v {
             FieldType::Keyword => Some(PayloadSchemaType::Keyword.into()),
             FieldType::Integer => Some(PayloadSchemaType::Integer.into()),
@@ -849,6 +858,7 @@ pub async fn search(
         timeout,
         shard_key_selector,
         sparse_indices,
+        compact_response,
     } = search_points;
 
     let vector_struct =
@@ -885,17 +895,94 @@ pub async fn search(
     .await
     .map_err(error_to_status)?;
 
-    let response = SearchResponse {
-        result: scored_points
-            .into_iter()
-            .map(|point| point.into())
-            .collect(),
-        time: timing.elapsed().as_secs_f64(),
-    };
+    let response = search_response(scored_points, compact_response.unwrap_or_default(), timing);
 
     Ok(Response::new(response))
 }
 
+/// Search once, and stream the found points in chunks of `chunk_size`, in the order of their scores
+///
+/// Pages are read from the same search result, so the search is not repeated for every chunk.
+/// Read consistency of the request is not applied, every shard is read from a single replica.
+pub async fn search_stream(
+    toc: Arc<TableOfContent>,
+    search_points: SearchPoints,
+    chunk_size: usize,
+    shard_selection: Option<ShardId>,
+) -> Result<BoxStream<'static, Result<SearchResponse, Status>>, Status> {
+    let collection_name = search_points.collection_name.clone();
+    let shard_key_selector = search_points.shard_key_selector.clone();
+    let timeout = search_points.timeout.map(Duration::from_secs);
+    let compact_response = search_points.compact_response.unwrap_or_default();
+    let search_request = CoreSearchRequest::try_from(search_points)?;
+
+    let shard_selector = convert_shard_selector_for_read(shard_selection, shard_key_selector);
+
+    let cursor = toc
+        .search_cursor(&collection_name, search_request, shard_selector, timeout)
+        .await
+        .map_err(error_to_status)?;
+
+    let chunks = stream::try_unfold(cursor, move |mut cursor| {
+        let toc = toc.clone();
+        async move {
+            let timing = Instant::now();
+            let scored_points = toc
+                .cursor_next_page(&mut cursor, chunk_size)
+                .await
+                .map_err(error_to_status)?;
+            if scored_points.is_empty() {
+                return Ok(None);
+            }
+            let response = search_response(scored_points, compact_response, timing);
+            Ok(Some((response, cursor)))
+        }
+    });
+
+    Ok(chunks.boxed())
+}
+
+fn search_response(
+    scored_points: Vec<ScoredPoint>,
+    compact_response: bool,
+    timing: Instant,
+) -> SearchResponse {
+    let compact_result = if compact_response {
+        compact_scored_points(&scored_points)
+    } else {
+        None
+    };
+
+    match compact_result {
+        Some(compact_result) => SearchResponse {
+            result: vec![],
+            time: timing.elapsed().as_secs_f64(),
+            compact_result: Some(compact_result),
+        },
+        None => SearchResponse {
+            result: scored_points
+                .into_iter()
+                .map(|point| point.into())
+                .collect(),
+            time: timing.elapsed().as_secs_f64(),
+            compact_result: None,
+        },
+    }
+}
+
+/// Pack ids and scores of found points, if all ids are integers
+fn compact_scored_points(scored_points: &[ScoredPoint]) -> Option<CompactScoredPoints> {
+    let ids = scored_points
+        .iter()
+        .map(|point| match point.id {
+            ExtendedPointId::NumId(id) => Some(id),
+            ExtendedPointId::Uuid(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let scores = scored_points.iter().map(|point| point.score).collect();
+    Some(CompactScoredPoints { ids, scores })
+}
+
 pub async fn core_search_batch(
     toc: &TableOfContent,
     collection_name: String,
@@ -1041,6 +1128,8 @@ pub async fn recommend(
         read_consistency,
         timeout,
         shard_key_selector,
+        positive_weights,
+        negative_weights,
     } = recommend_points;
 
     let timeout = timeout.map(Duration::from_secs);
@@ -1065,6 +1154,8 @@ pub async fn recommend(
     let request = collection::operations::types::RecommendRequestInternal {
         positive,
         negative,
+        positive_weights: Some(positive_weights).filter(|w| !w.is_empty()),
+        negative_weights: Some(negative_weights).filter(|w| !w.is_empty()),
         strategy: strategy.map(|s| s.try_into()).transpose()?,
         filter: filter.map(|f| f.try_into()).transpose()?,
         params: params.map(|p| p.into()),
@@ -1112,16 +1203,27 @@ pub async fn recommend_batch(
     toc: &TableOfContent,
     collection_name: String,
     recommend_points: Vec<RecommendPoints>,
+    batch_filter: Option<FilterGrpc>,
+    batch_params: Option<SearchParamsGrpc>,
     read_consistency: Option<ReadConsistencyGrpc>,
     timeout: Option<Duration>,
 ) -> Result<Response<RecommendBatchResponse>, Status> {
+    let batch_filter: Option<Filter> = batch_filter.map(TryInto::try_into).transpose()?;
+    let batch_params = batch_params.map(Into::into);
+
     let mut requests = Vec::with_capacity(recommend_points.len());
 
     for mut request in recommend_points {
         let shard_selector =
             convert_shard_selector_for_read(None, request.shard_key_selector.take());
-        let internal_request: collection::operations::types::RecommendRequestInternal =
+        let mut internal_request: collection::operations::types::RecommendRequestInternal =
             request.try_into()?;
+        apply_batch_filter_and_params(
+            &mut internal_request.filter,
+            &mut internal_request.params,
+            batch_filter.as_ref(),
+            batch_params,
+        );
         requests.push((internal_request, shard_selector));
     }
 
@@ -1221,14 +1323,25 @@ pub async fn discover_batch(
     toc: &TableOfContent,
     collection_name: String,
     discover_points: Vec<DiscoverPoints>,
+    batch_filter: Option<FilterGrpc>,
+    batch_params: Option<SearchParamsGrpc>,
     read_consistency: Option<ReadConsistencyGrpc>,
     timeout: Option<Duration>,
 ) -> Result<Response<DiscoverBatchResponse>, Status> {
+    let batch_filter: Option<Filter> = batch_filter.map(TryInto::try_into).transpose()?;
+    let batch_params = batch_params.map(Into::into);
+
     let mut requests = Vec::with_capacity(discover_points.len());
 
     for discovery_request in discover_points {
-        let (internal_request, _collection_name, _consistency, _timeout, shard_key_selector) =
+        let (mut internal_request, _collection_name, _consistency, _timeout, shard_key_selector) =
             try_discover_request_from_grpc(discovery_request)?;
+        apply_batch_filter_and_params(
+            &mut internal_request.filter,
+            &mut internal_request.params,
+            batch_filter.as_ref(),
+            batch_params,
+        );
         let shard_selector = convert_shard_selector_for_read(None, shard_key_selector);
         requests.push((internal_request, shard_selector));
     }
@@ -1267,6 +1380,8 @@ pub async fn scroll(
         with_vectors,
         read_consistency,
         shard_key_selector,
+        order_by,
+        query,
     } = scroll_points;
 
     let scroll_request = ScrollRequestInternal {
@@ -1277,6 +1392,11 @@ pub async fn scroll(
         with_vector: with_vectors
             .map(|selector| selector.into())
             .unwrap_or_default(),
+        order_by: order_by
+            .map(OrderBy::try_from)
+            .transpose()?
+            .map(OrderByInterface::Struct),
+        query: query.map(ScrollQuery::try_from).transpose()?,
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
@@ -1307,6 +1427,96 @@ pub async fn scroll(
     Ok(Response::new(response))
 }
 
+/// Evaluate the filter once, and stream the matching points in pages of `limit`, in ascending
+/// order of their IDs
+///
+/// Pages are read from the same set of matching points, so the filter is not evaluated again for
+/// every page. Read consistency of the request is not applied, every shard is read from a single
+/// replica.
+pub async fn scroll_stream(
+    toc: Arc<TableOfContent>,
+    scroll_points: ScrollPoints,
+    shard_selection: Option<ShardId>,
+) -> Result<BoxStream<'static, Result<ScrollResponse, Status>>, Status> {
+    let ScrollPoints {
+        collection_name,
+        filter,
+        offset,
+        limit,
+        with_payload,
+        with_vectors,
+        read_consistency: _,
+        shard_key_selector,
+        order_by,
+        query,
+    } = scroll_points;
+
+    if order_by.is_some() || query.is_some() {
+        return Err(Status::invalid_argument(
+            "`order_by` and `query` are not supported by the scroll stream",
+        ));
+    }
+
+    let default_request = ScrollRequestInternal::default();
+    let page_size = limit
+        .map(|l| l as usize)
+        .or(default_request.limit)
+        .unwrap_or_default();
+
+    let scroll_request = ScrollRequestInternal {
+        offset: offset.map(|o| o.try_into()).transpose()?,
+        limit: None,
+        filter: filter.map(|f| f.try_into()).transpose()?,
+        with_payload: with_payload.map(|wp| wp.try_into()).transpose()?,
+        with_vector: with_vectors
+            .map(|selector| selector.into())
+            .unwrap_or_default(),
+        order_by: None,
+        query: None,
+    };
+
+    let shard_selector = convert_shard_selector_for_read(shard_selection, shard_key_selector);
+
+    let cursor = toc
+        .scroll_cursor(&collection_name, scroll_request, shard_selector)
+        .await
+        .map_err(error_to_status)?;
+
+    let pages = stream::try_unfold(cursor, move |mut cursor| {
+        let toc = toc.clone();
+        async move {
+            let timing = Instant::now();
+            let points = toc
+                .cursor_next_page(&mut cursor, page_size)
+                .await
+                .map_err(error_to_status)?;
+            if points.is_empty() {
+                return Ok(None);
+            }
+            let response = ScrollResponse {
+                next_page_offset: cursor.peek().map(|point| point.id.into()),
+                result: points
+                    .into_iter()
+                    .map(|point| {
+                        Record {
+                            id: point.id,
+                            payload: point.payload,
+                            vector: point.vector,
+                            shard_key: point.shard_key,
+                            order_value: None,
+                        }
+                        .into()
+                    })
+                    .collect(),
+                time: timing.elapsed().as_secs_f64(),
+            };
+            Ok(Some((response, cursor)))
+        }
+    });
+
+    Ok(pages.boxed())
+}
+
 pub async fn count(
     toc: &TableOfContent,
     count_points: CountPoints,
@@ -1348,6 +1558,51 @@ pub async fn count(
     Ok(Response::new(response))
 }
 
+pub async fn facet(
+    toc: &TableOfContent,
+    facet_counts: FacetCounts,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<FacetResponse>, Status> {
+    let FacetCounts {
+        collection_name,
+        key,
+        filter,
+        limit,
+        exact,
+        read_consistency,
+        shard_key_selector,
+    } = facet_counts;
+
+    let facet_request = FacetRequestInternal {
+        key,
+        limit: limit.map(|limit| limit as usize),
+        filter: filter.map(|f| f.try_into()).transpose()?,
+        exact: exact.unwrap_or_default(),
+    };
+
+    let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+
+    let shard_selector = convert_shard_selector_for_read(shard_selection, shard_key_selector);
+
+    let timing = Instant::now();
+    let facet_result = do_facet_points(
+        toc,
+        &collection_name,
+        facet_request,
+        read_consistency,
+        shard_selector,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = FacetResponse {
+        hits: facet_result.hits.into_iter().map(Into::into).collect(),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
 pub async fn get(
     toc: &TableOfContent,
     get_points: GetPoints,
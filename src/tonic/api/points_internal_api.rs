@@ -1,15 +1,18 @@
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
+use api::grpc::idempotency::{idempotency_key, OperationDeduplicator};
 use api::grpc::qdrant::points_internal_server::PointsInternal;
 use api::grpc::qdrant::{
     ClearPayloadPointsInternal, CoreSearchBatchPointsInternal, CountPointsInternal, CountResponse,
     CreateFieldIndexCollectionInternal, DeleteFieldIndexCollectionInternal,
-    DeletePayloadPointsInternal, DeletePointsInternal, DeleteVectorsInternal, GetPointsInternal,
-    GetResponse, PointsOperationResponse, RecommendPointsInternal, RecommendResponse,
-    ScrollPointsInternal, ScrollResponse, SearchBatchPointsInternal, SearchBatchResponse,
-    SearchPointsInternal, SearchResponse, SetPayloadPointsInternal, SyncPointsInternal,
-    UpdateVectorsInternal, UpsertPointsInternal,
+    DeletePayloadPointsInternal, DeletePointsInternal, DeleteVectorsInternal, FacetCountsInternal,
+    FacetResponse, GetPointsInternal, GetResponse, PointsOperationResponse,
+    RecommendPointsInternal, RecommendResponse, ScrollPointsInternal, ScrollResponse,
+    SearchBatchPointsInternal, SearchBatchResponse, SearchPointsInternal, SearchResponse,
+    SetPayloadPointsInternal, SyncPointsInternal, UpdateBatchInternal, UpdateVectorsInternal,
+    UpsertPointsInternal,
 };
 use storage::content_manager::toc::TableOfContent;
 use tonic::{Request, Response, Status};
@@ -18,18 +21,43 @@ use super::points_common::core_search_list;
 use super::validate_and_log;
 use crate::tonic::api::points_common::{
     clear_payload, count, create_field_index_internal, delete, delete_field_index_internal,
-    delete_payload, delete_vectors, get, overwrite_payload, recommend, scroll, set_payload, sync,
-    update_vectors, upsert,
+    delete_payload, delete_vectors, facet, get, overwrite_payload, recommend, scroll, set_payload,
+    sync, update_batch_internal, update_vectors, upsert,
 };
 
 /// This API is intended for P2P communication within a distributed deployment.
 pub struct PointsInternalService {
     toc: Arc<TableOfContent>,
+    /// Prevents updates retried by other peers from being applied twice
+    deduplicator: OperationDeduplicator<PointsOperationResponse>,
 }
 
 impl PointsInternalService {
     pub fn new(toc: Arc<TableOfContent>) -> Self {
-        Self { toc }
+        Self {
+            toc,
+            deduplicator: OperationDeduplicator::default(),
+        }
+    }
+
+    /// Apply the update at most once for the given idempotency key
+    async fn deduplicated<F, Fut>(
+        &self,
+        idempotency_key: Option<String>,
+        update: F,
+    ) -> Result<Response<PointsOperationResponse>, Status>
+    where
+        F: FnOnce(Arc<TableOfContent>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Response<PointsOperationResponse>, Status>> + Send + 'static,
+    {
+        let toc = self.toc.clone();
+        self.deduplicator
+            .deduplicate(idempotency_key, || async move {
+                update(toc).await.map(Response::into_inner)
+            })
+            .await
+            .map_err(|err| Status::internal(format!("Failed to apply update: {err}")))?
+            .map(Response::new)
     }
 }
 
@@ -40,6 +68,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<UpsertPointsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let UpsertPointsInternal {
             upsert_points,
             shard_id,
@@ -48,7 +77,10 @@ impl PointsInternal for PointsInternalService {
         let upsert_points =
             upsert_points.ok_or_else(|| Status::invalid_argument("UpsertPoints is missing"))?;
 
-        upsert(self.toc.as_ref(), upsert_points, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            upsert(&toc, upsert_points, shard_id).await
+        })
+        .await
     }
 
     async fn delete(
@@ -56,6 +88,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<DeletePointsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let DeletePointsInternal {
             delete_points,
             shard_id,
@@ -64,7 +97,10 @@ impl PointsInternal for PointsInternalService {
         let delete_points =
             delete_points.ok_or_else(|| Status::invalid_argument("DeletePoints is missing"))?;
 
-        delete(self.toc.as_ref(), delete_points, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            delete(&toc, delete_points, shard_id).await
+        })
+        .await
     }
 
     async fn update_vectors(
@@ -72,6 +108,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<UpdateVectorsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let request = request.into_inner();
         let shard_id = request.shard_id;
         let update_point_vectors = request.update_vectors;
@@ -79,7 +116,10 @@ impl PointsInternal for PointsInternalService {
         let update_point_vectors = update_point_vectors
             .ok_or_else(|| Status::invalid_argument("UpdateVectors is missing"))?;
 
-        update_vectors(self.toc.as_ref(), update_point_vectors, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            update_vectors(&toc, update_point_vectors, shard_id).await
+        })
+        .await
     }
 
     async fn delete_vectors(
@@ -87,6 +127,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<DeleteVectorsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let request = request.into_inner();
         let shard_id = request.shard_id;
         let delete_point_vectors = request.delete_vectors;
@@ -94,7 +135,10 @@ impl PointsInternal for PointsInternalService {
         let delete_point_vectors = delete_point_vectors
             .ok_or_else(|| Status::invalid_argument("DeleteVectors is missing"))?;
 
-        delete_vectors(self.toc.as_ref(), delete_point_vectors, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            delete_vectors(&toc, delete_point_vectors, shard_id).await
+        })
+        .await
     }
 
     async fn set_payload(
@@ -102,6 +146,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<SetPayloadPointsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let SetPayloadPointsInternal {
             set_payload_points,
             shard_id,
@@ -110,7 +155,10 @@ impl PointsInternal for PointsInternalService {
         let set_payload_points = set_payload_points
             .ok_or_else(|| Status::invalid_argument("SetPayloadPoints is missing"))?;
 
-        set_payload(self.toc.as_ref(), set_payload_points, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            set_payload(&toc, set_payload_points, shard_id).await
+        })
+        .await
     }
 
     async fn delete_payload(
@@ -118,6 +166,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<DeletePayloadPointsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let DeletePayloadPointsInternal {
             delete_payload_points,
             shard_id,
@@ -126,7 +175,10 @@ impl PointsInternal for PointsInternalService {
         let delete_payload_points = delete_payload_points
             .ok_or_else(|| Status::invalid_argument("DeletePayloadPoints is missing"))?;
 
-        delete_payload(self.toc.as_ref(), delete_payload_points, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            delete_payload(&toc, delete_payload_points, shard_id).await
+        })
+        .await
     }
 
     async fn clear_payload(
@@ -134,6 +186,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<ClearPayloadPointsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let ClearPayloadPointsInternal {
             clear_payload_points,
             shard_id,
@@ -142,7 +195,10 @@ impl PointsInternal for PointsInternalService {
         let clear_payload_points = clear_payload_points
             .ok_or_else(|| Status::invalid_argument("ClearPayloadPoints is missing"))?;
 
-        clear_payload(self.toc.as_ref(), clear_payload_points, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            clear_payload(&toc, clear_payload_points, shard_id).await
+        })
+        .await
     }
 
     async fn create_field_index(
@@ -150,6 +206,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<CreateFieldIndexCollectionInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let CreateFieldIndexCollectionInternal {
             create_field_index_collection,
             shard_id,
@@ -158,8 +215,10 @@ impl PointsInternal for PointsInternalService {
         let create_field_index_collection = create_field_index_collection
             .ok_or_else(|| Status::invalid_argument("CreateFieldIndexCollection is missing"))?;
 
-        create_field_index_internal(self.toc.as_ref(), create_field_index_collection, shard_id)
-            .await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            create_field_index_internal(&toc, create_field_index_collection, shard_id).await
+        })
+        .await
     }
 
     async fn delete_field_index(
@@ -167,6 +226,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<DeleteFieldIndexCollectionInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let DeleteFieldIndexCollectionInternal {
             delete_field_index_collection,
             shard_id,
@@ -175,8 +235,30 @@ impl PointsInternal for PointsInternalService {
         let delete_field_index_collection = delete_field_index_collection
             .ok_or_else(|| Status::invalid_argument("DeleteFieldIndexCollection is missing"))?;
 
-        delete_field_index_internal(self.toc.as_ref(), delete_field_index_collection, shard_id)
-            .await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            delete_field_index_internal(&toc, delete_field_index_collection, shard_id).await
+        })
+        .await
+    }
+
+    async fn update_batch(
+        &self,
+        request: Request<UpdateBatchInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
+        let UpdateBatchInternal {
+            update_batch,
+            shard_id,
+        } = request.into_inner();
+
+        let update_batch =
+            update_batch.ok_or_else(|| Status::invalid_argument("UpdateBatchPoints is missing"))?;
+
+        self.deduplicated(idempotency_key, move |toc| async move {
+            update_batch_internal(&toc, update_batch, shard_id).await
+        })
+        .await
     }
 
     async fn search(
@@ -300,18 +382,37 @@ impl PointsInternal for PointsInternalService {
         count(self.toc.as_ref(), count_points, shard_id).await
     }
 
+    async fn facet(
+        &self,
+        request: Request<FacetCountsInternal>,
+    ) -> Result<Response<FacetResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let FacetCountsInternal {
+            facet_counts,
+            shard_id,
+        } = request.into_inner();
+
+        let facet_counts =
+            facet_counts.ok_or_else(|| Status::invalid_argument("FacetCounts is missing"))?;
+        facet(self.toc.as_ref(), facet_counts, shard_id).await
+    }
+
     async fn sync(
         &self,
         request: Request<SyncPointsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let SyncPointsInternal {
             sync_points,
             shard_id,
         } = request.into_inner();
         let sync_points =
             sync_points.ok_or_else(|| Status::invalid_argument("SyncPoints is missing"))?;
-        sync(self.toc.as_ref(), sync_points, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            sync(&toc, sync_points, shard_id).await
+        })
+        .await
     }
 
     async fn overwrite_payload(
@@ -319,6 +420,7 @@ impl PointsInternal for PointsInternalService {
         request: Request<SetPayloadPointsInternal>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate_and_log(request.get_ref());
+        let idempotency_key = idempotency_key(&request);
         let SetPayloadPointsInternal {
             set_payload_points,
             shard_id,
@@ -327,6 +429,9 @@ impl PointsInternal for PointsInternalService {
         let set_payload_points = set_payload_points
             .ok_or_else(|| Status::invalid_argument("SetPayloadPoints is missing"))?;
 
-        overwrite_payload(self.toc.as_ref(), set_payload_points, shard_id).await
+        self.deduplicated(idempotency_key, move |toc| async move {
+            overwrite_payload(&toc, set_payload_points, shard_id).await
+        })
+        .await
     }
 }
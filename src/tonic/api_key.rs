@@ -5,6 +5,7 @@ use futures_util::future::BoxFuture;
 use reqwest::header::HeaderValue;
 use reqwest::StatusCode;
 use tonic::body::BoxBody;
+use tonic::codegen::Body as _;
 use tonic::Code;
 use tower::Service;
 use tower_layer::Layer;
@@ -12,22 +13,55 @@ use tower_layer::Layer;
 use crate::common::auth::AuthKeys;
 use crate::common::strings::ct_eq;
 
-const READ_ONLY_RPC_PATHS: [&str; 13] = [
+const READ_ONLY_RPC_PATHS: [&str; 17] = [
     "/qdrant.Collections/List",
     "/qdrant.Collections/Get",
     "/qdrant.Points/Scroll",
+    "/qdrant.Points/ScrollStream",
     "/qdrant.Points/Get",
     "/qdrant.Points/Count",
     "/qdrant.Points/Search",
     "/qdrant.Points/SearchGroups",
     "/qdrant.Points/SearchBatch",
+    "/qdrant.Points/SearchStream",
     "/qdrant.Points/Recommend",
     "/qdrant.Points/RecommendGroups",
     "/qdrant.Points/RecommendBatch",
     "/qdrant.Points/Discover",
     "/qdrant.Points/DiscoverBatch",
+    "/qdrant.Points/Facet",
+    "/arrow.flight.protocol.FlightService/DoGet",
 ];
 
+/// RPCs, which can be made with JWT tokens with a filter claim.
+/// The filter is added to the filter of the request by the service.
+const FILTERED_RPC_PATHS: [&str; 7] = [
+    "/qdrant.Points/Scroll",
+    "/qdrant.Points/ScrollStream",
+    "/qdrant.Points/Count",
+    "/qdrant.Points/Search",
+    "/qdrant.Points/SearchGroups",
+    "/qdrant.Points/SearchBatch",
+    "/qdrant.Points/SearchStream",
+];
+
+/// RPCs of collection services, which are not addressed to a single collection
+const NON_COLLECTION_RPC_PATHS: [&str; 6] = [
+    "/qdrant.Collections/List",
+    "/qdrant.Collections/UpdateAliases",
+    "/qdrant.Collections/ListAliases",
+    "/qdrant.Snapshots/CreateFull",
+    "/qdrant.Snapshots/ListFull",
+    "/qdrant.Snapshots/DeleteFull",
+];
+
+/// Any request, addressed to a single collection, has its name as the first field
+#[derive(Clone, PartialEq, prost::Message)]
+struct CollectionRequest {
+    #[prost(string, tag = "1")]
+    collection_name: String,
+}
+
 #[derive(Clone)]
 pub struct ApiKeyMiddleware<T> {
     service: T,
@@ -42,9 +76,11 @@ pub struct ApiKeyMiddlewareLayer {
 impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for ApiKeyMiddleware<S>
 where
     S: Service<
-        tonic::codegen::http::Request<tonic::transport::Body>,
-        Response = tonic::codegen::http::Response<tonic::body::BoxBody>,
-    >,
+            tonic::codegen::http::Request<tonic::transport::Body>,
+            Response = tonic::codegen::http::Response<tonic::body::BoxBody>,
+        > + Clone
+        + Send
+        + 'static,
     S::Future: Send + 'static,
 {
     type Response = tonic::codegen::http::Response<tonic::body::BoxBody>;
@@ -57,40 +93,130 @@ where
 
     fn call(
         &mut self,
-        request: tonic::codegen::http::Request<tonic::transport::Body>,
+        mut request: tonic::codegen::http::Request<tonic::transport::Body>,
     ) -> Self::Future {
-        // Grab API key from request
-        let key =
-            // Request header
-            request.headers().get("api-key").and_then(|key| key.to_str().ok()).map(|key| key.to_string())
-                // Fall back to authentication header with bearer token
-                .or_else(|| {
-                    request.headers().get("authorization")
-                        .and_then(|auth| {
-                            Bearer::parse(auth).ok().map(|bearer| bearer.token().into())
-                        })
-                });
-
-        if let Some(key) = key {
-            let is_allowed = self.auth_keys.can_write(&key)
-                || (is_read_only(&request) && self.auth_keys.can_read(&key));
-            if is_allowed {
+        let Some(key) = request_api_key(&request) else {
+            return Box::pin(async move { Ok(forbidden_response()) });
+        };
+
+        let is_read_only = is_read_only(&request);
+        if self.auth_keys.can_access(&key, is_read_only, None) {
+            return Box::pin(self.service.call(request));
+        }
+
+        let claims = self.auth_keys.validate_jwt(&key);
+        let is_collection_scoped = match &claims {
+            Some(claims) => {
+                if claims.filter.is_some() && !FILTERED_RPC_PATHS.contains(&request.uri().path()) {
+                    return Box::pin(async move { Ok(forbidden_response()) });
+                }
+                claims.collections.is_some()
+            }
+            None => self.auth_keys.is_collection_scoped(&key),
+        };
+
+        if let Some(claims) = &claims {
+            if !is_collection_scoped {
+                if !claims.allows(is_read_only, None) {
+                    return Box::pin(async move { Ok(forbidden_response()) });
+                }
+                // Claims are used by the services to restrict the request
+                request.extensions_mut().insert(claims.clone());
                 return Box::pin(self.service.call(request));
             }
         }
 
-        let mut response = Self::Response::new(BoxBody::default());
-        *response.status_mut() = StatusCode::FORBIDDEN;
-        response.headers_mut().append(
-            "grpc-status",
-            HeaderValue::from(Code::PermissionDenied as i32),
-        );
-        response
-            .headers_mut()
-            .append("grpc-message", HeaderValue::from_static("Invalid api-key"));
-
-        Box::pin(async move { Ok(response) })
+        if !is_collection_scoped || !is_collection_request(&request) {
+            return Box::pin(async move { Ok(forbidden_response()) });
+        }
+
+        // The collection name is only known from the message, so read the whole body first
+        let clone = self.service.clone();
+        let mut service = std::mem::replace(&mut self.service, clone);
+        let auth_keys = self.auth_keys.clone();
+        let (parts, mut body) = request.into_parts();
+
+        Box::pin(async move {
+            let mut message = Vec::new();
+            while let Some(chunk) = body.data().await {
+                match chunk {
+                    Ok(chunk) => message.extend_from_slice(&chunk),
+                    Err(_) => return Ok(forbidden_response()),
+                }
+            }
+
+            let collection = collection_name(&message);
+            let is_allowed = match &claims {
+                Some(claims) => claims.allows(is_read_only, collection.as_deref()),
+                None => auth_keys.can_access(&key, is_read_only, collection.as_deref()),
+            };
+            if !is_allowed {
+                return Ok(forbidden_response());
+            }
+
+            let mut request = tonic::codegen::http::Request::from_parts(parts, message.into());
+            // Claims are used by the services to check other collections, named in the message
+            if let Some(claims) = claims.or_else(|| auth_keys.scoped_claims(&key)) {
+                request.extensions_mut().insert(claims);
+            }
+            service.call(request).await
+        })
+    }
+}
+
+/// Grab API key from request
+pub(super) fn request_api_key<R>(req: &tonic::codegen::http::Request<R>) -> Option<String> {
+    // Request header
+    req.headers()
+        .get("api-key")
+        .and_then(|key| key.to_str().ok())
+        .map(|key| key.to_string())
+        // Fall back to authentication header with bearer token
+        .or_else(|| {
+            req.headers()
+                .get("authorization")
+                .and_then(|auth| Bearer::parse(auth).ok().map(|bearer| bearer.token().into()))
+        })
+}
+
+fn forbidden_response() -> tonic::codegen::http::Response<BoxBody> {
+    let mut response = tonic::codegen::http::Response::new(BoxBody::default());
+    *response.status_mut() = StatusCode::FORBIDDEN;
+    response.headers_mut().append(
+        "grpc-status",
+        HeaderValue::from(Code::PermissionDenied as i32),
+    );
+    response
+        .headers_mut()
+        .append("grpc-message", HeaderValue::from_static("Invalid api-key"));
+    response
+}
+
+/// Extract the collection name from a gRPC message
+///
+/// Compressed messages are not supported, they are rejected for keys restricted to collections.
+pub(super) fn collection_name(message: &[u8]) -> Option<String> {
+    // 1 byte of compression flag, 4 bytes of the message length
+    let compressed = *message.first()?;
+    if compressed != 0 {
+        return None;
     }
+    let length = u32::from_be_bytes(message.get(1..5)?.try_into().ok()?) as usize;
+    let payload = message.get(5..5 + length)?;
+    let request = <CollectionRequest as prost::Message>::decode(payload).ok()?;
+    Some(request.collection_name).filter(|name| !name.is_empty())
+}
+
+pub(super) fn is_collection_request<R>(req: &tonic::codegen::http::Request<R>) -> bool {
+    let uri_path = req.uri().path();
+    let is_collection_service = [
+        "/qdrant.Points/",
+        "/qdrant.Collections/",
+        "/qdrant.Snapshots/",
+    ]
+    .iter()
+    .any(|prefix| uri_path.starts_with(prefix));
+    is_collection_service && !NON_COLLECTION_RPC_PATHS.contains(&uri_path)
 }
 
 impl ApiKeyMiddlewareLayer {
@@ -116,3 +242,17 @@ fn is_read_only<R>(req: &tonic::codegen::http::Request<R>) -> bool {
         .iter()
         .any(|ro_uri_path| ct_eq(uri_path, ro_uri_path))
 }
+
+#[cfg(test)]
+mod tests {
+    use tonic::codegen::http::Request;
+
+    use super::*;
+
+    #[test]
+    fn test_read_only_facet() {
+        let request = |path: &str| Request::post(path).body(()).unwrap();
+        assert!(is_read_only(&request("/qdrant.Points/Facet")));
+        assert!(!is_read_only(&request("/qdrant.Points/Upsert")));
+    }
+}
@@ -1,7 +1,9 @@
 mod api;
 mod api_key;
 mod logging;
+mod tls;
 mod tonic_telemetry;
+mod usage_metering;
 
 use std::io;
 use std::net::{IpAddr, SocketAddr};
@@ -28,22 +30,25 @@ use ::api::grpc::qdrant::{
     WaitOnConsensusCommitRequest, WaitOnConsensusCommitResponse,
 };
 use ::api::grpc::QDRANT_DESCRIPTOR_SET;
+use arrow_flight::flight_service_server::FlightServiceServer;
 use storage::content_manager::consensus_manager::ConsensusStateRef;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
 use tokio::runtime::Handle;
 use tokio::signal;
 use tonic::codec::CompressionEncoding;
-use tonic::transport::{Server, ServerTlsConfig};
+use tonic::transport::Server;
 use tonic::{Request, Response, Status};
 
 use crate::common::auth::AuthKeys;
-use crate::common::helpers;
 use crate::common::http_client::HttpClient;
 use crate::common::telemetry_ops::requests_telemetry::TonicTelemetryCollector;
+use crate::common::usage_metering::UsageMeter;
+use crate::common::{certificate_helpers, helpers};
 use crate::settings::Settings;
 use crate::tonic::api::collections_api::CollectionsService;
 use crate::tonic::api::collections_internal_api::CollectionsInternalService;
+use crate::tonic::api::flight_api::FlightPointsService;
 use crate::tonic::api::points_api::PointsService;
 use crate::tonic::api::points_internal_api::PointsInternalService;
 use crate::tonic::api::snapshots_api::{ShardSnapshotsService, SnapshotsService};
@@ -142,13 +147,41 @@ async fn wait_stop_signal(for_what: &str) {
     }
 }
 
+fn cert_ttl_display(settings: &Settings) -> String {
+    settings
+        .tls
+        .as_ref()
+        .and_then(|tls| tls.cert_ttl)
+        .map(|ttl| ttl.to_string())
+        .unwrap_or_else(|| "none".into())
+}
+
+/// Set up compression and message size limit of a generated gRPC service.
+///
+/// Compressed requests are always accepted, responses are compressed with `compression` if set.
+macro_rules! grpc_service {
+    ($service:expr, $compression:expr) => {{
+        let service = $service
+            .accept_compressed(CompressionEncoding::Gzip)
+            .max_decoding_message_size(usize::MAX);
+        match $compression {
+            Some(encoding) => service.send_compressed(encoding),
+            None => service,
+        }
+    }};
+}
+
 pub fn init(
     dispatcher: Arc<Dispatcher>,
     telemetry_collector: Arc<parking_lot::Mutex<TonicTelemetryCollector>>,
+    usage_meter: Option<Arc<UsageMeter>>,
     settings: Settings,
     grpc_port: u16,
     runtime: Handle,
 ) -> io::Result<()> {
+    let http_client = HttpClient::from_settings(&settings)?;
+    let compression = settings.service.grpc_compression.encoding();
+
     runtime.block_on(async {
         let socket =
             SocketAddr::from((settings.service.host.parse::<IpAddr>().unwrap(), grpc_port));
@@ -157,7 +190,10 @@ pub fn init(
         let health_service = HealthService::default();
         let collections_service = CollectionsService::new(dispatcher.clone());
         let points_service = PointsService::new(dispatcher.clone());
+        let flight_service = FlightPointsService::new(dispatcher.clone());
         let snapshot_service = SnapshotsService::new(dispatcher.clone());
+        let shard_snapshots_service =
+            ShardSnapshotsService::new(dispatcher.toc().clone(), http_client);
 
         // Only advertise the public services. By default, all services in QDRANT_DESCRIPTOR_SET
         // will be advertised, so explicitly list the services to be included.
@@ -166,6 +202,7 @@ pub fn init(
             .with_service_name("qdrant.Collections")
             .with_service_name("qdrant.Points")
             .with_service_name("qdrant.Snapshots")
+            .with_service_name("qdrant.ShardSnapshots")
             .with_service_name("qdrant.Qdrant")
             .with_service_name("grpc.health.v1.Health")
             .build()
@@ -173,19 +210,19 @@ pub fn init(
 
         log::info!("Qdrant gRPC listening on {}", grpc_port);
 
-        let mut server = Server::builder();
+        let tls_server_config = if settings.service.enable_tls {
+            log::info!(
+                "TLS enabled for gRPC API (TTL: {})",
+                cert_ttl_display(&settings)
+            );
 
-        if settings.service.enable_tls {
-            log::info!("TLS enabled for gRPC API (TTL not supported)");
-
-            let tls_server_config = helpers::load_tls_external_server_config(settings.tls()?)?;
-
-            server = server
-                .tls_config(tls_server_config)
-                .map_err(helpers::tonic_error_to_io_error)?;
+            let config = certificate_helpers::tonic_tls_server_config(&settings, false)
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+            Some(config)
         } else {
             log::info!("TLS disabled for gRPC API");
-        }
+            None
+        };
 
         // The stack of middleware that our service will be wrapped in
         let middleware_layer = tower::ServiceBuilder::new()
@@ -196,46 +233,53 @@ pub fn init(
             .option_layer({
                 AuthKeys::try_create(&settings.service).map(api_key::ApiKeyMiddlewareLayer::new)
             })
+            .option_layer(usage_meter.map(usage_metering::UsageMeteringLayer::new))
             .into_inner();
 
-        server
+        let router = Server::builder()
             .layer(middleware_layer)
             .add_service(reflection_service)
-            .add_service(
-                QdrantServer::new(qdrant_service)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
-            )
-            .add_service(
-                CollectionsServer::new(collections_service)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
-            )
-            .add_service(
-                PointsServer::new(points_service)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
-            )
-            .add_service(
-                SnapshotsServer::new(snapshot_service)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
-            )
-            .add_service(
-                HealthServer::new(health_service)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
-            )
-            .serve_with_shutdown(socket, async {
-                wait_stop_signal("gRPC service").await;
-            })
-            .await
-            .map_err(helpers::tonic_error_to_io_error)
+            .add_service(grpc_service!(
+                QdrantServer::new(qdrant_service),
+                compression
+            ))
+            .add_service(grpc_service!(
+                CollectionsServer::new(collections_service),
+                compression
+            ))
+            .add_service(grpc_service!(
+                PointsServer::new(points_service),
+                compression
+            ))
+            .add_service(grpc_service!(
+                FlightServiceServer::new(flight_service),
+                compression
+            ))
+            .add_service(grpc_service!(
+                SnapshotsServer::new(snapshot_service),
+                compression
+            ))
+            .add_service(grpc_service!(
+                ShardSnapshotsServer::new(shard_snapshots_service),
+                compression
+            ))
+            .add_service(grpc_service!(
+                HealthServer::new(health_service),
+                compression
+            ));
+
+        let shutdown = wait_stop_signal("gRPC service");
+
+        // With TLS, accept connections ourselves, so that certificates are reloaded on TTL
+        match tls_server_config {
+            Some(config) => {
+                router
+                    .serve_with_incoming_shutdown(tls::incoming(socket, config).await?, shutdown)
+                    .await
+            }
+            None => router.serve_with_shutdown(socket, shutdown).await,
+        }
+        .map_err(helpers::tonic_error_to_io_error)
     })?;
 
     Ok(())
@@ -249,7 +293,7 @@ pub fn init_internal(
     settings: Settings,
     host: String,
     internal_grpc_port: u16,
-    tls_config: Option<ServerTlsConfig>,
+    tls_config: Option<rustls::ServerConfig>,
     to_consensus: tokio::sync::mpsc::Sender<crate::consensus::Message>,
     runtime: Handle,
 ) -> std::io::Result<()> {
@@ -258,6 +302,8 @@ pub fn init_internal(
     use crate::tonic::api::raft_api::RaftService;
 
     let http_client = HttpClient::from_settings(&settings)?;
+    let cert_ttl = cert_ttl_display(&settings);
+    let compression = settings.cluster.p2p.compression.encoding();
 
     runtime
         .block_on(async {
@@ -273,7 +319,7 @@ pub fn init_internal(
 
             log::debug!("Qdrant internal gRPC listening on {}", internal_grpc_port);
 
-            let mut server = Server::builder()
+            let server = Server::builder()
                 // Internally use a high limit for pending accept streams.
                 // We can have a huge number of reset/dropped HTTP2 streams in our internal
                 // communication when there are a lot of clients dropping connections. This
@@ -283,10 +329,8 @@ pub fn init_internal(
                 // More info: <https://github.com/qdrant/qdrant/issues/1907>
                 .http2_max_pending_accept_reset_streams(Some(1024));
 
-            if let Some(config) = tls_config {
-                log::info!("TLS enabled for internal gRPC API (TTL not supported)");
-
-                server = server.tls_config(config)?;
+            if tls_config.is_some() {
+                log::info!("TLS enabled for internal gRPC API (TTL: {cert_ttl})");
             } else {
                 log::info!("TLS disabled for internal gRPC API");
             };
@@ -299,48 +343,45 @@ pub fn init_internal(
                 ))
                 .into_inner();
 
-            server
+            let router = server
                 .layer(middleware_layer)
-                .add_service(
-                    QdrantServer::new(qdrant_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    QdrantInternalServer::new(qdrant_internal_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    CollectionsInternalServer::new(collections_internal_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    PointsInternalServer::new(points_internal_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    ShardSnapshotsServer::new(shard_snapshots_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    RaftServer::new(raft_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .serve_with_shutdown(socket, async {
-                    wait_stop_signal("internal gRPC").await;
-                })
-                .await
+                .add_service(grpc_service!(
+                    QdrantServer::new(qdrant_service),
+                    compression
+                ))
+                .add_service(grpc_service!(
+                    QdrantInternalServer::new(qdrant_internal_service),
+                    compression
+                ))
+                .add_service(grpc_service!(
+                    CollectionsInternalServer::new(collections_internal_service),
+                    compression
+                ))
+                .add_service(grpc_service!(
+                    PointsInternalServer::new(points_internal_service),
+                    compression
+                ))
+                .add_service(grpc_service!(
+                    ShardSnapshotsServer::new(shard_snapshots_service),
+                    compression
+                ))
+                .add_service(grpc_service!(RaftServer::new(raft_service), compression));
+
+            let shutdown = wait_stop_signal("internal gRPC");
+
+            // With TLS, accept connections ourselves, so that certificates are reloaded on TTL
+            match tls_config {
+                Some(config) => {
+                    router
+                        .serve_with_incoming_shutdown(
+                            tls::incoming(socket, config).await?,
+                            shutdown,
+                        )
+                        .await
+                }
+                None => router.serve_with_shutdown(socket, shutdown).await,
+            }
+            .map_err(helpers::tonic_error_to_io_error)
         })
         .unwrap();
     Ok(())
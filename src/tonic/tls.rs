@@ -0,0 +1,59 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::Stream;
+use rustls::ServerConfig;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// Max time for a client to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before accepting new connections after an error, e.g. if there are too many open files
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_secs(1);
+
+/// Accept TLS connections on the given address for a tonic server.
+///
+/// Unlike `Server::tls_config`, the certificate is resolved for each handshake by `config`,
+/// so it is reloaded from disk once its TTL is reached, without restarting the server.
+pub async fn incoming(
+    addr: SocketAddr,
+    config: ServerConfig,
+) -> io::Result<impl Stream<Item = io::Result<TlsStream<TcpStream>>>> {
+    let listener = TcpListener::bind(addr).await?;
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+    let (sender, mut receiver) = mpsc::channel(128);
+
+    tokio::spawn(async move {
+        // Stops once the server is shut down and the receiver is dropped
+        while !sender.is_closed() {
+            let (stream, peer_addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    log::warn!("Failed to accept gRPC connection: {err}");
+                    tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                    continue;
+                }
+            };
+
+            // Handshakes are done concurrently, so a slow client doesn't block others
+            let acceptor = acceptor.clone();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => {
+                        let _ = sender.send(Ok(stream)).await;
+                    }
+                    Ok(Err(err)) => log::debug!("TLS handshake with {peer_addr} failed: {err}"),
+                    Err(_) => log::debug!("TLS handshake with {peer_addr} timed out"),
+                }
+            });
+        }
+    });
+
+    Ok(futures::stream::poll_fn(move |cx| receiver.poll_recv(cx)))
+}
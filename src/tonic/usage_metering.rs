@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures_util::future::BoxFuture;
+use tonic::body::BoxBody;
+use tonic::codegen::Body as _;
+use tower::Service;
+use tower_layer::Layer;
+
+use crate::common::usage_metering::UsageMeter;
+use crate::tonic::api_key::{collection_name, is_collection_request, request_api_key};
+
+/// Client streaming RPC, its requests are not read in advance
+const STREAMING_RPC_PATH: &str = "/arrow.flight.protocol.FlightService/DoPut";
+
+/// Accounts requests to the API key they were made with
+///
+/// Must run after the API key middleware, so only authorized requests are accounted.
+/// Requests are read completely to measure their size and to find the collection they are
+/// addressed to, except for streamed requests, which are accounted without both.
+#[derive(Clone)]
+pub struct UsageMeteringService<T> {
+    service: T,
+    usage_meter: Arc<UsageMeter>,
+}
+
+#[derive(Clone)]
+pub struct UsageMeteringLayer {
+    usage_meter: Arc<UsageMeter>,
+}
+
+impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for UsageMeteringService<S>
+where
+    S: Service<
+            tonic::codegen::http::Request<tonic::transport::Body>,
+            Response = tonic::codegen::http::Response<BoxBody>,
+        > + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = tonic::codegen::http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(
+        &mut self,
+        request: tonic::codegen::http::Request<tonic::transport::Body>,
+    ) -> Self::Future {
+        let api_key = request_api_key(&request);
+        let usage_meter = self.usage_meter.clone();
+        let clone = self.service.clone();
+        let mut service = std::mem::replace(&mut self.service, clone);
+
+        Box::pin(async move {
+            let instant = Instant::now();
+            let (request, key, bytes_read) = if request.uri().path() == STREAMING_RPC_PATH {
+                (request, usage_meter.usage_key(api_key.as_deref(), None), 0)
+            } else {
+                let is_collection_request = is_collection_request(&request);
+                let (parts, mut body) = request.into_parts();
+                let mut message = Vec::new();
+                while let Some(chunk) = body.data().await {
+                    match chunk {
+                        Ok(chunk) => message.extend_from_slice(&chunk),
+                        // Let the service handle the broken request
+                        Err(_) => break,
+                    }
+                }
+
+                let collection = if is_collection_request {
+                    collection_name(&message)
+                } else {
+                    None
+                };
+                let key = usage_meter.usage_key(api_key.as_deref(), collection);
+                let bytes_read = message.len() as u64;
+                let request = tonic::codegen::http::Request::from_parts(parts, message.into());
+                (request, key, bytes_read)
+            };
+
+            let response = service.call(request).await?;
+            usage_meter.add_request(key.clone(), bytes_read, 0, instant.elapsed());
+
+            // Responses might be streamed, their size is accounted while they are sent
+            Ok(response.map(|body| {
+                body.map_data(move |chunk| {
+                    usage_meter.add_bytes_written(&key, chunk.len() as u64);
+                    chunk
+                })
+                .boxed_unsync()
+            }))
+        })
+    }
+}
+
+impl UsageMeteringLayer {
+    pub fn new(usage_meter: Arc<UsageMeter>) -> Self {
+        Self { usage_meter }
+    }
+}
+
+impl<S> Layer<S> for UsageMeteringLayer {
+    type Service = UsageMeteringService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        UsageMeteringService {
+            service,
+            usage_meter: self.usage_meter.clone(),
+        }
+    }
+}
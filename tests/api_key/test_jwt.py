@@ -0,0 +1,138 @@
+# Tokens restricted to some collections must not reach other collections through the request body
+import os
+
+import grpc
+import jwt
+import pytest
+import requests
+from qdrant_client import QdrantClient, grpc as qgrpc
+
+QDRANT_HOST = os.environ.get("QDRANT_HOST", "localhost")
+REST_URL = f"http://{QDRANT_HOST}:6333"
+CLIENT = QdrantClient(prefer_grpc=True, timeout=3.0, host=QDRANT_HOST)
+
+ADMIN_KEY = "my-secret"
+JWT_SECRET = "my-jwt-secret"
+
+TENANT_COLLECTION = "jwt_tenant_collection"
+OTHER_COLLECTION = "jwt_other_collection"
+
+TENANT_TOKEN = jwt.encode({"collections": [TENANT_COLLECTION]}, JWT_SECRET, algorithm="HS256")
+FILTERED_TOKEN = jwt.encode(
+    {
+        "collections": [TENANT_COLLECTION],
+        "filter": {"must": [{"key": "group", "match": {"value": 1}}]},
+    },
+    JWT_SECRET,
+    algorithm="HS256",
+)
+
+
+def rest(method, path, data=None, key=ADMIN_KEY):
+    return requests.request(
+        method=method, url=f"{REST_URL}{path}", headers={"api-key": key}, json=data
+    )
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    for collection_name in (TENANT_COLLECTION, OTHER_COLLECTION):
+        rest("DELETE", f"/collections/{collection_name}")
+        rest(
+            "PUT",
+            f"/collections/{collection_name}",
+            {"vectors": {"size": 4, "distance": "Dot"}},
+        ).raise_for_status()
+        rest(
+            "PUT",
+            f"/collections/{collection_name}/points?wait=true",
+            {
+                "points": [
+                    {"id": 1, "vector": [0.05, 0.61, 0.76, 0.74], "payload": {"group": 1}},
+                    {"id": 2, "vector": [0.19, 0.81, 0.75, 0.11], "payload": {"group": 2}},
+                ]
+            },
+        ).raise_for_status()
+    yield
+    for collection_name in (TENANT_COLLECTION, OTHER_COLLECTION):
+        rest("DELETE", f"/collections/{collection_name}")
+
+
+SEARCH_GROUPS = {
+    "vector": [0.2, 0.1, 0.9, 0.7],
+    "group_by": "group",
+    "group_size": 1,
+    "limit": 2,
+}
+
+
+def test_rest_with_lookup():
+    path = f"/collections/{TENANT_COLLECTION}/points/search/groups"
+
+    response = rest("POST", path, {**SEARCH_GROUPS, "with_lookup": TENANT_COLLECTION}, TENANT_TOKEN)
+    assert response.ok, response.text
+
+    response = rest("POST", path, {**SEARCH_GROUPS, "with_lookup": OTHER_COLLECTION}, TENANT_TOKEN)
+    assert response.status_code == 403, response.text
+
+
+def test_rest_lookup_from():
+    path = f"/collections/{TENANT_COLLECTION}/points/recommend"
+    request = {"positive": [1], "limit": 3, "lookup_from": {"collection": OTHER_COLLECTION}}
+
+    response = rest("POST", path, request, TENANT_TOKEN)
+    assert response.status_code == 403, response.text
+
+
+def test_rest_filtered_token_with_lookup():
+    path = f"/collections/{TENANT_COLLECTION}/points/search/groups"
+
+    response = rest("POST", path, SEARCH_GROUPS, FILTERED_TOKEN)
+    assert response.ok, response.text
+
+    # Looked up points can't be restricted by the filter of the token
+    request = {**SEARCH_GROUPS, "with_lookup": TENANT_COLLECTION}
+    response = rest("POST", path, request, FILTERED_TOKEN)
+    assert response.status_code == 403, response.text
+
+
+def search_groups(lookup_collection=None):
+    return qgrpc.SearchPointGroups(
+        collection_name=TENANT_COLLECTION,
+        vector=[0.2, 0.1, 0.9, 0.7],
+        group_by="group",
+        group_size=1,
+        limit=2,
+        with_lookup=qgrpc.WithLookup(collection=lookup_collection) if lookup_collection else None,
+    )
+
+
+def assert_grpc_forbidden(stub, request, token):
+    with pytest.raises(grpc.RpcError) as error:
+        stub(request, metadata=(("api-key", token),), timeout=1.0)
+    assert error.value.code() == grpc.StatusCode.PERMISSION_DENIED
+
+
+def test_grpc_with_lookup():
+    CLIENT.grpc_points.SearchGroups(
+        search_groups(TENANT_COLLECTION), metadata=(("api-key", TENANT_TOKEN),), timeout=1.0
+    )
+    assert_grpc_forbidden(
+        CLIENT.grpc_points.SearchGroups, search_groups(OTHER_COLLECTION), TENANT_TOKEN
+    )
+    assert_grpc_forbidden(
+        CLIENT.grpc_points.SearchGroups, search_groups(TENANT_COLLECTION), FILTERED_TOKEN
+    )
+
+
+def test_grpc_lookup_from():
+    assert_grpc_forbidden(
+        CLIENT.grpc_points.Recommend,
+        qgrpc.RecommendPoints(
+            collection_name=TENANT_COLLECTION,
+            positive=[qgrpc.PointId(num=1)],
+            limit=3,
+            lookup_from=qgrpc.LookupLocation(collection_name=OTHER_COLLECTION),
+        ),
+        TENANT_TOKEN,
+    )
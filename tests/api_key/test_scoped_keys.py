@@ -0,0 +1,258 @@
+# Keys restricted to some collections must not reach other collections through the request body
+import os
+
+import grpc
+import pytest
+import requests
+from qdrant_client import QdrantClient, grpc as qgrpc
+
+QDRANT_HOST = os.environ.get("QDRANT_HOST", "localhost")
+REST_URL = f"http://{QDRANT_HOST}:6333"
+CLIENT = QdrantClient(prefer_grpc=True, timeout=3.0, host=QDRANT_HOST)
+
+ADMIN_KEY = "my-secret"
+TENANT_KEY = "my-tenant-secret"
+
+TENANT_COLLECTION = "tenant_collection"
+OTHER_COLLECTION = "other_collection"
+LOOKUP_FROM_OTHER = {"lookup_from": {"collection": OTHER_COLLECTION}}
+
+
+def rest(method, path, data=None, key=ADMIN_KEY):
+    return requests.request(
+        method=method, url=f"{REST_URL}{path}", headers={"api-key": key}, json=data
+    )
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    for collection_name in (TENANT_COLLECTION, OTHER_COLLECTION):
+        rest("DELETE", f"/collections/{collection_name}")
+        rest(
+            "PUT",
+            f"/collections/{collection_name}",
+            {"vectors": {"size": 4, "distance": "Dot"}},
+        ).raise_for_status()
+        rest(
+            "PUT",
+            f"/collections/{collection_name}/points?wait=true",
+            {
+                "points": [
+                    {"id": 1, "vector": [0.05, 0.61, 0.76, 0.74], "payload": {"group": 1}},
+                    {"id": 2, "vector": [0.19, 0.81, 0.75, 0.11], "payload": {"group": 2}},
+                    {"id": 3, "vector": [0.36, 0.55, 0.47, 0.94], "payload": {"group": 1}},
+                ]
+            },
+        ).raise_for_status()
+    yield
+    for collection_name in (TENANT_COLLECTION, OTHER_COLLECTION, "tenant_clone"):
+        rest("DELETE", f"/collections/{collection_name}")
+
+
+def assert_rest_forbidden(method, path, data):
+    response = rest(method, path, data, key=TENANT_KEY)
+    assert response.status_code == 403, response.text
+
+
+def assert_rest_allowed(method, path, data):
+    response = rest(method, path, data, key=TENANT_KEY)
+    assert response.ok, response.text
+
+
+def test_rest_search_groups_with_lookup():
+    request = {
+        "vector": [0.2, 0.1, 0.9, 0.7],
+        "group_by": "group",
+        "group_size": 1,
+        "limit": 2,
+    }
+    path = f"/collections/{TENANT_COLLECTION}/points/search/groups"
+    assert_rest_allowed("POST", path, {**request, "with_lookup": TENANT_COLLECTION})
+    assert_rest_forbidden("POST", path, {**request, "with_lookup": OTHER_COLLECTION})
+    assert_rest_forbidden(
+        "POST", path, {**request, "with_lookup": {"collection": OTHER_COLLECTION}}
+    )
+
+
+def test_rest_recommend_lookup_from():
+    request = {"positive": [1], "limit": 3}
+    path = f"/collections/{TENANT_COLLECTION}/points/recommend"
+    assert_rest_allowed("POST", path, request)
+    assert_rest_forbidden("POST", path, {**request, **LOOKUP_FROM_OTHER})
+
+
+def test_rest_recommend_batch_lookup_from():
+    path = f"/collections/{TENANT_COLLECTION}/points/recommend/batch"
+    assert_rest_forbidden(
+        "POST",
+        path,
+        {
+            "searches": [
+                {"positive": [1], "limit": 3},
+                {"positive": [1], "limit": 3, **LOOKUP_FROM_OTHER},
+            ]
+        },
+    )
+
+
+def test_rest_recommend_groups_lookup():
+    request = {"positive": [1], "group_by": "group", "group_size": 1, "limit": 2}
+    path = f"/collections/{TENANT_COLLECTION}/points/recommend/groups"
+    assert_rest_allowed("POST", path, request)
+    assert_rest_forbidden("POST", path, {**request, **LOOKUP_FROM_OTHER})
+    assert_rest_forbidden("POST", path, {**request, "with_lookup": OTHER_COLLECTION})
+
+
+def test_rest_discover_lookup_from():
+    request = {"target": 1, "limit": 3}
+    path = f"/collections/{TENANT_COLLECTION}/points/discover"
+    assert_rest_allowed("POST", path, request)
+    assert_rest_forbidden("POST", path, {**request, **LOOKUP_FROM_OTHER})
+
+    path = f"/collections/{TENANT_COLLECTION}/points/discover/batch"
+    assert_rest_forbidden(
+        "POST",
+        path,
+        {"searches": [{**request, **LOOKUP_FROM_OTHER}]},
+    )
+
+
+def test_rest_copy_points():
+    path = f"/collections/{TENANT_COLLECTION}/points/copy"
+    assert_rest_forbidden("POST", path, {"target_collection": OTHER_COLLECTION})
+
+
+def test_rest_clone_collection():
+    path = f"/collections/{TENANT_COLLECTION}/clone"
+    assert_rest_forbidden("POST", path, {"to": "tenant_clone"})
+
+
+def test_rest_create_collection_init_from():
+    path = f"/collections/{TENANT_COLLECTION}"
+    assert_rest_forbidden(
+        "PUT",
+        path,
+        {"vectors": {"size": 4, "distance": "Dot"}, "init_from": {"collection": OTHER_COLLECTION}},
+    )
+
+
+def test_rest_update_aliases():
+    assert_rest_forbidden(
+        "POST",
+        "/collections/aliases",
+        {
+            "actions": [
+                {
+                    "create_alias": {
+                        "collection_name": OTHER_COLLECTION,
+                        "alias_name": TENANT_COLLECTION,
+                    }
+                }
+            ]
+        },
+    )
+
+
+def assert_grpc_forbidden(stub, request):
+    with pytest.raises(grpc.RpcError) as error:
+        stub(request, metadata=(("api-key", TENANT_KEY),), timeout=1.0)
+    assert error.value.code() == grpc.StatusCode.PERMISSION_DENIED
+
+
+def assert_grpc_allowed(stub, request):
+    stub(request, metadata=(("api-key", TENANT_KEY),), timeout=1.0)
+
+
+def test_grpc_search_groups_with_lookup():
+    def request(lookup_collection):
+        return qgrpc.SearchPointGroups(
+            collection_name=TENANT_COLLECTION,
+            vector=[0.2, 0.1, 0.9, 0.7],
+            group_by="group",
+            group_size=1,
+            limit=2,
+            with_lookup=qgrpc.WithLookup(collection=lookup_collection),
+        )
+
+    assert_grpc_allowed(CLIENT.grpc_points.SearchGroups, request(TENANT_COLLECTION))
+    assert_grpc_forbidden(CLIENT.grpc_points.SearchGroups, request(OTHER_COLLECTION))
+
+
+def recommend_points(lookup_from=None):
+    return qgrpc.RecommendPoints(
+        collection_name=TENANT_COLLECTION,
+        positive=[qgrpc.PointId(num=1)],
+        limit=3,
+        lookup_from=lookup_from,
+    )
+
+
+def test_grpc_recommend_lookup_from():
+    lookup_from = qgrpc.LookupLocation(collection_name=OTHER_COLLECTION)
+
+    assert_grpc_allowed(CLIENT.grpc_points.Recommend, recommend_points())
+    assert_grpc_forbidden(CLIENT.grpc_points.Recommend, recommend_points(lookup_from))
+    assert_grpc_forbidden(
+        CLIENT.grpc_points.RecommendBatch,
+        qgrpc.RecommendBatchPoints(
+            collection_name=TENANT_COLLECTION,
+            recommend_points=[recommend_points(), recommend_points(lookup_from)],
+        ),
+    )
+
+
+def test_grpc_recommend_groups_lookup():
+    def request(lookup_from=None, with_lookup=None):
+        return qgrpc.RecommendPointGroups(
+            collection_name=TENANT_COLLECTION,
+            positive=[qgrpc.PointId(num=1)],
+            group_by="group",
+            group_size=1,
+            limit=2,
+            lookup_from=lookup_from,
+            with_lookup=with_lookup,
+        )
+
+    assert_grpc_allowed(CLIENT.grpc_points.RecommendGroups, request())
+    assert_grpc_forbidden(
+        CLIENT.grpc_points.RecommendGroups,
+        request(lookup_from=qgrpc.LookupLocation(collection_name=OTHER_COLLECTION)),
+    )
+    assert_grpc_forbidden(
+        CLIENT.grpc_points.RecommendGroups,
+        request(with_lookup=qgrpc.WithLookup(collection=OTHER_COLLECTION)),
+    )
+
+
+def test_grpc_discover_lookup_from():
+    def request(lookup_from=None):
+        return qgrpc.DiscoverPoints(
+            collection_name=TENANT_COLLECTION,
+            target=qgrpc.TargetVector(single=qgrpc.VectorExample(id=qgrpc.PointId(num=1))),
+            limit=3,
+            lookup_from=lookup_from,
+        )
+
+    lookup_from = qgrpc.LookupLocation(collection_name=OTHER_COLLECTION)
+    assert_grpc_allowed(CLIENT.grpc_points.Discover, request())
+    assert_grpc_forbidden(CLIENT.grpc_points.Discover, request(lookup_from))
+    assert_grpc_forbidden(
+        CLIENT.grpc_points.DiscoverBatch,
+        qgrpc.DiscoverBatchPoints(
+            collection_name=TENANT_COLLECTION,
+            discover_points=[request(lookup_from)],
+        ),
+    )
+
+
+def test_grpc_create_collection_init_from():
+    assert_grpc_forbidden(
+        CLIENT.grpc_collections.Create,
+        qgrpc.CreateCollection(
+            collection_name=TENANT_COLLECTION,
+            vectors_config=qgrpc.VectorsConfig(
+                params=qgrpc.VectorParams(size=4, distance=qgrpc.Distance.Dot)
+            ),
+            init_from_collection=OTHER_COLLECTION,
+        ),
+    )
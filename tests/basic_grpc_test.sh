@@ -58,6 +58,23 @@ $docker_grpcurl -d '{
   "limit": 3
 }' $QDRANT_HOST qdrant.Points/Search
 
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "vector": [0.2,0.1,0.9,0.7],
+  "limit": 3,
+  "compact_response": true
+}' $QDRANT_HOST qdrant.Points/Search
+
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "search_points": {
+    "collection_name": "test_collection",
+    "vector": [0.2,0.1,0.9,0.7],
+    "limit": 5
+  },
+  "chunk_size": 2
+}' $QDRANT_HOST qdrant.Points/SearchStream
+
 $docker_grpcurl -d '{
   "collection_name": "test_collection",
   "filter": {
@@ -94,6 +111,11 @@ $docker_grpcurl -d '{
   }
 }' $QDRANT_HOST qdrant.Points/Scroll
 
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "limit": 2
+}' $QDRANT_HOST qdrant.Points/ScrollStream
+
 $docker_grpcurl -d '{
   "collection_name": "test_collection",
   "with_vectors": {"enable": true},
@@ -106,6 +128,17 @@ $docker_grpcurl -d '{
   "negative": [{ "num": 2 }]
 }' $QDRANT_HOST qdrant.Points/Recommend
 
+# snapshot a single shard
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "shard_id": 0
+}' $QDRANT_HOST qdrant.ShardSnapshots/Create
+
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "shard_id": 0
+}' $QDRANT_HOST qdrant.ShardSnapshots/List
+
 # create alias
 $docker_grpcurl -d '{
   "actions": [
@@ -193,6 +226,7 @@ $docker_grpcurl $QDRANT_HOST describe
 $docker_grpcurl $QDRANT_HOST describe qdrant.Collections
 $docker_grpcurl $QDRANT_HOST describe qdrant.Points
 $docker_grpcurl $QDRANT_HOST describe qdrant.Snapshots
+$docker_grpcurl $QDRANT_HOST describe qdrant.ShardSnapshots
 $docker_grpcurl $QDRANT_HOST describe qdrant.Qdrant
 $docker_grpcurl $QDRANT_HOST describe grpc.health.v1.Health
 
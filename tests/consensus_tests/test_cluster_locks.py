@@ -0,0 +1,59 @@
+import pathlib
+
+from .fixtures import create_collection, upsert_random_points, random_dense_vector
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 3
+N_SHARDS = 3
+COLLECTION_NAME = "test_collection"
+
+
+def set_cluster_locks(peer_url, write, error_message=None):
+    r = requests.post(f"{peer_url}/cluster/locks", json={
+        "write": write,
+        "error_message": error_message,
+    })
+    assert_http_ok(r)
+    return r.json()["result"]
+
+
+def test_cluster_write_lock(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_urls, peer_dirs, bootstrap_url = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_urls[0], shard_number=N_SHARDS, replication_factor=2)
+    wait_collection_exists_and_active_on_all_peers(
+        collection_name=COLLECTION_NAME,
+        peer_api_uris=peer_urls,
+    )
+    upsert_random_points(peer_urls[0], 100)
+
+    previous_locks = set_cluster_locks(peer_urls[0], True, "Incident in progress")
+    assert previous_locks["write"] is False
+    wait_for_same_commit(peer_urls)
+
+    # Writes are rejected on every peer, reads are served
+    for peer_url in peer_urls:
+        r = requests.get(f"{peer_url}/cluster/locks")
+        assert_http_ok(r)
+        assert r.json()["result"]["write"] is True
+
+        r = requests.put(
+            f"{peer_url}/collections/{COLLECTION_NAME}/points?wait=true", json={
+                "points": [{"id": 1000, "vector": {"": random_dense_vector()}}]
+            })
+        assert r.status_code == 403
+        assert "Incident in progress" in r.json()["status"]["error"]
+
+        r = requests.post(f"{peer_url}/collections/{COLLECTION_NAME}/points/count", json={"exact": True})
+        assert_http_ok(r)
+        assert r.json()["result"]["count"] == 100
+
+    previous_locks = set_cluster_locks(peer_urls[1], False)
+    assert previous_locks["write"] is True
+    wait_for_same_commit(peer_urls)
+
+    for i, peer_url in enumerate(peer_urls):
+        upsert_random_points(peer_url, 10, offset=100 + i * 10)
@@ -217,3 +217,157 @@ def test_shard_consistency(tmp_path: pathlib.Path):
     assert len(result) == 6
     for point in result:
         assert point["shard_key"] in ["dogs", "birds"]
+
+
+def split_shard(
+        peer_url,
+        collection,
+        shard_key,
+        shard_number=None,
+        timeout=10
+):
+    r_batch = requests.post(
+        f"{peer_url}/collections/{collection}/shards/split?timeout={timeout}",
+        json={
+            "shard_key": shard_key,
+            "shards_number": shard_number,
+        }
+    )
+    assert_http_ok(r_batch)
+
+
+def check_shard_key_ring_size(peer_url, collection, shard_key, expected_size) -> bool:
+    r = requests.get(f"{peer_url}/collections/{collection}/cluster/distribution")
+    assert_http_ok(r)
+    for ring in r.json()["result"]["rings"]:
+        if ring.get("shard_key") == shard_key:
+            return len(ring["shards"]) == expected_size
+    return False
+
+
+def test_split_shard_key(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, 1)
+
+    create_collection_with_custom_sharding(peer_api_uris[0], shard_number=1, replication_factor=1)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+
+    create_shard(peer_api_uris[0], COLLECTION_NAME, shard_key="cats", shard_number=1)
+
+    num_points = 500
+    r = requests.put(
+        f"{peer_api_uris[0]}/collections/{COLLECTION_NAME}/points?wait=true", json={
+            "shard_key": "cats",
+            "points": [
+                {"id": i, "vector": [0.29, 0.81, 0.75, 0.11], "payload": {"idx": i}}
+                for i in range(num_points)
+            ]
+        })
+    assert_http_ok(r)
+
+    split_shard(peer_api_uris[0], COLLECTION_NAME, shard_key="cats", shard_number=1)
+
+    # Split is finished, when the new shard is added to the hash ring of the key
+    wait_for(check_shard_key_ring_size, peer_api_uris[0], COLLECTION_NAME, "cats", 2)
+
+    r = requests.post(
+        f"{peer_api_uris[0]}/collections/{COLLECTION_NAME}/points/count",
+        json={
+            "exact": True,
+            "shard_key": "cats",
+        }
+    )
+    assert_http_ok(r)
+    assert r.json()["result"]["count"] == num_points
+
+    # Points are moved, not copied
+    local_shards = get_collection_cluster_info(peer_api_uris[0], COLLECTION_NAME)["local_shards"]
+    assert len(local_shards) == 2
+    assert sum(shard["points_count"] for shard in local_shards) == num_points
+    assert all(shard["points_count"] > 0 for shard in local_shards)
+
+    # Points of the key are routed to both shards after the split
+    r = requests.post(
+        f"{peer_api_uris[0]}/collections/{COLLECTION_NAME}/points/scroll",
+        json={
+            "shard_key": "cats",
+            "limit": num_points + 1,
+        }
+    )
+    assert_http_ok(r)
+    assert len(r.json()["result"]["points"]) == num_points
+
+
+def scale_shard_replicas(
+        peer_url,
+        collection,
+        shard_key,
+        replication_factor,
+        timeout=10
+):
+    r_batch = requests.post(
+        f"{peer_url}/collections/{collection}/cluster?timeout={timeout}",
+        json={
+            "scale_shard_replicas": {
+                "shard_key": shard_key,
+                "replication_factor": replication_factor,
+            }
+        }
+    )
+    assert_http_ok(r_batch)
+
+
+def check_active_replicas_count(peer_url, collection, shard_id, expected_count) -> bool:
+    cluster_info = get_collection_cluster_info(peer_url, collection)
+    replicas = cluster_info["local_shards"] + cluster_info["remote_shards"]
+    active = [
+        replica for replica in replicas
+        if replica["shard_id"] == shard_id and replica["state"] == "Active"
+    ]
+    return len(active) == expected_count
+
+
+def test_scale_shard_key_replicas(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection_with_custom_sharding(peer_api_uris[0], shard_number=1, replication_factor=1)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+
+    create_shard(peer_api_uris[0], COLLECTION_NAME, shard_key="cats", shard_number=1, replication_factor=1)
+    create_shard(peer_api_uris[0], COLLECTION_NAME, shard_key="dogs", shard_number=1, replication_factor=1)
+
+    cluster_info = get_collection_cluster_info(peer_api_uris[0], COLLECTION_NAME)
+    cats_shard_id = next(
+        shard["shard_id"]
+        for shard in cluster_info["local_shards"] + cluster_info["remote_shards"]
+        if shard.get("shard_key") == "cats"
+    )
+
+    r = requests.put(
+        f"{peer_api_uris[0]}/collections/{COLLECTION_NAME}/points?wait=true", json={
+            "shard_key": "cats",
+            "points": [
+                {"id": i, "vector": [0.29, 0.81, 0.75, 0.11], "payload": {"idx": i}}
+                for i in range(100)
+            ]
+        })
+    assert_http_ok(r)
+
+    scale_shard_replicas(peer_api_uris[0], COLLECTION_NAME, shard_key="cats", replication_factor=N_PEERS)
+
+    # Missing replicas are added in background, one at a time
+    wait_for(check_active_replicas_count, peer_api_uris[0], COLLECTION_NAME, cats_shard_id, N_PEERS)
+
+    # Other keys keep the default number of replicas
+    cluster_info = get_collection_cluster_info(peer_api_uris[0], COLLECTION_NAME)
+    replicas = cluster_info["local_shards"] + cluster_info["remote_shards"]
+    assert len([replica for replica in replicas if replica.get("shard_key") == "dogs"]) == 1
+
+    # Every replica serves the data of the key
+    for peer_api_uri in peer_api_uris:
+        local_shards = get_collection_cluster_info(peer_api_uri, COLLECTION_NAME)["local_shards"]
+        cats_shard = next(shard for shard in local_shards if shard["shard_id"] == cats_shard_id)
+        assert cats_shard["points_count"] == 100
@@ -0,0 +1,64 @@
+import pathlib
+
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 2
+N_SHARDS = 3
+COLLECTION_NAME = "test_collection"
+
+
+def test_migrate_peers(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    old_peer_urls, _peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(old_peer_urls[0], shard_number=N_SHARDS, replication_factor=2)
+    wait_collection_exists_and_active_on_all_peers(
+        collection_name=COLLECTION_NAME,
+        peer_api_uris=old_peer_urls,
+    )
+    upsert_random_points(old_peer_urls[0], 100)
+
+    # Join new peers, e.g. running a new version
+    new_peer_urls = [
+        start_peer(make_peer_folder(tmp_path, N_PEERS + i), f"peer_1_{i}.log", bootstrap_uri)
+        for i in range(N_PEERS)
+    ]
+    leader = get_leader(old_peer_urls[0])
+    wait_for_uniform_cluster_status(old_peer_urls + new_peer_urls, leader)
+
+    old_peer_ids = [get_cluster_info(url)["peer_id"] for url in old_peer_urls]
+    new_peer_ids = [get_cluster_info(url)["peer_id"] for url in new_peer_urls]
+
+    # Peers can't be migrated to themselves
+    r = requests.post(f"{old_peer_urls[0]}/cluster/migrate", json={
+        "from_peers": old_peer_ids,
+        "to_peers": old_peer_ids[:1],
+    })
+    assert r.status_code == 400
+
+    r = requests.post(f"{old_peer_urls[0]}/cluster/migrate", json={
+        "from_peers": old_peer_ids,
+        "to_peers": new_peer_ids,
+    })
+    assert_http_ok(r)
+    wait_for_same_commit(old_peer_urls + new_peer_urls)
+
+    # All replicas are moved to the new peers
+    for peer_url in new_peer_urls:
+        cluster_info = get_collection_cluster_info(peer_url, COLLECTION_NAME)
+        assert len(cluster_info["local_shards"]) == N_SHARDS
+        assert all(shard["state"] == "Active" for shard in cluster_info["local_shards"])
+        assert all(shard["peer_id"] in new_peer_ids for shard in cluster_info["remote_shards"])
+
+    for peer_url in old_peer_urls:
+        cluster_info = get_collection_cluster_info(peer_url, COLLECTION_NAME)
+        assert len(cluster_info["local_shards"]) == 0
+
+    # Old peers have no shards left and can be removed without `force`
+    r = requests.delete(f"{new_peer_urls[0]}/cluster/peer/{old_peer_ids[-1]}")
+    assert_http_ok(r)
+
+    assert check_collection_points_count(new_peer_urls[0], COLLECTION_NAME, 100)
@@ -0,0 +1,45 @@
+import pathlib
+
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 3
+COLLECTION_NAME = "test_collection"
+
+
+def peer_uris(peer_url) -> dict:
+    peers = get_cluster_info(peer_url)["peers"]
+    return {int(peer_id): info["uri"].rstrip("/") for peer_id, info in peers.items()}
+
+
+def test_update_peer_uri(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_urls, peer_dirs, bootstrap_url = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_urls[0], shard_number=N_PEERS, replication_factor=2)
+    wait_collection_exists_and_active_on_all_peers(
+        collection_name=COLLECTION_NAME,
+        peer_api_uris=peer_urls,
+    )
+
+    # Same peer, reachable under another name
+    peer_id, old_uri = max(peer_uris(peer_urls[0]).items())
+    new_uri = old_uri.replace("127.0.0.1", "localhost")
+    assert new_uri != old_uri
+
+    r = requests.put(f"{peer_urls[0]}/cluster/peer/{peer_id}/uri", json={"uri": new_uri})
+    assert_http_ok(r)
+    wait_for_same_commit(peer_urls)
+
+    for peer_url in peer_urls:
+        assert peer_uris(peer_url)[peer_id] == new_uri
+
+    # Peer keeps its id and replicas and still takes part in the cluster
+    upsert_random_points(peer_urls[0], 100)
+    wait_for_all_replicas_active(peer_urls[0], COLLECTION_NAME)
+
+    # Unknown peers can't be updated
+    r = requests.put(f"{peer_urls[0]}/cluster/peer/{peer_id + 1000}/uri", json={"uri": new_uri})
+    assert r.status_code == 404
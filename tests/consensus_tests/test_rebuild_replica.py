@@ -0,0 +1,70 @@
+import pathlib
+
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 2
+COLLECTION_NAME = "test_collection"
+
+
+def rebuild_replica(peer_url, shard_id, peer_id):
+    return requests.post(
+        f"{peer_url}/collections/{COLLECTION_NAME}/cluster", json={
+            "rebuild_replica": {
+                "shard_id": shard_id,
+                "peer_id": peer_id,
+            }
+        })
+
+
+def test_rebuild_replica(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_urls, peer_dirs, bootstrap_url = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_urls[0], shard_number=1, replication_factor=N_PEERS)
+    wait_collection_exists_and_active_on_all_peers(
+        collection_name=COLLECTION_NAME,
+        peer_api_uris=peer_urls,
+    )
+
+    upsert_random_points(peer_urls[0], 100)
+
+    rebuilt_peer_id = get_cluster_info(peer_urls[1])["peer_id"]
+    r = rebuild_replica(peer_urls[0], 0, rebuilt_peer_id)
+    assert_http_ok(r)
+
+    # Replica is recovered from the other peer
+    wait_for_collection_shard_transfers_count(peer_urls[0], COLLECTION_NAME, 0)
+    wait_for_all_replicas_active(peer_urls[0], COLLECTION_NAME)
+
+    local_shard = check_collection_cluster(peer_urls[1], COLLECTION_NAME)
+    assert local_shard["state"] == "Active"
+    assert local_shard["points_count"] == 100
+
+
+def test_rebuild_last_active_replica(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_urls, peer_dirs, bootstrap_url = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_urls[0], shard_number=1, replication_factor=1)
+    wait_collection_exists_and_active_on_all_peers(
+        collection_name=COLLECTION_NAME,
+        peer_api_uris=peer_urls,
+    )
+
+    upsert_random_points(peer_urls[0], 100)
+
+    cluster_info = get_collection_cluster_info(peer_urls[0], COLLECTION_NAME)
+    if cluster_info["local_shards"]:
+        peer_id = cluster_info["peer_id"]
+    else:
+        peer_id = cluster_info["remote_shards"][0]["peer_id"]
+
+    # There is no other replica to recover the data from
+    r = rebuild_replica(peer_urls[0], 0, peer_id)
+    assert r.status_code == 400
+
+    wait_for_all_replicas_active(peer_urls[0], COLLECTION_NAME)
@@ -0,0 +1,51 @@
+import pathlib
+
+from .fixtures import upsert_random_points, create_collection
+from .utils import *
+
+N_PEERS = 3
+N_SHARDS = 2
+N_REPLICA = 3
+
+
+def get_local_checksums(peer_api_uri: str, collection_name: str) -> dict:
+    r = requests.get(
+        f"{peer_api_uri}/collections/{collection_name}/cluster",
+        params={"with_checksums": "true"},
+    )
+    assert_http_ok(r)
+    local_shards = r.json()["result"]["local_shards"]
+    return {shard["shard_id"]: shard["checksum"] for shard in local_shards}
+
+
+def test_shard_checksums(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, _peer_dirs, _bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(
+        peer_api_uris[0],
+        shard_number=N_SHARDS,
+        replication_factor=N_REPLICA,
+        write_consistency_factor=N_REPLICA,
+    )
+    wait_collection_exists_and_active_on_all_peers(
+        collection_name="test_collection",
+        peer_api_uris=peer_api_uris
+    )
+
+    # Checksums are not computed unless requested
+    cluster_info = get_collection_cluster_info(peer_api_uris[0], "test_collection")
+    assert all("checksum" not in shard for shard in cluster_info["local_shards"])
+
+    empty_checksums = get_local_checksums(peer_api_uris[0], "test_collection")
+
+    upsert_random_points(peer_api_uris[1], 100)
+
+    # All replicas have the same data
+    checksums = [get_local_checksums(uri, "test_collection") for uri in peer_api_uris]
+    assert len(checksums[0]) == N_SHARDS
+    for peer_checksums in checksums[1:]:
+        assert peer_checksums == checksums[0]
+
+    assert checksums[0] != empty_checksums
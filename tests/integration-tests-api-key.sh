@@ -11,7 +11,8 @@ export QDRANT__SERVICE__HOST="0.0.0.0"
 export QDRANT__SERVICE__API_KEY="my-secret"
 export QDRANT__SERVICE__READ_ONLY_API_KEY="my-ro-secret"
 
-./target/debug/qdrant &
+# The config adds keys, restricted to collections
+./target/debug/qdrant --config-path tests/api_key/config.yaml &
 
 #Capture PID of the process
 PID=$!
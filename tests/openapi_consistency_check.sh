@@ -35,8 +35,11 @@ fi
 # Cleanup
 rm -f ./docs/redoc/master/.diff.openapi.json
 
+# Ensure every route of the service is described in the generated file
+python3 tests/openapi_routes_check.py
+
 NUMBER_OF_APIS=$(cat ./docs/redoc/master/openapi.json | jq '.paths | length')
-EXPECTED_NUMBER_OF_APIS=48
+EXPECTED_NUMBER_OF_APIS=69
 
 if [ "$NUMBER_OF_APIS" -ne "$EXPECTED_NUMBER_OF_APIS" ]; then
     echo "ERROR: It looks like the total number of APIs has changed."
@@ -0,0 +1,68 @@
+#!/usr/bin/env python3
+# Makes sure the OpenAPI specification describes exactly the REST routes of the service.
+#
+# Routes are taken from the actix route macros, e.g. `#[post("/collections/{name}/points")]`,
+# and compared with the paths of `docs/redoc/master/openapi.json`. Names of path parameters are
+# ignored, the specification may name them differently.
+#
+# If this fails, describe the new route in `openapi/openapi-*.ytt.yaml` and regenerate the
+# specification, see docs/DEVELOPMENT.md#rest
+
+import json
+import pathlib
+import re
+import sys
+
+ROOT = pathlib.Path(__file__).resolve().parent.parent
+SPEC_PATH = ROOT / "docs" / "redoc" / "master" / "openapi.json"
+SOURCES = ROOT / "src" / "actix"
+
+ROUTE_MACRO = re.compile(r'#\[(get|post|put|patch|delete)\("([^"]+)"\)\]')
+PATH_PARAM = re.compile(r"\{[^}]+\}")
+
+# Routes, which are intentionally not part of the public API specification
+IGNORED_ROUTES = {
+    ("GET", "/"),
+    ("GET", "/stacktrace"),
+}
+
+
+def normalize(method, path):
+    return method.upper(), PATH_PARAM.sub("{}", path)
+
+
+def code_routes():
+    routes = set()
+    for source in SOURCES.rglob("*.rs"):
+        for method, path in ROUTE_MACRO.findall(source.read_text()):
+            routes.add(normalize(method, path))
+    return routes - {normalize(*route) for route in IGNORED_ROUTES}
+
+
+def spec_routes():
+    spec = json.loads(SPEC_PATH.read_text())
+    return {
+        normalize(method, path)
+        for path, operations in spec["paths"].items()
+        for method in operations
+    }
+
+
+def main():
+    in_code = code_routes()
+    in_spec = spec_routes()
+
+    missing = sorted(in_code - in_spec)
+    unknown = sorted(in_spec - in_code)
+    for method, path in missing:
+        print(f"ERROR: {method} {path} is served, but missing in the OpenAPI specification")
+    for method, path in unknown:
+        print(f"ERROR: {method} {path} is in the OpenAPI specification, but not served")
+
+    if missing or unknown:
+        sys.exit(1)
+    print(f"All {len(in_code)} routes are described in the OpenAPI specification.")
+
+
+if __name__ == "__main__":
+    main()